@@ -0,0 +1,227 @@
+//! User-provided outcome and satisfaction ratings for a session, stored in
+//! its frontmatter by `daily rate` or `daily feedback` so insights can
+//! prefer them over the facets Claude infers.
+//!
+//! Feedback given at SessionEnd, before the session is archived, waits in
+//! `{storage}/feedback/{session_id}.json` until the summary is written.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::frontmatter;
+use crate::config::Config;
+use crate::storage;
+
+pub const OUTCOMES: [&str; 3] = ["achieved", "partially_achieved", "not_achieved"];
+pub const SATISFACTIONS: [&str; 5] = [
+    "happy",
+    "satisfied",
+    "neutral",
+    "dissatisfied",
+    "frustrated",
+];
+
+const OUTCOME_KEY: &str = "user_outcome";
+const SATISFACTION_KEY: &str = "user_satisfaction";
+const NOTE_KEY: &str = "user_note";
+const STARS_KEY: &str = "user_stars";
+
+/// A user's rating of one session; `None` fields are not annotated
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub outcome: Option<String>,
+    pub satisfaction: Option<String>,
+    pub note: Option<String>,
+    /// 1-5 star rating from `daily feedback`
+    pub stars: Option<u8>,
+}
+
+impl Annotation {
+    /// Annotation stored in a session archive's frontmatter, if any
+    pub fn read(content: &str) -> Option<Self> {
+        let get = |key| {
+            frontmatter::get(content, key)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let annotation = Self {
+            outcome: get(OUTCOME_KEY),
+            satisfaction: get(SATISFACTION_KEY),
+            note: get(NOTE_KEY),
+            stars: get(STARS_KEY).and_then(|v| v.parse().ok()),
+        };
+        (!annotation.is_empty()).then_some(annotation)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outcome.is_none()
+            && self.satisfaction.is_none()
+            && self.note.is_none()
+            && self.stars.is_none()
+    }
+
+    /// The given satisfaction, or the one the star rating stands for
+    /// (5 stars is "happy", 1 is "frustrated")
+    pub fn effective_satisfaction(&self) -> Option<&str> {
+        self.satisfaction.as_deref().or_else(|| {
+            let stars = self.stars.filter(|s| (1..=5).contains(s))?;
+            Some(SATISFACTIONS[5 - stars as usize])
+        })
+    }
+
+    /// Reject values outside the facet vocabulary, so insights can aggregate them
+    pub fn validate(&self) -> Result<()> {
+        if let Some(outcome) = self.outcome.as_deref() {
+            if !OUTCOMES.contains(&outcome) {
+                anyhow::bail!(
+                    "Invalid outcome '{}'. Use one of: {}",
+                    outcome,
+                    OUTCOMES.join(", ")
+                );
+            }
+        }
+        if let Some(satisfaction) = self.satisfaction.as_deref() {
+            if !SATISFACTIONS.contains(&satisfaction) {
+                anyhow::bail!(
+                    "Invalid satisfaction '{}'. Use one of: {}",
+                    satisfaction,
+                    SATISFACTIONS.join(", ")
+                );
+            }
+        }
+        if let Some(stars) = self.stars {
+            if !(1..=5).contains(&stars) {
+                anyhow::bail!("Invalid rating {} stars. Use 1 to 5", stars);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fields set in `update` replace those of `self`
+    pub fn merge(self, update: Annotation) -> Self {
+        Self {
+            outcome: update.outcome.or(self.outcome),
+            satisfaction: update.satisfaction.or(self.satisfaction),
+            note: update.note.or(self.note),
+            stars: update.stars.or(self.stars),
+        }
+    }
+
+    /// Write the annotation into `content`'s frontmatter, removing unset fields
+    pub fn apply(&self, content: &str) -> String {
+        // The note is free text; keep it on one quoted line without inner quotes
+        let note = self
+            .note
+            .as_deref()
+            .map(|note| format!("\"{}\"", note.replace(['\n', '\r'], " ").replace('"', "'")));
+        let content = frontmatter::set(content, OUTCOME_KEY, self.outcome.as_deref());
+        let content = frontmatter::set(&content, SATISFACTION_KEY, self.satisfaction.as_deref());
+        let stars = self.stars.map(|s| s.to_string());
+        let content = frontmatter::set(&content, NOTE_KEY, note.as_deref());
+        frontmatter::set(&content, STARS_KEY, stars.as_deref())
+    }
+}
+
+fn pending_path(config: &Config, session_id: &str) -> PathBuf {
+    config
+        .storage_path()
+        .join("feedback")
+        .join(format!("{}.json", session_id))
+}
+
+/// Keep feedback for a session that has no archive yet
+pub fn save_pending(config: &Config, session_id: &str, annotation: &Annotation) -> Result<()> {
+    annotation.validate()?;
+    let path = pending_path(config, session_id);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("Failed to create feedback directory")?;
+    }
+    storage::write_file(config, &path, serde_json::to_string_pretty(annotation)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Remove and return the feedback waiting for a session, if any
+pub fn take_pending(config: &Config, session_id: &str) -> Option<Annotation> {
+    let path = pending_path(config, session_id);
+    let content = fs::read_to_string(&path).ok()?;
+    let _ = storage::remove_file(config, &path);
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCHIVE: &str = "---\ntitle: \"Fix bug\"\nsession_id: abc\n---\n\n# Fix bug\n";
+
+    #[test]
+    fn test_apply_and_read_roundtrip() {
+        assert_eq!(Annotation::read(ARCHIVE), None);
+
+        let annotation = Annotation {
+            outcome: Some("achieved".into()),
+            satisfaction: Some("happy".into()),
+            note: Some("Fixed the \"flaky\" test\nfinally".into()),
+            stars: Some(4),
+        };
+        let content = annotation.apply(ARCHIVE);
+        let read = Annotation::read(&content).unwrap();
+        assert_eq!(read.outcome.as_deref(), Some("achieved"));
+        assert_eq!(read.note.as_deref(), Some("Fixed the 'flaky' test finally"));
+        assert_eq!(read.stars, Some(4));
+
+        // Merging keeps fields the update leaves out
+        let merged = read.merge(Annotation {
+            satisfaction: Some("neutral".into()),
+            ..Default::default()
+        });
+        assert_eq!(merged.outcome.as_deref(), Some("achieved"));
+        assert_eq!(merged.satisfaction.as_deref(), Some("neutral"));
+
+        assert_eq!(Annotation::default().apply(&content), ARCHIVE);
+    }
+
+    #[test]
+    fn test_validate() {
+        let bad = Annotation {
+            outcome: Some("done".into()),
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        let good = Annotation {
+            satisfaction: Some("frustrated".into()),
+            ..Default::default()
+        };
+        assert!(good.validate().is_ok());
+
+        // Stars stand in for satisfaction unless one is given
+        let stars = |stars| Annotation {
+            stars: Some(stars),
+            ..Default::default()
+        };
+        assert!(stars(0).validate().is_err());
+        assert!(stars(6).validate().is_err());
+        assert_eq!(stars(5).effective_satisfaction(), Some("happy"));
+        assert_eq!(stars(2).effective_satisfaction(), Some("dissatisfied"));
+        assert_eq!(good.effective_satisfaction(), Some("frustrated"));
+    }
+
+    #[test]
+    fn test_pending_feedback_is_taken_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        let feedback = Annotation {
+            stars: Some(4),
+            note: Some("Quick fix".into()),
+            ..Default::default()
+        };
+        assert_eq!(take_pending(&config, "abc"), None);
+        save_pending(&config, "abc", &feedback).unwrap();
+        assert_eq!(take_pending(&config, "abc"), Some(feedback));
+        assert_eq!(take_pending(&config, "abc"), None);
+    }
+}
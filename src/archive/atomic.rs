@@ -0,0 +1,226 @@
+//! Crash-safe file writes.
+//!
+//! Archive files and the config are written to a hidden temp file next to
+//! the target, fsynced and renamed over it, so a crash leaves either the old
+//! or the new version on disk. [`recover`] cleans up after a crash: it drops
+//! leftover temp files and restores truncated archives from `.backups/`.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::edit;
+use super::manager::ArchiveManager;
+use crate::config::Config;
+use crate::storage;
+
+/// Suffix of in-flight temp files, which are named `.{file}.{pid}.tmp`
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Temp files younger than this may belong to a write still in progress
+const STALE_AFTER: Duration = Duration::from_secs(60);
+
+/// Write `contents` to `path` atomically: temp file, fsync, rename, then
+/// fsync of the directory so the rename itself is durable
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let temp = temp_path(path);
+
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Failed to write {}", path.display()));
+    }
+
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Temp file used while writing `path`
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}{}", name, std::process::id(), TEMP_SUFFIX))
+}
+
+/// What [`recover`] found and fixed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Recovery {
+    /// Leftover temp files of interrupted writes that were removed
+    pub removed_temp_files: Vec<PathBuf>,
+    /// Truncated archives replaced by their latest backup
+    pub restored: Vec<PathBuf>,
+    /// Truncated archives without a backup to restore from
+    pub damaged: Vec<PathBuf>,
+}
+
+/// Clean up files left behind by a crash mid-write
+pub fn recover(config: &Config) -> Result<Recovery> {
+    let storage = config.storage_path();
+    let mut recovery = Recovery::default();
+    if !storage.exists() {
+        return Ok(recovery);
+    }
+    remove_temp_files(
+        &storage,
+        SystemTime::now() - STALE_AFTER,
+        &mut recovery.removed_temp_files,
+    );
+
+    let manager = ArchiveManager::new(config.clone());
+    for date in manager.list_dates()? {
+        let mut files = vec![("daily".to_string(), manager.daily_summary_path(&date))];
+        files.extend(
+            manager
+                .list_sessions(&date)?
+                .into_iter()
+                .map(|name| {
+                    let path = manager.session_archive_path(&date, &name);
+                    (name, path)
+                })
+                .filter(|(_, path)| path.exists()),
+        );
+
+        for (name, path) in files {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if !is_truncated(&content) {
+                continue;
+            }
+            match edit::latest_backup(config, &date, &name) {
+                Some(backup) => {
+                    let previous = fs::read(&backup)
+                        .with_context(|| format!("Failed to read {}", backup.display()))?;
+                    storage::write_file(config, &path, previous)?;
+                    recovery.restored.push(path);
+                }
+                None => recovery.damaged.push(path),
+            }
+        }
+    }
+    Ok(recovery)
+}
+
+/// Run [`recover`] and report what it did, for startup paths that must not
+/// fail because of it
+pub fn recover_on_startup(config: &Config) {
+    match recover(config) {
+        Ok(recovery) => {
+            if !recovery.removed_temp_files.is_empty() {
+                eprintln!(
+                    "[daily] Removed {} unfinished write(s) left by a crash",
+                    recovery.removed_temp_files.len()
+                );
+            }
+            for path in &recovery.restored {
+                eprintln!("[daily] Restored truncated {} from backup", path.display());
+            }
+            for path in &recovery.damaged {
+                eprintln!(
+                    "[daily] {} looks truncated and has no backup; regenerate it",
+                    path.display()
+                );
+            }
+        }
+        Err(e) => eprintln!("[daily] Failed to check for interrupted writes: {}", e),
+    }
+}
+
+/// Remove `.*.tmp` files under `dir` last modified before `cutoff`
+fn remove_temp_files(dir: &Path, cutoff: SystemTime, removed: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => remove_temp_files(&path, cutoff, removed),
+            Ok(kind) if kind.is_file() => {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                let stale = entry
+                    .metadata()
+                    .and_then(|meta| meta.modified())
+                    .is_ok_and(|modified| modified < cutoff);
+                if name.starts_with('.')
+                    && name.ends_with(TEMP_SUFFIX)
+                    && stale
+                    && fs::remove_file(&path).is_ok()
+                {
+                    removed.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An empty file, or frontmatter that was never closed, is what a crash
+/// during a plain `fs::write` leaves behind
+fn is_truncated(content: &str) -> bool {
+    if content.trim().is_empty() {
+        return true;
+    }
+    content.starts_with("---\n") && !content[4..].contains("\n---")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_atomic_and_recover() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        let path = manager
+            .write_daily_summary("2026-03-01", "---\ndate: 2026-03-01\n---\n\n# Old\n")
+            .unwrap();
+        assert!(!temp_path(&path).exists());
+        manager
+            .edit_daily_summary("2026-03-01", "---\ndate: 2026-03-01\n---\n\n# New\n")
+            .unwrap();
+
+        // A crash mid-write: truncated daily.md and a stray temp file
+        fs::write(&path, "---\ndate: 2026-").unwrap();
+        let stray = temp_path(&path);
+        fs::write(&stray, "partial").unwrap();
+        let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        File::options()
+            .write(true)
+            .open(&stray)
+            .unwrap()
+            .set_modified(hour_ago)
+            .unwrap();
+        let session = manager.write_session("2026-03-01", "fix", "").unwrap();
+
+        let recovery = recover(&config).unwrap();
+        assert_eq!(recovery.removed_temp_files, vec![stray]);
+        assert_eq!(recovery.restored, vec![path.clone()]);
+        assert_eq!(recovery.damaged, vec![session]);
+        assert!(fs::read_to_string(&path).unwrap().contains("# Old"));
+        assert!(recover(&config).unwrap().restored.is_empty());
+    }
+}
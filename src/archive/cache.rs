@@ -0,0 +1,119 @@
+//! In-memory cache of archive file contents for the dashboard server.
+//!
+//! Entries are keyed by path and validated against the file's modification
+//! time and length on every lookup, so files rewritten by another process
+//! (a digest job, a hook) are re-read without any explicit invalidation.
+//! Writes through [`ArchiveManager`](super::ArchiveManager) drop the entry
+//! right away as well.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Files kept before the least recently used one is evicted
+const MAX_ENTRIES: usize = 1024;
+
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    content: Arc<str>,
+    last_used: u64,
+}
+
+/// Shared cache of archive reads, see the module docs
+#[derive(Default)]
+pub struct ReadCache {
+    entries: Mutex<HashMap<PathBuf, Entry>>,
+    clock: AtomicU64,
+}
+
+/// Modification time and length identifying one version of a file
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+impl ReadCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Content of `path`, from the cache while the file is unchanged,
+    /// otherwise from `load` (which may itself rewrite the file)
+    pub fn read(&self, path: &Path, load: impl FnOnce() -> Result<String>) -> Result<String> {
+        let now = self.clock.fetch_add(1, Ordering::Relaxed);
+        if let Some((modified, len)) = stamp(path) {
+            let mut entries = self.entries.lock().unwrap();
+            if let Some(entry) = entries.get_mut(path) {
+                if entry.modified == modified && entry.len == len {
+                    entry.last_used = now;
+                    return Ok(entry.content.to_string());
+                }
+            }
+        }
+
+        let content = load()?;
+        // Stamp after loading: an upgrade on read may have rewritten the file
+        if let Some((modified, len)) = stamp(path) {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.len() >= MAX_ENTRIES && !entries.contains_key(path) {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(path, _)| path.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+            entries.insert(
+                path.to_path_buf(),
+                Entry {
+                    modified,
+                    len,
+                    content: Arc::from(content.as_str()),
+                    last_used: now,
+                },
+            );
+        }
+        Ok(content)
+    }
+
+    /// Forget `path`, after it was written or removed
+    pub fn invalidate(&self, path: &Path) {
+        self.entries.lock().unwrap().remove(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_cache_revalidates_on_change() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("daily.md");
+        fs::write(&path, "first").unwrap();
+        let cache = ReadCache::new();
+        let load = || Ok(fs::read_to_string(&path)?);
+
+        assert_eq!(cache.read(&path, load).unwrap(), "first");
+        assert_eq!(cache.read(&path, || unreachable!()).unwrap(), "first");
+
+        // Rewritten by another process: length (and mtime) differ
+        fs::write(&path, "second version").unwrap();
+        assert_eq!(cache.read(&path, load).unwrap(), "second version");
+        assert_eq!(
+            cache.read(&path, || unreachable!()).unwrap(),
+            "second version"
+        );
+
+        cache.invalidate(&path);
+        let reloaded = cache.read(&path, || Ok("reloaded".to_string())).unwrap();
+        assert_eq!(reloaded, "reloaded");
+    }
+}
@@ -0,0 +1,115 @@
+//! Which sessions each digest card was drawn from.
+//!
+//! With `summarization.digest_citations` on, the digest prompt numbers the
+//! sessions it is given and asks for the numbers behind each card of the
+//! Key Insights, Tomorrow's Focus, skills and commands sections, one list
+//! per card in order, much like a session's `sources`. The numbers are
+//! turned into session IDs and names and saved next to the digest as
+//! `daily.citations.json`, keyed by section and card title, so the prose
+//! never mentions sessions and hand edits to other cards don't shift them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::SummaryCard;
+
+/// File next to daily.md holding a digest's citations
+pub const FILE_NAME: &str = "daily.citations.json";
+
+/// Sections with citations: key in the digest response
+pub const SECTIONS: [&str; 4] = ["insights", "tomorrow_focus", "skills", "commands"];
+
+/// A session a card was drawn from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitedSession {
+    /// Stable ID from the session's frontmatter, or its name for archives
+    /// that predate IDs
+    pub id: String,
+    pub name: String,
+}
+
+/// The sessions behind one card
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    pub section: String,
+    pub title: String,
+    pub sessions: Vec<CitedSession>,
+}
+
+/// Citations for the cards of each section, given as one list of session
+/// numbers (1-based, into `sessions`) per card. Numbers the model made up,
+/// cards without sessions and sections not in [`SECTIONS`] are dropped.
+pub fn from_numbers(
+    cited: &BTreeMap<String, Vec<Vec<usize>>>,
+    cards: &[(&str, &[SummaryCard])],
+    sessions: &[CitedSession],
+) -> Vec<Citation> {
+    let mut citations = Vec::new();
+    for (section, cards) in cards {
+        if !SECTIONS.contains(section) {
+            continue;
+        }
+        let Some(lists) = cited.get(*section) else {
+            continue;
+        };
+        for (card, numbers) in cards.iter().zip(lists) {
+            let mut cited_sessions: Vec<CitedSession> = Vec::new();
+            for n in numbers {
+                let Some(session) = n.checked_sub(1).and_then(|i| sessions.get(i)) else {
+                    continue;
+                };
+                if !cited_sessions.contains(session) {
+                    cited_sessions.push(session.clone());
+                }
+            }
+            if !cited_sessions.is_empty() {
+                citations.push(Citation {
+                    section: section.to_string(),
+                    title: card.title.clone(),
+                    sessions: cited_sessions,
+                });
+            }
+        }
+    }
+    citations
+}
+
+/// Sessions cited for the card titled `title` in `section`
+pub fn for_card<'a>(citations: &'a [Citation], section: &str, title: &str) -> &'a [CitedSession] {
+    citations
+        .iter()
+        .find(|c| c.section == section && c.title.trim() == title.trim())
+        .map(|c| c.sessions.as_slice())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_numbers() {
+        let card = |title: &str| SummaryCard {
+            title: title.into(),
+            content: String::new(),
+        };
+        let session = |name: &str| CitedSession {
+            id: format!("id-{}", name),
+            name: name.into(),
+        };
+        let sessions = vec![session("09_10-auth"), session("14_00-ci")];
+        let insights = vec![card("Token refresh"), card("Flaky CI"), card("Unsourced")];
+        let cited: BTreeMap<String, Vec<Vec<usize>>> =
+            serde_json::from_str(r#"{"insights": [[1], [2, 2, 9], []], "overview": [[1]]}"#)
+                .unwrap();
+
+        let citations = from_numbers(&cited, &[("insights", &insights)], &sessions);
+        assert_eq!(citations.len(), 2);
+        assert_eq!(citations[1].sessions, vec![session("14_00-ci")]);
+        assert_eq!(
+            for_card(&citations, "insights", "Token refresh"),
+            &[session("09_10-auth")]
+        );
+        assert!(for_card(&citations, "skills", "Token refresh").is_empty());
+    }
+}
@@ -1,9 +1,31 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
 
+use super::citations::Citation;
+use super::edit;
+use super::frontmatter;
 use super::manager::ArchiveManager;
+use super::notes;
 use super::templates::Templates;
-use crate::config::Config;
+use crate::config::{layers, load_config_in, Config};
+use crate::goals::{GoalReport, GoalStore};
+use crate::todos::{TodoCheck, TodoStore};
+
+/// Digest sections that regeneration can preserve, as `(key, heading)`
+pub const SECTIONS: [(&str, &str); 6] = [
+    ("overview", "Overview"),
+    ("key_work", "Key Work"),
+    ("insights", "Key Insights"),
+    ("reflections", "Reflections"),
+    ("tomorrow_focus", "Tomorrow's Focus"),
+    ("skills", "Skills & Commands Identified"),
+];
+
+/// Frontmatter key holding a hash of each section as generated, so hand
+/// edits can be told apart from generated text
+const SECTION_HASHES_KEY: &str = "section_hashes";
 
 /// A single card within a daily summary section
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +34,344 @@ pub struct SummaryCard {
     pub content: String,
 }
 
+/// One project's subsection of a digest grouped with
+/// `summarization.digest_group_by = "project"`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSection {
+    /// Last component of the sessions' working directory
+    pub project: String,
+    /// Working directory of the project's sessions
+    pub cwd: String,
+    pub sessions: Vec<String>,
+    pub content: String,
+}
+
+/// Body of a `## {header}` section of a daily.md, or None if absent or empty
+pub fn section<'a>(content: &'a str, header: &str) -> Option<&'a str> {
+    let pattern = format!("## {}\n", header);
+    let start = content.find(&pattern)? + pattern.len();
+    let end = content[start..]
+        .find("\n## ")
+        .map(|i| start + i)
+        .unwrap_or(content.len());
+    let section = content[start..end].trim();
+    if section.is_empty() || section == "No sessions recorded yet." {
+        None
+    } else {
+        Some(section)
+    }
+}
+
+fn section_hash(body: &str) -> String {
+    format!("{:x}", Sha256::digest(body.as_bytes()))[..12].to_string()
+}
+
+/// Record the hash of each generated section in the frontmatter
+fn stamp_sections(content: &str) -> String {
+    let hashes: Vec<String> = SECTIONS
+        .iter()
+        .filter_map(|(key, heading)| {
+            edit::section_body(content, heading)
+                .map(|body| format!("{}={}", key, section_hash(body)))
+        })
+        .collect();
+    let value = format!("\"{}\"", hashes.join(","));
+    frontmatter::set(content, SECTION_HASHES_KEY, Some(&value))
+}
+
+/// Record the current text of section `key` as generated, so it no longer
+/// counts as edited (used when a single section is regenerated)
+pub fn restamp_section(content: &str, key: &str) -> String {
+    let Some(heading) = SECTIONS.iter().find(|(k, _)| *k == key).map(|(_, h)| *h) else {
+        return content.to_string();
+    };
+    let mut hashes: Vec<String> = frontmatter::get(content, SECTION_HASHES_KEY)
+        .unwrap_or_default()
+        .split(',')
+        .filter(|pair| !pair.is_empty() && pair.split_once('=').map(|(k, _)| k) != Some(key))
+        .map(String::from)
+        .collect();
+    if let Some(body) = edit::section_body(content, heading) {
+        hashes.push(format!("{}={}", key, section_hash(body)));
+    }
+    let value = format!("\"{}\"", hashes.join(","));
+    frontmatter::set(content, SECTION_HASHES_KEY, Some(&value))
+}
+
+/// Keys of sections edited by hand since the digest was generated.
+/// Digests written before section hashes were recorded report none.
+pub fn edited_sections(content: &str) -> Vec<&'static str> {
+    let Some(recorded) = frontmatter::get(content, SECTION_HASHES_KEY) else {
+        return Vec::new();
+    };
+    let recorded: Vec<(&str, &str)> = recorded
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+    SECTIONS
+        .iter()
+        .filter(|(key, heading)| {
+            let hash = recorded.iter().find(|(k, _)| k == key).map(|(_, h)| *h);
+            match (hash, edit::section_body(content, heading)) {
+                (Some(hash), Some(body)) => hash != section_hash(body),
+                _ => false,
+            }
+        })
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Check `--keep` section keys against [`SECTIONS`]
+pub fn validate_section_keys(keys: &[String]) -> Result<()> {
+    for key in keys {
+        if !SECTIONS.iter().any(|(k, _)| k == key) {
+            let known: Vec<&str> = SECTIONS.iter().map(|(k, _)| *k).collect();
+            anyhow::bail!(
+                "Unknown digest section '{}'. Use one of: {}",
+                key,
+                known.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Carry sections of the previous digest over into a regenerated one
+/// verbatim: those listed in `keep` plus any edited by hand. Returns the
+/// merged content and the keys that were preserved.
+pub fn preserve_sections(
+    generated: &str,
+    previous: &str,
+    keep: &[String],
+) -> Result<(String, Vec<&'static str>)> {
+    let edited = edited_sections(previous);
+    let mut content = generated.to_string();
+    let mut preserved = Vec::new();
+    for (key, heading) in SECTIONS {
+        if !edited.contains(&key) && !keep.iter().any(|k| k == key) {
+            continue;
+        }
+        let Some(body) = edit::section_body(previous, heading) else {
+            continue;
+        };
+        // The recorded hash stays that of the generated text, so the
+        // section still counts as edited on the next regeneration
+        content = edit::replace_section(&content, heading, body)?;
+        preserved.push(key);
+    }
+    Ok((content, preserved))
+}
+
+/// Parse a section into cards by splitting on ### subsections.
+/// Backward compat: if no ### found, the entire section becomes a single card.
+pub fn section_cards(content: &str, header: &str) -> Vec<SummaryCard> {
+    let Some(section) = section(content, header) else {
+        return Vec::new();
+    };
+
+    let mut cards = Vec::new();
+    let mut current_title: Option<String> = None;
+    let mut current_lines: Vec<&str> = Vec::new();
+    let mut flush = |title: Option<String>, lines: &mut Vec<&str>| {
+        if let Some(title) = title {
+            let body = lines.join("\n").trim().to_string();
+            if !body.is_empty() {
+                cards.push(SummaryCard {
+                    title,
+                    content: body,
+                });
+            }
+        }
+        lines.clear();
+    };
+
+    for line in section.lines() {
+        if let Some(title) = line.strip_prefix("### ") {
+            flush(current_title.take(), &mut current_lines);
+            current_title = Some(title.trim().to_string());
+        } else {
+            current_lines.push(line);
+        }
+    }
+    flush(current_title, &mut current_lines);
+
+    if cards.is_empty() {
+        cards.push(SummaryCard {
+            title: header.to_string(),
+            content: section.to_string(),
+        });
+    }
+    cards
+}
+
+/// Skill and command cards of a digest's "## Skills & Commands Identified"
+/// section, split on its "### Potential Skills" and "### Potential Commands"
+/// markers.
+///
+/// The LLM-generated card content may contain rogue ## headings (e.g.
+/// "## 可复用技能"), so the markers are located directly in the full content
+/// rather than through `section`, which would stop at the embedded headings.
+pub fn skill_command_cards(content: &str) -> (Vec<SummaryCard>, Vec<SummaryCard>) {
+    let skills_marker = "\n### Potential Skills\n";
+    let commands_marker = "\n### Potential Commands\n";
+
+    // Also support file starting with the marker (no leading newline)
+    let skills_start = content
+        .find(skills_marker)
+        .map(|i| i + skills_marker.len())
+        .or_else(|| {
+            content
+                .strip_prefix("### Potential Skills\n")
+                .map(|_| "### Potential Skills\n".len())
+        });
+    let commands_start = content
+        .find(commands_marker)
+        .map(|i| i + commands_marker.len());
+
+    // Skills section: from after "### Potential Skills\n" to "### Potential Commands\n" (or footer)
+    let skills_text = skills_start.map(|start| {
+        let end = content[start..]
+            .find(commands_marker)
+            .map(|i| start + i)
+            .unwrap_or_else(|| find_footer(content, start));
+        content[start..end].trim()
+    });
+
+    // Commands section: from after "### Potential Commands\n" to the footer
+    let commands_text =
+        commands_start.map(|start| content[start..find_footer(content, start)].trim());
+
+    (marker_cards(skills_text), marker_cards(commands_text))
+}
+
+/// Find the footer marker: "---" followed by "*Generated" or "*Archived"
+fn find_footer(content: &str, from: usize) -> usize {
+    let mut search_from = from;
+    loop {
+        match content[search_from..].find("\n---\n") {
+            Some(pos) => {
+                let after = search_from + pos + 4; // skip past "\n---\n"
+                if content[after..].starts_with('*') {
+                    return search_from + pos;
+                }
+                // This "---" is a content separator (e.g. between cards), skip it
+                search_from = after;
+            }
+            None => return content.len(),
+        }
+    }
+}
+
+fn marker_cards(text: Option<&str>) -> Vec<SummaryCard> {
+    let section = match text {
+        Some(s) if !s.is_empty() => s,
+        _ => return Vec::new(),
+    };
+    let mut cards: Vec<SummaryCard> = Vec::new();
+    let mut current_title: Option<String> = None;
+    let mut current_lines: Vec<&str> = Vec::new();
+    let mut flush = |title: Option<String>, lines: &mut Vec<&str>| {
+        if let Some(title) = title {
+            let body = lines.join("\n").trim().to_string();
+            if !body.is_empty() {
+                cards.push(SummaryCard {
+                    title,
+                    content: body,
+                });
+            }
+        }
+        lines.clear();
+    };
+
+    for line in section.lines() {
+        // Card titles are ### level; skip any ## lines (LLM noise)
+        if let Some(title) = line.strip_prefix("### ") {
+            flush(current_title.take(), &mut current_lines);
+            current_title = Some(title.trim().to_string());
+        } else if !line.starts_with("## ") {
+            current_lines.push(line);
+        }
+    }
+    flush(current_title, &mut current_lines);
+    cards
+}
+
+/// Kebab-case file name a skill or command card is installed under
+pub fn card_slug(title: &str) -> String {
+    // Remove markdown formatting like backticks and /
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else if c == ' ' || c == '/' {
+                '-'
+            } else if c.is_alphanumeric() {
+                // Keep non-ASCII alphanumeric (CJK etc.)
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    // Collapse multiple dashes and trim
+    let mut result = String::new();
+    let mut prev_dash = false;
+    for c in cleaned.chars() {
+        if c == '-' {
+            if !prev_dash && !result.is_empty() {
+                result.push('-');
+            }
+            prev_dash = true;
+        } else {
+            result.push(c.to_ascii_lowercase());
+            prev_dash = false;
+        }
+    }
+    result.trim_matches('-').to_string()
+}
+
+/// Point a digest's references to session `old` at `new`: the frontmatter
+/// `sessions` list and mentions of the name in the body
+pub fn rename_session_reference(content: &str, old: &str, new: &str) -> String {
+    let mut content = content.to_string();
+    if let Some(list) = frontmatter::get(&content, "sessions") {
+        let renamed: Vec<&str> = list
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| if name == old { new } else { name })
+            .collect();
+        let list = format!("[{}]", renamed.join(", "));
+        content = frontmatter::set(&content, "sessions", Some(&list));
+    }
+
+    // Only whole names: `10_00-fix` must not match inside `10_00-fix-tests`
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let body_start = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---").map(|end| end + 8))
+        .unwrap_or(0);
+    let mut renamed = content[..body_start].to_string();
+    let body = &content[body_start..];
+    let mut last = 0;
+    for (at, _) in body.match_indices(old) {
+        let before = body[..at].chars().next_back();
+        let after = body[at + old.len()..].chars().next();
+        if before.is_some_and(is_name_char) || after.is_some_and(is_name_char) {
+            continue;
+        }
+        renamed.push_str(&body[last..at]);
+        renamed.push_str(new);
+        last = at + old.len();
+    }
+    renamed.push_str(&body[last..]);
+    renamed
+}
+
 /// Represents a daily summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailySummary {
@@ -24,6 +384,14 @@ pub struct DailySummary {
     pub commands: Vec<SummaryCard>,
     pub reflections: String,
     pub tomorrow_focus: Vec<SummaryCard>,
+    /// Progress on active goals, recorded in `goals.json` when saved
+    pub goal_progress: Vec<GoalReport>,
+    /// TODOs the day's sessions completed, recorded in `todos.json` when saved
+    pub completed_todos: Vec<TodoCheck>,
+    /// Sessions behind each card, saved to `daily.citations.json`
+    pub citations: Vec<Citation>,
+    /// Per-project subsections, when grouping by project
+    pub projects: Vec<ProjectSection>,
 }
 
 impl DailySummary {
@@ -39,6 +407,10 @@ impl DailySummary {
             commands: Vec::new(),
             reflections: String::new(),
             tomorrow_focus: Vec::new(),
+            goal_progress: Vec::new(),
+            completed_todos: Vec::new(),
+            citations: Vec::new(),
+            projects: Vec::new(),
         }
     }
 
@@ -74,9 +446,25 @@ impl DailySummary {
 
     /// Generate Markdown content for this summary
     pub fn to_markdown(&self) -> String {
-        Templates::daily_summary(
+        let goal_progress: Vec<SummaryCard> = self
+            .goal_progress
+            .iter()
+            .map(|report| SummaryCard {
+                title: report.title.clone(),
+                content: report.progress.clone(),
+            })
+            .collect();
+        let projects: Vec<SummaryCard> = self
+            .projects
+            .iter()
+            .map(|section| SummaryCard {
+                title: section.project.clone(),
+                content: section.content.clone(),
+            })
+            .collect();
+        stamp_sections(&Templates::daily_summary(
             &self.date,
-            self.sessions.len(),
+            &self.sessions,
             &self.overview,
             &self.session_details,
             &self.insights,
@@ -84,7 +472,9 @@ impl DailySummary {
             &self.commands,
             &self.reflections,
             &self.tomorrow_focus,
-        )
+            &goal_progress,
+            &projects,
+        ))
     }
 
     /// Render a slice of SummaryCards as markdown subsections
@@ -97,11 +487,107 @@ impl DailySummary {
             .join("\n\n")
     }
 
-    /// Save this summary to disk
-    pub fn save(&self, config: &Config) -> Result<std::path::PathBuf> {
-        let manager = ArchiveManager::new(config.clone());
+    /// Save this summary to disk and record its goal progress and TODO
+    /// checks. When it replaces an existing digest, the sections in `keep`
+    /// and those edited by hand are carried over and the previous version
+    /// is backed up. Returns the path and preserved keys.
+    pub fn save(
+        &self,
+        config: &Config,
+        keep: &[String],
+    ) -> Result<(std::path::PathBuf, Vec<&'static str>)> {
+        let (content, preserved) = self.merge_previous(config, keep)?;
+        let path = self.save_content(config, &content)?;
+        Ok((path, preserved))
+    }
+
+    /// Markdown for this summary with the sections of an existing digest
+    /// that [`save`](Self::save) would carry over (plus its notes), and
+    /// their keys
+    pub fn merge_previous(
+        &self,
+        config: &Config,
+        keep: &[String],
+    ) -> Result<(String, Vec<&'static str>)> {
         let content = self.to_markdown();
-        manager.write_daily_summary(&self.date, &content)
+        match ArchiveManager::new(config.clone()).read_daily_summary(&self.date) {
+            Ok(previous) => {
+                let (content, preserved) = preserve_sections(&content, &previous, keep)?;
+                Ok((notes::carry_over(&content, &previous)?, preserved))
+            }
+            Err(_) => Ok((content, Vec::new())),
+        }
+    }
+
+    /// Write `content` (this summary as reviewed) as the date's digest and
+    /// record its goal progress and TODO checks, backing up a digest it
+    /// replaces
+    pub fn save_content(&self, config: &Config, content: &str) -> Result<std::path::PathBuf> {
+        let manager = ArchiveManager::new(config.clone());
+        self.record_goals(config)?;
+        self.record_todos(config)?;
+        manager.write_citations(&self.date, &self.citations)?;
+        self.write_project_digests(config)?;
+        if manager.has_digest(&self.date) {
+            manager.edit_daily_summary(&self.date, content)
+        } else {
+            manager.write_daily_summary(&self.date, content)
+        }
+    }
+
+    /// Write each project's subsection into the project's own storage, for
+    /// projects whose `.daily.toml` gives them one
+    fn write_project_digests(&self, config: &Config) -> Result<()> {
+        for section in &self.projects {
+            let cwd = Path::new(&section.cwd);
+            let Some(storage) = layers::project_storage_path(cwd) else {
+                continue;
+            };
+            let mut project_config = match load_config_in(cwd) {
+                Ok(project_config) => project_config,
+                Err(e) => {
+                    eprintln!(
+                        "[daily] Warning: Failed to load config of {}: {:#}",
+                        section.cwd, e
+                    );
+                    continue;
+                }
+            };
+            project_config.storage.path = storage;
+            if project_config.storage_path() == config.storage_path() {
+                continue;
+            }
+            let content = Templates::project_digest(
+                &self.date,
+                &section.project,
+                &section.sessions,
+                &section.content,
+            );
+            ArchiveManager::new(project_config).write_project_digest(
+                &self.date,
+                &section.project,
+                &content,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn record_goals(&self, config: &Config) -> Result<()> {
+        let mut goals = GoalStore::load(config)?;
+        if goals.goals.is_empty() {
+            return Ok(());
+        }
+        goals.record(&self.date, &self.goal_progress);
+        goals.save(config)
+    }
+
+    fn record_todos(&self, config: &Config) -> Result<()> {
+        let mut todos = TodoStore::load(config)?;
+        if todos.todos.is_empty() {
+            return Ok(());
+        }
+        todos.record(&self.date, &self.completed_todos);
+        todos.save(config)
     }
 
     /// Load daily summary from disk, or create new if not exists
@@ -158,5 +644,89 @@ mod tests {
         let md = summary.to_markdown();
         assert!(md.contains("date: 2026-01-16"));
         assert!(md.contains("session_count: 1"));
+        assert!(!md.contains("## By Project"));
+
+        summary.projects.push(ProjectSection {
+            project: "claude-daily".into(),
+            cwd: "/work/claude-daily".into(),
+            sessions: vec!["test-session".into()],
+            content: "Shipped the parser.".into(),
+        });
+        let md = summary.to_markdown();
+        assert!(md.contains(
+            "## By Project\n\n### claude-daily\n\nShipped the parser.\n\n## Key Insights"
+        ));
+    }
+
+    #[test]
+    fn test_section_cards() {
+        let md = DailySummary::new("2026-01-16".to_string())
+            .with_content(
+                "Overview".into(),
+                String::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                String::new(),
+                vec![
+                    SummaryCard {
+                        title: "Ship parser".into(),
+                        content: "- Add tests".into(),
+                    },
+                    SummaryCard {
+                        title: "✅ Fix CI".into(),
+                        content: "Done".into(),
+                    },
+                ],
+            )
+            .to_markdown();
+
+        let cards = section_cards(&md, "Tomorrow's Focus");
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].title, "Ship parser");
+        assert_eq!(cards[0].content, "- Add tests");
+        assert_eq!(cards[1].title, "✅ Fix CI");
+
+        let legacy = "## Tomorrow's Focus\n\n- Review PR\n";
+        let cards = section_cards(legacy, "Tomorrow's Focus");
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].content, "- Review PR");
+        assert!(section_cards(legacy, "Insights").is_empty());
+    }
+
+    #[test]
+    fn test_preserve_edited_sections() {
+        let generate = |reflections: &str| {
+            DailySummary::new("2026-01-16".to_string())
+                .with_content(
+                    "Overview".into(),
+                    "Work".into(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    reflections.into(),
+                    Vec::new(),
+                )
+                .to_markdown()
+        };
+        let previous = generate("Generated reflections.");
+        assert!(edited_sections(&previous).is_empty());
+
+        let previous =
+            edit::replace_section(&previous, "Reflections", "My own reflections.").unwrap();
+        assert_eq!(edited_sections(&previous), vec!["reflections"]);
+
+        let regenerated = generate("New reflections.");
+        let (merged, preserved) =
+            preserve_sections(&regenerated, &previous, &["overview".to_string()]).unwrap();
+        assert_eq!(preserved, vec!["overview", "reflections"]);
+        assert_eq!(section(&merged, "Reflections"), Some("My own reflections."));
+        // Still detected as hand-edited after regeneration
+        assert_eq!(edited_sections(&merged), vec!["reflections"]);
+        // A section regenerated on its own counts as generated again
+        assert!(edited_sections(&restamp_section(&merged, "reflections")).is_empty());
+
+        assert!(validate_section_keys(&["reflections".to_string()]).is_ok());
+        assert!(validate_section_keys(&["bogus".to_string()]).is_err());
     }
 }
@@ -0,0 +1,169 @@
+//! Hand edits of session archives and digests from the web UI.
+//!
+//! Every edit first copies the previous version to
+//! `{storage}/.backups/{date}/{file}.{timestamp}.md`, keeping the most
+//! recent few per file, so a bad edit can be undone by hand.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::atomic::write_atomic;
+use crate::config::Config;
+
+/// Backups kept per edited file; older ones are removed
+const MAX_BACKUPS: usize = 10;
+
+/// Apply a full replacement and/or `## {header}` section edits to `current`
+pub fn apply(
+    current: &str,
+    content: Option<&str>,
+    sections: &BTreeMap<String, String>,
+) -> Result<String> {
+    let mut updated = content.unwrap_or(current).to_string();
+    for (header, body) in sections {
+        updated = replace_section(&updated, header, body)?;
+    }
+    Ok(updated)
+}
+
+/// Byte range of a `## {header}` section's body, up to the next heading or
+/// the archive footer
+fn section_range(content: &str, header: &str) -> Option<(usize, usize)> {
+    let pattern = format!("## {}\n", header.trim());
+    let start = content.find(&pattern)? + pattern.len();
+    let rest = &content[start..];
+    let end = [rest.find("\n## "), rest.find("\n---\n")]
+        .into_iter()
+        .flatten()
+        .min()
+        .map(|i| start + i + 1)
+        .unwrap_or(content.len());
+    Some((start, end))
+}
+
+/// Trimmed body of a `## {header}` section, including its `###` subsections
+pub fn section_body<'a>(content: &'a str, header: &str) -> Option<&'a str> {
+    section_range(content, header).map(|(start, end)| content[start..end].trim())
+}
+
+/// Replace the body of a `## {header}` section
+pub fn replace_section(content: &str, header: &str, body: &str) -> Result<String> {
+    let (start, end) = section_range(content, header)
+        .ok_or_else(|| anyhow::anyhow!("Section '{}' not found", header.trim()))?;
+    let trailing = if end < content.len() { "\n" } else { "" };
+    Ok(format!(
+        "{}\n{}\n{}{}",
+        &content[..start],
+        body.trim(),
+        trailing,
+        &content[end..]
+    ))
+}
+
+/// Copy the previous version of an archive file into the backup folder
+pub fn backup(config: &Config, date: &str, file_name: &str, previous: &str) -> Result<PathBuf> {
+    let dir = backups_dir(config, date);
+    fs::create_dir_all(&dir).context("Failed to create backups directory")?;
+    let stamp = chrono::Local::now().format("%Y%m%dT%H%M%S%3f");
+    let path = dir.join(format!("{}.{}.md", file_name, stamp));
+    write_atomic(&path, previous)
+        .with_context(|| format!("Failed to write backup {}", path.display()))?;
+
+    let existing = list_backups(config, date, file_name);
+    let excess = existing.len().saturating_sub(MAX_BACKUPS);
+    for old in existing.into_iter().take(excess) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(path)
+}
+
+/// Most recent backup of an archive file, if any
+pub fn latest_backup(config: &Config, date: &str, file_name: &str) -> Option<PathBuf> {
+    list_backups(config, date, file_name).pop()
+}
+
+/// Move the backups of a renamed session along with it
+pub fn rename_backups(config: &Config, date: &str, old: &str, new: &str) -> Result<()> {
+    let prefix = format!("{}.", old);
+    for path in list_backups(config, date, old) {
+        let Some(stamp) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix(&prefix))
+        else {
+            continue;
+        };
+        let target = path.with_file_name(format!("{}.{}", new, stamp));
+        fs::rename(&path, &target)
+            .with_context(|| format!("Failed to move backup {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Backups of an archive file, oldest first
+fn list_backups(config: &Config, date: &str, file_name: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}.", file_name);
+    let Ok(entries) = fs::read_dir(backups_dir(config, date)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.strip_suffix(".md"))
+                .is_some_and(|stamp| !stamp.contains('.'))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+fn backups_dir(config: &Config, date: &str) -> PathBuf {
+    config.storage_path().join(".backups").join(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SESSION: &str = "---\ntitle: \"Fix bug\"\n---\n\n# Fix bug\n\n## Summary\n\nOld summary.\n\n## Key Decisions\n\n- One\n\n---\n*Archived by Daily Context Archive System*\n";
+
+    #[test]
+    fn test_replace_section() {
+        let edited = replace_section(SESSION, "Summary", "New summary.\n").unwrap();
+        assert!(edited.contains("## Summary\n\nNew summary.\n\n## Key Decisions"));
+        assert!(!edited.contains("Old summary"));
+
+        // The last section keeps the footer
+        let edited = replace_section(&edited, "Key Decisions", "- Two").unwrap();
+        assert!(edited.ends_with("- Two\n\n---\n*Archived by Daily Context Archive System*\n"));
+
+        assert!(replace_section(SESSION, "Missing", "x").is_err());
+    }
+
+    #[test]
+    fn test_backups_are_capped() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        for i in 0..MAX_BACKUPS + 2 {
+            backup(&config, "2024-01-15", "fix-bug", &format!("v{}", i)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        backup(&config, "2024-01-15", "other", "x").unwrap();
+
+        let names: Vec<String> = fs::read_dir(backups_dir(&config, "2024-01-15"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|n| n.starts_with("fix-bug."))
+            .collect();
+        assert_eq!(names.len(), MAX_BACKUPS);
+    }
+}
@@ -0,0 +1,80 @@
+//! Minimal line-based access to the YAML frontmatter of archive files.
+//! Archives are written by our own templates, so one `key: value` per line
+//! is all that needs handling.
+
+/// Split `---\n...\n---` frontmatter from the body, if present
+fn split(content: &str) -> Option<(&str, &str)> {
+    let stripped = content.strip_prefix("---\n")?;
+    let end = stripped.find("\n---")?;
+    Some((&stripped[..end], &stripped[end + 1..]))
+}
+
+/// Read a frontmatter value, with surrounding quotes removed
+pub fn get<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let (frontmatter, _) = split(content)?;
+    frontmatter.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"'))
+    })
+}
+
+/// Content after the frontmatter; content without frontmatter is returned whole
+pub fn body(content: &str) -> &str {
+    match split(content) {
+        Some((_, rest)) => rest.strip_prefix("---").unwrap_or(rest),
+        None => content,
+    }
+}
+
+/// Set (or with `None`, remove) a frontmatter value, returning the new content.
+/// Content without frontmatter is returned unchanged.
+pub fn set(content: &str, key: &str, value: Option<&str>) -> String {
+    let Some((frontmatter, rest)) = split(content) else {
+        return content.to_string();
+    };
+
+    let mut lines: Vec<String> = frontmatter
+        .lines()
+        .filter(|line| line.split_once(':').is_none_or(|(k, _)| k.trim() != key))
+        .map(str::to_string)
+        .collect();
+    if let Some(value) = value {
+        lines.push(format!("{}: {}", key, value));
+    }
+
+    format!("---\n{}\n{}", lines.join("\n"), rest)
+}
+
+/// Whether an archive is marked `pinned: true`
+pub fn is_pinned(content: &str) -> bool {
+    get(content, "pinned") == Some("true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCHIVE: &str = "---\ntitle: \"Fix bug\"\ndate: 2024-01-15\n---\n\n# Fix bug\n";
+
+    #[test]
+    fn test_get_and_set() {
+        assert_eq!(get(ARCHIVE, "title"), Some("Fix bug"));
+        assert_eq!(get(ARCHIVE, "pinned"), None);
+
+        let pinned = set(ARCHIVE, "pinned", Some("true"));
+        assert!(is_pinned(&pinned));
+        assert!(pinned.ends_with("---\n\n# Fix bug\n"));
+
+        // Setting again replaces rather than duplicates
+        let pinned = set(&pinned, "pinned", Some("true"));
+        assert_eq!(pinned.matches("pinned:").count(), 1);
+
+        assert_eq!(set(&pinned, "pinned", None), ARCHIVE);
+    }
+
+    #[test]
+    fn test_set_without_frontmatter_is_noop() {
+        assert_eq!(set("# Title\n", "pinned", Some("true")), "# Title\n");
+        assert!(!is_pinned("# Title\n"));
+    }
+}
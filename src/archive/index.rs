@@ -0,0 +1,269 @@
+//! Per-date metadata index of session archives, so listings and filters
+//! don't have to read every session file on each request.
+//!
+//! Stored at `{storage}/.index/{date}.json` and refreshed lazily: only
+//! sessions whose file changed since they were indexed are re-read.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::frontmatter;
+use super::manager::ArchiveManager;
+use super::retention::compressed_path;
+use super::session::has_skill_hints;
+use crate::config::Config;
+
+const SKILL_HINTS_HEADING: &str = "## Potential Skills/Commands";
+
+/// Metadata kept for one session archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionIndexEntry {
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub has_skill_hints: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    /// File modification time (ns since epoch) and size when the entry was built
+    #[serde(default)]
+    stamp: (u128, u64),
+}
+
+/// Filters applied to a date's sessions; `None` fields match everything
+#[derive(Debug, Default, Clone)]
+pub struct SessionFilter {
+    /// Case-insensitive title substring
+    pub title: Option<String>,
+    pub tag: Option<String>,
+    pub has_skill_hints: Option<bool>,
+}
+
+impl SessionFilter {
+    /// Build a filter from API query parameters, ignoring empty values
+    pub fn from_params(params: &HashMap<String, String>) -> Self {
+        let get = |key: &str| {
+            params
+                .get(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            title: get("title").or_else(|| get("q")),
+            tag: get("tag"),
+            has_skill_hints: get("has_skill_hints").and_then(|v| v.parse().ok()),
+        }
+    }
+
+    pub fn matches(&self, entry: &SessionIndexEntry) -> bool {
+        self.title
+            .as_ref()
+            .is_none_or(|query| entry.title.to_lowercase().contains(&query.to_lowercase()))
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| entry.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            && self
+                .has_skill_hints
+                .is_none_or(|wanted| entry.has_skill_hints == wanted)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DateIndex {
+    #[serde(default)]
+    sessions: BTreeMap<String, SessionIndexEntry>,
+}
+
+fn index_path(config: &Config, date: &str) -> PathBuf {
+    config
+        .storage_path()
+        .join(".index")
+        .join(format!("{}.json", date))
+}
+
+/// Sessions of a date in name order, re-reading only changed files.
+/// Saving the refreshed index is best-effort.
+pub(super) fn load(
+    manager: &ArchiveManager,
+    config: &Config,
+    date: &str,
+) -> Result<Vec<SessionIndexEntry>> {
+    let path = index_path(config, date);
+    let mut index: DateIndex = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let names = manager.list_sessions(date)?;
+    let mut changed = index.sessions.len() != names.len();
+    let mut sessions = BTreeMap::new();
+    for name in names {
+        let stamp = file_stamp(&manager.session_archive_path(date, &name));
+        let entry = match index.sessions.remove(&name) {
+            Some(entry) if entry.stamp == stamp => entry,
+            _ => {
+                let Ok(content) = manager.read_session(date, &name) else {
+                    continue;
+                };
+                changed = true;
+                build_entry(&name, &content, stamp)
+            }
+        };
+        sessions.insert(name, entry);
+    }
+
+    let index = DateIndex { sessions };
+    if changed {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string(&index) {
+            let _ = fs::write(&path, json);
+        }
+    }
+    Ok(index.sessions.into_values().collect())
+}
+
+fn build_entry(name: &str, content: &str, stamp: (u128, u64)) -> SessionIndexEntry {
+    SessionIndexEntry {
+        name: name.to_string(),
+        title: frontmatter::get(content, "title")
+            .unwrap_or(name)
+            .to_string(),
+        tags: frontmatter::get(content, "tags")
+            .map(parse_tags)
+            .unwrap_or_default(),
+        has_skill_hints: skill_hints_section(content).is_some_and(has_skill_hints),
+        pinned: frontmatter::is_pinned(content),
+        stamp,
+    }
+}
+
+/// Modification time and size of a session file or its compacted copy
+fn file_stamp(path: &Path) -> (u128, u64) {
+    let compressed = compressed_path(path);
+    let path = if path.exists() {
+        path
+    } else {
+        compressed.as_path()
+    };
+    let Ok(meta) = fs::metadata(path) else {
+        return (0, 0);
+    };
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    (modified, meta.len())
+}
+
+/// Parse a YAML flow list like `[claude-code, "session-archive"]`
+fn parse_tags(value: &str) -> Vec<String> {
+    value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|tag| tag.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn skill_hints_section(content: &str) -> Option<&str> {
+    let start = content.find(SKILL_HINTS_HEADING)? + SKILL_HINTS_HEADING.len();
+    let rest = &content[start..];
+    let end = [rest.find("\n## "), rest.find("\n---\n")]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session(title: &str, skills: &str) -> String {
+        format!(
+            "---\ntitle: \"{title}\"\ntags: [claude-code, rust]\n---\n\n# {title}\n\n## Summary\n\nDone.\n\n{SKILL_HINTS_HEADING}\n\n{skills}\n\n---\n*Archived by Daily Context Archive System*\n"
+        )
+    }
+
+    #[test]
+    fn test_index_entries_and_filters() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        manager
+            .write_session(
+                "2024-01-15",
+                "fix-bug",
+                &session("Fix parser bug", "None identified"),
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-01-15",
+                "add-cli",
+                &session("Add CLI flag", "- **release-notes**: trigger: release"),
+            )
+            .unwrap();
+
+        let entries = manager.session_index("2024-01-15").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "add-cli");
+        assert!(entries[0].has_skill_hints);
+        assert!(!entries[1].has_skill_hints);
+        assert_eq!(entries[1].tags, vec!["claude-code", "rust"]);
+        assert!(index_path(&config, "2024-01-15").exists());
+
+        let filter = SessionFilter {
+            title: Some("parser".into()),
+            tag: Some("Rust".into()),
+            ..Default::default()
+        };
+        let matched: Vec<_> = entries.iter().filter(|e| filter.matches(e)).collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].name, "fix-bug");
+
+        let filter = SessionFilter {
+            has_skill_hints: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(entries.iter().filter(|e| filter.matches(e)).count(), 1);
+    }
+
+    #[test]
+    fn test_index_refreshes_changed_and_removed_sessions() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        manager
+            .write_session("2024-01-15", "a", &session("First", ""))
+            .unwrap();
+        manager
+            .write_session("2024-01-15", "b", &session("Second", ""))
+            .unwrap();
+        assert_eq!(manager.session_index("2024-01-15").unwrap().len(), 2);
+
+        manager.set_pinned("2024-01-15", "a", true).unwrap();
+        fs::remove_file(manager.session_archive_path("2024-01-15", "b")).unwrap();
+
+        let entries = manager.session_index("2024-01-15").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].pinned);
+    }
+}
@@ -0,0 +1,493 @@
+//! Configurable archive layout: where each date's folder lives under the
+//! storage root (`archive.directory_layout`) and how session files are
+//! named (`archive.session_name_template`).
+//!
+//! Templates use `{var}` placeholders; unknown variables are kept literally.
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIRECTORY_LAYOUT: &str = "{date}";
+pub const DEFAULT_SESSION_NAME_TEMPLATE: &str = "{HH}_{mm}-{topic}";
+
+enum Token<'a> {
+    Literal(&'a str),
+    Var(&'a str),
+}
+
+fn tokens(template: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Literal(&rest[..start]));
+        }
+        tokens.push(Token::Var(&rest[start + 1..start + len]));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Literal(rest));
+    }
+    tokens
+}
+
+fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    tokens(template)
+        .into_iter()
+        .map(|token| match token {
+            Token::Literal(text) => text.to_string(),
+            Token::Var(var) => value(var).unwrap_or_else(|| format!("{{{}}}", var)),
+        })
+        .collect()
+}
+
+/// Check that a directory layout identifies the date: it must use `{date}`
+/// or all of `{YYYY}`, `{MM}` and `{DD}`, and stay inside the storage root
+pub fn validate_layout(layout: &str) -> Result<()> {
+    let vars: Vec<&str> = tokens(layout)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Var(var) => Some(var),
+            Token::Literal(_) => None,
+        })
+        .collect();
+    let has = |var: &str| vars.contains(&var);
+    let identifies_date = has("date") || (has("YYYY") && has("MM") && has("DD"));
+    if !identifies_date {
+        anyhow::bail!(
+            "Invalid directory_layout '{}': use {{date}} or all of {{YYYY}}, {{MM}} and {{DD}}",
+            layout
+        );
+    }
+    if layout.starts_with('/') || layout.split('/').any(|segment| segment == "..") {
+        anyhow::bail!(
+            "Invalid directory_layout '{}': must be a relative path inside the storage directory",
+            layout
+        );
+    }
+    Ok(())
+}
+
+/// Invalid layouts fall back to the default so archives are never written
+/// to an ambiguous location
+fn effective_layout(layout: &str) -> &str {
+    if validate_layout(layout).is_ok() {
+        layout
+    } else {
+        DEFAULT_DIRECTORY_LAYOUT
+    }
+}
+
+/// Directory of a date relative to the storage root
+pub fn date_dir(layout: &str, date: &str) -> PathBuf {
+    let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+        return PathBuf::from(date);
+    };
+    let rendered = render(effective_layout(layout), |var| {
+        let format = match var {
+            "date" => "%Y-%m-%d",
+            "YYYY" => "%Y",
+            "MM" => "%m",
+            "DD" => "%d",
+            _ => return None,
+        };
+        Some(day.format(format).to_string())
+    });
+    PathBuf::from(rendered.trim_matches('/'))
+}
+
+/// Date components collected while matching a path against a layout
+#[derive(Debug, Default, Clone)]
+struct DateParts {
+    date: Option<String>,
+    year: Option<String>,
+    month: Option<String>,
+    day: Option<String>,
+}
+
+impl DateParts {
+    fn set(&mut self, var: &str, value: &str) -> Option<()> {
+        let slot = match var {
+            "date" => &mut self.date,
+            "YYYY" => &mut self.year,
+            "MM" => &mut self.month,
+            "DD" => &mut self.day,
+            _ => return None,
+        };
+        // A variable used twice must match the same value
+        match slot {
+            Some(existing) if existing != value => None,
+            _ => {
+                *slot = Some(value.to_string());
+                Some(())
+            }
+        }
+    }
+
+    fn to_date(&self) -> Option<String> {
+        let from_parts = match (&self.year, &self.month, &self.day) {
+            (Some(y), Some(m), Some(d)) => Some(format!("{}-{}-{}", y, m, d)),
+            _ => None,
+        };
+        let date = match (&self.date, from_parts) {
+            (Some(date), Some(parts)) if *date != parts => return None,
+            (Some(date), _) => date.clone(),
+            (None, Some(parts)) => parts,
+            (None, None) => return None,
+        };
+        NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?;
+        Some(date)
+    }
+}
+
+fn match_segment(segment: &str, name: &str, mut parts: DateParts) -> Option<DateParts> {
+    let mut rest = name;
+    for token in tokens(segment) {
+        match token {
+            Token::Literal(text) => rest = rest.strip_prefix(text)?,
+            Token::Var(var) => {
+                let width = match var {
+                    "date" => 10,
+                    "YYYY" => 4,
+                    "MM" | "DD" => 2,
+                    _ => {
+                        rest = rest.strip_prefix(&format!("{{{}}}", var))?;
+                        continue;
+                    }
+                };
+                let value = rest.get(..width)?;
+                parts.set(var, value)?;
+                rest = &rest[width..];
+            }
+        }
+    }
+    rest.is_empty().then_some(parts)
+}
+
+fn walk(dir: &Path, segments: &[&str], parts: DateParts, found: &mut Vec<(String, PathBuf)>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        if let Some(date) = parts.to_date() {
+            found.push((date, dir.to_path_buf()));
+        }
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(parts) = match_segment(segment, &name, parts.clone()) {
+            walk(&path, rest, parts, found);
+        }
+    }
+}
+
+/// Date folders under `root` laid out by `layout`, as `(date, path)`
+fn find_date_dirs(root: &Path, layout: &str) -> Vec<(String, PathBuf)> {
+    let segments: Vec<&str> = effective_layout(layout)
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let mut found = Vec::new();
+    walk(root, &segments, DateParts::default(), &mut found);
+    found
+}
+
+/// Dates archived under `root` with the given layout, most recent first
+pub fn find_dates(root: &Path, layout: &str) -> Vec<String> {
+    let mut dates: Vec<String> = find_date_dirs(root, layout)
+        .into_iter()
+        .map(|(date, _)| date)
+        .collect();
+    dates.sort();
+    dates.dedup();
+    dates.reverse();
+    dates
+}
+
+/// Values available to `session_name_template`
+pub struct SessionNameVars<'a> {
+    pub archived_at: NaiveDateTime,
+    /// Sanitized AI-generated topic
+    pub topic: &'a str,
+    /// Last component of the session's working directory
+    pub project: &'a str,
+    pub session_id: &'a str,
+}
+
+/// Render a session file name (without `.md`) from the template
+pub fn session_name(template: &str, vars: &SessionNameVars) -> String {
+    let rendered = render(template, |var| {
+        let format = match var {
+            "topic" => return Some(vars.topic.to_string()),
+            "project" => return Some(vars.project.to_string()),
+            "session_id" => return Some(vars.session_id.to_string()),
+            "short_id" => return Some(vars.session_id.chars().take(8).collect()),
+            "date" => "%Y-%m-%d",
+            "YYYY" => "%Y",
+            "MM" => "%m",
+            "DD" => "%d",
+            "HH" => "%H",
+            "mm" => "%M",
+            "ss" => "%S",
+            _ => return None,
+        };
+        Some(vars.archived_at.format(format).to_string())
+    });
+    // Session names are file names and URL segments
+    let name = rendered.replace(['/', '\\'], "-");
+    let name = name.trim();
+    if name.is_empty() {
+        vars.topic.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Sanitize topic for use in filename
+pub fn sanitize_topic(topic: &str) -> String {
+    // Convert to lowercase, replace spaces with hyphens, remove invalid chars
+    let sanitized: String = topic
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '-' => c,
+            ' ' | '_' => '-',
+            _ => '-',
+        })
+        .collect();
+
+    // Remove consecutive hyphens and trim
+    let mut result = String::new();
+    let mut prev_hyphen = false;
+    for c in sanitized.chars() {
+        if c == '-' {
+            if !prev_hyphen && !result.is_empty() {
+                result.push(c);
+            }
+            prev_hyphen = true;
+        } else {
+            result.push(c);
+            prev_hyphen = false;
+        }
+    }
+
+    // Trim trailing hyphen and limit length
+    let result = result.trim_end_matches('-').to_string();
+    if result.len() > 50 {
+        result[..50].trim_end_matches('-').to_string()
+    } else if result.is_empty() {
+        "session".to_string()
+    } else {
+        result
+    }
+}
+
+/// Name of session `old_name` after changing its topic to `topic`: the
+/// parts the template renders around `{topic}` (time, project, ...) are
+/// kept as they are in the old name
+pub fn replace_topic(
+    template: &str,
+    old_name: &str,
+    vars: &SessionNameVars,
+    topic: &str,
+) -> String {
+    const MARK: &str = "\u{1}";
+    let marked = session_name(
+        template,
+        &SessionNameVars {
+            archived_at: vars.archived_at,
+            topic: MARK,
+            project: vars.project,
+            session_id: vars.session_id,
+        },
+    );
+    let Some((before, after)) = marked.split_once(MARK) else {
+        return topic.to_string();
+    };
+    // Every other variable renders at a fixed width for a given session
+    let (before, after) = (before.chars().count(), after.chars().count());
+    let old: Vec<char> = old_name.chars().collect();
+    if old.len() < before + after {
+        return topic.to_string();
+    }
+    let prefix: String = old[..before].iter().collect();
+    let suffix: String = old[old.len() - after..].iter().collect();
+    format!("{}{}{}", prefix, topic, suffix)
+}
+
+/// Date folders to move from layout `from` to layout `to`, as `(source, target)`
+pub fn plan_migration(root: &Path, from: &str, to: &str) -> Result<Vec<(PathBuf, PathBuf)>> {
+    validate_layout(from)?;
+    validate_layout(to)?;
+    let mut moves: Vec<(PathBuf, PathBuf)> = find_date_dirs(root, from)
+        .into_iter()
+        .map(|(date, source)| (source, root.join(date_dir(to, &date))))
+        .filter(|(source, target)| source != target)
+        .collect();
+    moves.sort();
+    Ok(moves)
+}
+
+/// Move date folders, removing parent folders the old layout leaves empty
+pub fn migrate(root: &Path, moves: &[(PathBuf, PathBuf)]) -> Result<()> {
+    for (source, target) in moves {
+        if target.exists() {
+            anyhow::bail!(
+                "Cannot move {} to {}: target already exists",
+                source.display(),
+                target.display()
+            );
+        }
+    }
+    for (source, target) in moves {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::rename(source, target).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                source.display(),
+                target.display()
+            )
+        })?;
+
+        let mut dir = source.parent();
+        while let Some(parent) = dir.filter(|d| *d != root && d.starts_with(root)) {
+            // remove_dir only succeeds on empty folders
+            if fs::remove_dir(parent).is_err() {
+                break;
+            }
+            dir = parent.parent();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_date_dir_layouts() {
+        assert_eq!(
+            date_dir(DEFAULT_DIRECTORY_LAYOUT, "2024-01-15"),
+            PathBuf::from("2024-01-15")
+        );
+        assert_eq!(
+            date_dir("{YYYY}/{MM}/{DD}/", "2024-01-15"),
+            PathBuf::from("2024/01/15")
+        );
+        assert_eq!(
+            date_dir("{YYYY}/{MM}/{date}", "2024-01-15"),
+            PathBuf::from("2024/01/2024-01-15")
+        );
+        // Layouts that don't identify the date fall back to the default
+        assert!(validate_layout("{YYYY}/{MM}").is_err());
+        assert!(validate_layout("../{date}").is_err());
+        assert_eq!(
+            date_dir("{YYYY}/{MM}", "2024-01-15"),
+            PathBuf::from("2024-01-15")
+        );
+    }
+
+    #[test]
+    fn test_find_dates_matches_layout() {
+        let dir = TempDir::new().unwrap();
+        for path in [
+            "2024/01/15",
+            "2024/02/03",
+            "2024/13/01",
+            "jobs",
+            "2024-01-20",
+        ] {
+            fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+
+        assert_eq!(
+            find_dates(dir.path(), "{YYYY}/{MM}/{DD}"),
+            vec!["2024-02-03", "2024-01-15"]
+        );
+        assert_eq!(find_dates(dir.path(), "{date}"), vec!["2024-01-20"]);
+    }
+
+    #[test]
+    fn test_session_name_template() {
+        let vars = SessionNameVars {
+            archived_at: NaiveDate::from_ymd_opt(2024, 1, 15)
+                .unwrap()
+                .and_hms_opt(14, 5, 9)
+                .unwrap(),
+            topic: "fix-auth-bug",
+            project: "my-app",
+            session_id: "abcdef123456",
+        };
+        assert_eq!(
+            session_name(DEFAULT_SESSION_NAME_TEMPLATE, &vars),
+            "14_05-fix-auth-bug"
+        );
+        assert_eq!(
+            session_name("{project}/{HH}{mm}{ss}-{topic}-{short_id}", &vars),
+            "my-app-140509-fix-auth-bug-abcdef12"
+        );
+        assert_eq!(
+            session_name("{unknown}-{topic}", &vars),
+            "{unknown}-fix-auth-bug"
+        );
+
+        // Renaming keeps the time and id around the new topic
+        assert_eq!(
+            replace_topic(
+                DEFAULT_SESSION_NAME_TEMPLATE,
+                "09_30-wrong-topic",
+                &vars,
+                "login-flow"
+            ),
+            "09_30-login-flow"
+        );
+        assert_eq!(
+            replace_topic(
+                "{project}/{HH}{mm}{ss}-{topic}-{short_id}",
+                "my-app-093000-old-abcdef12",
+                &vars,
+                "new"
+            ),
+            "my-app-093000-new-abcdef12"
+        );
+        assert_eq!(sanitize_topic("Fix  Login Flow!"), "fix-login-flow");
+    }
+
+    #[test]
+    fn test_migrate_between_layouts() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("2024-01-15/transcripts")).unwrap();
+        fs::write(root.join("2024-01-15/fix.md"), "# Fix").unwrap();
+
+        let moves = plan_migration(root, "{date}", "{YYYY}/{MM}/{DD}").unwrap();
+        assert_eq!(moves.len(), 1);
+        migrate(root, &moves).unwrap();
+
+        assert!(root.join("2024/01/15/fix.md").exists());
+        assert!(root.join("2024/01/15/transcripts").is_dir());
+        assert!(!root.join("2024-01-15").exists());
+
+        // And back again, cleaning up the emptied year/month folders
+        let moves = plan_migration(root, "{YYYY}/{MM}/{DD}", "{date}").unwrap();
+        migrate(root, &moves).unwrap();
+        assert!(root.join("2024-01-15/fix.md").exists());
+        assert!(!root.join("2024").exists());
+    }
+}
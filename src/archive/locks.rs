@@ -0,0 +1,110 @@
+//! Dates finalized with `daily lock`.
+//!
+//! Locks are kept in `{storage}/locks.json`. Digests of a locked date are
+//! refused unless forced, and sessions are not archived under it, so a
+//! digest that was already shared is not rewritten by a late session.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::storage;
+
+/// Locked dates and when each was locked
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DayLocks {
+    #[serde(default)]
+    pub dates: BTreeMap<String, DateTime<Local>>,
+}
+
+fn locks_path(config: &Config) -> PathBuf {
+    config.storage_path().join("locks.json")
+}
+
+impl DayLocks {
+    /// Saved locks; none if the file doesn't exist yet
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = locks_path(config);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = locks_path(config);
+        fs::create_dir_all(config.storage_path())?;
+        storage::write_file(config, &path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Lock `date`; returns false if it already was
+    pub fn lock(&mut self, date: &str) -> Result<bool> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}': expected YYYY-MM-DD", date))?;
+        if self.dates.contains_key(date) {
+            return Ok(false);
+        }
+        self.dates.insert(date.to_string(), Local::now());
+        Ok(true)
+    }
+
+    /// Unlock `date`; returns false if it wasn't locked
+    pub fn unlock(&mut self, date: &str) -> bool {
+        self.dates.remove(date).is_some()
+    }
+}
+
+/// Whether `date` is locked; an unreadable lock file counts as no locks
+pub fn is_locked(config: &Config, date: &str) -> bool {
+    DayLocks::load(config).is_ok_and(|locks| locks.dates.contains_key(date))
+}
+
+/// Fail if `date` is locked, naming the way out
+pub fn ensure_unlocked(config: &Config, date: &str, action: &str) -> Result<()> {
+    if is_locked(config, date) {
+        anyhow::bail!(
+            "{} is locked; {} refused (unlock with `daily lock --remove {}`)",
+            date,
+            action,
+            date
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_and_unlock_dates() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        let mut locks = DayLocks::load(&config).unwrap();
+        assert!(locks.lock("2024-01-15").unwrap());
+        assert!(!locks.lock("2024-01-15").unwrap());
+        assert!(locks.lock("yesterday").is_err());
+        locks.save(&config).unwrap();
+
+        assert!(is_locked(&config, "2024-01-15"));
+        let err = ensure_unlocked(&config, "2024-01-15", "digest").unwrap_err();
+        assert!(err.to_string().contains("daily lock --remove 2024-01-15"));
+        assert!(ensure_unlocked(&config, "2024-01-16", "digest").is_ok());
+
+        let mut locks = DayLocks::load(&config).unwrap();
+        assert!(locks.unlock("2024-01-15"));
+        assert!(!locks.unlock("2024-01-15"));
+        locks.save(&config).unwrap();
+        assert!(!is_locked(&config, "2024-01-15"));
+    }
+}
@@ -1,18 +1,83 @@
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
-
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::annotation::Annotation;
+use super::cache::ReadCache;
+use super::citations::{self, Citation};
+use super::daily;
+use super::edit;
+use super::frontmatter;
+use super::index::{self, SessionIndexEntry};
+use super::layout;
+use super::retention;
+use super::schema::{self, Kind};
 use super::templates::Templates;
 use crate::config::Config;
+use crate::storage;
+
+/// An archive file brought up to the current schema version
+#[derive(Debug, Clone, Serialize)]
+pub struct MigratedFile {
+    pub path: PathBuf,
+    pub from: u32,
+    pub steps: Vec<&'static str>,
+}
 
 /// Manages archive directory structure and file operations
 pub struct ArchiveManager {
     config: Config,
+    cache: Option<Arc<ReadCache>>,
 }
 
 impl ArchiveManager {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            cache: None,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// A manager whose session and summary reads go through `cache`
+    pub fn with_cache(config: Config, cache: Arc<ReadCache>) -> Self {
+        Self {
+            config,
+            cache: Some(cache),
+        }
+    }
+
+    /// Read `path` through the cache, if this manager has one
+    fn cached_read(&self, path: &Path, load: impl FnOnce() -> Result<String>) -> Result<String> {
+        match &self.cache {
+            Some(cache) => cache.read(path, load),
+            None => load(),
+        }
+    }
+
+    fn invalidate(&self, path: &Path) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(path);
+        }
+    }
+
+    /// Write a file under the storage folder and drop it from the cache
+    fn write_file(&self, path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+        storage::write_file(&self.config, path, contents)?;
+        self.invalidate(path);
+        Ok(())
+    }
+
+    /// Delete a file under the storage folder and drop it from the cache
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        storage::remove_file(&self.config, path)?;
+        self.invalidate(path);
+        Ok(())
     }
 
     /// Ensure the storage directory exists
@@ -35,7 +100,8 @@ impl ArchiveManager {
             let daily_md = today_dir.join("daily.md");
             let today = chrono::Local::now().format("%Y-%m-%d").to_string();
             let content = Templates::daily_init(&today);
-            fs::write(&daily_md, content).context("Failed to write daily.md")?;
+            self.write_file(&daily_md, content)
+                .context("Failed to write daily.md")?;
         }
 
         Ok(today_dir)
@@ -43,6 +109,7 @@ impl ArchiveManager {
 
     /// Ensure a specific date's directory exists
     pub fn ensure_date_dir(&self, date: &str) -> Result<PathBuf> {
+        check_date(date)?;
         let date_dir = self.config.date_dir(date);
 
         if !date_dir.exists() {
@@ -51,7 +118,8 @@ impl ArchiveManager {
             // Initialize daily.md
             let daily_md = date_dir.join("daily.md");
             let content = Templates::daily_init(date);
-            fs::write(&daily_md, content).context("Failed to write daily.md")?;
+            self.write_file(&daily_md, content)
+                .context("Failed to write daily.md")?;
         }
 
         Ok(date_dir)
@@ -67,6 +135,38 @@ impl ArchiveManager {
         self.config.date_dir(date).join("daily.md")
     }
 
+    /// Path of a digest translation, e.g. `daily.zh.md`
+    pub fn translated_summary_path(&self, date: &str, language: &str) -> Result<PathBuf> {
+        check_date(date)?;
+        if language.is_empty()
+            || !language
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            anyhow::bail!("Invalid language '{}'", language);
+        }
+        Ok(self
+            .config
+            .date_dir(date)
+            .join(format!("daily.{}.md", language)))
+    }
+
+    /// Languages a date's digest was translated into, sorted
+    pub fn digest_languages(&self, date: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.config.date_dir(date)) else {
+            return Vec::new();
+        };
+        let mut languages: Vec<String> = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().to_string_lossy().to_string();
+                let language = name.strip_prefix("daily.")?.strip_suffix(".md")?;
+                (!language.is_empty()).then(|| language.to_string())
+            })
+            .collect();
+        languages.sort();
+        languages
+    }
+
     /// List all session archives for a date
     pub fn list_sessions(&self, date: &str) -> Result<Vec<String>> {
         let date_dir = self.config.date_dir(date);
@@ -81,21 +181,25 @@ impl ArchiveManager {
             let path = entry.path();
 
             if path.is_file() {
-                if let Some(name) = path.file_stem() {
-                    let name_str = name.to_string_lossy().to_string();
-                    // Skip daily.md
-                    if name_str != "daily" {
-                        sessions.push(name_str);
-                    }
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                // Sessions compacted by `daily prune` are stored as .md.zst
+                let name = file_name
+                    .strip_suffix(".md.zst")
+                    .or_else(|| file_name.strip_suffix(".md"))
+                    .or_else(|| path.file_stem().and_then(|s| s.to_str()));
+                // Skip daily.md and its translations (daily.zh.md)
+                if let Some(name) = name.filter(|n| *n != "daily" && !n.starts_with("daily.")) {
+                    sessions.push(name.to_string());
                 }
             }
         }
 
         sessions.sort();
+        sessions.dedup();
         Ok(sessions)
     }
 
-    /// List all available dates in the archive
+    /// List all available dates in the archive, most recent first
     pub fn list_dates(&self) -> Result<Vec<String>> {
         let storage_path = self.config.storage_path();
 
@@ -103,63 +207,348 @@ impl ArchiveManager {
             return Ok(Vec::new());
         }
 
-        let mut dates = Vec::new();
-        for entry in fs::read_dir(&storage_path)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                if let Some(name) = path.file_name() {
-                    let name_str = name.to_string_lossy().to_string();
-                    // Check if it looks like a date (yyyy-mm-dd)
-                    if name_str.len() == 10 && name_str.chars().nth(4) == Some('-') {
-                        dates.push(name_str);
-                    }
-                }
-            }
-        }
-
-        dates.sort();
-        dates.reverse(); // Most recent first
-        Ok(dates)
+        Ok(layout::find_dates(
+            &storage_path,
+            &self.config.archive.directory_layout,
+        ))
     }
 
-    /// Read a session archive file
+    /// Read a session archive file, decompressing it if it was compacted.
+    /// Files in an older format are upgraded (and rewritten) first.
     pub fn read_session(&self, date: &str, task_name: &str) -> Result<String> {
+        check_session(date, task_name)?;
         let path = self.session_archive_path(date, task_name);
-        fs::read_to_string(&path).context(format!(
-            "Failed to read session archive: {}",
-            path.display()
-        ))
+        let compressed = retention::compressed_path(&path);
+        if !path.exists() && compressed.exists() {
+            // Compacted archives are upgraded in memory only
+            return self.cached_read(&compressed, || {
+                let content = retention::read_compressed(&compressed)?;
+                Ok(schema::upgrade(&content, Kind::Session)
+                    .map(|upgrade| upgrade.content)
+                    .unwrap_or(content))
+            });
+        }
+        self.cached_read(&path, || {
+            let content = fs::read_to_string(&path).context(format!(
+                "Failed to read session archive: {}",
+                path.display()
+            ))?;
+            Ok(upgrade_in_place(
+                &self.config,
+                &path,
+                content,
+                Kind::Session,
+            ))
+        })
     }
 
-    /// Read the daily summary file
+    /// Read the daily summary file, upgrading an older format first
     pub fn read_daily_summary(&self, date: &str) -> Result<String> {
+        check_date(date)?;
         let path = self.daily_summary_path(date);
-        fs::read_to_string(&path)
-            .context(format!("Failed to read daily summary: {}", path.display()))
+        self.cached_read(&path, || {
+            let content = fs::read_to_string(&path)
+                .context(format!("Failed to read daily summary: {}", path.display()))?;
+            Ok(upgrade_in_place(&self.config, &path, content, Kind::Daily))
+        })
+    }
+
+    /// A digest translation written with `write_translated_summary`
+    pub fn read_translated_summary(&self, date: &str, language: &str) -> Result<String> {
+        let path = self.translated_summary_path(date, language)?;
+        self.cached_read(&path, || {
+            fs::read_to_string(&path).context(format!("No {} digest for {}", language, date))
+        })
+    }
+
+    /// Upgrade every archive file to the current schema version, or with
+    /// `dry_run` only report what would change
+    pub fn migrate_schema(&self, dry_run: bool) -> Result<Vec<MigratedFile>> {
+        let mut migrated = Vec::new();
+        for date in self.list_dates()? {
+            let mut files = vec![(self.daily_summary_path(&date), Kind::Daily)];
+            for name in self.list_sessions(&date)? {
+                files.push((self.session_archive_path(&date, &name), Kind::Session));
+            }
+            for (path, kind) in files {
+                // Compacted sessions stay compacted; they upgrade on read
+                let Ok(content) = fs::read_to_string(&path) else {
+                    continue;
+                };
+                let Some(upgrade) = schema::upgrade(&content, kind) else {
+                    continue;
+                };
+                if !dry_run {
+                    self.write_file(&path, &upgrade.content)?;
+                }
+                migrated.push(MigratedFile {
+                    path,
+                    from: upgrade.from,
+                    steps: upgrade.steps,
+                });
+            }
+        }
+        Ok(migrated)
     }
 
     /// Write a session archive file
     pub fn write_session(&self, date: &str, task_name: &str, content: &str) -> Result<PathBuf> {
+        check_session(date, task_name)?;
         self.ensure_date_dir(date)?;
         let path = self.session_archive_path(date, task_name);
-        fs::write(&path, content).context(format!(
+        self.write_file(&path, content).context(format!(
             "Failed to write session archive: {}",
             path.display()
         ))?;
+        // A rewritten session supersedes its compacted copy
+        let compressed = retention::compressed_path(&path);
+        if compressed.exists() {
+            self.remove_file(&compressed)?;
+        }
         Ok(path)
     }
 
     /// Write the daily summary file
     pub fn write_daily_summary(&self, date: &str, content: &str) -> Result<PathBuf> {
+        check_date(date)?;
         self.ensure_date_dir(date)?;
         let path = self.daily_summary_path(date);
-        fs::write(&path, content)
+        self.write_file(&path, content)
             .context(format!("Failed to write daily summary: {}", path.display()))?;
         Ok(path)
     }
 
+    pub fn write_translated_summary(
+        &self,
+        date: &str,
+        language: &str,
+        content: &str,
+    ) -> Result<PathBuf> {
+        self.ensure_date_dir(date)?;
+        let path = self.translated_summary_path(date, language)?;
+        self.write_file(&path, content).context(format!(
+            "Failed to write digest translation: {}",
+            path.display()
+        ))?;
+        Ok(path)
+    }
+
+    /// Sessions cited for the cards of a date's digest; none when the
+    /// digest was generated without `summarization.digest_citations`
+    pub fn read_citations(&self, date: &str) -> Vec<Citation> {
+        let path = self.config.date_dir(date).join(citations::FILE_NAME);
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save a digest's citations, removing stale ones when there are none
+    pub fn write_citations(&self, date: &str, cited: &[Citation]) -> Result<()> {
+        let path = self.config.date_dir(date).join(citations::FILE_NAME);
+        if cited.is_empty() {
+            if path.exists() {
+                self.remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        self.ensure_date_dir(date)?;
+        self.write_file(&path, serde_json::to_string_pretty(cited)?)?;
+        Ok(())
+    }
+
+    /// Write a project's part of a grouped digest as `projects/{slug}.md` in
+    /// the date directory
+    pub fn write_project_digest(
+        &self,
+        date: &str,
+        project: &str,
+        content: &str,
+    ) -> Result<PathBuf> {
+        let dir = self.ensure_date_dir(date)?.join("projects");
+        fs::create_dir_all(&dir).context("Failed to create projects directory")?;
+        let path = dir.join(format!("{}.md", daily::card_slug(project)));
+        self.write_file(&path, content).context(format!(
+            "Failed to write project digest: {}",
+            path.display()
+        ))?;
+        Ok(path)
+    }
+
+    /// Replace a session archive with hand-edited content, backing up the previous version
+    pub fn edit_session(&self, date: &str, task_name: &str, content: &str) -> Result<PathBuf> {
+        check_session(date, task_name)?;
+        let previous = self.read_session(date, task_name)?;
+        edit::backup(&self.config, date, task_name, &previous)?;
+        self.write_session(date, task_name, content)
+    }
+
+    /// Replace a daily summary with hand-edited content, backing up the previous version
+    pub fn edit_daily_summary(&self, date: &str, content: &str) -> Result<PathBuf> {
+        check_date(date)?;
+        let previous = self.read_daily_summary(date)?;
+        edit::backup(&self.config, date, "daily", &previous)?;
+        self.write_daily_summary(date, content)
+    }
+
+    /// Pin or unpin a session by rewriting its frontmatter
+    pub fn set_pinned(&self, date: &str, task_name: &str, pinned: bool) -> Result<()> {
+        let content = self.read_session(date, task_name)?;
+        if !content.starts_with("---\n") {
+            anyhow::bail!("Session {}/{} has no frontmatter", date, task_name);
+        }
+        let updated = frontmatter::set(&content, "pinned", pinned.then_some("true"));
+        self.write_session(date, task_name, &updated)?;
+        Ok(())
+    }
+
+    /// Merge a user rating into a session's frontmatter, or remove it with
+    /// `clear`, returning the stored annotation
+    pub fn set_annotation(
+        &self,
+        date: &str,
+        task_name: &str,
+        update: Annotation,
+        clear: bool,
+    ) -> Result<Annotation> {
+        update.validate()?;
+        let content = self.read_session(date, task_name)?;
+        if !content.starts_with("---\n") {
+            anyhow::bail!("Session {}/{} has no frontmatter", date, task_name);
+        }
+        let current = if clear {
+            Annotation::default()
+        } else {
+            Annotation::read(&content).unwrap_or_default()
+        };
+        let updated = current.merge(update).apply(&content);
+        self.write_session(date, task_name, &updated)?;
+        Ok(Annotation::read(&updated).unwrap_or_default())
+    }
+
+    /// Give a session a new topic: the file is renamed per
+    /// `archive.session_name_template`, the topic becomes its title and the
+    /// digest's references to it are rewritten. Returns the new name.
+    pub fn rename_session(&self, date: &str, name: &str, topic: &str) -> Result<String> {
+        check_session(date, name)?;
+        let content = self.read_session(date, name)?;
+        let session_id = frontmatter::get(&content, "session_id").unwrap_or_default();
+        let project = frontmatter::get(&content, "cwd")
+            .and_then(|cwd| Path::new(cwd).file_name())
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // One frontmatter line, without quotes that would end the value early
+        let title = topic.split_whitespace().collect::<Vec<_>>().join(" ");
+        let title = title.replace('"', "'");
+        let topic = layout::sanitize_topic(topic);
+        let archived_at = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap_or_default()
+            .and_time(chrono::NaiveTime::MIN);
+        let new_name = layout::replace_topic(
+            &self.config.archive.session_name_template,
+            name,
+            &layout::SessionNameVars {
+                archived_at,
+                topic: &topic,
+                project: &project,
+                session_id,
+            },
+            &topic,
+        );
+
+        if new_name == name {
+            anyhow::bail!("Session {}/{} is already named {}", date, name, new_name);
+        }
+        let target = self.session_archive_path(date, &new_name);
+        if target.exists() || retention::compressed_path(&target).exists() {
+            anyhow::bail!("Session {}/{} already exists", date, new_name);
+        }
+
+        let old_title = frontmatter::get(&content, "title").unwrap_or(name);
+        let heading = format!("\n# {}\n", old_title);
+        let mut updated = frontmatter::set(&content, "title", Some(&format!("\"{}\"", title)));
+        if let Some(at) = updated.find(&heading) {
+            updated.replace_range(at..at + heading.len(), &format!("\n# {}\n", title));
+        }
+        self.write_session(date, &new_name, &updated)?;
+        self.remove_session(date, name)?;
+        edit::rename_backups(&self.config, date, name, &new_name)?;
+
+        if self.daily_summary_path(date).exists() {
+            let digest = self.read_daily_summary(date)?;
+            let renamed = daily::rename_session_reference(&digest, name, &new_name);
+            if renamed != digest {
+                self.write_daily_summary(date, &renamed)?;
+            }
+        }
+        Ok(new_name)
+    }
+
+    /// Sessions of a date whose frontmatter `session_id` is one of `ids`
+    pub fn find_sessions_by_id(&self, date: &str, ids: &[String]) -> Result<Vec<String>> {
+        Ok(self
+            .list_sessions(date)?
+            .into_iter()
+            .filter(|name| {
+                self.read_session(date, name).is_ok_and(|content| {
+                    frontmatter::get(&content, "session_id")
+                        .is_some_and(|id| ids.iter().any(|wanted| wanted == id))
+                })
+            })
+            .collect())
+    }
+
+    /// Delete a session archive, including its compacted copy
+    pub fn remove_session(&self, date: &str, task_name: &str) -> Result<()> {
+        check_session(date, task_name)?;
+        let path = self.session_archive_path(date, task_name);
+        let compressed = retention::compressed_path(&path);
+        if !path.exists() && !compressed.exists() {
+            anyhow::bail!("Session {}/{} not found", date, task_name);
+        }
+        for path in [path, compressed] {
+            if path.exists() {
+                self.remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a session is pinned (false if it can't be read)
+    pub fn is_pinned(&self, date: &str, task_name: &str) -> bool {
+        self.read_session(date, task_name)
+            .is_ok_and(|content| frontmatter::is_pinned(&content))
+    }
+
+    /// All pinned sessions as `(date, name)`, most recent date first
+    pub fn list_pinned(&self) -> Result<Vec<(String, String)>> {
+        let mut pinned = Vec::new();
+        for date in self.list_dates()? {
+            for entry in self.session_index(&date)? {
+                if entry.pinned {
+                    pinned.push((date.clone(), entry.name));
+                }
+            }
+        }
+        Ok(pinned)
+    }
+
+    /// Metadata of a date's sessions from the on-disk index, refreshed for changed files
+    pub fn session_index(&self, date: &str) -> Result<Vec<SessionIndexEntry>> {
+        index::load(self, &self.config, date)
+    }
+
+    /// Whether a date has a generated digest (not just the placeholder daily.md)
+    pub fn has_digest(&self, date: &str) -> bool {
+        self.read_daily_summary(date)
+            .map(|content| {
+                content.contains("## Overview")
+                    && !content.contains("No sessions recorded yet")
+                    && !content.contains("_No sessions archived yet._")
+            })
+            .unwrap_or(false)
+    }
+
     /// Check if a date has session files (un-digested sessions)
     pub fn has_sessions(&self, date: &str) -> bool {
         match self.list_sessions(date) {
@@ -167,6 +556,46 @@ impl ArchiveManager {
             Err(_) => false,
         }
     }
+
+    /// Dates with sessions but no digest, oldest first
+    pub fn undigested_dates(&self) -> Result<Vec<String>> {
+        let mut dates: Vec<String> = self
+            .list_dates()?
+            .into_iter()
+            .filter(|date| self.has_sessions(date) && !self.has_digest(date))
+            .collect();
+        dates.reverse();
+        Ok(dates)
+    }
+}
+
+/// Reject a date that is not `YYYY-MM-DD`: anything else would be used
+/// as a path relative to the storage root
+fn check_date(date: &str) -> Result<()> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}': expected YYYY-MM-DD", date))?;
+    Ok(())
+}
+
+/// Reject a session name that could resolve outside its date directory
+fn check_session(date: &str, task_name: &str) -> Result<()> {
+    check_date(date)?;
+    if task_name.is_empty() || task_name.contains(['/', '\\']) || task_name.contains("..") {
+        anyhow::bail!("Invalid session name '{}'", task_name);
+    }
+    Ok(())
+}
+
+/// Upgrade `content` read from `path` to the current schema, writing the
+/// result back; a failed write just leaves the upgrade for the next read
+fn upgrade_in_place(config: &Config, path: &Path, content: String, kind: Kind) -> String {
+    match schema::upgrade(&content, kind) {
+        Some(upgrade) => {
+            let _ = storage::write_file(config, path, &upgrade.content);
+            upgrade.content
+        }
+        None => content,
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +620,93 @@ mod tests {
         assert!(today_dir.join("daily.md").exists());
     }
 
+    #[test]
+    fn test_pin_and_unpin_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        let content = "---\ntitle: \"Fix bug\"\nschema_version: 2\n---\n\n# Fix bug\n";
+        manager
+            .write_session("2024-01-15", "fix-bug", content)
+            .unwrap();
+        manager
+            .write_session("2024-01-16", "other", content)
+            .unwrap();
+
+        manager.set_pinned("2024-01-15", "fix-bug", true).unwrap();
+        assert!(manager.is_pinned("2024-01-15", "fix-bug"));
+        assert_eq!(
+            manager.list_pinned().unwrap(),
+            vec![("2024-01-15".to_string(), "fix-bug".to_string())]
+        );
+
+        manager.set_pinned("2024-01-15", "fix-bug", false).unwrap();
+        assert!(manager.list_pinned().unwrap().is_empty());
+        assert_eq!(
+            manager.read_session("2024-01-15", "fix-bug").unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn test_translated_summaries() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        manager
+            .write_daily_summary("2024-01-15", "# Daily\n")
+            .unwrap();
+        manager
+            .write_session("2024-01-15", "10_00-fix-bug", "# Fix bug\n")
+            .unwrap();
+        manager
+            .write_translated_summary("2024-01-15", "zh", "# 日报\n")
+            .unwrap();
+
+        assert_eq!(
+            manager.read_translated_summary("2024-01-15", "zh").unwrap(),
+            "# 日报\n"
+        );
+        assert!(manager.read_translated_summary("2024-01-15", "en").is_err());
+        assert!(manager
+            .translated_summary_path("2024-01-15", "../x")
+            .is_err());
+        assert_eq!(manager.digest_languages("2024-01-15"), vec!["zh"]);
+        assert_eq!(
+            manager.list_sessions("2024-01-15").unwrap(),
+            vec!["10_00-fix-bug"]
+        );
+    }
+
+    #[test]
+    fn test_set_annotation_merges_and_clears() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        let content = "---\ntitle: \"Fix bug\"\nschema_version: 2\n---\n\n# Fix bug\n";
+        manager
+            .write_session("2024-01-15", "fix-bug", content)
+            .unwrap();
+
+        let rate = |outcome: Option<&str>, satisfaction: Option<&str>, clear| {
+            let update = Annotation {
+                outcome: outcome.map(Into::into),
+                satisfaction: satisfaction.map(Into::into),
+                note: None,
+                stars: None,
+            };
+            manager.set_annotation("2024-01-15", "fix-bug", update, clear)
+        };
+        rate(Some("achieved"), None, false).unwrap();
+        let annotation = rate(None, Some("happy"), false).unwrap();
+        assert_eq!(annotation.outcome.as_deref(), Some("achieved"));
+        assert_eq!(annotation.satisfaction.as_deref(), Some("happy"));
+        assert!(rate(Some("done"), None, false).is_err());
+
+        assert!(rate(None, None, true).unwrap().is_empty());
+        assert_eq!(
+            manager.read_session("2024-01-15", "fix-bug").unwrap(),
+            content
+        );
+    }
+
     #[test]
     fn test_list_sessions_empty() {
         let temp_dir = TempDir::new().unwrap();
@@ -200,4 +716,160 @@ mod tests {
         let sessions = manager.list_sessions("2026-01-16").unwrap();
         assert!(sessions.is_empty());
     }
+
+    #[test]
+    fn test_find_and_remove_sessions_by_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        for (name, id) in [("first", "a"), ("resumed", "b"), ("other", "c")] {
+            let content = format!("---\ntitle: \"{name}\"\nsession_id: {id}\n---\n");
+            manager.write_session("2024-01-15", name, &content).unwrap();
+        }
+
+        let ids = vec!["a".to_string(), "b".to_string()];
+        let found = manager.find_sessions_by_id("2024-01-15", &ids).unwrap();
+        assert_eq!(found, vec!["first", "resumed"]);
+
+        manager.remove_session("2024-01-15", "first").unwrap();
+        assert_eq!(
+            manager.list_sessions("2024-01-15").unwrap(),
+            vec!["other", "resumed"]
+        );
+        assert!(manager.remove_session("2024-01-15", "first").is_err());
+    }
+
+    #[test]
+    fn test_session_changes_stay_in_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = temp_dir.path().join("storage");
+        let mut config = Config::default();
+        config.storage.path = storage;
+        let manager = ArchiveManager::new(config);
+        let outside = temp_dir.path().join("outside.md");
+        fs::write(&outside, "---\ntitle: \"outside\"\n---\n").unwrap();
+
+        for name in ["../../outside", "..\\outside", "a/b", ""] {
+            assert!(manager.remove_session("2024-01-15", name).is_err());
+            assert!(manager.rename_session("2024-01-15", name, "moved").is_err());
+            assert!(manager.set_pinned("2024-01-15", name, true).is_err());
+            assert!(manager
+                .set_annotation("2024-01-15", name, Annotation::default(), true)
+                .is_err());
+        }
+        assert!(manager.remove_session("../outside", "x").is_err());
+        assert_eq!(
+            fs::read_to_string(&outside).unwrap(),
+            "---\ntitle: \"outside\"\n---\n"
+        );
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_undigested_dates() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        for date in ["2024-01-17", "2024-01-15", "2024-01-16"] {
+            manager.write_session(date, "work", "# Work\n").unwrap();
+        }
+        manager
+            .write_daily_summary("2024-01-16", "## Overview\nDone.\n")
+            .unwrap();
+        // A placeholder daily.md without sessions needs no digest
+        manager.ensure_date_dir("2024-01-18").unwrap();
+
+        assert_eq!(
+            manager.undigested_dates().unwrap(),
+            vec!["2024-01-15", "2024-01-17"]
+        );
+    }
+
+    #[test]
+    fn test_old_schema_upgrades_on_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        let path = manager
+            .write_daily_summary(
+                "2026-03-01",
+                "---\ndate: 2026-03-01\ntotal_sessions: 2\n---\n",
+            )
+            .unwrap();
+        manager
+            .write_session("2026-03-01", "fix", "---\ndate: 2026-03-01\n---\n")
+            .unwrap();
+
+        let migrated = manager.migrate_schema(true).unwrap();
+        assert_eq!(migrated.len(), 2);
+        assert_eq!(migrated[0].steps.len(), 2);
+
+        let content = manager.read_daily_summary("2026-03-01").unwrap();
+        assert_eq!(schema::version(&content), schema::SCHEMA_VERSION);
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        assert_eq!(manager.migrate_schema(false).unwrap().len(), 1);
+        assert!(manager.migrate_schema(false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rename_session_updates_digest() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ArchiveManager::new(test_config(&temp_dir));
+        let session = Templates::session_archive(
+            "10_00-fix",
+            "2024-01-15",
+            "abc",
+            "/work/app",
+            None,
+            None,
+            "",
+            "",
+            "",
+            "",
+            "",
+        );
+        manager
+            .write_session("2024-01-15", "10_00-fix", &session)
+            .unwrap();
+        manager
+            .write_session("2024-01-15", "10_00-fix-tests", "# Tests\n")
+            .unwrap();
+        manager
+            .write_daily_summary(
+                "2024-01-15",
+                "---\nsessions: [10_00-fix, 10_00-fix-tests]\nschema_version: 1\n---\n\n\
+                 See [[10_00-fix]] and [[10_00-fix-tests]].\n",
+            )
+            .unwrap();
+
+        let new_name = manager
+            .rename_session("2024-01-15", "10_00-fix", "Login Flow")
+            .unwrap();
+        assert_eq!(new_name, "10_00-login-flow");
+        assert!(!manager
+            .session_archive_path("2024-01-15", "10_00-fix")
+            .exists());
+
+        let renamed = manager.read_session("2024-01-15", &new_name).unwrap();
+        assert_eq!(frontmatter::get(&renamed, "title"), Some("Login Flow"));
+        assert!(renamed.contains("\n# Login Flow\n"));
+
+        let digest = manager.read_daily_summary("2024-01-15").unwrap();
+        assert_eq!(
+            frontmatter::get(&digest, "sessions"),
+            Some("[10_00-login-flow, 10_00-fix-tests]")
+        );
+        assert!(digest.contains("See [[10_00-login-flow]] and [[10_00-fix-tests]]."));
+
+        // The new name is taken now
+        assert!(manager
+            .rename_session("2024-01-15", "10_00-fix-tests", "login-flow")
+            .is_err());
+
+        // Renaming again replaces the heading written by the first rename
+        let again = manager
+            .rename_session("2024-01-15", &new_name, "Sign-in \"flow\"")
+            .unwrap();
+        let renamed = manager.read_session("2024-01-15", &again).unwrap();
+        assert_eq!(frontmatter::get(&renamed, "title"), Some("Sign-in 'flow'"));
+        assert!(renamed.contains("\n# Sign-in 'flow'\n"));
+        assert!(!renamed.contains("# Login Flow"));
+    }
 }
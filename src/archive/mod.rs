@@ -1,8 +1,28 @@
-mod daily;
+pub mod annotation;
+pub mod atomic;
+pub mod cache;
+pub mod citations;
+pub mod daily;
+pub mod edit;
+pub mod frontmatter;
+pub mod index;
+pub mod layout;
+pub mod locks;
 mod manager;
+pub mod notes;
+pub mod paths;
+pub mod permalink;
+pub mod retention;
+pub mod schema;
 pub mod session;
+pub mod sources;
+pub mod stats;
 mod templates;
+pub mod transcript;
 
-pub use daily::{DailySummary, SummaryCard};
+pub use daily::{
+    card_slug, section, section_cards, skill_command_cards, DailySummary, ProjectSection,
+    SummaryCard,
+};
 pub use manager::ArchiveManager;
 pub use session::SessionArchive;
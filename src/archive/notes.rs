@@ -0,0 +1,126 @@
+//! Journal notes added by hand with `daily note`.
+//!
+//! Notes live in a `## Notes` section of the day's daily.md as
+//! `- **HH:MM** text` lines. The digest prompt is given them, and a
+//! regenerated digest carries the section over unchanged.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use super::edit;
+use super::locks;
+use super::manager::ArchiveManager;
+
+/// Heading of the notes section in daily.md
+pub const NOTES_HEADING: &str = "Notes";
+
+/// A timestamped note on a day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Note {
+    /// Local time the note was added, `HH:MM`
+    pub time: String,
+    pub text: String,
+}
+
+/// Append a note to `date`'s daily.md, creating the file if needed
+pub fn add(manager: &ArchiveManager, date: &str, text: &str, at: DateTime<Local>) -> Result<Note> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}': expected YYYY-MM-DD", date))?;
+    locks::ensure_unlocked(manager.config(), date, "note")?;
+    // One line per note keeps the section parseable
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        anyhow::bail!("Note is empty");
+    }
+    let note = Note {
+        time: at.format("%H:%M").to_string(),
+        text,
+    };
+
+    manager.ensure_date_dir(date)?;
+    let content = manager.read_daily_summary(date)?;
+    let line = format!("- **{}** {}", note.time, note.text);
+    let updated = match edit::section_body(&content, NOTES_HEADING) {
+        Some(body) if !body.is_empty() => {
+            edit::replace_section(&content, NOTES_HEADING, &format!("{}\n{}", body, line))?
+        }
+        Some(_) => edit::replace_section(&content, NOTES_HEADING, &line)?,
+        None => insert_section(&content, &line),
+    };
+    manager.write_daily_summary(date, &updated)?;
+    Ok(note)
+}
+
+/// Notes recorded in a daily.md, oldest first
+pub fn list(content: &str) -> Vec<Note> {
+    let Some(body) = edit::section_body(content, NOTES_HEADING) else {
+        return Vec::new();
+    };
+    body.lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("- **")?;
+            let (time, text) = rest.split_once("** ")?;
+            Some(Note {
+                time: time.to_string(),
+                text: text.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Copy the notes section of `previous` into a regenerated digest
+pub fn carry_over(generated: &str, previous: &str) -> Result<String> {
+    let Some(body) = edit::section_body(previous, NOTES_HEADING).filter(|b| !b.is_empty()) else {
+        return Ok(generated.to_string());
+    };
+    if edit::section_body(generated, NOTES_HEADING).is_some() {
+        return edit::replace_section(generated, NOTES_HEADING, body);
+    }
+    Ok(insert_section(generated, body))
+}
+
+/// Add a notes section ahead of the digest footer, or at the end
+fn insert_section(content: &str, body: &str) -> String {
+    let section = format!("## {}\n\n{}\n\n", NOTES_HEADING, body.trim());
+    match content.find("\n---\n*Generated") {
+        Some(i) => format!("{}{}{}", &content[..=i], section, &content[i + 1..]),
+        None => format!("{}\n\n{}", content.trim_end(), section.trim_end()) + "\n",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::DailySummary;
+    use crate::config::Config;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_add_notes_and_carry_over() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        let at = Local.with_ymd_and_hms(2024, 1, 15, 14, 5, 0).unwrap();
+        add(&manager, "2024-01-15", "Switched auth\nto JWT", at).unwrap();
+        add(&manager, "2024-01-15", "Pairing with Sam", at).unwrap();
+        assert!(add(&manager, "2024-01-15", "  ", at).is_err());
+        assert!(add(&manager, "yesterday", "x", at).is_err());
+
+        let placeholder = manager.read_daily_summary("2024-01-15").unwrap();
+        let notes = list(&placeholder);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].time, "14:05");
+        assert_eq!(notes[0].text, "Switched auth to JWT");
+        assert!(!manager.has_digest("2024-01-15"));
+
+        let generated = DailySummary::new("2024-01-15".into()).to_markdown();
+        let merged = carry_over(&generated, &placeholder).unwrap();
+        assert_eq!(list(&merged), notes);
+        assert!(merged.find("## Notes").unwrap() < merged.find("*Generated").unwrap());
+        assert_eq!(carry_over(&merged, &placeholder).unwrap(), merged);
+    }
+}
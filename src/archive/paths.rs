@@ -0,0 +1,86 @@
+//! `archive.ignore_paths` / `archive.include_paths` rules.
+//!
+//! A session is kept or dropped by the project directory it ran in. A
+//! pattern matching that directory or any of its parents applies, so
+//! "/tmp" covers everything below it just like "/tmp/**".
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use std::path::Path;
+
+use crate::config::Config;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Compiled ignore and include patterns; the default allows every path
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    ignore: Vec<Pattern>,
+    include: Vec<Pattern>,
+}
+
+impl PathFilter {
+    /// Patterns from the config. Invalid ones are left out here and
+    /// reported by `daily config validate`.
+    pub fn from_config(config: &Config) -> Self {
+        let compile = |patterns: &[String]| -> Vec<Pattern> {
+            patterns.iter().filter_map(|p| compile(p).ok()).collect()
+        };
+        Self {
+            ignore: compile(&config.archive.ignore_paths),
+            include: compile(&config.archive.include_paths),
+        }
+    }
+
+    /// Whether sessions in `dir` may be archived and counted. With
+    /// `include_paths` set, sessions of unknown directory are not.
+    pub fn allows(&self, dir: Option<&Path>) -> bool {
+        let Some(dir) = dir else {
+            return self.include.is_empty();
+        };
+        let matches = |patterns: &[Pattern]| {
+            dir.ancestors().any(|path| {
+                patterns
+                    .iter()
+                    .any(|p| p.matches_path_with(path, MATCH_OPTIONS))
+            })
+        };
+        !matches(&self.ignore) && (self.include.is_empty() || matches(&self.include))
+    }
+}
+
+/// Compile a pattern, expanding a leading `~`
+pub fn compile(pattern: &str) -> Result<Pattern> {
+    let expanded = shellexpand::tilde(pattern);
+    Pattern::new(expanded.trim_end_matches('/'))
+        .with_context(|| format!("Invalid glob pattern '{}'", pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_and_include_paths() {
+        let mut config = Config::default();
+        config.archive.ignore_paths = vec!["/tmp".into(), "/work/clients/secret-*".into()];
+        let filter = PathFilter::from_config(&config);
+        assert!(!filter.allows(Some(Path::new("/tmp/experiment"))));
+        assert!(!filter.allows(Some(Path::new("/work/clients/secret-bank/api"))));
+        assert!(filter.allows(Some(Path::new("/work/clients/acme"))));
+        assert!(filter.allows(Some(Path::new("/tmpfiles"))));
+        assert!(filter.allows(None));
+
+        config.archive.include_paths = vec!["/work/**".into()];
+        let filter = PathFilter::from_config(&config);
+        assert!(filter.allows(Some(Path::new("/work/clients/acme"))));
+        assert!(!filter.allows(Some(Path::new("/home/me/notes"))));
+        assert!(!filter.allows(Some(Path::new("/work/clients/secret-bank"))));
+        assert!(!filter.allows(None));
+        assert!(compile("[").is_err());
+    }
+}
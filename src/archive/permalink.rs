@@ -0,0 +1,229 @@
+//! Stable links to sessions and digests.
+//!
+//! Session file names change with `daily rename` and layout migrations, so
+//! each session archive carries a UUID-shaped `id` in its frontmatter that
+//! stays with it. [`resolve`] turns a reference, as used by
+//! `/api/resolve?ref=...` and in notification links, into the current
+//! location of the session or digest.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use super::frontmatter;
+use super::manager::ArchiveManager;
+
+/// Frontmatter key holding a session's stable ID
+pub const ID_KEY: &str = "id";
+
+/// A fresh random ID for a new session archive
+pub fn new_id() -> String {
+    let state = RandomState::new();
+    let mut hasher = state.build_hasher();
+    std::process::id().hash(&mut hasher);
+    std::time::SystemTime::now().hash(&mut hasher);
+    let high = hasher.finish();
+    let low = state.hash_one(high);
+    format_uuid(high, low)
+}
+
+/// An ID derived from `seed`, so archives upgraded in memory only
+/// (compacted ones) get the same ID on every read
+pub fn derived_id(seed: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    let high = hasher.finish();
+    high.hash(&mut hasher);
+    format_uuid(high, hasher.finish())
+}
+
+/// Lay 128 bits out as a version 4 UUID
+fn format_uuid(high: u64, low: u64) -> String {
+    let high = (high & !0xf000) | 0x4000;
+    let low = (low & !(0xc << 60)) | (0x8 << 60);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// Whether `value` looks like an ID from [`new_id`] (or a Claude Code
+/// session ID, which has the same shape)
+pub fn is_id(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Where a reference points
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Target {
+    Session {
+        date: String,
+        name: String,
+        id: Option<String>,
+    },
+    Digest {
+        date: String,
+    },
+}
+
+impl Target {
+    /// Path of the web UI view for the target
+    pub fn web_path(&self) -> String {
+        match self {
+            Target::Session { date, name, .. } => {
+                format!("/day/{}/session/{}", date, encode_segment(name))
+            }
+            Target::Digest { date } => format!("/day/{}", date),
+        }
+    }
+}
+
+/// Percent-encode the characters that can't appear in a path segment
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Find what a reference points to. Accepted forms:
+/// - a session `id`, or the Claude Code `session_id` of an archive
+/// - `YYYY-MM-DD/name` for a session under its current name
+/// - `YYYY-MM-DD` for that day's digest
+pub fn resolve(manager: &ArchiveManager, reference: &str) -> Result<Option<Target>> {
+    let reference = reference.trim().trim_matches('/');
+    if is_id(reference) {
+        return find_by_id(manager, reference);
+    }
+
+    let (date, name) = match reference.split_once('/') {
+        Some((date, name)) => (date, Some(name)),
+        None => (reference, None),
+    };
+    if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+        anyhow::bail!(
+            "Invalid reference '{}', expected an ID, YYYY-MM-DD or YYYY-MM-DD/name",
+            reference
+        );
+    }
+    match name {
+        Some(name) => {
+            let Ok(content) = manager.read_session(date, name) else {
+                return Ok(None);
+            };
+            Ok(Some(Target::Session {
+                date: date.to_string(),
+                name: name.to_string(),
+                id: frontmatter::get(&content, ID_KEY).map(str::to_string),
+            }))
+        }
+        None if manager.has_digest(date) || manager.has_sessions(date) => {
+            Ok(Some(Target::Digest {
+                date: date.to_string(),
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Newest session whose `id` or `session_id` is `id`; a stable `id`
+/// match wins over a Claude Code session ID shared with checkpoints
+fn find_by_id(manager: &ArchiveManager, id: &str) -> Result<Option<Target>> {
+    let mut by_session_id = None;
+    for date in manager.list_dates()?.into_iter().rev() {
+        for name in manager.list_sessions(&date)? {
+            let Ok(content) = manager.read_session(&date, &name) else {
+                continue;
+            };
+            let stable = frontmatter::get(&content, ID_KEY);
+            let target = || Target::Session {
+                date: date.clone(),
+                name: name.clone(),
+                id: stable.map(str::to_string),
+            };
+            if stable == Some(id) {
+                return Ok(Some(target()));
+            }
+            if by_session_id.is_none() && frontmatter::get(&content, "session_id") == Some(id) {
+                by_session_id = Some(target());
+            }
+        }
+    }
+    Ok(by_session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ids() {
+        let id = new_id();
+        assert!(is_id(&id));
+        assert_ne!(id, new_id());
+        assert_eq!(&id[14..15], "4");
+        assert_eq!(derived_id("a"), derived_id("a"));
+        assert_ne!(derived_id("a"), derived_id("b"));
+        assert!(!is_id("2024-01-15"));
+    }
+
+    #[test]
+    fn test_resolve_survives_rename() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config);
+        let id = new_id();
+        let content = format!(
+            "---\ntitle: \"fix-auth\"\nsession_id: abc\nschema_version: 2\nid: {}\n---\n\n# fix-auth\n",
+            id
+        );
+        manager
+            .write_session("2024-01-15", "fix-auth", &content)
+            .unwrap();
+
+        let target = resolve(&manager, "2024-01-15/fix-auth").unwrap().unwrap();
+        assert_eq!(
+            target.web_path(),
+            "/day/2024-01-15/session/fix-auth".to_string()
+        );
+        assert_eq!(
+            resolve(&manager, "2024-01-15").unwrap(),
+            Some(Target::Digest {
+                date: "2024-01-15".into()
+            })
+        );
+
+        let renamed = manager
+            .rename_session("2024-01-15", "fix-auth", "login flow")
+            .unwrap();
+        assert_eq!(resolve(&manager, "2024-01-15/fix-auth").unwrap(), None);
+        assert_eq!(
+            resolve(&manager, &id).unwrap(),
+            Some(Target::Session {
+                date: "2024-01-15".into(),
+                name: renamed,
+                id: Some(id),
+            })
+        );
+        assert!(resolve(&manager, "yesterday").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Age-based archive cleanup for `daily prune`.
+//!
+//! Old transcript copies and (optionally) session archives are compressed
+//! with zstd or deleted. Digests and pinned sessions are never touched, and
+//! session archives are only deleted once their date has a digest.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manager::ArchiveManager;
+use crate::config::Config;
+use crate::storage;
+
+const ZSTD_LEVEL: i32 = 19;
+
+/// What `daily prune` does with a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneAction {
+    Compress,
+    Delete,
+}
+
+/// A file selected by the retention rules
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneItem {
+    pub path: PathBuf,
+    pub date: String,
+    /// "session" or "transcript"
+    pub kind: &'static str,
+    pub action: PruneAction,
+    pub bytes: u64,
+}
+
+/// `path` with `.zst` appended
+pub fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".zst");
+    PathBuf::from(name)
+}
+
+/// Read a zstd-compressed text file
+pub fn read_compressed(path: &Path) -> Result<String> {
+    let file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let bytes = zstd::decode_all(file)
+        .with_context(|| format!("Failed to decompress {}", path.display()))?;
+    String::from_utf8(bytes).context("Compressed archive is not valid UTF-8")
+}
+
+/// Files eligible for pruning as of `today`, oldest first
+pub fn plan(config: &Config, today: NaiveDate) -> Result<Vec<PruneItem>> {
+    let retention = &config.storage.retention;
+    let action = match retention.action.as_str() {
+        "compress" => PruneAction::Compress,
+        "delete" => PruneAction::Delete,
+        other => anyhow::bail!(
+            "Invalid storage.retention.action '{}'. Use 'compress' or 'delete'",
+            other
+        ),
+    };
+
+    let manager = ArchiveManager::new(config.clone());
+    let mut items = Vec::new();
+
+    let mut dates = manager.list_dates()?;
+    dates.reverse();
+    for date in dates {
+        let Some(age) = age_in_days(&date, today) else {
+            continue;
+        };
+
+        if retention.transcript_days.is_some_and(|days| age > days) {
+            let dir = config.date_dir(&date).join("transcripts");
+            for path in files_in(&dir) {
+                if let Some(item) = item_for(&path, &date, "transcript", action) {
+                    items.push(item);
+                }
+            }
+        }
+
+        if retention.session_days.is_some_and(|days| age > days) {
+            // Deleting sessions of an undigested date would lose their content
+            if action == PruneAction::Delete && !manager.has_digest(&date) {
+                continue;
+            }
+            for name in manager.list_sessions(&date)? {
+                if manager.is_pinned(&date, &name) {
+                    continue;
+                }
+                let path = manager.session_archive_path(&date, &name);
+                let path = if path.exists() {
+                    path
+                } else {
+                    compressed_path(&path)
+                };
+                if let Some(item) = item_for(&path, &date, "session", action) {
+                    items.push(item);
+                }
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Apply one pruning step, returning the bytes reclaimed
+pub fn apply(config: &Config, item: &PruneItem) -> Result<u64> {
+    match item.action {
+        PruneAction::Delete => {
+            storage::remove_file(config, &item.path)?;
+            Ok(item.bytes)
+        }
+        PruneAction::Compress => {
+            let input = fs::read(&item.path)
+                .with_context(|| format!("Failed to read {}", item.path.display()))?;
+            let output = zstd::encode_all(input.as_slice(), ZSTD_LEVEL)
+                .with_context(|| format!("Failed to compress {}", item.path.display()))?;
+            storage::write_file(config, &compressed_path(&item.path), &output)?;
+            storage::remove_file(config, &item.path)?;
+            Ok(item.bytes.saturating_sub(output.len() as u64))
+        }
+    }
+}
+
+/// Human-readable byte count
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn age_in_days(date: &str, today: NaiveDate) -> Option<u64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    u64::try_from((today - date).num_days()).ok()
+}
+
+/// Files under `dir`, including sub-agent transcripts in subfolders
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+    {
+        if path.is_dir() {
+            files.extend(files_in(&path));
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+fn item_for(path: &Path, date: &str, kind: &'static str, action: PruneAction) -> Option<PruneItem> {
+    let is_compressed = path.extension().is_some_and(|ext| ext == "zst");
+    // Already compacted files only go when deleting
+    if action == PruneAction::Compress && is_compressed {
+        return None;
+    }
+    let bytes = fs::metadata(path).ok()?.len();
+    Some(PruneItem {
+        path: path.to_path_buf(),
+        date: date.to_string(),
+        kind,
+        action,
+        bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SESSION: &str =
+        "---\ntitle: \"Fix bug\"\nschema_version: 2\n---\n\n# Fix bug\n\n## Summary\n\nFixed it.\n";
+
+    fn setup(dir: &TempDir) -> (Config, ArchiveManager) {
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        config.storage.retention.transcript_days = Some(30);
+        config.storage.retention.session_days = Some(60);
+        let manager = ArchiveManager::new(config.clone());
+
+        for date in ["2024-01-01", "2024-03-25"] {
+            manager.write_session(date, "fix-bug", SESSION).unwrap();
+            let transcripts = config.date_dir(date).join("transcripts");
+            fs::create_dir_all(&transcripts).unwrap();
+            fs::write(transcripts.join("abc.jsonl"), "{}\n".repeat(100)).unwrap();
+        }
+        manager
+            .write_session("2024-01-01", "pinned", SESSION)
+            .unwrap();
+        manager.set_pinned("2024-01-01", "pinned", true).unwrap();
+        (config, manager)
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+    }
+
+    #[test]
+    fn test_compress_keeps_sessions_readable() {
+        let dir = TempDir::new().unwrap();
+        let (config, manager) = setup(&dir);
+
+        let items = plan(&config, today()).unwrap();
+        // Old transcript + old unpinned session; recent date and pinned session untouched
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.date == "2024-01-01"));
+
+        for item in &items {
+            apply(&config, item).unwrap();
+        }
+        assert_eq!(
+            manager.list_sessions("2024-01-01").unwrap(),
+            vec!["fix-bug", "pinned"]
+        );
+        assert_eq!(
+            manager.read_session("2024-01-01", "fix-bug").unwrap(),
+            SESSION
+        );
+
+        // Nothing left to compress
+        assert!(plan(&config, today()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_skips_undigested_sessions() {
+        let dir = TempDir::new().unwrap();
+        let (mut config, _) = setup(&dir);
+        config.storage.retention.action = "delete".into();
+
+        let items = plan(&config, today()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, "transcript");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,152 @@
+//! Versioned format of session archives and daily summaries.
+//!
+//! Both carry `schema_version` in their frontmatter; files written before it
+//! existed count as version 0. [`upgrade`] runs the migration steps a file
+//! is missing, so readers only ever see the current format. `ArchiveManager`
+//! applies it lazily on read, and `daily migrate` upgrades everything at once.
+
+use super::frontmatter;
+use super::permalink;
+
+/// Frontmatter key holding the format version
+pub const VERSION_KEY: &str = "schema_version";
+
+/// Current format version of session archives and daily summaries
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Which kind of archive file a step applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Session,
+    Daily,
+}
+
+/// Rewrites the content of one file to the next version
+type MigrationStep = fn(&str, Kind) -> String;
+
+/// Migration steps: target version and what it changes
+const MIGRATIONS: [(u32, &str, MigrationStep); 2] = [
+    (
+        1,
+        "Rename the daily `total_sessions` field to `session_count`",
+        rename_total_sessions,
+    ),
+    (2, "Give each session a stable `id`", add_session_id),
+];
+
+/// Format version of a file, 0 when it has none
+pub fn version(content: &str) -> u32 {
+    frontmatter::get(content, VERSION_KEY)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Outcome of [`upgrade`]: the new content and the steps applied
+#[derive(Debug, Clone)]
+pub struct Upgrade {
+    pub content: String,
+    pub from: u32,
+    pub steps: Vec<&'static str>,
+}
+
+/// Bring a file up to [`SCHEMA_VERSION`]. `None` when it is already current,
+/// newer than this build understands, or has no frontmatter to stamp.
+pub fn upgrade(content: &str, kind: Kind) -> Option<Upgrade> {
+    let from = version(content);
+    if from >= SCHEMA_VERSION || !content.starts_with("---\n") {
+        return None;
+    }
+
+    let mut content = content.to_string();
+    let mut steps = Vec::new();
+    for (version, description, step) in MIGRATIONS {
+        if version > from {
+            content = step(&content, kind);
+            steps.push(description);
+        }
+    }
+    let content = frontmatter::set(&content, VERSION_KEY, Some(&SCHEMA_VERSION.to_string()));
+    if version(&content) != SCHEMA_VERSION {
+        // Unterminated frontmatter can't be stamped
+        return None;
+    }
+    Some(Upgrade {
+        content,
+        from,
+        steps,
+    })
+}
+
+/// v1: the SessionStart hook used to write `total_sessions` where digests
+/// write `session_count`
+fn rename_total_sessions(content: &str, kind: Kind) -> String {
+    if kind != Kind::Daily {
+        return content.to_string();
+    }
+    let Some(total) = frontmatter::get(content, "total_sessions").map(str::to_string) else {
+        return content.to_string();
+    };
+    let content = frontmatter::set(content, "total_sessions", None);
+    if frontmatter::get(&content, "session_count").is_some() {
+        return content;
+    }
+    frontmatter::set(&content, "session_count", Some(&total))
+}
+
+/// v2: sessions carry an `id` that survives renames. It is derived from
+/// fields that never change, so a compacted archive (upgraded in memory on
+/// every read) keeps the same one.
+fn add_session_id(content: &str, kind: Kind) -> String {
+    if kind != Kind::Session || frontmatter::get(content, permalink::ID_KEY).is_some() {
+        return content.to_string();
+    }
+    let seed = ["session_id", "date", "title", "created"]
+        .map(|key| frontmatter::get(content, key).unwrap_or_default())
+        .join("\n");
+    frontmatter::set(
+        content,
+        permalink::ID_KEY,
+        Some(&permalink::derived_id(&seed)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_daily() {
+        let old =
+            "---\ndate: 2024-01-15\nsessions: []\ntotal_sessions: 0\n---\n\n# Daily Summary\n";
+        let upgrade = upgrade(old, Kind::Daily).unwrap();
+        assert_eq!(upgrade.from, 0);
+        assert_eq!(upgrade.steps.len(), 2);
+        assert_eq!(version(&upgrade.content), SCHEMA_VERSION);
+        assert_eq!(
+            frontmatter::get(&upgrade.content, "session_count"),
+            Some("0")
+        );
+        assert!(frontmatter::get(&upgrade.content, "total_sessions").is_none());
+        assert!(upgrade.content.ends_with("---\n\n# Daily Summary\n"));
+
+        // Current, future and frontmatter-less files are left alone
+        assert!(super::upgrade(&upgrade.content, Kind::Daily).is_none());
+        let future = frontmatter::set(old, VERSION_KEY, Some("99"));
+        assert!(super::upgrade(&future, Kind::Daily).is_none());
+        assert!(super::upgrade("# Notes\n", Kind::Session).is_none());
+        assert!(frontmatter::get(&upgrade.content, permalink::ID_KEY).is_none());
+    }
+
+    #[test]
+    fn test_upgrade_session_adds_stable_id() {
+        let old = "---\ntitle: \"Fix bug\"\nsession_id: abc\nschema_version: 1\n---\n\n# Fix bug\n";
+        let first = upgrade(old, Kind::Session).unwrap();
+        let id = frontmatter::get(&first.content, permalink::ID_KEY).unwrap();
+        assert!(permalink::is_id(id));
+        let again = upgrade(old, Kind::Session).unwrap();
+        assert_eq!(
+            frontmatter::get(&again.content, permalink::ID_KEY),
+            Some(id)
+        );
+    }
+}
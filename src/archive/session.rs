@@ -1,14 +1,21 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use super::frontmatter;
 use super::manager::ArchiveManager;
+use super::permalink;
+use super::sources::{self, SourceMap};
 use super::templates::Templates;
 use crate::config::Config;
+use crate::transcript::code_changes::CodeChanges;
 use crate::transcript::TranscriptData;
 
 /// Represents a summarized session ready for archiving
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionArchive {
+    /// Stable ID that survives renames, see [`permalink`]
+    #[serde(default = "permalink::new_id")]
+    pub id: String,
     pub title: String,
     pub date: String,
     pub session_id: String,
@@ -20,12 +27,29 @@ pub struct SessionArchive {
     pub code_changes: String,
     pub learnings: String,
     pub skill_hints: String,
+    /// Earlier sessions this one resumes (`claude --resume`), oldest first
+    #[serde(default)]
+    pub resumed_from: Vec<String>,
+    /// Tool an imported session came from (`aider`, `codex`, `cursor`);
+    /// None for Claude Code sessions
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Languages of the files the session changed, most changed first
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Transcript lines each summary item was based on
+    #[serde(default)]
+    pub sources: SourceMap,
+    /// Archived with a stub summary by the triviality pre-pass
+    #[serde(default)]
+    pub trivial: bool,
 }
 
 impl SessionArchive {
     /// Create a new session archive from raw data
     pub fn new(title: String, date: String, session_id: String, cwd: String) -> Self {
         Self {
+            id: permalink::new_id(),
             title,
             date,
             session_id,
@@ -37,6 +61,11 @@ impl SessionArchive {
             code_changes: String::new(),
             learnings: String::new(),
             skill_hints: String::new(),
+            resumed_from: Vec::new(),
+            source: None,
+            languages: Vec::new(),
+            sources: SourceMap::default(),
+            trivial: false,
         }
     }
 
@@ -48,17 +77,13 @@ impl SessionArchive {
 
     /// Fill in data from transcript
     pub fn with_transcript_data(mut self, data: &TranscriptData) -> Self {
-        // Build code changes from files modified
-        if !data.files_modified.is_empty() {
-            self.code_changes = data
-                .files_modified
-                .iter()
-                .map(|f| format!("- `{}`", f))
-                .collect::<Vec<_>>()
-                .join("\n");
-        } else {
-            self.code_changes = "_No files modified._".to_string();
-        }
+        let changes = CodeChanges::collect(data);
+        self.code_changes = changes.to_markdown();
+        self.languages = changes
+            .languages()
+            .into_iter()
+            .map(|l| l.language.to_string())
+            .collect();
 
         self
     }
@@ -78,9 +103,15 @@ impl SessionArchive {
         self
     }
 
+    /// Set where the summary items came from in the transcript
+    pub fn with_sources(mut self, sources: SourceMap) -> Self {
+        self.sources = sources;
+        self
+    }
+
     /// Generate Markdown content for this archive
     pub fn to_markdown(&self) -> String {
-        Templates::session_archive(
+        let content = Templates::session_archive(
             &self.title,
             &self.date,
             &self.session_id,
@@ -92,17 +123,74 @@ impl SessionArchive {
             &self.code_changes,
             &self.learnings,
             &self.skill_hints,
-        )
+        );
+        let mut content = frontmatter::set(&content, permalink::ID_KEY, Some(&self.id));
+        if !self.resumed_from.is_empty() {
+            let ids = format!("[{}]", self.resumed_from.join(", "));
+            content = frontmatter::set(&content, "resumed_from", Some(&ids));
+        }
+        if !self.languages.is_empty() {
+            let languages = format!("[{}]", self.languages.join(", "));
+            content = frontmatter::set(&content, "languages", Some(&languages));
+        }
+        if !self.sources.is_empty() {
+            content = frontmatter::set(
+                &content,
+                sources::FRONTMATTER_KEY,
+                Some(&self.sources.to_frontmatter()),
+            );
+        }
+        if self.trivial {
+            content = frontmatter::set(&content, "trivial", Some("true"));
+        }
+        if let Some(source) = &self.source {
+            content = frontmatter::set(&content, "source", Some(source));
+            let tags = format!("[{}, session-archive]", source);
+            content = frontmatter::set(&content, "tags", Some(&tags));
+        }
+        content
     }
 
     /// Save this archive to disk
     pub fn save(&self, config: &Config) -> Result<std::path::PathBuf> {
         let manager = ArchiveManager::new(config.clone());
-        let content = self.to_markdown();
+        let mut content = self.to_markdown();
+        // Re-summarizing into an existing archive keeps the links to it working
+        if let Ok(existing) = manager.read_session(&self.date, &self.title) {
+            if let Some(id) = frontmatter::get(&existing, permalink::ID_KEY) {
+                content = frontmatter::set(&content, permalink::ID_KEY, Some(id));
+            }
+        }
         manager.write_session(&self.date, &self.title, &content)
     }
 }
 
+/// File name prefix of checkpoint archives written by the Stop hook
+pub const CHECKPOINT_PREFIX: &str = "checkpoint-";
+
+/// Archive name for a session's in-progress checkpoint summary
+pub fn checkpoint_title(session_id: &str) -> String {
+    let short_id: String = session_id.chars().take(8).collect();
+    format!("{}{}", CHECKPOINT_PREFIX, short_id)
+}
+
+/// Check if skill_hints suggest extractable knowledge
+pub fn has_skill_hints(skill_hints: &str) -> bool {
+    let hints_lower = skill_hints.to_lowercase();
+
+    // Skip if explicitly marked as none
+    if hints_lower.contains("none identified")
+        || hints_lower.contains("no skills")
+        || hints_lower.contains("no potential")
+        || skill_hints.trim().is_empty()
+    {
+        return false;
+    }
+
+    // Check for skill markers (name, trigger, etc.)
+    hints_lower.contains("**") || hints_lower.contains("trigger:") || hints_lower.contains("- ")
+}
+
 /// Get git branch from working directory
 pub fn get_git_branch(cwd: &str) -> Option<String> {
     std::process::Command::new("git")
@@ -147,5 +235,9 @@ mod tests {
         let md = archive.to_markdown();
         assert!(md.contains("title: \"test-session\""));
         assert!(md.contains("# test-session"));
+        assert_eq!(
+            frontmatter::get(&md, permalink::ID_KEY),
+            Some(archive.id.as_str())
+        );
     }
 }
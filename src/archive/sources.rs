@@ -0,0 +1,150 @@
+//! Where in the transcript each part of a session summary came from.
+//!
+//! The summarizer asks the model which of the numbered user requests each
+//! item of the Summary, Key Decisions and Learnings sections is based on.
+//! Each request becomes a transcript line range, from the request to the
+//! line before the next one, and the ranges are kept in the archive's
+//! `sources` frontmatter as one list per item, in order. The summary
+//! paragraph counts as a single item.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{frontmatter, section};
+use crate::topics::bullets;
+
+/// Frontmatter key holding the source map as JSON
+pub const FRONTMATTER_KEY: &str = "sources";
+
+/// Sections with sources: key in the summary response and archive heading
+pub const SECTIONS: [(&str, &str); 3] = [
+    ("summary", "Summary"),
+    ("decisions", "Key Decisions & Trade-offs"),
+    ("learnings", "Learnings"),
+];
+
+/// First and last transcript line (1-based, inclusive)
+pub type LineRange = [usize; 2];
+
+/// Line ranges supporting each item, by section key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SourceMap(pub BTreeMap<String, Vec<Vec<LineRange>>>);
+
+impl SourceMap {
+    /// Line ranges for the request numbers (1-based) cited for each item.
+    /// `request_lines` is the line of every user request; numbers the model
+    /// made up and sections not in [`SECTIONS`] are dropped.
+    pub fn from_requests(
+        cited: &BTreeMap<String, Vec<Vec<usize>>>,
+        request_lines: &[usize],
+        line_count: usize,
+    ) -> Self {
+        let mut map = BTreeMap::new();
+        if request_lines.is_empty() {
+            return Self(map);
+        }
+        for (key, _) in SECTIONS {
+            let Some(items) = cited.get(key) else {
+                continue;
+            };
+            let ranges: Vec<Vec<LineRange>> = items
+                .iter()
+                .map(|requests| {
+                    let mut ranges: Vec<LineRange> = requests
+                        .iter()
+                        .filter_map(|&n| {
+                            let start = *request_lines.get(n.checked_sub(1)?)?;
+                            let end = request_lines
+                                .get(n)
+                                .map(|next| next - 1)
+                                .unwrap_or(line_count)
+                                .max(start);
+                            Some([start, end])
+                        })
+                        .collect();
+                    ranges.sort();
+                    ranges.dedup();
+                    ranges
+                })
+                .collect();
+            if ranges.iter().any(|r| !r.is_empty()) {
+                map.insert(key.to_string(), ranges);
+            }
+        }
+        Self(map)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Source map stored in a session archive, if any
+    pub fn read(content: &str) -> Option<Self> {
+        frontmatter::get(content, FRONTMATTER_KEY).and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// Single-line JSON for the frontmatter
+    pub fn to_frontmatter(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Line ranges of each item of `section`
+    pub fn items(&self, section: &str) -> &[Vec<LineRange>] {
+        self.0.get(section).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Items of a section of a session archive, in the order sources list
+/// them: the summary paragraph whole, bullets for the other sections
+pub fn section_items<'a>(content: &'a str, key: &str) -> Vec<&'a str> {
+    let Some((_, heading)) = SECTIONS.iter().find(|(k, _)| *k == key) else {
+        return Vec::new();
+    };
+    let text = section(content, heading).map(str::trim).unwrap_or_default();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let items: Vec<&str> = bullets(Some(text)).collect();
+    if key == "summary" || items.is_empty() {
+        vec![text]
+    } else {
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map_from_requests() {
+        let cited = BTreeMap::from([
+            ("summary".to_string(), vec![vec![1, 3]]),
+            ("decisions".to_string(), vec![vec![2, 2], vec![], vec![9]]),
+            ("skill_hints".to_string(), vec![vec![1]]),
+        ]);
+        let map = SourceMap::from_requests(&cited, &[1, 5, 12], 20);
+        assert_eq!(map.items("summary"), &[vec![[1, 4], [12, 20]]]);
+        assert_eq!(map.items("decisions"), &[vec![[5, 11]], vec![], vec![]]);
+        assert!(map.items("skill_hints").is_empty());
+        assert!(SourceMap::from_requests(&cited, &[], 20).is_empty());
+
+        let content = format!(
+            "---\ntitle: \"Fix\"\n{}: {}\n---\n\n## Summary\n\nFixed the parser.\n\n\
+             ## Key Decisions & Trade-offs\n\n- Use a lock\n- Retry once\n\n## Learnings\n\nNone\n",
+            FRONTMATTER_KEY,
+            map.to_frontmatter()
+        );
+        assert_eq!(SourceMap::read(&content), Some(map));
+        assert_eq!(
+            section_items(&content, "summary"),
+            vec!["Fixed the parser."]
+        );
+        assert_eq!(
+            section_items(&content, "decisions"),
+            vec!["Use a lock", "Retry once"]
+        );
+        assert_eq!(section_items(&content, "learnings"), vec!["None"]);
+    }
+}
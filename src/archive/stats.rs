@@ -0,0 +1,191 @@
+//! Storage-level statistics of the archive for `daily stats`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::daily::{card_slug, skill_command_cards};
+use super::manager::ArchiveManager;
+use super::retention::compressed_path;
+use crate::config::Config;
+
+/// Number of sessions listed under `largest_sessions`
+const LARGEST_SESSIONS: usize = 5;
+
+/// A session archive and its size on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSize {
+    pub date: String,
+    pub name: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveStats {
+    pub storage_path: PathBuf,
+    pub days: usize,
+    pub sessions: usize,
+    pub digests: usize,
+    /// Everything under the storage directory, including transcripts and jobs
+    pub total_bytes: u64,
+    pub largest_sessions: Vec<SessionSize>,
+    /// Average length in characters of a session archive
+    pub avg_session_chars: usize,
+    /// Average length in characters of a generated digest
+    pub avg_digest_chars: usize,
+    /// Extracted skills waiting in `pending-skills/` for review
+    pub pending_skills: usize,
+    /// Commands suggested by digests that are not in ~/.claude/commands
+    pub pending_commands: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_date: Option<String>,
+}
+
+impl ArchiveStats {
+    /// Walk the archive of `config`
+    pub fn collect(config: &Config) -> Result<Self> {
+        let commands_dir = dirs::home_dir().map(|home| home.join(".claude").join("commands"));
+        Self::collect_with(config, commands_dir.as_deref())
+    }
+
+    /// Walk the archive, counting commands as installed when `commands_dir`
+    /// has a file for them
+    fn collect_with(config: &Config, commands_dir: Option<&Path>) -> Result<Self> {
+        let manager = ArchiveManager::new(config.clone());
+        let dates = manager.list_dates()?;
+        let mut stats = ArchiveStats {
+            storage_path: config.storage_path(),
+            days: dates.len(),
+            newest_date: dates.first().cloned(),
+            oldest_date: dates.last().cloned(),
+            total_bytes: dir_size(&config.storage_path()),
+            pending_skills: count_pending_skills(&config.storage.path.join("pending-skills")),
+            ..Default::default()
+        };
+
+        let mut session_chars = 0;
+        let mut digest_chars = 0;
+        let mut sizes = Vec::new();
+        for date in &dates {
+            for name in manager.list_sessions(date)? {
+                let path = manager.session_archive_path(date, &name);
+                let bytes = fs::metadata(&path)
+                    .or_else(|_| fs::metadata(compressed_path(&path)))
+                    .map(|meta| meta.len())
+                    .unwrap_or(0);
+                if let Ok(content) = manager.read_session(date, &name) {
+                    session_chars += content.chars().count();
+                }
+                stats.sessions += 1;
+                sizes.push(SessionSize {
+                    date: date.clone(),
+                    name,
+                    bytes,
+                });
+            }
+
+            if !manager.has_digest(date) {
+                continue;
+            }
+            let Ok(content) = manager.read_daily_summary(date) else {
+                continue;
+            };
+            stats.digests += 1;
+            digest_chars += content.chars().count();
+            let (_, commands) = skill_command_cards(&content);
+            stats.pending_commands += commands
+                .iter()
+                .filter(|card| {
+                    let file = format!("{}.md", card_slug(&card.title));
+                    !commands_dir.is_some_and(|dir| dir.join(file).exists())
+                })
+                .count();
+        }
+
+        sizes.sort_by_key(|size| std::cmp::Reverse(size.bytes));
+        sizes.truncate(LARGEST_SESSIONS);
+        stats.largest_sessions = sizes;
+        stats.avg_session_chars = session_chars.checked_div(stats.sessions).unwrap_or(0);
+        stats.avg_digest_chars = digest_chars.checked_div(stats.digests).unwrap_or(0);
+        Ok(stats)
+    }
+}
+
+/// Total size of the files under `dir`
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Skill files in `pending-skills/<date>/`
+fn count_pending_skills(pending_dir: &Path) -> usize {
+    let Ok(dates) = fs::read_dir(pending_dir) else {
+        return 0;
+    };
+    dates
+        .flatten()
+        .filter_map(|date| fs::read_dir(date.path()).ok())
+        .flat_map(|files| files.flatten())
+        .filter(|file| file.path().extension().is_some_and(|ext| ext == "md"))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        manager
+            .write_session("2026-03-01", "small", "# Small\n")
+            .unwrap();
+        manager
+            .write_session("2026-03-02", "large", &"x".repeat(100))
+            .unwrap();
+        manager
+            .write_daily_summary(
+                "2026-03-02",
+                "## Overview\nBusy day.\n\n## Skills & Commands Identified\n\n\
+                 ### Potential Commands\n\n### /deploy\nShip it.\n\n### /lint\nCheck it.\n",
+            )
+            .unwrap();
+        let pending = temp_dir.path().join("pending-skills").join("2026-03-02");
+        fs::create_dir_all(&pending).unwrap();
+        fs::write(pending.join("review-pr.md"), "skill").unwrap();
+
+        let commands_dir = temp_dir.path().join("commands");
+        fs::create_dir_all(&commands_dir).unwrap();
+        fs::write(commands_dir.join("deploy.md"), "installed").unwrap();
+
+        let stats = ArchiveStats::collect_with(&config, Some(&commands_dir)).unwrap();
+        assert_eq!(stats.days, 2);
+        assert_eq!(stats.sessions, 2);
+        assert_eq!(stats.digests, 1);
+        assert_eq!(stats.oldest_date.as_deref(), Some("2026-03-01"));
+        assert_eq!(stats.newest_date.as_deref(), Some("2026-03-02"));
+        assert_eq!(stats.largest_sessions[0].name, "large");
+        assert_eq!(stats.largest_sessions[0].bytes, 100);
+        assert_eq!(stats.avg_session_chars, 54);
+        assert_eq!(stats.pending_skills, 1);
+        assert_eq!(stats.pending_commands, 1);
+        assert!(stats.total_bytes >= 108);
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::Local;
 
 use super::daily::SummaryCard;
+use super::schema::SCHEMA_VERSION;
 
 /// Templates for generating Obsidian-compatible Markdown files
 pub struct Templates;
@@ -35,6 +36,7 @@ git_branch: "{git_branch_str}"
 transcript_path: "{transcript_path_str}"
 tags: [claude-code, session-archive]
 created: {created}
+schema_version: {SCHEMA_VERSION}
 ---
 
 # {title}
@@ -74,7 +76,7 @@ created: {created}
     #[allow(clippy::too_many_arguments)]
     pub fn daily_summary(
         date: &str,
-        session_count: usize,
+        sessions: &[String],
         overview: &str,
         session_details: &str,
         insights: &[SummaryCard],
@@ -82,13 +84,31 @@ created: {created}
         commands: &[SummaryCard],
         reflections: &str,
         tomorrow_focus: &[SummaryCard],
+        goal_progress: &[SummaryCard],
+        projects: &[SummaryCard],
     ) -> String {
         let updated = Local::now().to_rfc3339();
+        let session_count = sessions.len();
+        let sessions = format!("[{}]", sessions.join(", "));
 
         let insights_md = Self::render_cards(insights);
         let skills_md = Self::render_cards(skills);
         let commands_md = Self::render_cards(commands);
         let tomorrow_md = Self::render_cards(tomorrow_focus);
+        let goals_md = if goal_progress.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "## Goal Progress\n\n{}\n\n",
+                Self::render_cards(goal_progress)
+            )
+        };
+
+        let projects_md = if projects.is_empty() {
+            String::new()
+        } else {
+            format!("## By Project\n\n{}\n\n", Self::render_cards(projects))
+        };
 
         format!(
             r#"---
@@ -96,6 +116,8 @@ date: {date}
 updated: {updated}
 tags: [daily-summary, claude-code]
 session_count: {session_count}
+sessions: {sessions}
+schema_version: {SCHEMA_VERSION}
 ---
 
 # Daily Summary - {date}
@@ -108,7 +130,7 @@ session_count: {session_count}
 
 {session_details}
 
-## Key Insights
+{projects_md}## Key Insights
 
 {insights_md}
 
@@ -120,7 +142,7 @@ session_count: {session_count}
 
 {tomorrow_md}
 
-## Skills & Commands Identified
+{goals_md}## Skills & Commands Identified
 
 ### Potential Skills
 
@@ -137,6 +159,33 @@ session_count: {session_count}
         )
     }
 
+    /// Generate one project's part of a grouped daily summary, for the
+    /// project's own storage
+    pub fn project_digest(date: &str, project: &str, sessions: &[String], content: &str) -> String {
+        let updated = Local::now().to_rfc3339();
+        let session_count = sessions.len();
+        let sessions = format!("[{}]", sessions.join(", "));
+
+        format!(
+            r#"---
+date: {date}
+updated: {updated}
+project: {project}
+tags: [daily-summary, claude-code, project]
+session_count: {session_count}
+sessions: {sessions}
+---
+
+# {project} - {date}
+
+{content}
+
+---
+*Generated by Daily Context Archive System*
+"#
+        )
+    }
+
     /// Render a slice of SummaryCards as markdown subsections
     fn render_cards(cards: &[SummaryCard]) -> String {
         if cards.is_empty() {
@@ -160,6 +209,8 @@ created: {created}
 updated: {created}
 tags: [daily-summary, claude-code]
 session_count: 0
+sessions: []
+schema_version: {SCHEMA_VERSION}
 ---
 
 # Daily Summary - {date}
@@ -0,0 +1,193 @@
+//! Archived copies of session transcripts.
+//!
+//! Claude Code eventually cleans up `~/.claude/projects/`, so the JSONL is
+//! copied to `{date}/transcripts/{session_id}.jsonl` (or `.jsonl.zst`) at
+//! summarize time, with sub-agent transcripts under `{session_id}/subagents/`.
+//! Readers resolve the archived copy before the original path.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use super::frontmatter;
+use super::retention::compressed_path;
+use crate::config::Config;
+use crate::storage;
+use crate::transcript::sidechain;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Location of the uncompressed archived copy for a session
+pub fn archived_path(config: &Config, date: &str, session_id: &str) -> PathBuf {
+    config
+        .date_dir(date)
+        .join("transcripts")
+        .join(format!("{}.jsonl", session_id))
+}
+
+/// Copy `source` and its sub-agent transcripts into the date's archive
+/// folder, replacing any earlier copy
+pub fn copy_to_archive(
+    config: &Config,
+    date: &str,
+    session_id: &str,
+    source: &Path,
+) -> Result<PathBuf> {
+    let target = archived_path(config, date, session_id);
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).context("Failed to create transcripts directory")?;
+    }
+    let copied = copy_file(config, source, &target)?;
+
+    let sidechains = sidechain::files(source);
+    if !sidechains.is_empty() {
+        let dir = target.with_extension("").join("subagents");
+        fs::create_dir_all(&dir).context("Failed to create subagents directory")?;
+        for path in sidechains {
+            let Some(stem) = sidechain::session_stem(&path) else {
+                continue;
+            };
+            let target = dir.join(format!("{}.jsonl", stem));
+            copy_file(config, &path, &target)?;
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Copy one JSONL file to `target` (or `target.zst` with
+/// `archive.compress_transcript`), removing the other variant
+fn copy_file(config: &Config, source: &Path, target: &Path) -> Result<PathBuf> {
+    let compressed = compressed_path(target);
+    let (copied, other) = if config.archive.compress_transcript {
+        (compressed.as_path(), target)
+    } else {
+        (target, compressed.as_path())
+    };
+    let output = if config.archive.compress_transcript {
+        let input = fs::File::open(source)
+            .with_context(|| format!("Failed to open {}", source.display()))?;
+        zstd::encode_all(input, ZSTD_LEVEL).context("Failed to compress transcript")?
+    } else {
+        fs::read(source).context("Failed to copy transcript")?
+    };
+    storage::write_file(config, copied, output)?;
+    if other.exists() {
+        let _ = storage::remove_file(config, other);
+    }
+    Ok(copied.to_path_buf())
+}
+
+/// Transcript for a session archive: the archived copy if present, otherwise
+/// the original `transcript_path` while it still exists
+pub fn resolve(config: &Config, date: &str, content: &str) -> Option<PathBuf> {
+    if let Some(session_id) = frontmatter::get(content, "session_id").filter(|id| !id.is_empty()) {
+        let archived = archived_path(config, date, session_id);
+        let compressed = compressed_path(&archived);
+        if archived.exists() {
+            return Some(archived);
+        }
+        if compressed.exists() {
+            return Some(compressed);
+        }
+    }
+
+    frontmatter::get(content, "transcript_path")
+        .filter(|p| !p.is_empty() && *p != "N/A")
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+}
+
+/// Open a transcript for line reading, decompressing `.zst` copies
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "zst") {
+        let decoder = zstd::stream::read::Decoder::new(file)
+            .with_context(|| format!("Failed to decompress {}", path.display()))?;
+        Ok(Box::new(BufReader::new(decoder)))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const JSONL: &str = "{\"type\":\"user\"}\n{\"type\":\"assistant\"}\n";
+
+    fn setup(dir: &TempDir) -> (Config, PathBuf, String) {
+        let mut config = Config::default();
+        config.storage.path = dir.path().join("archive");
+        let source = dir.path().join("abc.jsonl");
+        fs::write(&source, JSONL).unwrap();
+        let content = format!(
+            "---\nsession_id: abc\ntranscript_path: {}\n---\n",
+            source.display()
+        );
+        (config, source, content)
+    }
+
+    fn read_all(path: &Path) -> String {
+        let lines: Vec<String> = open(path).unwrap().lines().map(Result::unwrap).collect();
+        lines.join("\n") + "\n"
+    }
+
+    #[test]
+    fn test_archived_copy_outlives_original() {
+        let dir = TempDir::new().unwrap();
+        let (config, source, content) = setup(&dir);
+
+        // Before copying, the original is used
+        assert_eq!(
+            resolve(&config, "2024-01-15", &content),
+            Some(source.clone())
+        );
+
+        let subagents = dir.path().join("abc").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        fs::write(subagents.join("agent-1.jsonl"), JSONL).unwrap();
+
+        let copy = copy_to_archive(&config, "2024-01-15", "abc", &source).unwrap();
+        fs::remove_file(&source).unwrap();
+        assert_eq!(resolve(&config, "2024-01-15", &content), Some(copy.clone()));
+        assert_eq!(read_all(&copy), JSONL);
+        // Sub-agent transcripts stay findable next to the copy
+        assert_eq!(
+            sidechain::files(&copy),
+            vec![copy
+                .with_extension("")
+                .join("subagents")
+                .join("agent-1.jsonl")]
+        );
+    }
+
+    #[test]
+    fn test_compressed_copy_is_readable() {
+        let dir = TempDir::new().unwrap();
+        let (mut config, source, content) = setup(&dir);
+        config.archive.compress_transcript = true;
+
+        let copy = copy_to_archive(&config, "2024-01-15", "abc", &source).unwrap();
+        assert!(copy.to_string_lossy().ends_with(".jsonl.zst"));
+        fs::remove_file(&source).unwrap();
+
+        let resolved = resolve(&config, "2024-01-15", &content).unwrap();
+        assert_eq!(read_all(&resolved), JSONL);
+    }
+
+    #[test]
+    fn test_resolve_missing_transcript() {
+        let dir = TempDir::new().unwrap();
+        let (config, source, content) = setup(&dir);
+        fs::remove_file(&source).unwrap();
+        assert_eq!(resolve(&config, "2024-01-15", &content), None);
+        assert_eq!(
+            resolve(&config, "2024-01-15", "---\ntranscript_path: N/A\n---\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Questions answered from the archive.
+//!
+//! Sessions and digests are ranked against the question by keyword overlap
+//! (tf-idf over words of three or more characters) and, with
+//! `embeddings.enabled`, by semantic similarity as well. The best matches
+//! are numbered and handed to the summarizer model, which answers citing
+//! them as `[n]`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::{frontmatter, ArchiveManager};
+use crate::config::Config;
+use crate::embeddings;
+use crate::summarizer::{Prompts, SummarizerEngine};
+
+/// Question words too common to say anything about relevance
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "how", "did", "does", "what", "was", "were", "with", "that", "this",
+    "use", "used", "why", "when", "where", "which", "who", "you", "are", "from", "have", "has",
+    "not", "but", "about", "into", "any", "can", "there",
+];
+
+/// Characters of each source passed to the model
+const EXCERPT_CHARS: usize = 4000;
+
+/// An archive file used to answer a question
+#[derive(Debug, Clone, Serialize)]
+pub struct Source {
+    /// "session" or "digest"
+    pub kind: String,
+    pub date: String,
+    /// Session name; `None` for a digest
+    pub name: Option<String>,
+    pub title: String,
+    /// Relevance to the question, 0-1
+    pub score: f32,
+    #[serde(skip)]
+    pub content: String,
+}
+
+impl Source {
+    /// `date/name` for a session, `date` for a digest
+    pub fn reference(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}/{}", self.date, name),
+            None => self.date.clone(),
+        }
+    }
+}
+
+/// The model's answer and the sources it was given
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    pub question: String,
+    pub answer: String,
+    pub sources: Vec<Source>,
+}
+
+/// Answer `question` from the `limit` most relevant archives
+pub async fn ask(config: &Config, question: &str, limit: usize) -> Result<Answer> {
+    let question = question.trim();
+    if question.is_empty() {
+        anyhow::bail!("Question is empty");
+    }
+
+    let manager = ArchiveManager::new(config.clone());
+    let mut sources = keyword_search(&manager, question)?;
+    if config.embeddings.enabled {
+        match embeddings::search(config, question, limit).await {
+            Ok(related) => {
+                for session in related {
+                    merge(
+                        &mut sources,
+                        &manager,
+                        &session.date,
+                        &session.name,
+                        session.score,
+                    );
+                }
+            }
+            Err(e) => eprintln!(
+                "[daily] Semantic search failed, using keywords only: {:#}",
+                e
+            ),
+        }
+    }
+    sources.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sources.truncate(limit);
+
+    if sources.is_empty() {
+        return Ok(Answer {
+            question: question.to_string(),
+            answer: "No archived sessions or digests match this question.".to_string(),
+            sources,
+        });
+    }
+
+    let numbered: Vec<(String, String)> = sources
+        .iter()
+        .map(|s| (s.reference(), excerpt(&s.content)))
+        .collect();
+    let prompt = Prompts::ask(question, &numbered, &config.summarization.summary_language);
+    let answer = SummarizerEngine::new(config.clone())
+        .answer(&prompt)
+        .await?;
+
+    Ok(Answer {
+        question: question.to_string(),
+        answer: answer.trim().to_string(),
+        sources,
+    })
+}
+
+/// Sessions and digests sharing words with `question`, scored by tf-idf and
+/// normalized so the best match is 1
+fn keyword_search(manager: &ArchiveManager, question: &str) -> Result<Vec<Source>> {
+    let terms: HashSet<String> = words(question)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut documents = Vec::new();
+    for date in manager.list_dates()? {
+        for name in manager.list_sessions(&date)? {
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            if let Ok(content) = manager.read_session(&date, &name) {
+                documents.push(source(&date, Some(&name), content, 0.0));
+            }
+        }
+        if manager.has_digest(&date) {
+            if let Ok(content) = manager.read_daily_summary(&date) {
+                documents.push(source(&date, None, content, 0.0));
+            }
+        }
+    }
+
+    let counts: Vec<HashMap<String, usize>> = documents
+        .iter()
+        .map(|doc| {
+            let mut counts = HashMap::new();
+            for word in words(&doc.content).filter(|w| terms.contains(w)) {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+            counts
+        })
+        .collect();
+
+    let total = documents.len() as f32;
+    let idf: HashMap<&String, f32> = terms
+        .iter()
+        .map(|term| {
+            let df = counts.iter().filter(|c| c.contains_key(term)).count() as f32;
+            (term, (1.0 + total / (1.0 + df)).ln())
+        })
+        .collect();
+
+    let mut scored: Vec<Source> = documents
+        .into_iter()
+        .zip(&counts)
+        .filter(|(_, counts)| !counts.is_empty())
+        .map(|(mut doc, counts)| {
+            doc.score = counts
+                .iter()
+                .map(|(term, &tf)| (1.0 + tf as f32).ln() * idf[term])
+                .sum();
+            doc
+        })
+        .collect();
+
+    let best = scored.iter().map(|s| s.score).fold(0.0, f32::max);
+    if best > 0.0 {
+        for s in &mut scored {
+            s.score /= best;
+        }
+    }
+    Ok(scored)
+}
+
+/// Add a semantic match, keeping the better score if keywords found it too
+fn merge(sources: &mut Vec<Source>, manager: &ArchiveManager, date: &str, name: &str, score: f32) {
+    if let Some(existing) = sources
+        .iter_mut()
+        .find(|s| s.date == date && s.name.as_deref() == Some(name))
+    {
+        existing.score = existing.score.max(score);
+    } else if let Ok(content) = manager.read_session(date, name) {
+        sources.push(source(date, Some(name), content, score));
+    }
+}
+
+fn source(date: &str, name: Option<&str>, content: String, score: f32) -> Source {
+    let title = frontmatter::get(&content, "title")
+        .or(name)
+        .unwrap_or("Daily digest")
+        .to_string();
+    Source {
+        kind: if name.is_some() { "session" } else { "digest" }.to_string(),
+        date: date.to_string(),
+        name: name.map(str::to_string),
+        title,
+        score,
+        content,
+    }
+}
+
+/// Lowercased words of three or more characters
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+}
+
+/// Start of an archive without its frontmatter, cut to `EXCERPT_CHARS`
+fn excerpt(content: &str) -> String {
+    frontmatter::body(content)
+        .trim()
+        .chars()
+        .take(EXCERPT_CHARS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_keyword_search_ranks_matching_archives() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        let session = |title: &str, body: &str| {
+            format!("---\ntitle: {}\n---\n\n## Summary\n\n{}\n", title, body)
+        };
+        manager
+            .write_session(
+                "2024-01-01",
+                "10_00-ws",
+                &session(
+                    "Websocket reconnect",
+                    "Fixed the websocket reconnect loop with exponential backoff. \
+                     The websocket now reconnects after a server restart.",
+                ),
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-01-02",
+                "11_00-css",
+                &session("Button styles", "Tweaked the button padding."),
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-01-02",
+                "checkpoint-ws",
+                &session("Checkpoint", "websocket reconnect"),
+            )
+            .unwrap();
+        manager
+            .write_daily_summary(
+                "2024-01-02",
+                "# Daily\n\n## Overview\n\nMostly CSS, one reconnect fix.\n",
+            )
+            .unwrap();
+
+        let mut sources =
+            keyword_search(&manager, "How did I fix the websocket reconnect bug?").unwrap();
+        sources.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].reference(), "2024-01-01/10_00-ws");
+        assert_eq!(sources[0].title, "Websocket reconnect");
+        assert_eq!(sources[0].score, 1.0);
+        assert_eq!(sources[1].kind, "digest");
+        assert!(sources[1].score < 1.0);
+    }
+}
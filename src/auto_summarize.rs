@@ -2,11 +2,15 @@ use anyhow::{Context, Result};
 use chrono::{Local, NaiveTime};
 use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::archive::paths::PathFilter;
+use crate::archive::session::CHECKPOINT_PREFIX;
 use crate::archive::ArchiveManager;
 use crate::config::Config;
-use crate::transcript::TranscriptParser;
+use crate::hooks::activity;
+use crate::transcript::{resume, sidechain, TranscriptParser};
+use crate::usage::scanner::decode_project_dir;
 
 /// Represents an unsummarized transcript that needs processing
 #[derive(Debug, Clone)]
@@ -39,12 +43,8 @@ pub fn find_all_transcripts() -> Result<Vec<PathBuf>> {
                 let sub_path = sub_entry.path();
 
                 if sub_path.extension().is_some_and(|ext| ext == "jsonl") {
-                    // Skip agent transcripts (temporary agent sessions)
-                    if !sub_path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .is_some_and(|name| name.starts_with("agent-"))
-                    {
+                    // Skip agent transcripts (sub-agent sidechains of a session)
+                    if !sidechain::is_sidechain_file(&sub_path) {
                         transcripts.push(sub_path);
                     }
                 }
@@ -66,6 +66,11 @@ fn get_archived_transcript_paths(config: &Config) -> Result<HashSet<String>> {
     for date in dates {
         let sessions = archive_manager.list_sessions(&date)?;
         for session in sessions {
+            // Checkpoints are partial; the session still needs a full summary
+            if session.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+
             // Read the session file to extract transcript path from frontmatter
             if let Ok(content) = archive_manager.read_session(&date, &session) {
                 // Extract transcript_path from frontmatter
@@ -97,9 +102,19 @@ fn get_archived_transcript_paths(config: &Config) -> Result<HashSet<String>> {
     Ok(archived_paths)
 }
 
-/// Check if a transcript has been recently modified (within the configured inactive threshold)
+/// Check if a transcript has been recently modified or had a prompt heartbeat
+/// (within the inactive threshold of its project)
 /// This helps avoid processing active sessions
-fn is_transcript_active(path: &std::path::Path, inactive_minutes: u64) -> bool {
+fn is_transcript_active(config: &Config, path: &Path) -> bool {
+    let inactive_minutes = inactive_minutes(config, project_dir(path).as_deref());
+
+    // UserPromptSubmit heartbeats catch sessions idle on disk but still open
+    if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
+        if activity::is_recently_active(config, session_id, inactive_minutes) {
+            return true;
+        }
+    }
+
     if let Ok(metadata) = fs::metadata(path) {
         if let Ok(modified) = metadata.modified() {
             if let Ok(elapsed) = modified.elapsed() {
@@ -111,6 +126,76 @@ fn is_transcript_active(path: &std::path::Path, inactive_minutes: u64) -> bool {
     false
 }
 
+/// Project directory decoded from the transcript's `~/.claude/projects/` folder
+fn project_dir(transcript: &Path) -> Option<PathBuf> {
+    let folder = transcript.parent()?.file_name()?.to_str()?;
+    Some(PathBuf::from(decode_project_dir(folder)))
+}
+
+/// Minutes of inactivity before a session in `project` counts as ended:
+/// the longest matching `project_inactive_minutes` entry, or the global setting
+fn inactive_minutes(config: &Config, project: Option<&Path>) -> u64 {
+    let policy = &config.summarization.auto_summarize_policy;
+    project
+        .and_then(|project| {
+            policy
+                .project_inactive_minutes
+                .iter()
+                .map(|(dir, minutes)| (expand_dir(dir), *minutes))
+                .filter(|(dir, _)| project.starts_with(dir))
+                .max_by_key(|(dir, _)| dir.components().count())
+                .map(|(_, minutes)| minutes)
+        })
+        .unwrap_or(config.summarization.auto_summarize_inactive_minutes)
+}
+
+fn expand_dir(dir: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(dir).as_ref())
+}
+
+/// Parse a "HH:MM-HH:MM" window
+pub fn parse_quiet_hours(value: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = value.split_once('-')?;
+    let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+    Some((parse(start)?, parse(end)?))
+}
+
+/// Whether `now` falls in the policy's quiet hours
+pub fn in_quiet_hours(config: &Config, now: NaiveTime) -> bool {
+    let Some((start, end)) = config
+        .summarization
+        .auto_summarize_policy
+        .quiet_hours
+        .as_deref()
+        .and_then(parse_quiet_hours)
+    else {
+        return false;
+    };
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Why the policy leaves a session with `turns` prompts, run in `cwd`, to be
+/// summarized by hand; None if auto-summarize may take it
+fn policy_skip_reason(config: &Config, cwd: Option<&Path>, turns: usize) -> Option<String> {
+    let policy = &config.summarization.auto_summarize_policy;
+    if turns < policy.min_turns {
+        return Some(format!(
+            "{} prompt(s), fewer than min_turns = {}",
+            turns, policy.min_turns
+        ));
+    }
+    let cwd = cwd?;
+    policy
+        .skip_dirs
+        .iter()
+        .find(|dir| cwd.starts_with(expand_dir(dir)))
+        .map(|dir| format!("{} is under skip_dirs entry {}", cwd.display(), dir))
+}
+
 /// Check if a transcript was modified yesterday
 /// This helps limit auto-summarization to only yesterday's sessions
 fn is_transcript_from_yesterday(path: &std::path::Path) -> bool {
@@ -137,13 +222,16 @@ fn is_transcript_from_yesterday(path: &std::path::Path) -> bool {
 /// This function now uses transcript_path from session.md frontmatter for accurate tracking.
 /// It also applies safety measures:
 /// 1. Only processes transcripts from yesterday or today (to avoid processing too many old files)
-/// 2. Only processes transcripts that haven't been modified in the last 2 hours (likely inactive)
-/// 3. Limits to MAX_AUTO_SUMMARIZE to prevent fork bomb
+/// 2. Only processes transcripts idle for their project's inactivity threshold
+/// 3. Skips sessions excluded by `summarization.auto_summarize_policy`
+/// 4. Limits to MAX_AUTO_SUMMARIZE to prevent fork bomb
 pub fn find_unsummarized_transcripts(config: &Config) -> Result<Vec<UnsummarizedTranscript>> {
     let all_transcripts = find_all_transcripts()?;
     let archived_paths = get_archived_transcript_paths(config)?;
+    let paths = PathFilter::from_config(config);
 
     let mut unsummarized = Vec::new();
+    let mut superseded = HashSet::new();
     const MAX_AUTO_SUMMARIZE: usize = 3; // Conservative limit to prevent fork bomb
 
     for transcript_path in all_transcripts {
@@ -154,10 +242,7 @@ pub fn find_unsummarized_transcripts(config: &Config) -> Result<Vec<Unsummarized
         }
 
         // Skip if transcript is still active (modified within the configured threshold)
-        if is_transcript_active(
-            &transcript_path,
-            config.summarization.auto_summarize_inactive_minutes,
-        ) {
+        if is_transcript_active(config, &transcript_path) {
             continue;
         }
 
@@ -178,6 +263,22 @@ pub fn find_unsummarized_transcripts(config: &Config) -> Result<Vec<Unsummarized
             continue;
         }
 
+        let cwd = data
+            .cwd()
+            .map(PathBuf::from)
+            .or_else(|| project_dir(&transcript_path));
+        if !paths.allows(cwd.as_deref()) {
+            continue;
+        }
+        if let Some(reason) = policy_skip_reason(config, cwd.as_deref(), data.user_messages.len()) {
+            eprintln!(
+                "[daily] Not auto-summarizing {}: {}",
+                transcript_path.display(),
+                reason
+            );
+            continue;
+        }
+
         // Extract session ID from file name
         let session_id = transcript_path
             .file_stem()
@@ -185,19 +286,76 @@ pub fn find_unsummarized_transcripts(config: &Config) -> Result<Vec<Unsummarized
             .map(|s| s.to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
+        superseded.extend(resume::parents(&transcript_path, &data));
         unsummarized.push(UnsummarizedTranscript {
             path: transcript_path.clone(),
             session_id,
-            cwd: None, // CWD is not available from transcript files
+            cwd,
         });
+    }
+
+    // A resumed session's summary covers the sessions it continues
+    unsummarized.retain(|t| !superseded.contains(&t.session_id));
+    // Conservative limit to prevent fork bomb
+    unsummarized.truncate(MAX_AUTO_SUMMARIZE);
 
-        // Conservative limit to prevent fork bomb
-        if unsummarized.len() >= MAX_AUTO_SUMMARIZE {
-            break;
+    Ok(unsummarized)
+}
+
+/// Find transcripts modified within the last `days` days that have no session archive.
+///
+/// Used by `daily backfill`. Unlike auto-summarize there is no count cap, since the caller
+/// bounds concurrency itself. Active, empty and resumed-over transcripts are skipped, and results
+/// are sorted oldest first so archives are written in chronological order.
+pub fn find_backfill_transcripts(
+    config: &Config,
+    days: u64,
+) -> Result<Vec<UnsummarizedTranscript>> {
+    let archived_paths = get_archived_transcript_paths(config)?;
+    let paths = PathFilter::from_config(config);
+    let max_age = std::time::Duration::from_secs(days * 24 * 60 * 60);
+
+    let mut candidates: Vec<(std::time::SystemTime, PathBuf)> = find_all_transcripts()?
+        .into_iter()
+        .filter(|path| !archived_paths.contains(&path.to_string_lossy().to_string()))
+        .filter(|path| !is_transcript_active(config, path))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let age = modified.elapsed().unwrap_or_default();
+            (age <= max_age).then_some((modified, path))
+        })
+        .collect();
+    candidates.sort();
+
+    let mut transcripts = Vec::new();
+    let mut superseded = HashSet::new();
+    for (_, path) in candidates {
+        let data = match TranscriptParser::parse(&path) {
+            Ok(data) if !data.is_empty() => data,
+            _ => continue,
+        };
+        let cwd = data.cwd().map(PathBuf::from).or_else(|| project_dir(&path));
+        if !paths.allows(cwd.as_deref()) {
+            continue;
         }
+        let session_id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        superseded.extend(resume::parents(&path, &data));
+        transcripts.push(UnsummarizedTranscript {
+            cwd,
+            path,
+            session_id,
+        });
     }
 
-    Ok(unsummarized)
+    // A resumed session's summary covers the sessions it continues
+    transcripts.retain(|t| !superseded.contains(&t.session_id));
+
+    Ok(transcripts)
 }
 
 /// Check if auto-summarization should be triggered on `daily show`
@@ -205,10 +363,13 @@ pub fn find_unsummarized_transcripts(config: &Config) -> Result<Vec<Unsummarized
 /// Returns true if:
 /// 1. auto_summarize_enabled is true (master switch)
 /// 2. auto_summarize_on_show is true
+/// 3. It is not within the policy's quiet hours
 ///
 /// This bypasses time-based checks and triggers on every `daily show` invocation.
 pub fn should_trigger_auto_summarize_on_show(config: &Config) -> bool {
-    config.summarization.auto_summarize_enabled && config.summarization.auto_summarize_on_show
+    config.summarization.auto_summarize_enabled
+        && config.summarization.auto_summarize_on_show
+        && !in_quiet_hours(config, Local::now().time())
 }
 
 /// Check if auto-summarization should be triggered (time-based)
@@ -217,6 +378,7 @@ pub fn should_trigger_auto_summarize_on_show(config: &Config) -> bool {
 /// 1. auto_summarize_enabled is true
 /// 2. Current time is after the configured trigger time (e.g., 06:00)
 /// 3. Last check was NOT today after the trigger time
+/// 4. It is not within the policy's quiet hours
 ///
 /// Note: This function only controls WHEN to check, not WHICH transcripts to process.
 /// The actual filtering (only yesterday's transcripts) is done in find_unsummarized_transcripts()
@@ -239,6 +401,11 @@ pub fn should_trigger_auto_summarize(config: &Config) -> Result<bool> {
         return Ok(false);
     }
 
+    // Wait out quiet hours; the check stays due until they end
+    if in_quiet_hours(config, current_time) {
+        return Ok(false);
+    }
+
     // Check last check time
     if let Some(last_check_str) = &config.summarization.last_auto_summarize_check {
         if let Ok(last_check) = chrono::DateTime::parse_from_rfc3339(last_check_str) {
@@ -277,4 +444,38 @@ mod tests {
 
         assert!(should_trigger_auto_summarize(&config).unwrap());
     }
+
+    #[test]
+    fn test_auto_summarize_policy() {
+        let mut config = Config::default();
+        config.summarization.auto_summarize_inactive_minutes = 30;
+        let policy = &mut config.summarization.auto_summarize_policy;
+        policy.quiet_hours = Some("22:00-07:00".to_string());
+        policy.min_turns = 3;
+        policy.skip_dirs = vec!["/tmp".to_string()];
+        policy
+            .project_inactive_minutes
+            .insert("/work".to_string(), 60);
+        policy
+            .project_inactive_minutes
+            .insert("/work/monorepo".to_string(), 120);
+
+        let at = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").unwrap();
+        assert!(in_quiet_hours(&config, at("23:30")));
+        assert!(in_quiet_hours(&config, at("06:59")));
+        assert!(!in_quiet_hours(&config, at("07:00")));
+        assert!(!in_quiet_hours(&config, at("12:00")));
+
+        let minutes = |p: &str| inactive_minutes(&config, Some(Path::new(p)));
+        assert_eq!(minutes("/work/monorepo/api"), 120);
+        assert_eq!(minutes("/work/site"), 60);
+        assert_eq!(minutes("/home/me"), 30);
+        assert_eq!(inactive_minutes(&config, None), 30);
+
+        let skip = |cwd: &str, turns| policy_skip_reason(&config, Some(Path::new(cwd)), turns);
+        assert!(skip("/work/site", 5).is_none());
+        assert!(skip("/work/site", 2).unwrap().contains("min_turns"));
+        assert!(skip("/tmp/scratch", 5).unwrap().contains("skip_dirs"));
+        assert!(skip("/tmpfiles", 5).is_none());
+    }
 }
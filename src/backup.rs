@@ -0,0 +1,262 @@
+//! Timestamped snapshots of the storage directory.
+//!
+//! `daily backup now` writes `daily-{YYYYMMDD-HHMMSS}.tar.zst` (or `.tar`)
+//! to `backup.dir`, keeping the newest `backup.keep`. The dashboard server
+//! takes one on `backup.schedule`. Restoring extracts a snapshot next to the
+//! storage directory and swaps it in; the current storage is moved aside,
+//! never deleted.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+const PREFIX: &str = "daily-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const ZSTD_LEVEL: i32 = 3;
+
+/// A snapshot file in the backup directory
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    /// e.g. "20240115-093000"
+    pub timestamp: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+impl Snapshot {
+    fn taken_at(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.timestamp, TIMESTAMP_FORMAT).ok()
+    }
+}
+
+/// Where snapshots go: `backup.dir`, or a `-backups` folder beside the storage
+pub fn backup_dir(config: &Config) -> PathBuf {
+    match &config.backup.dir {
+        Some(dir) => PathBuf::from(shellexpand::tilde(&dir.to_string_lossy()).to_string()),
+        None => {
+            let storage = config.storage_path();
+            let name = storage
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "daily".into());
+            storage.with_file_name(format!("{}-backups", name))
+        }
+    }
+}
+
+/// Snapshot the storage directory and drop snapshots beyond `backup.keep`
+pub fn create(config: &Config) -> Result<Snapshot> {
+    let storage = config.storage_path();
+    let dir = backup_dir(config);
+    if dir.starts_with(&storage) {
+        anyhow::bail!(
+            "backup.dir ({}) must be outside the storage directory",
+            dir.display()
+        );
+    }
+    if !storage.is_dir() {
+        anyhow::bail!("Storage directory {} does not exist", storage.display());
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let timestamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let path = dir.join(format!("{}{}.{}", PREFIX, timestamp, config.backup.format));
+    let partial = path.with_extension("partial");
+    write_archive(&storage, &partial, &config.backup.format)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path)?;
+
+    prune(config)?;
+    Ok(Snapshot {
+        bytes: fs::metadata(&path)?.len(),
+        timestamp,
+        path,
+    })
+}
+
+fn write_archive(storage: &Path, path: &Path, format: &str) -> Result<()> {
+    let file = File::create(path)?;
+    let writer: Box<dyn Write> = match format {
+        "tar" => Box::new(file),
+        _ => Box::new(zstd::stream::write::Encoder::new(file, ZSTD_LEVEL)?.auto_finish()),
+    };
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", storage)?;
+    builder.into_inner()?.flush()?;
+    Ok(())
+}
+
+/// Snapshots in the backup directory, newest first
+pub fn list(config: &Config) -> Result<Vec<Snapshot>> {
+    let dir = backup_dir(config);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(timestamp) = name
+            .strip_prefix(PREFIX)
+            .and_then(|rest| rest.strip_suffix(".tar.zst").or(rest.strip_suffix(".tar")))
+        else {
+            continue;
+        };
+        snapshots.push(Snapshot {
+            timestamp: timestamp.to_string(),
+            path: entry.path(),
+            bytes: entry.metadata()?.len(),
+        });
+    }
+    snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(snapshots)
+}
+
+/// Delete the snapshots past the newest `backup.keep`
+fn prune(config: &Config) -> Result<()> {
+    for snapshot in list(config)?.into_iter().skip(config.backup.keep) {
+        fs::remove_file(&snapshot.path)
+            .with_context(|| format!("Failed to delete {}", snapshot.path.display()))?;
+    }
+    Ok(())
+}
+
+/// Whether `backup.schedule` calls for a snapshot at `now`
+pub fn is_due(config: &Config, now: NaiveDateTime) -> bool {
+    let interval = match config.backup.schedule.as_str() {
+        "hourly" => Duration::hours(1),
+        "daily" => Duration::days(1),
+        "weekly" => Duration::weeks(1),
+        _ => return false,
+    };
+    list(config)
+        .ok()
+        .and_then(|snapshots| snapshots.first().and_then(Snapshot::taken_at))
+        .is_none_or(|last| now - last >= interval)
+}
+
+/// Replace the storage directory with the snapshot taken at `timestamp`
+/// ("latest" for the newest). Returns where the previous storage was moved.
+pub fn restore(config: &Config, timestamp: &str) -> Result<Option<PathBuf>> {
+    let snapshots = list(config)?;
+    let snapshot = if timestamp == "latest" {
+        snapshots.first()
+    } else {
+        snapshots.iter().find(|s| s.timestamp == timestamp)
+    }
+    .with_context(|| {
+        format!(
+            "No snapshot '{}' in {}; see `daily backup list`",
+            timestamp,
+            backup_dir(config).display()
+        )
+    })?;
+
+    let storage = config.storage_path();
+    let now = Local::now().format(TIMESTAMP_FORMAT);
+    let staging = sibling(&storage, &format!("restoring-{}", now));
+    fs::create_dir_all(&staging)?;
+    if let Err(e) = extract(&snapshot.path, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e.context(format!("Failed to extract {}", snapshot.path.display())));
+    }
+
+    let previous = if storage.exists() {
+        let previous = sibling(&storage, &format!("before-restore-{}", now));
+        fs::rename(&storage, &previous)
+            .with_context(|| format!("Failed to move {} aside", storage.display()))?;
+        Some(previous)
+    } else {
+        None
+    };
+    fs::rename(&staging, &storage)
+        .with_context(|| format!("Failed to move the snapshot into {}", storage.display()))?;
+    Ok(previous)
+}
+
+fn extract(snapshot: &Path, target: &Path) -> Result<()> {
+    let file = File::open(snapshot)?;
+    let reader: Box<dyn Read> = if snapshot.extension().is_some_and(|e| e == "zst") {
+        Box::new(zstd::stream::read::Decoder::new(file)?)
+    } else {
+        Box::new(file)
+    };
+    tar::Archive::new(reader).unpack(target)?;
+    Ok(())
+}
+
+/// `{storage}.{suffix}` next to the storage directory
+fn sibling(storage: &Path, suffix: &str) -> PathBuf {
+    let name = storage
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "daily".into());
+    storage.with_file_name(format!("{}.{}", name, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_list_and_restore() {
+        let dir = TempDir::new().unwrap();
+        let storage = dir.path().join("daily");
+        let mut config = Config::default();
+        config.storage.path = storage.clone();
+        config.backup.keep = 2;
+        fs::create_dir_all(storage.join("2024-01-01")).unwrap();
+        fs::write(storage.join("2024-01-01/daily.md"), "# Day one\n").unwrap();
+
+        let snapshot = create(&config).unwrap();
+        assert_eq!(
+            snapshot.path.parent().unwrap(),
+            dir.path().join("daily-backups")
+        );
+        assert!(!is_due(&config, Local::now().naive_local()));
+        config.backup.schedule = "daily".into();
+        assert!(!is_due(&config, Local::now().naive_local()));
+        assert!(is_due(
+            &config,
+            Local::now().naive_local() + Duration::days(2)
+        ));
+
+        // Only the newest `keep` snapshots are kept
+        for stamp in ["20200101-000000", "20200102-000000"] {
+            fs::write(
+                backup_dir(&config).join(format!("daily-{}.tar.zst", stamp)),
+                "",
+            )
+            .unwrap();
+        }
+        prune(&config).unwrap();
+        let timestamps: Vec<String> = list(&config)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(
+            timestamps,
+            vec![snapshot.timestamp.clone(), "20200102-000000".into()]
+        );
+
+        fs::write(storage.join("2024-01-01/daily.md"), "# Changed\n").unwrap();
+        let previous = restore(&config, &snapshot.timestamp).unwrap().unwrap();
+        assert_eq!(
+            fs::read_to_string(storage.join("2024-01-01/daily.md")).unwrap(),
+            "# Day one\n"
+        );
+        assert_eq!(
+            fs::read_to_string(previous.join("2024-01-01/daily.md")).unwrap(),
+            "# Changed\n"
+        );
+        assert!(restore(&config, "19990101-000000").is_err());
+    }
+}
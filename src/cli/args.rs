@@ -16,6 +16,22 @@ pub struct Cli {
     /// Config file path
     #[arg(short, long, global = true)]
     pub config: Option<PathBuf>,
+
+    /// Print machine-readable JSON instead of colored text, where the command supports it
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Print without colors (also when NO_COLOR is set)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print without colors or truncation, tables as tab-separated values
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Use a separate profile with its own config, archive and dashboard port
+    #[arg(long, global = true, env = "DAILY_PROFILE")]
+    pub profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -33,6 +49,23 @@ pub enum Commands {
         /// Do not open browser automatically
         #[arg(long)]
         no_open: bool,
+
+        /// Path prefix to serve under, e.g. "/daily" behind a reverse proxy
+        /// (default: server.base_path)
+        #[arg(long)]
+        base_path: Option<String>,
+
+        /// PEM certificate chain to serve HTTPS with (default: server.tls_cert)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for --tls-cert (default: server.tls_key)
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Run as the `daily daemon` service, which also auto-digests
+        #[arg(long, hide = true)]
+        daemon: bool,
     },
 
     /// View archives (interactive date selection if no date specified)
@@ -41,6 +74,22 @@ pub enum Commands {
         #[arg(short, long)]
         date: Option<String>,
 
+        /// View the current week, Monday to today
+        #[arg(long, conflicts_with_all = ["date", "last", "from"])]
+        week: bool,
+
+        /// View the last N days, today included
+        #[arg(long, value_name = "DAYS", conflicts_with_all = ["date", "from"])]
+        last: Option<u32>,
+
+        /// First day of a range to view (format: yyyy-mm-dd)
+        #[arg(long, conflicts_with = "date")]
+        from: Option<String>,
+
+        /// Last day of the range (format: yyyy-mm-dd, default: today)
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+
         /// Show daily summary only
         #[arg(long)]
         summary_only: bool,
@@ -93,6 +142,51 @@ pub enum Commands {
         /// Job ID for tracking (internal use)
         #[arg(long)]
         job_id: Option<String>,
+
+        /// Archive under this local time instead of now (format: "yyyy-mm-dd HH:MM")
+        #[arg(long)]
+        archive_at: Option<String>,
+
+        /// Write an in-progress checkpoint that the final summary replaces (internal use)
+        #[arg(long)]
+        checkpoint: bool,
+    },
+
+    /// Summarize past transcripts that have no session archive yet
+    Backfill {
+        /// Only consider transcripts modified within the last N days
+        #[arg(short, long, default_value = "30")]
+        days: u64,
+
+        /// Maximum number of summarizations running at once
+        #[arg(short, long, default_value = "2")]
+        concurrency: usize,
+
+        /// List the transcripts that would be summarized without running anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Archive sessions from other AI coding tools' chat logs
+    Import {
+        /// Tool the logs come from: cursor, aider or codex
+        #[arg(short, long)]
+        source: String,
+
+        /// Log file, or a directory searched recursively
+        path: PathBuf,
+
+        /// Project directory for logs that don't record one
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// List the sessions found without summarizing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Summarize sessions again even if they were imported before
+        #[arg(long)]
+        force: bool,
     },
 
     /// Generate daily digest from sessions (consolidate sessions into daily.md)
@@ -110,8 +204,40 @@ pub enum Commands {
         background: bool,
 
         /// Force regenerate daily summary even without session files (re-process existing daily.md)
-        #[arg(short, long)]
+        #[arg(short, long, visible_alias = "regenerate")]
         force: bool,
+
+        /// Digest sections to keep verbatim when regenerating (comma-separated:
+        /// overview, key_work, insights, reflections, tomorrow_focus, skills).
+        /// Sections edited by hand are always kept.
+        #[arg(long, value_delimiter = ',')]
+        keep: Vec<String>,
+
+        /// Add a By Project section with one subsection per project worked
+        /// on (overrides summarization.digest_group_by)
+        #[arg(long, value_name = "GROUPING", value_parser = ["project"])]
+        group_by: Option<String>,
+
+        /// Review the digest before saving: view it in a pager, edit or
+        /// regenerate single sections, then save or discard
+        #[arg(short, long, conflicts_with_all = ["background", "job_id"])]
+        interactive: bool,
+
+        /// Digest every past date that has sessions but no digest yet
+        #[arg(long, conflicts_with_all = ["relative_date", "date", "force", "background", "interactive"])]
+        all_missing: bool,
+
+        /// With --all-missing, skip dates before this one (format: yyyy-mm-dd)
+        #[arg(long, requires = "all_missing")]
+        since: Option<String>,
+
+        /// With --all-missing, maximum number of digests running at once
+        #[arg(long, default_value = "1")]
+        concurrency: usize,
+
+        /// Job ID for tracking (internal use)
+        #[arg(long)]
+        job_id: Option<String>,
     },
 
     /// Extract skill from archive
@@ -155,12 +281,175 @@ pub enum Commands {
         delete: Option<String>,
     },
 
+    /// Pin a session so it stays at the top of lists (lists pinned sessions if no target)
+    Pin {
+        /// Session to pin (format: YYYY-MM-DD/session-name)
+        target: Option<String>,
+
+        /// Unpin the session instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Finalize a day so its digest is no longer regenerated, or list locked days
+    Lock {
+        /// Date to lock (format: YYYY-MM-DD)
+        date: Option<String>,
+
+        /// Unlock the date instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Run the dashboard as a service that starts at login (systemd,
+    /// launchd or a Windows scheduled task), with auto-digest
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
+    /// One-line archive status (today's sessions and cost, last digest,
+    /// running job) for Claude Code's statusLine setting
+    Statusline,
+
+    /// Append a timestamped note to a day's daily.md, for the digest to pick up
+    Note {
+        /// Note text, e.g. "Switched the auth design to JWT after team discussion"
+        text: String,
+
+        /// Day to add the note to (format: YYYY-MM-DD, default: today)
+        #[arg(short, long)]
+        date: Option<String>,
+    },
+
+    /// Give a session a new topic, renaming its file and the digest's references to it
+    Rename {
+        /// Session to rename (format: YYYY-MM-DD/session-name)
+        target: String,
+
+        /// New topic, e.g. "fix-login-flow"; the time and other parts of the name are kept
+        topic: String,
+    },
+
+    /// Record your own outcome and satisfaction for a session (preferred over inferred facets)
+    Rate {
+        /// Session to rate (format: YYYY-MM-DD/session-name)
+        target: String,
+
+        /// Outcome: achieved, partially_achieved, not_achieved
+        #[arg(long)]
+        outcome: Option<String>,
+
+        /// Satisfaction: happy, satisfied, neutral, dissatisfied, frustrated
+        #[arg(long)]
+        satisfaction: Option<String>,
+
+        /// Free-text note about the session
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Remove the existing rating before applying the other options
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Give a session a 1-5 star rating and comment; counts as its satisfaction in insights
+    Feedback {
+        /// Session to rate (format: YYYY-MM-DD/session-name)
+        target: String,
+
+        /// Rating from 1 (frustrated) to 5 (happy)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=5))]
+        stars: u8,
+
+        /// What went well or badly
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
+    /// Answer a question from past sessions and digests, citing them
+    Ask {
+        /// Question, e.g. "what approach did I use for the websocket reconnect bug?"
+        question: String,
+
+        /// Maximum number of sessions and digests to pass to the model
+        #[arg(short = 'n', long, default_value = "5")]
+        limit: usize,
+    },
+
+    /// Find earlier sessions on the same topic (requires embeddings.enabled)
+    Related {
+        /// Session to match (format: YYYY-MM-DD/session-name)
+        target: Option<String>,
+
+        /// Maximum number of related sessions to show
+        #[arg(short = 'n', long, default_value = "5")]
+        limit: usize,
+
+        /// Embed new or changed sessions before searching
+        #[arg(long)]
+        reindex: bool,
+    },
+
+    /// Show archive statistics: days, sessions, size on disk, pending skills
+    Stats,
+
+    /// Check the install and show the last hook invocations and their failures
+    Doctor,
+
+    /// Compress or delete old transcripts and sessions per storage.retention
+    Prune {
+        /// Show what would be pruned without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Upgrade session archives and digests written in an older format
+    Migrate {
+        /// List the files that would change without rewriting them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Sync the archive with the remote storage.backend (S3 or WebDAV)
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    /// Move archived date folders to a new archive.directory_layout
+    MigrateLayout {
+        /// Layout the archives currently use (default: the configured layout)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Layout to move to, saved to the config afterwards (e.g. "{YYYY}/{MM}/{DD}")
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Show what would move without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Turn "Tomorrow's Focus" items into tracked work
+    Focus {
+        #[command(subcommand)]
+        action: FocusAction,
+    },
+
     /// Manage background jobs
     Jobs {
         #[command(subcommand)]
         action: JobsAction,
     },
 
+    /// Summarizations queued while Claude was unreachable (no network or login)
+    Queue {
+        #[command(subcommand)]
+        action: Option<QueueAction>,
+    },
+
     /// Initialize configuration (interactive by default)
     Init {
         /// Storage path (default: ~/.claude/daily)
@@ -178,6 +467,9 @@ pub enum Commands {
 
     /// Show or update configuration
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+
         /// Set storage path
         #[arg(long)]
         set_storage: Option<PathBuf>,
@@ -200,16 +492,24 @@ pub enum Commands {
 
     /// Uninstall plugin from Claude Code (removes hooks and commands only, keeps archive data)
     Uninstall {
-        /// Scope: user or project
+        /// Scope: user, project, or all
         #[arg(short, long, default_value = "user")]
         scope: String,
+
+        /// Show what would be removed without removing
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Remove hooks only (disable automatic summarization, keep commands)
     UninstallHooks {
-        /// Scope: user or project
+        /// Scope: user, project, or all
         #[arg(short, long, default_value = "user")]
         scope: String,
+
+        /// Show what would be removed without removing
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Re-install hooks only (re-enable automatic summarization)
@@ -233,11 +533,137 @@ pub enum Commands {
         version: Option<String>,
     },
 
+    /// Claude Code token usage from session transcripts
+    Usage {
+        #[command(subcommand)]
+        action: Option<UsageAction>,
+
+        /// Group by project directory instead of by day
+        #[arg(long)]
+        by_project: bool,
+
+        /// Only sessions started in the last N days
+        #[arg(short, long)]
+        days: Option<i64>,
+    },
+
+    /// Track goals across days; digests report progress on the active ones
+    Goal {
+        #[command(subcommand)]
+        action: Option<GoalAction>,
+    },
+
+    /// Capture TODOs; digests carry open ones into Tomorrow's Focus and
+    /// check off those the day's sessions finished
+    Todo {
+        #[command(subcommand)]
+        action: Option<TodoAction>,
+    },
+
+    /// Search the key decisions recorded across all sessions
+    Decisions {
+        /// Words that must all appear in the decision, its session title or project
+        query: Option<String>,
+
+        /// Only decisions from projects whose directory contains this text
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Maximum number of decisions to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// Rewrite decisions.md from the whole archive
+        #[arg(long)]
+        write: bool,
+    },
+
+    /// Browse and refresh the topic index (sessions from all dates grouped by subject)
+    Topics {
+        #[command(subcommand)]
+        action: Option<TopicsAction>,
+    },
+
+    /// Try out prompt templates before putting them in the config
+    Templates {
+        #[command(subcommand)]
+        action: TemplatesAction,
+    },
+
+    /// Inspect or update the model pricing data used for cost estimates
+    Pricing {
+        #[command(subcommand)]
+        action: PricingAction,
+    },
+
+    /// Export usage or insights data as CSV or JSON tables
+    Export {
+        #[command(subcommand)]
+        what: ExportAction,
+    },
+
+    /// Render the whole archive (digests, sessions, topics, insights) as a
+    /// static HTML site
+    Publish {
+        /// Output directory
+        #[arg(short, long, default_value = "./site")]
+        out: PathBuf,
+
+        /// light or dark (default: output.html_theme)
+        #[arg(long)]
+        theme: Option<String>,
+    },
+
+    /// Render a date's digest as one self-contained HTML page with secrets
+    /// redacted, or upload it (share.upload), and print its link
+    Share {
+        /// Date (YYYY-MM-DD, default: today)
+        date: Option<String>,
+
+        /// Output file (default: daily-<date>.html, unless uploading)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        /// Upload instead of writing a file: gist or paste (default: share.upload)
+        #[arg(long, value_parser = ["gist", "paste"])]
+        upload: Option<String>,
+
+        /// light or dark (default: output.html_theme)
+        #[arg(long)]
+        theme: Option<String>,
+    },
+
     /// Generate insights and trend analysis from archives
     Insights {
         /// Number of days to analyze (default: 30)
         #[arg(short, long, default_value = "30")]
         days: usize,
+
+        /// Include tool usage analytics parsed from session transcripts
+        #[arg(long)]
+        tools: bool,
+    },
+
+    /// Compare the last seven days' cost, friction and success rates with
+    /// the weeks before and report anomalies
+    Anomalies {
+        /// Send the insights.anomaly webhook when anomalies are found
+        #[arg(long)]
+        notify: bool,
+
+        /// Run in background (default: foreground)
+        #[arg(long)]
+        background: bool,
+
+        /// Job ID for tracking (internal use)
+        #[arg(long)]
+        job_id: Option<String>,
+    },
+
+    /// Snapshot the storage directory, list snapshots or restore one
+    Backup {
+        #[command(subcommand)]
+        action: Option<BackupAction>,
     },
 
     /// Handle Claude Code hooks (internal use)
@@ -247,6 +673,313 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command can print `--json` output; the rest refuse the
+    /// flag rather than quietly printing text
+    pub fn supports_json(&self) -> bool {
+        match self {
+            Commands::Daemon { action } => matches!(action, DaemonAction::Status),
+            Commands::Jobs { action } => matches!(
+                action,
+                JobsAction::List { .. }
+                    | JobsAction::Killall { .. }
+                    | JobsAction::Reap { .. }
+                    | JobsAction::Prune { .. }
+            ),
+            Commands::View { .. }
+            | Commands::Today { .. }
+            | Commands::Yest { .. }
+            | Commands::Pin { .. }
+            | Commands::Lock { .. }
+            | Commands::Statusline
+            | Commands::Note { .. }
+            | Commands::Rename { .. }
+            | Commands::Rate { .. }
+            | Commands::Feedback { .. }
+            | Commands::Ask { .. }
+            | Commands::Related { .. }
+            | Commands::Stats
+            | Commands::Doctor
+            | Commands::Prune { .. }
+            | Commands::Migrate { .. }
+            | Commands::Storage { .. }
+            | Commands::Config { .. }
+            | Commands::Focus { .. }
+            | Commands::Queue { .. }
+            | Commands::Usage { .. }
+            | Commands::Goal { .. }
+            | Commands::Todo { .. }
+            | Commands::Decisions { .. }
+            | Commands::Topics { .. }
+            | Commands::Templates { .. }
+            | Commands::Pricing { .. }
+            | Commands::Publish { .. }
+            | Commands::Share { .. }
+            | Commands::Insights { .. }
+            | Commands::Anomalies { .. }
+            | Commands::Backup { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum UsageAction {
+    /// Usage grouped into 5-hour billing blocks
+    Blocks {
+        /// Show only the active block
+        #[arg(long)]
+        active: bool,
+
+        /// Only blocks started in the last N days
+        #[arg(long, value_name = "DAYS")]
+        recent: Option<i64>,
+
+        /// Token limit per block: a number, or "max" for the largest past block
+        /// (default: usage.block_token_limit)
+        #[arg(short, long, value_name = "N|max")]
+        token_limit: Option<String>,
+
+        /// Send the usage.block_near_limit webhook when the active block
+        /// reaches usage.block_warn_percent of the limit (once per block)
+        #[arg(long)]
+        notify: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageAction {
+    /// Show the backend and the number of changes waiting to be pushed
+    Status,
+
+    /// Push pending changes to the backend now
+    Push {
+        /// Upload every archive file, e.g. after switching an existing
+        /// archive to a remote backend
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Download archive files from the backend that are missing locally
+    Pull,
+}
+
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// Take a snapshot now
+    Now,
+
+    /// List snapshots, newest first (default)
+    List,
+
+    /// Replace the storage directory with a snapshot; the current one is
+    /// moved aside, not deleted
+    Restore {
+        /// Snapshot timestamp from `daily backup list`, or "latest"
+        timestamp: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GoalAction {
+    /// Add a goal, e.g. `daily goal add "ship billing MVP"`
+    Add { title: String },
+
+    /// List goals with the progress digests reported (default)
+    List {
+        /// Include goals marked done
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Mark a goal done
+    Done { id: u32 },
+
+    /// Mark a done goal active again
+    Reopen { id: u32 },
+
+    /// Delete a goal and its recorded progress
+    Remove { id: u32 },
+}
+
+#[derive(Subcommand)]
+pub enum TodoAction {
+    /// Capture a TODO, e.g. `daily todo add "write release notes"`
+    Add { text: String },
+
+    /// List open TODOs (default)
+    List {
+        /// Include TODOs already done
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Mark a TODO done
+    Done { id: u32 },
+}
+
+#[derive(Subcommand)]
+pub enum TopicsAction {
+    /// List topics with their session counts (default)
+    List,
+
+    /// File new sessions under topics and rewrite the topic pages
+    Update {
+        /// Drop existing assignments and refile every session
+        #[arg(long)]
+        rebuild: bool,
+
+        /// Run in background (default: foreground)
+        #[arg(long)]
+        background: bool,
+
+        /// Job ID for tracking (internal use)
+        #[arg(long)]
+        job_id: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplatesAction {
+    /// Re-summarize recent sessions with a candidate session summary template
+    /// and write current and candidate archives side by side (archives are
+    /// not changed)
+    Test {
+        /// Candidate template file
+        #[arg(long)]
+        template: PathBuf,
+
+        /// Number of recent sessions to re-summarize
+        #[arg(long, default_value = "3")]
+        sessions: usize,
+
+        /// Folder for the outputs (default: a new folder under the system temp dir)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PricingAction {
+    /// Fetch the latest LiteLLM pricing dataset and update the cache
+    Refresh,
+
+    /// Show when pricing was last fetched and, for a model, the rates applied
+    Show {
+        /// Model name as it appears in transcripts, e.g. claude-sonnet-4-5
+        model: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportAction {
+    /// Claude Code token usage, one row per session (or per day)
+    Usage {
+        /// Output format: csv or json
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+
+        /// First date to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last date to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// One row per: session or day
+        #[arg(long, default_value = "session")]
+        per: String,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Archive insights (goals, outcomes, friction, cost), one row per
+    /// session (or per day)
+    Insights {
+        /// Output format: csv or json
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+
+        /// First date to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last date to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Number of most recent archive days when no --from/--to is given
+        #[arg(short, long, default_value = "30")]
+        days: usize,
+
+        /// One row per: session or day
+        #[arg(long, default_value = "session")]
+        per: String,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// A date's digest as a standalone HTML page with highlighted code
+    Digest {
+        /// Date (YYYY-MM-DD, default: today)
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// light or dark (default: output.html_theme)
+        #[arg(long)]
+        theme: Option<String>,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Flashcards from the learnings of session archives, for Anki or
+    /// other spaced-repetition tools
+    Anki {
+        /// Output format: anki (tab-separated, for File > Import) or json
+        #[arg(short, long, default_value = "anki")]
+        format: String,
+
+        /// First date to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last date to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonAction {
+    /// Register and start the service for the active profile
+    Install {
+        /// Port for the dashboard (default: server.port or 31456)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Host to bind to (default: 127.0.0.1)
+        #[arg(long)]
+        host: Option<String>,
+    },
+
+    /// Stop the service and remove it
+    Uninstall,
+
+    /// Show whether the service is installed and running
+    Status,
+}
+
 #[derive(Subcommand)]
 pub enum JobsAction {
     /// List background jobs
@@ -276,6 +1009,31 @@ pub enum JobsAction {
         job_id: String,
     },
 
+    /// Kill all queued and running jobs
+    Killall {
+        /// Only jobs of this type, e.g. auto_summarize or digest
+        #[arg(long = "type")]
+        job_type: Option<String>,
+    },
+
+    /// Find job and claude processes left running without a job, and kill them
+    Reap {
+        /// List the processes without killing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Live job table: enter tails the selected job's log, x kills it
+    Watch {
+        /// Include completed and failed jobs
+        #[arg(short, long)]
+        all: bool,
+
+        /// Seconds between refreshes
+        #[arg(short, long, default_value = "1")]
+        interval: u64,
+    },
+
     /// Cleanup old jobs
     Cleanup {
         /// Keep jobs from last N days (default: 7)
@@ -286,6 +1044,66 @@ pub enum JobsAction {
         #[arg(long)]
         dry_run: bool,
     },
+
+    /// Remove finished jobs past the jobs.retention limits (age, count, total size)
+    Prune {
+        /// Show what would be removed without removing
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QueueAction {
+    /// List queued summarizations (default)
+    List,
+
+    /// Retry every queued summarization now and wait for the results
+    Flush,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Show the effective configuration
+    Show {
+        /// Print every value with the layer it came from (default, user
+        /// config, project .daily.toml, or DAILY_* environment variable)
+        #[arg(long)]
+        origin: bool,
+    },
+
+    /// Check the config file for unknown keys, bad time formats and a
+    /// missing storage folder (exits non-zero on errors)
+    Validate,
+
+    /// Upgrade an older config file to the current config_version
+    Migrate {
+        /// Show the steps without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FocusAction {
+    /// Create or update labeled GitHub issues from a digest's Tomorrow's Focus
+    PushGithub {
+        /// Repository as owner/name (default: integrations.github.repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Digest date (format: yyyy-mm-dd, default: latest digest)
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// Issue label (default: integrations.github.label)
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Show what would change without calling the API for writes
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -295,4 +1113,30 @@ pub enum HookType {
 
     /// SessionEnd hook handler
     SessionEnd,
+
+    /// PreCompact hook handler
+    PreCompact,
+
+    /// Stop hook handler
+    Stop,
+
+    /// UserPromptSubmit hook handler
+    UserPromptSubmit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_json() {
+        let command = |args: &[&str]| Cli::try_parse_from(args).unwrap().command;
+
+        assert!(command(&["daily", "view", "--json"]).supports_json());
+        assert!(command(&["daily", "jobs", "list", "--json"]).supports_json());
+        assert!(command(&["daily", "daemon", "status"]).supports_json());
+        assert!(!command(&["daily", "digest", "--json"]).supports_json());
+        assert!(!command(&["daily", "jobs", "log", "abc"]).supports_json());
+        assert!(!command(&["daily", "daemon", "uninstall"]).supports_json());
+    }
 }
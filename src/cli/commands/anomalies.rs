@@ -0,0 +1,134 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::{load_config, Config};
+use crate::insights::anomalies::{self, AnomalyReport, MetricChange};
+use crate::jobs::{self, JobCommand, JobManager, JobType};
+use crate::notifications::{notify, WebhookEvent};
+use crate::usage::pricing::PricingData;
+
+/// Compare the last seven days with the baseline weeks, in the foreground or
+/// as an `Anomalies` job. With `send`, anomalies go out as the
+/// `insights.anomaly` webhook.
+pub async fn run(send: bool, background: bool, job_id: Option<String>, json: bool) -> Result<()> {
+    let config = load_config()?;
+
+    if background {
+        let job_id = spawn_anomalies_job(&config, send)?;
+        if json {
+            return print_json(&serde_json::json!({ "job_id": job_id }));
+        }
+        println!("{} Anomaly check started: {}", "✓".green(), job_id);
+        return Ok(());
+    }
+
+    let Some(job_id) = job_id else {
+        let report = check(&config, send).await?;
+        if json {
+            return print_json(&report);
+        }
+        print_report(&report);
+        return Ok(());
+    };
+
+    // Running as a tracked job: wait for a queue slot and record the outcome
+    let job_manager = JobManager::new(&config)?;
+    if let Err(e) = job_manager
+        .wait_for_slot(&job_id, config.jobs.max_concurrent)
+        .await
+    {
+        eprintln!("[daily] Warning: Job queue unavailable, running now: {}", e);
+    }
+
+    let result = check(&config, send).await;
+    if let Ok(report) = &result {
+        for metric in &report.metrics {
+            eprintln!("[daily] {}", metric.message);
+        }
+    }
+    let update = match &result {
+        Ok(_) => job_manager.mark_completed(&job_id),
+        Err(e) => job_manager.mark_error(&job_id, e),
+    };
+    if let Err(e) = update {
+        eprintln!("[daily] Warning: Failed to update job status: {}", e);
+    }
+    let _ = job_manager.truncate_log_if_needed(&job_id);
+
+    result.map(|_| ())
+}
+
+/// Detect anomalies and, with `send`, deliver them and start the next week
+async fn check(config: &Config, send: bool) -> Result<AnomalyReport> {
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let today = Local::now().date_naive();
+    let report = anomalies::detect(config, &pricing, today);
+
+    if send {
+        let found: Vec<&MetricChange> = report.anomalies().collect();
+        if !found.is_empty() {
+            notify(
+                config,
+                WebhookEvent::InsightsAnomaly,
+                serde_json::json!({
+                    "from": report.from,
+                    "to": report.to,
+                    "summary": found.iter().map(|m| m.message.as_str()).collect::<Vec<_>>().join("; "),
+                    "anomalies": found,
+                }),
+            )
+            .await;
+        }
+        anomalies::mark_checked(config, today)?;
+    }
+    Ok(report)
+}
+
+fn print_report(report: &AnomalyReport) {
+    println!(
+        "\n  {} {} to {}, vs the {} weeks before",
+        "This week:".bold(),
+        report.from,
+        report.to,
+        report.baseline_weeks
+    );
+    if report.metrics.is_empty() {
+        println!("    {}", "No usage or rated sessions to compare".dimmed());
+    }
+    for metric in &report.metrics {
+        if metric.anomalous {
+            println!("    {} {}", "!".yellow(), metric.message);
+        } else {
+            println!("    {} {}", "✓".green(), metric.message.dimmed());
+        }
+    }
+    println!();
+}
+
+/// Spawn a detached `daily anomalies --job-id` process tracked as an
+/// `Anomalies` job; fails if one is already queued or running
+pub(crate) fn spawn_anomalies_job(config: &Config, send: bool) -> Result<String> {
+    let job_manager = JobManager::new(config)?;
+    if let Some(job) = job_manager.active_job_of_type(JobType::Anomalies)? {
+        anyhow::bail!(
+            "An anomaly check is already {} as job {}",
+            job.status.to_string().to_lowercase(),
+            job.id
+        );
+    }
+    let mut command = JobCommand::new(
+        JobType::Anomalies,
+        "anomalies",
+        config.storage_path(),
+        ["anomalies"],
+    );
+    if send {
+        command = command.arg("--notify");
+    }
+    let (job_id, _) = jobs::spawn(config, command)?;
+    Ok(job_id)
+}
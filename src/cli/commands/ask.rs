@@ -0,0 +1,38 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::ask;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Answer `question` from the archive and list the sources it cites
+pub async fn run(question: String, limit: usize, json: bool) -> Result<()> {
+    let config = load_config()?;
+    if !json {
+        println!("{} Searching archives...", "→".cyan());
+    }
+
+    let answer = ask::ask(&config, &question, limit).await?;
+    if json {
+        return print_json(&answer);
+    }
+
+    println!();
+    println!("{}", answer.answer);
+    if answer.sources.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "Sources:".cyan().bold());
+    for (i, source) in answer.sources.iter().enumerate() {
+        println!(
+            "  [{}] {}  {}",
+            i + 1,
+            source.reference(),
+            source.title.dimmed()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use std::process::{Child, ExitStatus};
+use tokio::task::JoinSet;
+
+use crate::auto_summarize::{find_backfill_transcripts, UnsummarizedTranscript};
+use crate::config::{load_config, Config};
+use crate::jobs::{self, JobCommand, JobManager, JobStatus, JobType};
+
+/// Summarize past transcripts that have no session archive yet,
+/// running at most `concurrency` background jobs at a time
+pub async fn run(days: u64, concurrency: usize, dry_run: bool) -> Result<()> {
+    let config = load_config()?;
+    let concurrency = concurrency.max(1);
+
+    println!(
+        "{} Scanning transcripts from the last {} days...",
+        "→".cyan(),
+        days
+    );
+    let transcripts = find_backfill_transcripts(&config, days)?;
+
+    if transcripts.is_empty() {
+        println!("{} Nothing to backfill", "✓".green());
+        return Ok(());
+    }
+
+    println!(
+        "\n{} {} unsummarized transcript(s):",
+        "Found".bold(),
+        transcripts.len().to_string().bright_yellow()
+    );
+    for transcript in &transcripts {
+        println!(
+            "  {} {} {}",
+            archive_time(transcript).dimmed(),
+            transcript.session_id,
+            transcript
+                .cwd
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+                .dimmed()
+        );
+    }
+
+    if dry_run {
+        println!("\n{} Dry run, no jobs started", "ℹ".blue());
+        return Ok(());
+    }
+
+    println!(
+        "\n{} Summarizing with concurrency {}...\n",
+        "→".cyan(),
+        concurrency
+    );
+
+    let job_manager = JobManager::new(&config)?;
+    let total = transcripts.len();
+    let mut queue = transcripts.into_iter();
+    let mut running = JoinSet::new();
+    let mut finished = 0;
+    let mut failed = 0;
+
+    loop {
+        // Top up the pool
+        while running.len() < concurrency {
+            let Some(transcript) = queue.next() else {
+                break;
+            };
+            match spawn_job(&config, &transcript) {
+                Ok((job_id, mut child)) => {
+                    running.spawn_blocking(move || {
+                        let exit = child.wait();
+                        (transcript, job_id, exit)
+                    });
+                }
+                Err(e) => {
+                    finished += 1;
+                    failed += 1;
+                    println!(
+                        "  [{}/{}] {} {} {}",
+                        finished,
+                        total,
+                        "✗".red(),
+                        transcript.session_id,
+                        e.to_string().red()
+                    );
+                }
+            }
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (transcript, job_id, exit) = joined.context("Backfill worker panicked")?;
+        finished += 1;
+
+        let outcome = job_outcome(&job_manager, &job_id, exit);
+
+        match outcome {
+            Ok(()) => println!(
+                "  [{}/{}] {} {} {}",
+                finished,
+                total,
+                "✓".green(),
+                transcript.session_id,
+                archive_time(&transcript).dimmed()
+            ),
+            Err(error) => {
+                failed += 1;
+                println!(
+                    "  [{}/{}] {} {} {} (daily jobs log {})",
+                    finished,
+                    total,
+                    "✗".red(),
+                    transcript.session_id,
+                    error.red(),
+                    job_id
+                );
+            }
+        }
+    }
+
+    println!(
+        "\n{} Backfill finished: {} archived, {} failed",
+        if failed == 0 {
+            "✓".green()
+        } else {
+            "!".yellow()
+        },
+        (total - failed).to_string().green(),
+        failed.to_string().red()
+    );
+
+    Ok(())
+}
+
+/// Outcome of a finished job process, as recorded by the child or, failing
+/// that, its exit status
+pub(crate) fn job_outcome(
+    job_manager: &JobManager,
+    job_id: &str,
+    exit: std::io::Result<ExitStatus>,
+) -> std::result::Result<(), String> {
+    // The child marks its own job; a job still "Running" means it exited before
+    // it could update the record (e.g. it finished before registration, or crashed)
+    match job_manager.load_job(job_id).map(|j| j.status) {
+        Ok(JobStatus::Completed) => Ok(()),
+        Ok(JobStatus::Failed { error }) => Err(error),
+        _ if exit.as_ref().is_ok_and(|status| status.success()) => {
+            let _ = job_manager.mark_completed(job_id);
+            Ok(())
+        }
+        _ => {
+            let error = match exit {
+                Ok(status) => format!("process exited with {}", status),
+                Err(e) => e.to_string(),
+            };
+            let _ = job_manager.mark_failed(job_id, &error);
+            Err(error)
+        }
+    }
+}
+
+/// Spawn a foreground summarize process for one transcript and register it as a job
+fn spawn_job(config: &Config, transcript: &UnsummarizedTranscript) -> Result<(String, Child)> {
+    let short_id: String = transcript.session_id.chars().take(8).collect();
+    let task_name = format!("backfill-{}", short_id);
+    let cwd_str = transcript
+        .cwd
+        .as_ref()
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| ".".to_string());
+
+    let command = JobCommand::new(
+        JobType::Backfill,
+        &task_name,
+        &transcript.path,
+        [
+            "summarize".to_string(),
+            "--transcript".to_string(),
+            transcript.path.to_string_lossy().into_owned(),
+            "--task-name".to_string(),
+            task_name.clone(),
+            "--cwd".to_string(),
+            cwd_str,
+            "--archive-at".to_string(),
+            archive_time(transcript),
+            "--foreground".to_string(),
+        ],
+    )
+    .route_by_cwd();
+    jobs::spawn(config, command)
+}
+
+/// Local time of the transcript's last modification, used as the archive date/time
+fn archive_time(transcript: &UnsummarizedTranscript) -> String {
+    let modified = std::fs::metadata(&transcript.path)
+        .and_then(|m| m.modified())
+        .map(DateTime::<Local>::from)
+        .unwrap_or_else(|_| Local::now());
+    modified.format("%Y-%m-%d %H:%M").to_string()
+}
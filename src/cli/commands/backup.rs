@@ -0,0 +1,82 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::retention::format_bytes;
+use crate::backup;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Snapshot the storage directory now
+pub async fn now(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let snapshot = backup::create(&config)?;
+    if json {
+        return print_json(&snapshot);
+    }
+    println!(
+        "{} Snapshot {} written to {} ({})",
+        "✓".green(),
+        snapshot.timestamp,
+        snapshot.path.display(),
+        format_bytes(snapshot.bytes)
+    );
+    Ok(())
+}
+
+/// List snapshots, newest first
+pub async fn list(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let snapshots = backup::list(&config)?;
+    if json {
+        return print_json(&snapshots);
+    }
+
+    let dir = backup::backup_dir(&config);
+    if snapshots.is_empty() {
+        println!("No snapshots in {}", dir.display());
+        println!("  {} Take one with `daily backup now`", "→".cyan());
+        return Ok(());
+    }
+    println!("{} {}", "Snapshots in".bold(), dir.display());
+    for snapshot in &snapshots {
+        println!(
+            "  {}  {}",
+            snapshot.timestamp.cyan(),
+            format_bytes(snapshot.bytes).dimmed()
+        );
+    }
+    if config.backup.schedule == "off" {
+        println!(
+            "\n  {} Set backup.schedule to take snapshots while `daily show` runs",
+            "→".cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Swap the storage directory for a snapshot, keeping the current one aside
+pub async fn restore(timestamp: &str, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let previous = backup::restore(&config, timestamp)?;
+    if json {
+        return print_json(&serde_json::json!({
+            "restored": timestamp,
+            "storage": config.storage_path(),
+            "previous": previous,
+        }));
+    }
+    println!(
+        "{} Restored {} into {}",
+        "✓".green(),
+        timestamp,
+        config.storage_path().display()
+    );
+    if let Some(previous) = previous {
+        println!(
+            "  {} Previous storage moved to {}",
+            "!".yellow(),
+            previous.display()
+        );
+    }
+    Ok(())
+}
@@ -1,11 +1,23 @@
 use anyhow::{Context, Result};
+use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use std::path::PathBuf;
 
-use crate::config::{get_config_path, load_config, save_config};
+use crate::cli::output::print_json;
+use crate::config::validate::Severity;
+use crate::config::{
+    active_profile, get_config_path, load_config, load_layered, migrate_config, save_config,
+    validate_config,
+};
+use crate::server::handlers::build_config_dto;
 
 /// Show or update configuration
-pub async fn run(set_storage: Option<PathBuf>, show: bool, interactive: bool) -> Result<()> {
+pub async fn run(
+    set_storage: Option<PathBuf>,
+    show: bool,
+    interactive: bool,
+    json: bool,
+) -> Result<()> {
     let mut config = load_config()?;
 
     // Interactive mode
@@ -22,46 +34,186 @@ pub async fn run(set_storage: Option<PathBuf>, show: bool, interactive: bool) ->
     }
 
     // Show current config
+    if json {
+        return print_json(&build_config_dto(&config));
+    }
+
     if show || set_storage.is_none() {
-        let config_path = get_config_path()?;
-        println!("[daily] Configuration file: {}", config_path.display());
-        println!();
-        println!("Current settings:");
-        println!("  Storage path: {}", config.storage.path.display());
-        println!("  Summarization model: {}", config.summarization.model);
-        println!(
-            "  Summary language: {} ({})",
-            config.summarization.summary_language,
-            if config.summarization.summary_language == "zh" {
-                "Chinese"
+        print_settings(&config)?;
+    }
+
+    Ok(())
+}
+
+/// `daily config show`: the effective settings, or with `origin` every
+/// value and the layer it came from
+pub fn show(origin: bool, json: bool) -> Result<()> {
+    let layered = load_layered()?;
+    if !origin {
+        if json {
+            return print_json(&build_config_dto(&layered.config));
+        }
+        return print_settings(&layered.config);
+    }
+
+    if json {
+        let values: serde_json::Map<String, serde_json::Value> = layered
+            .values
+            .iter()
+            .map(|(key, (value, origin))| {
+                (
+                    key.clone(),
+                    serde_json::json!({ "value": value, "origin": origin.to_string() }),
+                )
+            })
+            .collect();
+        return print_json(&values);
+    }
+
+    let width = layered.values.keys().map(String::len).max().unwrap_or(0);
+    for (key, (value, origin)) in &layered.values {
+        let value = value.to_string();
+        let origin = origin.to_string();
+        let origin = if origin == "default" {
+            origin.dimmed()
+        } else {
+            origin.cyan()
+        };
+        println!("{:width$} = {}  {}", key, value, origin, width = width);
+    }
+    Ok(())
+}
+
+/// `daily config validate`: report problems in the config file, failing
+/// if any of them is an error
+pub fn validate(json: bool) -> Result<()> {
+    let (path, issues) = validate_config()?;
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+
+    if json {
+        print_json(&serde_json::json!({ "path": path, "issues": issues }))?;
+    } else if issues.is_empty() {
+        println!("[daily] {} is valid", path.display());
+    } else {
+        println!("[daily] {}", path.display());
+        for issue in &issues {
+            let label = match issue.severity {
+                Severity::Error => "error".red().bold(),
+                Severity::Warning => "warning".yellow().bold(),
+            };
+            let key = if issue.key.is_empty() {
+                String::new()
             } else {
-                "English"
-            }
-        );
-        println!(
-            "  Enable daily summary: {}",
-            config.summarization.enable_daily_summary
-        );
+                format!("{}: ", issue.key.cyan())
+            };
+            println!("  {} {}{}", label, key, issue.message);
+        }
+    }
+
+    if errors > 0 {
+        anyhow::bail!("Configuration has {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// `daily config migrate`: upgrade the config file to the current version
+pub fn migrate(dry_run: bool, json: bool) -> Result<()> {
+    let (path, migration) = migrate_config(dry_run)?;
+    if json {
+        return print_json(&serde_json::json!({
+            "path": path,
+            "from": migration.from,
+            "to": migration.to,
+            "steps": migration.steps,
+            "dry_run": dry_run,
+        }));
+    }
+
+    if migration.from == migration.to {
         println!(
-            "  Enable extraction hints: {}",
-            config.summarization.enable_extraction_hints
+            "[daily] {} is already at config_version {}",
+            path.display(),
+            migration.to
         );
-        println!("  SessionStart hook: {}", config.hooks.enable_session_start);
-        println!("  SessionEnd hook: {}", config.hooks.enable_session_end);
-        println!("  Background timeout: {}s", config.hooks.background_timeout);
-        println!();
-        println!("Archive settings:");
+        return Ok(());
+    }
+    println!(
+        "[daily] {}migrating {} from config_version {} to {}",
+        if dry_run { "(dry run) " } else { "" },
+        path.display(),
+        migration.from,
+        migration.to
+    );
+    for step in &migration.steps {
+        println!("  - {}", step);
+    }
+    if !dry_run {
         println!(
-            "  Author: {}",
-            config.archive.author.as_deref().unwrap_or("(not set)")
+            "[daily] Previous file kept as {}",
+            path.with_extension("toml.bak").display()
         );
-        println!("  Tags: {}", config.archive.tags.join(", "));
-        println!("  Include cwd: {}", config.archive.include_cwd);
-        println!("  Include git info: {}", config.archive.include_git_info);
-        println!();
-        println!("Tip: Use 'daily config -i' for interactive configuration");
     }
+    Ok(())
+}
 
+fn print_settings(config: &crate::config::Config) -> Result<()> {
+    let config_path = get_config_path()?;
+    println!("[daily] Configuration file: {}", config_path.display());
+    if let Some(profile) = active_profile() {
+        println!("[daily] Profile: {}", profile);
+    }
+    println!();
+    println!("Current settings:");
+    println!("  Storage path: {}", config.storage.path.display());
+    println!("  Summarization model: {}", config.summarization.model);
+    println!(
+        "  Summary language: {} ({})",
+        config.summarization.summary_language,
+        if config.summarization.summary_language == "zh" {
+            "Chinese"
+        } else {
+            "English"
+        }
+    );
+    println!(
+        "  Enable daily summary: {}",
+        config.summarization.enable_daily_summary
+    );
+    println!(
+        "  Enable extraction hints: {}",
+        config.summarization.enable_extraction_hints
+    );
+    println!("  SessionStart hook: {}", config.hooks.enable_session_start);
+    println!("  SessionEnd hook: {}", config.hooks.enable_session_end);
+    println!("  Background timeout: {}s", config.hooks.background_timeout);
+    println!();
+    println!("Archive settings:");
+    println!(
+        "  Author: {}",
+        config.archive.author.as_deref().unwrap_or("(not set)")
+    );
+    println!("  Tags: {}", config.archive.tags.join(", "));
+    println!("  Include cwd: {}", config.archive.include_cwd);
+    println!("  Include git info: {}", config.archive.include_git_info);
+    println!(
+        "  Session name template: {}",
+        config.archive.session_name_template
+    );
+    println!("  Directory layout: {}", config.archive.directory_layout);
+    println!(
+        "  Copy transcript: {}{}",
+        config.archive.copy_transcript,
+        if config.archive.compress_transcript {
+            " (zstd)"
+        } else {
+            ""
+        }
+    );
+    println!();
+    println!("Tip: Use 'daily config -i' for interactive configuration");
     Ok(())
 }
 
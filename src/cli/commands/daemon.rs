@@ -0,0 +1,68 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::daemon::Service;
+
+/// Register the dashboard with the service manager and start it
+pub async fn install(port: Option<u16>, host: Option<String>) -> Result<()> {
+    let config = load_config()?;
+    let service = Service::new(&config, port, host)?;
+    service.install()?;
+    let status = service.status();
+    println!(
+        "{} Installed {} {}",
+        "✓".green(),
+        service.platform,
+        service.name.cyan()
+    );
+    println!("  {} {}", "Definition:".dimmed(), status.location);
+    println!("  {} {}", "Log:".dimmed(), status.log.display());
+    if !status.running {
+        println!(
+            "{} Not running yet; check the log or `daily daemon status`",
+            "!".yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Stop the service and remove its registration
+pub async fn uninstall() -> Result<()> {
+    let config = load_config()?;
+    let service = Service::new(&config, None, None)?;
+    if !service.status().installed {
+        println!("{} {} is not installed", "!".yellow(), service.name);
+        return Ok(());
+    }
+    service.uninstall()?;
+    println!(
+        "{} Removed {} {}",
+        "✓".green(),
+        service.platform,
+        service.name
+    );
+    Ok(())
+}
+
+pub async fn status(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let status = Service::new(&config, None, None)?.status();
+    if json {
+        return print_json(&status);
+    }
+    let state = match (status.installed, status.running) {
+        (true, true) => "running".green(),
+        (true, false) => "installed, not running".yellow(),
+        (false, _) => "not installed".dimmed(),
+    };
+    println!("{} ({}): {}", status.name.bold(), status.platform, state);
+    if status.installed {
+        println!("  {} {}", "Definition:".dimmed(), status.location);
+        println!("  {} {}", "Log:".dimmed(), status.log.display());
+    } else {
+        println!("  {}", "Install with `daily daemon install`".dimmed());
+    }
+    Ok(())
+}
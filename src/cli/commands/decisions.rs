@@ -0,0 +1,55 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::ArchiveManager;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::decisions;
+
+/// List decisions matching a query, or rewrite the decisions.md ledger
+pub async fn run(
+    query: Option<String>,
+    project: Option<String>,
+    limit: usize,
+    write: bool,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config);
+
+    if write {
+        let path = decisions::write_ledger(&manager)?;
+        if json {
+            return print_json(&serde_json::json!({ "path": path }));
+        }
+        println!("{} Decisions written to {}", "✓".green(), path.display());
+        return Ok(());
+    }
+
+    let found = decisions::search(&manager, query.as_deref(), project.as_deref(), limit)?;
+    if json {
+        return print_json(&found);
+    }
+    if found.is_empty() {
+        println!("No matching decisions.");
+        return Ok(());
+    }
+    for decision in &found {
+        let project = decision
+            .project
+            .as_deref()
+            .map(decisions::project_name)
+            .unwrap_or("-");
+        println!(
+            "{}  {}  {}",
+            decision.date.dimmed(),
+            project.cyan(),
+            decision.text
+        );
+        println!(
+            "            {}",
+            format!("{}/{}", decision.date, decision.session).dimmed()
+        );
+    }
+    Ok(())
+}
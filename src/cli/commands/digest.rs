@@ -1,9 +1,16 @@
 use anyhow::{Context, Result};
-use chrono::{Duration, Local};
-use std::process::{Command, Stdio};
+use chrono::{Duration, Local, NaiveDate};
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Editor, Select};
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use tokio::task::JoinSet;
 
-use crate::archive::ArchiveManager;
-use crate::config::load_config;
+use crate::archive::{daily, edit, locks, ArchiveManager};
+use crate::cli::commands::backfill::job_outcome;
+use crate::config::{load_config, Config};
+use crate::jobs::{self, JobCommand, JobManager, JobType};
+use crate::notifications::{notify, scripts, WebhookEvent};
 use crate::summarizer::SummarizerEngine;
 
 /// Parse relative date string to actual date
@@ -19,13 +26,22 @@ fn parse_relative_date(relative: &str) -> Option<String> {
 }
 
 /// Run the digest command - generate daily summary from sessions
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     relative_date: Option<String>,
     date: Option<String>,
     background: bool,
     force: bool,
+    keep: Vec<String>,
+    group_by: Option<String>,
+    interactive: bool,
+    job_id: Option<String>,
 ) -> Result<()> {
-    let config = load_config()?;
+    let mut config = load_config()?;
+    daily::validate_section_keys(&keep)?;
+    if let Some(group_by) = group_by {
+        config.summarization.digest_group_by = group_by;
+    }
 
     // Determine target date: relative_date takes precedence, then --date, then today
     let target_date = if let Some(rel) = relative_date {
@@ -40,6 +56,11 @@ pub async fn run(
         date.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string())
     };
 
+    // A locked day's digest is only rewritten on request
+    if !force {
+        locks::ensure_unlocked(&config, &target_date, "digest")?;
+    }
+
     let manager = ArchiveManager::new(config.clone());
 
     // Check if there are sessions to digest
@@ -66,54 +87,317 @@ pub async fn run(
         }
     }
 
+    if interactive {
+        let engine = SummarizerEngine::new(config.clone());
+        return review_digest(&config, &engine, &target_date, sessions.len(), force, &keep).await;
+    }
+
     if background {
-        // Background mode: spawn detached process
         eprintln!(
             "[daily] Starting background digest for {} ({} sessions)",
             target_date,
             sessions.len()
         );
+        let (job_id, _) = spawn_digest_job(&config, &target_date, force, &keep)?;
+        eprintln!("[daily] Background digest started: {}", job_id);
+        return Ok(());
+    }
 
-        let exe = std::env::current_exe().context("Failed to get current executable")?;
+    let engine = SummarizerEngine::new(config.clone());
+    let Some(job_id) = job_id else {
+        return digest_date(
+            &config,
+            &engine,
+            &target_date,
+            sessions.len(),
+            force,
+            &keep,
+            None,
+        )
+        .await;
+    };
 
-        let mut args = vec!["digest", "--date", &target_date];
-        if force {
-            args.push("--force");
+    // Running as a tracked job: wait for a queue slot and record the outcome
+    let job_manager = JobManager::new(&config)?;
+    if let Err(e) = job_manager
+        .wait_for_slot(&job_id, config.jobs.max_concurrent)
+        .await
+    {
+        eprintln!("[daily] Warning: Job queue unavailable, running now: {}", e);
+    }
+
+    let result = digest_date(
+        &config,
+        &engine,
+        &target_date,
+        sessions.len(),
+        force,
+        &keep,
+        Some(&job_id),
+    )
+    .await;
+    let usage = engine.usage();
+    if usage.calls > 0 {
+        if let Err(e) = job_manager.record_usage(&job_id, &usage) {
+            eprintln!("[daily] Warning: Failed to record token usage: {}", e);
+        }
+    }
+    if let Some(quality) = engine.quality() {
+        if let Err(e) = job_manager.record_quality(&job_id, &quality) {
+            eprintln!("[daily] Warning: Failed to record quality score: {}", e);
         }
+    }
+    let update = match &result {
+        Ok(_) => job_manager.mark_completed(&job_id),
+        Err(e) => job_manager.mark_error(&job_id, e),
+    };
+    if let Err(e) = update {
+        eprintln!("[daily] Warning: Failed to update job status: {}", e);
+    }
+    let _ = job_manager.truncate_log_if_needed(&job_id);
 
-        Command::new(&exe)
-            .args(&args)
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-            .context("Failed to spawn background digest process")?;
+    result
+}
+
+/// Digest every past date with sessions but no digest, oldest first,
+/// running at most `concurrency` digest jobs at a time
+pub async fn run_all_missing(
+    since: Option<String>,
+    concurrency: usize,
+    group_by: Option<String>,
+) -> Result<()> {
+    let mut config = load_config()?;
+    if let Some(group_by) = group_by {
+        config.summarization.digest_group_by = group_by;
+    }
+    let concurrency = concurrency.max(1);
+    if let Some(since) = &since {
+        NaiveDate::parse_from_str(since, "%Y-%m-%d")
+            .with_context(|| format!("Invalid --since date '{}': expected yyyy-mm-dd", since))?;
+    }
 
-        eprintln!("[daily] Background digest started");
+    // Today's digest is left to the session-start hook, as more sessions may follow
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let manager = ArchiveManager::new(config.clone());
+    let dates: Vec<(String, usize)> = manager
+        .undigested_dates()?
+        .into_iter()
+        .filter(|date| *date < today && since.as_ref().is_none_or(|since| date >= since))
+        .filter(|date| !locks::is_locked(&config, date))
+        .map(|date| {
+            let sessions = manager.list_sessions(&date).map(|s| s.len()).unwrap_or(0);
+            (date, sessions)
+        })
+        .collect();
+
+    if dates.is_empty() {
+        println!("{} Every past date with sessions has a digest", "✓".green());
         return Ok(());
     }
 
-    // Foreground mode: perform the digest
-    if !sessions.is_empty() {
+    println!(
+        "{} {} date(s) without a digest, digesting with concurrency {}...\n",
+        "→".cyan(),
+        dates.len().to_string().bright_yellow(),
+        concurrency
+    );
+
+    let job_manager = JobManager::new(&config)?;
+    let total = dates.len();
+    let mut queue = dates.into_iter();
+    let mut running = JoinSet::new();
+    let mut finished = 0;
+    let mut failed = Vec::new();
+
+    loop {
+        while running.len() < concurrency {
+            let Some((date, sessions)) = queue.next() else {
+                break;
+            };
+            match spawn_digest_job(&config, &date, false, &[]) {
+                Ok((job_id, mut child)) => {
+                    running.spawn_blocking(move || {
+                        let exit = child.wait();
+                        (date, sessions, job_id, exit)
+                    });
+                }
+                Err(e) => {
+                    finished += 1;
+                    println!(
+                        "  {} {} {} {}",
+                        progress_bar(finished, total),
+                        "✗".red(),
+                        date,
+                        e.to_string().red()
+                    );
+                    failed.push(date);
+                }
+            }
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (date, sessions, job_id, exit) = joined.context("Digest worker panicked")?;
+        finished += 1;
+
+        match job_outcome(&job_manager, &job_id, exit) {
+            Ok(()) => println!(
+                "  {} {} {} {}",
+                progress_bar(finished, total),
+                "✓".green(),
+                date,
+                format!("({} sessions)", sessions).dimmed()
+            ),
+            Err(error) => {
+                println!(
+                    "  {} {} {} {} (daily jobs log {})",
+                    progress_bar(finished, total),
+                    "✗".red(),
+                    date,
+                    error.red(),
+                    job_id
+                );
+                failed.push(date);
+            }
+        }
+    }
+
+    println!(
+        "\n{} Digested {} of {} date(s)",
+        if failed.is_empty() {
+            "✓".green()
+        } else {
+            "!".yellow()
+        },
+        (total - failed.len()).to_string().green(),
+        total
+    );
+    if !failed.is_empty() {
+        failed.sort();
+        println!(
+            "  {} {} (retry with `daily digest --date <date>`)",
+            "Failed:".red(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// `[#####---------------] 3/12`
+fn progress_bar(done: usize, total: usize) -> String {
+    const WIDTH: usize = 20;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    format!(
+        "[{}{}] {}/{}",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        done,
+        total
+    )
+}
+
+/// Spawn a detached `daily digest --job-id` process tracked as a `Digest` job.
+/// Progress goes to the job log; the child is returned so callers may await it.
+/// Locked dates are refused unless `force` is set.
+pub(crate) fn spawn_digest_job(
+    config: &Config,
+    date: &str,
+    force: bool,
+    keep: &[String],
+) -> Result<(String, Child)> {
+    if !force {
+        locks::ensure_unlocked(config, date, "digest")?;
+    }
+    let job_manager = JobManager::new(config)?;
+    if let Some(job) = job_manager.active_digest_job(date)? {
+        anyhow::bail!(
+            "A digest for {} is already {} as job {}",
+            date,
+            job.status.to_string().to_lowercase(),
+            job.id
+        );
+    }
+    if let Some(holder) = job_manager.digest_lock_holder(date) {
+        anyhow::bail!(
+            "A digest for {} is already running ({}); wait for it to finish",
+            date,
+            holder.describe()
+        );
+    }
+    // Sessions of the date still being summarized finish first
+    let depends_on = job_manager.summaries_in_flight(date)?;
+    let mut args = vec!["digest".to_string(), "--date".to_string(), date.to_string()];
+    if force {
+        args.push("--force".to_string());
+    }
+    if !keep.is_empty() {
+        args.push("--keep".to_string());
+        args.push(keep.join(","));
+    }
+    let command = JobCommand::new(
+        JobType::Digest,
+        format!("digest-{}", date),
+        config.date_dir(date),
+        args,
+    )
+    .env(
+        "DAILY_SUMMARIZATION__DIGEST_GROUP_BY",
+        &config.summarization.digest_group_by,
+    )
+    .after(depends_on);
+    let (job_id, child) = jobs::spawn(config, command)?;
+    Ok((job_id, child))
+}
+
+/// Generate (or with `force`, regenerate) the daily summary for a date.
+/// Sections of an existing digest listed in `keep` or edited by hand are
+/// carried over verbatim, and the previous digest is backed up.
+/// Fails if another digest of the same date is in progress.
+async fn digest_date(
+    config: &Config,
+    engine: &SummarizerEngine,
+    target_date: &str,
+    session_count: usize,
+    force: bool,
+    keep: &[String],
+    job_id: Option<&str>,
+) -> Result<()> {
+    let _lock = JobManager::new(config)?.lock_digest(target_date, job_id)?;
+
+    if session_count > 0 {
         eprintln!(
             "[daily] Digesting {} sessions for {}...",
-            sessions.len(),
-            target_date
+            session_count, target_date
         );
     }
 
-    let engine = SummarizerEngine::new(config.clone());
-
     // Generate daily summary from all sessions (or regenerate if force mode)
-    match engine.update_daily_summary(&target_date).await {
+    match engine.update_daily_summary(target_date).await {
         Ok(summary) => {
-            let summary_path = summary.save(&config)?;
+            let (summary_path, kept) = summary.save(config, keep)?;
+            if !kept.is_empty() {
+                eprintln!("[daily] Kept sections: {}", kept.join(", "));
+            }
             eprintln!("[daily] Daily summary created: {}", summary_path.display());
+            save_translations(config, engine, target_date);
+            scripts::post_digest(config, target_date, &summary_path);
+            notify(
+                config,
+                WebhookEvent::DigestGenerated,
+                serde_json::json!({
+                    "date": target_date,
+                    "session_count": session_count,
+                    "path": summary_path,
+                    "regenerated": force,
+                }),
+            )
+            .await;
             // Session files are preserved for reference
         }
         Err(e) => {
             eprintln!("[daily] Error: Failed to create daily summary: {}", e);
-            if !sessions.is_empty() {
+            if session_count > 0 {
                 eprintln!("[daily] Session files preserved for retry");
             }
             return Err(e);
@@ -123,3 +407,261 @@ pub async fn run(
     eprintln!("[daily] Digest complete!");
     Ok(())
 }
+
+/// Save the date's digest again as `daily.{lang}.md` for each of
+/// `summarization.digest_languages`. The summary language is copied as
+/// is and the others are translated in parallel; a failed translation is
+/// reported and leaves daily.md alone.
+fn save_translations(config: &Config, engine: &SummarizerEngine, date: &str) {
+    let languages = &config.summarization.digest_languages;
+    if languages.is_empty() {
+        return;
+    }
+    let manager = ArchiveManager::new(config.clone());
+    let content = match manager.read_daily_summary(date) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("[daily] Failed to read digest for translation: {}", e);
+            return;
+        }
+    };
+
+    let (copies, translate): (Vec<String>, Vec<String>) = languages
+        .iter()
+        .cloned()
+        .partition(|language| *language == config.summarization.summary_language);
+    let mut results: Vec<(String, Result<String>)> = copies
+        .into_iter()
+        .map(|language| (language, Ok(content.clone())))
+        .collect();
+    if !translate.is_empty() {
+        eprintln!(
+            "[daily] Translating digest into {}...",
+            translate.join(", ")
+        );
+        results.extend(engine.translate_digest(&content, &translate));
+    }
+    for (language, translated) in results {
+        match translated.and_then(|text| manager.write_translated_summary(date, &language, &text)) {
+            Ok(path) => eprintln!("[daily] Saved {} digest: {}", language, path.display()),
+            Err(e) => eprintln!("[daily] Failed to save {} digest: {}", language, e),
+        }
+    }
+}
+
+/// Generate a digest and review it before anything is written: view it in
+/// a pager, edit or regenerate single sections, then save or discard.
+/// Sections changed by hand stay recorded as edited, so a later
+/// regeneration carries them over.
+async fn review_digest(
+    config: &Config,
+    engine: &SummarizerEngine,
+    date: &str,
+    session_count: usize,
+    force: bool,
+    keep: &[String],
+) -> Result<()> {
+    let _lock = JobManager::new(config)?.lock_digest(date, None)?;
+
+    eprintln!("[daily] Generating digest for {}...", date);
+    let summary = engine.update_daily_summary(date).await?;
+    let (mut content, kept) = summary.merge_previous(config, keep)?;
+    println!("\n{}\n", content);
+    if !kept.is_empty() {
+        println!(
+            "{} Kept from the previous digest: {}",
+            "→".cyan(),
+            kept.join(", ")
+        );
+    }
+
+    let theme = ColorfulTheme::default();
+    let actions = [
+        "Save",
+        "View in pager",
+        "Edit a section",
+        "Regenerate a section",
+        "Edit the whole digest",
+        "Discard",
+    ];
+    loop {
+        let action = Select::with_theme(&theme)
+            .with_prompt(format!("Digest for {}", date))
+            .items(&actions)
+            .default(0)
+            .interact()?;
+        match action {
+            0 => break,
+            1 => page(&content),
+            2 => {
+                let Some((_, heading)) = pick_section(&theme, &content, "Edit")? else {
+                    continue;
+                };
+                let body = edit::section_body(&content, heading).unwrap_or_default();
+                if let Some(edited) = Editor::new().extension(".md").edit(body)? {
+                    content = edit::replace_section(&content, heading, &edited)?;
+                    println!("{} {} updated", "✓".green(), heading);
+                }
+            }
+            3 => {
+                let Some((key, heading)) = pick_section(&theme, &content, "Regenerate")? else {
+                    continue;
+                };
+                eprintln!("[daily] Regenerating {}...", heading);
+                match engine
+                    .regenerate_digest_section(date, &content, heading)
+                    .await
+                {
+                    Ok(body) => {
+                        content = regenerate_section(&content, &body, key, heading)?;
+                        println!("\n## {}\n\n{}\n", heading, body);
+                    }
+                    Err(e) => println!("{} {:#}", "!".yellow(), e),
+                }
+            }
+            4 => {
+                if let Some(edited) = Editor::new().extension(".md").edit(&content)? {
+                    content = edited;
+                }
+            }
+            _ => {
+                println!("{}", "Discarded; daily.md was not changed.".dimmed());
+                return Ok(());
+            }
+        }
+    }
+
+    let path = summary.save_content(config, &content)?;
+    println!("{} Saved {}", "✓".green(), path.display());
+    let edited = daily::edited_sections(&content);
+    if !edited.is_empty() {
+        println!("  {} {}", "Edited by hand:".dimmed(), edited.join(", "));
+    }
+    save_translations(config, engine, date);
+    scripts::post_digest(config, date, &path);
+    notify(
+        config,
+        WebhookEvent::DigestGenerated,
+        serde_json::json!({
+            "date": date,
+            "session_count": session_count,
+            "path": path,
+            "regenerated": force,
+        }),
+    )
+    .await;
+    Ok(())
+}
+
+/// `content` with section `heading` replaced by the regenerated `body` and
+/// recorded as generated again
+fn regenerate_section(content: &str, body: &str, key: &str, heading: &str) -> Result<String> {
+    let content = edit::replace_section(content, heading, body)?;
+    Ok(daily::restamp_section(&content, key))
+}
+
+/// Ask for one of the digest sections present in `content`
+fn pick_section(
+    theme: &ColorfulTheme,
+    content: &str,
+    verb: &str,
+) -> Result<Option<(&'static str, &'static str)>> {
+    let sections: Vec<(&'static str, &'static str)> = daily::SECTIONS
+        .iter()
+        .copied()
+        .filter(|(_, heading)| edit::section_body(content, heading).is_some())
+        .collect();
+    let headings: Vec<&str> = sections.iter().map(|(_, heading)| *heading).collect();
+    let picked = Select::with_theme(theme)
+        .with_prompt(format!("{} which section?", verb))
+        .items(&headings)
+        .default(0)
+        .interact_opt()?;
+    Ok(picked.map(|i| sections[i]))
+}
+
+/// Show text in `$PAGER` (default `less`), or print it if no pager starts
+fn page(text: &str) {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut words = pager.split_whitespace();
+    let child = words.next().and_then(|program| {
+        Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+    let Some(mut child) = child else {
+        println!("{}", text);
+        return;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::daily::{edited_sections, section, DailySummary, SummaryCard};
+
+    fn generate(overview: &str, reflections: &str) -> DailySummary {
+        DailySummary::new("2024-01-15".to_string()).with_content(
+            overview.into(),
+            "Shipped the parser.".into(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            reflections.into(),
+            vec![SummaryCard {
+                title: "Review PR".into(),
+                content: "- Check the tests".into(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_review_keeps_other_sections_and_translations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        let original = generate("Parser day.", "Generated reflections.").to_markdown();
+        manager
+            .write_daily_summary("2024-01-15", &original)
+            .unwrap();
+        manager
+            .write_translated_summary("2024-01-15", "zh", "# 每日总结\n")
+            .unwrap();
+
+        // Edit one section by hand
+        let content =
+            edit::replace_section(&original, "Reflections", "My own reflections.").unwrap();
+        assert_eq!(section(&content, "Overview"), Some("Parser day."));
+        assert_eq!(edited_sections(&content), vec!["reflections"]);
+
+        // Regenerate another; the edit survives and stays marked as edited
+        let summary = generate("Parser and CI day.", "Other reflections.");
+        let content =
+            regenerate_section(&content, "Parser and CI day.", "overview", "Overview").unwrap();
+        assert_eq!(section(&content, "Overview"), Some("Parser and CI day."));
+        assert_eq!(
+            section(&content, "Reflections"),
+            Some("My own reflections.")
+        );
+        for heading in ["Key Work", "Tomorrow's Focus"] {
+            assert_eq!(section(&content, heading), section(&original, heading));
+        }
+        assert_eq!(edited_sections(&content), vec!["reflections"]);
+
+        summary.save_content(&config, &content).unwrap();
+        assert_eq!(manager.read_daily_summary("2024-01-15").unwrap(), content);
+        assert_eq!(manager.digest_languages("2024-01-15"), vec!["zh"]);
+        let translation = manager.translated_summary_path("2024-01-15", "zh").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(translation).unwrap(),
+            "# 每日总结\n"
+        );
+    }
+}
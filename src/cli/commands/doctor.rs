@@ -0,0 +1,66 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::hooks::events::{self, HookOutcome};
+
+/// Check the install and show the most recent `daily hook` invocations
+pub async fn run(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let recent = events::recent(&config, events::RECENT_EVENTS);
+    if json {
+        return print_json(&recent);
+    }
+
+    let storage = config.storage_path();
+    println!("{}", "Install".bold());
+    println!(
+        "  Storage:       {} {}",
+        storage.display(),
+        if storage.is_dir() {
+            "ok".green()
+        } else {
+            "missing".red()
+        }
+    );
+    println!("  Hook timeout:  {}s", config.hooks.timeout_secs);
+    println!();
+
+    println!("{}", "Recent hook events".bold());
+    if recent.is_empty() {
+        println!("  No hook has run yet. Install hooks with `daily install-hooks`.");
+        return Ok(());
+    }
+    for event in &recent {
+        let outcome = match event.outcome {
+            HookOutcome::Ok => "ok".green(),
+            HookOutcome::Failed => "failed".red(),
+            HookOutcome::TimedOut => "timed out".yellow(),
+        };
+        println!(
+            "  {}  {:<18} {:<9} {:>6}ms",
+            event.at.format("%Y-%m-%d %H:%M:%S"),
+            event.hook,
+            outcome,
+            event.duration_ms
+        );
+        if let Some(error) = &event.error {
+            println!("      {}", error.dimmed());
+        }
+    }
+
+    let failures = recent
+        .iter()
+        .filter(|e| e.outcome != HookOutcome::Ok)
+        .count();
+    if failures > 0 {
+        println!();
+        println!(
+            "  {} of the last {} hook runs failed",
+            failures,
+            recent.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+use crate::archive::ArchiveManager;
+use crate::config::load_config;
+use crate::export::{self, Format, Granularity};
+use crate::flashcards;
+use crate::insights::collector::InsightsFilter;
+use crate::render::{self, Theme};
+use crate::usage::pricing::PricingData;
+
+/// Export Claude Code usage per session or per day
+pub async fn usage(
+    format: String,
+    from: Option<String>,
+    to: Option<String>,
+    per: String,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let format: Format = format.parse()?;
+    let per: Granularity = per.parse()?;
+    let config = load_config()?;
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let table = export::usage_table(
+        &config,
+        &pricing,
+        from.as_deref(),
+        to.as_deref(),
+        per,
+        format,
+    )?;
+    write(table, output)
+}
+
+/// Export archive insights per session or per day
+pub async fn insights(
+    format: String,
+    from: Option<String>,
+    to: Option<String>,
+    days: usize,
+    per: String,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let format: Format = format.parse()?;
+    let per: Granularity = per.parse()?;
+    let config = load_config()?;
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let filter = InsightsFilter {
+        from,
+        to,
+        ..Default::default()
+    };
+    let table = export::insights_table(&config, &pricing, Some(days), &filter, per, format)?;
+    write(table, output)
+}
+
+/// Render a date's digest as an HTML page
+pub async fn digest(
+    date: Option<String>,
+    theme: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let config = load_config()?;
+    let theme: Theme = theme.unwrap_or(config.output.html_theme.clone()).parse()?;
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    let content = ArchiveManager::new(config)
+        .read_daily_summary(&date)
+        .with_context(|| format!("No digest for {}", date))?;
+    write(render::digest_page(&date, &content, theme), output)
+}
+
+/// Export learnings as flashcards
+pub async fn anki(
+    format: String,
+    from: Option<String>,
+    to: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let config = load_config()?;
+    let cards = flashcards::collect(&ArchiveManager::new(config), from.as_deref(), to.as_deref())?;
+    let content = match format.to_lowercase().as_str() {
+        "anki" | "tsv" => flashcards::to_anki(&cards),
+        "json" => serde_json::to_string_pretty(&cards)?,
+        _ => anyhow::bail!("Unknown format '{}': expected anki or json", format),
+    };
+    eprintln!("{} {} cards", "→".cyan(), cards.len());
+    write(content, output)
+}
+
+fn write(mut table: String, output: Option<PathBuf>) -> Result<()> {
+    if !table.ends_with('\n') {
+        table.push('\n');
+    }
+    match output {
+        Some(path) => {
+            std::fs::write(&path, table)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("{} Exported to {}", "✓".green(), path.display());
+        }
+        None => print!("{}", table),
+    }
+    Ok(())
+}
@@ -26,7 +26,8 @@ pub async fn run_skill(
     println!("[daily] Applying quality gate (踩过坑? 下次还会遇到? 能说清楚?)...");
 
     // Extract skill using Claude
-    let skill_content = engine.extract_skill(&session_content, None).await?;
+    let package = engine.extract_skill(&session_content, None).await?;
+    let skill_content = package.skill;
 
     // Check if skill is extractable
     if skill_content.trim().starts_with("NOT_EXTRACTABLE:") {
@@ -65,11 +66,18 @@ pub async fn run_skill(
     // Create skill directory structure
     fs::create_dir_all(&output_path)?;
 
-    // Create optional resource directories (empty for now)
-    // Users can add scripts/references/assets as needed
+    // Create optional resource directories, with any files the model generated
+    // Users can add more scripts/references/assets as needed
     fs::create_dir_all(output_path.join("scripts"))?;
     fs::create_dir_all(output_path.join("references"))?;
     fs::create_dir_all(output_path.join("assets"))?;
+    for file in &package.files {
+        let path = output_path.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &file.content)?;
+    }
 
     // Write SKILL.md
     let skill_file = output_path.join("SKILL.md");
@@ -80,9 +88,22 @@ pub async fn run_skill(
     println!("Skill structure created:");
     println!("  {}/", output_path.display());
     println!("  ├── SKILL.md (generated)");
-    println!("  ├── scripts/ (empty - add executable code if needed)");
-    println!("  ├── references/ (empty - add documentation if needed)");
-    println!("  └── assets/ (empty - add templates/files if needed)");
+    for (branch, dir, hint) in [
+        ("├──", "scripts", "add executable code if needed"),
+        ("├──", "references", "add documentation if needed"),
+        ("└──", "assets", "add templates/files if needed"),
+    ] {
+        let generated = package
+            .files
+            .iter()
+            .filter(|f| f.path.starts_with(&format!("{}/", dir)))
+            .count();
+        if generated == 0 {
+            println!("  {} {}/ (empty - {})", branch, dir, hint);
+        } else {
+            println!("  {} {}/ ({} generated)", branch, dir, generated);
+        }
+    }
     println!();
     println!("Preview of SKILL.md:");
     println!("{}", "-".repeat(50));
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::archive::{section_cards, ArchiveManager};
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::integrations::github::{focus_items, GithubClient, PushAction, PushResult};
+
+#[derive(Serialize)]
+struct PushReport {
+    date: String,
+    repo: String,
+    dry_run: bool,
+    issues: Vec<PushResult>,
+}
+
+/// Create or update GitHub issues from a digest's "Tomorrow's Focus" items
+pub async fn push_github(
+    repo: Option<String>,
+    date: Option<String>,
+    label: Option<String>,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config.clone());
+
+    let repo = repo
+        .or_else(|| config.integrations.github.repo.clone())
+        .context("No repository given. Use --repo owner/name or set integrations.github.repo")?;
+    let date = match date {
+        Some(date) => date,
+        None => manager
+            .list_dates()?
+            .into_iter()
+            .find(|d| manager.has_digest(d))
+            .context("No digest found. Run `daily digest` first")?,
+    };
+
+    let content = manager
+        .read_daily_summary(&date)
+        .with_context(|| format!("No digest for {}", date))?;
+    let items = focus_items(&date, &section_cards(&content, "Tomorrow's Focus"));
+    if items.is_empty() {
+        if json {
+            return print_json(&PushReport {
+                date,
+                repo,
+                dry_run,
+                issues: Vec::new(),
+            });
+        }
+        println!(
+            "{} No Tomorrow's Focus items in the {} digest",
+            "ℹ".blue(),
+            date
+        );
+        return Ok(());
+    }
+
+    let client = GithubClient::from_config(&config, &repo, label)?;
+    let issues = client.push(&date, &items, dry_run).await?;
+
+    if json {
+        return print_json(&PushReport {
+            date,
+            repo,
+            dry_run,
+            issues,
+        });
+    }
+
+    for issue in &issues {
+        let verb = match (issue.action, dry_run) {
+            (PushAction::Created, true) => "would create",
+            (PushAction::Created, false) => "created",
+            (PushAction::Updated, true) => "would update",
+            (PushAction::Updated, false) => "updated",
+            (PushAction::Closed, true) => "would close",
+            (PushAction::Closed, false) => "closed",
+            (PushAction::Unchanged, _) => "unchanged",
+        };
+        let number = issue.number.map(|n| format!("#{}", n)).unwrap_or_default();
+        println!("  {:<12} {:<6} {}", verb.dimmed(), number, issue.title);
+    }
+
+    let changed = issues
+        .iter()
+        .filter(|i| i.action != PushAction::Unchanged)
+        .count();
+    if dry_run {
+        println!(
+            "\n{} Dry run: {} of {} focus item(s) would change in {}",
+            "ℹ".blue(),
+            changed,
+            issues.len(),
+            repo
+        );
+    } else {
+        println!(
+            "\n{} Synced {} focus item(s) from {} to {} ({} changed)",
+            "✓".green(),
+            issues.len(),
+            date,
+            repo.bright_yellow(),
+            changed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::goals::{Goal, GoalStatus, GoalStore};
+
+/// Add an active goal
+pub async fn add(title: String, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut store = GoalStore::load(&config)?;
+    let goal = store.add(&title)?.clone();
+    store.save(&config)?;
+
+    if json {
+        return print_json(&goal);
+    }
+    println!("{} Added goal {}: {}", "✓".green(), goal.id, goal.title);
+    println!(
+        "  {}",
+        "Digests will report progress on it until it is marked done".dimmed()
+    );
+    Ok(())
+}
+
+/// List goals, active ones first, with the latest progress digests reported
+pub async fn list(all: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let store = GoalStore::load(&config)?;
+    let mut goals: Vec<&Goal> = store
+        .goals
+        .iter()
+        .filter(|g| all || g.status == GoalStatus::Active)
+        .collect();
+    goals.sort_by_key(|g| g.status != GoalStatus::Active);
+
+    if json {
+        return print_json(&goals);
+    }
+
+    if goals.is_empty() {
+        println!(
+            "No goals yet. Add one with {}",
+            "daily goal add \"ship billing MVP\"".cyan()
+        );
+        return Ok(());
+    }
+
+    for goal in goals {
+        let marker = match goal.status {
+            GoalStatus::Active => "→".cyan(),
+            GoalStatus::Done => "✓".green(),
+        };
+        let sessions: usize = goal.progress.iter().map(|p| p.sessions).sum();
+        println!(
+            "{} {} {}  {}",
+            marker,
+            format!("#{}", goal.id).dimmed(),
+            goal.title.bold(),
+            format!(
+                "{} session(s) over {} day(s)",
+                sessions,
+                goal.progress.len()
+            )
+            .dimmed()
+        );
+        if let Some(latest) = goal.progress.last() {
+            println!("    {} {}", latest.date.dimmed(), latest.note);
+        }
+    }
+    Ok(())
+}
+
+/// Mark a goal done or active again
+pub async fn set_status(id: u32, status: GoalStatus, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut store = GoalStore::load(&config)?;
+    let goal = store.get_mut(id)?;
+    goal.set_status(status);
+    let goal = goal.clone();
+    store.save(&config)?;
+
+    if json {
+        return print_json(&goal);
+    }
+    match status {
+        GoalStatus::Done => println!("{} Goal {} done: {}", "✓".green(), id, goal.title),
+        GoalStatus::Active => println!("{} Goal {} active again: {}", "✓".green(), id, goal.title),
+    }
+    Ok(())
+}
+
+/// Delete a goal and its recorded progress
+pub async fn remove(id: u32, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut store = GoalStore::load(&config)?;
+    let goal = store.remove(id)?;
+    store.save(&config)?;
+
+    if json {
+        return print_json(&goal);
+    }
+    println!("{} Removed goal {}: {}", "✓".green(), id, goal.title);
+    Ok(())
+}
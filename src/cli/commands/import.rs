@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+use crate::archive::ArchiveManager;
+use crate::config::{load_config, Config};
+use crate::embeddings;
+use crate::import::{self, ImportedSession, Source};
+use crate::notifications::scripts;
+use crate::summarizer::SummarizerEngine;
+
+/// Summarize sessions from another tool's logs into session archives,
+/// skipping those already imported unless `force`
+pub async fn run(
+    source: String,
+    path: PathBuf,
+    cwd: Option<PathBuf>,
+    dry_run: bool,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let source = Source::parse(&source)?;
+    let manager = ArchiveManager::new(config.clone());
+
+    let sessions = import::read(source, &path)?;
+    if sessions.is_empty() {
+        println!(
+            "{} No {} sessions found in {}",
+            "ℹ".blue(),
+            source.as_str(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let mut pending = Vec::new();
+    for session in sessions {
+        let existing = existing_archives(&manager, &session)?;
+        if existing.is_empty() || force {
+            pending.push((session, existing));
+        } else {
+            println!(
+                "  {} {} (already imported)",
+                "skip".dimmed(),
+                session.session_id
+            );
+        }
+    }
+
+    println!(
+        "\n{} {} {} session(s) to import:",
+        "Found".bold(),
+        pending.len().to_string().bright_yellow(),
+        source.as_str()
+    );
+    for (session, _) in &pending {
+        println!(
+            "  {} {} {} {}",
+            session
+                .started_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .dimmed(),
+            session.session_id,
+            format!("({} prompt(s))", session.data.user_messages.len()).dimmed(),
+            session.cwd.as_deref().unwrap_or_default().dimmed()
+        );
+    }
+    if dry_run || pending.is_empty() {
+        if dry_run {
+            println!("\n{} Dry run, nothing archived", "ℹ".blue());
+        }
+        return Ok(());
+    }
+
+    let engine = SummarizerEngine::new(config.clone());
+    let mut failed = 0;
+    for (session, existing) in &pending {
+        let cwd = cwd
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned())
+            .or_else(|| session.cwd.clone())
+            .unwrap_or_else(|| ".".to_string());
+        match import_session(&config, &engine, session, &cwd, existing).await {
+            Ok(path) => println!("{} {}", "✓".green(), path.display()),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{} {}: {:#}", "✗".red(), session.session_id, e);
+            }
+        }
+    }
+
+    println!(
+        "\n{} Imported {} of {} session(s)",
+        if failed == 0 {
+            "✓".green()
+        } else {
+            "!".yellow()
+        },
+        pending.len() - failed,
+        pending.len()
+    );
+    Ok(())
+}
+
+/// Archives of the session's date that came from an earlier import of it
+fn existing_archives(manager: &ArchiveManager, session: &ImportedSession) -> Result<Vec<String>> {
+    let date = session.started_at.format("%Y-%m-%d").to_string();
+    manager.find_sessions_by_id(&date, std::slice::from_ref(&session.session_id))
+}
+
+async fn import_session(
+    config: &Config,
+    engine: &SummarizerEngine,
+    session: &ImportedSession,
+    cwd: &str,
+    existing: &[String],
+) -> Result<PathBuf> {
+    eprintln!(
+        "[daily] Summarizing {} session: {}",
+        session.source.as_str(),
+        session.session_id
+    );
+    let mut archive = engine
+        .summarize_transcript(
+            &session.data,
+            &session.session_id,
+            &session.path,
+            cwd,
+            session.started_at,
+        )
+        .await
+        .context("Failed to summarize session")?;
+    archive.source = Some(session.source.as_str().to_string());
+
+    let path = archive.save(config)?;
+    let manager = ArchiveManager::new(config.clone());
+    // A forced re-import replaces the earlier archives of the session
+    for name in existing.iter().filter(|name| **name != archive.title) {
+        manager.remove_session(&archive.date, name)?;
+    }
+    scripts::post_archive(
+        config,
+        &archive.date,
+        &archive.title,
+        &archive.session_id,
+        &path,
+    );
+    if let Err(e) = embeddings::index_session(config, &archive.date, &archive.title).await {
+        eprintln!("[daily] Embedding index update failed: {}", e);
+    }
+    Ok(path)
+}
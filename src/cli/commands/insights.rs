@@ -1,48 +1,92 @@
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 
+use crate::cli::output::{self, print_json};
 use crate::config::load_config;
-use crate::insights::collector::InsightsData;
+use crate::i18n::{self, t};
+use crate::insights::collector::{InsightsData, InsightsFilter};
+use crate::insights::patterns::WorkPatterns;
+use crate::insights::tools::ToolInsights;
+use crate::server::dto::{InsightsDto, ToolInsightsDto};
+use crate::server::handlers::{build_insights_dto, build_tool_insights_dto};
 use crate::usage::pricing::PricingData;
 
-/// Run the insights command, displaying aggregated archive and facet data
-pub async fn run(days: usize) -> Result<()> {
+/// `--json` output: the `/api/insights` shape, plus tool analytics with `--tools`
+#[derive(Serialize)]
+struct InsightsJson {
+    #[serde(flatten)]
+    insights: InsightsDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<ToolInsightsDto>,
+}
+
+/// Run the insights command, displaying aggregated archive and facet data.
+/// With `tools`, also parses session transcripts for tool usage analytics.
+pub async fn run(days: usize, tools: bool, json: bool) -> Result<()> {
     let config = load_config()?;
 
+    if json {
+        let pricing = PricingData::load()
+            .await
+            .with_overrides(&config.usage.price_overrides);
+        let data =
+            InsightsData::collect(&config, Some(days), &pricing, &InsightsFilter::default())?;
+        let tools = if tools {
+            Some(build_tool_insights_dto(ToolInsights::collect(
+                &config,
+                Some(days),
+            )?))
+        } else {
+            None
+        };
+        return print_json(&InsightsJson {
+            insights: build_insights_dto(data),
+            tools,
+        });
+    }
+
     println!(
         "\n{}",
-        format!("  Daily Insights (last {} days)", days)
+        format!("  {}", i18n::fmt("insights.title", &[("days", &days)]))
             .bold()
             .bright_yellow()
     );
-    println!("{}", "  ─────────────────────────────".dimmed());
+    println!("  {}", output::rule('─', 29).dimmed());
 
-    let pricing = PricingData::load().await;
-    let data = InsightsData::collect(&config, Some(days), &pricing)?;
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let data = InsightsData::collect(&config, Some(days), &pricing, &InsightsFilter::default())?;
 
     // Overview stats
     println!(
-        "\n  {} {} days, {} sessions",
-        "Overview:".bold(),
-        data.total_days.to_string().bright_yellow(),
-        data.total_sessions.to_string().bright_yellow()
+        "\n  {} {}",
+        t("insights.overview").bold(),
+        i18n::fmt(
+            "insights.overview_counts",
+            &[
+                ("days", &data.total_days.to_string().bright_yellow()),
+                ("sessions", &data.total_sessions.to_string().bright_yellow()),
+            ],
+        )
     );
 
     // Daily activity (simple bar chart)
     if !data.daily_stats.is_empty() {
-        println!("\n  {}", "Activity Timeline:".bold());
+        println!("\n  {}", t("insights.timeline").bold());
         let max_count = data
             .daily_stats
             .iter()
             .map(|d| d.session_count)
             .max()
             .unwrap_or(1);
+        // Date, marker and count take about 20 columns
+        let bar_max = output::width(50).saturating_sub(20).clamp(5, 30);
         for stat in &data.daily_stats {
-            let bar_len = if max_count > 0 {
-                (stat.session_count * 30) / max_count
-            } else {
-                0
-            };
+            let bar_len = (stat.session_count * bar_max)
+                .checked_div(max_count)
+                .unwrap_or(0);
             let bar: String = "\u{2588}".repeat(bar_len);
             let digest_marker = if stat.has_digest { "\u{2713}" } else { " " };
             println!(
@@ -55,25 +99,91 @@ pub async fn run(days: usize) -> Result<()> {
         }
     }
 
+    // Activity heatmap (Monday-first rows, one column per week)
+    let activity = &data.activity;
+    println!(
+        "\n  {} {}",
+        t("insights.streaks").bold(),
+        i18n::fmt(
+            "insights.streak_counts",
+            &[
+                (
+                    "current",
+                    &format!("{}d", activity.current_streak).bright_yellow(),
+                ),
+                (
+                    "longest",
+                    &format!("{}d", activity.longest_streak).bright_yellow(),
+                ),
+                ("active", &activity.active_days.to_string().bright_yellow()),
+            ],
+        )
+    );
+    let weeks = activity.weeks();
+    // Most recent weeks that fit next to the day labels
+    let weeks = &weeks[weeks
+        .len()
+        .saturating_sub(output::width(80).saturating_sub(6))..];
+    let max_count = activity
+        .heatmap
+        .iter()
+        .map(|d| d.session_count)
+        .max()
+        .unwrap_or(0);
+    if max_count > 0 {
+        for (row, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+            let cells: String = weeks
+                .iter()
+                .map(|week| match week[row] {
+                    Some(day) => heatmap_cell(day.session_count, max_count),
+                    None => " ".to_string(),
+                })
+                .collect();
+            println!("  {:>3} {}", label.dimmed(), cells);
+        }
+    }
+
+    print_work_patterns(&data.work_patterns);
+
     // Goal distribution
     if !data.goal_distribution.is_empty() {
-        println!("\n  {}", "Goal Distribution:".bold());
+        println!("\n  {}", t("insights.goal_distribution").bold());
+        let width = label_width(data.goal_distribution.iter().map(|i| i.name.as_str()));
         for item in &data.goal_distribution {
             println!(
                 "    {} {}",
-                format!("{:>20}", item.name).cyan(),
+                label(&item.name, width).cyan(),
                 format!("{}", item.count).dimmed()
             );
         }
     }
 
+    // Goals from `daily goal`
+    if !data.tracked_goals.is_empty() {
+        println!("\n  {}", t("insights.tracked_goals").bold());
+        let width = label_width(data.tracked_goals.iter().map(|g| g.title.as_str()));
+        for goal in &data.tracked_goals {
+            let status = match &goal.completed {
+                Some(date) => format!("done {}", date).green(),
+                None => "active".cyan(),
+            };
+            println!(
+                "    {} {} {}",
+                label(&goal.title, width).cyan(),
+                format!("{} sessions, {} days", goal.session_count, goal.day_count).dimmed(),
+                status
+            );
+        }
+    }
+
     // Friction points
     if !data.friction_distribution.is_empty() {
-        println!("\n  {}", "Friction Points:".bold());
+        println!("\n  {}", t("insights.friction").bold());
+        let width = label_width(data.friction_distribution.iter().map(|i| i.name.as_str()));
         for item in &data.friction_distribution {
             println!(
                 "    {} {}",
-                format!("{:>20}", item.name).red(),
+                label(&item.name, width).red(),
                 format!("{}", item.count).dimmed()
             );
         }
@@ -81,35 +191,144 @@ pub async fn run(days: usize) -> Result<()> {
 
     // Satisfaction
     if !data.satisfaction_distribution.is_empty() {
-        println!("\n  {}", "Satisfaction:".bold());
+        println!("\n  {}", t("insights.satisfaction").bold());
+        let width = label_width(
+            data.satisfaction_distribution
+                .iter()
+                .map(|i| i.name.as_str()),
+        );
         for item in &data.satisfaction_distribution {
+            let name = label(&item.name, width);
             let color_name = match item.name.as_str() {
-                "happy" => item.name.green(),
-                "satisfied" => item.name.bright_green(),
-                "neutral" => item.name.yellow(),
-                "frustrated" => item.name.red(),
-                _ => item.name.normal(),
+                "happy" => name.green(),
+                "satisfied" => name.bright_green(),
+                "neutral" => name.yellow(),
+                "frustrated" => name.red(),
+                _ => name.normal(),
             };
-            println!(
-                "    {:>20} {}",
-                color_name,
-                format!("{}", item.count).dimmed()
-            );
+            println!("    {} {}", color_name, format!("{}", item.count).dimmed());
         }
     }
 
     // Languages
     if !data.language_distribution.is_empty() {
-        println!("\n  {}", "Languages:".bold());
-        for item in data.language_distribution.iter().take(10) {
+        println!("\n  {}", t("insights.languages").bold());
+        let languages = &data.language_distribution[..data.language_distribution.len().min(10)];
+        let width = label_width(languages.iter().map(|i| i.name.as_str()));
+        for item in languages {
             println!(
                 "    {} {}",
-                format!("{:>20}", item.name).bright_blue(),
+                label(&item.name, width).bright_blue(),
                 format!("{}", item.count).dimmed()
             );
         }
     }
 
+    if tools {
+        print_tool_usage(&ToolInsights::collect(&config, Some(days))?);
+    }
+
     println!();
     Ok(())
 }
+
+/// Print the hour-of-day histogram and focus blocks
+fn print_work_patterns(patterns: &WorkPatterns) {
+    let Some(peak_hour) = patterns.peak_hour else {
+        return;
+    };
+    println!("\n  {}", t("insights.work_patterns").bold());
+    let max_count = patterns.hourly.iter().copied().max().unwrap_or(0);
+    let bars: String = patterns
+        .hourly
+        .iter()
+        .map(|&count| hour_bar(count, max_count))
+        .collect();
+    println!("    {}", bars.bright_yellow());
+    println!("    {}", "0     6     12    18    23".dimmed());
+    println!(
+        "    peak {}, {} late night (22:00-06:00), longest focus {}",
+        format!("{:02}:00", peak_hour).bright_yellow(),
+        format!("{:.0}%", patterns.late_night_ratio).bright_yellow(),
+        format!("{}m", patterns.longest_focus_minutes).bright_yellow()
+    );
+    for block in &patterns.focus_blocks {
+        println!(
+            "    {} {}-{} {}",
+            block.date.dimmed(),
+            block.start,
+            block.end,
+            format!("{}m", block.minutes).dimmed()
+        );
+    }
+}
+
+/// Width of a label column: the longest of `names`, up to a third of the
+/// terminal
+fn label_width<'a>(names: impl Iterator<Item = &'a str>) -> usize {
+    let max = output::width(120) / 3;
+    names.map(output::display_width).max().unwrap_or(0).min(max)
+}
+
+/// `name` right-aligned in a label column `width` wide
+fn label(name: &str, width: usize) -> String {
+    output::pad(&output::ellipsize(name, width), width, true)
+}
+
+/// Render one hour of the histogram as a bar of 8 heights
+fn hour_bar(count: usize, max_count: usize) -> char {
+    if count == 0 {
+        return ' ';
+    }
+    let level = (count * 8).div_ceil(max_count).clamp(1, 8);
+    [
+        '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}',
+        '\u{2588}',
+    ][level - 1]
+}
+
+/// Print the tool usage section
+fn print_tool_usage(tools: &ToolInsights) {
+    println!("\n  {}", t("insights.tool_usage").bold());
+    if tools.total_calls == 0 {
+        println!(
+            "    {}",
+            "No tool calls found in archived transcripts".dimmed()
+        );
+        return;
+    }
+    println!(
+        "    {} calls, {} failed ({:.1}%), longest chain {}",
+        tools.total_calls.to_string().bright_yellow(),
+        tools.total_failures.to_string().red(),
+        tools.failure_rate,
+        tools.longest_chain.to_string().bright_yellow()
+    );
+    let listed = &tools.tools[..tools.tools.len().min(15)];
+    let width = label_width(listed.iter().map(|t| t.name.as_str()));
+    for tool in listed {
+        let failures = if tool.failures > 0 {
+            format!("{} failed ({:.0}%)", tool.failures, tool.failure_rate)
+                .red()
+                .to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "    {} {} {}",
+            label(&tool.name, width).cyan(),
+            format!("{:>5}", tool.calls).dimmed(),
+            failures
+        );
+    }
+}
+
+/// Render a single heatmap cell shaded by activity level
+fn heatmap_cell(count: usize, max_count: usize) -> String {
+    if count == 0 {
+        return "\u{00b7}".dimmed().to_string();
+    }
+    let level = (count * 4).div_ceil(max_count).clamp(1, 4);
+    let cell = ["\u{2591}", "\u{2592}", "\u{2593}", "\u{2588}"][level - 1];
+    cell.green().to_string()
+}
@@ -30,34 +30,9 @@ pub async fn run(scope: String) -> Result<()> {
     fs::create_dir_all(&hooks_dir)?;
 
     // Write hooks configuration
-    let hooks_config = r#"{
-  "description": "Daily Context Archive hooks for automatic session archiving",
-  "hooks": {
-    "SessionStart": [
-      {
-        "hooks": [
-          {
-            "type": "command",
-            "command": "daily hook session-start"
-          }
-        ]
-      }
-    ],
-    "SessionEnd": [
-      {
-        "hooks": [
-          {
-            "type": "command",
-            "command": "daily hook session-end"
-          }
-        ]
-      }
-    ]
-  }
-}
-"#;
+    let hooks_config = hooks_file_content()?;
 
-    let hooks_file = hooks_dir.join("daily-hooks.json");
+    let hooks_file = hooks_dir.join(HOOKS_FILE);
     fs::write(&hooks_file, hooks_config)?;
     println!("[daily] Hooks installed: {}", hooks_file.display());
 
@@ -249,34 +224,9 @@ pub async fn run_hooks_only(scope: String) -> Result<()> {
     fs::create_dir_all(&hooks_dir)?;
 
     // Write hooks configuration
-    let hooks_config = r#"{
-  "description": "Daily Context Archive hooks for automatic session archiving",
-  "hooks": {
-    "SessionStart": [
-      {
-        "hooks": [
-          {
-            "type": "command",
-            "command": "daily hook session-start"
-          }
-        ]
-      }
-    ],
-    "SessionEnd": [
-      {
-        "hooks": [
-          {
-            "type": "command",
-            "command": "daily hook session-end"
-          }
-        ]
-      }
-    ]
-  }
-}
-"#;
+    let hooks_config = hooks_file_content()?;
 
-    let hooks_file = hooks_dir.join("daily-hooks.json");
+    let hooks_file = hooks_dir.join(HOOKS_FILE);
     fs::write(&hooks_file, hooks_config)?;
     println!("[daily] Hooks installed: {}", hooks_file.display());
 
@@ -317,29 +267,49 @@ pub async fn run_hooks_only(scope: String) -> Result<()> {
     Ok(())
 }
 
+/// Hooks configuration file written to `{scope}/.claude/hooks/`
+pub(crate) const HOOKS_FILE: &str = "daily-hooks.json";
+
+/// Slash command files written to `{scope}/.claude/commands/`
+pub(crate) const COMMAND_FILES: &[&str] = &[
+    "daily-view.md",
+    "daily-get-skill.md",
+    "daily-get-command.md",
+];
+
+/// Claude Code hook events wired by install, with the daily command each runs
+pub(crate) const DAILY_HOOKS: &[(&str, &str)] = &[
+    ("SessionStart", "daily hook session-start"),
+    ("SessionEnd", "daily hook session-end"),
+    ("PreCompact", "daily hook pre-compact"),
+    ("Stop", "daily hook stop"),
+    ("UserPromptSubmit", "daily hook user-prompt-submit"),
+];
+
 /// Create the daily hooks configuration
 fn create_daily_hooks() -> Map<String, Value> {
     let mut hooks = Map::new();
-
-    let session_start_hook = json!([{
-        "hooks": [{
-            "type": "command",
-            "command": "daily hook session-start"
-        }]
-    }]);
-
-    let session_end_hook = json!([{
-        "hooks": [{
-            "type": "command",
-            "command": "daily hook session-end"
-        }]
-    }]);
-
-    hooks.insert("SessionStart".to_string(), session_start_hook);
-    hooks.insert("SessionEnd".to_string(), session_end_hook);
+    for (event_name, command) in DAILY_HOOKS {
+        let hook = json!([{
+            "hooks": [{
+                "type": "command",
+                "command": command
+            }]
+        }]);
+        hooks.insert(event_name.to_string(), hook);
+    }
     hooks
 }
 
+/// Content of `hooks/daily-hooks.json`
+fn hooks_file_content() -> Result<String> {
+    let config = json!({
+        "description": "Daily Context Archive hooks for automatic session archiving",
+        "hooks": create_daily_hooks()
+    });
+    Ok(serde_json::to_string_pretty(&config)? + "\n")
+}
+
 /// Check if a hook array already contains the daily hook command
 fn has_daily_hook(hooks_array: &[Value], command: &str) -> bool {
     for hook_entry in hooks_array {
@@ -368,10 +338,8 @@ fn merge_hooks(settings: &mut Value, daily_hooks: &Map<String, Value>) -> bool {
     let hooks = settings["hooks"].as_object_mut().unwrap();
 
     for (event_name, daily_hook_value) in daily_hooks {
-        let command = match event_name.as_str() {
-            "SessionStart" => "daily hook session-start",
-            "SessionEnd" => "daily hook session-end",
-            _ => continue,
+        let Some((_, command)) = DAILY_HOOKS.iter().find(|(name, _)| name == event_name) else {
+            continue;
         };
 
         if let Some(existing) = hooks.get_mut(event_name) {
@@ -1,54 +1,96 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 
+use crate::archive::retention::format_bytes;
+use crate::cli::output::{self, print_json, Table};
 use crate::config::load_config;
-use crate::jobs::{JobManager, JobStatus};
+use crate::i18n::{self, t};
+use crate::jobs::orphans::Orphan;
+use crate::jobs::{JobManager, JobStatus, JobType, PrunedJob};
+use crate::server::handlers::build_job_dtos;
+use crate::usage::overhead;
 
 /// List all jobs
-pub async fn list(all: bool) -> Result<()> {
+pub async fn list(all: bool, json: bool) -> Result<()> {
     let config = load_config()?;
+    if json {
+        return print_json(&build_job_dtos(&config, all)?);
+    }
+
     let manager = JobManager::new(&config)?;
 
     let jobs = manager.list(all)?;
 
     if jobs.is_empty() {
         if all {
-            println!("No jobs found.");
+            println!("{}", t("jobs.none"));
         } else {
-            println!("No running jobs. Use --all to see completed jobs.");
+            println!("{}", t("jobs.none_running"));
         }
         return Ok(());
     }
 
-    // Print header
-    println!(
-        "{:<28} {:<12} {:<20} {:<10}",
-        "ID".bold(),
-        "STATUS".bold(),
-        "TASK".bold(),
-        "ELAPSED".bold()
-    );
-    println!("{}", "-".repeat(72));
-
-    for job in jobs {
-        let status_str = match &job.status {
-            JobStatus::Running => "Running".green().to_string(),
-            JobStatus::Completed => "Completed".blue().to_string(),
-            JobStatus::Failed { .. } => "Failed".red().to_string(),
+    let mut table = Table::new(&[
+        t("jobs.id"),
+        t("jobs.status"),
+        t("jobs.task"),
+        t("jobs.elapsed"),
+        t("jobs.cost"),
+    ])
+    .shrink(2)
+    .shrink(0)
+    .align_right(4);
+    for job in &jobs {
+        let status = match &job.status {
+            JobStatus::Queued if !job.waiting_on(&jobs).is_empty() => t("jobs.waiting").yellow(),
+            JobStatus::Queued => t("jobs.queued").yellow(),
+            JobStatus::Running => t("jobs.running").green(),
+            JobStatus::Completed if job.quality.as_ref().is_some_and(|q| q.low) => {
+                t("jobs.low_quality").yellow()
+            }
+            JobStatus::Completed => t("jobs.completed").blue(),
+            JobStatus::Failed { .. } if job.timed_out => t("jobs.timed_out").red(),
+            JobStatus::Failed { .. } => t("jobs.failed").red(),
         };
 
-        let task_display = if job.task_name.len() > 18 {
-            format!("{}...", &job.task_name[..15])
-        } else {
-            job.task_name.clone()
-        };
+        let cost = job
+            .usage
+            .as_ref()
+            .and_then(|usage| usage.cost_usd)
+            .map(|cost| format!("${:.4}", cost))
+            .unwrap_or_else(|| "-".into());
+
+        table.row(vec![
+            job.id.normal(),
+            status,
+            job.task_name.normal(),
+            job.elapsed_human().normal(),
+            cost.normal(),
+        ]);
+    }
+    table.print();
 
+    let overhead = overhead::summarize(&jobs, None);
+    if overhead.totals.jobs > 0 {
+        println!();
+        let totals = &overhead.totals;
         println!(
-            "{:<28} {:<12} {:<20} {:<10}",
-            job.id,
-            status_str,
-            task_display,
-            job.elapsed_human()
+            "{}",
+            i18n::fmt(
+                "jobs.overhead",
+                &[
+                    ("cost", &format!("${:.4}", totals.total_cost_usd)),
+                    ("jobs", &totals.jobs),
+                    (
+                        "input",
+                        &(totals.input_tokens
+                            + totals.cache_creation_tokens
+                            + totals.cache_read_tokens),
+                    ),
+                    ("output", &totals.output_tokens),
+                ],
+            )
         );
     }
 
@@ -82,9 +124,34 @@ pub async fn log(job_id: String, tail: Option<usize>, follow: bool) -> Result<()
             finished.format("%Y-%m-%d %H:%M:%S")
         );
     }
-    println!("{}", "-".repeat(50));
+    if let Some(usage) = &job.usage {
+        println!(
+            "{} {} in / {} out over {} call(s){}",
+            "Tokens:".bold(),
+            usage.total_input_tokens(),
+            usage.output_tokens,
+            usage.calls,
+            usage
+                .cost_usd
+                .map(|cost| format!(" (${:.4})", cost))
+                .unwrap_or_default()
+        );
+    }
+    if let Some(quality) = &job.quality {
+        let score = format!("{}/100", quality.score);
+        let score = if quality.low {
+            format!("{} (low)", score).yellow().to_string()
+        } else {
+            score
+        };
+        println!("{} {}", "Quality:".bold(), score);
+        for issue in &quality.issues {
+            println!("  - {}", issue.dimmed());
+        }
+    }
+    println!("{}", output::rule('-', 50));
 
-    if follow && job.status == JobStatus::Running {
+    if follow && job.status.is_active() {
         // Follow mode - continuously read log
         follow_log(&manager, &job_id).await?;
     } else {
@@ -129,7 +196,7 @@ async fn follow_log(manager: &JobManager, job_id: &str) -> Result<()> {
     loop {
         // Check if job is still running
         if let Ok(job) = manager.load_job(job_id) {
-            if job.status != JobStatus::Running {
+            if !job.status.is_active() {
                 // Print remaining content and exit
                 if let Ok(file) = std::fs::File::open(&log_path) {
                     let mut reader = BufReader::new(file);
@@ -173,7 +240,7 @@ pub async fn kill(job_id: String) -> Result<()> {
 
     let job = manager.load_job(&job_id).context("Job not found")?;
 
-    if job.status != JobStatus::Running {
+    if !job.status.is_active() {
         println!(
             "{} Job {} is not running (status: {})",
             "Warning:".yellow(),
@@ -202,6 +269,116 @@ pub async fn kill(job_id: String) -> Result<()> {
     Ok(())
 }
 
+#[derive(Serialize)]
+struct KilledJob {
+    id: String,
+    job_type: String,
+    pid: u32,
+    killed: bool,
+}
+
+/// Kill every active job, or those of one type
+pub async fn killall(job_type: Option<String>, json: bool) -> Result<()> {
+    let job_type: Option<JobType> = job_type.as_deref().map(str::parse).transpose()?;
+    let config = load_config()?;
+    let manager = JobManager::new(&config)?;
+    let killed: Vec<KilledJob> = manager
+        .kill_all(job_type.as_ref())?
+        .into_iter()
+        .map(|(job, killed)| KilledJob {
+            id: job.id,
+            job_type: job.job_type.key().to_string(),
+            pid: job.pid,
+            killed,
+        })
+        .collect();
+
+    if json {
+        return print_json(&killed);
+    }
+    if killed.is_empty() {
+        println!("No running jobs to kill.");
+        return Ok(());
+    }
+    for job in &killed {
+        if job.killed {
+            println!("  {} {} (PID: {})", "killed".green(), job.id, job.pid);
+        } else {
+            println!("  {} {} (PID: {})", "failed".red(), job.id, job.pid);
+        }
+    }
+    let count = killed.iter().filter(|job| job.killed).count();
+    println!(
+        "\n{} Killed {} of {} job(s)",
+        "✓".green(),
+        count,
+        killed.len()
+    );
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ReapReport {
+    dry_run: bool,
+    orphans: Vec<Orphan>,
+    killed: Vec<u32>,
+}
+
+/// Kill processes left running without a job
+pub async fn reap(dry_run: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = JobManager::new(&config)?;
+    let orphans = manager.orphans()?;
+    let killed = if dry_run {
+        vec![]
+    } else {
+        manager.reap(&orphans)
+    };
+
+    if json {
+        return print_json(&ReapReport {
+            dry_run,
+            orphans,
+            killed,
+        });
+    }
+    if orphans.is_empty() {
+        println!("{} No orphaned processes", "✓".green());
+        return Ok(());
+    }
+    for orphan in &orphans {
+        let action = if dry_run {
+            "found".yellow()
+        } else if killed.contains(&orphan.pid) {
+            "killed".green()
+        } else {
+            "failed".red()
+        };
+        println!(
+            "  {} {} ({}): {}",
+            action,
+            orphan.pid,
+            orphan.reason,
+            orphan.command.dimmed()
+        );
+    }
+    if dry_run {
+        println!(
+            "\n{} {} orphaned process(es); run without --dry-run to kill them",
+            "!".yellow(),
+            orphans.len()
+        );
+    } else {
+        println!(
+            "\n{} Killed {} of {} orphaned process(es)",
+            "✓".green(),
+            killed.len(),
+            orphans.len()
+        );
+    }
+    Ok(())
+}
+
 /// Cleanup old jobs
 pub async fn cleanup(days: u32, dry_run: bool) -> Result<()> {
     let config = load_config()?;
@@ -212,7 +389,7 @@ pub async fn cleanup(days: u32, dry_run: bool) -> Result<()> {
         let cutoff = chrono::Local::now() - chrono::Duration::days(days as i64);
         let to_remove: Vec<_> = jobs
             .iter()
-            .filter(|j| j.status != JobStatus::Running && j.started_at < cutoff)
+            .filter(|j| !j.status.is_active() && j.started_at < cutoff)
             .collect();
 
         if to_remove.is_empty() {
@@ -235,3 +412,52 @@ pub async fn cleanup(days: u32, dry_run: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[derive(Serialize)]
+struct JobsPruneReport {
+    dry_run: bool,
+    jobs: Vec<PrunedJob>,
+    reclaimed_bytes: u64,
+}
+
+/// Remove finished jobs past the `jobs.retention` limits
+pub async fn prune(dry_run: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = JobManager::new(&config)?;
+    let jobs = manager.plan_prune(&config.jobs.retention)?;
+    let reclaimed = if dry_run {
+        jobs.iter().map(|job| job.bytes).sum()
+    } else {
+        manager.prune(&jobs)
+    };
+
+    if json {
+        return print_json(&JobsPruneReport {
+            dry_run,
+            jobs,
+            reclaimed_bytes: reclaimed,
+        });
+    }
+
+    if jobs.is_empty() {
+        println!("{} No jobs past the retention limits", "✓".green());
+        return Ok(());
+    }
+    for job in &jobs {
+        println!(
+            "  {} {} ({}, {})",
+            if dry_run { "would remove" } else { "removed" }.dimmed(),
+            job.id,
+            job.reason,
+            format_bytes(job.bytes)
+        );
+    }
+    println!(
+        "\n{} {} {} job(s), {}",
+        "✓".green(),
+        if dry_run { "Would remove" } else { "Removed" },
+        jobs.len(),
+        format_bytes(reclaimed).bright_yellow()
+    );
+    Ok(())
+}
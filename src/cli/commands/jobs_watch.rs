@@ -0,0 +1,393 @@
+//! `daily jobs watch`: a live view of the background jobs.
+//!
+//! The table is built from the same job DTOs the dashboard's jobs page
+//! gets from `/api/jobs`, plus each job's last log line, and is redrawn
+//! every refresh interval or key press. Enter opens the selected job's log,
+//! following it as it grows; `x` kills the selected job after a y/n prompt.
+
+use anyhow::Result;
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    terminal::{self, ClearType},
+};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::config::{load_config, Config};
+use crate::jobs::JobManager;
+use crate::server::dto::JobDto;
+use crate::server::handlers::build_job_dtos;
+
+/// Log lines searched backwards for the last non-blank one
+const LAST_LINE_LOOKBACK: usize = 5;
+
+const HELP: &str = "↑/↓ select · enter log · x kill · a all · q quit";
+const LOG_HELP: &str = "esc back · x kill · q quit";
+
+/// Show the live job table until the user quits
+pub async fn watch(all: bool, interval_secs: u64) -> Result<()> {
+    let config = load_config()?;
+    let interval = Duration::from_secs(interval_secs.max(1));
+    tokio::task::spawn_blocking(move || run(&config, all, interval)).await?
+}
+
+fn run(config: &Config, all: bool, interval: Duration) -> Result<()> {
+    let manager = JobManager::new(config)?;
+    let mut state = Watch {
+        all,
+        ..Default::default()
+    };
+    let _terminal = RawTerminal::enter()?;
+    let mut stdout = io::stdout();
+    let mut refreshed: Option<Instant> = None;
+
+    loop {
+        if refreshed.is_none_or(|at| at.elapsed() >= interval) {
+            state.refresh(config, &manager);
+            refreshed = Some(Instant::now());
+        }
+        let (width, height) = terminal::size()
+            .ok()
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .unwrap_or((100, 30));
+        let lines = state.render(&manager, width as usize, height as usize);
+        queue!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(ClearType::All)
+        )?;
+        write!(stdout, "{}", lines.join("\r\n"))?;
+        stdout.flush()?;
+
+        let wait = interval.saturating_sub(refreshed.map(|at| at.elapsed()).unwrap_or_default());
+        if !event::poll(wait)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !state.handle_key(key, &manager) {
+                return Ok(());
+            }
+            // Show the effect of the key (a kill, the other job list) at once
+            refreshed = None;
+        }
+    }
+}
+
+/// Raw mode on the alternate screen, restored when dropped
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[derive(Default)]
+enum Mode {
+    #[default]
+    Table,
+    /// Following the log of a job
+    Log(String),
+    /// Waiting for y/n before killing a job
+    ConfirmKill(String),
+}
+
+struct Row {
+    job: JobDto,
+    last_line: String,
+}
+
+#[derive(Default)]
+struct Watch {
+    all: bool,
+    rows: Vec<Row>,
+    selected: usize,
+    mode: Mode,
+    /// Result of the last action, shown in the footer
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl Watch {
+    fn refresh(&mut self, config: &Config, manager: &JobManager) {
+        let selected_id = self.selected_job().map(|job| job.id.clone());
+        match build_job_dtos(config, self.all) {
+            Ok(jobs) => {
+                self.rows = jobs
+                    .into_iter()
+                    .map(|job| {
+                        let last_line = last_log_line(manager, &job.id);
+                        Row { job, last_line }
+                    })
+                    .collect();
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        // Keep the same job selected as rows come and go
+        if let Some(index) =
+            selected_id.and_then(|id| self.rows.iter().position(|row| row.job.id == id))
+        {
+            self.selected = index;
+        }
+        self.move_selection(0);
+    }
+
+    fn selected_job(&self) -> Option<&JobDto> {
+        self.rows.get(self.selected).map(|row| &row.job)
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Apply a key press; false once the user quits
+    fn handle_key(&mut self, key: KeyEvent, manager: &JobManager) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+        match std::mem::take(&mut self.mode) {
+            Mode::ConfirmKill(job_id) => {
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.message = Some(match manager.kill(&job_id) {
+                        Ok(true) => format!("Killed job {}", job_id),
+                        Ok(false) => format!("Job {} is not running", job_id),
+                        Err(e) => format!("Failed to kill {}: {}", job_id, e),
+                    });
+                } else {
+                    self.message = None;
+                }
+            }
+            Mode::Log(job_id) => match key.code {
+                KeyCode::Char('q') => return false,
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Left => {}
+                KeyCode::Char('x') => self.mode = Mode::ConfirmKill(job_id),
+                _ => self.mode = Mode::Log(job_id),
+            },
+            Mode::Table => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+                KeyCode::Char('a') => {
+                    self.all = !self.all;
+                    self.selected = 0;
+                }
+                KeyCode::Enter | KeyCode::Char('l') => {
+                    if let Some(job) = self.selected_job() {
+                        self.mode = Mode::Log(job.id.clone());
+                    }
+                }
+                KeyCode::Char('x') => {
+                    if let Some(job) = self.selected_job() {
+                        self.mode = Mode::ConfirmKill(job.id.clone());
+                    }
+                }
+                _ => {}
+            },
+        }
+        true
+    }
+
+    fn render(&self, manager: &JobManager, width: usize, height: usize) -> Vec<String> {
+        let mut lines = match &self.mode {
+            Mode::Log(job_id) => self.render_log(manager, job_id, width, height),
+            _ => self.render_table(width, height),
+        };
+        let footer = if let Mode::ConfirmKill(job_id) = &self.mode {
+            format!("Kill job {}? (y/n)", job_id)
+                .red()
+                .bold()
+                .to_string()
+        } else if let Some(error) = &self.error {
+            fit(error, width).red().to_string()
+        } else if let Some(message) = &self.message {
+            fit(message, width).yellow().to_string()
+        } else {
+            String::new()
+        };
+        lines.truncate(height.saturating_sub(1));
+        lines.push(footer);
+        lines
+    }
+
+    fn render_table(&self, width: usize, height: usize) -> Vec<String> {
+        let title = format!(
+            "daily jobs · {} · {}",
+            if self.all { "all" } else { "active" },
+            HELP
+        );
+        let mut lines = vec![
+            fit(&title, width).bold().to_string(),
+            format!(
+                "  {:<28} {:<10} {:<20} {:<8} {}",
+                "ID", "STATUS", "TASK", "ELAPSED", "LAST LOG"
+            )
+            .bold()
+            .to_string(),
+        ];
+        if self.rows.is_empty() {
+            lines.push(
+                if self.all {
+                    "  No jobs found."
+                } else {
+                    "  No running jobs. Press a to see completed jobs."
+                }
+                .dimmed()
+                .to_string(),
+            );
+            return lines;
+        }
+
+        // Scroll so the selected row stays on screen
+        let visible = height.saturating_sub(lines.len() + 1).max(1);
+        let first = self.selected.saturating_sub(visible - 1);
+        for (i, row) in self.rows.iter().enumerate().skip(first).take(visible) {
+            let job = &row.job;
+            let status = if !job.waiting_on.is_empty() {
+                "Waiting"
+            } else {
+                match job.status_type.as_str() {
+                    "queued" => "Queued",
+                    "running" => "Running",
+                    "completed" => "Completed",
+                    _ if job.timed_out => "Timed out",
+                    _ => "Failed",
+                }
+            };
+            let status_cell = format!("{:<10}", status);
+            let status_cell = match job.status_type.as_str() {
+                "queued" => status_cell.yellow(),
+                "running" => status_cell.green(),
+                "completed" => status_cell.blue(),
+                _ => status_cell.red(),
+            };
+            let fixed = format!("{:<28} ", fit(&job.id, 28));
+            let rest = format!(
+                " {:<20} {:<8} ",
+                fit(&job.task_name, 20),
+                fit(&job.elapsed, 8)
+            );
+            let used = 2 + fixed.chars().count() + 10 + rest.chars().count();
+            let last_line = fit(&row.last_line, width.saturating_sub(used)).dimmed();
+            let marker = if i == self.selected { "›" } else { " " };
+            lines.push(format!(
+                "{} {}{}{}{}",
+                marker.bold(),
+                fixed,
+                status_cell,
+                rest,
+                last_line
+            ));
+        }
+        lines
+    }
+
+    fn render_log(
+        &self,
+        manager: &JobManager,
+        job_id: &str,
+        width: usize,
+        height: usize,
+    ) -> Vec<String> {
+        let status = manager
+            .load_job(job_id)
+            .map(|job| job.status.to_string())
+            .unwrap_or_else(|_| "unknown".into());
+        let title = format!("{} ({}) · {}", job_id, status, LOG_HELP);
+        let mut lines = vec![fit(&title, width).bold().to_string()];
+        let rows = height.saturating_sub(2);
+        match manager.read_log(job_id, Some(rows)) {
+            Ok(content) if !content.is_empty() => {
+                lines.extend(content.lines().map(|line| fit(line, width)));
+            }
+            Ok(_) => lines.push("(no log output)".dimmed().to_string()),
+            Err(_) => lines.push("(log file not found)".dimmed().to_string()),
+        }
+        lines
+    }
+}
+
+/// Last non-blank line of a job's log
+fn last_log_line(manager: &JobManager, job_id: &str) -> String {
+    manager
+        .read_log(job_id, Some(LAST_LINE_LOOKBACK))
+        .ok()
+        .and_then(|log| {
+            log.lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string)
+        })
+        .unwrap_or_default()
+}
+
+/// `text` cut to `width` characters, ending in "…" when cut
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_selection() {
+        assert_eq!(fit("digest-2024-01-15", 20), "digest-2024-01-15");
+        assert_eq!(fit("digest-2024-01-15", 8), "digest-…");
+        assert_eq!(fit("abc", 0), "");
+
+        let mut watch = Watch::default();
+        watch.move_selection(1);
+        assert_eq!(watch.selected, 0);
+        watch.rows = (0..3)
+            .map(|i| Row {
+                job: JobDto {
+                    id: format!("job-{}", i),
+                    pid: 0,
+                    task_name: "task".into(),
+                    status: "Running".into(),
+                    status_type: "running".into(),
+                    job_type: "manual".into(),
+                    started_at: String::new(),
+                    finished_at: None,
+                    elapsed: "1s".into(),
+                    queue_position: None,
+                    depends_on: vec![],
+                    waiting_on: vec![],
+                    usage: None,
+                    quality: None,
+                    timed_out: false,
+                },
+                last_line: String::new(),
+            })
+            .collect();
+        watch.move_selection(5);
+        assert_eq!(watch.selected, 2);
+        watch.move_selection(-1);
+        assert_eq!(watch.selected_job().map(|j| j.id.as_str()), Some("job-1"));
+        watch.move_selection(-4);
+        assert_eq!(watch.selected, 0);
+    }
+}
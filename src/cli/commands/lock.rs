@@ -0,0 +1,60 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::locks::DayLocks;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Lock or unlock a date, or list locked dates when none is given
+pub async fn run(date: Option<String>, remove: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut locks = DayLocks::load(&config)?;
+
+    let Some(date) = date else {
+        if json {
+            return print_json(&locks.dates);
+        }
+        if locks.dates.is_empty() {
+            println!("No locked days. Lock one with: daily lock YYYY-MM-DD");
+            return Ok(());
+        }
+        println!("{}", "Locked days:".cyan().bold());
+        for (date, at) in locks.dates.iter().rev() {
+            println!(
+                "  {} {}",
+                date,
+                format!("locked {}", at.format("%Y-%m-%d %H:%M")).dimmed()
+            );
+        }
+        return Ok(());
+    };
+
+    let changed = if remove {
+        locks.unlock(&date)
+    } else {
+        locks.lock(&date)?
+    };
+    if changed {
+        locks.save(&config)?;
+    }
+
+    if json {
+        return print_json(&serde_json::json!({
+            "date": date,
+            "locked": !remove,
+        }));
+    }
+    match (remove, changed) {
+        (false, true) => {
+            println!("{} Locked {}", "✓".green(), date);
+            println!(
+                "  {} Digest runs for this day now need --force; new sessions are not archived under it",
+                "→".cyan()
+            );
+        }
+        (false, false) => println!("{} {} was already locked", "!".yellow(), date),
+        (true, true) => println!("{} Unlocked {}", "✓".green(), date),
+        (true, false) => println!("{} {} was not locked", "!".yellow(), date),
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::schema::SCHEMA_VERSION;
+use crate::archive::ArchiveManager;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Upgrade every archive file to the current `schema_version`. Files are
+/// also upgraded one by one when read, so this is only needed to convert
+/// the whole archive up front.
+pub async fn run(dry_run: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config.clone());
+    let migrated = manager.migrate_schema(dry_run)?;
+    if json {
+        return print_json(&migrated);
+    }
+
+    let root = config.storage_path();
+    for file in &migrated {
+        println!(
+            "  {} {} (v{} -> v{})",
+            if dry_run {
+                "would upgrade".dimmed()
+            } else {
+                "upgrade".dimmed()
+            },
+            file.path
+                .strip_prefix(&root)
+                .unwrap_or(&file.path)
+                .display(),
+            file.from,
+            SCHEMA_VERSION
+        );
+        for step in &file.steps {
+            println!("      - {}", step);
+        }
+    }
+
+    if dry_run {
+        println!(
+            "\n{} Dry run: {} file(s) would be upgraded to schema v{}",
+            "ℹ".blue(),
+            migrated.len(),
+            SCHEMA_VERSION
+        );
+    } else {
+        println!(
+            "{} Upgraded {} file(s) to schema v{}",
+            "✓".green(),
+            migrated.len(),
+            SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::layout;
+use crate::config::{load_config, save_config};
+use crate::storage::{self, Change};
+
+/// Move date folders from one `archive.directory_layout` to another.
+/// `from` defaults to the configured layout; `to` is saved to the config once
+/// the move succeeds, so archives and config never disagree.
+pub async fn run(from: Option<String>, to: Option<String>, dry_run: bool) -> Result<()> {
+    let mut config = load_config()?;
+    let from = from.unwrap_or_else(|| config.archive.directory_layout.clone());
+    let to = to.unwrap_or_else(|| config.archive.directory_layout.clone());
+
+    let root = config.storage_path();
+    let moves = layout::plan_migration(&root, &from, &to)?;
+
+    for (source, target) in &moves {
+        println!(
+            "  {} {} -> {}",
+            if dry_run {
+                "would move".dimmed()
+            } else {
+                "move".dimmed()
+            },
+            source.strip_prefix(&root).unwrap_or(source).display(),
+            target.strip_prefix(&root).unwrap_or(target).display()
+        );
+    }
+
+    if dry_run {
+        println!(
+            "\n{} Dry run: {} date folder(s) would move from '{}' to '{}'",
+            "ℹ".blue(),
+            moves.len(),
+            from,
+            to
+        );
+        return Ok(());
+    }
+
+    // Files as they were before the move, to queue for the storage backend
+    let moved: Vec<_> = moves
+        .iter()
+        .map(|(source, target)| (storage::files_under(&config, source), source, target))
+        .collect();
+    layout::migrate(&root, &moves)?;
+    for (files, source, target) in moved {
+        for old in files {
+            let Ok(relative) = old.strip_prefix(source) else {
+                continue;
+            };
+            storage::record(&config, &old, Change::Delete);
+            storage::record(&config, &target.join(relative), Change::Write);
+        }
+    }
+
+    if config.archive.directory_layout != to {
+        config.archive.directory_layout = to.clone();
+        save_config(&config)?;
+    }
+
+    println!(
+        "{} Moved {} date folder(s); archive.directory_layout = '{}'",
+        "✓".green(),
+        moves.len(),
+        to
+    );
+    Ok(())
+}
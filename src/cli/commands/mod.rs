@@ -1,14 +1,46 @@
+pub mod anomalies;
+pub mod ask;
+pub mod backfill;
+pub mod backup;
 pub mod config;
+pub mod daemon;
+pub mod decisions;
 pub mod digest;
+pub mod doctor;
+pub mod export;
 pub mod extract;
+pub mod focus;
+pub mod goal;
+pub mod import;
 pub mod init;
 pub mod insights;
 pub mod install;
 pub mod jobs;
+pub mod jobs_watch;
+pub mod lock;
+pub mod migrate;
+pub mod migrate_layout;
+pub mod note;
+pub mod pin;
+pub mod pricing;
+pub mod prune;
+pub mod publish;
+pub mod queue;
+pub mod rate;
+pub mod related;
+pub mod rename;
+pub mod share;
 pub mod show;
 pub mod skills;
+pub mod stats;
+pub mod statusline;
+pub mod storage;
 pub mod summarize;
+pub mod templates;
+pub mod todo;
+pub mod topics;
 pub mod trash;
 pub mod uninstall;
 pub mod update;
+pub mod usage;
 pub mod view;
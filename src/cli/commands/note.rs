@@ -0,0 +1,33 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+
+use crate::archive::{notes, ArchiveManager};
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Append a note to a day's daily.md
+pub async fn run(text: String, date: Option<String>, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let now = Local::now();
+    let date = date.unwrap_or_else(|| now.format("%Y-%m-%d").to_string());
+    let manager = ArchiveManager::new(config);
+
+    let note = notes::add(&manager, &date, &text, now)?;
+    if json {
+        return print_json(&serde_json::json!({
+            "date": date,
+            "time": note.time,
+            "text": note.text,
+        }));
+    }
+    println!("{} Noted on {} at {}", "✓".green(), date, note.time);
+    if manager.has_digest(&date) {
+        println!(
+            "  {} Run `daily digest --regenerate --date {}` to work it into the digest",
+            "→".cyan(),
+            date
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::ArchiveManager;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::server::handlers::build_pinned_dtos;
+
+/// Pin or unpin a session, or list pinned sessions when no target is given
+pub async fn run(target: Option<String>, remove: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config);
+
+    let Some(target) = target else {
+        return list_pinned(&manager, json);
+    };
+
+    let (date, name) = parse_session_ref(&target)?;
+    manager.set_pinned(date, name, !remove)?;
+
+    if json {
+        return print_json(&serde_json::json!({
+            "date": date,
+            "name": name,
+            "pinned": !remove,
+        }));
+    }
+    if remove {
+        println!("{} Unpinned {}/{}", "✓".green(), date, name);
+    } else {
+        println!("{} Pinned {}/{}", "✓".green(), date, name);
+    }
+    Ok(())
+}
+
+fn list_pinned(manager: &ArchiveManager, json: bool) -> Result<()> {
+    let pinned = build_pinned_dtos(manager)?;
+    if json {
+        return print_json(&pinned);
+    }
+
+    if pinned.is_empty() {
+        println!("No pinned sessions. Pin one with: daily pin YYYY-MM-DD/session-name");
+        return Ok(());
+    }
+
+    println!("{}", "Pinned sessions:".cyan().bold());
+    println!();
+    for item in &pinned {
+        println!(
+            "  {} {}/{}  {}",
+            "★".yellow(),
+            item.date.dimmed(),
+            item.session.name,
+            item.session.title.dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Split `YYYY-MM-DD/session-name` (a trailing `.md` is allowed)
+pub(crate) fn parse_session_ref(target: &str) -> Result<(&str, &str)> {
+    let (date, name) = target
+        .split_once('/')
+        .filter(|(date, name)| !date.is_empty() && !name.is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid session '{}'. Use format: YYYY-MM-DD/session-name",
+                target
+            )
+        })?;
+    Ok((date, name.strip_suffix(".md").unwrap_or(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_ref() {
+        assert_eq!(
+            parse_session_ref("2024-01-15/fix-bug").unwrap(),
+            ("2024-01-15", "fix-bug")
+        );
+        assert_eq!(
+            parse_session_ref("2024-01-15/fix-bug.md").unwrap(),
+            ("2024-01-15", "fix-bug")
+        );
+        assert!(parse_session_ref("fix-bug").is_err());
+        assert!(parse_session_ref("2024-01-15/").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::usage::pricing::{ModelPricing, PricingData, PricingSource};
+
+/// `--json` output of `daily pricing show`
+#[derive(Serialize)]
+struct PricingStatus<'a> {
+    source: PricingSource,
+    fetched_at: Option<DateTime<Utc>>,
+    stale: bool,
+    models: usize,
+    cache_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<ModelRates<'a>>,
+}
+
+#[derive(Serialize)]
+struct ModelRates<'a> {
+    query: &'a str,
+    /// Dataset entry the query resolved to (None = unknown, costs are $0)
+    matched: Option<&'a str>,
+    /// Whether the rates come from `usage.price_overrides`
+    is_override: bool,
+    pricing: Option<&'a ModelPricing>,
+}
+
+/// Fetch the LiteLLM dataset now and replace the cache
+pub async fn refresh(json: bool) -> Result<()> {
+    let pricing = PricingData::refresh().await?;
+    if json {
+        return print_json(&status(&pricing, None));
+    }
+    println!(
+        "{} Fetched pricing for {} models",
+        "✓".green(),
+        pricing.model_count()
+    );
+    if let Some(path) = PricingData::cache_path() {
+        println!("  {} {}", "Cache:".bold(), path.display());
+    }
+    Ok(())
+}
+
+/// Show the pricing data in use, without fetching
+pub fn show(model: Option<String>, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let pricing = PricingData::load_offline().with_overrides(&config.usage.price_overrides);
+    let status = status(&pricing, model.as_deref());
+    if json {
+        return print_json(&status);
+    }
+
+    let source = match status.source {
+        PricingSource::Live => "LiteLLM (live)",
+        PricingSource::Cache => "LiteLLM (cached)",
+        PricingSource::Embedded => "built-in snapshot",
+    };
+    println!("{} {}", "Source:".bold(), source);
+    match status.fetched_at {
+        Some(at) => {
+            let days = (Utc::now() - at).num_days();
+            let fetched = format!(
+                "{} ({} days ago)",
+                at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                days
+            );
+            if status.stale {
+                println!(
+                    "{} {} — run `daily pricing refresh`",
+                    "Fetched:".bold(),
+                    fetched.yellow()
+                );
+            } else {
+                println!("{} {}", "Fetched:".bold(), fetched);
+            }
+        }
+        None => println!("{} never — run `daily pricing refresh`", "Fetched:".bold()),
+    }
+    println!("{} {}", "Models:".bold(), status.models);
+
+    let Some(rates) = status.model else {
+        return Ok(());
+    };
+    println!();
+    let (Some(matched), Some(pricing)) = (rates.matched, rates.pricing) else {
+        println!(
+            "{} No pricing for '{}'; its usage is counted as $0",
+            "!".yellow(),
+            rates.query
+        );
+        return Ok(());
+    };
+    let origin = if rates.is_override {
+        " [usage.price_overrides]"
+    } else {
+        ""
+    };
+    if matched == rates.query {
+        println!("{} {}{}", "Model:".bold(), matched, origin);
+    } else {
+        println!(
+            "{} {} (matched {}){}",
+            "Model:".bold(),
+            rates.query,
+            matched,
+            origin
+        );
+    }
+    println!(
+        "  {:<14} {:>12} {:>16}",
+        "$ / 1M tokens".dimmed(),
+        "base",
+        "above 200k"
+    );
+    for (label, base, tiered) in [
+        (
+            "Input",
+            pricing.input_cost_per_token,
+            pricing.input_cost_per_token_above_200k_tokens,
+        ),
+        (
+            "Output",
+            pricing.output_cost_per_token,
+            pricing.output_cost_per_token_above_200k_tokens,
+        ),
+        (
+            "Cache write",
+            pricing.cache_creation_input_token_cost,
+            pricing.cache_creation_input_token_cost_above_200k_tokens,
+        ),
+        (
+            "Cache read",
+            pricing.cache_read_input_token_cost,
+            pricing.cache_read_input_token_cost_above_200k_tokens,
+        ),
+    ] {
+        println!(
+            "  {:<14} {:>12} {:>16}",
+            label,
+            per_million(base),
+            per_million(tiered)
+        );
+    }
+    Ok(())
+}
+
+fn status<'a>(pricing: &'a PricingData, model: Option<&'a str>) -> PricingStatus<'a> {
+    PricingStatus {
+        source: pricing.source(),
+        fetched_at: pricing.fetched_at(),
+        stale: pricing.is_stale(),
+        models: pricing.model_count(),
+        cache_path: PricingData::cache_path().map(|p| p.display().to_string()),
+        model: model.map(|query| {
+            let found = pricing.lookup(query);
+            ModelRates {
+                query,
+                matched: found.map(|(key, _)| key),
+                is_override: found.is_some_and(|(key, _)| pricing.is_override(key)),
+                pricing: found.map(|(_, pricing)| pricing),
+            }
+        }),
+    }
+}
+
+fn per_million(cost_per_token: Option<f64>) -> String {
+    cost_per_token
+        .map(|cost| format!("${:.2}", cost * 1_000_000.0))
+        .unwrap_or_else(|| "-".into())
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::archive::retention::{self, format_bytes, PruneAction, PruneItem};
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+#[derive(Serialize)]
+struct PruneReport {
+    dry_run: bool,
+    items: Vec<PruneItem>,
+    reclaimed_bytes: u64,
+}
+
+/// Apply the storage retention rules, reporting reclaimed space
+pub async fn run(dry_run: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let items = retention::plan(&config, Local::now().date_naive())?;
+
+    let mut reclaimed = 0;
+    let mut failed = 0;
+    for item in &items {
+        if dry_run {
+            reclaimed += item.bytes;
+        } else {
+            match retention::apply(&config, item) {
+                Ok(bytes) => reclaimed += bytes,
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("[daily] {}", e);
+                }
+            }
+        }
+        if !json {
+            let verb = match (item.action, dry_run) {
+                (PruneAction::Compress, true) => "would compress",
+                (PruneAction::Compress, false) => "compressed",
+                (PruneAction::Delete, true) => "would delete",
+                (PruneAction::Delete, false) => "deleted",
+            };
+            println!(
+                "  {} {:<10} {} ({})",
+                verb.dimmed(),
+                item.kind,
+                item.path.display(),
+                format_bytes(item.bytes)
+            );
+        }
+    }
+
+    if json {
+        return print_json(&PruneReport {
+            dry_run,
+            items,
+            reclaimed_bytes: reclaimed,
+        });
+    }
+
+    if items.is_empty() {
+        println!("{} Nothing to prune", "✓".green());
+    } else if dry_run {
+        println!(
+            "\n{} Dry run: {} file(s), up to {} would be reclaimed",
+            "ℹ".blue(),
+            items.len(),
+            format_bytes(reclaimed).bright_yellow()
+        );
+    } else {
+        println!(
+            "\n{} Pruned {} file(s), reclaimed {}",
+            if failed == 0 {
+                "✓".green()
+            } else {
+                "!".yellow()
+            },
+            items.len() - failed,
+            format_bytes(reclaimed).bright_yellow()
+        );
+    }
+
+    Ok(())
+}
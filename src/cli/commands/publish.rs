@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::publish;
+use crate::render::Theme;
+use crate::usage::pricing::PricingData;
+
+/// Write the archive as a static site to `out`
+pub async fn run(out: PathBuf, theme: Option<String>, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let theme: Theme = theme.unwrap_or(config.output.html_theme.clone()).parse()?;
+    let out = std::path::absolute(&out)
+        .with_context(|| format!("Invalid output directory {}", out.display()))?;
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+
+    let report = publish::publish(&config, &pricing, &out, theme)?;
+    if json {
+        return print_json(&report);
+    }
+    println!(
+        "{} Published {} dates, {} sessions and {} topics to {}",
+        "✓".green(),
+        report.dates,
+        report.sessions,
+        report.topics,
+        report.out.display()
+    );
+    if report.excluded > 0 {
+        println!(
+            "  {} {} sessions left out by publish.exclude_tags",
+            "!".yellow(),
+            report.excluded
+        );
+    }
+    println!(
+        "  {} Open {}",
+        "→".cyan(),
+        report.out.join("index.html").display()
+    );
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::process::Child;
+use tokio::task::JoinSet;
+
+use crate::cli::commands::backfill::job_outcome;
+use crate::cli::output::print_json;
+use crate::config::{load_config, Config};
+use crate::jobs::offline::{self, QueuedSummary};
+use crate::jobs::{self, JobCommand, JobManager, JobType};
+
+/// List summarizations waiting for Claude to be reachable
+pub async fn list(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let queued = offline::list(&config)?;
+
+    if json {
+        return print_json(&queued);
+    }
+
+    if queued.is_empty() {
+        println!("{} No summarizations waiting for Claude", "✓".green());
+        return Ok(());
+    }
+
+    println!(
+        "{} {} summarization(s) waiting for Claude:\n",
+        "Queued".bold(),
+        queued.len().to_string().bright_yellow()
+    );
+    for entry in &queued {
+        println!(
+            "  {} {} {}",
+            entry.archive_at.dimmed(),
+            entry.session_id,
+            entry.cwd.dimmed()
+        );
+        println!(
+            "    {} attempt(s), last {}: {}",
+            entry.attempts,
+            entry.last_attempt.format("%Y-%m-%d %H:%M"),
+            entry.error.red()
+        );
+    }
+    println!("\nRetry now with {}", "daily queue flush".cyan());
+    Ok(())
+}
+
+/// Outcome of retrying one queued summarization, for `--json`
+#[derive(Debug, Serialize)]
+struct FlushResult {
+    session_id: String,
+    job_id: Option<String>,
+    error: Option<String>,
+}
+
+/// Retry every queued summarization now and wait for the jobs to finish.
+/// Those that still can't reach Claude go back into the queue.
+pub async fn flush(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let queued = offline::list(&config)?;
+
+    if queued.is_empty() {
+        if json {
+            return print_json(&Vec::<FlushResult>::new());
+        }
+        println!("{} Nothing queued", "✓".green());
+        return Ok(());
+    }
+
+    if !json {
+        println!(
+            "{} Retrying {} queued summarization(s)...\n",
+            "→".cyan(),
+            queued.len()
+        );
+    }
+
+    let job_manager = JobManager::new(&config)?;
+    let total = queued.len();
+    let mut running = JoinSet::new();
+    let mut results = vec![];
+    for mut entry in queued {
+        match spawn_queued_job(&config, &mut entry, JobType::Manual) {
+            Ok((job_id, mut child)) => {
+                running.spawn_blocking(move || {
+                    let exit = child.wait();
+                    (entry, job_id, exit)
+                });
+            }
+            Err(e) => results.push(FlushResult {
+                session_id: entry.session_id,
+                job_id: None,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    while let Some(joined) = running.join_next().await {
+        let (entry, job_id, exit) = joined.context("Queue flush worker panicked")?;
+        let error = job_outcome(&job_manager, &job_id, exit).err();
+        results.push(FlushResult {
+            session_id: entry.session_id,
+            job_id: Some(job_id),
+            error,
+        });
+    }
+
+    if json {
+        return print_json(&results);
+    }
+
+    for (i, result) in results.iter().enumerate() {
+        match &result.error {
+            None => println!(
+                "  [{}/{}] {} {}",
+                i + 1,
+                total,
+                "✓".green(),
+                result.session_id
+            ),
+            Some(error) => println!(
+                "  [{}/{}] {} {} {}",
+                i + 1,
+                total,
+                "✗".red(),
+                result.session_id,
+                error.red()
+            ),
+        }
+    }
+    let remaining = offline::list(&config)?.len();
+    if remaining == 0 {
+        println!("\n{} Queue is empty", "✓".green());
+    } else {
+        println!(
+            "\n{} {} still waiting for Claude (daily queue)",
+            "!".yellow(),
+            remaining
+        );
+    }
+    Ok(())
+}
+
+/// Start background retries of queued summarizations whose
+/// `summarization.offline_retry_minutes` have passed, at most `limit` of
+/// them. Returns how many were started.
+pub(crate) fn retry_due(config: &Config, limit: Option<usize>) -> usize {
+    let retry_minutes = config.summarization.offline_retry_minutes;
+    if retry_minutes == 0 {
+        return 0;
+    }
+    let now = chrono::Local::now();
+    let due = match offline::list(config) {
+        Ok(queued) => queued.into_iter().filter(|e| e.is_due(now, retry_minutes)),
+        Err(e) => {
+            eprintln!("[daily] Failed to read offline queue: {}", e);
+            return 0;
+        }
+    };
+
+    let mut started = 0;
+    for mut entry in due.take(limit.unwrap_or(usize::MAX)) {
+        match spawn_queued_job(config, &mut entry, JobType::AutoSummarize) {
+            Ok((job_id, _)) => {
+                started += 1;
+                eprintln!(
+                    "[daily] Retrying queued summarization of {}: {}",
+                    entry.session_id, job_id
+                );
+            }
+            Err(e) => eprintln!(
+                "[daily] Failed to retry queued summarization of {}: {}",
+                entry.session_id, e
+            ),
+        }
+    }
+    started
+}
+
+/// Spawn a foreground summarize process for a queued request, archiving
+/// under its original date and time
+fn spawn_queued_job(
+    config: &Config,
+    entry: &mut QueuedSummary,
+    job_type: JobType,
+) -> Result<(String, Child)> {
+    offline::mark_attempt(config, entry)?;
+    // The queue lives in this storage, whatever the session's cwd resolves to now
+    let command = JobCommand::new(
+        job_type,
+        &entry.task_name,
+        &entry.transcript,
+        [
+            "summarize".to_string(),
+            "--transcript".to_string(),
+            entry.transcript.to_string_lossy().into_owned(),
+            "--task-name".to_string(),
+            entry.task_name.clone(),
+            "--cwd".to_string(),
+            entry.cwd.clone(),
+            "--archive-at".to_string(),
+            entry.archive_at.clone(),
+            "--foreground".to_string(),
+        ],
+    );
+    let (job_id, child) = jobs::spawn(config, command)?;
+    Ok((job_id, child))
+}
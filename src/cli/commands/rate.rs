@@ -0,0 +1,77 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use super::pin::parse_session_ref;
+use crate::archive::annotation::Annotation;
+use crate::archive::ArchiveManager;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Store the user's own outcome/satisfaction rating for a session
+pub async fn run(
+    target: String,
+    outcome: Option<String>,
+    satisfaction: Option<String>,
+    note: Option<String>,
+    clear: bool,
+    json: bool,
+) -> Result<()> {
+    let update = Annotation {
+        outcome,
+        satisfaction,
+        note,
+        stars: None,
+    };
+    if update.is_empty() && !clear {
+        anyhow::bail!("Nothing to rate. Pass --outcome, --satisfaction, --note or --clear");
+    }
+    save(&target, update, clear, json)
+}
+
+/// Store a star rating and comment for a session
+pub async fn feedback(
+    target: String,
+    stars: u8,
+    comment: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let update = Annotation {
+        stars: Some(stars),
+        note: comment,
+        ..Default::default()
+    };
+    save(&target, update, false, json)
+}
+
+fn save(target: &str, update: Annotation, clear: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config);
+    let (date, name) = parse_session_ref(target)?;
+    let annotation = manager.set_annotation(date, name, update, clear)?;
+
+    if json {
+        return print_json(&serde_json::json!({
+            "date": date,
+            "name": name,
+            "annotation": annotation,
+        }));
+    }
+    if annotation.is_empty() {
+        println!("{} Cleared rating for {}/{}", "✓".green(), date, name);
+        return Ok(());
+    }
+    println!("{} Rated {}/{}", "✓".green(), date, name);
+    if let Some(outcome) = &annotation.outcome {
+        println!("  Outcome:      {}", outcome);
+    }
+    if let Some(stars) = annotation.stars {
+        println!("  Stars:        {}", "★".repeat(stars as usize));
+    }
+    if let Some(satisfaction) = annotation.effective_satisfaction() {
+        println!("  Satisfaction: {}", satisfaction);
+    }
+    if let Some(note) = &annotation.note {
+        println!("  Note:         {}", note.dimmed());
+    }
+    Ok(())
+}
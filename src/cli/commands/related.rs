@@ -0,0 +1,60 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use super::pin::parse_session_ref;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::embeddings;
+
+/// Show sessions related to `target`, optionally refreshing the index first
+pub async fn run(target: Option<String>, limit: usize, reindex: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+
+    if reindex {
+        let stats = embeddings::update_index(&config).await?;
+        if !json {
+            println!(
+                "{} Indexed {} session(s), {} embedded this run",
+                "✓".green(),
+                stats.total,
+                stats.embedded
+            );
+        }
+    }
+
+    let Some(target) = target else {
+        if reindex {
+            return Ok(());
+        }
+        anyhow::bail!("Specify a session (YYYY-MM-DD/session-name) or use --reindex");
+    };
+
+    let (date, name) = parse_session_ref(&target)?;
+    let related = embeddings::find_related(&config, date, name, limit).await?;
+
+    if json {
+        return print_json(&related);
+    }
+
+    if related.is_empty() {
+        println!(
+            "No related sessions found. Run {} to index past sessions.",
+            "daily related --reindex".cyan()
+        );
+        return Ok(());
+    }
+
+    println!("{}", format!("Related to {}/{}:", date, name).cyan().bold());
+    println!();
+    for session in &related {
+        println!(
+            "  {} {}/{}  {}",
+            format!("{:.2}", session.score).dimmed(),
+            session.date.dimmed(),
+            session.name,
+            session.title.dimmed()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::ArchiveManager;
+use crate::cli::commands::pin::parse_session_ref;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::embeddings;
+
+/// Rename a session to a new topic
+pub async fn run(target: String, topic: String, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let manager = ArchiveManager::new(config.clone());
+
+    let (date, name) = parse_session_ref(&target)?;
+    let new_name = manager.rename_session(date, name, &topic)?;
+    if let Err(e) = embeddings::rename_session(&config, date, name, &new_name) {
+        eprintln!(
+            "[daily] Warning: Failed to update the embeddings index: {}",
+            e
+        );
+    }
+
+    if json {
+        return print_json(&serde_json::json!({
+            "date": date,
+            "old_name": name,
+            "name": new_name,
+        }));
+    }
+    println!("{} Renamed {}/{} to {}", "✓".green(), date, name, new_name);
+    Ok(())
+}
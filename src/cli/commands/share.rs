@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::publish::share;
+use crate::render::Theme;
+
+/// What a share wrote or uploaded, for `--json`
+#[derive(Debug, Serialize)]
+struct ShareResult {
+    date: String,
+    /// HTML file written, if any
+    path: Option<PathBuf>,
+    /// Link to the page: the upload's, or else the file's
+    link: String,
+}
+
+/// Render a date's digest as a single redacted HTML page, then write it to
+/// a file and/or upload it by `share.upload`
+pub async fn run(
+    date: Option<String>,
+    out: Option<PathBuf>,
+    upload: Option<String>,
+    theme: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let mut config = load_config()?;
+    if let Some(upload) = upload {
+        config.share.upload = upload;
+    }
+    let theme: Theme = theme.unwrap_or(config.output.html_theme.clone()).parse()?;
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    let html = share::page(&config, &date, theme)?;
+    let uploading = !config.share.upload.is_empty();
+
+    // Without an upload the page goes to a file in the current directory
+    let out = out.or_else(|| (!uploading).then(|| PathBuf::from(share::file_name(&date))));
+    let path = match out {
+        Some(out) => {
+            let path = std::path::absolute(&out)
+                .with_context(|| format!("Invalid output file {}", out.display()))?;
+            std::fs::write(&path, &html)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Some(path)
+        }
+        None => None,
+    };
+    let link = match &path {
+        Some(path) if !uploading => share::file_link(path),
+        _ => share::upload(&config, &date, &html).await?,
+    };
+
+    if json {
+        return print_json(&ShareResult { date, path, link });
+    }
+    if let Some(path) = &path {
+        println!("{} Wrote {}", "✓".green(), path.display());
+    }
+    if uploading {
+        println!(
+            "{} Uploaded the digest of {} ({})",
+            "✓".green(),
+            date,
+            config.share.upload
+        );
+    }
+    println!("  {} {}", "→".cyan(), link);
+    Ok(())
+}
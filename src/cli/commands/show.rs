@@ -1,24 +1,51 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::signal;
 
 use crate::auto_summarize::{
-    find_unsummarized_transcripts, should_trigger_auto_summarize,
+    find_unsummarized_transcripts, in_quiet_hours, should_trigger_auto_summarize,
     should_trigger_auto_summarize_on_show,
 };
-use crate::config::{load_config, save_config};
-use crate::server::{create_router, handlers::AppState};
+use crate::config::{active_profile, load_config, save_config};
+use crate::jobs::{self, JobCommand, JobInfo, JobManager, JobStatus, JobType};
+use crate::server::dto::WsMessage;
+use crate::server::router::normalize_base_path;
+use crate::server::{create_router, handlers::AppState, tls};
 use crate::usage::pricing::PricingData;
 
 const DEFAULT_PORT: u16 = 31456;
 const MAX_PORT_ATTEMPTS: u16 = 100;
 
+/// How long a stopping server waits for running jobs to finish
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(30);
+
 /// Run the web dashboard server
-pub async fn run(port: Option<u16>, host: String, open_browser: bool) -> Result<()> {
+pub async fn run(
+    port: Option<u16>,
+    host: String,
+    open_browser: bool,
+    base_path: Option<String>,
+    tls_cert: Option<PathBuf>,
+    tls_key: Option<PathBuf>,
+    daemon: bool,
+) -> Result<()> {
     let mut config = load_config()?;
 
+    // Pick up work interrupted by the last shutdown before looking for new work
+    match resume_interrupted_jobs(&config) {
+        Ok(0) => {}
+        Ok(count) => println!("{} {} interrupted job(s)", "Resuming".yellow(), count),
+        Err(e) => eprintln!(
+            "{} Failed to resume interrupted jobs: {}",
+            "Warning:".yellow(),
+            e
+        ),
+    }
+
     // Check if we should trigger auto-summarization
     // Either: on_show is enabled (triggers every time) OR time-based trigger is due
     let should_trigger =
@@ -53,19 +80,46 @@ pub async fn run(port: Option<u16>, host: String, open_browser: bool) -> Result<
         }
     }
 
-    let pricing = PricingData::load().await;
-    let state = Arc::new(AppState {
-        config: RwLock::new(config),
-        pricing,
-    });
+    crate::archive::atomic::recover_on_startup(&config);
+    crate::jobs::auto_prune(&config);
+
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let port = port.or(config.server.port);
+
+    // Flags override the config for this run only
+    if base_path.is_some() {
+        config.server.base_path = base_path;
+    }
+    if tls_cert.is_some() {
+        config.server.tls_cert = tls_cert;
+        config.server.tls_key = tls_key;
+    }
+    let tls = match (&config.server.tls_cert, &config.server.tls_key) {
+        (Some(cert), Some(key)) => Some(tls::acceptor(cert, key)?),
+        (None, None) => None,
+        _ => anyhow::bail!("server.tls_cert and server.tls_key must be set together"),
+    };
+    let base_path = normalize_base_path(config.server.base_path.as_deref().unwrap_or_default());
+    let state = Arc::new(AppState::new(config, pricing, &host));
+    tokio::spawn(schedule_backups(state.clone()));
+    if daemon {
+        tokio::spawn(schedule_auto_digest(state.clone()));
+    }
+    tokio::spawn(schedule_offline_retry(state.clone()));
 
     // Find available port
     let (listener, actual_port) = find_available_port(&host, port).await?;
-    let url = format!("http://{}:{}", host, actual_port);
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let url = format!("{}://{}:{}{}/", scheme, host, actual_port, base_path);
 
     println!("{}", "Starting Daily Dashboard...".green().bold());
     println!();
     println!("  {} {}", "URL:".dimmed(), url.cyan());
+    if let Some(profile) = active_profile() {
+        println!("  {} {}", "Profile:".dimmed(), profile);
+    }
     println!();
     println!("{}", "Press Ctrl+C to stop the server".dimmed());
     println!();
@@ -78,15 +132,34 @@ pub async fn run(port: Option<u16>, host: String, open_browser: bool) -> Result<
     }
 
     // Create router and start server
-    let app = create_router(state);
-
-    // Run server with graceful shutdown on Ctrl+C
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+    let app = create_router(state.clone());
+
+    // Run server with graceful shutdown on Ctrl+C or SIGTERM; websocket
+    // clients are told first so open connections don't hold it up
+    let stopping = {
+        let state = state.clone();
+        async move {
+            shutdown_signal().await;
+            println!();
+            println!("{}", "Stopping server...".dimmed());
+            state.emit(WsMessage::ServerShutdown);
+        }
+    };
+    match tls {
+        Some(acceptor) => tls::serve(listener, acceptor, app, stopping)
+            .await
+            .context("Server error")?,
+        None => axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(stopping)
         .await
-        .context("Server error")?;
+        .context("Server error")?,
+    }
 
-    println!();
+    let config = state.config.read().unwrap().clone();
+    finish_running_jobs(&config).await;
     println!("{}", "Server stopped.".dimmed());
 
     Ok(())
@@ -125,12 +198,6 @@ async fn find_available_port(host: &str, port: Option<u16>) -> Result<(TcpListen
 
 /// Trigger auto-summarization for unsummarized transcripts
 async fn trigger_auto_summarize(config: &crate::config::Config) -> Result<usize> {
-    use crate::jobs::{JobManager, JobType};
-    use std::process::{Command, Stdio};
-
-    #[cfg(unix)]
-    use std::os::unix::process::CommandExt;
-
     // Find unsummarized transcripts
     let unsummarized = find_unsummarized_transcripts(config)?;
 
@@ -140,28 +207,22 @@ async fn trigger_auto_summarize(config: &crate::config::Config) -> Result<usize>
 
     // Initialize job manager
     let job_manager = JobManager::new(config)?;
+    // Transcripts already being summarized, e.g. by a resumed job
+    let active: Vec<PathBuf> = job_manager
+        .list(false)?
+        .into_iter()
+        .map(|job| job.transcript_path)
+        .collect();
 
     let mut spawned_count = 0;
 
     for transcript in unsummarized {
+        if active.contains(&transcript.path) {
+            continue;
+        }
         // Generate task name from session ID
         let task_name = format!("auto-{}", transcript.session_id);
 
-        // Generate job ID
-        let job_id = JobManager::generate_job_id(&task_name);
-        let transcript_path_str = transcript.path.to_string_lossy().to_string();
-
-        // Create log file for the job
-        let (stdout_file, stderr_file) = match job_manager.create_log_file(&job_id) {
-            Ok(f) => {
-                let f2 = f.try_clone().unwrap_or_else(|_| {
-                    std::fs::File::create("/dev/null").expect("Failed to open /dev/null")
-                });
-                (Stdio::from(f), Stdio::from(f2))
-            }
-            Err(_) => (Stdio::null(), Stdio::null()),
-        };
-
         // Default CWD for auto-summarize jobs
         let cwd_str = transcript
             .cwd
@@ -169,53 +230,210 @@ async fn trigger_auto_summarize(config: &crate::config::Config) -> Result<usize>
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_else(|| ".".to_string());
 
-        // Spawn background process for summarization
-        let mut cmd = Command::new("daily");
-        cmd.args([
-            "summarize",
-            "--transcript",
-            &transcript_path_str,
-            "--task-name",
+        match spawn_summarize_job(
+            config,
+            &transcript.path,
             &task_name,
-            "--cwd",
             &cwd_str,
-            "--job-id",
-            &job_id,
-            "--foreground",
-        ])
-        .stdin(Stdio::null())
-        .stdout(stdout_file)
-        .stderr(stderr_file);
-
-        // Create a new process group so it doesn't get killed
-        #[cfg(unix)]
-        cmd.process_group(0);
-
-        match cmd.spawn() {
-            Ok(child) => {
-                // Register the job with AutoSummarize type
-                if let Err(e) = job_manager.register(
-                    &job_id,
-                    child.id(),
-                    &task_name,
-                    &transcript.path,
-                    JobType::AutoSummarize,
-                ) {
-                    eprintln!("[daily] Failed to register auto-summarize job: {}", e);
-                } else {
-                    spawned_count += 1;
-                }
+            JobType::AutoSummarize,
+        ) {
+            Ok(_) => spawned_count += 1,
+            Err(e) => eprintln!(
+                "[daily] Failed to start auto-summarize job for {}: {}",
+                transcript.session_id, e
+            ),
+        }
+    }
+
+    Ok(spawned_count)
+}
+
+/// Start a background `daily summarize` of `transcript` and register it,
+/// returning the job id
+fn spawn_summarize_job(
+    config: &crate::config::Config,
+    transcript: &Path,
+    task_name: &str,
+    cwd: &str,
+    job_type: JobType,
+) -> Result<String> {
+    let command = JobCommand::new(
+        job_type,
+        task_name,
+        transcript,
+        [
+            "summarize".to_string(),
+            "--transcript".to_string(),
+            transcript.to_string_lossy().into_owned(),
+            "--task-name".to_string(),
+            task_name.to_string(),
+            "--cwd".to_string(),
+            cwd.to_string(),
+            "--foreground".to_string(),
+        ],
+    )
+    .route_by_cwd();
+    Ok(jobs::spawn(config, command)?.0)
+}
+
+/// Start again the digests and summarizations whose process died while
+/// queued or running, e.g. killed along with a stopped `daily daemon`, with
+/// the arguments (cwd, `--force`, `--keep`, ...) they were started with.
+/// Summarizations go first so resumed digests wait for them. The old
+/// records are closed with a pointer to their replacement.
+fn resume_interrupted_jobs(config: &crate::config::Config) -> Result<usize> {
+    let job_manager = JobManager::new(config)?;
+    let mut interrupted: Vec<JobInfo> = job_manager
+        .interrupted()?
+        .into_iter()
+        .filter(|job| job.job_type.is_resumable())
+        .collect();
+    interrupted.sort_by_key(|job| job.job_type == JobType::Digest);
+    let mut resumed = 0;
+    for job in interrupted {
+        let Some(mut command) = job.command.clone() else {
+            eprintln!(
+                "{} Not resuming {} job {}: started by an older version",
+                "Warning:".yellow(),
+                job.job_type,
+                job.id
+            );
+            continue;
+        };
+        if job.job_type.is_summarization() && !job.transcript_path.exists() {
+            continue;
+        }
+        if let Some(date) = job.task_name.strip_prefix("digest-") {
+            command.depends_on = job_manager.summaries_in_flight(date)?;
+        }
+        match jobs::spawn(config, command) {
+            Ok((job_id, _)) => {
+                job_manager.mark_resumed(&job.id, &job_id)?;
+                resumed += 1;
             }
-            Err(e) => {
-                eprintln!(
-                    "[daily] Failed to spawn auto-summarize process for {}: {}",
-                    transcript.session_id, e
-                );
+            Err(e) => eprintln!(
+                "{} Could not resume {} job {}: {:#}",
+                "Warning:".yellow(),
+                job.job_type,
+                job.id,
+                e
+            ),
+        }
+    }
+    Ok(resumed)
+}
+
+/// After the server stops accepting requests, give running jobs up to
+/// [`SHUTDOWN_GRACE`] to finish. Jobs still running after that keep going
+/// in their own process group; if they are killed anyway (a service
+/// manager stopping the whole unit), the next start resumes them.
+async fn finish_running_jobs(config: &crate::config::Config) {
+    let Ok(job_manager) = JobManager::new(config) else {
+        return;
+    };
+    let running = || {
+        job_manager
+            .list(false)
+            .map(|jobs| {
+                jobs.iter()
+                    .filter(|job| job.status == JobStatus::Running)
+                    .count()
+            })
+            .unwrap_or(0)
+    };
+    let count = running();
+    if count == 0 {
+        return;
+    }
+    println!(
+        "{} Waiting up to {}s for {} running job(s)...",
+        "→".cyan(),
+        SHUTDOWN_GRACE.as_secs(),
+        count
+    );
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_GRACE;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        if running() == 0 {
+            println!("{} Jobs finished", "✓".green());
+            return;
+        }
+    }
+    println!(
+        "{} {} job(s) still running in the background; see `daily jobs`",
+        "!".yellow(),
+        running()
+    );
+}
+
+/// Take storage snapshots on `backup.schedule` while the server runs.
+/// Checked hourly, so changes to the schedule apply without a restart.
+async fn schedule_backups(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+        let config = state.config.read().unwrap().clone();
+        if !crate::backup::is_due(&config, chrono::Local::now().naive_local()) {
+            continue;
+        }
+        match tokio::task::spawn_blocking(move || crate::backup::create(&config)).await {
+            Ok(Ok(snapshot)) => {
+                eprintln!("[daily] Backup written to {}", snapshot.path.display())
             }
+            Ok(Err(e)) => eprintln!("{} Backup failed: {:#}", "Warning:".yellow(), e),
+            Err(e) => eprintln!("{} Backup task failed: {}", "Warning:".yellow(), e),
         }
     }
+}
 
-    Ok(spawned_count)
+/// Digest yesterday's sessions once `summarization.digest_time` has passed,
+/// checked every 15 minutes outside the auto-summarize quiet hours, so the
+/// dashboard run by `daily daemon` keeps digests current without a new
+/// Claude Code session starting. A digest that keeps failing is retried
+/// less often and then given up, see [`check_auto_digest`].
+///
+/// [`check_auto_digest`]: crate::hooks::session_start::check_auto_digest
+async fn schedule_auto_digest(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(15 * 60));
+    loop {
+        interval.tick().await;
+        let config = state.config.read().unwrap().clone();
+        if !config.summarization.auto_digest_enabled
+            || in_quiet_hours(&config, chrono::Local::now().time())
+        {
+            continue;
+        }
+        let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let digesting = crate::jobs::JobManager::new(&config)
+            .and_then(|jobs| jobs.active_digest_job(&yesterday))
+            .map(|job| job.is_some())
+            .unwrap_or(true);
+        if digesting
+            || state.archive().has_digest(&yesterday)
+            || crate::archive::locks::is_locked(&config, &yesterday)
+        {
+            continue;
+        }
+        crate::hooks::session_start::check_auto_digest(&config);
+    }
+}
+
+/// Retry the oldest summarization queued while Claude was unreachable once
+/// its `summarization.offline_retry_minutes` have passed, checked every
+/// minute outside the auto-summarize quiet hours; when it gets through, it
+/// retries the rest of the queue.
+async fn schedule_offline_retry(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+    loop {
+        interval.tick().await;
+        let config = state.config.read().unwrap().clone();
+        if in_quiet_hours(&config, chrono::Local::now().time()) {
+            continue;
+        }
+        crate::cli::commands::queue::retry_due(&config, Some(1));
+    }
 }
 
 async fn shutdown_signal() {
@@ -241,3 +459,71 @@ async fn shutdown_signal() {
         _ = terminate => {},
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_replays_arguments() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = crate::config::Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = JobManager::new(&config).unwrap();
+        let transcript = temp_dir.path().join("session.jsonl");
+        std::fs::write(&transcript, "{}\n").unwrap();
+
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = exited.id();
+        exited.wait().unwrap();
+        let summarize = [
+            "summarize".to_string(),
+            "--transcript".to_string(),
+            transcript.to_string_lossy().into_owned(),
+            "--task-name".to_string(),
+            "fix-login".to_string(),
+            "--cwd".to_string(),
+            "/work/api".to_string(),
+            "--foreground".to_string(),
+        ];
+        let command = JobCommand::new(JobType::SessionEnd, "fix-login", &transcript, summarize);
+        manager
+            .register_command("summarize-old", dead_pid, &command)
+            .unwrap();
+        let digest = [
+            "digest",
+            "--date",
+            "2024-01-15",
+            "--force",
+            "--keep",
+            "overview",
+        ];
+        let command = JobCommand::new(
+            JobType::Digest,
+            "digest-2024-01-15",
+            config.date_dir("2024-01-15"),
+            digest,
+        );
+        manager
+            .register_command("digest-old", dead_pid, &command)
+            .unwrap();
+
+        assert_eq!(resume_interrupted_jobs(&config).unwrap(), 2);
+        for old in ["summarize-old", "digest-old"] {
+            let job = manager.load_job(old).unwrap();
+            let JobStatus::Failed { error } = job.status else {
+                panic!("{} was not closed", old);
+            };
+            let new_id = error.rsplit(' ').next().unwrap();
+            let resumed = manager.load_job(new_id).unwrap();
+            assert_eq!(resumed.task_name, job.task_name);
+            assert_eq!(resumed.job_type, job.job_type);
+            assert_eq!(
+                resumed.command.unwrap().args,
+                job.command.unwrap().args,
+                "{} lost its arguments",
+                old
+            );
+        }
+    }
+}
@@ -2,12 +2,15 @@ use anyhow::Result;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::config::load_config;
+use crate::archive::frontmatter;
+use crate::config::{load_config, Config};
+use crate::skills::{self, SkillKind};
+use crate::storage::{self, Change};
 
 /// Review pending skills
 pub async fn run_review(install: Option<String>, delete: Option<String>) -> Result<()> {
     let config = load_config()?;
-    let pending_dir = config.storage.path.join("pending-skills");
+    let pending_dir = skills::pending_dir(&config);
 
     if !pending_dir.exists() {
         println!("No pending skills to review.");
@@ -16,12 +19,12 @@ pub async fn run_review(install: Option<String>, delete: Option<String>) -> Resu
 
     // Handle install action
     if let Some(skill_path) = install {
-        return install_skill(&pending_dir, &skill_path);
+        return install_skill(&config, &pending_dir, &skill_path);
     }
 
     // Handle delete action
     if let Some(skill_path) = delete {
-        return delete_skill(&pending_dir, &skill_path);
+        return delete_skill(&config, &pending_dir, &skill_path);
     }
 
     // List all pending skills
@@ -30,27 +33,13 @@ pub async fn run_review(install: Option<String>, delete: Option<String>) -> Resu
 
 /// List all pending skills
 fn list_pending_skills(pending_dir: &Path) -> Result<()> {
-    let mut skills: Vec<(String, String, PathBuf)> = Vec::new();
-
-    if let Ok(entries) = fs::read_dir(pending_dir) {
-        for entry in entries.flatten() {
-            if entry.path().is_dir() {
-                let date = entry.file_name().to_string_lossy().to_string();
-                if let Ok(files) = fs::read_dir(entry.path()) {
-                    for file in files.flatten() {
-                        if file.path().extension().is_some_and(|e| e == "md") {
-                            let name = file
-                                .path()
-                                .file_stem()
-                                .map(|s| s.to_string_lossy().to_string())
-                                .unwrap_or_default();
-                            skills.push((date.clone(), name, file.path()));
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let skills: Vec<(String, String, PathBuf)> = skills::pending_skills(pending_dir)
+        .into_iter()
+        .filter_map(|skill| match skill.kind {
+            SkillKind::Pending { date } => Some((date, skill.name, skill.path)),
+            SkillKind::Installed => None,
+        })
+        .collect();
 
     if skills.is_empty() {
         println!("No pending skills to review.");
@@ -67,9 +56,19 @@ fn list_pending_skills(pending_dir: &Path) -> Result<()> {
         // Read and show preview
         if let Ok(content) = fs::read_to_string(path) {
             // Extract description from frontmatter
-            if let Some(desc) = extract_description(&content) {
+            if let Some(desc) = skills::field(&content, "description") {
                 println!("   {}", desc);
             }
+            if let Some(of) = skills::field(&content, "updates") {
+                println!("   Updates installed skill: {}", of);
+            }
+            if let Some(count) = skills::field(&content, "occurrences") {
+                println!("   Extracted {} times", count);
+            }
+            let files = package_files(&skills::files_dir(path));
+            if !files.is_empty() {
+                println!("   Files: {}", files.join(", "));
+            }
 
             // Show trigger conditions if present
             if let Some(trigger) = extract_section(&content, "## When to Use") {
@@ -95,7 +94,7 @@ fn list_pending_skills(pending_dir: &Path) -> Result<()> {
 }
 
 /// Install a skill to user's skills directory
-fn install_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
+fn install_skill(config: &Config, pending_dir: &Path, skill_ref: &str) -> Result<()> {
     let (date, name) = parse_skill_ref(skill_ref)?;
     let skill_path = pending_dir.join(&date).join(format!("{}.md", name));
 
@@ -103,22 +102,27 @@ fn install_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
         anyhow::bail!("Skill not found: {}/{}", date, name);
     }
 
-    // Read skill content
+    // Read skill content, minus the review bookkeeping
     let content = fs::read_to_string(&skill_path)?;
+    let updates = skills::field(&content, "updates");
+    let content = frontmatter::set(&content, "updates", None);
+    let content = frontmatter::set(&content, "occurrences", None);
 
-    // Install to ~/.claude/skills/{name}/SKILL.md
-    let target_dir = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".claude")
-        .join("skills")
-        .join(&name);
+    // Install to ~/.claude/skills/{name}/SKILL.md, over the skill it updates
+    let target_dir = skills::installed_dir().join(updates.as_deref().unwrap_or(&name));
 
     fs::create_dir_all(&target_dir)?;
     let target_file = target_dir.join("SKILL.md");
     fs::write(&target_file, &content)?;
 
+    // Bring along its scripts/references/assets
+    let files_dir = skills::files_dir(&skill_path);
+    if files_dir.is_dir() {
+        skills::copy_tree(&files_dir, &target_dir)?;
+    }
+
     // Remove from pending
-    fs::remove_file(&skill_path)?;
+    remove_pending(config, &skill_path)?;
 
     // Clean up empty date directory
     let date_dir = pending_dir.join(&date);
@@ -134,8 +138,21 @@ fn install_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
     Ok(())
 }
 
+/// Remove a pending skill and its package files
+fn remove_pending(config: &Config, skill_path: &Path) -> Result<()> {
+    let files_dir = skills::files_dir(skill_path);
+    let files = storage::files_under(config, &files_dir);
+    if files_dir.is_dir() {
+        fs::remove_dir_all(&files_dir)?;
+    }
+    for file in &files {
+        storage::record(config, file, Change::Delete);
+    }
+    storage::remove_file(config, skill_path)
+}
+
 /// Delete a pending skill
-fn delete_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
+fn delete_skill(config: &Config, pending_dir: &Path, skill_ref: &str) -> Result<()> {
     let (date, name) = parse_skill_ref(skill_ref)?;
     let skill_path = pending_dir.join(&date).join(format!("{}.md", name));
 
@@ -143,7 +160,7 @@ fn delete_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
         anyhow::bail!("Skill not found: {}/{}", date, name);
     }
 
-    fs::remove_file(&skill_path)?;
+    remove_pending(config, &skill_path)?;
 
     // Clean up empty date directory
     let date_dir = pending_dir.join(&date);
@@ -156,6 +173,25 @@ fn delete_skill(pending_dir: &Path, skill_ref: &str) -> Result<()> {
     Ok(())
 }
 
+/// Files of a pending skill package, relative to its folder
+fn package_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() {
+            files.extend(
+                package_files(&entry.path())
+                    .into_iter()
+                    .map(|file| format!("{}/{}", name, file)),
+            );
+        } else {
+            files.push(name);
+        }
+    }
+    files.sort();
+    files
+}
+
 /// Parse skill reference like "2026-01-18/skill-name"
 fn parse_skill_ref(skill_ref: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = skill_ref.split('/').collect();
@@ -165,21 +201,6 @@ fn parse_skill_ref(skill_ref: &str) -> Result<(String, String)> {
     Ok((parts[0].to_string(), parts[1].to_string()))
 }
 
-/// Extract description from YAML frontmatter
-fn extract_description(content: &str) -> Option<String> {
-    for line in content.lines() {
-        let line = line.trim();
-        if line.starts_with("description:") {
-            let desc = line.trim_start_matches("description:").trim();
-            let desc = desc.trim_matches('"').trim_matches('\'');
-            if !desc.is_empty() {
-                return Some(desc.to_string());
-            }
-        }
-    }
-    None
-}
-
 /// Extract a section from markdown content
 fn extract_section(content: &str, header: &str) -> Option<String> {
     if let Some(start) = content.find(header) {
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::archive::retention::format_bytes;
+use crate::archive::stats::ArchiveStats;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+
+/// Print storage-level statistics of the archive
+pub async fn run(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let stats = ArchiveStats::collect(&config)?;
+    if json {
+        return print_json(&stats);
+    }
+
+    println!("{}", "Archive".bold());
+    println!("  Location:  {}", stats.storage_path.display());
+    println!("  Size:      {}", format_bytes(stats.total_bytes));
+    match (&stats.oldest_date, &stats.newest_date) {
+        (Some(oldest), Some(newest)) => {
+            println!("  Days:      {} ({} to {})", stats.days, oldest, newest)
+        }
+        _ => println!("  Days:      0"),
+    }
+    println!("  Sessions:  {}", stats.sessions);
+    println!("  Digests:   {}", stats.digests);
+    println!();
+    println!("{}", "Averages".bold());
+    println!("  Session:   {} chars", stats.avg_session_chars);
+    println!("  Digest:    {} chars", stats.avg_digest_chars);
+    println!();
+    println!("{}", "Pending".bold());
+    println!(
+        "  Skills:    {} (review with `daily skills`)",
+        stats.pending_skills
+    );
+    println!(
+        "  Commands:  {} suggested, not installed",
+        stats.pending_commands
+    );
+
+    if !stats.largest_sessions.is_empty() {
+        println!();
+        println!("{}", "Largest sessions".bold());
+        for session in &stats.largest_sessions {
+            println!(
+                "  {:>9}  {}/{}",
+                format_bytes(session.bytes),
+                session.date,
+                session.name
+            );
+        }
+    }
+    Ok(())
+}
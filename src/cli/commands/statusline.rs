@@ -0,0 +1,141 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone};
+use serde::Serialize;
+
+use crate::archive::paths::PathFilter;
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::ArchiveManager;
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::jobs::JobManager;
+use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
+
+/// Dates searched back for the latest digest
+const DIGEST_LOOKBACK: usize = 14;
+
+/// Archive status shown in Claude Code's status line
+#[derive(Debug, Serialize)]
+pub struct Status {
+    /// Sessions archived today
+    pub sessions: usize,
+    /// Claude Code cost of today's messages
+    pub cost_usd: f64,
+    /// When the newest digest was written
+    pub last_digest: Option<DateTime<Local>>,
+    /// Type and task of the newest queued or running job
+    pub active_job: Option<String>,
+}
+
+impl Status {
+    /// e.g. `daily 3 sessions · $4.21 today · digest 06:02 · ⟳ Digest 2024-01-15`
+    pub fn line(&self, now: DateTime<Local>) -> String {
+        let mut parts = vec![
+            format!(
+                "daily {} session{}",
+                self.sessions,
+                if self.sessions == 1 { "" } else { "s" }
+            ),
+            format!("${:.2} today", self.cost_usd),
+        ];
+        if let Some(digest) = self.last_digest {
+            let when = if digest.date_naive() == now.date_naive() {
+                digest.format("%H:%M")
+            } else {
+                digest.format("%b %-d")
+            };
+            parts.push(format!("digest {}", when));
+        }
+        if let Some(job) = &self.active_job {
+            parts.push(format!("⟳ {}", job));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Print the status line. Claude Code runs this as its `statusLine`
+/// command and shows the first line printed; nothing is fetched from the
+/// network so it returns quickly.
+pub async fn run(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let now = Local::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    let manager = ArchiveManager::new(config.clone());
+
+    let sessions = manager
+        .list_sessions(&today)
+        .unwrap_or_default()
+        .iter()
+        .filter(|name| !name.starts_with(CHECKPOINT_PREFIX))
+        .count();
+
+    let pricing = PricingData::load_offline().with_overrides(&config.usage.price_overrides);
+    let midnight = Local
+        .from_local_datetime(&now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .unwrap_or(now);
+    let cost_usd = scanner::cost_since(
+        &pricing,
+        &PathFilter::from_config(&config),
+        midnight.to_utc(),
+    );
+
+    let last_digest = manager
+        .list_dates()
+        .unwrap_or_default()
+        .iter()
+        .take(DIGEST_LOOKBACK)
+        .find(|date| manager.has_digest(date))
+        .and_then(|date| std::fs::metadata(manager.daily_summary_path(date)).ok())
+        .and_then(|meta| meta.modified().ok())
+        .map(DateTime::<Local>::from);
+
+    let active_job = JobManager::new(&config)
+        .and_then(|jobs| jobs.list(false))
+        .ok()
+        .and_then(|jobs| jobs.into_iter().next())
+        .map(|job| format!("{} {}", job.job_type, job.task_name));
+
+    let status = Status {
+        sessions,
+        cost_usd,
+        last_digest,
+        active_job,
+    };
+    if json {
+        return print_json(&status);
+    }
+    println!("{}", status.line(now));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        let now = Local.with_ymd_and_hms(2024, 1, 15, 18, 30, 0).unwrap();
+        let status = Status {
+            sessions: 3,
+            cost_usd: 4.213,
+            last_digest: Some(Local.with_ymd_and_hms(2024, 1, 15, 6, 2, 0).unwrap()),
+            active_job: Some("Digest 2024-01-15".into()),
+        };
+        assert_eq!(
+            status.line(now),
+            "daily 3 sessions · $4.21 today · digest 06:02 · ⟳ Digest 2024-01-15"
+        );
+
+        let quiet = Status {
+            sessions: 1,
+            cost_usd: 0.0,
+            last_digest: Some(Local.with_ymd_and_hms(2024, 1, 9, 6, 2, 0).unwrap()),
+            active_job: None,
+        };
+        assert_eq!(
+            quiet.line(now),
+            "daily 1 session · $0.00 today · digest Jan 9"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::storage::{self, SyncReport};
+
+#[derive(Serialize)]
+struct StorageStatus {
+    backend: String,
+    location: String,
+    pending: usize,
+}
+
+/// Show the configured backend and how many changes are waiting
+pub async fn status(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let status = StorageStatus {
+        backend: config.storage.backend.clone(),
+        location: storage::open(&config)?.location(),
+        pending: storage::pending_count(&config),
+    };
+    if json {
+        return print_json(&status);
+    }
+
+    println!("{} {}", "Backend:".bold(), status.backend.cyan());
+    println!("{} {}", "Location:".bold(), status.location);
+    if storage::is_remote(&config) {
+        println!(
+            "{} {}",
+            "Local copy:".bold(),
+            config.storage_path().display()
+        );
+        println!("{} {} change(s)", "Pending:".bold(), status.pending);
+    }
+    Ok(())
+}
+
+/// Push pending changes, or with `all` every archive file
+pub async fn push(all: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    if !storage::is_remote(&config) {
+        anyhow::bail!("storage.backend is \"local\"; there is nothing to push");
+    }
+    if all {
+        storage::queue_all(&config)?;
+    }
+    let report = storage::flush(&config).await?;
+    print_report(&report, json)
+}
+
+/// Download files other machines pushed
+pub async fn pull(json: bool) -> Result<()> {
+    let config = load_config()?;
+    if !storage::is_remote(&config) {
+        anyhow::bail!("storage.backend is \"local\"; there is nothing to pull");
+    }
+    let report = storage::pull(&config).await?;
+    print_report(&report, json)
+}
+
+fn print_report(report: &SyncReport, json: bool) -> Result<()> {
+    if json {
+        return print_json(report);
+    }
+    for key in &report.uploaded {
+        println!("  {} {}", "uploaded".dimmed(), key);
+    }
+    for key in &report.deleted {
+        println!("  {} {}", "deleted".dimmed(), key);
+    }
+    for key in &report.downloaded {
+        println!("  {} {}", "downloaded".dimmed(), key);
+    }
+    for failure in &report.failed {
+        println!("  {} {}", "failed".red(), failure);
+    }
+
+    let transferred = report.uploaded.len() + report.deleted.len() + report.downloaded.len();
+    if report.failed.is_empty() {
+        println!(
+            "{} Synced {} file(s) with {}",
+            "✓".green(),
+            transferred,
+            report.location
+        );
+    } else {
+        println!(
+            "{} {} file(s) synced, {} failed and stay queued",
+            "!".yellow(),
+            transferred,
+            report.failed.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use colored::Colorize;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::daily::section;
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::{frontmatter, transcript, ArchiveManager};
+use crate::cli::output::print_json;
+use crate::config::{load_config, Config};
+use crate::summarizer::quality::{self, QualityReport};
+use crate::summarizer::{SummarizerEngine, TemplateEngine, TemplateKind};
+
+/// A past session re-summarized with the candidate template
+#[derive(Serialize)]
+struct Comparison {
+    date: String,
+    name: String,
+    current: PathBuf,
+    candidate: Option<PathBuf>,
+    current_quality: QualityReport,
+    candidate_quality: Option<QualityReport>,
+    error: Option<String>,
+}
+
+/// A past session whose transcript is still around
+struct Sample {
+    date: String,
+    name: String,
+    content: String,
+    transcript: PathBuf,
+}
+
+/// Re-summarize the most recent sessions with a candidate session summary
+/// template, writing current and candidate archives side by side under
+/// `output` (the archive itself is left untouched)
+pub async fn test(
+    template: PathBuf,
+    sessions: usize,
+    output: Option<PathBuf>,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let candidate = fs::read_to_string(&template)
+        .with_context(|| format!("Failed to read template {}", template.display()))?;
+
+    let warnings = TemplateEngine::validate(&candidate, TemplateKind::SessionSummary);
+    if !json {
+        for warning in &warnings {
+            println!("{} {}", "!".yellow(), warning.message);
+        }
+    }
+
+    let manager = ArchiveManager::new(config.clone());
+    let samples = sample(&config, &manager, sessions)?;
+    if samples.is_empty() {
+        anyhow::bail!("No archived sessions with a transcript to test against");
+    }
+
+    let output = output.unwrap_or_else(|| {
+        std::env::temp_dir()
+            .join("daily-template-test")
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string())
+    });
+    fs::create_dir_all(&output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+
+    let mut candidate_config = config.clone();
+    candidate_config.prompt_templates.session_summary = Some(candidate);
+    let min_score = config.summarization.min_quality_score;
+
+    let mut comparisons = Vec::new();
+    for (i, sample) in samples.iter().enumerate() {
+        if !json {
+            println!(
+                "{} {}/{}  {}/{}",
+                "→".cyan(),
+                i + 1,
+                samples.len(),
+                sample.date,
+                sample.name
+            );
+        }
+
+        let dir = output.join(format!("{}--{}", sample.date, sample.name));
+        fs::create_dir_all(&dir)?;
+        let current = dir.join("current.md");
+        fs::write(&current, &sample.content)?;
+
+        // A fresh engine per session, so its quality report is this one's
+        let engine = SummarizerEngine::new(candidate_config.clone());
+        let cwd = frontmatter::get(&sample.content, "cwd").unwrap_or_default();
+        let result = engine
+            .summarize_session(&sample.transcript, "", cwd, archived_at(sample))
+            .await;
+
+        let (candidate, candidate_quality, error) = match result {
+            Ok(archive) => {
+                let path = dir.join("candidate.md");
+                fs::write(&path, archive.to_markdown())?;
+                (Some(path), engine.quality(), None)
+            }
+            Err(e) => (None, None, Some(format!("{:#}", e))),
+        };
+
+        comparisons.push(Comparison {
+            date: sample.date.clone(),
+            name: sample.name.clone(),
+            current,
+            candidate,
+            current_quality: score_archive(&sample.name, &sample.content, min_score),
+            candidate_quality,
+            error,
+        });
+    }
+
+    fs::write(output.join("index.md"), index(&template, &comparisons))?;
+
+    if json {
+        return print_json(&serde_json::json!({
+            "template": template,
+            "output": output,
+            "warnings": warnings.iter().map(|w| &w.message).collect::<Vec<_>>(),
+            "sessions": comparisons,
+        }));
+    }
+
+    println!();
+    println!(
+        "{:<48} {:>8} {:>10}",
+        "Session".bold(),
+        "Current".bold(),
+        "Candidate".bold()
+    );
+    for comparison in &comparisons {
+        let candidate = match (&comparison.candidate_quality, &comparison.error) {
+            (Some(quality), _) => quality.score.to_string(),
+            (None, Some(_)) => "failed".red().to_string(),
+            (None, None) => "-".into(),
+        };
+        println!(
+            "{:<48} {:>8} {:>10}",
+            format!("{}/{}", comparison.date, comparison.name),
+            comparison.current_quality.score,
+            candidate
+        );
+        if let Some(error) = &comparison.error {
+            println!("  {}", error.dimmed());
+        }
+    }
+    println!();
+    println!(
+        "{} Outputs written to {} (archives unchanged)",
+        "✓".green(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// The `count` most recent sessions with a readable transcript, newest first
+fn sample(config: &Config, manager: &ArchiveManager, count: usize) -> Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+    for date in manager.list_dates()? {
+        let mut names = manager.list_sessions(&date)?;
+        names.reverse();
+        for name in names {
+            if samples.len() >= count {
+                return Ok(samples);
+            }
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            let Ok(content) = manager.read_session(&date, &name) else {
+                continue;
+            };
+            let Some(transcript) = transcript::resolve(config, &date, &content) else {
+                continue;
+            };
+            samples.push(Sample {
+                date: date.clone(),
+                name,
+                content,
+                transcript,
+            });
+        }
+    }
+    Ok(samples)
+}
+
+/// When the session was archived, so the candidate gets the same time-based name
+fn archived_at(sample: &Sample) -> NaiveDateTime {
+    frontmatter::get(&sample.content, "created")
+        .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+        .map(|created| created.naive_local())
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(&sample.date, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .unwrap_or_else(|| chrono::Local::now().naive_local())
+}
+
+/// Quality score of an existing archive, on the same scale as new summaries
+fn score_archive(name: &str, content: &str, min_score: u8) -> QualityReport {
+    quality::score_session(
+        name,
+        section(content, "Summary").unwrap_or_default(),
+        section(content, "Key Decisions & Trade-offs").unwrap_or_default(),
+        section(content, "Learnings").unwrap_or_default(),
+        min_score,
+    )
+}
+
+/// Markdown overview linking each pair of outputs
+fn index(template: &Path, comparisons: &[Comparison]) -> String {
+    let mut out = format!(
+        "# Template test\n\nCandidate: `{}`\n\n| Session | Current | Candidate |\n|---|---|---|\n",
+        template.display()
+    );
+    for c in comparisons {
+        let dir = format!("{}--{}", c.date, c.name);
+        let candidate = match (&c.candidate_quality, &c.error) {
+            (Some(quality), _) => format!("[{}]({}/candidate.md)", quality.score, dir),
+            (None, Some(error)) => format!("failed: {}", error.replace('|', "\\|")),
+            (None, None) => "-".into(),
+        };
+        out.push_str(&format!(
+            "| {}/{} | [{}]({}/current.md) | {} |\n",
+            c.date, c.name, c.current_quality.score, dir, candidate
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sample_picks_recent_sessions_with_transcripts() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        let session = |id: &str| format!("---\nsession_id: {}\n---\n\n## Summary\n\nDone.\n", id);
+        for (date, name, id) in [
+            ("2024-01-01", "09_00-old", "a"),
+            ("2024-01-02", "09_00-morning", "b"),
+            ("2024-01-02", "15_00-afternoon", "c"),
+            ("2024-01-02", "checkpoint-d", "d"),
+            ("2024-01-02", "16_00-no-transcript", "e"),
+        ] {
+            manager.write_session(date, name, &session(id)).unwrap();
+            if id != "e" {
+                let transcripts = config.date_dir(date).join("transcripts");
+                fs::create_dir_all(&transcripts).unwrap();
+                fs::write(transcripts.join(format!("{}.jsonl", id)), "{}\n").unwrap();
+            }
+        }
+
+        let names = |count| {
+            sample(&config, &manager, count)
+                .unwrap()
+                .into_iter()
+                .map(|s| format!("{}/{}", s.date, s.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(2),
+            vec!["2024-01-02/15_00-afternoon", "2024-01-02/09_00-morning"]
+        );
+        assert_eq!(names(10).len(), 3);
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::load_config;
+use crate::todos::{Todo, TodoStore};
+
+/// Capture an open TODO
+pub async fn add(text: String, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut store = TodoStore::load(&config)?;
+    let todo = store.add(&text)?.clone();
+    store.save(&config)?;
+
+    if json {
+        return print_json(&todo);
+    }
+    println!("{} Added TODO {}: {}", "✓".green(), todo.id, todo.text);
+    println!(
+        "  {}",
+        "Digests will list it under Tomorrow's Focus until a session finishes it".dimmed()
+    );
+    Ok(())
+}
+
+/// List TODOs, open ones first
+pub async fn list(all: bool, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let store = TodoStore::load(&config)?;
+    let mut todos: Vec<&Todo> = store.todos.iter().filter(|t| all || !t.is_done()).collect();
+    todos.sort_by_key(|t| t.is_done());
+
+    if json {
+        return print_json(&todos);
+    }
+
+    if todos.is_empty() {
+        println!(
+            "No open TODOs. Add one with {}",
+            "daily todo add \"write release notes\"".cyan()
+        );
+        return Ok(());
+    }
+
+    for todo in todos {
+        let marker = if todo.is_done() {
+            "✅".normal()
+        } else {
+            "☐".cyan()
+        };
+        let when = match (&todo.done_on, &todo.session) {
+            (Some(date), Some(session)) => format!("done in {} ({})", session, date),
+            _ => match todo.done {
+                Some(done) => format!("done {}", done.format("%Y-%m-%d")),
+                None => format!("added {}", todo.created.format("%Y-%m-%d")),
+            },
+        };
+        println!(
+            "{} {} {}  {}",
+            marker,
+            format!("#{}", todo.id).dimmed(),
+            todo.text,
+            when.dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Mark a TODO done by hand
+pub async fn done(id: u32, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut store = TodoStore::load(&config)?;
+    let todo = store.complete(id)?.clone();
+    store.save(&config)?;
+
+    if json {
+        return print_json(&todo);
+    }
+    println!("{} TODO {} done: {}", "✓".green(), id, todo.text);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::output::print_json;
+use crate::config::{load_config, Config};
+use crate::jobs::{self, JobCommand, JobManager, JobType};
+use crate::summarizer::SummarizerEngine;
+use crate::topics::{self, TopicIndex};
+
+/// List topics, most sessions first
+pub async fn list(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let mut index = TopicIndex::load(&config);
+    index
+        .topics
+        .sort_by_key(|t| std::cmp::Reverse(t.sessions.len()));
+
+    if json {
+        return print_json(&index);
+    }
+
+    if index.topics.is_empty() {
+        println!(
+            "No topics yet. Run {} to build them.",
+            "daily topics update".cyan()
+        );
+        return Ok(());
+    }
+
+    for topic in &index.topics {
+        println!(
+            "  {:>4}  {}  {}",
+            topic.sessions.len().to_string().dimmed(),
+            topic.title.bold(),
+            topic.description.dimmed()
+        );
+    }
+    println!();
+    println!(
+        "Pages in {}",
+        topics::topics_dir(&config).display().to_string().cyan()
+    );
+    Ok(())
+}
+
+/// Refresh the topic pages, in the foreground or as a `Topics` job
+pub async fn update(
+    rebuild: bool,
+    background: bool,
+    job_id: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+
+    if background {
+        let job_id = spawn_topics_job(&config, rebuild)?;
+        if json {
+            return print_json(&serde_json::json!({ "job_id": job_id }));
+        }
+        println!("{} Topic update started: {}", "✓".green(), job_id);
+        return Ok(());
+    }
+
+    let engine = SummarizerEngine::new(config.clone());
+    let Some(job_id) = job_id else {
+        let stats = topics::update(&config, &engine, rebuild).await?;
+        if json {
+            return print_json(&stats);
+        }
+        println!(
+            "{} {} topic(s), {} session(s) filed this run",
+            "✓".green(),
+            stats.topics,
+            stats.assigned
+        );
+        if stats.unassigned > 0 {
+            println!(
+                "{} {} session(s) left unfiled; they are retried next update",
+                "!".yellow(),
+                stats.unassigned
+            );
+        }
+        return Ok(());
+    };
+
+    // Running as a tracked job: wait for a queue slot and record the outcome
+    let job_manager = JobManager::new(&config)?;
+    if let Err(e) = job_manager
+        .wait_for_slot(&job_id, config.jobs.max_concurrent)
+        .await
+    {
+        eprintln!("[daily] Warning: Job queue unavailable, running now: {}", e);
+    }
+
+    let result = topics::update(&config, &engine, rebuild).await;
+    if let Ok(stats) = &result {
+        eprintln!(
+            "[daily] {} topic(s), {} session(s) filed, {} left unfiled",
+            stats.topics, stats.assigned, stats.unassigned
+        );
+    }
+    let usage = engine.usage();
+    if usage.calls > 0 {
+        if let Err(e) = job_manager.record_usage(&job_id, &usage) {
+            eprintln!("[daily] Warning: Failed to record token usage: {}", e);
+        }
+    }
+    let update = match &result {
+        Ok(_) => job_manager.mark_completed(&job_id),
+        Err(e) => job_manager.mark_error(&job_id, e),
+    };
+    if let Err(e) = update {
+        eprintln!("[daily] Warning: Failed to update job status: {}", e);
+    }
+    let _ = job_manager.truncate_log_if_needed(&job_id);
+
+    result.map(|_| ())
+}
+
+/// Spawn a detached `daily topics update --job-id` process tracked as a
+/// `Topics` job; fails if one is already queued or running
+pub(crate) fn spawn_topics_job(config: &Config, rebuild: bool) -> Result<String> {
+    let job_manager = JobManager::new(config)?;
+    if let Some(job) = job_manager.active_job_of_type(JobType::Topics)? {
+        anyhow::bail!(
+            "A topic update is already {} as job {}",
+            job.status.to_string().to_lowercase(),
+            job.id
+        );
+    }
+    let mut command = JobCommand::new(
+        JobType::Topics,
+        "topics",
+        topics::topics_dir(config),
+        ["topics", "update"],
+    );
+    if rebuild {
+        command = command.arg("--rebuild");
+    }
+    let (job_id, _) = jobs::spawn(config, command)?;
+    Ok(job_id)
+}
@@ -1,189 +1,369 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-/// Uninstall plugin from Claude Code
-pub async fn run(scope: String) -> Result<()> {
-    let target_dir = match scope.as_str() {
-        "user" => dirs::home_dir()
-            .context("Failed to get home directory")?
-            .join(".claude"),
-        "project" => std::env::current_dir()
-            .context("Failed to get current directory")?
-            .join(".claude"),
-        _ => {
-            anyhow::bail!("Invalid scope: {}. Use 'user' or 'project'", scope);
-        }
-    };
-
-    println!("[daily] Uninstalling plugin from: {}", target_dir.display());
+use super::install::{COMMAND_FILES, DAILY_HOOKS, HOOKS_FILE};
 
-    let mut removed_count = 0;
+/// What uninstall changes in one `.claude` directory
+struct UninstallPlan {
+    target_dir: PathBuf,
+    /// Files written by install (hooks JSON, slash commands)
+    files: Vec<PathBuf>,
+    /// settings.json with daily hooks removed; `None` content means the file
+    /// only held daily hooks and is deleted
+    settings: Option<(PathBuf, Option<Value>)>,
+    /// Directories install created that are empty once the files are gone
+    empty_dirs: Vec<PathBuf>,
+}
 
-    // Remove hooks configuration file
-    let hooks_file = target_dir.join("hooks").join("daily-hooks.json");
-    if hooks_file.exists() {
-        fs::remove_file(&hooks_file)?;
-        println!("[daily] Removed: {}", hooks_file.display());
-        removed_count += 1;
+impl UninstallPlan {
+    fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.settings.is_none() && self.empty_dirs.is_empty()
     }
 
-    // Remove command files
-    let commands_dir = target_dir.join("commands");
-    let command_files = [
-        "daily-view.md",
-        "daily-get-skill.md",
-        "daily-get-command.md",
-    ];
-
-    for cmd_file in &command_files {
-        let file_path = commands_dir.join(cmd_file);
-        if file_path.exists() {
-            fs::remove_file(&file_path)?;
-            println!("[daily] Removed: {}", file_path.display());
-            removed_count += 1;
-        }
+    fn item_count(&self) -> usize {
+        self.files.len() + self.settings.iter().count() + self.empty_dirs.len()
     }
+}
 
-    // Remove daily hooks from settings.json
-    let settings_file = target_dir.join("settings.json");
-    if settings_file.exists() {
-        let content = fs::read_to_string(&settings_file).context("Failed to read settings.json")?;
-        let mut settings: Value =
-            serde_json::from_str(&content).context("Failed to parse settings.json")?;
+/// Uninstall plugin from Claude Code
+pub async fn run(scope: String, dry_run: bool) -> Result<()> {
+    let mut removed_count = 0;
 
-        if remove_daily_hooks(&mut settings) {
-            let output = serde_json::to_string_pretty(&settings)?;
-            fs::write(&settings_file, output)?;
-            println!("[daily] Removed hooks from: {}", settings_file.display());
-            removed_count += 1;
-        }
+    for target_dir in scope_dirs(&scope)? {
+        println!("[daily] Uninstalling plugin from: {}", target_dir.display());
+        let plan = plan_uninstall(&target_dir, true)?;
+        removed_count += execute(&plan, dry_run)?;
     }
 
     println!();
-    if removed_count > 0 {
+    if removed_count == 0 {
+        println!("[daily] Nothing to uninstall. Plugin was not installed.");
+    } else if dry_run {
+        println!(
+            "[daily] Dry run: {} items would be removed. Nothing was changed.",
+            removed_count
+        );
+    } else {
         println!(
             "[daily] Uninstall complete! Removed {} items.",
             removed_count
         );
         println!("[daily] Note: Archive data (~/.claude/daily/) was preserved.");
         println!("[daily] Tip: Use 'daily trash' to delete the binary itself.");
-    } else {
-        println!("[daily] Nothing to uninstall. Plugin was not installed.");
     }
 
     Ok(())
 }
 
 /// Uninstall hooks only (disable automatic summarization, keep commands)
-pub async fn run_hooks_only(scope: String) -> Result<()> {
-    let target_dir = match scope.as_str() {
-        "user" => dirs::home_dir()
+pub async fn run_hooks_only(scope: String, dry_run: bool) -> Result<()> {
+    let mut removed_count = 0;
+
+    for target_dir in scope_dirs(&scope)? {
+        println!("[daily] Removing hooks from: {}", target_dir.display());
+        let plan = plan_uninstall(&target_dir, false)?;
+        removed_count += execute(&plan, dry_run)?;
+    }
+
+    println!();
+    if removed_count == 0 {
+        println!("[daily] No hooks found. Automatic summarization was not enabled.");
+    } else if dry_run {
+        println!(
+            "[daily] Dry run: {} items would be removed. Nothing was changed.",
+            removed_count
+        );
+    } else {
+        println!("[daily] Hooks removed! Automatic summarization is now disabled.");
+        println!(
+            "[daily] Slash commands (/daily-view, /daily-get-skill, etc.) are still available."
+        );
+        println!("[daily] Tip: Use 'daily install-hooks' to re-enable automatic summarization.");
+    }
+
+    Ok(())
+}
+
+/// Resolve `user`, `project`, or `all` to `.claude` directories
+fn scope_dirs(scope: &str) -> Result<Vec<PathBuf>> {
+    let user = || -> Result<PathBuf> {
+        Ok(dirs::home_dir()
             .context("Failed to get home directory")?
-            .join(".claude"),
-        "project" => std::env::current_dir()
+            .join(".claude"))
+    };
+    let project = || -> Result<PathBuf> {
+        Ok(std::env::current_dir()
             .context("Failed to get current directory")?
-            .join(".claude"),
-        _ => {
-            anyhow::bail!("Invalid scope: {}. Use 'user' or 'project'", scope);
-        }
+            .join(".claude"))
     };
 
-    println!("[daily] Removing hooks from: {}", target_dir.display());
+    match scope {
+        "user" => Ok(vec![user()?]),
+        "project" => Ok(vec![project()?]),
+        "all" => {
+            let mut dirs = vec![user()?];
+            let project = project()?;
+            if !dirs.contains(&project) {
+                dirs.push(project);
+            }
+            Ok(dirs)
+        }
+        _ => anyhow::bail!("Invalid scope: {}. Use 'user', 'project' or 'all'", scope),
+    }
+}
 
-    let mut removed_count = 0;
+/// Work out what to remove from `target_dir` without touching anything
+fn plan_uninstall(target_dir: &Path, include_commands: bool) -> Result<UninstallPlan> {
+    let hooks_dir = target_dir.join("hooks");
+    let commands_dir = target_dir.join("commands");
 
-    // Remove hooks configuration file
-    let hooks_file = target_dir.join("hooks").join("daily-hooks.json");
+    let mut files = Vec::new();
+    let hooks_file = hooks_dir.join(HOOKS_FILE);
     if hooks_file.exists() {
-        fs::remove_file(&hooks_file)?;
-        println!("[daily] Removed: {}", hooks_file.display());
-        removed_count += 1;
+        files.push(hooks_file);
+    }
+    if include_commands {
+        files.extend(
+            COMMAND_FILES
+                .iter()
+                .map(|name| commands_dir.join(name))
+                .filter(|path| path.exists()),
+        );
+    }
+
+    // Only remove directories that would be left empty by this uninstall
+    let mut empty_dirs = Vec::new();
+    let mut candidates = vec![&hooks_dir];
+    if include_commands {
+        candidates.push(&commands_dir);
+    }
+    for dir in candidates {
+        if let Ok(entries) = fs::read_dir(dir) {
+            let left_behind = entries
+                .filter_map(|e| e.ok())
+                .any(|e| !files.contains(&e.path()));
+            if !left_behind {
+                empty_dirs.push(dir.clone());
+            }
+        }
     }
 
-    // Remove daily hooks from settings.json
     let settings_file = target_dir.join("settings.json");
-    if settings_file.exists() {
+    let settings = if settings_file.exists() {
         let content = fs::read_to_string(&settings_file).context("Failed to read settings.json")?;
         let mut settings: Value =
             serde_json::from_str(&content).context("Failed to parse settings.json")?;
 
         if remove_daily_hooks(&mut settings) {
-            let output = serde_json::to_string_pretty(&settings)?;
-            fs::write(&settings_file, output)?;
-            println!("[daily] Removed hooks from: {}", settings_file.display());
-            removed_count += 1;
+            let emptied = settings.as_object().is_some_and(|o| o.is_empty());
+            Some((settings_file, (!emptied).then_some(settings)))
+        } else {
+            None
         }
-    }
-
-    println!();
-    if removed_count > 0 {
-        println!("[daily] Hooks removed! Automatic summarization is now disabled.");
-        println!(
-            "[daily] Slash commands (/daily-view, /daily-get-skill, etc.) are still available."
-        );
-        println!("[daily] Tip: Use 'daily install-hooks' to re-enable automatic summarization.");
     } else {
-        println!("[daily] No hooks found. Automatic summarization was not enabled.");
-    }
+        None
+    };
 
-    Ok(())
+    Ok(UninstallPlan {
+        target_dir: target_dir.to_path_buf(),
+        files,
+        settings,
+        empty_dirs,
+    })
 }
 
-/// Remove daily hooks from settings, returns true if changes were made
-fn remove_daily_hooks(settings: &mut Value) -> bool {
-    let mut changed = false;
+/// Print and (unless `dry_run`) apply a plan, returning the number of items
+fn execute(plan: &UninstallPlan, dry_run: bool) -> Result<usize> {
+    if plan.is_empty() {
+        return Ok(0);
+    }
+    let verb = if dry_run { "Would remove" } else { "Removed" };
 
-    if let Some(hooks) = settings.get_mut("hooks").and_then(|h| h.as_object_mut()) {
-        // Remove daily hooks from SessionStart
-        if let Some(session_start) = hooks.get_mut("SessionStart") {
-            if let Some(arr) = session_start.as_array_mut() {
-                let original_len = arr.len();
-                arr.retain(|entry| !is_daily_hook_entry(entry, "daily hook session-start"));
-                if arr.len() != original_len {
-                    changed = true;
+    for file in &plan.files {
+        if !dry_run {
+            fs::remove_file(file)?;
+        }
+        println!("[daily] {}: {}", verb, file.display());
+    }
+
+    if let Some((settings_file, settings)) = &plan.settings {
+        match settings {
+            Some(settings) => {
+                if !dry_run {
+                    let output = serde_json::to_string_pretty(settings)?;
+                    fs::write(settings_file, output)?;
                 }
-                // Remove the event entirely if no hooks remain
-                if arr.is_empty() {
-                    hooks.remove("SessionStart");
+                println!("[daily] {} hooks from: {}", verb, settings_file.display());
+            }
+            None => {
+                // Nothing but daily hooks was in it, so it was created by install
+                if !dry_run {
+                    fs::remove_file(settings_file)?;
                 }
+                println!("[daily] {}: {}", verb, settings_file.display());
             }
         }
+    }
 
-        // Remove daily hooks from SessionEnd
-        if let Some(session_end) = hooks.get_mut("SessionEnd") {
-            if let Some(arr) = session_end.as_array_mut() {
-                let original_len = arr.len();
-                arr.retain(|entry| !is_daily_hook_entry(entry, "daily hook session-end"));
-                if arr.len() != original_len {
+    for dir in &plan.empty_dirs {
+        if !dry_run {
+            fs::remove_dir(dir)?;
+        }
+        println!("[daily] {} empty directory: {}", verb, dir.display());
+    }
+
+    if !dry_run {
+        // Leave no empty .claude behind in a project that only had daily in it
+        let _ = fs::remove_dir(&plan.target_dir);
+    }
+
+    Ok(plan.item_count())
+}
+
+/// Remove daily hooks from settings, returns true if changes were made.
+///
+/// This is the inverse of `merge_hooks`: only the daily commands are removed,
+/// other hooks sharing the same entry or event stay, and entries/events/the
+/// hooks object are dropped only once they are empty.
+fn remove_daily_hooks(settings: &mut Value) -> bool {
+    let mut changed = false;
+
+    let Some(hooks) = settings.get_mut("hooks").and_then(|h| h.as_object_mut()) else {
+        return false;
+    };
+
+    for (event_name, command) in DAILY_HOOKS {
+        let Some(entries) = hooks.get_mut(*event_name).and_then(|e| e.as_array_mut()) else {
+            continue;
+        };
+
+        for entry in entries.iter_mut() {
+            if let Some(inner_hooks) = entry.get_mut("hooks").and_then(|h| h.as_array_mut()) {
+                let original_len = inner_hooks.len();
+                inner_hooks.retain(|hook| !is_daily_command(hook, command));
+                if inner_hooks.len() != original_len {
                     changed = true;
                 }
-                // Remove the event entirely if no hooks remain
-                if arr.is_empty() {
-                    hooks.remove("SessionEnd");
-                }
             }
         }
 
-        // Remove hooks object entirely if empty
-        if hooks.is_empty() {
-            settings.as_object_mut().unwrap().remove("hooks");
+        // Drop entries whose hook list we emptied
+        entries.retain(|entry| {
+            entry
+                .get("hooks")
+                .and_then(|h| h.as_array())
+                .is_none_or(|h| !h.is_empty())
+        });
+
+        // Remove the event entirely if no hooks remain
+        if entries.is_empty() {
+            hooks.remove(*event_name);
         }
     }
 
+    // Remove hooks object entirely if empty
+    if changed && hooks.is_empty() {
+        settings.as_object_mut().unwrap().remove("hooks");
+    }
+
     changed
 }
 
-/// Check if a hook entry contains the specified daily command
-fn is_daily_hook_entry(entry: &Value, command: &str) -> bool {
-    if let Some(inner_hooks) = entry.get("hooks").and_then(|h| h.as_array()) {
-        for inner_hook in inner_hooks {
-            if let Some(cmd) = inner_hook.get("command").and_then(|c| c.as_str()) {
-                if cmd == command {
-                    return true;
+/// Check if a single hook runs the specified daily command
+fn is_daily_command(hook: &Value, command: &str) -> bool {
+    hook.get("command").and_then(|c| c.as_str()) == Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_daily_hooks_keeps_foreign_entries() {
+        let mut settings = json!({
+            "model": "opus",
+            "hooks": {
+                "Stop": [
+                    { "hooks": [{ "type": "command", "command": "notify-send done" }] },
+                    { "hooks": [{ "type": "command", "command": "daily hook stop" }] }
+                ],
+                "PreCompact": [
+                    { "hooks": [{ "type": "command", "command": "daily hook pre-compact" }] }
+                ]
+            }
+        });
+
+        assert!(remove_daily_hooks(&mut settings));
+        assert_eq!(
+            settings,
+            json!({
+                "model": "opus",
+                "hooks": {
+                    "Stop": [
+                        { "hooks": [{ "type": "command", "command": "notify-send done" }] }
+                    ]
                 }
+            })
+        );
+        assert!(!remove_daily_hooks(&mut settings));
+    }
+
+    #[test]
+    fn test_remove_daily_hooks_from_shared_entry() {
+        let mut settings = json!({
+            "hooks": {
+                "SessionEnd": [{
+                    "matcher": "",
+                    "hooks": [
+                        { "type": "command", "command": "daily hook session-end" },
+                        { "type": "command", "command": "./backup.sh" }
+                    ]
+                }]
             }
-        }
+        });
+
+        assert!(remove_daily_hooks(&mut settings));
+        assert_eq!(
+            settings["hooks"]["SessionEnd"][0]["hooks"],
+            json!([{ "type": "command", "command": "./backup.sh" }])
+        );
+    }
+
+    #[test]
+    fn test_plan_and_execute_reverse_install() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join(".claude");
+        fs::create_dir_all(target.join("hooks")).unwrap();
+        fs::create_dir_all(target.join("commands")).unwrap();
+        fs::write(target.join("hooks").join(HOOKS_FILE), "{}").unwrap();
+        fs::write(target.join("commands").join("daily-view.md"), "").unwrap();
+        fs::write(target.join("commands").join("mine.md"), "").unwrap();
+        fs::write(
+            target.join("settings.json"),
+            json!({ "hooks": { "Stop": [
+                { "hooks": [{ "type": "command", "command": "daily hook stop" }] }
+            ]}})
+            .to_string(),
+        )
+        .unwrap();
+
+        // Dry run changes nothing
+        let plan = plan_uninstall(&target, true).unwrap();
+        assert_eq!(plan.files.len(), 2);
+        assert_eq!(plan.empty_dirs, vec![target.join("hooks")]);
+        assert!(matches!(plan.settings, Some((_, None))));
+        assert_eq!(execute(&plan, true).unwrap(), 4);
+        assert!(target.join("settings.json").exists());
+
+        execute(&plan, false).unwrap();
+        assert!(!target.join("settings.json").exists());
+        assert!(!target.join("hooks").exists());
+        // User files are left alone
+        assert!(target.join("commands").join("mine.md").exists());
+        assert!(plan_uninstall(&target, true).unwrap().is_empty());
     }
-    false
 }
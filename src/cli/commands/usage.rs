@@ -0,0 +1,255 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use std::fs;
+
+use crate::archive::paths::PathFilter;
+use crate::cli::output::print_json;
+use crate::config::{load_config, Config};
+use crate::notifications::{notify, WebhookEvent};
+use crate::usage::blocks::{self, TokenLimit, UsageBlock};
+use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
+
+/// Start of the last block the near-limit webhook was sent for
+const NOTIFIED_MARKER: &str = ".usage_block_notified";
+
+/// Show Claude Code usage by day, or by project with `by_project`
+pub async fn summary(by_project: bool, days: Option<i64>, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let summary = scanner::summarize_recent(&pricing, &PathFilter::from_config(&config), days);
+    if json {
+        if by_project {
+            return print_json(&summary.projects);
+        }
+        return print_json(&summary);
+    }
+
+    if summary.total_sessions == 0 {
+        println!("No usage found in ~/.claude/projects.");
+        return Ok(());
+    }
+
+    if by_project {
+        println!(
+            "{:<48} {:>8} {:>14} {:>10}",
+            "PROJECT".bold(),
+            "SESSIONS".bold(),
+            "TOKENS".bold(),
+            "COST".bold()
+        );
+        println!("{}", "-".repeat(83));
+        for project in &summary.projects {
+            let tokens = project.input_tokens
+                + project.output_tokens
+                + project.cache_creation_tokens
+                + project.cache_read_tokens;
+            println!(
+                "{:<48} {:>8} {:>14} {:>10}",
+                shorten_path(&project.project, 48),
+                project.session_count,
+                tokens,
+                format!("${:.2}", project.total_cost_usd)
+            );
+        }
+    } else {
+        println!(
+            "{:<12} {:>8} {:>14} {:>10}",
+            "DATE".bold(),
+            "SESSIONS".bold(),
+            "TOKENS".bold(),
+            "COST".bold()
+        );
+        println!("{}", "-".repeat(47));
+        for day in &summary.daily_usage {
+            let tokens = day.input_tokens
+                + day.output_tokens
+                + day.cache_creation_tokens
+                + day.cache_read_tokens;
+            println!(
+                "{:<12} {:>8} {:>14} {:>10}",
+                day.date,
+                day.session_count,
+                tokens,
+                format!("${:.2}", day.total_cost_usd)
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{} ${:.2} over {} sessions",
+        "Total:".bold(),
+        summary.total_cost_usd,
+        summary.total_sessions
+    );
+    Ok(())
+}
+
+/// Keep the end of a long path, which names the project
+fn shorten_path(path: &str, width: usize) -> String {
+    let chars = path.chars().count();
+    if chars <= width {
+        return path.to_string();
+    }
+    let tail: String = path.chars().skip(chars - (width - 3)).collect();
+    format!("...{}", tail)
+}
+
+/// Show usage grouped into 5-hour billing blocks
+pub async fn blocks(
+    active: bool,
+    recent: Option<i64>,
+    token_limit: Option<String>,
+    notify: bool,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let limit = match token_limit {
+        Some(limit) => Some(limit.parse::<TokenLimit>()?),
+        None => config.usage.block_token_limit.map(TokenLimit::Tokens),
+    };
+
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let mut report = blocks::report(&pricing, &PathFilter::from_config(&config), limit, recent);
+
+    if notify {
+        notify_near_limit(&config, report.active()).await?;
+    }
+
+    if active {
+        report.blocks.retain(|block| block.is_active);
+    }
+    if json {
+        return print_json(&report);
+    }
+
+    if report.blocks.is_empty() {
+        if active {
+            println!("No active billing block.");
+        } else {
+            println!("No usage found in ~/.claude/projects.");
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:>14} {:>10} {:>7}  {}",
+        "BLOCK".bold(),
+        "TOKENS".bold(),
+        "COST".bold(),
+        "LIMIT".bold(),
+        "MODELS".bold()
+    );
+    println!("{}", "-".repeat(80));
+    for block in &report.blocks {
+        let span = format!(
+            "{} - {}",
+            local(block.start).format("%Y-%m-%d %H:%M"),
+            local(block.end).format("%H:%M")
+        );
+        let percent = block
+            .limit
+            .as_ref()
+            .map(|limit| format!("{:.0}%", limit.used_percent))
+            .unwrap_or_else(|| "-".into());
+        let line = format!(
+            "{:<24} {:>14} {:>10} {:>7}  {}",
+            span,
+            block.total_tokens,
+            format!("${:.2}", block.cost_usd),
+            percent,
+            block.models.join(", ")
+        );
+        if block.is_active {
+            println!("{}", line.green());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    if let Some(block) = report.active() {
+        print_active(block);
+    }
+    Ok(())
+}
+
+fn print_active(block: &UsageBlock) {
+    println!();
+    println!(
+        "{} {} - {}",
+        "Active block:".bold(),
+        local(block.start).format("%H:%M"),
+        local(block.end).format("%H:%M")
+    );
+    if let Some(rate) = &block.burn_rate {
+        println!(
+            "  {} {:.0} tokens/min, ${:.2}/h",
+            "Burn rate:".bold(),
+            rate.tokens_per_minute,
+            rate.cost_per_hour
+        );
+    }
+    if let Some(projection) = &block.projection {
+        println!(
+            "  {} {} tokens, ${:.2} ({}h {:02}m left)",
+            "Projected:".bold(),
+            projection.total_tokens,
+            projection.cost_usd,
+            projection.remaining_minutes / 60,
+            projection.remaining_minutes % 60
+        );
+    }
+    if let Some(limit) = &block.limit {
+        let mut status = format!(
+            "{:.0}% of {} tokens used",
+            limit.used_percent, limit.token_limit
+        );
+        if let Some(projected) = limit.projected_percent {
+            status.push_str(&format!(", {:.0}% projected", projected));
+        }
+        println!("  {} {}", "Limit:".bold(), status);
+        if let Some(at) = limit.exhausted_at {
+            println!(
+                "  {} limit reached around {}",
+                "Warning:".yellow().bold(),
+                local(at).format("%H:%M")
+            );
+        }
+    }
+}
+
+/// Send `usage.block_near_limit` once for the active block when it has
+/// used `usage.block_warn_percent` of the token limit
+async fn notify_near_limit(config: &Config, active: Option<&UsageBlock>) -> Result<()> {
+    let Some(block) = active else {
+        return Ok(());
+    };
+    let Some(limit) = &block.limit else {
+        anyhow::bail!(
+            "--notify needs a token limit: pass --token-limit or set usage.block_token_limit"
+        );
+    };
+    if limit.used_percent < config.usage.block_warn_percent as f64 {
+        return Ok(());
+    }
+
+    let marker = config.storage_path().join(NOTIFIED_MARKER);
+    let start = block.start.to_rfc3339();
+    if fs::read_to_string(&marker).is_ok_and(|notified| notified.trim() == start) {
+        return Ok(());
+    }
+    notify(config, WebhookEvent::BlockNearLimit, block).await;
+    fs::create_dir_all(config.storage_path())?;
+    fs::write(&marker, start)?;
+    Ok(())
+}
+
+fn local(at: DateTime<Utc>) -> DateTime<Local> {
+    at.with_timezone(&Local)
+}
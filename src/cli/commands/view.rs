@@ -1,26 +1,42 @@
-use anyhow::Result;
-use chrono::{Duration, Local};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, FuzzySelect};
 
+use crate::archive::paths::PathFilter;
 use crate::archive::ArchiveManager;
+use crate::cli::output::{self, print_json};
 use crate::config::load_config;
+use crate::i18n::{self, t};
+use crate::server::dto::{DateArchiveDto, DateInfo, DateRangeDto};
+use crate::server::handlers::{build_daily_summary_dto, build_date_info, build_session_briefs};
+use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
 
 /// View archives with interactive selection
-pub async fn run(date: Option<String>, summary_only: bool, list: bool) -> Result<()> {
+pub async fn run(date: Option<String>, summary_only: bool, list: bool, json: bool) -> Result<()> {
     let config = load_config()?;
     let manager = ArchiveManager::new(config);
 
     // If date is provided, view that date directly
     if let Some(view_date) = date {
-        return view_date_archive(&manager, &view_date, summary_only, list).await;
+        return view_date_archive(&manager, &view_date, summary_only, list, json).await;
     }
 
     // Otherwise, show interactive date selection
     let dates = manager.list_dates()?;
 
+    // No prompt in JSON mode: list the dates instead
+    if json {
+        let infos: Vec<DateInfo> = dates
+            .into_iter()
+            .map(|d| build_date_info(&manager, d))
+            .collect();
+        return print_json(&infos);
+    }
+
     if dates.is_empty() {
-        println!("{}", "No archives found.".yellow());
+        println!("{}", t("view.no_archives").yellow());
         return Ok(());
     }
 
@@ -31,12 +47,13 @@ pub async fn run(date: Option<String>, summary_only: bool, list: bool) -> Result
             let sessions = manager.list_sessions(d).unwrap_or_default();
             let count = sessions.len();
             let label = format_date_label(d);
-            format!("{} {} ({} sessions)", d, label, count)
+            let count = i18n::fmt("view.session_count", &[("count", &count)]);
+            format!("{} {} ({})", d, label, count)
         })
         .collect();
 
     let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select a date to view")
+        .with_prompt(t("view.select_date"))
         .items(&items)
         .default(0)
         .interact_opt()?;
@@ -45,31 +62,146 @@ pub async fn run(date: Option<String>, summary_only: bool, list: bool) -> Result
         Some(idx) => {
             let view_date = &dates[idx];
             println!();
-            view_date_archive(&manager, view_date, summary_only, list).await
+            view_date_archive(&manager, view_date, summary_only, list, false).await
         }
         None => {
-            println!("{}", "Cancelled.".dimmed());
+            println!("{}", t("cancelled").dimmed());
             Ok(())
         }
     }
 }
 
 /// View today's archive
-pub async fn run_today(summary_only: bool, list: bool) -> Result<()> {
+pub async fn run_today(summary_only: bool, list: bool, json: bool) -> Result<()> {
     let config = load_config()?;
     let manager = ArchiveManager::new(config);
     let today = Local::now().format("%Y-%m-%d").to_string();
-    view_date_archive(&manager, &today, summary_only, list).await
+    view_date_archive(&manager, &today, summary_only, list, json).await
 }
 
 /// View yesterday's archive
-pub async fn run_yesterday(summary_only: bool, list: bool) -> Result<()> {
+pub async fn run_yesterday(summary_only: bool, list: bool, json: bool) -> Result<()> {
     let config = load_config()?;
     let manager = ArchiveManager::new(config);
     let yesterday = (Local::now() - Duration::days(1))
         .format("%Y-%m-%d")
         .to_string();
-    view_date_archive(&manager, &yesterday, summary_only, list).await
+    view_date_archive(&manager, &yesterday, summary_only, list, json).await
+}
+
+/// View every archived day of `--week`, `--last` or `--from/--to`, oldest
+/// first, followed by session and cost totals
+pub async fn run_range(
+    week: bool,
+    last: Option<u32>,
+    from: Option<String>,
+    to: Option<String>,
+    summary_only: bool,
+    list: bool,
+    json: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let today = Local::now().date_naive();
+    let (start, end) = date_range(week, last, from.as_deref(), to.as_deref(), today)?;
+    let manager = ArchiveManager::new(config.clone());
+
+    let days: Vec<String> = start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| day.format("%Y-%m-%d").to_string())
+        .collect();
+    let mut dates: Vec<String> = manager
+        .list_dates()?
+        .into_iter()
+        .filter(|d| days.contains(d))
+        .collect();
+    dates.sort();
+
+    let total_sessions: usize = dates
+        .iter()
+        .map(|d| build_date_info(&manager, d.clone()).session_count)
+        .sum();
+    let pricing = PricingData::load()
+        .await
+        .with_overrides(&config.usage.price_overrides);
+    let usage = scanner::scan_all_sessions(None, &pricing, &PathFilter::from_config(&config));
+    let total_cost_usd = scanner::aggregate_usage(&usage, Some(&days)).total_cost_usd;
+
+    if json {
+        return print_json(&DateRangeDto {
+            from: start.to_string(),
+            to: end.to_string(),
+            days: dates
+                .iter()
+                .map(|d| date_archive_dto(&manager, d))
+                .collect(),
+            total_sessions,
+            total_cost_usd,
+        });
+    }
+
+    if dates.is_empty() {
+        println!(
+            "{}",
+            i18n::fmt("view.no_archives_range", &[("from", &start), ("to", &end)]).yellow()
+        );
+        return Ok(());
+    }
+
+    for date in &dates {
+        if list {
+            list_sessions(&manager, date).await?;
+        } else if summary_only {
+            show_daily_summary(&manager, date).await?;
+        } else {
+            show_full_archive(&manager, date).await?;
+        }
+        println!();
+    }
+
+    println!("{}", output::rule('=', 50));
+    let cost = format!("${:.2}", total_cost_usd).green();
+    println!(
+        "{}",
+        i18n::fmt(
+            "view.range_total",
+            &[
+                ("from", &start),
+                ("to", &end),
+                ("days", &dates.len()),
+                ("sessions", &total_sessions),
+                ("cost", &cost),
+            ],
+        )
+    );
+
+    Ok(())
+}
+
+/// First and last day selected by `--week`, `--last` or `--from/--to`
+fn date_range(
+    week: bool,
+    last: Option<u32>,
+    from: Option<&str>,
+    to: Option<&str>,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}': expected YYYY-MM-DD", date))
+    };
+    if week {
+        let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        return Ok((monday, today));
+    }
+    if let Some(days) = last {
+        anyhow::ensure!(days > 0, "--last needs at least 1 day");
+        return Ok((today - Duration::days(days as i64 - 1), today));
+    }
+    let start = parse(from.context("Pass --week, --last or --from")?)?;
+    let end = to.map(parse).transpose()?.unwrap_or(today);
+    anyhow::ensure!(start <= end, "--from {} is after --to {}", start, end);
+    Ok((start, end))
 }
 
 /// Format date with relative label (today, yesterday, etc.)
@@ -80,9 +212,9 @@ fn format_date_label(date: &str) -> String {
         .to_string();
 
     if date == today {
-        "(today)".green().to_string()
+        t("view.today").green().to_string()
     } else if date == yesterday {
-        "(yesterday)".cyan().to_string()
+        t("view.yesterday").cyan().to_string()
     } else {
         String::new()
     }
@@ -94,7 +226,12 @@ async fn view_date_archive(
     date: &str,
     summary_only: bool,
     list: bool,
+    json: bool,
 ) -> Result<()> {
+    if json {
+        return print_date_json(manager, date, summary_only, list);
+    }
+
     if list {
         return list_sessions(manager, date).await;
     }
@@ -103,26 +240,74 @@ async fn view_date_archive(
         return show_daily_summary(manager, date).await;
     }
 
-    show_full_archive(manager, date).await
+    show_full_archive(manager, date).await?;
+    println!();
+    let command = "daily view --date DATE".cyan();
+    println!("{}", i18n::fmt("view.hint", &[("command", &command)]));
+    Ok(())
+}
+
+/// Same views as text mode, using the dashboard API shapes
+fn print_date_json(
+    manager: &ArchiveManager,
+    date: &str,
+    summary_only: bool,
+    list: bool,
+) -> Result<()> {
+    let archive = date_archive_dto(manager, date);
+    if list {
+        return print_json(&archive.sessions);
+    }
+    if summary_only {
+        return print_json(&archive.summary);
+    }
+    print_json(&archive)
+}
+
+fn date_archive_dto(manager: &ArchiveManager, date: &str) -> DateArchiveDto {
+    DateArchiveDto {
+        date: date.to_string(),
+        summary: build_daily_summary_dto(manager, date).ok(),
+        sessions: build_session_briefs(manager, date).unwrap_or_default(),
+    }
 }
 
 async fn list_sessions(manager: &ArchiveManager, date: &str) -> Result<()> {
-    let sessions = manager.list_sessions(date)?;
+    let mut sessions = manager.list_sessions(date)?;
+    sessions.sort_by_key(|session| !manager.is_pinned(date, session));
 
     if sessions.is_empty() {
-        println!("{}", format!("No sessions found for {}", date).yellow());
+        println!(
+            "{}",
+            i18n::fmt("view.no_sessions_for", &[("date", &date)]).yellow()
+        );
         return Ok(());
     }
 
-    println!("{}", format!("Sessions for {}:", date).cyan().bold());
+    println!(
+        "{}",
+        i18n::fmt("view.sessions_for", &[("date", &date)])
+            .cyan()
+            .bold()
+    );
     println!();
 
     for (i, session) in sessions.iter().enumerate() {
-        println!("  {}. {}", (i + 1).to_string().green(), session);
+        let marker = if manager.is_pinned(date, session) {
+            " ★".yellow().to_string()
+        } else {
+            String::new()
+        };
+        let number = format!("{}.", i + 1);
+        let name = output::ellipsize(session, output::width(100).saturating_sub(number.len() + 5));
+        println!("  {} {}{}", number.green(), name, marker);
     }
 
     println!();
-    println!("Total: {} sessions", sessions.len());
+    println!(
+        "{}",
+        i18n::fmt("view.total_sessions", &[("count", &sessions.len())])
+    );
 
     Ok(())
 }
@@ -130,16 +315,21 @@ async fn list_sessions(manager: &ArchiveManager, date: &str) -> Result<()> {
 async fn show_daily_summary(manager: &ArchiveManager, date: &str) -> Result<()> {
     match manager.read_daily_summary(date) {
         Ok(content) => {
-            println!("{}", format!("Daily Summary - {}", date).cyan().bold());
-            println!("{}", "=".repeat(50));
+            println!(
+                "{}",
+                i18n::fmt("view.daily_summary", &[("date", &date)])
+                    .cyan()
+                    .bold()
+            );
+            println!("{}", output::rule('=', 50));
             println!();
-            println!("{}", content);
+            println!("{}", i18n::localize_headings(&content, i18n::lang()));
             Ok(())
         }
         Err(_) => {
             println!(
                 "{}",
-                format!("No daily summary found for {}", date).yellow()
+                i18n::fmt("view.no_summary", &[("date", &date)]).yellow()
             );
             Ok(())
         }
@@ -148,8 +338,13 @@ async fn show_daily_summary(manager: &ArchiveManager, date: &str) -> Result<()>
 
 async fn show_full_archive(manager: &ArchiveManager, date: &str) -> Result<()> {
     // Show daily summary first
-    println!("{}", format!("Daily Archive - {}", date).cyan().bold());
-    println!("{}", "=".repeat(50));
+    println!(
+        "{}",
+        i18n::fmt("view.daily_archive", &[("date", &date)])
+            .cyan()
+            .bold()
+    );
+    println!("{}", output::rule('=', 50));
     println!();
 
     // Try to show daily summary
@@ -157,13 +352,13 @@ async fn show_full_archive(manager: &ArchiveManager, date: &str) -> Result<()> {
         // Extract just the overview section for brevity
         if let Some(start) = content.find("## Overview") {
             let after_header = &content[start..];
-            if let Some(end) = after_header.find("\n## Sessions") {
-                println!("{}", &after_header[..end]);
-            } else {
-                println!(
-                    "{}",
-                    after_header.lines().take(10).collect::<Vec<_>>().join("\n")
-                );
+            let lines: Vec<&str> = match after_header.find("\n## Sessions") {
+                Some(end) => after_header[..end].lines().collect(),
+                None => after_header.lines().take(10).collect(),
+            };
+            let overview = i18n::localize_headings(&lines.join("\n"), i18n::lang());
+            for line in overview.lines() {
+                output::print_wrapped(line, "");
             }
         }
     }
@@ -171,18 +366,25 @@ async fn show_full_archive(manager: &ArchiveManager, date: &str) -> Result<()> {
     println!();
 
     // List sessions
-    let sessions = manager.list_sessions(date)?;
+    let mut sessions = manager.list_sessions(date)?;
+    sessions.sort_by_key(|session| !manager.is_pinned(date, session));
 
     if sessions.is_empty() {
-        println!("{}", "No sessions archived yet.".yellow());
+        println!("{}", t("view.no_sessions_yet").yellow());
         return Ok(());
     }
 
-    println!("{}", "Sessions:".green().bold());
+    println!("{}", t("view.sessions").green().bold());
     println!();
 
+    let line_width = output::width(100).saturating_sub(4);
     for session in &sessions {
-        println!("  {} {}", "●".green(), session);
+        let name = output::ellipsize(session, line_width);
+        if manager.is_pinned(date, session) {
+            println!("  {} {}", "★".yellow(), name);
+        } else {
+            println!("  {} {}", "●".green(), name);
+        }
 
         // Show brief summary if available
         if let Ok(content) = manager.read_session(date, session) {
@@ -191,18 +393,67 @@ async fn show_full_archive(manager: &ArchiveManager, date: &str) -> Result<()> {
                 let after_header = &content[start + 11..];
                 let first_line = after_header.lines().next().unwrap_or("");
                 if !first_line.is_empty() && !first_line.starts_with('#') {
-                    let truncated: String = first_line.chars().take(80).collect();
-                    println!("    {}", truncated.dimmed());
+                    println!("    {}", output::ellipsize(first_line, line_width).dimmed());
                 }
             }
         }
     }
 
-    println!();
-    println!(
-        "Use {} to see a specific session",
-        "daily view --date DATE".cyan()
-    );
-
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_range() {
+        // A Thursday
+        let today = NaiveDate::from_ymd_opt(2024, 1, 18).unwrap();
+        let day = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        let week = date_range(true, None, None, None, today).unwrap();
+        assert_eq!(week, (day("2024-01-15"), today));
+        let last = date_range(false, Some(7), None, None, today).unwrap();
+        assert_eq!(last, (day("2024-01-12"), today));
+        let range = date_range(false, None, Some("2024-01-01"), Some("2024-01-03"), today);
+        assert_eq!(range.unwrap(), (day("2024-01-01"), day("2024-01-03")));
+        let open = date_range(false, None, Some("2024-01-10"), None, today).unwrap();
+        assert_eq!(open, (day("2024-01-10"), today));
+
+        assert!(date_range(false, Some(0), None, None, today).is_err());
+        assert!(date_range(false, None, Some("2024-01-05"), Some("2024-01-01"), today).is_err());
+        assert!(date_range(false, None, Some("last week"), None, today).is_err());
+    }
+
+    #[test]
+    fn test_date_json() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = crate::config::Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config);
+        manager
+            .write_session(
+                "2024-01-15",
+                "fix-login",
+                "---\ntitle: \"Fix login\"\n---\n\nFixed it.\n",
+            )
+            .unwrap();
+        manager
+            .write_daily_summary("2024-01-15", "# Daily Summary\n\nOne session.\n")
+            .unwrap();
+
+        let json = serde_json::to_value(date_archive_dto(&manager, "2024-01-15")).unwrap();
+        assert_eq!(json["date"], "2024-01-15");
+        assert_eq!(json["sessions"][0]["name"], "fix-login");
+        assert_eq!(json["sessions"][0]["title"], "Fix login");
+        assert!(json["summary"]["raw_content"]
+            .as_str()
+            .unwrap()
+            .contains("One session."));
+
+        let empty = serde_json::to_value(date_archive_dto(&manager, "2024-01-16")).unwrap();
+        assert!(empty["summary"].is_null());
+        assert_eq!(empty["sessions"], serde_json::json!([]));
+    }
+}
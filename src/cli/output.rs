@@ -0,0 +1,354 @@
+use anyhow::Result;
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Print a value as pretty JSON on stdout, for `--json` mode
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Narrowest width tables and bars are laid out for
+const MIN_WIDTH: usize = 40;
+
+/// How text output is laid out, set once from the global flags
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    plain: bool,
+    /// Columns available; `None` when output isn't a terminal
+    width: Option<usize>,
+}
+
+static STYLE: OnceLock<Style> = OnceLock::new();
+
+/// Apply `--no-color` and `--plain`. Color is also off when `NO_COLOR` is
+/// set; `--plain` additionally turns off truncation and prints tables as
+/// tab-separated values, for piping into other tools.
+pub fn init(no_color: bool, plain: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || plain || no_color_env {
+        colored::control::set_override(false);
+    }
+    let width = if plain { None } else { terminal_width() };
+    let _ = STYLE.set(Style { plain, width });
+}
+
+fn style() -> Style {
+    *STYLE.get_or_init(|| Style {
+        plain: false,
+        width: terminal_width(),
+    })
+}
+
+/// `COLUMNS` if set, else the size of the terminal on stdout
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+    {
+        return Some(columns.max(MIN_WIDTH));
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| (columns as usize).max(MIN_WIDTH))
+}
+
+/// Whether `--plain` was given
+pub fn plain() -> bool {
+    style().plain
+}
+
+/// Columns available for a line, capped at `max` so wide terminals keep
+/// a readable layout
+pub fn width(max: usize) -> usize {
+    style().width.map_or(max, |w| w.min(max))
+}
+
+/// Columns `text` takes up on screen, ignoring color escapes
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip to the end of the `ESC [ ... m` sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        width += c.width().unwrap_or(0);
+    }
+    width
+}
+
+/// `text` cut to `max` columns with a trailing `…`; unchanged with
+/// `--plain`, where full values matter more than alignment
+pub fn ellipsize(text: &str, max: usize) -> String {
+    if plain() {
+        return text.to_string();
+    }
+    truncate(text, max)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Word-wrap `text` into lines of at most `max` columns. Words longer than
+/// a line (URLs, CJK runs) are split by character.
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let max = max.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut used = 0;
+    for word in text.split_whitespace() {
+        let w = word.width();
+        if used > 0 && used + 1 + w <= max {
+            line.push(' ');
+            line.push_str(word);
+            used += 1 + w;
+            continue;
+        }
+        if used > 0 {
+            lines.push(std::mem::take(&mut line));
+            used = 0;
+        }
+        for c in word.chars() {
+            let cw = c.width().unwrap_or(0);
+            if used + cw > max && used > 0 {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            line.push(c);
+            used += cw;
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Print `text` wrapped to the terminal after `indent`, keeping the
+/// text's own leading whitespace (e.g. of a nested list item) on every line
+pub fn print_wrapped(text: &str, indent: &str) {
+    if plain() {
+        println!("{}{}", indent, text);
+        return;
+    }
+    let body = text.trim_start();
+    let indent = format!("{}{}", indent, &text[..text.len() - body.len()]);
+    let max = width(100).saturating_sub(indent.width());
+    for line in wrap(body, max) {
+        println!("{}{}", indent, line);
+    }
+}
+
+/// A horizontal rule of `ch` as wide as the terminal allows, up to `max`
+pub fn rule(ch: char, max: usize) -> String {
+    ch.to_string().repeat(width(max))
+}
+
+/// Pad `cell` with spaces to `width` columns, on the left if `right`
+pub fn pad(cell: &str, width: usize, right: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(cell)));
+    if right {
+        format!("{}{}", fill, cell)
+    } else {
+        format!("{}{}", cell, fill)
+    }
+}
+
+/// Columns of colored cells that fit the terminal: shrinkable columns are
+/// ellipsized first, and when that isn't enough each row is printed as a
+/// block of `HEADER value` lines instead
+pub struct Table {
+    headers: Vec<&'static str>,
+    right: Vec<bool>,
+    shrink: Vec<usize>,
+    rows: Vec<Vec<ColoredString>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            right: vec![false; headers.len()],
+            shrink: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Right-align column `col`, e.g. for amounts
+    pub fn align_right(mut self, col: usize) -> Self {
+        self.right[col] = true;
+        self
+    }
+
+    /// Let column `col` be ellipsized on narrow terminals, in the order
+    /// columns are given
+    pub fn shrink(mut self, col: usize) -> Self {
+        self.shrink.push(col);
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<ColoredString>) {
+        self.rows.push(cells);
+    }
+
+    pub fn print(&self) {
+        let style = style();
+        for line in self.render(style.width, style.plain) {
+            println!("{}", line);
+        }
+    }
+
+    /// Lines of the table for a terminal `width` columns wide
+    fn render(&self, width: Option<usize>, plain: bool) -> Vec<String> {
+        if plain {
+            let mut lines = vec![self.headers.join("\t")];
+            lines.extend(self.rows.iter().map(|row| {
+                row.iter()
+                    .map(|cell| cell.input.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            }));
+            return lines;
+        }
+
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.width()).collect();
+        for row in &self.rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.input.width());
+            }
+        }
+        let gaps = 2 * (widths.len().saturating_sub(1));
+        let total = |widths: &[usize]| widths.iter().sum::<usize>() + gaps;
+        if let Some(limit) = width {
+            for &col in &self.shrink {
+                let over = total(&widths).saturating_sub(limit);
+                let floor = self.headers[col].width().max(8);
+                widths[col] = widths[col].saturating_sub(over).max(floor);
+            }
+            if total(&widths) > limit {
+                return self.render_stacked(limit);
+            }
+        }
+
+        let mut lines = Vec::new();
+        let header: Vec<String> = self
+            .headers
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (h, w))| pad(&h.bold().to_string(), *w, self.right[i]))
+            .collect();
+        lines.push(header.join("  ").trim_end().to_string());
+        lines.push("-".repeat(total(&widths)));
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, w))| {
+                    let mut cell = cell.clone();
+                    cell.input = truncate(&cell.input, *w);
+                    pad(&cell.to_string(), *w, self.right[i])
+                })
+                .collect();
+            lines.push(cells.join("  ").trim_end().to_string());
+        }
+        lines
+    }
+
+    /// One block per row with a line per column, for very narrow terminals
+    fn render_stacked(&self, limit: usize) -> Vec<String> {
+        let label = self.headers.iter().map(|h| h.width()).max().unwrap_or(0);
+        let value = limit.saturating_sub(label + 1).max(1);
+        let mut lines = Vec::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            for (header, cell) in self.headers.iter().zip(row) {
+                let mut cell = cell.clone();
+                cell.input = truncate(&cell.input, value);
+                lines.push(format!(
+                    "{} {}",
+                    pad(&header.dimmed().to_string(), label, false),
+                    cell
+                ));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_helpers() {
+        assert_eq!(display_width("\x1b[32mok\x1b[0m"), 2);
+        assert_eq!(display_width("日报"), 4);
+        assert_eq!(truncate("fix-login-redirect", 10), "fix-login…");
+        assert_eq!(truncate("日报日报", 5), "日报…");
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn test_table_adapts_to_width() {
+        let mut table = Table::new(&["ID", "TASK", "COST"]).shrink(1).align_right(2);
+        table.row(vec![
+            "job-1".normal(),
+            "summarize a very long session title".normal(),
+            "$0.10".normal(),
+        ]);
+
+        let wide = table.render(Some(120), false);
+        assert!(wide[2].ends_with("summarize a very long session title  $0.10"));
+
+        let narrow = table.render(Some(30), false);
+        assert!(narrow.iter().all(|line| display_width(line) <= 30));
+        assert!(narrow[2].contains('…'));
+
+        // Too narrow for columns: one line per value
+        let stacked = table.render(Some(12), false);
+        assert_eq!(stacked.len(), 3);
+        assert!(stacked[0].ends_with("job-1"));
+
+        let plain = table.render(None, true);
+        assert_eq!(
+            plain[1],
+            "job-1\tsummarize a very long session title\t$0.10"
+        );
+    }
+}
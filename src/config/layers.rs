@@ -0,0 +1,401 @@
+//! Layered configuration: built-in defaults, then the user config file,
+//! then a `.daily.toml` in the project root, then `DAILY_*` environment
+//! variables. Each effective value remembers the layer it came from, so
+//! `daily config show --origin` can explain it and saving only writes
+//! the user layer back.
+//!
+//! Environment variables name a key by its path with `__` between levels,
+//! e.g. `DAILY_SUMMARIZATION__MODEL=sonnet` or `DAILY_JOBS__MAX_CONCURRENT=4`.
+//! Values are parsed as TOML when possible (`true`, `4`, `["a", "b"]`) and
+//! used as plain strings otherwise.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use super::settings::Config;
+
+/// Per-project overrides, looked up from the working directory upwards
+pub const PROJECT_FILE: &str = ".daily.toml";
+
+const ENV_PREFIX: &str = "DAILY_";
+const ENV_SEPARATOR: &str = "__";
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    User(PathBuf),
+    Project(PathBuf),
+    Env(String),
+}
+
+impl Origin {
+    /// Whether the value overrides the user config without living in it
+    fn is_override(&self) -> bool {
+        matches!(self, Origin::Project(_) | Origin::Env(_))
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::User(path) => write!(f, "user config ({})", path.display()),
+            Origin::Project(path) => write!(f, "project config ({})", path.display()),
+            Origin::Env(var) => write!(f, "env ${}", var),
+        }
+    }
+}
+
+/// Effective configuration with the origin of every leaf value
+pub struct Layered {
+    pub config: Config,
+    /// Dotted key path (e.g. `summarization.model`) to value and origin
+    pub values: BTreeMap<String, (Value, Origin)>,
+    /// Defaults plus the user config, without project or env overrides
+    base: Table,
+}
+
+impl Layered {
+    /// Load all layers for the process: the project file is searched from
+    /// the current directory and overrides come from the real environment
+    pub fn load(defaults: Config, user_path: &Path) -> Result<Self> {
+        match std::env::current_dir() {
+            Ok(dir) => Self::load_in(defaults, user_path, &dir),
+            Err(_) => Self::from_layers(defaults, user_path, None, std::env::vars()),
+        }
+    }
+
+    /// Like [`Layered::load`], with the project file searched from `dir`,
+    /// e.g. the session cwd a hook reports
+    pub fn load_in(defaults: Config, user_path: &Path, dir: &Path) -> Result<Self> {
+        let project = find_project_config(dir);
+        Self::from_layers(defaults, user_path, project.as_deref(), std::env::vars())
+    }
+
+    pub fn from_layers(
+        defaults: Config,
+        user_path: &Path,
+        project: Option<&Path>,
+        env: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self> {
+        let mut table = Table::try_from(&defaults).context("Failed to serialize default config")?;
+        let mut origins = BTreeMap::new();
+        mark_leaves(&table, "", &Origin::Default, &mut origins);
+
+        if user_path.exists() {
+            let user = read_table(user_path)?;
+            merge(
+                &mut table,
+                user,
+                "",
+                &Origin::User(user_path.to_path_buf()),
+                &mut origins,
+            );
+        }
+        let base = table.clone();
+
+        if let Some(project) = project {
+            let mut overrides = read_table(project)?;
+            if let Some(root) = project.parent() {
+                anchor_storage_path(&mut overrides, root);
+            }
+            merge(
+                &mut table,
+                overrides,
+                "",
+                &Origin::Project(project.to_path_buf()),
+                &mut origins,
+            );
+        }
+
+        let mut env: Vec<(String, String)> = env.into_iter().collect();
+        env.sort();
+        for (var, raw) in env {
+            let Some(path) = var
+                .strip_prefix(ENV_PREFIX)
+                .filter(|rest| rest.contains(ENV_SEPARATOR))
+            else {
+                continue;
+            };
+            let segments: Vec<String> = path
+                .split(ENV_SEPARATOR)
+                .map(|s| s.to_lowercase())
+                .collect();
+            let mut overlay = Table::new();
+            set_path(&mut overlay, &segments, parse_env_value(&raw));
+            merge(&mut table, overlay, "", &Origin::Env(var), &mut origins);
+        }
+
+        let config: Config = Value::Table(table.clone())
+            .try_into()
+            .context("Invalid configuration after applying overrides")?;
+        let mut values = BTreeMap::new();
+        for (path, origin) in origins {
+            if let Some(value) = get_path(&table, &path) {
+                values.insert(path, (value.clone(), origin));
+            }
+        }
+        Ok(Self {
+            config,
+            values,
+            base,
+        })
+    }
+
+    /// `config` with project and env overrides it still carries replaced by
+    /// the user layer, so saving doesn't copy them into the user config
+    pub fn user_layer(&self, config: &Config) -> Result<Config> {
+        let mut table = Table::try_from(config).context("Failed to serialize config")?;
+        for (path, (value, origin)) in &self.values {
+            if !origin.is_override() || get_path(&table, path) != Some(value) {
+                continue;
+            }
+            let segments: Vec<String> = path.split('.').map(str::to_string).collect();
+            match get_path(&self.base, path) {
+                Some(base) => set_path(&mut table, &segments, base.clone()),
+                None => remove_path(&mut table, &segments),
+            }
+        }
+        Value::Table(table)
+            .try_into()
+            .context("Failed to restore user configuration")
+    }
+}
+
+/// Nearest `.daily.toml` in `start` or one of its parents
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Storage path the nearest `.daily.toml` from `start` routes the
+/// project's archives to, if it sets one. Unlike the effective config, this
+/// ignores a `DAILY_STORAGE__PATH` the process runs under.
+pub fn project_storage_path(start: &Path) -> Option<PathBuf> {
+    let project = find_project_config(start)?;
+    let mut overrides = read_table(&project).ok()?;
+    anchor_storage_path(&mut overrides, project.parent()?);
+    match overrides.get("storage")?.get("path")? {
+        Value::String(path) => Some(PathBuf::from(path)),
+        _ => None,
+    }
+}
+
+/// A relative `storage.path` in a project file names a directory inside
+/// the project (e.g. `.daily` committed with the code), not one relative
+/// to wherever `daily` happens to run
+fn anchor_storage_path(overrides: &mut Table, root: &Path) {
+    let Some(Value::Table(storage)) = overrides.get_mut("storage") else {
+        return;
+    };
+    let Some(Value::String(path)) = storage.get_mut("path") else {
+        return;
+    };
+    if !path.starts_with('~') && Path::new(path.as_str()).is_relative() {
+        *path = root.join(path.as_str()).to_string_lossy().into_owned();
+    }
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Invalid TOML in {}", path.display()))
+}
+
+fn parse_env_value(raw: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut t| t.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn mark_leaves(
+    table: &Table,
+    prefix: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    for (key, value) in table {
+        let path = join(prefix, key);
+        match value {
+            Value::Table(inner) => mark_leaves(inner, &path, origin, origins),
+            _ => {
+                origins.insert(path, origin.clone());
+            }
+        }
+    }
+}
+
+fn merge(
+    base: &mut Table,
+    overlay: Table,
+    prefix: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    for (key, value) in overlay {
+        let path = join(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(inner)), Value::Table(overlay)) => {
+                merge(inner, overlay, &path, origin, origins)
+            }
+            (_, value) => {
+                match &value {
+                    Value::Table(inner) => mark_leaves(inner, &path, origin, origins),
+                    _ => {
+                        origins.insert(path, origin.clone());
+                    }
+                }
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn get_path<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut value = table.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
+
+fn set_path(table: &mut Table, segments: &[String], value: Value) {
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for segment in parents {
+        let entry = current
+            .entry(segment.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        let Value::Table(next) = entry else {
+            return;
+        };
+        current = next;
+    }
+    current.insert(last.clone(), value);
+}
+
+fn remove_path(table: &mut Table, segments: &[String]) {
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for segment in parents {
+        let Some(Value::Table(next)) = current.get_mut(segment) else {
+            return;
+        };
+        current = next;
+    }
+    current.remove(last);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_layers_and_origins() {
+        let dir = TempDir::new().unwrap();
+        let user = dir.path().join("config.toml");
+        fs::write(
+            &user,
+            "[summarization]\nmodel = \"sonnet\"\nsummary_language = \"zh\"\n",
+        )
+        .unwrap();
+        let project_dir = dir.path().join("repo").join("src");
+        fs::create_dir_all(&project_dir).unwrap();
+        let project = dir.path().join("repo").join(PROJECT_FILE);
+        fs::write(&project, "[summarization]\nsummary_language = \"en\"\n").unwrap();
+        assert_eq!(find_project_config(&project_dir), Some(project.clone()));
+
+        let env = vec![
+            ("DAILY_JOBS__MAX_CONCURRENT".to_string(), "4".to_string()),
+            ("DAILY_PROFILE".to_string(), "work".to_string()),
+        ];
+        let layered = Layered::from_layers(Config::default(), &user, Some(&project), env).unwrap();
+
+        assert_eq!(layered.config.summarization.model, "sonnet");
+        assert_eq!(layered.config.summarization.summary_language, "en");
+        assert_eq!(layered.config.jobs.max_concurrent, 4);
+
+        let origin = |key: &str| layered.values[key].1.clone();
+        assert_eq!(origin("summarization.model"), Origin::User(user.clone()));
+        assert_eq!(
+            origin("summarization.summary_language"),
+            Origin::Project(project.clone())
+        );
+        assert_eq!(
+            origin("jobs.max_concurrent"),
+            Origin::Env("DAILY_JOBS__MAX_CONCURRENT".into())
+        );
+        assert_eq!(origin("hooks.enable_session_end"), Origin::Default);
+
+        // Saving keeps overrides out of the user config but keeps real changes
+        let mut changed = layered.config.clone();
+        changed.summarization.model = "haiku".into();
+        let user_layer = layered.user_layer(&changed).unwrap();
+        assert_eq!(user_layer.summarization.model, "haiku");
+        assert_eq!(user_layer.summarization.summary_language, "zh");
+        assert_eq!(user_layer.jobs.max_concurrent, 2);
+    }
+
+    #[test]
+    fn test_project_storage_path_is_anchored_to_project() {
+        let dir = TempDir::new().unwrap();
+        let user = dir.path().join("config.toml");
+        let project = dir.path().join("repo").join(PROJECT_FILE);
+        fs::create_dir_all(project.parent().unwrap()).unwrap();
+        fs::write(&project, "[storage]\npath = \".daily\"\n").unwrap();
+
+        let layered =
+            Layered::from_layers(Config::default(), &user, Some(&project), Vec::new()).unwrap();
+        assert_eq!(
+            layered.config.storage.path,
+            dir.path().join("repo").join(".daily")
+        );
+        // Project overrides still stay out of the user config
+        let user_layer = layered.user_layer(&layered.config).unwrap();
+        assert_eq!(user_layer.storage.path, Config::default().storage.path);
+
+        let src = dir.path().join("repo").join("src");
+        fs::create_dir_all(&src).unwrap();
+        assert_eq!(
+            project_storage_path(&src),
+            Some(dir.path().join("repo").join(".daily"))
+        );
+        assert_eq!(project_storage_path(dir.path()), None);
+    }
+
+    #[test]
+    fn test_parse_env_value() {
+        assert_eq!(parse_env_value("true"), Value::Boolean(true));
+        assert_eq!(parse_env_value("12"), Value::Integer(12));
+        assert_eq!(parse_env_value("sonnet"), Value::String("sonnet".into()));
+        assert_eq!(
+            parse_env_value("[\"a\", \"b\"]"),
+            Value::Array(vec!["a".into(), "b".into()])
+        );
+    }
+}
@@ -1,6 +1,21 @@
+pub mod layers;
 mod settings;
+pub mod validate;
 
+pub use settings::active_profile;
 pub use settings::get_config_path;
 pub use settings::load_config;
+pub use settings::load_config_in;
+pub use settings::load_layered;
+pub use settings::migrate_config;
 pub use settings::save_config;
+pub use settings::use_profile;
+pub use settings::validate_config;
 pub use settings::Config;
+pub use settings::FacetAliases;
+pub use settings::JobRetentionConfig;
+pub use settings::S3Config;
+pub use settings::SkillsConfig;
+pub use settings::TrivialityConfig;
+pub use settings::WebDavConfig;
+pub use settings::WebhookConfig;
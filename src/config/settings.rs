@@ -1,11 +1,28 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::layers::Layered;
+use super::validate::{self, Issue, Migration, CONFIG_VERSION};
+use crate::archive::atomic::write_atomic;
+use crate::archive::layout;
+use crate::i18n::Lang;
+use crate::usage::pricing::ModelPricing;
 
 const APP_NAME: &str = "daily";
 
+/// Environment variable selecting the active profile (set by `--profile`,
+/// so spawned jobs and hooks inherit it)
+pub const PROFILE_ENV: &str = "DAILY_PROFILE";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Schema version the file was written for (missing = 0, before
+    /// versioning); upgraded by `daily config migrate`
+    #[serde(default)]
+    pub config_version: u32,
     pub storage: StorageConfig,
     pub archive: ArchiveConfig,
     pub summarization: SummarizationConfig,
@@ -14,11 +31,508 @@ pub struct Config {
     /// Custom prompt templates (None = use built-in defaults)
     #[serde(default)]
     pub prompt_templates: PromptTemplatesConfig,
+    /// Background job queue settings
+    #[serde(default)]
+    pub jobs: JobsConfig,
+    /// Outbound notifications (webhooks)
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Embeddings index for related-session discovery
+    #[serde(default)]
+    pub embeddings: EmbeddingsConfig,
+    /// External services digests can be pushed to
+    #[serde(default)]
+    pub integrations: IntegrationsConfig,
+    /// Web dashboard settings
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// Claude Code usage tracking
+    #[serde(default)]
+    pub usage: UsageConfig,
+    /// Deduplication of extracted skills
+    #[serde(default)]
+    pub skills: SkillsConfig,
+    /// Per-topic knowledge base pages built across dates
+    #[serde(default)]
+    pub topics: TopicsConfig,
+    /// Weekly anomaly checks against the trailing baseline
+    #[serde(default)]
+    pub insights: InsightsConfig,
+    /// Snapshots of the storage directory (`daily backup`)
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// Static site built by `daily publish`
+    #[serde(default)]
+    pub publish: PublishConfig,
+    /// Where `daily share` puts a digest page
+    #[serde(default)]
+    pub share: ShareConfig,
+}
+
+/// How new pending skills are compared with installed and pending ones.
+/// Similarity is a 0-1 score over the skills' names and descriptions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkillsConfig {
+    /// At or above this a new skill is a duplicate: it is dropped if the
+    /// match is installed, or merged into the match if that is pending
+    #[serde(default = "default_skill_duplicate_threshold")]
+    pub duplicate_threshold: f64,
+    /// At or above this (and below `duplicate_threshold`) a skill close to an
+    /// installed one is saved as an update of it rather than a new skill
+    #[serde(default = "default_skill_update_threshold")]
+    pub update_threshold: f64,
+}
+
+impl Default for SkillsConfig {
+    fn default() -> Self {
+        Self {
+            duplicate_threshold: default_skill_duplicate_threshold(),
+            update_threshold: default_skill_update_threshold(),
+        }
+    }
+}
+
+fn default_skill_duplicate_threshold() -> f64 {
+    0.8
+}
+
+fn default_skill_update_threshold() -> f64 {
+    0.5
+}
+
+/// Topic index pages (`{storage}/topics/`), grouping sessions from all
+/// dates by subject
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopicsConfig {
+    /// Refresh the topic pages from the session-start hook
+    #[serde(default)]
+    pub auto_update: bool,
+    /// Minimum hours between automatic refreshes
+    #[serde(default = "default_topics_interval_hours")]
+    pub interval_hours: u32,
+}
+
+impl Default for TopicsConfig {
+    fn default() -> Self {
+        Self {
+            auto_update: false,
+            interval_hours: default_topics_interval_hours(),
+        }
+    }
+}
+
+fn default_topics_interval_hours() -> u32 {
+    24
+}
+
+/// Anomaly detection comparing the last seven days with the weeks before
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InsightsConfig {
+    /// Check for anomalies once a week from the session-start hook and send
+    /// the `insights.anomaly` webhook when any are found
+    #[serde(default)]
+    pub anomaly_alerts: bool,
+    /// Change (percent) against the baseline that counts as an anomaly
+    #[serde(default = "default_anomaly_threshold_pct")]
+    pub anomaly_threshold_pct: u32,
+    /// Number of preceding weeks averaged into the baseline
+    #[serde(default = "default_baseline_weeks")]
+    pub baseline_weeks: u32,
+    /// Score (0-100) of each satisfaction level in the satisfaction trend;
+    /// levels not listed score 50
+    #[serde(default = "default_satisfaction_weights")]
+    pub satisfaction_weights: BTreeMap<String, f64>,
+    /// Facet category names from other vocabularies mapped onto the ones
+    /// insights use
+    #[serde(default)]
+    pub aliases: FacetAliases,
+}
+
+impl Default for InsightsConfig {
+    fn default() -> Self {
+        Self {
+            anomaly_alerts: false,
+            anomaly_threshold_pct: default_anomaly_threshold_pct(),
+            baseline_weeks: default_baseline_weeks(),
+            satisfaction_weights: default_satisfaction_weights(),
+            aliases: FacetAliases::default(),
+        }
+    }
+}
+
+fn default_satisfaction_weights() -> BTreeMap<String, f64> {
+    [
+        ("happy", 100.0),
+        ("satisfied", 75.0),
+        ("likely_satisfied", 75.0),
+        ("neutral", 50.0),
+        ("dissatisfied", 25.0),
+        ("frustrated", 25.0),
+    ]
+    .into_iter()
+    .map(|(level, weight)| (level.to_string(), weight))
+    .collect()
+}
+
+/// Per-field renames applied to facets as they are loaded, e.g.
+/// `friction = { misread_intent = "misunderstood_request" }`. Categories
+/// that map to the same name are counted together.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FacetAliases {
+    #[serde(default)]
+    pub goal: BTreeMap<String, String>,
+    #[serde(default)]
+    pub friction: BTreeMap<String, String>,
+    #[serde(default)]
+    pub satisfaction: BTreeMap<String, String>,
+    #[serde(default)]
+    pub outcome: BTreeMap<String, String>,
+    #[serde(default)]
+    pub session_type: BTreeMap<String, String>,
+}
+
+fn default_anomaly_threshold_pct() -> u32 {
+    50
+}
+
+fn default_baseline_weeks() -> u32 {
+    4
+}
+
+/// Timestamped snapshots of the storage directory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupConfig {
+    /// Where snapshots are written (None = `{storage}-backups` beside the
+    /// storage directory); must be outside the storage directory
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// "tar.zst" or "tar"
+    #[serde(default = "default_backup_format")]
+    pub format: String,
+    /// "off", "hourly", "daily" or "weekly"; taken by `daily show` while
+    /// the dashboard runs
+    #[serde(default = "default_backup_schedule")]
+    pub schedule: String,
+    /// Snapshots kept; older ones are deleted after each new snapshot
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            format: default_backup_format(),
+            schedule: default_backup_schedule(),
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+fn default_backup_format() -> String {
+    "tar.zst".into()
+}
+
+fn default_backup_schedule() -> String {
+    "off".into()
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+/// Static HTML site of the archive (`daily publish`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishConfig {
+    /// Site name shown in page titles and the navigation
+    #[serde(default = "default_publish_title")]
+    pub title: String,
+    /// Sessions with any of these tags are left out of the site, including
+    /// topic pages and insights
+    #[serde(default = "default_publish_exclude_tags")]
+    pub exclude_tags: Vec<String>,
+    /// Regular expressions whose matches are replaced with `[redacted]` in
+    /// every published page and in the insights data
+    #[serde(default = "default_publish_redact")]
+    pub redact: Vec<String>,
+}
+
+impl Default for PublishConfig {
+    fn default() -> Self {
+        Self {
+            title: default_publish_title(),
+            exclude_tags: default_publish_exclude_tags(),
+            redact: default_publish_redact(),
+        }
+    }
+}
+
+fn default_publish_title() -> String {
+    "Daily Archive".into()
+}
+
+fn default_publish_exclude_tags() -> Vec<String> {
+    vec!["private".into()]
+}
+
+/// API keys and tokens in common formats
+fn default_publish_redact() -> Vec<String> {
+    vec![
+        r"sk-[A-Za-z0-9_-]{20,}".into(),
+        r"gh[pousr]_[A-Za-z0-9]{36,}".into(),
+        r"AKIA[0-9A-Z]{16}".into(),
+        r"xox[abpr]-[A-Za-z0-9-]{10,}".into(),
+    ]
+}
+
+/// Single-digest pages from `daily share`, redacted with `publish.redact`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShareConfig {
+    /// "" to write an HTML file, "gist" to upload it as a GitHub gist, or
+    /// "paste" to POST it to `paste_url`
+    #[serde(default)]
+    pub upload: String,
+    /// Environment variable holding a GitHub token with the gist scope
+    #[serde(default = "default_share_gist_token_env")]
+    pub gist_token_env: String,
+    /// Create public gists instead of secret ones
+    #[serde(default)]
+    pub gist_public: bool,
+    /// Paste service that takes the page as the body of a POST and answers
+    /// with its link (in the `Location` header or the response body)
+    #[serde(default)]
+    pub paste_url: String,
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        Self {
+            upload: String::new(),
+            gist_token_env: default_share_gist_token_env(),
+            gist_public: false,
+            paste_url: String::new(),
+        }
+    }
+}
+
+fn default_share_gist_token_env() -> String {
+    "GITHUB_TOKEN".into()
+}
+
+/// Claude Code usage tracking
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageConfig {
+    /// Token limit of a 5-hour billing block (None = no limit, unless
+    /// `--token-limit` is given)
+    #[serde(default)]
+    pub block_token_limit: Option<u64>,
+    /// Share of the token limit (percent) at which `daily usage blocks
+    /// --notify` sends the `usage.block_near_limit` webhook
+    #[serde(default = "default_block_warn_percent")]
+    pub block_warn_percent: u8,
+    /// Per-token costs by model name, over the LiteLLM pricing data, for
+    /// negotiated rates (Bedrock, Vertex, corporate proxies). Costs left out
+    /// come from the LiteLLM entry the model matches.
+    #[serde(default)]
+    pub price_overrides: BTreeMap<String, ModelPricing>,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            block_token_limit: None,
+            block_warn_percent: default_block_warn_percent(),
+            price_overrides: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_block_warn_percent() -> u8 {
+    90
+}
+
+/// Web dashboard settings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerConfig {
+    /// Port for `daily show` when `--port` is not given (None = 31456, or
+    /// the next free port); a profile defaults to one derived from its name
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Path prefix the dashboard is served under, e.g. "/daily" behind a
+    /// reverse proxy (None = the root)
+    #[serde(default)]
+    pub base_path: Option<String>,
+    /// PEM certificate chain; with `tls_key`, the dashboard is served over HTTPS
+    #[serde(default)]
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for `tls_cert`
+    #[serde(default)]
+    pub tls_key: Option<PathBuf>,
+    /// API requests allowed per minute from one client address (0 = no limit)
+    #[serde(default = "default_rate_limit_per_minute")]
+    pub rate_limit_per_minute: u32,
+    /// Limit loopback clients too, as a reverse proxy on the same machine
+    /// needs (None = only when the dashboard listens on a non-loopback
+    /// address)
+    #[serde(default)]
+    pub rate_limit_loopback: Option<bool>,
+    /// Largest API request body accepted, in bytes
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            base_path: None,
+            tls_cert: None,
+            tls_key: None,
+            rate_limit_per_minute: default_rate_limit_per_minute(),
+            rate_limit_loopback: None,
+            max_body_bytes: default_max_body_bytes(),
+        }
+    }
+}
+
+fn default_rate_limit_per_minute() -> u32 {
+    300
+}
+
+fn default_max_body_bytes() -> usize {
+    256 * 1024
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StorageConfig {
+    /// Local archive folder; with a remote backend it is the working copy
+    /// whose changes are pushed after every command
     pub path: PathBuf,
+    /// Age-based cleanup applied by `daily prune`
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    /// "local", "s3" or "webdav"
+    #[serde(default = "default_storage_backend")]
+    pub backend: String,
+    #[serde(default)]
+    pub s3: S3Config,
+    #[serde(default)]
+    pub webdav: WebDavConfig,
+}
+
+fn default_storage_backend() -> String {
+    "local".into()
+}
+
+/// S3-compatible object store for `storage.backend = "s3"`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct S3Config {
+    #[serde(default)]
+    pub bucket: Option<String>,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    /// Endpoint of a non-AWS store such as MinIO or R2
+    /// (default: https://s3.{region}.amazonaws.com)
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Address objects as {endpoint}/{bucket}/{key}, as most self-hosted
+    /// stores expect, instead of {bucket}.{host}/{key}
+    #[serde(default)]
+    pub path_style: bool,
+    /// Key prefix for all archive objects, e.g. "daily/"
+    #[serde(default)]
+    pub prefix: String,
+    /// Environment variables holding the credentials
+    #[serde(default = "default_s3_access_key_env")]
+    pub access_key_env: String,
+    #[serde(default = "default_s3_secret_key_env")]
+    pub secret_key_env: String,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: None,
+            region: default_s3_region(),
+            endpoint: None,
+            path_style: false,
+            prefix: String::new(),
+            access_key_env: default_s3_access_key_env(),
+            secret_key_env: default_s3_secret_key_env(),
+        }
+    }
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".into()
+}
+
+fn default_s3_access_key_env() -> String {
+    "AWS_ACCESS_KEY_ID".into()
+}
+
+fn default_s3_secret_key_env() -> String {
+    "AWS_SECRET_ACCESS_KEY".into()
+}
+
+/// WebDAV share (e.g. a NAS or Nextcloud) for `storage.backend = "webdav"`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebDavConfig {
+    /// Folder URL the archive lives under, e.g. https://nas.local/dav/daily
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Environment variable holding the password
+    #[serde(default = "default_webdav_password_env")]
+    pub password_env: String,
+}
+
+impl Default for WebDavConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            username: None,
+            password_env: default_webdav_password_env(),
+        }
+    }
+}
+
+fn default_webdav_password_env() -> String {
+    "DAILY_WEBDAV_PASSWORD".into()
+}
+
+/// Retention rules for `daily prune`. Digests (`daily.md`) and pinned
+/// sessions are always kept.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetentionConfig {
+    /// Days to keep transcript copies under `{date}/transcripts/` (None = forever)
+    #[serde(default = "default_transcript_retention_days")]
+    pub transcript_days: Option<u64>,
+    /// Days to keep individual session archives (None = forever)
+    #[serde(default)]
+    pub session_days: Option<u64>,
+    /// "compress" (zstd, still readable) or "delete"
+    #[serde(default = "default_retention_action")]
+    pub action: String,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            transcript_days: default_transcript_retention_days(),
+            session_days: None,
+            action: default_retention_action(),
+        }
+    }
+}
+
+fn default_transcript_retention_days() -> Option<u64> {
+    Some(90)
+}
+
+fn default_retention_action() -> String {
+    "compress".into()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,6 +541,41 @@ pub struct ArchiveConfig {
     pub tags: Vec<String>,
     pub include_cwd: bool,
     pub include_git_info: bool,
+    /// Copy the transcript JSONL into `{date}/transcripts/` at summarize time,
+    /// so the conversation survives Claude Code's cleanup of `~/.claude/projects/`
+    #[serde(default = "default_copy_transcript")]
+    pub copy_transcript: bool,
+    /// Store the transcript copy zstd-compressed (`.jsonl.zst`)
+    #[serde(default)]
+    pub compress_transcript: bool,
+    /// Session file name, e.g. "{HH}_{mm}-{topic}" or "{project}-{topic}".
+    /// Variables: topic, project, session_id, short_id, date, YYYY, MM, DD, HH, mm, ss
+    #[serde(default = "default_session_name_template")]
+    pub session_name_template: String,
+    /// Folder of each date under the storage path, e.g. "{date}" or "{YYYY}/{MM}/{DD}".
+    /// Change it with `daily migrate-layout` to move existing archives along
+    #[serde(default = "default_directory_layout")]
+    pub directory_layout: String,
+    /// Glob patterns of project directories whose sessions are never
+    /// archived or counted in usage, e.g. "/tmp/**" or "~/clients/secret-*"
+    #[serde(default)]
+    pub ignore_paths: Vec<String>,
+    /// When not empty, only project directories matching one of these globs
+    /// are archived and counted
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+}
+
+fn default_session_name_template() -> String {
+    layout::DEFAULT_SESSION_NAME_TEMPLATE.into()
+}
+
+fn default_directory_layout() -> String {
+    layout::DEFAULT_DIRECTORY_LAYOUT.into()
+}
+
+fn default_copy_transcript() -> bool {
+    true
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -44,6 +593,10 @@ pub struct SummarizationConfig {
     /// Language for summary output ("en" for English, "zh" for Chinese)
     #[serde(default = "default_summary_language")]
     pub summary_language: String,
+    /// Also save each digest as `daily.{lang}.md` in these languages,
+    /// translated from the one generated in `summary_language`
+    #[serde(default)]
+    pub digest_languages: Vec<String>,
     /// Enable auto-summarization of unsummarized sessions on daily show
     #[serde(default = "default_auto_summarize_enabled")]
     pub auto_summarize_enabled: bool,
@@ -59,6 +612,117 @@ pub struct SummarizationConfig {
     /// Minutes of inactivity before a transcript is considered "inactive" and eligible for auto-summarization
     #[serde(default = "default_auto_summarize_inactive_minutes")]
     pub auto_summarize_inactive_minutes: u64,
+    /// Per-project thresholds, quiet hours and session filters applied by
+    /// the auto-summarize scheduler
+    #[serde(default)]
+    pub auto_summarize_policy: AutoSummarizePolicy,
+    /// Estimated token budget for a session summary prompt; longer transcripts
+    /// lose lines from the middle (0 = no limit)
+    #[serde(default = "default_max_prompt_tokens")]
+    pub max_prompt_tokens: usize,
+    /// Quality score (0-100) below which a summary or digest is flagged
+    /// as low quality in its job record
+    #[serde(default = "default_min_quality_score")]
+    pub min_quality_score: u8,
+    /// Ask the model once more, listing the problems found, when a result
+    /// scores below `min_quality_score`
+    #[serde(default)]
+    pub retry_low_quality: bool,
+    /// Record which sessions each digest card came from, in
+    /// `daily.citations.json` next to the digest
+    #[serde(default)]
+    pub digest_citations: bool,
+    /// "project" to add a By Project section to digests, one subsection per
+    /// working directory; "" (default) for a single narrative
+    #[serde(default)]
+    pub digest_group_by: String,
+    /// Seconds a single Claude CLI call may run before it is killed and
+    /// its job fails as timed out (0 = no limit)
+    #[serde(default = "default_call_timeout_secs")]
+    pub call_timeout_secs: u64,
+    /// Minutes between automatic retries of a summarization queued while
+    /// Claude was unreachable (0 = only on `daily queue flush`)
+    #[serde(default = "default_offline_retry_minutes")]
+    pub offline_retry_minutes: u64,
+    /// Which sessions get a stub summary instead of a full one
+    #[serde(default)]
+    pub triviality: TrivialityConfig,
+}
+
+/// Sessions small enough to archive with a stub summary, skipping the
+/// summarization model
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrivialityConfig {
+    /// Off by default: the stub summary is not written in `summary_language`
+    #[serde(default = "default_triviality_enabled")]
+    pub enabled: bool,
+    /// Sessions with more prompts than this are always summarized
+    #[serde(default = "default_trivial_max_turns")]
+    pub max_turns: usize,
+    /// Sessions whose transcript is estimated over this many tokens are
+    /// always summarized
+    #[serde(default = "default_trivial_max_tokens")]
+    pub max_tokens: usize,
+    /// Ask `check_model` whether a session within the thresholds is really
+    /// trivial, instead of trusting the thresholds alone
+    #[serde(default)]
+    pub model_check: bool,
+    /// Cheap model for the `model_check` pre-pass
+    #[serde(default = "default_trivial_check_model")]
+    pub check_model: String,
+    /// Summary written for trivial sessions; `{{first_prompt}}`, `{{turns}}`
+    /// and `{{project}}` are substituted
+    #[serde(default = "default_trivial_summary_template")]
+    pub summary_template: String,
+}
+
+impl Default for TrivialityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_triviality_enabled(),
+            max_turns: default_trivial_max_turns(),
+            max_tokens: default_trivial_max_tokens(),
+            model_check: false,
+            check_model: default_trivial_check_model(),
+            summary_template: default_trivial_summary_template(),
+        }
+    }
+}
+
+fn default_triviality_enabled() -> bool {
+    false
+}
+
+fn default_trivial_max_turns() -> usize {
+    2
+}
+
+fn default_trivial_max_tokens() -> usize {
+    2000
+}
+
+fn default_trivial_check_model() -> String {
+    "haiku".into()
+}
+
+fn default_trivial_summary_template() -> String {
+    "Short session ({{turns}} prompt(s)) in {{project}}: {{first_prompt}}".into()
+}
+
+fn default_call_timeout_secs() -> u64 {
+    600
+}
+
+fn default_offline_retry_minutes() -> u64 {
+    15
+}
+
+fn default_min_quality_score() -> u8 {
+    60
+}
+
+fn default_max_prompt_tokens() -> usize {
+    150_000 // Leaves room for the response within a 200k context window
 }
 
 fn default_summary_language() -> String {
@@ -89,11 +753,244 @@ fn default_auto_summarize_inactive_minutes() -> u64 {
     30 // 30 minutes of inactivity before considering a session ended
 }
 
+/// Which sessions auto-summarize picks up, and when
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AutoSummarizePolicy {
+    /// Inactivity threshold by project directory, overriding
+    /// `auto_summarize_inactive_minutes`; the longest matching path wins
+    #[serde(default)]
+    pub project_inactive_minutes: BTreeMap<String, u64>,
+    /// "HH:MM-HH:MM" window in which no auto-summarize jobs start (may span midnight)
+    #[serde(default)]
+    pub quiet_hours: Option<String>,
+    /// Skip sessions with fewer prompts than this
+    #[serde(default)]
+    pub min_turns: usize,
+    /// Directories whose sessions are never auto-summarized, subdirectories included
+    #[serde(default)]
+    pub skip_dirs: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HooksConfig {
     pub enable_session_start: bool,
     pub enable_session_end: bool,
     pub background_timeout: u64,
+    /// Snapshot the transcript into the archive before context compaction
+    #[serde(default = "default_enable_pre_compact")]
+    pub enable_pre_compact: bool,
+    /// Record prompt heartbeats used for inactivity detection
+    #[serde(default = "default_enable_user_prompt_submit")]
+    pub enable_user_prompt_submit: bool,
+    /// Write incremental checkpoint summaries from the Stop hook (costs a model call each)
+    #[serde(default)]
+    pub enable_stop_checkpoint: bool,
+    /// Minimum minutes between checkpoint summaries of the same session
+    #[serde(default = "default_checkpoint_interval_minutes")]
+    pub checkpoint_interval_minutes: u64,
+    /// Ask for a 1-5 star rating and comment on the terminal when a session ends
+    #[serde(default)]
+    pub ask_feedback: bool,
+    /// Shell commands run after each session archive is written
+    #[serde(default)]
+    pub post_archive: Vec<String>,
+    /// Shell commands run after each digest is written
+    #[serde(default)]
+    pub post_digest: Vec<String>,
+    /// Seconds a `daily hook` handler may take before it is abandoned
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_enable_pre_compact() -> bool {
+    true
+}
+
+fn default_enable_user_prompt_submit() -> bool {
+    true
+}
+
+fn default_checkpoint_interval_minutes() -> u64 {
+    30
+}
+
+/// Background job queue configuration
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobsConfig {
+    /// Maximum number of summarization jobs running at once; the rest wait queued
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent: usize,
+    /// Limits on finished job records and logs, applied by `daily jobs prune`
+    #[serde(default)]
+    pub retention: JobRetentionConfig,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_max_concurrent_jobs(),
+            retention: JobRetentionConfig::default(),
+        }
+    }
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    2
+}
+
+/// Retention rules for finished jobs. Queued and running jobs are always kept;
+/// the newest finished jobs are kept first.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobRetentionConfig {
+    /// Days to keep a finished job (None = forever)
+    #[serde(default = "default_job_max_age_days")]
+    pub max_age_days: Option<u32>,
+    /// Number of finished jobs to keep (None = no limit)
+    #[serde(default = "default_job_max_count")]
+    pub max_count: Option<usize>,
+    /// Total size of job records and logs to keep, in MB (None = no limit)
+    #[serde(default = "default_job_max_total_mb")]
+    pub max_total_mb: Option<u64>,
+    /// Prune on session start and when the dashboard starts
+    #[serde(default = "default_job_auto_prune")]
+    pub auto_prune: bool,
+}
+
+impl Default for JobRetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: default_job_max_age_days(),
+            max_count: default_job_max_count(),
+            max_total_mb: default_job_max_total_mb(),
+            auto_prune: true,
+        }
+    }
+}
+
+fn default_job_max_age_days() -> Option<u32> {
+    Some(30)
+}
+
+fn default_job_max_count() -> Option<usize> {
+    Some(500)
+}
+
+fn default_job_max_total_mb() -> Option<u64> {
+    Some(100)
+}
+
+fn default_job_auto_prune() -> bool {
+    true
+}
+
+/// Outbound notification configuration
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NotificationsConfig {
+    /// Webhooks called on job and digest events
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// A single webhook endpoint
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events to deliver, e.g. "job.completed" (empty = all events)
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Shared secret for the `X-Daily-Signature` HMAC-SHA256 header
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// External service integrations
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct IntegrationsConfig {
+    #[serde(default)]
+    pub github: GithubConfig,
+}
+
+/// GitHub issues created from "Tomorrow's Focus" by `daily focus push-github`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GithubConfig {
+    /// Default repository ("owner/name") when `--repo` is not given
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// Label applied to focus issues, also used to find them again
+    #[serde(default = "default_github_label")]
+    pub label: String,
+    /// Environment variable holding the API token
+    #[serde(default = "default_github_token_env")]
+    pub token_env: String,
+    /// API base URL, for GitHub Enterprise (default: https://api.github.com)
+    #[serde(default)]
+    pub api_url: Option<String>,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            repo: None,
+            label: default_github_label(),
+            token_env: default_github_token_env(),
+            api_url: None,
+        }
+    }
+}
+
+fn default_github_label() -> String {
+    "daily-focus".into()
+}
+
+fn default_github_token_env() -> String {
+    "GITHUB_TOKEN".into()
+}
+
+/// Embeddings provider used to find related sessions
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingsConfig {
+    /// Off by default; session summaries are sent to the provider when enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// "openai" (any OpenAI-compatible `/v1/embeddings` API) or "ollama" (local)
+    #[serde(default = "default_embeddings_provider")]
+    pub provider: String,
+    #[serde(default = "default_embeddings_model")]
+    pub model: String,
+    /// Override the provider's base URL (e.g. a self-hosted gateway)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (unused by ollama)
+    #[serde(default = "default_embeddings_api_key_env")]
+    pub api_key_env: String,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_embeddings_provider(),
+            model: default_embeddings_model(),
+            base_url: None,
+            api_key_env: default_embeddings_api_key_env(),
+        }
+    }
+}
+
+fn default_embeddings_provider() -> String {
+    "openai".into()
+}
+
+fn default_embeddings_model() -> String {
+    "text-embedding-3-small".into()
+}
+
+fn default_embeddings_api_key_env() -> String {
+    "OPENAI_API_KEY".into()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -101,6 +998,17 @@ pub struct OutputConfig {
     pub terminal_format: String,
     pub date_format: String,
     pub time_format: String,
+    /// Theme of digests rendered as HTML: "light" or "dark"
+    #[serde(default = "default_html_theme")]
+    pub html_theme: String,
+    /// Language of CLI messages and the dashboard, e.g. "zh"; defaults to
+    /// `summarization.summary_language`
+    #[serde(default)]
+    pub ui_language: Option<String>,
+}
+
+fn default_html_theme() -> String {
+    "light".into()
 }
 
 /// Custom prompt templates configuration
@@ -131,12 +1039,25 @@ impl Default for Config {
             .join("daily");
 
         Self {
-            storage: StorageConfig { path: default_path },
+            config_version: CONFIG_VERSION,
+            storage: StorageConfig {
+                path: default_path,
+                retention: RetentionConfig::default(),
+                backend: default_storage_backend(),
+                s3: S3Config::default(),
+                webdav: WebDavConfig::default(),
+            },
             archive: ArchiveConfig {
                 author: None,
                 tags: vec!["claude-code".into(), "daily-archive".into()],
                 include_cwd: true,
                 include_git_info: true,
+                copy_transcript: default_copy_transcript(),
+                compress_transcript: false,
+                session_name_template: default_session_name_template(),
+                directory_layout: default_directory_layout(),
+                ignore_paths: Vec::new(),
+                include_paths: Vec::new(),
             },
             summarization: SummarizationConfig {
                 model: "haiku".into(),
@@ -146,28 +1067,70 @@ impl Default for Config {
                 digest_time: "06:00".into(),
                 auto_digest_enabled: true,
                 summary_language: "en".into(),
+                digest_languages: Vec::new(),
                 auto_summarize_enabled: true,
                 auto_summarize_time: "06:00".into(),
                 last_auto_summarize_check: None,
                 auto_summarize_on_show: false,
                 auto_summarize_inactive_minutes: 30,
+                auto_summarize_policy: AutoSummarizePolicy::default(),
+                max_prompt_tokens: default_max_prompt_tokens(),
+                min_quality_score: default_min_quality_score(),
+                retry_low_quality: false,
+                digest_citations: false,
+                digest_group_by: String::new(),
+                call_timeout_secs: default_call_timeout_secs(),
+                offline_retry_minutes: default_offline_retry_minutes(),
+                triviality: TrivialityConfig::default(),
             },
             hooks: HooksConfig {
                 enable_session_start: true,
                 enable_session_end: true,
                 background_timeout: 300,
+                enable_pre_compact: true,
+                enable_user_prompt_submit: true,
+                enable_stop_checkpoint: false,
+                checkpoint_interval_minutes: 30,
+                ask_feedback: false,
+                post_archive: Vec::new(),
+                post_digest: Vec::new(),
+                timeout_secs: default_hook_timeout_secs(),
             },
             output: OutputConfig {
                 terminal_format: "colored".into(),
                 date_format: "%Y-%m-%d".into(),
                 time_format: "%H:%M:%S".into(),
+                html_theme: default_html_theme(),
+                ui_language: None,
             },
             prompt_templates: PromptTemplatesConfig::default(),
+            jobs: JobsConfig::default(),
+            notifications: NotificationsConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
+            integrations: IntegrationsConfig::default(),
+            server: ServerConfig::default(),
+            usage: UsageConfig::default(),
+            skills: SkillsConfig::default(),
+            topics: TopicsConfig::default(),
+            insights: InsightsConfig::default(),
+            backup: BackupConfig::default(),
+            publish: PublishConfig::default(),
+            share: ShareConfig::default(),
         }
     }
 }
 
 impl Config {
+    /// Language of CLI output and dashboard labels
+    pub fn ui_language(&self) -> Lang {
+        Lang::parse(
+            self.output
+                .ui_language
+                .as_deref()
+                .unwrap_or(&self.summarization.summary_language),
+        )
+    }
+
     /// Get the storage path, expanding ~ if present
     pub fn storage_path(&self) -> PathBuf {
         let path_str = self.storage.path.to_string_lossy();
@@ -182,33 +1145,167 @@ impl Config {
     /// Get today's archive directory
     pub fn today_dir(&self) -> PathBuf {
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        self.storage_path().join(today)
+        self.date_dir(&today)
     }
 
-    /// Get archive directory for a specific date
+    /// Get archive directory for a specific date, following `archive.directory_layout`
     pub fn date_dir(&self, date: &str) -> PathBuf {
-        self.storage_path().join(date)
+        self.storage_path()
+            .join(layout::date_dir(&self.archive.directory_layout, date))
+    }
+}
+
+/// Profile selected with `--profile` or `DAILY_PROFILE`, if any
+pub fn active_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+}
+
+/// Activate a profile for this process and the jobs it spawns
+pub fn use_profile(name: &str) -> Result<()> {
+    validate_profile(name)?;
+    std::env::set_var(PROFILE_ENV, name);
+    Ok(())
+}
+
+fn validate_profile(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        anyhow::bail!(
+            "Invalid profile '{}'. Use letters, digits, '-' or '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// First of the ports profile dashboards are spread over, just above the
+/// default profile's 31456
+const PROFILE_PORT_BASE: u16 = 31457;
+
+const PROFILE_PORTS: u16 = 1000;
+
+/// Config for a profile that has never been used: its own archive folder
+/// next to the default one, and its own dashboard port so it can run
+/// alongside other profiles
+fn profile_default(name: &str) -> Config {
+    let mut config = Config::default();
+    config.storage.path = dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".claude")
+        .join(format!("daily-{}", name));
+    config.server.port = Some(profile_port(name));
+    config
+}
+
+/// Dashboard port of a profile, derived from its name so it is the same on
+/// every run and machine
+fn profile_port(name: &str) -> u16 {
+    let digest = Sha256::digest(name.as_bytes());
+    PROFILE_PORT_BASE + u16::from_be_bytes([digest[0], digest[1]]) % PROFILE_PORTS
+}
+
+fn defaults() -> Config {
+    match active_profile() {
+        Some(profile) => profile_default(&profile),
+        None => Config::default(),
     }
 }
 
-/// Load configuration from file or create default
+/// Load every config layer, creating the user config file if missing
+pub fn load_layered() -> Result<Layered> {
+    let (defaults, path) = user_config()?;
+    Layered::load(defaults, &path)
+}
+
+/// Load the effective configuration: defaults, user config, project
+/// `.daily.toml`, then `DAILY_*` environment variables
 pub fn load_config() -> Result<Config> {
-    let config: Config =
-        confy::load(APP_NAME, Some("config")).context("Failed to load configuration")?;
-    Ok(config)
+    Ok(load_layered()?.config)
+}
+
+/// Load the effective configuration with the project `.daily.toml` looked
+/// up from `dir` instead of the current directory
+pub fn load_config_in(dir: &Path) -> Result<Config> {
+    let (defaults, path) = user_config()?;
+    Ok(Layered::load_in(defaults, &path, dir)?.config)
+}
+
+/// Defaults and the user config path, creating the file on first use
+fn user_config() -> Result<(Config, PathBuf)> {
+    if let Some(profile) = active_profile() {
+        validate_profile(&profile)?;
+    }
+    let path = get_config_path()?;
+    let defaults = defaults();
+    if !path.exists() {
+        store(&path, &defaults).context("Failed to create configuration")?;
+    }
+    Ok((defaults, path))
 }
 
-/// Save configuration to file
+/// Save configuration to the user config file, leaving out project and
+/// environment overrides
 pub fn save_config(config: &Config) -> Result<()> {
-    confy::store(APP_NAME, Some("config"), config).context("Failed to save configuration")?;
-    Ok(())
+    let user = load_layered()?.user_layer(config)?;
+    store(&get_config_path()?, &user).context("Failed to save configuration")
+}
+
+/// Write a config file atomically, so a crash never leaves it half written
+fn store(path: &Path, config: &Config) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let content = toml::to_string_pretty(config).context("Failed to serialize configuration")?;
+    write_atomic(path, content)
+}
+
+/// Check the user config file for unknown keys and invalid values
+pub fn validate_config() -> Result<(PathBuf, Vec<Issue>)> {
+    let (path, defaults) = (get_config_path()?, defaults());
+    if !path.exists() {
+        return Ok((path, validate::check_values(&defaults)));
+    }
+    let issues = validate::validate_file(&defaults, &path)?;
+    Ok((path, issues))
 }
 
-/// Get the configuration file path
+/// Upgrade the user config file to the current `config_version`, keeping
+/// the previous file as `{name}.toml.bak`
+pub fn migrate_config(dry_run: bool) -> Result<(PathBuf, Migration)> {
+    let path = get_config_path()?;
+    if !path.exists() {
+        anyhow::bail!("No config file at {}", path.display());
+    }
+    let migration = validate::migrate(&defaults(), &path)?;
+    if !dry_run && migration.from < migration.to {
+        let backup = path.with_extension("toml.bak");
+        std::fs::copy(&path, &backup)
+            .with_context(|| format!("Failed to back up {}", path.display()))?;
+        let content =
+            toml::to_string(&migration.table).context("Failed to serialize configuration")?;
+        write_atomic(&path, content)?;
+    }
+    Ok((path, migration))
+}
+
+/// Get the configuration file path; profiles live in `profiles/{name}.toml`
+/// next to the default config
 pub fn get_config_path() -> Result<PathBuf> {
     let path = confy::get_configuration_file_path(APP_NAME, Some("config"))
         .context("Failed to get configuration path")?;
-    Ok(path)
+    match active_profile() {
+        Some(profile) => Ok(path
+            .with_file_name("profiles")
+            .join(format!("{}.toml", profile))),
+        None => Ok(path),
+    }
 }
 
 #[cfg(test)]
@@ -226,6 +1323,26 @@ mod tests {
         assert_eq!(config.summarization.model, "haiku");
     }
 
+    #[test]
+    fn test_profile_defaults() {
+        assert!(validate_profile("client-work_2").is_ok());
+        assert!(validate_profile("../etc").is_err());
+        assert!(validate_profile("").is_err());
+        assert!(profile_default("work")
+            .storage
+            .path
+            .ends_with(".claude/daily-work"));
+
+        let work = profile_port("work");
+        assert_eq!(profile_default("work").server.port, Some(work));
+        assert_eq!(profile_port("work"), work);
+        assert_ne!(profile_port("personal"), work);
+        for name in ["work", "personal", "client-a", "x"] {
+            assert!((PROFILE_PORT_BASE..PROFILE_PORT_BASE + PROFILE_PORTS)
+                .contains(&profile_port(name)));
+        }
+    }
+
     #[test]
     fn test_today_dir() {
         let config = Config::default();
@@ -0,0 +1,574 @@
+//! `daily config validate` and `daily config migrate`.
+//!
+//! Validation reads the user config file as raw TOML, so keys serde would
+//! silently drop (typos, removed settings) are reported instead of ignored,
+//! then checks values that only fail later at runtime: `HH:MM` times, enum
+//! strings and the storage folder.
+//!
+//! `config_version` records the schema a file was written for. Files
+//! without it predate versioning (version 0); `migrate` applies each step
+//! above the file's version in order and stamps the result.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+use super::layers::Layered;
+use super::settings::Config;
+use crate::archive::layout;
+
+/// Schema version written by this build
+pub const CONFIG_VERSION: u32 = 1;
+
+pub const VERSION_KEY: &str = "config_version";
+
+/// Upgrades the raw file table in place, given the default config table
+type MigrationStep = fn(&mut Table, &Table);
+
+/// Migration steps: target version and what it changes
+const MIGRATIONS: [(u32, &str, MigrationStep); 1] = [(
+    1,
+    "Add settings introduced since the file was written, with their defaults",
+    fill_missing,
+)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the config file
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Dotted key path, e.g. `summarization.digest_time`
+    pub key: String,
+    pub message: String,
+}
+
+impl Issue {
+    fn error(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Outcome of `migrate`: the upgraded table and the steps applied
+pub struct Migration {
+    pub from: u32,
+    pub to: u32,
+    pub steps: Vec<&'static str>,
+    pub table: Table,
+}
+
+/// Check the config file at `path` on top of `defaults`
+pub fn validate_file(defaults: &Config, path: &Path) -> Result<Vec<Issue>> {
+    let raw = read_raw(path)?;
+    let mut issues = Vec::new();
+
+    match raw.get(VERSION_KEY) {
+        None => issues.push(Issue::warning(
+            VERSION_KEY,
+            "Missing; the file predates versioning. Run `daily config migrate`",
+        )),
+        Some(value) => match value.as_integer() {
+            Some(v) if v > CONFIG_VERSION as i64 => issues.push(Issue::error(
+                VERSION_KEY,
+                format!(
+                    "Version {} is newer than this build supports ({}); update daily",
+                    v, CONFIG_VERSION
+                ),
+            )),
+            Some(v) if v < CONFIG_VERSION as i64 => issues.push(Issue::warning(
+                VERSION_KEY,
+                format!("Version {} is outdated. Run `daily config migrate`", v),
+            )),
+            Some(_) => {}
+            None => issues.push(Issue::error(VERSION_KEY, "Must be an integer")),
+        },
+    }
+
+    let layered = match Layered::from_layers(defaults.clone(), path, None, Vec::new()) {
+        Ok(layered) => layered,
+        Err(e) => {
+            issues.push(Issue::error("", format!("{:#}", e)));
+            return Ok(issues);
+        }
+    };
+    let known = Table::try_from(&layered.config).context("Failed to serialize config")?;
+    for key in unknown_keys(&raw, &known, "") {
+        issues.push(Issue::warning(&key, "Unknown key, ignored"));
+    }
+    issues.extend(check_values(&layered.config));
+    Ok(issues)
+}
+
+/// Range and format checks on an effective configuration
+pub fn check_values(config: &Config) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for (key, value) in [
+        (
+            "summarization.digest_time",
+            &config.summarization.digest_time,
+        ),
+        (
+            "summarization.auto_summarize_time",
+            &config.summarization.auto_summarize_time,
+        ),
+    ] {
+        if parse_hh_mm(value).is_none() {
+            issues.push(Issue::error(
+                key,
+                format!("'{}' is not a time in HH:MM format", value),
+            ));
+        }
+    }
+
+    for (key, patterns) in [
+        ("archive.ignore_paths", &config.archive.ignore_paths),
+        ("archive.include_paths", &config.archive.include_paths),
+    ] {
+        for pattern in patterns {
+            if let Err(e) = crate::archive::paths::compile(pattern) {
+                issues.push(Issue::error(key, format!("{:#}", e)));
+            }
+        }
+    }
+
+    if let Some(quiet) = &config.summarization.auto_summarize_policy.quiet_hours {
+        if crate::auto_summarize::parse_quiet_hours(quiet).is_none() {
+            issues.push(Issue::error(
+                "summarization.auto_summarize_policy.quiet_hours",
+                format!("'{}' is not a window in HH:MM-HH:MM format", quiet),
+            ));
+        }
+    }
+
+    let one_of = |key: &str, value: &str, allowed: &[&str]| {
+        (!allowed.contains(&value)).then(|| {
+            Issue::error(
+                key,
+                format!("'{}' is not one of: {}", value, allowed.join(", ")),
+            )
+        })
+    };
+    issues.extend(one_of(
+        "summarization.summary_language",
+        &config.summarization.summary_language,
+        &["en", "zh"],
+    ));
+    if let Some(language) = &config.output.ui_language {
+        issues.extend(one_of("output.ui_language", language, &["en", "zh"]));
+    }
+    for language in &config.summarization.digest_languages {
+        issues.extend(one_of(
+            "summarization.digest_languages",
+            language,
+            &["en", "zh"],
+        ));
+    }
+    issues.extend(one_of(
+        "summarization.digest_group_by",
+        &config.summarization.digest_group_by,
+        &["", "project"],
+    ));
+    issues.extend(one_of(
+        "storage.retention.action",
+        &config.storage.retention.action,
+        &["compress", "delete"],
+    ));
+    issues.extend(one_of(
+        "storage.backend",
+        &config.storage.backend,
+        &["local", "s3", "webdav"],
+    ));
+    if config.storage.backend == "s3" && config.storage.s3.bucket.is_none() {
+        issues.push(Issue::error(
+            "storage.s3.bucket",
+            "Required when storage.backend is \"s3\"",
+        ));
+    }
+    if config.storage.backend == "webdav" && config.storage.webdav.url.is_none() {
+        issues.push(Issue::error(
+            "storage.webdav.url",
+            "Required when storage.backend is \"webdav\"",
+        ));
+    }
+    issues.extend(one_of(
+        "embeddings.provider",
+        &config.embeddings.provider,
+        &["openai", "ollama"],
+    ));
+
+    if let Err(e) = layout::validate_layout(&config.archive.directory_layout) {
+        issues.push(Issue::error("archive.directory_layout", format!("{:#}", e)));
+    }
+    if config.server.tls_cert.is_some() != config.server.tls_key.is_some() {
+        issues.push(Issue::error(
+            "server.tls_cert",
+            "server.tls_cert and server.tls_key must be set together",
+        ));
+    }
+    for (key, path) in [
+        ("server.tls_cert", &config.server.tls_cert),
+        ("server.tls_key", &config.server.tls_key),
+    ] {
+        if let Some(path) = path.as_ref().filter(|path| !path.is_file()) {
+            issues.push(Issue::error(
+                key,
+                format!("{} does not exist", path.display()),
+            ));
+        }
+    }
+    if config.server.max_body_bytes == 0 {
+        issues.push(Issue::error("server.max_body_bytes", "Must be at least 1"));
+    }
+    if config.jobs.max_concurrent == 0 {
+        issues.push(Issue::error("jobs.max_concurrent", "Must be at least 1"));
+    }
+    if !(1..=100).contains(&config.usage.block_warn_percent) {
+        issues.push(Issue::error(
+            "usage.block_warn_percent",
+            "Must be between 1 and 100",
+        ));
+    }
+
+    if config.summarization.min_quality_score > 100 {
+        issues.push(Issue::error(
+            "summarization.min_quality_score",
+            "Must be between 0 and 100",
+        ));
+    }
+
+    let skills = &config.skills;
+    for (key, value) in [
+        ("skills.duplicate_threshold", skills.duplicate_threshold),
+        ("skills.update_threshold", skills.update_threshold),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            issues.push(Issue::error(key, "Must be between 0 and 1"));
+        }
+    }
+    if skills.update_threshold > skills.duplicate_threshold {
+        issues.push(Issue::warning(
+            "skills.update_threshold",
+            "Above skills.duplicate_threshold; no skill is saved as an update",
+        ));
+    }
+
+    let triviality = &config.summarization.triviality;
+    if triviality.enabled && triviality.max_turns == 0 {
+        issues.push(Issue::error(
+            "summarization.triviality.max_turns",
+            "Must be at least 1 when summarization.triviality.enabled is on",
+        ));
+    }
+    if triviality.model_check && triviality.check_model.trim().is_empty() {
+        issues.push(Issue::error(
+            "summarization.triviality.check_model",
+            "Required when summarization.triviality.model_check is on",
+        ));
+    }
+
+    if config.topics.auto_update && config.topics.interval_hours == 0 {
+        issues.push(Issue::error(
+            "topics.interval_hours",
+            "Must be at least 1 when topics.auto_update is on",
+        ));
+    }
+
+    if config.insights.anomaly_threshold_pct == 0 {
+        issues.push(Issue::error(
+            "insights.anomaly_threshold_pct",
+            "Must be at least 1",
+        ));
+    }
+    if config.insights.baseline_weeks == 0 {
+        issues.push(Issue::error(
+            "insights.baseline_weeks",
+            "Must be at least 1",
+        ));
+    }
+
+    for (level, weight) in &config.insights.satisfaction_weights {
+        if !(0.0..=100.0).contains(weight) {
+            issues.push(Issue::error(
+                &format!("insights.satisfaction_weights.{}", level),
+                "Must be between 0 and 100",
+            ));
+        }
+    }
+
+    if let Err(e) = config.output.html_theme.parse::<crate::render::Theme>() {
+        issues.push(Issue::error("output.html_theme", e.to_string()));
+    }
+
+    for (i, pattern) in config.publish.redact.iter().enumerate() {
+        if let Err(e) = regex::Regex::new(pattern) {
+            issues.push(Issue::error(
+                &format!("publish.redact[{}]", i),
+                format!("Invalid regular expression: {}", e),
+            ));
+        }
+    }
+
+    issues.extend(one_of(
+        "share.upload",
+        &config.share.upload,
+        &["", "gist", "paste"],
+    ));
+    if config.share.upload == "paste" && config.share.paste_url.is_empty() {
+        issues.push(Issue::error(
+            "share.paste_url",
+            "Required when share.upload is \"paste\"",
+        ));
+    }
+
+    let backup = &config.backup;
+    if !["tar.zst", "tar"].contains(&backup.format.as_str()) {
+        issues.push(Issue::error(
+            "backup.format",
+            format!("Unknown format '{}' (tar.zst or tar)", backup.format),
+        ));
+    }
+    if !["off", "hourly", "daily", "weekly"].contains(&backup.schedule.as_str()) {
+        issues.push(Issue::error(
+            "backup.schedule",
+            format!(
+                "Unknown schedule '{}' (off, hourly, daily or weekly)",
+                backup.schedule
+            ),
+        ));
+    }
+    if backup.keep == 0 {
+        issues.push(Issue::error("backup.keep", "Must be at least 1"));
+    }
+    if crate::backup::backup_dir(config).starts_with(config.storage_path()) {
+        issues.push(Issue::error(
+            "backup.dir",
+            "Must be outside the storage directory",
+        ));
+    }
+
+    for (model, costs) in &config.usage.price_overrides {
+        let key = format!("usage.price_overrides.{}", model);
+        let rates = [
+            costs.input_cost_per_token,
+            costs.output_cost_per_token,
+            costs.cache_creation_input_token_cost,
+            costs.cache_read_input_token_cost,
+            costs.input_cost_per_token_above_200k_tokens,
+            costs.output_cost_per_token_above_200k_tokens,
+            costs.cache_creation_input_token_cost_above_200k_tokens,
+            costs.cache_read_input_token_cost_above_200k_tokens,
+        ];
+        if rates.iter().all(Option::is_none) {
+            issues.push(Issue::warning(
+                &key,
+                "Sets no costs; LiteLLM rates are used",
+            ));
+        } else if rates.iter().flatten().any(|rate| *rate < 0.0) {
+            issues.push(Issue::error(&key, "Costs must not be negative"));
+        }
+    }
+
+    let storage = config.storage_path();
+    if !storage.is_dir() {
+        issues.push(Issue::warning(
+            "storage.path",
+            format!(
+                "{} does not exist; it is created by `daily init` or the first archive",
+                storage.display()
+            ),
+        ));
+    }
+
+    issues
+}
+
+/// Upgrade the config file at `path` to `CONFIG_VERSION`
+pub fn migrate(defaults: &Config, path: &Path) -> Result<Migration> {
+    let mut table = read_raw(path)?;
+    let from = match table.get(VERSION_KEY) {
+        None => 0,
+        Some(value) => value
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("Invalid {} in {}", VERSION_KEY, path.display()))?,
+    };
+    if from > CONFIG_VERSION {
+        anyhow::bail!(
+            "{} has config_version {}, newer than this build supports ({})",
+            path.display(),
+            from,
+            CONFIG_VERSION
+        );
+    }
+
+    let defaults = Table::try_from(defaults).context("Failed to serialize default config")?;
+    let mut steps = Vec::new();
+    for (version, description, step) in MIGRATIONS {
+        if version > from {
+            step(&mut table, &defaults);
+            steps.push(description);
+        }
+    }
+    table.insert(VERSION_KEY.into(), Value::Integer(CONFIG_VERSION as i64));
+
+    Ok(Migration {
+        from,
+        to: CONFIG_VERSION,
+        steps,
+        table,
+    })
+}
+
+fn read_raw(path: &Path) -> Result<Table> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Invalid TOML in {}", path.display()))
+}
+
+/// Keys of `raw` that don't survive a round trip through `Config`
+fn unknown_keys(raw: &Table, known: &Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in raw {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match (value, known.get(key)) {
+            (_, None) => unknown.push(path),
+            (Value::Table(inner), Some(Value::Table(known))) => {
+                unknown.extend(unknown_keys(inner, known, &path))
+            }
+            (Value::Array(items), Some(Value::Array(known))) => {
+                for (i, (item, known)) in items.iter().zip(known).enumerate() {
+                    if let (Value::Table(item), Value::Table(known)) = (item, known) {
+                        unknown.extend(unknown_keys(item, known, &format!("{}[{}]", path, i)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    unknown
+}
+
+/// Insert keys of `defaults` missing from `table`, keeping existing values
+fn fill_missing(table: &mut Table, defaults: &Table) {
+    for (key, default) in defaults {
+        match (table.get_mut(key), default) {
+            (None, _) => {
+                table.insert(key.clone(), default.clone());
+            }
+            (Some(Value::Table(inner)), Value::Table(defaults)) => fill_missing(inner, defaults),
+            _ => {}
+        }
+    }
+}
+
+fn parse_hh_mm(value: &str) -> Option<(u32, u32)> {
+    let (h, m) = value.split_once(':')?;
+    if h.is_empty() || h.len() > 2 || m.len() != 2 {
+        return None;
+    }
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some((h, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_reports_unknown_keys_and_bad_values() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "config_version = 1\n\n[storage]\npath = \"/nonexistent/daily\"\n\n\
+             [summarization]\nmodle = \"sonnet\"\ndigest_time = \"6am\"\n\n\
+             [[notifications.webhooks]]\nurl = \"https://example.com\"\nevent = [\"job.failed\"]\n",
+        )
+        .unwrap();
+
+        let issues = validate_file(&Config::default(), &path).unwrap();
+        let keys: Vec<(&str, Severity)> = issues
+            .iter()
+            .map(|i| (i.key.as_str(), i.severity))
+            .collect();
+        assert!(keys.contains(&("summarization.modle", Severity::Warning)));
+        assert!(keys.contains(&("notifications.webhooks[0].event", Severity::Warning)));
+        assert!(keys.contains(&("summarization.digest_time", Severity::Error)));
+        assert!(keys.contains(&("storage.path", Severity::Warning)));
+        assert!(!keys.iter().any(|(k, _)| *k == VERSION_KEY));
+    }
+
+    #[test]
+    fn test_check_triviality_values() {
+        let mut config = Config::default();
+        config.summarization.triviality.max_turns = 0;
+        config.summarization.triviality.check_model = " ".to_string();
+        assert!(check_values(&config)
+            .iter()
+            .all(|i| !i.key.starts_with("summarization.triviality")));
+
+        config.summarization.triviality.enabled = true;
+        config.summarization.triviality.model_check = true;
+        let keys: Vec<String> = check_values(&config).into_iter().map(|i| i.key).collect();
+        assert!(keys.contains(&"summarization.triviality.max_turns".to_string()));
+        assert!(keys.contains(&"summarization.triviality.check_model".to_string()));
+    }
+
+    #[test]
+    fn test_migrate_legacy_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[summarization]\nmodel = \"sonnet\"\n").unwrap();
+
+        let issues = validate_file(&Config::default(), &path).unwrap();
+        assert!(issues.iter().any(|i| i.key == VERSION_KEY));
+
+        let migration = migrate(&Config::default(), &path).unwrap();
+        assert_eq!((migration.from, migration.to), (0, CONFIG_VERSION));
+        assert_eq!(migration.steps.len(), 1);
+        let table = migration.table;
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(CONFIG_VERSION as i64));
+        assert_eq!(table["summarization"]["model"].as_str(), Some("sonnet"));
+        assert_eq!(
+            table["summarization"]["max_tokens"].as_integer(),
+            Some(4096)
+        );
+        assert!(table.contains_key("jobs"));
+
+        fs::write(&path, toml::to_string(&table).unwrap()).unwrap();
+        assert!(migrate(&Config::default(), &path).unwrap().steps.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hh_mm() {
+        assert_eq!(parse_hh_mm("06:30"), Some((6, 30)));
+        assert_eq!(parse_hh_mm("24:00"), None);
+        assert_eq!(parse_hh_mm("6:30"), Some((6, 30)));
+        assert_eq!(parse_hh_mm("06:5"), None);
+    }
+}
@@ -0,0 +1,456 @@
+//! Running the dashboard as a background service that starts at login.
+//!
+//! `daily daemon install` registers `daily show --no-open` with the
+//! platform's service manager: a systemd user unit on Linux, a launchd
+//! agent on macOS, and a scheduled task started at logon on Windows. The
+//! server also runs the auto-digest check, so yesterday's sessions are
+//! digested after a reboot without starting a Claude Code session.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{active_profile, Config};
+
+/// Service manager the daemon is registered with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Systemd,
+    Launchd,
+    Windows,
+}
+
+impl Platform {
+    pub fn current() -> Result<Self> {
+        if cfg!(target_os = "macos") {
+            Ok(Platform::Launchd)
+        } else if cfg!(windows) {
+            Ok(Platform::Windows)
+        } else if cfg!(target_os = "linux") {
+            Ok(Platform::Systemd)
+        } else {
+            anyhow::bail!("No supported service manager on this platform")
+        }
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Platform::Systemd => write!(f, "systemd user unit"),
+            Platform::Launchd => write!(f, "launchd agent"),
+            Platform::Windows => write!(f, "Windows scheduled task"),
+        }
+    }
+}
+
+/// What the service runs
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub platform: Platform,
+    /// Unit, agent or task name; one per profile
+    pub name: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    /// PATH for the service, so the summarizer finds the `claude` CLI
+    pub path_env: String,
+    pub log: PathBuf,
+}
+
+/// Whether the service is registered and running
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub platform: String,
+    /// Unit file, plist or task name
+    pub location: String,
+    pub installed: bool,
+    pub running: bool,
+    pub log: PathBuf,
+}
+
+impl Service {
+    /// Service for this binary and the active profile
+    pub fn new(config: &Config, port: Option<u16>, host: Option<String>) -> Result<Self> {
+        let platform = Platform::current()?;
+        let program = std::env::current_exe().context("Failed to get current executable")?;
+        let profile = active_profile();
+        let mut args = Vec::new();
+        if let Some(profile) = &profile {
+            args.extend(["--profile".to_string(), profile.clone()]);
+        }
+        args.extend([
+            "show".to_string(),
+            "--no-open".to_string(),
+            "--daemon".to_string(),
+        ]);
+        if let Some(port) = port {
+            args.extend(["--port".to_string(), port.to_string()]);
+        }
+        if let Some(host) = host {
+            args.extend(["--host".to_string(), host]);
+        }
+        let base = match platform {
+            Platform::Launchd => "com.daily.dashboard",
+            _ => "daily",
+        };
+        Ok(Self {
+            platform,
+            name: match profile {
+                Some(profile) => format!("{}-{}", base, profile),
+                None => base.to_string(),
+            },
+            program,
+            args,
+            path_env: std::env::var("PATH").unwrap_or_default(),
+            log: config.storage_path().join("jobs").join("daemon.log"),
+        })
+    }
+
+    /// File the service is defined in (none for Windows tasks)
+    pub fn definition_path(&self) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        match self.platform {
+            Platform::Systemd => Some(
+                home.join(".config/systemd/user")
+                    .join(format!("{}.service", self.name)),
+            ),
+            Platform::Launchd => Some(
+                home.join("Library/LaunchAgents")
+                    .join(format!("{}.plist", self.name)),
+            ),
+            Platform::Windows => None,
+        }
+    }
+
+    pub fn install(&self) -> Result<()> {
+        if let Some(parent) = self.log.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match self.platform {
+            Platform::Systemd => {
+                self.write_definition(&systemd_unit(self))?;
+                run("systemctl", &["--user", "daemon-reload"])?;
+                run(
+                    "systemctl",
+                    &[
+                        "--user",
+                        "enable",
+                        "--now",
+                        &format!("{}.service", self.name),
+                    ],
+                )?;
+            }
+            Platform::Launchd => {
+                let path = self.write_definition(&launchd_plist(self))?;
+                let path = path.to_string_lossy();
+                // Reloading picks up a changed plist
+                let _ = run("launchctl", &["unload", &path]);
+                run("launchctl", &["load", "-w", &path])?;
+            }
+            Platform::Windows => {
+                let args = schtasks_create_args(self);
+                run(
+                    "schtasks",
+                    &args.iter().map(String::as_str).collect::<Vec<_>>(),
+                )?;
+                run("schtasks", &["/Run", "/TN", &self.name])?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn uninstall(&self) -> Result<()> {
+        match self.platform {
+            Platform::Systemd => {
+                let unit = format!("{}.service", self.name);
+                let _ = run("systemctl", &["--user", "disable", "--now", &unit]);
+                self.remove_definition()?;
+                run("systemctl", &["--user", "daemon-reload"])?;
+            }
+            Platform::Launchd => {
+                if let Some(path) = self.definition_path() {
+                    let _ = run("launchctl", &["unload", "-w", &path.to_string_lossy()]);
+                }
+                self.remove_definition()?;
+            }
+            Platform::Windows => {
+                let _ = run("schtasks", &["/End", "/TN", &self.name]);
+                run("schtasks", &["/Delete", "/TN", &self.name, "/F"])?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> ServiceStatus {
+        let definition = self.definition_path();
+        let (installed, running) = match self.platform {
+            Platform::Systemd => (
+                definition.as_deref().is_some_and(Path::exists),
+                succeeds(
+                    "systemctl",
+                    &[
+                        "--user",
+                        "is-active",
+                        "--quiet",
+                        &format!("{}.service", self.name),
+                    ],
+                ),
+            ),
+            Platform::Launchd => {
+                let installed = definition.as_deref().is_some_and(Path::exists);
+                // A loaded agent with a pid is running
+                let running = Command::new("launchctl")
+                    .args(["list", &self.name])
+                    .output()
+                    .is_ok_and(|out| {
+                        out.status.success()
+                            && String::from_utf8_lossy(&out.stdout).contains("\"PID\"")
+                    });
+                (installed, running)
+            }
+            Platform::Windows => {
+                let output = Command::new("schtasks")
+                    .args(["/Query", "/TN", &self.name, "/FO", "LIST"])
+                    .output();
+                match output {
+                    Ok(out) if out.status.success() => (
+                        true,
+                        String::from_utf8_lossy(&out.stdout).contains("Running"),
+                    ),
+                    _ => (false, false),
+                }
+            }
+        };
+        ServiceStatus {
+            name: self.name.clone(),
+            platform: self.platform.to_string(),
+            location: definition
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| self.name.clone()),
+            installed,
+            running,
+            log: self.log.clone(),
+        }
+    }
+
+    fn write_definition(&self, content: &str) -> Result<PathBuf> {
+        let path = self
+            .definition_path()
+            .context("Could not determine home directory")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    fn remove_definition(&self) -> Result<()> {
+        match self.definition_path() {
+            Some(path) if path.exists() => std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display())),
+            _ => Ok(()),
+        }
+    }
+
+    fn command_line(&self) -> Vec<String> {
+        std::iter::once(self.program.to_string_lossy().to_string())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+}
+
+/// systemd user unit restarting the dashboard if it exits. On stop only
+/// the dashboard gets SIGTERM, so it can wait for running jobs before the
+/// rest of the unit is killed.
+pub fn systemd_unit(service: &Service) -> String {
+    let exec = service
+        .command_line()
+        .iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Unit]\n\
+         Description=Daily dashboard and auto-digest\n\
+         After=network-online.target\n\
+         \n\
+         [Service]\n\
+         ExecStart={}\n\
+         Environment={}\n\
+         Restart=on-failure\n\
+         RestartSec=10\n\
+         KillMode=mixed\n\
+         TimeoutStopSec=60\n\
+         StandardOutput=append:{}\n\
+         StandardError=append:{}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        exec,
+        systemd_quote(&format!("PATH={}", service.path_env)),
+        service.log.display(),
+        service.log.display()
+    )
+}
+
+/// launchd agent started at login and kept alive
+pub fn launchd_plist(service: &Service) -> String {
+    let args: String = service
+        .command_line()
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect();
+    let log = xml_escape(&service.log.to_string_lossy());
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20   <key>Label</key>\n\
+         \x20   <string>{}</string>\n\
+         \x20   <key>ProgramArguments</key>\n\
+         \x20   <array>\n\
+         {}\
+         \x20   </array>\n\
+         \x20   <key>EnvironmentVariables</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>PATH</key>\n\
+         \x20       <string>{}</string>\n\
+         \x20   </dict>\n\
+         \x20   <key>RunAtLoad</key>\n\
+         \x20   <true/>\n\
+         \x20   <key>KeepAlive</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>SuccessfulExit</key>\n\
+         \x20       <false/>\n\
+         \x20   </dict>\n\
+         \x20   <key>StandardOutPath</key>\n\
+         \x20   <string>{}</string>\n\
+         \x20   <key>StandardErrorPath</key>\n\
+         \x20   <string>{}</string>\n\
+         </dict>\n\
+         </plist>\n",
+        xml_escape(&service.name),
+        args,
+        xml_escape(&service.path_env),
+        log,
+        log
+    )
+}
+
+/// `schtasks /Create` arguments for a task started at logon
+pub fn schtasks_create_args(service: &Service) -> Vec<String> {
+    let command = service
+        .command_line()
+        .iter()
+        .map(|arg| {
+            if arg.contains(' ') {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    [
+        "/Create",
+        "/TN",
+        &service.name,
+        "/TR",
+        &command,
+        "/SC",
+        "ONLOGON",
+        "/RL",
+        "LIMITED",
+        "/F",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn systemd_quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .any(|c| c.is_whitespace() || c == '"' || c == '\\')
+    {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn succeeds(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(platform: Platform) -> Service {
+        Service {
+            platform,
+            name: "daily-work".into(),
+            program: PathBuf::from("/opt/my tools/daily"),
+            args: vec![
+                "--profile".into(),
+                "work".into(),
+                "show".into(),
+                "--no-open".into(),
+            ],
+            path_env: "/usr/bin:/opt/claude".into(),
+            log: PathBuf::from("/home/me/.claude/daily/jobs/daemon.log"),
+        }
+    }
+
+    #[test]
+    fn test_service_definitions() {
+        let unit = systemd_unit(&service(Platform::Systemd));
+        assert!(unit.contains("ExecStart=\"/opt/my tools/daily\" --profile work show --no-open\n"));
+        assert!(unit.contains("Environment=PATH=/usr/bin:/opt/claude\n"));
+        assert!(unit.contains("StandardOutput=append:/home/me/.claude/daily/jobs/daemon.log\n"));
+        assert!(unit.contains("WantedBy=default.target"));
+
+        let plist = launchd_plist(&service(Platform::Launchd));
+        assert!(plist.contains("<string>daily-work</string>"));
+        assert!(plist.contains(
+            "        <string>/opt/my tools/daily</string>\n        <string>--profile</string>"
+        ));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+
+        let args = schtasks_create_args(&service(Platform::Windows));
+        assert_eq!(
+            args[4],
+            "\"/opt/my tools/daily\" --profile work show --no-open"
+        );
+        assert!(args.contains(&"ONLOGON".to_string()));
+    }
+}
@@ -0,0 +1,216 @@
+//! Ledger of the key decisions recorded in every session archive.
+//!
+//! Each session summary has a "Key Decisions & Trade-offs" section; the
+//! ledger collects its items from all dates into `{storage}/decisions.md`,
+//! newest first with the project and a link to the session, so a choice
+//! made months ago can be found without re-reading digests. The file is
+//! rewritten after every archived session and by `daily decisions --write`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::archive::daily::section;
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::{frontmatter, ArchiveManager};
+use crate::config::Config;
+use crate::storage;
+use crate::topics::bullets;
+
+/// Heading of the decisions section in session archives
+const DECISIONS_HEADING: &str = "Key Decisions & Trade-offs";
+
+/// One decision and the session it was made in
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub date: String,
+    /// Session archive name
+    pub session: String,
+    pub title: String,
+    /// Working directory of the session
+    pub project: Option<String>,
+    pub text: String,
+}
+
+impl Decision {
+    /// Whether every word of `query` appears in the decision, its session
+    /// title or project (case-insensitive)
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {} {}",
+            self.text,
+            self.title,
+            self.project.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        query
+            .split_whitespace()
+            .all(|word| haystack.contains(&word.to_lowercase()))
+    }
+}
+
+pub fn ledger_path(config: &Config) -> PathBuf {
+    config.storage_path().join("decisions.md")
+}
+
+/// Decisions from all session archives, newest session first
+pub fn collect(manager: &ArchiveManager) -> Result<Vec<Decision>> {
+    let mut decisions = Vec::new();
+    for date in manager.list_dates()? {
+        let mut sessions = manager.list_sessions(&date)?;
+        sessions.sort();
+        for name in sessions.iter().rev() {
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            let Ok(content) = manager.read_session(&date, name) else {
+                continue;
+            };
+            let title = frontmatter::get(&content, "title").unwrap_or(name);
+            let project = frontmatter::get(&content, "cwd")
+                .filter(|cwd| !cwd.is_empty())
+                .map(String::from);
+            for text in bullets(section(&content, DECISIONS_HEADING)) {
+                decisions.push(Decision {
+                    date: date.clone(),
+                    session: name.clone(),
+                    title: title.to_string(),
+                    project: project.clone(),
+                    text: text.to_string(),
+                });
+            }
+        }
+    }
+    Ok(decisions)
+}
+
+/// Decisions matching `query` (every word) and `project` (part of the
+/// directory), newest first, at most `limit`
+pub fn search(
+    manager: &ArchiveManager,
+    query: Option<&str>,
+    project: Option<&str>,
+    limit: usize,
+) -> Result<Vec<Decision>> {
+    Ok(collect(manager)?
+        .into_iter()
+        .filter(|d| query.is_none_or(|q| d.matches(q)))
+        .filter(|d| project.is_none_or(|p| d.project.as_deref().is_some_and(|dp| dp.contains(p))))
+        .take(limit)
+        .collect())
+}
+
+/// Rewrite `decisions.md` from the current archive
+pub fn write_ledger(manager: &ArchiveManager) -> Result<PathBuf> {
+    let config = manager.config();
+    let path = ledger_path(config);
+    storage::write_file(config, &path, render(config, &collect(manager)?))?;
+    Ok(path)
+}
+
+fn render(config: &Config, decisions: &[Decision]) -> String {
+    let mut out = format!(
+        "---\ntitle: \"Decisions\"\ntags: [decisions, claude-code]\ndecision_count: {}\n---\n\n# Decisions\n",
+        decisions.len()
+    );
+    if decisions.is_empty() {
+        out.push_str("\n_None yet._\n");
+        return out;
+    }
+    let mut current_date = "";
+    for decision in decisions {
+        if decision.date != current_date {
+            current_date = &decision.date;
+            out.push_str(&format!("\n## {}\n\n", current_date));
+        }
+        let project = decision
+            .project
+            .as_deref()
+            .map(|p| format!("`{}` · ", project_name(p)))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "- {}{} ([{}]({}))\n",
+            project,
+            decision.text,
+            decision.title,
+            session_link(config, &decision.date, &decision.session)
+        ));
+    }
+    out
+}
+
+/// Last component of a project directory
+pub fn project_name(project: &str) -> &str {
+    Path::new(project)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(project)
+}
+
+/// Path of a session archive relative to the storage directory
+fn session_link(config: &Config, date: &str, name: &str) -> String {
+    let storage = config.storage_path();
+    let date_dir = config.date_dir(date);
+    let relative = date_dir.strip_prefix(&storage).unwrap_or(Path::new(date));
+    format!("{}/{}.md", relative.to_string_lossy(), name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_search_and_write_decisions() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        let session = |title: &str, cwd: &str, decisions: &str| {
+            format!(
+                "---\ntitle: \"{}\"\ncwd: \"{}\"\n---\n\n## Summary\n\nWork.\n\n\
+                 ## Key Decisions & Trade-offs\n\n{}\n\n## Code Changes\n\n- `a.rs`\n",
+                title, cwd, decisions
+            )
+        };
+        manager
+            .write_session(
+                "2024-01-02",
+                "10_00-auth",
+                &session(
+                    "Auth",
+                    "/work/api",
+                    "- Switched to JWT for auth\n- Kept Postgres",
+                ),
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-03-05",
+                "09_00-cache",
+                &session("Cache", "/work/web", "- Cache pages in Redis"),
+            )
+            .unwrap();
+
+        let decisions = collect(&manager).unwrap();
+        assert_eq!(decisions.len(), 3);
+        assert_eq!(decisions[0].text, "Cache pages in Redis");
+        assert_eq!(decisions[1].project.as_deref(), Some("/work/api"));
+
+        let found = search(&manager, Some("jwt API"), None, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].session, "10_00-auth");
+        assert_eq!(
+            search(&manager, None, Some("/work/api"), 10).unwrap().len(),
+            2
+        );
+        assert_eq!(search(&manager, None, None, 1).unwrap().len(), 1);
+
+        let path = write_ledger(&manager).unwrap();
+        let ledger = fs::read_to_string(path).unwrap();
+        assert!(ledger.find("## 2024-03-05").unwrap() < ledger.find("## 2024-01-02").unwrap());
+        assert!(ledger
+            .contains("- `api` · Switched to JWT for auth ([Auth](2024-01-02/10_00-auth.md))"));
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::config::Config;
+
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Inputs sent per request, to stay under provider payload limits
+const BATCH_SIZE: usize = 32;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+enum Provider {
+    OpenAi,
+    Ollama,
+}
+
+/// Minimal client for OpenAI-compatible and Ollama embedding endpoints
+pub struct EmbeddingClient {
+    provider: Provider,
+    model: String,
+    base_url: String,
+    api_key: Option<String>,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl EmbeddingClient {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let settings = &config.embeddings;
+        let provider = match settings.provider.as_str() {
+            "openai" => Provider::OpenAi,
+            "ollama" => Provider::Ollama,
+            other => anyhow::bail!(
+                "Unknown embeddings provider '{}'. Use 'openai' or 'ollama'",
+                other
+            ),
+        };
+
+        let default_base = match provider {
+            Provider::OpenAi => OPENAI_BASE_URL,
+            Provider::Ollama => OLLAMA_BASE_URL,
+        };
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| default_base.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let api_key = match provider {
+            Provider::OpenAi => Some(std::env::var(&settings.api_key_env).with_context(|| {
+                format!("Embeddings API key not found in ${}", settings.api_key_env)
+            })?),
+            Provider::Ollama => None,
+        };
+
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Self {
+            provider,
+            model: settings.model.clone(),
+            base_url,
+            api_key,
+            http,
+        })
+    }
+
+    /// Model name, recorded in the index so a model change triggers a rebuild
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Embed texts, returning one vector per input in the same order
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(BATCH_SIZE) {
+            let batch_vectors = match self.provider {
+                Provider::OpenAi => self.embed_openai(batch).await?,
+                Provider::Ollama => self.embed_ollama(batch).await?,
+            };
+            if batch_vectors.len() != batch.len() {
+                anyhow::bail!(
+                    "Embeddings provider returned {} vectors for {} inputs",
+                    batch_vectors.len(),
+                    batch.len()
+                );
+            }
+            vectors.extend(batch_vectors);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_openai(&self, batch: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self
+            .http
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": batch }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response: OpenAiResponse = request
+            .send()
+            .await
+            .context("Failed to reach embeddings API")?
+            .error_for_status()
+            .context("Embeddings API returned an error")?
+            .json()
+            .await
+            .context("Failed to parse embeddings response")?;
+
+        let mut data = response.data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+
+    async fn embed_ollama(&self, batch: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response: OllamaResponse = self
+            .http
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": batch }))
+            .send()
+            .await
+            .context("Failed to reach Ollama (is `ollama serve` running?)")?
+            .error_for_status()
+            .context("Ollama returned an error")?
+            .json()
+            .await
+            .context("Failed to parse Ollama embeddings response")?;
+        Ok(response.embeddings)
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::archive::frontmatter;
+use crate::config::Config;
+
+/// Characters of session text sent for embedding
+const MAX_EMBED_CHARS: usize = 8000;
+
+/// Session vectors keyed by `date/name`, stored at `{storage}/.embeddings/index.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmbeddingIndex {
+    pub model: String,
+    #[serde(default)]
+    pub entries: HashMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Hash of the embedded text, to detect edited or re-summarized sessions
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+impl EmbeddingIndex {
+    fn path(config: &Config) -> PathBuf {
+        config.storage_path().join(".embeddings").join("index.json")
+    }
+
+    /// The index on disk, whatever model built it
+    pub fn read(config: &Config) -> Option<Self> {
+        fs::read_to_string(Self::path(config))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+    }
+
+    /// Load the index, starting fresh if missing or built with another model
+    pub fn load(config: &Config, model: &str) -> Self {
+        Self::read(config)
+            .filter(|index| index.model == model)
+            .unwrap_or_else(|| Self {
+                model: model.to_string(),
+                entries: HashMap::new(),
+            })
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = Self::path(config);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create embeddings directory")?;
+        }
+        fs::write(&path, serde_json::to_string(self)?).context("Failed to write embeddings index")
+    }
+
+    /// Whether `key` has a vector for exactly this text
+    pub fn is_fresh(&self, key: &str, text: &str) -> bool {
+        self.entries
+            .get(key)
+            .is_some_and(|entry| entry.hash == text_hash(text))
+    }
+
+    pub fn insert(&mut self, key: String, text: &str, vector: Vec<f32>) {
+        self.entries.insert(
+            key,
+            IndexEntry {
+                hash: text_hash(text),
+                vector,
+            },
+        );
+    }
+
+    /// Other entries ranked by cosine similarity to `key`, best first
+    pub fn nearest(&self, key: &str, limit: usize) -> Vec<(String, f32)> {
+        let Some(target) = self.entries.get(key) else {
+            return Vec::new();
+        };
+        let mut scored = self.nearest_to(&target.vector, usize::MAX);
+        scored.retain(|(other, _)| other.as_str() != key);
+        scored.truncate(limit);
+        scored
+    }
+
+    /// Entries ranked by cosine similarity to `vector`, best first
+    pub fn nearest_to(&self, vector: &[f32], limit: usize) -> Vec<(String, f32)> {
+        let mut scored: Vec<(String, f32)> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (key.clone(), cosine(vector, &entry.vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+/// Index key for a session
+pub fn session_key(date: &str, name: &str) -> String {
+    format!("{}/{}", date, name)
+}
+
+/// Text embedded for a session: its title plus the archive body without frontmatter
+pub fn embedding_text(content: &str) -> String {
+    let title = frontmatter::get(content, "title").unwrap_or_default();
+    let body = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---").map(|end| &rest[end + 4..]))
+        .unwrap_or(content)
+        .trim();
+    let text = format!("{}\n\n{}", title, body);
+    text.chars().take(MAX_EMBED_CHARS).collect()
+}
+
+fn text_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_ranks_by_cosine() {
+        let mut index = EmbeddingIndex::default();
+        index.insert("a".into(), "a", vec![1.0, 0.0]);
+        index.insert("b".into(), "b", vec![0.9, 0.1]);
+        index.insert("c".into(), "c", vec![0.0, 1.0]);
+
+        let nearest = index.nearest("a", 5);
+        assert_eq!(nearest.len(), 2);
+        assert_eq!(nearest[0].0, "b");
+        assert_eq!(nearest[1].0, "c");
+        assert!(nearest[1].1.abs() < 1e-6);
+        assert!(index.nearest("missing", 5).is_empty());
+    }
+
+    #[test]
+    fn test_freshness_tracks_text() {
+        let mut index = EmbeddingIndex::default();
+        index.insert("a".into(), "old summary", vec![1.0]);
+        assert!(index.is_fresh("a", "old summary"));
+        assert!(!index.is_fresh("a", "new summary"));
+        assert!(!index.is_fresh("b", "old summary"));
+    }
+
+    #[test]
+    fn test_embedding_text_strips_frontmatter() {
+        let content = "---\ntitle: \"Fix bug\"\nsession_id: abc\n---\n\n# Fix bug\n\nDetails\n";
+        let text = embedding_text(content);
+        assert!(text.starts_with("Fix bug\n\n# Fix bug"));
+        assert!(!text.contains("session_id"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Optional embeddings index over session archives, used to find related
+//! sessions by meaning rather than shared keywords.
+
+mod client;
+mod index;
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::archive::frontmatter;
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+
+use client::EmbeddingClient;
+use index::{embedding_text, session_key, EmbeddingIndex};
+
+/// A session similar to the one queried
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedSession {
+    pub date: String,
+    pub name: String,
+    pub title: String,
+    /// Cosine similarity in [-1, 1]
+    pub score: f32,
+}
+
+/// Result of bringing the index up to date
+#[derive(Debug, Default)]
+pub struct IndexStats {
+    pub embedded: usize,
+    pub total: usize,
+}
+
+fn ensure_enabled(config: &Config) -> Result<()> {
+    if !config.embeddings.enabled {
+        anyhow::bail!(
+            "Embeddings are disabled. Set `embeddings.enabled = true` in the config to use related sessions"
+        );
+    }
+    Ok(())
+}
+
+/// Embed every session whose text changed since it was last indexed,
+/// and drop entries for sessions that no longer exist
+pub async fn update_index(config: &Config) -> Result<IndexStats> {
+    ensure_enabled(config)?;
+    let client = EmbeddingClient::from_config(config)?;
+    let manager = ArchiveManager::new(config.clone());
+    let mut index = EmbeddingIndex::load(config, client.model());
+
+    let mut live = HashSet::new();
+    let mut stale = Vec::new();
+    for date in manager.list_dates()? {
+        for name in manager.list_sessions(&date)? {
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            let Ok(content) = manager.read_session(&date, &name) else {
+                continue;
+            };
+            let key = session_key(&date, &name);
+            let text = embedding_text(&content);
+            if !index.is_fresh(&key, &text) {
+                stale.push((key.clone(), text));
+            }
+            live.insert(key);
+        }
+    }
+
+    index.entries.retain(|key, _| live.contains(key));
+
+    let texts: Vec<String> = stale.iter().map(|(_, text)| text.clone()).collect();
+    let vectors = client.embed(&texts).await?;
+    for ((key, text), vector) in stale.iter().zip(vectors) {
+        index.insert(key.clone(), text, vector);
+    }
+    index.save(config)?;
+
+    Ok(IndexStats {
+        embedded: stale.len(),
+        total: index.entries.len(),
+    })
+}
+
+/// Keep a renamed session's vector under its new name until it is
+/// re-embedded; a no-op without an index
+pub fn rename_session(config: &Config, date: &str, old: &str, new: &str) -> Result<()> {
+    let Some(mut index) = EmbeddingIndex::read(config) else {
+        return Ok(());
+    };
+    let Some(entry) = index.entries.remove(&session_key(date, old)) else {
+        return Ok(());
+    };
+    index.entries.insert(session_key(date, new), entry);
+    index.save(config)
+}
+
+/// Embed a single session if its text changed; a no-op when embeddings are disabled
+pub async fn index_session(config: &Config, date: &str, name: &str) -> Result<()> {
+    if !config.embeddings.enabled || name.starts_with(CHECKPOINT_PREFIX) {
+        return Ok(());
+    }
+    let client = EmbeddingClient::from_config(config)?;
+    let manager = ArchiveManager::new(config.clone());
+    let mut index = EmbeddingIndex::load(config, client.model());
+
+    let text = embedding_text(&manager.read_session(date, name)?);
+    let key = session_key(date, name);
+    if index.is_fresh(&key, &text) {
+        return Ok(());
+    }
+    let vector = client
+        .embed(std::slice::from_ref(&text))
+        .await?
+        .pop()
+        .unwrap_or_default();
+    index.insert(key, &text, vector);
+    index.save(config)
+}
+
+/// Indexed sessions closest in meaning to a free-text `query`, best first
+pub async fn search(config: &Config, query: &str, limit: usize) -> Result<Vec<RelatedSession>> {
+    ensure_enabled(config)?;
+    let client = EmbeddingClient::from_config(config)?;
+    let vector = client
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    let manager = ArchiveManager::new(config.clone());
+    let index = EmbeddingIndex::load(config, client.model());
+    Ok(to_sessions(&manager, index.nearest_to(&vector, usize::MAX))
+        .take(limit)
+        .collect())
+}
+
+/// Sessions most similar to `date/name`, best first.
+/// The queried session is embedded on demand; others must already be indexed.
+pub async fn find_related(
+    config: &Config,
+    date: &str,
+    name: &str,
+    limit: usize,
+) -> Result<Vec<RelatedSession>> {
+    ensure_enabled(config)?;
+    index_session(config, date, name).await?;
+
+    let manager = ArchiveManager::new(config.clone());
+    let index = EmbeddingIndex::load(config, &config.embeddings.model);
+
+    Ok(to_sessions(
+        &manager,
+        index.nearest(&session_key(date, name), usize::MAX),
+    )
+    .take(limit)
+    .collect())
+}
+
+/// Ranked index keys as sessions; ones deleted since the last reindex are skipped
+fn to_sessions(
+    manager: &ArchiveManager,
+    ranked: Vec<(String, f32)>,
+) -> impl Iterator<Item = RelatedSession> + '_ {
+    ranked.into_iter().filter_map(|(key, score)| {
+        let (date, name) = key.split_once('/')?;
+        let content = manager.read_session(date, name).ok()?;
+        Some(RelatedSession {
+            date: date.to_string(),
+            name: name.to_string(),
+            title: frontmatter::get(&content, "title")
+                .unwrap_or(name)
+                .to_string(),
+            score,
+        })
+    })
+}
@@ -0,0 +1,318 @@
+//! Tidy tables of usage and insights data for spreadsheets.
+//!
+//! Every table has one row per session or per day, with list columns
+//! (models, goal categories, friction types) joined by `;` so the CSV stays
+//! rectangular. JSON output is the same rows as an array of objects.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::archive::paths::PathFilter;
+use crate::config::Config;
+use crate::insights::collector::{InsightsData, InsightsFilter};
+use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
+use crate::usage::types::SessionUsage;
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => anyhow::bail!("Unknown format '{}': expected csv or json", s),
+        }
+    }
+}
+
+/// What a row stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Session,
+    Day,
+}
+
+impl std::str::FromStr for Granularity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "session" => Ok(Granularity::Session),
+            "day" => Ok(Granularity::Day),
+            _ => anyhow::bail!("Unknown row type '{}': expected session or day", s),
+        }
+    }
+}
+
+/// Claude Code usage of one session
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageRow {
+    pub date: String,
+    pub session_id: String,
+    pub project: String,
+    pub models: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+    pub subagent_cost_usd: f64,
+}
+
+/// Claude Code usage of one day
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageDayRow {
+    pub date: String,
+    pub session_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+    pub subagent_cost_usd: f64,
+}
+
+/// Archive insights of one session
+#[derive(Debug, Clone, Serialize)]
+pub struct InsightsRow {
+    pub date: String,
+    pub session_id: String,
+    pub session_name: String,
+    pub session_type: String,
+    pub outcome: String,
+    pub satisfaction: String,
+    pub claude_helpfulness: String,
+    pub goal_categories: String,
+    pub friction_types: String,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    pub brief_summary: String,
+}
+
+/// Archive insights of one day
+#[derive(Debug, Clone, Serialize)]
+pub struct InsightsDayRow {
+    pub date: String,
+    pub session_count: usize,
+    pub has_digest: bool,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+/// One row per session started between `from` and `to` (YYYY-MM-DD,
+/// inclusive), oldest first
+pub fn usage_rows(
+    sessions: &HashMap<String, SessionUsage>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Vec<UsageRow> {
+    let mut rows: Vec<UsageRow> = sessions
+        .values()
+        .filter_map(|usage| {
+            let date = usage
+                .first_timestamp
+                .as_deref()
+                .and_then(scanner::extract_date_from_timestamp)?;
+            if from.is_some_and(|from| date.as_str() < from)
+                || to.is_some_and(|to| date.as_str() > to)
+            {
+                return None;
+            }
+            let mut models: Vec<&str> = usage.model_calls.keys().map(String::as_str).collect();
+            models.sort_unstable();
+            Some(UsageRow {
+                date,
+                session_id: usage.session_id.clone(),
+                project: usage.project.clone().unwrap_or_default(),
+                models: models.join(";"),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_tokens: usage.cache_creation_tokens,
+                cache_read_tokens: usage.cache_read_tokens,
+                total_cost_usd: usage.total_cost_usd,
+                subagent_cost_usd: usage.subagent_cost_usd,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.date, &a.session_id).cmp(&(&b.date, &b.session_id)));
+    rows
+}
+
+/// Session rows summed per day, oldest first
+pub fn usage_day_rows(rows: &[UsageRow]) -> Vec<UsageDayRow> {
+    let mut days: Vec<UsageDayRow> = Vec::new();
+    for row in rows {
+        if days.last().is_none_or(|day| day.date != row.date) {
+            days.push(UsageDayRow {
+                date: row.date.clone(),
+                session_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_cost_usd: 0.0,
+                subagent_cost_usd: 0.0,
+            });
+        }
+        let day = days.last_mut().expect("a day was just added");
+        day.session_count += 1;
+        day.input_tokens += row.input_tokens;
+        day.output_tokens += row.output_tokens;
+        day.cache_creation_tokens += row.cache_creation_tokens;
+        day.cache_read_tokens += row.cache_read_tokens;
+        day.total_cost_usd += row.total_cost_usd;
+        day.subagent_cost_usd += row.subagent_cost_usd;
+    }
+    days
+}
+
+/// One row per archived session, oldest first
+pub fn insights_rows(data: &InsightsData) -> Vec<InsightsRow> {
+    let mut rows: Vec<InsightsRow> = data
+        .session_details
+        .iter()
+        .map(|s| {
+            let usage = s.token_usage.as_ref();
+            InsightsRow {
+                date: s.date.clone(),
+                session_id: s.session_id.clone(),
+                session_name: s.session_name.clone(),
+                session_type: s.session_type.clone().unwrap_or_default(),
+                outcome: s.outcome.clone().unwrap_or_default(),
+                satisfaction: s.satisfaction.clone().unwrap_or_default(),
+                claude_helpfulness: s.claude_helpfulness.clone().unwrap_or_default(),
+                goal_categories: s.goal_categories.join(";"),
+                friction_types: s.friction_types.join(";"),
+                total_tokens: usage.map_or(0, |u| {
+                    u.input_tokens + u.output_tokens + u.cache_creation_tokens + u.cache_read_tokens
+                }),
+                total_cost_usd: usage.map_or(0.0, |u| u.total_cost_usd),
+                brief_summary: s.brief_summary.clone().unwrap_or_default(),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.date, &a.session_name).cmp(&(&b.date, &b.session_name)));
+    rows
+}
+
+/// One row per archived day, oldest first
+pub fn insights_day_rows(data: &InsightsData) -> Vec<InsightsDayRow> {
+    let mut rows: Vec<InsightsDayRow> = data
+        .daily_stats
+        .iter()
+        .map(|d| InsightsDayRow {
+            date: d.date.clone(),
+            session_count: d.session_count,
+            has_digest: d.has_digest,
+            total_tokens: d.total_tokens.unwrap_or(0),
+            total_cost_usd: d.total_cost.unwrap_or(0.0),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.date.cmp(&b.date));
+    rows
+}
+
+/// Usage table for sessions started between `from` and `to`
+pub fn usage_table(
+    config: &Config,
+    pricing: &PricingData,
+    from: Option<&str>,
+    to: Option<&str>,
+    per: Granularity,
+    format: Format,
+) -> Result<String> {
+    let rows = usage_rows(
+        &scanner::scan_all_sessions(None, pricing, &PathFilter::from_config(config)),
+        from,
+        to,
+    );
+    match per {
+        Granularity::Session => render(&rows, format),
+        Granularity::Day => render(&usage_day_rows(&rows), format),
+    }
+}
+
+/// Insights table of the archive, limited like `/api/insights`
+pub fn insights_table(
+    config: &Config,
+    pricing: &PricingData,
+    days: Option<usize>,
+    filter: &InsightsFilter,
+    per: Granularity,
+    format: Format,
+) -> Result<String> {
+    let data = InsightsData::collect(config, days, pricing, filter)?;
+    match per {
+        Granularity::Session => render(&insights_rows(&data), format),
+        Granularity::Day => render(&insights_day_rows(&data), format),
+    }
+}
+
+/// Render `rows` as CSV with a header row, or as a JSON array
+pub fn render<T: Serialize>(rows: &[T], format: Format) -> Result<String> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(rows)?),
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            let bytes = writer.into_inner().context("Failed to write CSV")?;
+            Ok(String::from_utf8(bytes)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_rows_csv() {
+        let mut sessions = HashMap::new();
+        for (id, date, cost) in [
+            ("s2", "2026-03-02", 0.02),
+            ("s1", "2026-03-01", 0.01),
+            ("s3", "2026-03-05", 0.03),
+        ] {
+            let mut model_calls = HashMap::new();
+            model_calls.insert("claude-sonnet-4-5".to_string(), 2);
+            model_calls.insert("claude-haiku-4-5".to_string(), 1);
+            sessions.insert(
+                id.to_string(),
+                SessionUsage {
+                    session_id: id.to_string(),
+                    input_tokens: 100,
+                    total_cost_usd: cost,
+                    model_calls,
+                    first_timestamp: Some(format!("{}T10:00:00Z", date)),
+                    project: Some("/work/a, b".into()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let rows = usage_rows(&sessions, Some("2026-03-01"), Some("2026-03-02"));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].session_id, "s1");
+
+        let csv = render(&rows, Format::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("date,session_id,project,models,input_tokens,"));
+        let days = usage_day_rows(&rows);
+        assert_eq!(days.len(), 2);
+        assert!((days[1].total_cost_usd - 0.02).abs() < 1e-9);
+        assert!(lines[1]
+            .starts_with("2026-03-01,s1,\"/work/a, b\",claude-haiku-4-5;claude-sonnet-4-5,100,"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Flashcards from the learnings recorded in session archives.
+//!
+//! Each item of a session's "Learnings" section becomes one card. A learning
+//! written as `term: explanation` (or `term — explanation`) asks for the
+//! explanation given the term; any other learning is shown as the answer to
+//! a prompt naming its session. Cards repeating an earlier learning are
+//! dropped, and every card is tagged with its project and the topics its
+//! session was filed under.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::archive::daily::section;
+use crate::archive::session::CHECKPOINT_PREFIX;
+use crate::archive::{frontmatter, ArchiveManager};
+use crate::decisions::project_name;
+use crate::topics::{bullets, TopicIndex};
+
+/// Heading of the learnings section in session archives
+const LEARNINGS_HEADING: &str = "Learnings";
+
+/// Longest term (in words) split off the front of a learning
+const MAX_TERM_WORDS: usize = 8;
+
+/// One question/answer card
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Card {
+    pub front: String,
+    pub back: String,
+    /// Project and topic tags, without spaces
+    pub tags: Vec<String>,
+    pub date: String,
+    /// Session archive name
+    pub session: String,
+}
+
+/// Cards from the sessions dated `from..=to`, oldest first
+pub fn collect(
+    manager: &ArchiveManager,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<Card>> {
+    let index = TopicIndex::load(manager.config());
+    let topics = session_topics(&index);
+    let mut dates = manager.list_dates()?;
+    dates.retain(|d| from.is_none_or(|f| d.as_str() >= f) && to.is_none_or(|t| d.as_str() <= t));
+    dates.sort();
+
+    let mut seen = HashSet::new();
+    let mut cards = Vec::new();
+    for date in dates {
+        let mut sessions = manager.list_sessions(&date)?;
+        sessions.sort();
+        for name in sessions {
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            let Ok(content) = manager.read_session(&date, &name) else {
+                continue;
+            };
+            let title = frontmatter::get(&content, "title").unwrap_or(&name);
+            let mut tags: Vec<String> = frontmatter::get(&content, "cwd")
+                .filter(|cwd| !cwd.is_empty())
+                .map(|cwd| tag(project_name(cwd)))
+                .into_iter()
+                .collect();
+            if let Some(slugs) = topics.get(format!("{}/{}", date, name).as_str()) {
+                tags.extend(slugs.iter().map(|s| tag(s)));
+            }
+
+            for learning in bullets(section(&content, LEARNINGS_HEADING)) {
+                let learning = single_line(learning);
+                if !seen.insert(normalize(&learning)) {
+                    continue;
+                }
+                let (front, back) = match split_term(&learning) {
+                    Some((term, explanation)) => (term.to_string(), explanation.to_string()),
+                    None => (format!("Lesson from \"{}\" ({})", title, date), learning),
+                };
+                cards.push(Card {
+                    front,
+                    back,
+                    tags: tags.clone(),
+                    date: date.clone(),
+                    session: name.clone(),
+                });
+            }
+        }
+    }
+    Ok(cards)
+}
+
+/// Tab-separated notes for Anki's File > Import
+pub fn to_anki(cards: &[Card]) -> String {
+    let mut out = String::from("#separator:tab\n#html:false\n#tags column:3\n");
+    for card in cards {
+        let mut tags = vec!["daily".to_string()];
+        tags.extend(card.tags.iter().cloned());
+        out.push_str(&format!(
+            "{}\t{}\t{}\n",
+            card.front.replace('\t', " "),
+            card.back.replace('\t', " "),
+            tags.join(" ")
+        ));
+    }
+    out
+}
+
+/// Topic slugs of each `date/name` session
+fn session_topics(index: &TopicIndex) -> HashMap<&str, Vec<&str>> {
+    let mut topics: HashMap<&str, Vec<&str>> = HashMap::new();
+    for topic in &index.topics {
+        for session in &topic.sessions {
+            topics.entry(session).or_default().push(&topic.slug);
+        }
+    }
+    topics
+}
+
+/// `term: explanation` or `term — explanation`, markdown emphasis removed
+fn split_term(learning: &str) -> Option<(&str, &str)> {
+    let (term, explanation) = [": ", " — ", " - "]
+        .iter()
+        .filter_map(|sep| learning.split_once(sep))
+        .min_by_key(|(term, _)| term.len())?;
+    let term = term.trim().trim_matches(['*', '_', '`']).trim();
+    let explanation = explanation.trim();
+    if term.is_empty() || explanation.is_empty() || term.split_whitespace().count() > MAX_TERM_WORDS
+    {
+        return None;
+    }
+    Some((term, explanation))
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Dedup key: lowercase letters and digits only
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn tag(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_cards_from_learnings() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        let session = |title: &str, learnings: &str| {
+            format!(
+                "---\ntitle: \"{}\"\ncwd: \"/work/my api\"\n---\n\n## Summary\n\nWork.\n\n\
+                 ## Learnings\n\n{}\n\n## Skill Hints\n\nNone\n",
+                title, learnings
+            )
+        };
+        manager
+            .write_session(
+                "2024-01-02",
+                "10_00-auth",
+                &session(
+                    "Auth",
+                    "- **Tokio Mutex**: never hold a std mutex across .await\n\
+                     - Refresh tokens must be rotated on every use",
+                ),
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-03-05",
+                "09_00-cache",
+                &session("Cache", "- refresh tokens must be rotated on every use!"),
+            )
+            .unwrap();
+        let topics_dir = crate::topics::topics_dir(&config);
+        std::fs::create_dir_all(&topics_dir).unwrap();
+        std::fs::write(
+            topics_dir.join("topics.json"),
+            r#"{"topics": [{"slug": "authentication", "title": "Authentication", "sessions": ["2024-01-02/10_00-auth"]}]}"#,
+        )
+        .unwrap();
+
+        let cards = collect(&manager, None, None).unwrap();
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].front, "Tokio Mutex");
+        assert_eq!(cards[0].back, "never hold a std mutex across .await");
+        assert_eq!(cards[0].tags, vec!["my-api", "authentication"]);
+        assert_eq!(cards[1].front, "Lesson from \"Auth\" (2024-01-02)");
+        let later = collect(&manager, Some("2024-02-01"), None).unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].tags, vec!["my-api"]);
+
+        let anki = to_anki(&cards);
+        assert!(anki.starts_with("#separator:tab\n"));
+        assert!(anki.contains(
+            "Tokio Mutex\tnever hold a std mutex across .await\tdaily my-api authentication\n"
+        ));
+    }
+}
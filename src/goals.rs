@@ -0,0 +1,260 @@
+//! Goals that span several days, e.g. "ship billing MVP".
+//!
+//! Goals are kept in `{storage}/goals.json`. Digests are given the active
+//! ones and report which of them the day's work moved forward; each report
+//! is recorded against the goal by date, so regenerating a digest replaces
+//! that day's entry instead of adding another.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::storage;
+
+/// Whether a goal is still being worked on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Active,
+    Done,
+}
+
+impl GoalStatus {
+    /// Name used by the API, e.g. "active"
+    pub fn key(&self) -> &'static str {
+        match self {
+            GoalStatus::Active => "active",
+            GoalStatus::Done => "done",
+        }
+    }
+}
+
+impl std::str::FromStr for GoalStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(GoalStatus::Active),
+            "done" => Ok(GoalStatus::Done),
+            _ => anyhow::bail!("Unknown goal status '{}': expected active or done", s),
+        }
+    }
+}
+
+/// A goal and the progress digests reported on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: u32,
+    pub title: String,
+    pub status: GoalStatus,
+    pub created: DateTime<Local>,
+    #[serde(default)]
+    pub completed: Option<DateTime<Local>>,
+    /// One entry per digested date that moved the goal forward, oldest first
+    #[serde(default)]
+    pub progress: Vec<GoalProgress>,
+}
+
+/// What one day's work did for a goal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub date: String,
+    pub note: String,
+    /// Sessions of the day that worked towards the goal
+    #[serde(default)]
+    pub sessions: usize,
+}
+
+/// Progress on a goal as reported in a digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalReport {
+    /// Id of the goal
+    pub goal: u32,
+    /// Filled in from the goal when the digest is parsed
+    #[serde(default)]
+    pub title: String,
+    pub progress: String,
+    #[serde(default)]
+    pub sessions: usize,
+}
+
+/// Per-goal numbers for insights
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalStat {
+    pub id: u32,
+    pub title: String,
+    pub status: GoalStatus,
+    pub session_count: usize,
+    pub day_count: usize,
+    /// Date the goal was marked done
+    pub completed: Option<String>,
+}
+
+/// All goals, in order of creation
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GoalStore {
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+}
+
+fn store_path(config: &Config) -> PathBuf {
+    config.storage_path().join("goals.json")
+}
+
+impl GoalStore {
+    /// Saved goals; none if the file doesn't exist yet
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = store_path(config);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = store_path(config);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        storage::write_file(config, &path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add an active goal and return it
+    pub fn add(&mut self, title: &str) -> Result<&Goal> {
+        let title = title.trim();
+        if title.is_empty() {
+            anyhow::bail!("Goal title is empty");
+        }
+        let id = self.goals.iter().map(|g| g.id).max().unwrap_or(0) + 1;
+        self.goals.push(Goal {
+            id,
+            title: title.to_string(),
+            status: GoalStatus::Active,
+            created: Local::now(),
+            completed: None,
+            progress: Vec::new(),
+        });
+        Ok(self.goals.last().unwrap())
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Result<&mut Goal> {
+        self.goals
+            .iter_mut()
+            .find(|g| g.id == id)
+            .with_context(|| format!("No goal with id {}", id))
+    }
+
+    pub fn remove(&mut self, id: u32) -> Result<Goal> {
+        let index = self
+            .goals
+            .iter()
+            .position(|g| g.id == id)
+            .with_context(|| format!("No goal with id {}", id))?;
+        Ok(self.goals.remove(index))
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Goal> {
+        self.goals.iter().filter(|g| g.status == GoalStatus::Active)
+    }
+
+    /// Replace the progress recorded for `date` with a digest's reports;
+    /// reports on unknown goals are ignored
+    pub fn record(&mut self, date: &str, reports: &[GoalReport]) {
+        for goal in &mut self.goals {
+            goal.progress.retain(|p| p.date != date);
+            if let Some(report) = reports.iter().find(|r| r.goal == goal.id) {
+                goal.progress.push(GoalProgress {
+                    date: date.to_string(),
+                    note: report.progress.clone(),
+                    sessions: report.sessions,
+                });
+                goal.progress.sort_by(|a, b| a.date.cmp(&b.date));
+            }
+        }
+    }
+
+    /// Sessions and days spent on each goal within `dates`, active goals first
+    pub fn stats(&self, dates: &[String]) -> Vec<GoalStat> {
+        let mut stats: Vec<GoalStat> = self
+            .goals
+            .iter()
+            .map(|goal| {
+                let progress: Vec<&GoalProgress> = goal
+                    .progress
+                    .iter()
+                    .filter(|p| dates.contains(&p.date))
+                    .collect();
+                GoalStat {
+                    id: goal.id,
+                    title: goal.title.clone(),
+                    status: goal.status,
+                    session_count: progress.iter().map(|p| p.sessions).sum(),
+                    day_count: progress.len(),
+                    completed: goal.completed.map(|c| c.format("%Y-%m-%d").to_string()),
+                }
+            })
+            .collect();
+        stats.sort_by_key(|s| s.status != GoalStatus::Active);
+        stats
+    }
+}
+
+impl Goal {
+    /// Mark the goal done or active again
+    pub fn set_status(&mut self, status: GoalStatus) {
+        self.completed = match status {
+            GoalStatus::Done => self.completed.or_else(|| Some(Local::now())),
+            GoalStatus::Active => None,
+        };
+        self.status = status;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_progress_and_stats() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        let mut store = GoalStore::default();
+        store.add("ship billing MVP").unwrap();
+        store.add("migrate CI").unwrap();
+        assert!(store.add("  ").is_err());
+
+        let report = |goal, sessions| GoalReport {
+            goal,
+            title: String::new(),
+            progress: "Moved forward".into(),
+            sessions,
+        };
+        store.record("2024-01-01", &[report(1, 2), report(9, 1)]);
+        store.record("2024-01-02", &[report(1, 1)]);
+        // Regenerating a digest replaces that day's entry
+        store.record("2024-01-02", &[report(1, 3)]);
+        store.get_mut(2).unwrap().set_status(GoalStatus::Done);
+        store.save(&config).unwrap();
+
+        let store = GoalStore::load(&config).unwrap();
+        assert_eq!(store.active().count(), 1);
+        let dates = vec!["2024-01-01".to_string(), "2024-01-02".to_string()];
+        let stats = store.stats(&dates);
+        assert_eq!(
+            (stats[0].id, stats[0].session_count, stats[0].day_count),
+            (1, 5, 2)
+        );
+        assert_eq!(stats[1].status, GoalStatus::Done);
+        assert!(stats[1].completed.is_some());
+        assert_eq!(store.stats(&dates[..1])[0].session_count, 2);
+    }
+}
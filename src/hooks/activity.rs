@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Per-session heartbeat written by the UserPromptSubmit and Stop hooks.
+/// Stored as `{storage}/.activity/{session_id}.json` and removed at session end.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionActivity {
+    pub transcript_path: PathBuf,
+    pub cwd: PathBuf,
+    #[serde(default)]
+    pub last_prompt_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub prompt_count: u64,
+    #[serde(default)]
+    pub last_checkpoint_at: Option<DateTime<Local>>,
+}
+
+impl SessionActivity {
+    /// Whether a prompt was submitted within the last `minutes`
+    pub fn is_active_within(&self, minutes: u64, now: DateTime<Local>) -> bool {
+        self.last_prompt_at
+            .is_some_and(|at| now - at < Duration::minutes(minutes as i64))
+    }
+
+    /// Whether enough time has passed since the last checkpoint to write another
+    pub fn checkpoint_due(&self, interval_minutes: u64, now: DateTime<Local>) -> bool {
+        self.last_checkpoint_at
+            .is_none_or(|at| now - at >= Duration::minutes(interval_minutes as i64))
+    }
+}
+
+fn activity_dir(config: &Config) -> PathBuf {
+    config.storage_path().join(".activity")
+}
+
+fn activity_path(config: &Config, session_id: &str) -> PathBuf {
+    let safe_id: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    activity_dir(config).join(format!("{}.json", safe_id))
+}
+
+/// Load the heartbeat for a session, if one was recorded
+pub fn load(config: &Config, session_id: &str) -> Option<SessionActivity> {
+    let content = fs::read_to_string(activity_path(config, session_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Persist the heartbeat for a session
+pub fn save(config: &Config, session_id: &str, activity: &SessionActivity) -> Result<()> {
+    let path = activity_path(config, session_id);
+    fs::create_dir_all(activity_dir(config)).context("Failed to create activity directory")?;
+    let content = serde_json::to_string_pretty(activity)?;
+    fs::write(&path, content).context("Failed to write session activity")?;
+    Ok(())
+}
+
+/// Forget a session's heartbeat (ignores missing files)
+pub fn remove(config: &Config, session_id: &str) {
+    let _ = fs::remove_file(activity_path(config, session_id));
+}
+
+/// Every recorded heartbeat, keyed by session id
+pub fn list(config: &Config) -> Vec<(String, SessionActivity)> {
+    let Ok(entries) = fs::read_dir(activity_dir(config)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            let session_id = path.file_stem()?.to_str()?.to_string();
+            let activity = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            Some((session_id, activity))
+        })
+        .collect()
+}
+
+/// Record a prompt submission for a session
+pub fn record_prompt(
+    config: &Config,
+    session_id: &str,
+    transcript_path: &Path,
+    cwd: &Path,
+) -> Result<SessionActivity> {
+    let mut activity = load(config, session_id).unwrap_or_default();
+    activity.transcript_path = transcript_path.to_path_buf();
+    activity.cwd = cwd.to_path_buf();
+    activity.last_prompt_at = Some(Local::now());
+    activity.prompt_count += 1;
+    save(config, session_id, &activity)?;
+    Ok(activity)
+}
+
+/// Whether a session (by transcript file stem) had a prompt within the last `minutes`
+pub fn is_recently_active(config: &Config, session_id: &str, minutes: u64) -> bool {
+    load(config, session_id).is_some_and(|a| a.is_active_within(minutes, Local::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(dir: &TempDir) -> Config {
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        config
+    }
+
+    #[test]
+    fn test_record_prompt_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let config = test_config(&dir);
+
+        record_prompt(&config, "abc-123", Path::new("/t.jsonl"), Path::new("/p")).unwrap();
+        let activity =
+            record_prompt(&config, "abc-123", Path::new("/t.jsonl"), Path::new("/p")).unwrap();
+        assert_eq!(activity.prompt_count, 2);
+        assert!(is_recently_active(&config, "abc-123", 5));
+        assert!(!is_recently_active(&config, "other", 5));
+
+        remove(&config, "abc-123");
+        assert!(load(&config, "abc-123").is_none());
+    }
+
+    #[test]
+    fn test_checkpoint_due() {
+        let now = Local::now();
+        let mut activity = SessionActivity::default();
+        assert!(activity.checkpoint_due(30, now));
+
+        activity.last_checkpoint_at = Some(now - Duration::minutes(10));
+        assert!(!activity.checkpoint_due(30, now));
+        assert!(activity.checkpoint_due(10, now));
+    }
+}
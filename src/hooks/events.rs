@@ -0,0 +1,203 @@
+//! Outcome of each `daily hook` invocation.
+//!
+//! Hooks never fail Claude Code: errors are printed and swallowed, so a
+//! broken install (no `daily` on PATH, an unwritable jobs directory) would
+//! otherwise go unnoticed. [`run`] bounds each handler by
+//! `hooks.timeout_secs` and appends what happened to
+//! `{storage}/.hook-events.jsonl`, which `daily doctor` and
+//! `/api/hooks/events` show.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::archive::atomic::write_atomic;
+use crate::config::{load_config, Config};
+
+/// Events shown by `daily doctor` and the API
+pub const RECENT_EVENTS: usize = 20;
+
+/// Events kept in the log; older ones are dropped
+const MAX_EVENTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookOutcome {
+    Ok,
+    Failed,
+    TimedOut,
+}
+
+/// One hook invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookEvent {
+    pub at: DateTime<Local>,
+    /// Hook name as in `daily hook <name>`, e.g. "session-end"
+    pub hook: String,
+    pub outcome: HookOutcome,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn events_path(config: &Config) -> PathBuf {
+    config.storage_path().join(".hook-events.jsonl")
+}
+
+/// Held while appending and trimming, so concurrent hooks don't drop each
+/// other's events
+fn lock_path(config: &Config) -> PathBuf {
+    config.storage_path().join(".hook-events.lock")
+}
+
+/// Run a hook handler within `hooks.timeout_secs` and record the outcome.
+/// Failures are printed, not returned, so the hook never blocks or fails
+/// the Claude Code action that triggered it.
+pub async fn run<F>(hook: &str, handler: F) -> Result<()>
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    let Ok(config) = load_config() else {
+        // Without a config there is nowhere to record; let the handler report it
+        return handler.await;
+    };
+    let started = Instant::now();
+    let timeout = Duration::from_secs(config.hooks.timeout_secs.max(1));
+    // Spawned so the timeout still fires while the handler is stuck in a
+    // blocking call
+    let (outcome, error) = match tokio::time::timeout(timeout, tokio::spawn(handler)).await {
+        Ok(Ok(Ok(()))) => (HookOutcome::Ok, None),
+        Ok(Ok(Err(e))) => (HookOutcome::Failed, Some(format!("{:#}", e))),
+        Ok(Err(e)) => (HookOutcome::Failed, Some(e.to_string())),
+        Err(_) => (
+            HookOutcome::TimedOut,
+            Some(format!("Gave up after {}s", timeout.as_secs())),
+        ),
+    };
+    if let Some(error) = &error {
+        eprintln!("[daily] {} hook failed: {}", hook, error);
+    }
+    let event = HookEvent {
+        at: Local::now(),
+        hook: hook.to_string(),
+        outcome,
+        duration_ms: started.elapsed().as_millis() as u64,
+        error,
+    };
+    if let Err(e) = record(&config, &event) {
+        eprintln!("[daily] Failed to record hook event: {}", e);
+    }
+    if outcome == HookOutcome::TimedOut {
+        // The runtime would wait for the stuck handler on shutdown
+        std::process::exit(0);
+    }
+    Ok(())
+}
+
+/// Append an event, dropping the oldest beyond [`MAX_EVENTS`]
+pub fn record(config: &Config, event: &HookEvent) -> Result<()> {
+    let path = events_path(config);
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(config))?;
+    lock.lock()?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    drop(file);
+
+    let content = fs::read_to_string(&path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() > MAX_EVENTS {
+        let kept = lines[lines.len() - MAX_EVENTS..].join("\n") + "\n";
+        write_atomic(&path, kept)?;
+    }
+    Ok(())
+}
+
+/// The last `limit` events, newest first
+pub fn recent(config: &Config, limit: usize) -> Vec<HookEvent> {
+    let Ok(content) = fs::read_to_string(events_path(config)) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_recent_hook_events() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        for i in 0..MAX_EVENTS + 5 {
+            let event = HookEvent {
+                at: Local::now(),
+                hook: format!("hook-{}", i),
+                outcome: if i % 2 == 0 {
+                    HookOutcome::Ok
+                } else {
+                    HookOutcome::Failed
+                },
+                duration_ms: 3,
+                error: (i % 2 == 1).then(|| "spawn failed".to_string()),
+            };
+            record(&config, &event).unwrap();
+        }
+
+        let content = fs::read_to_string(events_path(&config)).unwrap();
+        assert_eq!(content.lines().count(), MAX_EVENTS);
+        let recent = recent(&config, 2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].hook, format!("hook-{}", MAX_EVENTS + 4));
+        assert_eq!(recent[0].outcome, HookOutcome::Ok);
+        assert_eq!(recent[1].error.as_deref(), Some("spawn failed"));
+    }
+
+    #[test]
+    fn test_record_from_concurrent_hooks() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        std::thread::scope(|scope| {
+            for t in 0..4 {
+                let config = &config;
+                scope.spawn(move || {
+                    for i in 0..MAX_EVENTS {
+                        let event = HookEvent {
+                            at: Local::now(),
+                            hook: format!("hook-{}-{}", t, i),
+                            outcome: HookOutcome::Ok,
+                            duration_ms: 1,
+                            error: None,
+                        };
+                        record(config, &event).unwrap();
+                    }
+                });
+            }
+        });
+
+        let content = fs::read_to_string(events_path(&config)).unwrap();
+        assert_eq!(content.lines().count(), MAX_EVENTS);
+        assert!(content
+            .lines()
+            .all(|line| serde_json::from_str::<HookEvent>(line).is_ok()));
+    }
+}
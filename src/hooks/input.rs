@@ -3,6 +3,8 @@ use serde::Deserialize;
 use std::io::{self, Read};
 use std::path::PathBuf;
 
+use crate::config::{load_config_in, Config};
+
 /// Input data received from Claude Code hooks via stdin
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -15,6 +17,12 @@ pub struct HookInput {
     pub reason: Option<String>, // Only for SessionEnd: prompt_input_exit, logout, clear, other
     #[serde(default)]
     pub permission_mode: Option<String>,
+    #[serde(default)]
+    pub trigger: Option<String>, // Only for PreCompact: manual, auto
+    #[serde(default)]
+    pub prompt: Option<String>, // Only for UserPromptSubmit
+    #[serde(default)]
+    pub stop_hook_active: Option<bool>, // Only for Stop
 }
 
 /// Read hook input JSON from stdin
@@ -35,6 +43,13 @@ pub fn read_hook_input() -> Result<HookInput> {
     Ok(input)
 }
 
+/// Configuration for the session a hook reports: a `.daily.toml` in the
+/// session's project, rather than the hook process's directory, may route
+/// it to its own storage
+pub fn project_config(input: &HookInput) -> Result<Config> {
+    load_config_in(&input.cwd)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,4 +82,20 @@ mod tests {
         let input: HookInput = serde_json::from_str(json).unwrap();
         assert_eq!(input.reason, Some("user_exit".to_string()));
     }
+
+    #[test]
+    fn test_parse_pre_compact_input() {
+        let json = r#"{
+            "session_id": "abc123",
+            "transcript_path": "/home/user/.claude/projects/xyz/session.jsonl",
+            "cwd": "/home/user/project",
+            "hook_event_name": "PreCompact",
+            "trigger": "auto",
+            "custom_instructions": ""
+        }"#;
+
+        let input: HookInput = serde_json::from_str(json).unwrap();
+        assert_eq!(input.trigger, Some("auto".to_string()));
+        assert!(input.prompt.is_none());
+    }
 }
@@ -1,5 +1,10 @@
+pub mod activity;
+pub mod events;
 mod input;
+pub mod pre_compact;
 pub mod session_end;
 pub mod session_start;
+pub mod stop;
+pub mod user_prompt_submit;
 
-pub use input::read_hook_input;
+pub use input::{project_config, read_hook_input, HookInput};
@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::archive::paths::PathFilter;
+use crate::config::Config;
+use crate::hooks::{project_config, read_hook_input, HookInput};
+
+/// Handle PreCompact hook from Claude Code
+/// Snapshots the transcript into the archive so the full pre-compaction
+/// conversation is kept even after Claude Code summarizes its context
+pub async fn handle() -> Result<()> {
+    let input = read_hook_input()?;
+    let config = project_config(&input)?;
+
+    if !config.hooks.enable_pre_compact {
+        return Ok(());
+    }
+
+    if let Some(path) =
+        snapshot_transcript(&config, &input).context("Failed to snapshot transcript")?
+    {
+        eprintln!("[daily] Transcript snapshot saved: {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Copy the transcript to `{storage}/{date}/transcripts/{session_id}-precompact-{HHMMSS}.jsonl`
+fn snapshot_transcript(config: &Config, input: &HookInput) -> Result<Option<PathBuf>> {
+    if !input.transcript_path.exists() || !PathFilter::from_config(config).allows(Some(&input.cwd))
+    {
+        return Ok(None);
+    }
+
+    let now = Local::now();
+    let dir = config
+        .date_dir(&now.format("%Y-%m-%d").to_string())
+        .join("transcripts");
+    fs::create_dir_all(&dir).context("Failed to create transcripts directory")?;
+
+    let trigger = input.trigger.as_deref().unwrap_or("auto");
+    eprintln!(
+        "[daily] Context compaction ({}), archiving transcript",
+        trigger
+    );
+
+    let target = dir.join(format!(
+        "{}-precompact-{}.jsonl",
+        input.session_id,
+        now.format("%H%M%S")
+    ));
+    fs::copy(&input.transcript_path, &target).context("Failed to copy transcript")?;
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_transcript_copies_jsonl() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().join("archive");
+
+        let transcript = dir.path().join("abc.jsonl");
+        fs::write(&transcript, "{\"type\":\"user\"}\n").unwrap();
+
+        let input = HookInput {
+            session_id: "abc".to_string(),
+            transcript_path: transcript,
+            cwd: dir.path().to_path_buf(),
+            hook_event_name: "PreCompact".to_string(),
+            reason: None,
+            permission_mode: None,
+            trigger: Some("manual".to_string()),
+            prompt: None,
+            stop_hook_active: None,
+        };
+
+        let path = snapshot_transcript(&config, &input).unwrap().unwrap();
+        assert!(path.starts_with(config.storage_path()));
+        assert_eq!(fs::read_to_string(path).unwrap(), "{\"type\":\"user\"}\n");
+    }
+}
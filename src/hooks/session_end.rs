@@ -1,33 +1,29 @@
-use anyhow::Result;
-use std::process::{Command, Stdio};
-
-#[cfg(unix)]
-use std::os::unix::process::CommandExt;
-
-use crate::config::load_config;
-use crate::hooks::read_hook_input;
-use crate::jobs::{JobManager, JobType};
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use crate::archive::annotation::{self, Annotation};
+use crate::archive::paths::PathFilter;
+use crate::config::Config;
+use crate::hooks::{activity, project_config, read_hook_input, HookInput};
+use crate::jobs::{self, JobCommand, JobManager, JobType};
 use crate::transcript::TranscriptParser;
 
+/// How long a spawned job is watched for an immediate exit
+const SPAWN_CONFIRM: Duration = Duration::from_millis(200);
+
 /// Handle SessionEnd hook from Claude Code
 /// Spawns background process for summarization
 pub async fn handle() -> Result<()> {
-    let config = load_config()?;
+    let input = read_hook_input()?;
+    let config = project_config(&input)?;
 
     // Check if hooks are enabled
     if !config.hooks.enable_session_end {
         return Ok(());
     }
 
-    // Read hook input from stdin
-    let input = match read_hook_input() {
-        Ok(input) => input,
-        Err(e) => {
-            eprintln!("[daily] Failed to read hook input: {}", e);
-            return Ok(()); // Don't block session exit
-        }
-    };
-
     // Archive on all session end reasons to collect complete history
     // Reasons: "prompt_input_exit" (Ctrl+D), "logout", "clear", "other"
     eprintln!(
@@ -47,84 +43,122 @@ pub async fn handle() -> Result<()> {
     // Generate task name from working directory
     let task_name = generate_task_name(&input.cwd);
 
-    // Initialize job manager
-    let job_manager = match JobManager::new(&config) {
-        Ok(m) => m,
-        Err(e) => {
-            eprintln!("[daily] Failed to initialize job manager: {}", e);
-            return Ok(()); // Don't block session exit
-        }
-    };
+    // The session is over; its heartbeat is no longer needed
+    activity::remove(&config, &input.session_id);
 
-    // Generate job ID
-    let job_id = JobManager::generate_job_id(&task_name);
-    let transcript_path = input.transcript_path.to_string_lossy().to_string();
-
-    // Create log file for the job
-    let (stdout_file, stderr_file) = match job_manager.create_log_file(&job_id) {
-        Ok(f) => {
-            let f2 = f.try_clone().unwrap_or_else(|_| {
-                std::fs::File::create("/dev/null").expect("Failed to open /dev/null")
-            });
-            (Stdio::from(f), Stdio::from(f2))
+    // Asked before the job starts, so the summary finds the rating waiting
+    if config.hooks.ask_feedback {
+        if let Some(feedback) = ask_feedback() {
+            if let Err(e) = annotation::save_pending(&config, &input.session_id, &feedback) {
+                eprintln!("[daily] Failed to save feedback: {}", e);
+            }
         }
-        Err(_) => (Stdio::null(), Stdio::null()),
+    }
+
+    spawn_summarize_job(&config, &input, &task_name, JobType::SessionEnd, &[])?;
+
+    Ok(())
+}
+
+/// Ask for a star rating and comment on the controlling terminal. Hook
+/// stdin carries the event JSON, so the prompt goes through `/dev/tty`;
+/// without one (or on an empty answer) no feedback is recorded.
+fn ask_feedback() -> Option<Annotation> {
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let mut writer = tty.try_clone().ok()?;
+    let mut reader = BufReader::new(tty);
+    let mut ask = |prompt: &str| -> Option<String> {
+        write!(writer, "{}", prompt).ok()?;
+        writer.flush().ok()?;
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        Some(line.trim().to_string())
     };
 
-    // Get cwd as string for passing to summarize command
-    let cwd_str = input.cwd.to_string_lossy().to_string();
-
-    // Spawn background process for summarization
-    // This ensures Claude Code can exit immediately
-    let mut cmd = Command::new("daily");
-    cmd.args([
-        "summarize",
-        "--transcript",
-        &transcript_path,
-        "--task-name",
-        &task_name,
-        "--cwd",
-        &cwd_str,
-        "--job-id",
-        &job_id,
-        "--foreground",
-    ])
-    .stdin(Stdio::null())
-    .stdout(stdout_file)
-    .stderr(stderr_file);
-
-    // Create a new process group so Ctrl+C/Cmd+C doesn't kill the background process
-    #[cfg(unix)]
-    cmd.process_group(0);
-
-    match cmd.spawn() {
-        Ok(child) => {
-            // Register the job
-            if let Err(e) = job_manager.register(
-                &job_id,
-                child.id(),
-                &task_name,
-                &input.transcript_path,
-                JobType::SessionEnd,
-            ) {
-                eprintln!("[daily] Failed to register job: {}", e);
-            }
-            eprintln!(
-                "[daily] Background summarization started: {} (PID: {})",
-                job_id,
-                child.id()
-            );
-        }
-        Err(e) => {
-            eprintln!("[daily] Failed to spawn summarization process: {}", e);
-        }
+    let stars: u8 = ask("[daily] Rate this session 1-5 (Enter to skip): ")?
+        .parse()
+        .ok()
+        .filter(|s| (1..=5).contains(s))?;
+    let comment = ask("[daily] Comment (optional): ").filter(|c| !c.is_empty());
+    Some(Annotation {
+        stars: Some(stars),
+        note: comment,
+        ..Default::default()
+    })
+}
+
+/// Spawn a detached `daily summarize --foreground` job for the hook's transcript
+/// and return its ID (`None` when the project is excluded from archiving).
+/// The job is watched for [`SPAWN_CONFIRM`] so one that dies at once, e.g. on
+/// a bad install, is reported as a hook failure rather than lost.
+pub(crate) fn spawn_summarize_job(
+    config: &Config,
+    input: &HookInput,
+    task_name: &str,
+    job_type: JobType,
+    extra_args: &[&str],
+) -> Result<Option<String>> {
+    if !PathFilter::from_config(config).allows(Some(&input.cwd)) {
+        eprintln!(
+            "[daily] {} is excluded by archive.ignore_paths/include_paths, not archiving",
+            input.cwd.display()
+        );
+        return Ok(None);
+    }
+
+    let job_manager = JobManager::new(config).context("Failed to initialize job manager")?;
+    let mut command = JobCommand::new(
+        job_type,
+        task_name,
+        &input.transcript_path,
+        [
+            "summarize".to_string(),
+            "--transcript".to_string(),
+            input.transcript_path.to_string_lossy().into_owned(),
+            "--task-name".to_string(),
+            task_name.to_string(),
+            "--cwd".to_string(),
+            input.cwd.to_string_lossy().into_owned(),
+            "--foreground".to_string(),
+        ],
+    );
+    command
+        .args
+        .extend(extra_args.iter().map(|arg| arg.to_string()));
+    let (job_id, mut child) = jobs::spawn(config, command)?;
+
+    if let Err(e) = confirm_started(&mut child) {
+        let _ = job_manager.mark_failed(&job_id, &e.to_string());
+        return Err(e.context(format!("Job {} did not start", job_id)));
     }
+    eprintln!(
+        "[daily] Background summarization started: {} (PID: {})",
+        job_id,
+        child.id()
+    );
+    Ok(Some(job_id))
+}
 
+/// Poll a just-spawned job without blocking on it: an exit within
+/// [`SPAWN_CONFIRM`] is a failure, a job still running is fine
+fn confirm_started(child: &mut Child) -> Result<()> {
+    let deadline = Instant::now() + SPAWN_CONFIRM;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            anyhow::ensure!(status.success(), "Process exited immediately ({})", status);
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
     Ok(())
 }
 
 /// Check if the transcript file is empty or contains no user messages
-fn is_transcript_empty(transcript_path: &std::path::Path) -> bool {
+pub(crate) fn is_transcript_empty(transcript_path: &std::path::Path) -> bool {
     // If file doesn't exist, consider it empty
     if !transcript_path.exists() {
         return true;
@@ -141,7 +175,7 @@ fn is_transcript_empty(transcript_path: &std::path::Path) -> bool {
 }
 
 /// Generate a task name from the working directory
-fn generate_task_name(cwd: &std::path::Path) -> String {
+pub(crate) fn generate_task_name(cwd: &std::path::Path) -> String {
     // Extract project name from path
     let name = cwd
         .file_name()
@@ -1,87 +1,112 @@
 use anyhow::Result;
-use chrono::{Local, Timelike};
+use chrono::{DateTime, Local, Timelike};
 use std::fs;
-use std::process::{Command, Stdio};
 
+use crate::archive::atomic;
 use crate::archive::ArchiveManager;
+use crate::auto_summarize::in_quiet_hours;
+use crate::cli::commands::anomalies::spawn_anomalies_job;
+use crate::cli::commands::digest::spawn_digest_job;
+use crate::cli::commands::queue;
+use crate::cli::commands::topics::spawn_topics_job;
 use crate::config::load_config;
-use crate::hooks::read_hook_input;
+use crate::hooks::{project_config, read_hook_input};
+use crate::insights::anomalies;
+use crate::jobs;
+use crate::topics;
 
 /// Handle SessionStart hook from Claude Code
 /// Creates today's directory if it doesn't exist and initializes daily.md
 pub async fn handle() -> Result<()> {
-    let config = load_config()?;
+    // Try to read hook input, but don't fail if not available
+    // (allows manual testing without stdin)
+    let input = read_hook_input().ok();
+    let config = match &input {
+        Some(input) => project_config(input)?,
+        None => load_config()?,
+    };
 
     // Check if hooks are enabled
     if !config.hooks.enable_session_start {
         return Ok(());
     }
 
-    // Try to read hook input, but don't fail if not available
-    // (allows manual testing without stdin)
-    let _input = read_hook_input().ok();
+    // Clean up after a crash mid-write in an earlier run
+    atomic::recover_on_startup(&config);
 
-    let today = Local::now().format("%Y-%m-%d").to_string();
     let daily_dir = config.today_dir();
 
-    // Create today's directory if first session of the day
+    // Create today's directory and daily.md if first session of the day
     if !daily_dir.exists() {
-        fs::create_dir_all(&daily_dir)?;
-
-        // Initialize daily.md with frontmatter
-        let daily_md = daily_dir.join("daily.md");
-        let now = Local::now();
-        let content = format!(
-            r#"---
-date: {}
-created: {}
-updated: {}
-tags: [daily-summary, claude-code]
-sessions: []
-total_sessions: 0
----
-
-# Daily Summary - {}
-
-## Overview
-
-_No sessions archived yet._
-
-## Sessions
-
-## Key Insights
+        ArchiveManager::new(config.clone()).ensure_today_dir()?;
+        eprintln!("[daily] Created daily directory: {}", daily_dir.display());
+    }
 
-## Skills & Commands Identified
+    // Scheduled jobs wait out the auto-summarize quiet hours; they stay due
+    // until a session starts after them
+    let quiet = in_quiet_hours(&config, Local::now().time());
 
-## Reflections
+    // Check for auto-digest of previous day's sessions
+    if config.summarization.auto_digest_enabled && !quiet {
+        check_auto_digest(&config);
+    }
 
----
-*Generated by Daily Context Archive System*
-"#,
-            today,
-            now.to_rfc3339(),
-            now.to_rfc3339(),
-            today
-        );
-        fs::write(&daily_md, content)?;
+    // Refresh the topic pages once topics.interval_hours have passed
+    if config.topics.auto_update && !quiet && topics::is_due(&config) {
+        if let Err(e) = spawn_topics_job(&config, false) {
+            eprintln!("[daily] Failed to start topic update: {}", e);
+        }
+    }
 
-        eprintln!("[daily] Created daily directory: {}", daily_dir.display());
+    // Weekly anomaly check, notifying through the insights.anomaly webhook
+    if config.insights.anomaly_alerts
+        && !quiet
+        && anomalies::is_due(&config, chrono::Local::now().date_naive())
+    {
+        if let Err(e) = spawn_anomalies_job(&config, true) {
+            eprintln!("[daily] Failed to start anomaly check: {}", e);
+        }
     }
 
-    // Check for auto-digest of previous day's sessions
-    if config.summarization.auto_digest_enabled {
-        check_auto_digest(&config);
+    // Probe whether Claude is reachable again with the oldest queued session;
+    // once it gets through, the rest of the queue follows
+    if !quiet {
+        queue::retry_due(&config, Some(1));
     }
 
     // Check for pending skills to review
     check_pending_skills(&config);
 
+    // Drop finished jobs past jobs.retention
+    jobs::auto_prune(&config);
+
     // Exit with 0 to allow session to continue
     Ok(())
 }
 
+/// Failed auto-digests of a date after which it is left to `daily digest`
+const AUTO_DIGEST_MAX_FAILURES: usize = 3;
+
+/// Wait after a date's first failed auto-digest, doubled after each further
+/// failure, so a digest that keeps failing doesn't start a paid job on
+/// every check
+const AUTO_DIGEST_BACKOFF_MINUTES: i64 = 60;
+
+/// Whether a date whose earlier auto-digests failed at `failures` (oldest
+/// first) may be tried again at `now`
+fn auto_digest_retry_due(failures: &[DateTime<Local>], now: DateTime<Local>) -> bool {
+    let Some(last) = failures.last() else {
+        return true;
+    };
+    if failures.len() >= AUTO_DIGEST_MAX_FAILURES {
+        return false;
+    }
+    let backoff = AUTO_DIGEST_BACKOFF_MINUTES << (failures.len() - 1);
+    now - *last >= chrono::Duration::minutes(backoff)
+}
+
 /// Check if we should auto-digest yesterday's sessions
-fn check_auto_digest(config: &crate::config::Config) {
+pub(crate) fn check_auto_digest(config: &crate::config::Config) {
     // Parse digest_time (format: "HH:MM")
     let digest_time = &config.summarization.digest_time;
     let parts: Vec<&str> = digest_time.split(':').collect();
@@ -113,20 +138,21 @@ fn check_auto_digest(config: &crate::config::Config) {
     if !manager.has_sessions(&yesterday) {
         return;
     }
+    let failures = jobs::JobManager::new(config)
+        .and_then(|jobs| jobs.digest_failures(&yesterday))
+        .unwrap_or_default();
+    if !auto_digest_retry_due(&failures, now) {
+        return;
+    }
 
     eprintln!(
         "[daily] Auto-digesting yesterday's sessions ({})...",
         yesterday
     );
 
-    // Spawn background digest process
-    if let Ok(exe) = std::env::current_exe() {
-        let _ = Command::new(&exe)
-            .args(["digest", "--date", &yesterday, "--foreground"])
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn();
+    // Spawn background digest job
+    if let Err(e) = spawn_digest_job(config, &yesterday, false, &[]) {
+        eprintln!("[daily] Failed to start auto-digest: {}", e);
     }
 }
 
@@ -183,3 +209,23 @@ fn check_pending_skills(config: &crate::config::Config) {
     eprintln!("[daily] ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     eprintln!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_digest_retry_due() {
+        let now = Local::now();
+        let ago = |minutes: i64| now - chrono::Duration::minutes(minutes);
+
+        assert!(auto_digest_retry_due(&[], now));
+        assert!(!auto_digest_retry_due(&[ago(30)], now));
+        assert!(auto_digest_retry_due(&[ago(60)], now));
+        // The wait doubles after the second failure
+        assert!(!auto_digest_retry_due(&[ago(200), ago(90)], now));
+        assert!(auto_digest_retry_due(&[ago(200), ago(120)], now));
+        // and the date is given up after the third
+        assert!(!auto_digest_retry_due(&[ago(900), ago(600), ago(300)], now));
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use chrono::Local;
+
+use crate::hooks::session_end::{generate_task_name, is_transcript_empty, spawn_summarize_job};
+use crate::hooks::{activity, project_config, read_hook_input};
+use crate::jobs::JobType;
+
+/// Handle Stop hook from Claude Code
+/// Writes an incremental checkpoint summary of a long-running session, at most
+/// once per `hooks.checkpoint_interval_minutes`. The checkpoint archive is
+/// replaced by each newer checkpoint and removed by the final session summary.
+pub async fn handle() -> Result<()> {
+    let input = read_hook_input()?;
+    let config = project_config(&input)?;
+
+    if !config.hooks.enable_stop_checkpoint {
+        return Ok(());
+    }
+
+    let now = Local::now();
+    let mut state = activity::load(&config, &input.session_id).unwrap_or_default();
+    if !state.checkpoint_due(config.hooks.checkpoint_interval_minutes, now) {
+        return Ok(());
+    }
+
+    if is_transcript_empty(&input.transcript_path) {
+        return Ok(());
+    }
+
+    // Record the checkpoint before spawning so concurrent Stop events don't double up
+    state.transcript_path = input.transcript_path.clone();
+    state.cwd = input.cwd.clone();
+    state.last_checkpoint_at = Some(now);
+    if let Err(e) = activity::save(&config, &input.session_id, &state) {
+        eprintln!("[daily] Failed to record checkpoint: {}", e);
+        return Ok(());
+    }
+
+    eprintln!("[daily] Writing checkpoint summary");
+    let task_name = generate_task_name(&input.cwd);
+    spawn_summarize_job(
+        &config,
+        &input,
+        &task_name,
+        JobType::Checkpoint,
+        &["--checkpoint"],
+    )?;
+
+    Ok(())
+}
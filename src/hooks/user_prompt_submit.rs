@@ -0,0 +1,26 @@
+use anyhow::{Context, Result};
+
+use crate::hooks::{activity, project_config, read_hook_input};
+
+/// Handle UserPromptSubmit hook from Claude Code
+/// Records an activity heartbeat so inactivity detection doesn't rely on
+/// transcript mtimes alone. Must stay fast and write nothing to stdout,
+/// since stdout from this hook is added to the prompt context.
+pub async fn handle() -> Result<()> {
+    let input = read_hook_input()?;
+    let config = project_config(&input)?;
+
+    if !config.hooks.enable_user_prompt_submit {
+        return Ok(());
+    }
+
+    activity::record_prompt(
+        &config,
+        &input.session_id,
+        &input.transcript_path,
+        &input.cwd,
+    )
+    .context("Failed to record activity")?;
+
+    Ok(())
+}
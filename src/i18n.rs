@@ -0,0 +1,240 @@
+//! Messages of the CLI in the user's language.
+//!
+//! The language is `output.ui_language`, or `summarization.summary_language`
+//! when that isn't set, so a user reading Chinese digests also gets Chinese
+//! terminal output. Archives keep their English section headings, which
+//! the parsers rely on; [`localize_headings`] translates them for display.
+
+use std::sync::OnceLock;
+
+/// A language the CLI has messages for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Zh,
+}
+
+impl Lang {
+    /// Language for a code such as "zh" or "zh-CN"; English for anything
+    /// without a catalog
+    pub fn parse(code: &str) -> Self {
+        if code.trim().to_lowercase().starts_with("zh") {
+            Lang::Zh
+        } else {
+            Lang::En
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+        }
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Language of this run's output, looked up from the config the first time
+/// a message is needed, so commands that print none (hooks) never load it
+pub fn lang() -> Lang {
+    *LANG.get_or_init(|| {
+        // Only an existing config picks the language; loading would create one
+        crate::config::get_config_path()
+            .ok()
+            .filter(|path| path.exists())
+            .and_then(|_| crate::config::load_config().ok())
+            .map(|config| config.ui_language())
+            .unwrap_or(Lang::En)
+    })
+}
+
+/// `(key, English, Chinese)`; `{name}` placeholders are filled by [`fmt`]
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("error", "Error", "错误"),
+    ("cancelled", "Cancelled.", "已取消。"),
+    // daily view
+    ("view.no_archives", "No archives found.", "没有找到归档。"),
+    ("view.select_date", "Select a date to view", "选择要查看的日期"),
+    ("view.session_count", "{count} sessions", "{count} 个会话"),
+    ("view.today", "(today)", "（今天）"),
+    ("view.yesterday", "(yesterday)", "（昨天）"),
+    (
+        "view.no_archives_range",
+        "No archives found from {from} to {to}.",
+        "{from} 至 {to} 没有找到归档。",
+    ),
+    (
+        "view.range_total",
+        "{from} to {to}: {days} day(s) archived, {sessions} session(s), {cost} of Claude Code usage",
+        "{from} 至 {to}：归档 {days} 天，{sessions} 个会话，Claude Code 用量 {cost}",
+    ),
+    (
+        "view.hint",
+        "Use {command} to see a specific session",
+        "使用 {command} 查看具体会话",
+    ),
+    (
+        "view.no_sessions_for",
+        "No sessions found for {date}",
+        "{date} 没有找到会话",
+    ),
+    ("view.sessions_for", "Sessions for {date}:", "{date} 的会话："),
+    ("view.total_sessions", "Total: {count} sessions", "共 {count} 个会话"),
+    ("view.daily_summary", "Daily Summary - {date}", "每日总结 - {date}"),
+    (
+        "view.no_summary",
+        "No daily summary found for {date}",
+        "{date} 没有找到每日总结",
+    ),
+    ("view.daily_archive", "Daily Archive - {date}", "每日归档 - {date}"),
+    ("view.no_sessions_yet", "No sessions archived yet.", "还没有归档的会话。"),
+    ("view.sessions", "Sessions:", "会话："),
+    // daily jobs list
+    ("jobs.none", "No jobs found.", "没有找到任务。"),
+    (
+        "jobs.none_running",
+        "No running jobs. Use --all to see completed jobs.",
+        "没有正在运行的任务。使用 --all 查看已完成的任务。",
+    ),
+    ("jobs.id", "ID", "ID"),
+    ("jobs.status", "STATUS", "状态"),
+    ("jobs.task", "TASK", "任务"),
+    ("jobs.elapsed", "ELAPSED", "耗时"),
+    ("jobs.cost", "COST", "费用"),
+    ("jobs.waiting", "Waiting", "等待中"),
+    ("jobs.queued", "Queued", "排队中"),
+    ("jobs.running", "Running", "运行中"),
+    ("jobs.low_quality", "Low qual.", "质量低"),
+    ("jobs.completed", "Completed", "已完成"),
+    ("jobs.failed", "Failed", "失败"),
+    ("jobs.timed_out", "Timed out", "超时"),
+    (
+        "jobs.overhead",
+        "Overhead: {cost} over {jobs} jobs ({input} in / {output} out tokens)",
+        "开销：{jobs} 个任务共 {cost}（输入 {input} / 输出 {output} tokens）",
+    ),
+    // daily insights
+    (
+        "insights.title",
+        "Daily Insights (last {days} days)",
+        "每日洞察（最近 {days} 天）",
+    ),
+    ("insights.overview", "Overview:", "概览："),
+    (
+        "insights.overview_counts",
+        "{days} days, {sessions} sessions",
+        "{days} 天，{sessions} 个会话",
+    ),
+    ("insights.timeline", "Activity Timeline:", "活动时间线："),
+    ("insights.streaks", "Streaks:", "连续记录："),
+    (
+        "insights.streak_counts",
+        "{current} current, {longest} longest, {active} active days this year",
+        "当前 {current}，最长 {longest}，今年活跃 {active} 天",
+    ),
+    ("insights.work_patterns", "Work Patterns:", "工作模式："),
+    ("insights.goal_distribution", "Goal Distribution:", "目标分布："),
+    ("insights.tracked_goals", "Tracked Goals:", "跟踪的目标："),
+    ("insights.friction", "Friction Points:", "阻碍点："),
+    ("insights.satisfaction", "Satisfaction:", "满意度："),
+    ("insights.languages", "Languages:", "语言："),
+    ("insights.tool_usage", "Tool Usage:", "工具使用："),
+];
+
+/// Digest section headings and their Chinese titles
+const SECTIONS: &[(&str, &str)] = &[
+    ("Overview", "概述"),
+    ("Key Work", "主要工作"),
+    ("Key Insights", "关键见解"),
+    ("Reflections", "反思"),
+    ("Tomorrow's Focus", "明日重点"),
+    ("Goal Progress", "目标进展"),
+    ("Skills & Commands Identified", "识别出的技能与命令"),
+    ("Potential Skills", "潜在技能"),
+    ("Potential Commands", "潜在命令"),
+    ("Sessions", "会话"),
+    ("Notes", "笔记"),
+];
+
+/// `key` in `lang`; the key itself when it has no message
+pub fn message(lang: Lang, key: &'static str) -> &'static str {
+    MESSAGES
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, en, zh)| match lang {
+            Lang::En => *en,
+            Lang::Zh => *zh,
+        })
+        .unwrap_or(key)
+}
+
+/// `key` in the output language
+pub fn t(key: &'static str) -> &'static str {
+    message(lang(), key)
+}
+
+/// `key` in the output language with its `{name}` placeholders filled
+pub fn fmt(key: &'static str, vars: &[(&str, &dyn std::fmt::Display)]) -> String {
+    vars.iter().fold(t(key).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// A digest's markdown with its `##`/`###` section headings in `lang`
+pub fn localize_headings(markdown: &str, lang: Lang) -> String {
+    if lang == Lang::En {
+        return markdown.to_string();
+    }
+    markdown
+        .lines()
+        .map(|line| {
+            let (hashes, title) = match line.split_once(' ') {
+                Some((hashes, title)) if hashes == "##" || hashes == "###" => (hashes, title),
+                _ => return line.to_string(),
+            };
+            match SECTIONS.iter().find(|(en, _)| *en == title.trim()) {
+                Some((_, zh)) => format!("{} {}", hashes, zh),
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_is_complete() {
+        for (i, (key, en, zh)) in MESSAGES.iter().enumerate() {
+            assert!(
+                !en.is_empty() && !zh.is_empty(),
+                "{} is missing a message",
+                key
+            );
+            assert!(
+                MESSAGES[..i].iter().all(|(k, _, _)| k != key),
+                "{} is defined twice",
+                key
+            );
+        }
+        assert_eq!(message(Lang::Zh, "view.sessions"), "会话：");
+        assert_eq!(message(Lang::En, "view.sessions"), "Sessions:");
+        assert_eq!(message(Lang::Zh, "no.such.key"), "no.such.key");
+        assert_eq!(Lang::parse("zh-CN"), Lang::Zh);
+        assert_eq!(Lang::parse("ja"), Lang::En);
+    }
+
+    #[test]
+    fn test_localize_headings() {
+        let digest = "# Daily Summary - 2024-01-15\n\n## Overview\n\nBusy.\n\n## Tomorrow's Focus\n\n### Overview of plans\n";
+        let zh = localize_headings(digest, Lang::Zh);
+        assert!(zh.contains("## 概述\n\nBusy."));
+        assert!(zh.contains("## 明日重点"));
+        // Card titles that only start like a section stay as written
+        assert!(zh.contains("### Overview of plans"));
+        assert_eq!(localize_headings(digest, Lang::En), digest);
+    }
+}
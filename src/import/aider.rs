@@ -0,0 +1,148 @@
+//! Aider's `.aider.chat.history.md`, kept in the project root. Each run
+//! starts with `# aider chat started at <time>`, user messages are `####`
+//! lines and aider's own output is quoted with `>`.
+
+use chrono::NaiveDateTime;
+use std::path::Path;
+
+use super::{add_file, modified_at, push_message, ImportedSession, Source};
+use crate::transcript::TranscriptData;
+
+pub const HISTORY_FILE: &str = ".aider.chat.history.md";
+
+const SESSION_START: &str = "# aider chat started at ";
+
+pub fn parse(path: &Path, content: &str) -> Vec<ImportedSession> {
+    let cwd = path
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.parent().map(|dir| dir.to_string_lossy().into_owned()));
+
+    let mut sessions = Vec::new();
+    let mut current: Option<(NaiveDateTime, Conversation)> = None;
+    for line in content.lines() {
+        if let Some(time) = line.strip_prefix(SESSION_START) {
+            if let Some((started_at, conversation)) = current.take() {
+                sessions.push(session(path, &cwd, started_at, conversation.finish()));
+            }
+            let started_at = NaiveDateTime::parse_from_str(time.trim(), "%Y-%m-%d %H:%M:%S")
+                .unwrap_or_else(|_| modified_at(path));
+            current = Some((started_at, Conversation::default()));
+            continue;
+        }
+        // Text before the first header still belongs to some session
+        let (_, conversation) =
+            current.get_or_insert_with(|| (modified_at(path), Conversation::default()));
+        conversation.line(line);
+    }
+    if let Some((started_at, conversation)) = current {
+        sessions.push(session(path, &cwd, started_at, conversation.finish()));
+    }
+    sessions
+}
+
+fn session(
+    path: &Path,
+    cwd: &Option<String>,
+    started_at: NaiveDateTime,
+    data: TranscriptData,
+) -> ImportedSession {
+    ImportedSession {
+        source: Source::Aider,
+        session_id: format!("aider-{}", started_at.format("%Y%m%d-%H%M%S")),
+        started_at,
+        cwd: cwd.clone(),
+        path: path.to_path_buf(),
+        data,
+    }
+}
+
+/// Messages of one run, collected line by line
+#[derive(Default)]
+struct Conversation {
+    data: TranscriptData,
+    user: Vec<String>,
+    assistant: Vec<String>,
+}
+
+impl Conversation {
+    fn line(&mut self, line: &str) {
+        if let Some(text) = line.strip_prefix("####") {
+            self.flush_assistant();
+            self.user.push(text.trim().to_string());
+        } else if let Some(output) = line.strip_prefix('>') {
+            self.flush_user();
+            if let Some(file) = output.trim().strip_prefix("Applied edit to ") {
+                add_file(&mut self.data, file);
+            }
+        } else {
+            self.flush_user();
+            self.assistant.push(line.to_string());
+        }
+    }
+
+    fn flush_user(&mut self) {
+        if !self.user.is_empty() {
+            push_message(&mut self.data.user_messages, &self.user.join("\n"));
+            self.user.clear();
+        }
+    }
+
+    fn flush_assistant(&mut self) {
+        if !self.assistant.is_empty() {
+            push_message(
+                &mut self.data.assistant_messages,
+                &self.assistant.join("\n"),
+            );
+            self.assistant.clear();
+        }
+    }
+
+    fn finish(mut self) -> TranscriptData {
+        self.flush_user();
+        self.flush_assistant();
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aider_history() {
+        let history = "\
+# aider chat started at 2024-01-15 10:23:45
+
+> /usr/local/bin/aider --model sonnet
+> Aider v0.50.0
+
+#### fix the login bug
+#### it fails on empty passwords
+
+The check runs before trimming. Here is the fix:
+
+> Applied edit to src/auth.py
+> Commit 1a2b3c4 fix: reject empty passwords
+
+# aider chat started at 2024-01-15 14:00:00
+
+> Aider v0.50.0
+";
+        let sessions = parse(Path::new("/work/app/.aider.chat.history.md"), history);
+        assert_eq!(sessions.len(), 2);
+
+        let first = &sessions[0];
+        assert_eq!(first.session_id, "aider-20240115-102345");
+        assert_eq!(
+            first.data.user_messages,
+            vec!["fix the login bug\nit fails on empty passwords"]
+        );
+        assert_eq!(
+            first.data.assistant_messages,
+            vec!["The check runs before trimming. Here is the fix:"]
+        );
+        assert_eq!(first.data.files_modified, vec!["src/auth.py"]);
+        assert!(sessions[1].data.is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! OpenAI Codex CLI rollouts, `~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl`.
+//! Current versions wrap each record as `{"type": ..., "payload": ...}`
+//! after a `session_meta` line; older ones write the items directly after a
+//! `{"id", "timestamp"}` header.
+
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use std::path::Path;
+
+use super::{add_file, modified_at, push_message, ImportedSession, Source};
+use crate::transcript::{ToolCall, TranscriptData};
+
+/// Context Codex injects as user messages
+const INJECTED_PREFIXES: [&str; 3] = [
+    "<environment_context>",
+    "<user_instructions>",
+    "# AGENTS.md",
+];
+
+pub fn parse(path: &Path, content: &str) -> Vec<ImportedSession> {
+    let mut data = TranscriptData::default();
+    let mut session_id = None;
+    let mut cwd = None;
+    let mut started_at = None;
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let kind = record.get("type").and_then(Value::as_str);
+        let item = record.get("payload").unwrap_or(&record);
+
+        if kind == Some("session_meta") || (kind.is_none() && item.get("id").is_some()) {
+            session_id = session_id.or_else(|| str_field(item, "id"));
+            started_at = started_at.or_else(|| str_field(item, "timestamp"));
+        }
+        if started_at.is_none() {
+            started_at = str_field(&record, "timestamp");
+        }
+        if cwd.is_none() {
+            cwd = str_field(item, "cwd");
+        }
+
+        match item.get("type").and_then(Value::as_str) {
+            Some("message") => {
+                let text = message_text(item);
+                match item.get("role").and_then(Value::as_str) {
+                    Some("user") if !INJECTED_PREFIXES.iter().any(|p| text.starts_with(p)) => {
+                        push_message(&mut data.user_messages, &text)
+                    }
+                    Some("assistant") => push_message(&mut data.assistant_messages, &text),
+                    _ => {}
+                }
+            }
+            Some("function_call") | Some("custom_tool_call") => {
+                let name = str_field(item, "name").unwrap_or_else(|| "tool".to_string());
+                // `arguments` is a JSON string; custom tools send raw `input`
+                let raw = str_field(item, "arguments")
+                    .or_else(|| str_field(item, "input"))
+                    .unwrap_or_default();
+                patched_files(&raw, &mut data);
+                data.tool_calls.push(ToolCall {
+                    name,
+                    input: serde_json::from_str(&raw).unwrap_or(Value::String(raw)),
+                    response: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let started_at = started_at
+        .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+        .map(|at| at.with_timezone(&Local).naive_local())
+        .unwrap_or_else(|| modified_at(path));
+    let session_id = session_id.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    vec![ImportedSession {
+        source: Source::Codex,
+        session_id: format!("codex-{}", session_id),
+        started_at,
+        cwd,
+        path: path.to_path_buf(),
+        data,
+    }]
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Text blocks (`input_text`, `output_text`, `text`) of a message item
+fn message_text(item: &Value) -> String {
+    match item.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Files named in an `apply_patch` envelope
+fn patched_files(patch: &str, data: &mut TranscriptData) {
+    // Inside JSON arguments the patch's newlines are still escaped
+    for line in patch.split("\\n").flat_map(str::lines) {
+        for marker in ["*** Update File: ", "*** Add File: ", "*** Delete File: "] {
+            if let Some(file) = line.trim().strip_prefix(marker) {
+                add_file(data, file);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codex_rollout() {
+        let rollout = r#"{"timestamp":"2025-09-01T08:00:00.000Z","type":"session_meta","payload":{"id":"0199-abc","timestamp":"2025-09-01T08:00:00.000Z","cwd":"/work/api"}}
+{"timestamp":"2025-09-01T08:00:01.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>\n  <cwd>/work/api</cwd>\n</environment_context>"}]}}
+{"timestamp":"2025-09-01T08:00:02.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"add a health endpoint"}]}}
+{"timestamp":"2025-09-01T08:00:05.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"apply_patch\",\"*** Begin Patch\\n*** Add File: src/health.rs\\n+pub fn ok() {}\\n*** End Patch\"]}"}}
+{"timestamp":"2025-09-01T08:00:09.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Added GET /health."}]}}"#;
+        let sessions = parse(Path::new("rollout-2025-09-01.jsonl"), rollout);
+        let session = &sessions[0];
+        assert_eq!(session.session_id, "codex-0199-abc");
+        assert_eq!(session.cwd.as_deref(), Some("/work/api"));
+        assert_eq!(session.data.user_messages, vec!["add a health endpoint"]);
+        assert_eq!(session.data.assistant_messages, vec!["Added GET /health."]);
+        assert_eq!(session.data.tool_calls[0].name, "shell");
+        assert_eq!(session.data.files_modified, vec!["src/health.rs"]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Cursor chats exported to Markdown, either with Cursor's "Export Chat"
+//! (`**User**` / `**Cursor**` turns) or by the SpecStory extension
+//! (`_**User**_` / `_**Assistant**_`, saved under `.specstory/history/`).
+//! Turns are separated by `---` lines. Cursor's own SQLite store is not read.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::path::Path;
+
+use super::{modified_at, push_message, ImportedSession, Source};
+use crate::transcript::TranscriptData;
+
+pub fn parse(path: &Path, content: &str) -> Vec<ImportedSession> {
+    let mut data = TranscriptData::default();
+    for block in content.split("\n---\n") {
+        // The speaker line opens the turn; anything above it is the header
+        let lines: Vec<&str> = block.lines().collect();
+        let Some((start, speaker)) = lines
+            .iter()
+            .enumerate()
+            .find_map(|(i, line)| speaker(line).map(|speaker| (i, speaker)))
+        else {
+            continue;
+        };
+        let text = lines[start + 1..].join("\n");
+        match speaker {
+            "User" => push_message(&mut data.user_messages, &text),
+            _ => push_message(&mut data.assistant_messages, &text),
+        }
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    vec![ImportedSession {
+        source: Source::Cursor,
+        session_id: format!("cursor-{}", stem),
+        started_at: started_at(content).unwrap_or_else(|| modified_at(path)),
+        cwd: project_dir(path),
+        path: path.to_path_buf(),
+        data,
+    }]
+}
+
+/// Who speaks in a `**User**`, `_**Assistant**_`, ... line
+fn speaker(line: &str) -> Option<&'static str> {
+    let line = line.trim();
+    if !line.starts_with("**") && !line.starts_with("_**") {
+        return None;
+    }
+    match line.trim_matches(|c| c == '*' || c == '_') {
+        "User" => Some("User"),
+        "Cursor" | "Assistant" => Some("Assistant"),
+        _ => None,
+    }
+}
+
+/// Time in the export header: `_Exported on 5/30/2025 at 10:00:00 ..._`
+/// from Cursor, `# Title (2025-05-30 10:00Z)` from SpecStory
+fn started_at(content: &str) -> Option<NaiveDateTime> {
+    let header = content.split("\n---\n").next()?;
+    if let Some(rest) = header.split("Exported on ").nth(1) {
+        let mut words = rest.split_whitespace();
+        let date = NaiveDate::parse_from_str(words.next()?, "%m/%d/%Y").ok()?;
+        let time = words
+            .nth(1)
+            .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M:%S").ok())
+            .unwrap_or_default();
+        return Some(date.and_time(time));
+    }
+    let (_, stamp) = header
+        .lines()
+        .find(|l| l.starts_with("# "))?
+        .rsplit_once('(')?;
+    let stamp = stamp.trim_end_matches(')').trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M").ok()
+}
+
+/// Project a SpecStory export belongs to: the folder holding `.specstory/`
+fn project_dir(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    path.ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == ".specstory"))
+        .and_then(Path::parent)
+        .map(|dir| dir.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cursor_export() {
+        let export = "\
+# Fix flaky test
+_Exported on 5/30/2025 at 10:15:00 GMT+2 from Cursor (0.50.5)_
+
+---
+
+**User**
+
+why does test_login fail on CI?
+
+---
+
+**Cursor**
+
+It depends on the local timezone.
+
+---
+
+**User**
+
+fix it
+";
+        let sessions = parse(Path::new("cursor_fix_flaky_test.md"), export);
+        let session = &sessions[0];
+        assert_eq!(session.session_id, "cursor-cursor_fix_flaky_test");
+        assert_eq!(session.started_at.to_string(), "2025-05-30 10:15:00");
+        assert_eq!(
+            session.data.user_messages,
+            vec!["why does test_login fail on CI?", "fix it"]
+        );
+        assert_eq!(session.data.assistant_messages.len(), 1);
+
+        let specstory = "<!-- Generated by SpecStory -->\n\n# Flaky test (2025-05-30 08:15Z)\n\n_**User**_\n\nhi\n";
+        assert_eq!(
+            started_at(specstory).unwrap().to_string(),
+            "2025-05-30 08:15:00"
+        );
+        let sessions = parse(Path::new("2025-05-30_08-15-flaky-test.md"), specstory);
+        assert_eq!(sessions[0].data.user_messages, vec!["hi"]);
+    }
+}
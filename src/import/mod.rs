@@ -0,0 +1,149 @@
+//! Importers for other AI coding tools' session logs.
+//!
+//! Each adapter turns a tool's chat log into [`ImportedSession`]s carrying
+//! the same [`TranscriptData`] the Claude Code parser produces, so
+//! `daily import` can summarize them into regular session archives.
+
+mod aider;
+mod codex;
+mod cursor;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::transcript::TranscriptData;
+
+/// Tool a log comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Cursor,
+    Aider,
+    Codex,
+}
+
+impl Source {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "cursor" => Ok(Self::Cursor),
+            "aider" => Ok(Self::Aider),
+            "codex" => Ok(Self::Codex),
+            _ => anyhow::bail!(
+                "Unknown source '{}'. Use one of: cursor, aider, codex",
+                name
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cursor => "cursor",
+            Self::Aider => "aider",
+            Self::Codex => "codex",
+        }
+    }
+
+    /// Whether a file found while walking a directory is one of this tool's logs
+    fn matches(&self, path: &Path) -> bool {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        match self {
+            Self::Aider => name == aider::HISTORY_FILE,
+            Self::Codex => name.ends_with(".jsonl"),
+            Self::Cursor => name.ends_with(".md"),
+        }
+    }
+}
+
+/// One conversation read from another tool's log
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub source: Source,
+    /// Stable id, so importing the same log twice can be detected
+    pub session_id: String,
+    /// Local time the conversation started; decides the archive date
+    pub started_at: NaiveDateTime,
+    /// Project directory, when the log records it
+    pub cwd: Option<String>,
+    /// Log file the session was read from
+    pub path: PathBuf,
+    pub data: TranscriptData,
+}
+
+/// Read every session of `source` under `path` (a log file or a directory
+/// searched recursively), oldest first. Empty conversations are dropped.
+pub fn read(source: Source, path: &Path) -> Result<Vec<ImportedSession>> {
+    if !path.exists() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect(source, path, &mut files);
+        files.sort();
+    } else {
+        files.push(path.to_path_buf());
+    }
+
+    let mut sessions = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let parsed = match source {
+            Source::Aider => aider::parse(&file, &content),
+            Source::Codex => codex::parse(&file, &content),
+            Source::Cursor => cursor::parse(&file, &content),
+        };
+        sessions.extend(parsed.into_iter().filter(|s| !s.data.is_empty()));
+    }
+    sessions.sort_by_key(|s| s.started_at);
+    Ok(sessions)
+}
+
+fn collect(source: Source, dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => {
+                // Skip .git, .venv and the like, but not SpecStory's
+                // export folder for Cursor chats
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if !name.starts_with('.') || name == ".specstory" {
+                    collect(source, &path, files);
+                }
+            }
+            Ok(kind) if kind.is_file() && source.matches(&path) => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Last modification of `path` as local time, for logs without timestamps
+fn modified_at(path: &Path) -> NaiveDateTime {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map(|modified| DateTime::<Local>::from(modified).naive_local())
+        .unwrap_or_else(|_| Local::now().naive_local())
+}
+
+/// Record a file the assistant changed, once
+fn add_file(data: &mut TranscriptData, file: &str) {
+    let file = file.trim();
+    if !file.is_empty() && !data.files_modified.iter().any(|f| f == file) {
+        data.files_modified.push(file.to_string());
+    }
+}
+
+/// Add a message unless it is blank
+fn push_message(messages: &mut Vec<String>, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        messages.push(text.to_string());
+    }
+}
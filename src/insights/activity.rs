@@ -0,0 +1,188 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+
+/// Number of calendar days covered by the heatmap (ending today)
+pub const HEATMAP_DAYS: i64 = 365;
+
+/// Calendar activity across the whole archive: heatmap plus streaks
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityData {
+    /// One entry per calendar day, oldest first, including inactive days
+    pub heatmap: Vec<HeatmapDay>,
+    /// Consecutive active days ending today (or yesterday if today has no sessions yet)
+    pub current_streak: usize,
+    /// Longest run of consecutive active days in the archive
+    pub longest_streak: usize,
+    /// Number of days with at least one session within the heatmap window
+    pub active_days: usize,
+}
+
+/// Session count for a single calendar day
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapDay {
+    pub date: String,
+    pub session_count: usize,
+}
+
+impl ActivityData {
+    /// Build the heatmap and streaks from every archived date
+    pub fn calculate(config: &Config, today: NaiveDate) -> Self {
+        let manager = ArchiveManager::new(config.clone());
+        let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+
+        for date in manager.list_dates().unwrap_or_default() {
+            if let Ok(day) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                let count = manager.list_sessions(&date).map(|s| s.len()).unwrap_or(0);
+                counts.insert(day, count);
+            }
+        }
+
+        Self::from_counts(&counts, today)
+    }
+
+    /// Build activity data from per-day session counts
+    fn from_counts(counts: &HashMap<NaiveDate, usize>, today: NaiveDate) -> Self {
+        let start = today - Duration::days(HEATMAP_DAYS - 1);
+        let heatmap: Vec<HeatmapDay> = (0..HEATMAP_DAYS)
+            .map(|offset| {
+                let day = start + Duration::days(offset);
+                HeatmapDay {
+                    date: day.format("%Y-%m-%d").to_string(),
+                    session_count: counts.get(&day).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+        let active_days = heatmap.iter().filter(|d| d.session_count > 0).count();
+
+        let is_active = |day: NaiveDate| counts.get(&day).is_some_and(|c| *c > 0);
+
+        // Today may not have sessions yet, so a streak ending yesterday still counts
+        let mut cursor = if is_active(today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut current_streak = 0;
+        while is_active(cursor) {
+            current_streak += 1;
+            cursor -= Duration::days(1);
+        }
+
+        let mut active: Vec<NaiveDate> = counts
+            .iter()
+            .filter(|(_, c)| **c > 0)
+            .map(|(d, _)| *d)
+            .collect();
+        active.sort();
+        let mut longest_streak = 0;
+        let mut run = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for day in active {
+            run = match prev {
+                Some(p) if day - p == Duration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest_streak = longest_streak.max(run);
+            prev = Some(day);
+        }
+
+        Self {
+            heatmap,
+            current_streak,
+            longest_streak,
+            active_days,
+        }
+    }
+
+    /// Group heatmap days into Monday-first week columns for grid rendering.
+    /// Days before the first Monday are padded with `None`.
+    pub fn weeks(&self) -> Vec<[Option<&HeatmapDay>; 7]> {
+        let mut weeks: Vec<[Option<&HeatmapDay>; 7]> = Vec::new();
+        for day in &self.heatmap {
+            let Ok(date) = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d") else {
+                continue;
+            };
+            let weekday = date.weekday().num_days_from_monday() as usize;
+            if weeks.is_empty() || weekday == 0 {
+                weeks.push([None; 7]);
+            }
+            if let Some(week) = weeks.last_mut() {
+                week[weekday] = Some(day);
+            }
+        }
+        weeks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_heatmap_covers_window() {
+        let counts = HashMap::from([(day("2026-03-10"), 2)]);
+        let data = ActivityData::from_counts(&counts, day("2026-03-10"));
+        assert_eq!(data.heatmap.len(), HEATMAP_DAYS as usize);
+        assert_eq!(data.heatmap.last().unwrap().date, "2026-03-10");
+        assert_eq!(data.heatmap.last().unwrap().session_count, 2);
+        assert_eq!(data.active_days, 1);
+    }
+
+    #[test]
+    fn test_streaks() {
+        let counts = HashMap::from([
+            (day("2026-03-01"), 1),
+            (day("2026-03-02"), 3),
+            (day("2026-03-03"), 1),
+            (day("2026-03-08"), 1),
+            (day("2026-03-09"), 2),
+        ]);
+        let data = ActivityData::from_counts(&counts, day("2026-03-09"));
+        assert_eq!(data.current_streak, 2);
+        assert_eq!(data.longest_streak, 3);
+    }
+
+    #[test]
+    fn test_current_streak_allows_inactive_today() {
+        let counts = HashMap::from([(day("2026-03-08"), 1), (day("2026-03-09"), 1)]);
+        let data = ActivityData::from_counts(&counts, day("2026-03-10"));
+        assert_eq!(data.current_streak, 2);
+
+        let data = ActivityData::from_counts(&counts, day("2026-03-12"));
+        assert_eq!(data.current_streak, 0);
+    }
+
+    #[test]
+    fn test_zero_count_days_break_streaks() {
+        let counts = HashMap::from([
+            (day("2026-03-01"), 1),
+            (day("2026-03-02"), 0),
+            (day("2026-03-03"), 1),
+        ]);
+        let data = ActivityData::from_counts(&counts, day("2026-03-03"));
+        assert_eq!(data.longest_streak, 1);
+        assert_eq!(data.current_streak, 1);
+    }
+
+    #[test]
+    fn test_weeks_are_monday_first() {
+        let data = ActivityData::from_counts(&HashMap::new(), day("2026-03-11"));
+        let weeks = data.weeks();
+        // 2026-03-11 is a Wednesday
+        let last = weeks.last().unwrap();
+        assert_eq!(last[2].unwrap().date, "2026-03-11");
+        assert!(last[3].is_none());
+        assert_eq!(
+            weeks.iter().flatten().filter(|d| d.is_some()).count(),
+            HEATMAP_DAYS as usize
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Week-over-baseline anomaly detection.
+//!
+//! The last seven days are compared with the average of the
+//! `insights.baseline_weeks` seven-day windows before them: cost from
+//! Claude Code usage, friction and success rates from session facets (using
+//! the same rates as `TrendData`). A metric moving the wrong way by
+//! `insights.anomaly_threshold_pct` or more is an anomaly.
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::archive::annotation::Annotation;
+use crate::archive::paths::PathFilter;
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
+
+use super::collector::extract_frontmatter_field;
+use super::facets::SessionFacet;
+use super::trends::{calc_friction_rate, calc_success_rate, pct_change};
+
+/// Sessions with facets needed in the current week before rates are compared
+const MIN_RATED_SESSIONS: usize = 3;
+
+/// Date of the last anomaly check with notifications, for weekly scheduling
+const CHECKED_MARKER: &str = ".anomalies_checked";
+
+/// This week's metrics against the trailing baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyReport {
+    /// First and last date of the current week (YYYY-MM-DD)
+    pub from: String,
+    pub to: String,
+    pub baseline_weeks: u32,
+    pub metrics: Vec<MetricChange>,
+}
+
+/// One metric this week compared with its weekly baseline average
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricChange {
+    /// "cost", "friction_rate" or "success_rate"
+    pub metric: String,
+    pub current: f64,
+    pub baseline: f64,
+    pub change_pct: f64,
+    pub anomalous: bool,
+    /// Project behind a cost rise, or the most common friction type
+    pub driver: Option<String>,
+    /// e.g. "cost up 80% this week, driven by project X"
+    pub message: String,
+}
+
+impl AnomalyReport {
+    pub fn anomalies(&self) -> impl Iterator<Item = &MetricChange> {
+        self.metrics.iter().filter(|m| m.anomalous)
+    }
+}
+
+/// Totals for one seven-day window
+#[derive(Debug, Default)]
+struct Window {
+    cost: f64,
+    cost_by_project: HashMap<String, f64>,
+    facets: Vec<SessionFacet>,
+}
+
+impl Window {
+    fn friction_rate(&self) -> f64 {
+        calc_friction_rate(&self.facets.iter().collect::<Vec<_>>())
+    }
+
+    fn success_rate(&self) -> f64 {
+        calc_success_rate(&self.facets.iter().collect::<Vec<_>>())
+    }
+}
+
+/// Compare the seven days ending `today` with the weeks before them
+pub fn detect(config: &Config, pricing: &PricingData, today: NaiveDate) -> AnomalyReport {
+    let weeks = config.insights.baseline_weeks.max(1);
+    let mut windows: Vec<Window> = (0..=weeks).map(|_| Window::default()).collect();
+    let window_of = |date: &str| {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+        let days_ago = (today - date).num_days();
+        (days_ago >= 0)
+            .then_some((days_ago / 7) as usize)
+            .filter(|&i| i <= weeks as usize)
+    };
+
+    for usage in
+        scanner::scan_all_sessions(None, pricing, &PathFilter::from_config(config)).values()
+    {
+        let Some(i) = usage
+            .first_timestamp
+            .as_deref()
+            .and_then(scanner::extract_date_from_timestamp)
+            .and_then(|date| window_of(&date))
+        else {
+            continue;
+        };
+        windows[i].cost += usage.total_cost_usd;
+        let project = usage.project.clone().unwrap_or_else(|| "unknown".into());
+        *windows[i].cost_by_project.entry(project).or_insert(0.0) += usage.total_cost_usd;
+    }
+
+    let facets: HashMap<String, SessionFacet> = SessionFacet::load_all(&config.insights.aliases)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let manager = ArchiveManager::new(config.clone());
+    for date in manager.list_dates().unwrap_or_default() {
+        let Some(i) = window_of(&date) else {
+            continue;
+        };
+        for name in manager.list_sessions(&date).unwrap_or_default() {
+            let Ok(content) = manager.read_session(&date, &name) else {
+                continue;
+            };
+            let facet =
+                extract_frontmatter_field(&content, "session_id").and_then(|id| facets.get(&id));
+            // The user's own rating wins over the inferred facet
+            let facet = match Annotation::read(&content) {
+                Some(annotation) => Some(SessionFacet::annotated(facet, &annotation)),
+                None => facet.cloned(),
+            };
+            windows[i].facets.extend(facet);
+        }
+    }
+
+    AnomalyReport {
+        from: (today - Duration::days(6)).format("%Y-%m-%d").to_string(),
+        to: today.format("%Y-%m-%d").to_string(),
+        baseline_weeks: weeks,
+        metrics: compare(&windows, config.insights.anomaly_threshold_pct as f64),
+    }
+}
+
+/// Metric changes of `windows[0]` (this week) against the average of the rest
+fn compare(windows: &[Window], threshold: f64) -> Vec<MetricChange> {
+    let (current, baseline) = windows.split_first().expect("at least one window");
+    let mut metrics = Vec::new();
+
+    let weeks = baseline.len().max(1) as f64;
+    let baseline_cost = baseline.iter().map(|w| w.cost).sum::<f64>() / weeks;
+    if baseline_cost > 0.0 || current.cost > 0.0 {
+        let change_pct = pct_change(baseline_cost, current.cost);
+        let anomalous = baseline_cost > 0.0 && change_pct >= threshold;
+        let driver = current
+            .cost_by_project
+            .iter()
+            .map(|(project, cost)| {
+                let before = baseline
+                    .iter()
+                    .filter_map(|w| w.cost_by_project.get(project))
+                    .sum::<f64>()
+                    / weeks;
+                (project, cost - before)
+            })
+            .filter(|(_, increase)| *increase > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(project, _)| project.clone());
+        let mut message = format!(
+            "cost {} this week (${:.2} vs ${:.2} weekly average)",
+            direction(change_pct),
+            current.cost,
+            baseline_cost
+        );
+        if let Some(project) = driver.as_ref().filter(|_| anomalous) {
+            message.push_str(&format!(", driven by project {}", project));
+        }
+        metrics.push(MetricChange {
+            metric: "cost".into(),
+            current: current.cost,
+            baseline: baseline_cost,
+            change_pct,
+            anomalous,
+            driver: driver.filter(|_| anomalous),
+            message,
+        });
+    }
+
+    // Rates are only compared once this week has enough rated sessions
+    let rated: Vec<&Window> = baseline.iter().filter(|w| !w.facets.is_empty()).collect();
+    if current.facets.len() < MIN_RATED_SESSIONS || rated.is_empty() {
+        return metrics;
+    }
+    let average =
+        |rate: fn(&Window) -> f64| rated.iter().map(|w| rate(w)).sum::<f64>() / rated.len() as f64;
+
+    let baseline_friction = average(Window::friction_rate);
+    let friction = current.friction_rate();
+    let change_pct = pct_change(baseline_friction, friction);
+    let anomalous = baseline_friction > 0.0 && change_pct >= threshold;
+    let mut frictions: HashMap<&str, usize> = HashMap::new();
+    for facet in &current.facets {
+        for (kind, count) in &facet.friction_counts {
+            *frictions.entry(kind.as_str()).or_insert(0) += count;
+        }
+    }
+    let driver = frictions
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(kind, _)| kind.to_string());
+    let mut message = format!(
+        "friction rate {} this week ({:.0}% of sessions vs {:.0}%)",
+        direction(change_pct),
+        friction,
+        baseline_friction
+    );
+    if let Some(kind) = driver.as_ref().filter(|_| anomalous) {
+        message.push_str(&format!(", mostly {}", kind));
+    }
+    metrics.push(MetricChange {
+        metric: "friction_rate".into(),
+        current: friction,
+        baseline: baseline_friction,
+        change_pct,
+        anomalous,
+        driver: driver.filter(|_| anomalous),
+        message,
+    });
+
+    let baseline_success = average(Window::success_rate);
+    let success = current.success_rate();
+    let change_pct = pct_change(baseline_success, success);
+    metrics.push(MetricChange {
+        metric: "success_rate".into(),
+        current: success,
+        baseline: baseline_success,
+        change_pct,
+        anomalous: baseline_success > 0.0 && change_pct <= -threshold,
+        driver: None,
+        message: format!(
+            "success rate {} this week ({:.0}% vs {:.0}%)",
+            direction(change_pct),
+            success,
+            baseline_success
+        ),
+    });
+
+    metrics
+}
+
+/// "up 80%", "down 25%" or "unchanged"
+fn direction(change_pct: f64) -> String {
+    if change_pct.round() == 0.0 {
+        "unchanged".to_string()
+    } else if change_pct > 0.0 {
+        format!("up {:.0}%", change_pct)
+    } else {
+        format!("down {:.0}%", -change_pct)
+    }
+}
+
+/// Whether a week has passed since the last notifying check
+pub fn is_due(config: &Config, today: NaiveDate) -> bool {
+    fs::read_to_string(config.storage_path().join(CHECKED_MARKER))
+        .ok()
+        .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok())
+        .is_none_or(|checked| today - checked >= Duration::days(7))
+}
+
+/// Remember that `today` has been checked, so the next check is a week later
+pub fn mark_checked(config: &Config, today: NaiveDate) -> anyhow::Result<()> {
+    fs::create_dir_all(config.storage_path())?;
+    fs::write(
+        config.storage_path().join(CHECKED_MARKER),
+        today.format("%Y-%m-%d").to_string(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(cost: &[(&str, f64)], outcomes: &[(&str, bool)]) -> Window {
+        Window {
+            cost: cost.iter().map(|(_, c)| c).sum(),
+            cost_by_project: cost.iter().map(|(p, c)| (p.to_string(), *c)).collect(),
+            facets: outcomes
+                .iter()
+                .map(|(outcome, friction)| SessionFacet {
+                    outcome: Some(outcome.to_string()),
+                    friction_counts: if *friction {
+                        HashMap::from([("wrong_approach".to_string(), 1)])
+                    } else {
+                        HashMap::new()
+                    },
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_metrics_past_threshold() {
+        let ok = ("achieved", false);
+        let rough = ("not_achieved", true);
+        let windows = vec![
+            window(&[("api", 10.0), ("web", 8.0)], &[ok, rough, rough, ok]),
+            window(&[("api", 10.0)], &[ok, ok, ok, rough]),
+            window(&[("api", 10.0)], &[ok, ok, ok, rough]),
+        ];
+
+        let metrics = compare(&windows, 50.0);
+        let cost = &metrics[0];
+        assert!(cost.anomalous);
+        assert_eq!(cost.change_pct.round(), 80.0);
+        assert_eq!(cost.driver.as_deref(), Some("web"));
+        assert_eq!(
+            cost.message,
+            "cost up 80% this week ($18.00 vs $10.00 weekly average), driven by project web"
+        );
+
+        // Friction doubled (25% -> 50%), success fell by a third (75% -> 50%)
+        assert!(metrics[1].anomalous);
+        assert_eq!(metrics[1].driver.as_deref(), Some("wrong_approach"));
+        assert!(!metrics[2].anomalous);
+        assert!(compare(&windows, 20.0)[2].anomalous);
+
+        // Too few rated sessions this week to compare rates
+        let windows = vec![window(&[], &[rough]), window(&[], &[ok, ok])];
+        assert!(compare(&windows, 50.0).is_empty());
+    }
+}
@@ -1,13 +1,19 @@
 use serde::Serialize;
 use std::collections::HashMap;
 
-use crate::archive::ArchiveManager;
+use crate::archive::annotation::Annotation;
+use crate::archive::paths::PathFilter;
+use crate::archive::{frontmatter, transcript, ArchiveManager};
 use crate::config::Config;
+use crate::decisions::project_name;
+use crate::goals::{GoalStat, GoalStore};
 use crate::usage::pricing::PricingData;
 use crate::usage::scanner;
 use crate::usage::types::{SessionUsage, UsageSummary};
 
+use super::activity::ActivityData;
 use super::facets::SessionFacet;
+use super::patterns::WorkPatterns;
 use super::trends::TrendData;
 
 /// Aggregated insights data from daily archives and Claude facets
@@ -19,11 +25,20 @@ pub struct InsightsData {
     pub goal_distribution: Vec<CategoryCount>,
     pub friction_distribution: Vec<CategoryCount>,
     pub satisfaction_distribution: Vec<CategoryCount>,
+    /// Sessions given stars with `daily feedback`, and their mean rating
+    pub rated_sessions: usize,
+    pub average_stars: Option<f64>,
     pub language_distribution: Vec<CategoryCount>,
     pub session_type_distribution: Vec<CategoryCount>,
     pub session_details: Vec<SessionInsight>,
     pub trends: Option<TrendData>,
     pub usage_summary: Option<UsageSummary>,
+    pub activity: ActivityData,
+    /// Goals from `daily goal`, with the work digests reported on them
+    /// within the analyzed dates
+    pub tracked_goals: Vec<GoalStat>,
+    /// Hours of day and focus blocks from the transcripts of included sessions
+    pub work_patterns: WorkPatterns,
 }
 
 /// Per-session insight combining archive metadata with facet analysis data
@@ -38,8 +53,12 @@ pub struct SessionInsight {
     pub friction_types: Vec<String>,
     pub friction_detail: Option<String>,
     pub satisfaction: Option<String>,
+    /// 1-5 star rating from `daily feedback`
+    pub stars: Option<u8>,
     pub claude_helpfulness: Option<String>,
     pub session_type: Option<String>,
+    /// Last component of the session's working directory
+    pub project: Option<String>,
     pub token_usage: Option<SessionUsage>,
 }
 
@@ -60,23 +79,156 @@ pub struct CategoryCount {
     pub count: usize,
 }
 
+/// Optional filters narrowing which sessions contribute to insights.
+/// An empty filter keeps the unfiltered behavior.
+#[derive(Debug, Clone, Default)]
+pub struct InsightsFilter {
+    /// Substring matched against the session's `cwd` frontmatter
+    pub project: Option<String>,
+    /// Session must include this goal category
+    pub goal_category: Option<String>,
+    /// Exact outcome, e.g. "achieved"
+    pub outcome: Option<String>,
+    /// Exact session type, e.g. "single_task"
+    pub session_type: Option<String>,
+    /// Session must include this friction type
+    pub friction_type: Option<String>,
+    /// Inclusive start date (YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Inclusive end date (YYYY-MM-DD)
+    pub to: Option<String>,
+}
+
+impl InsightsFilter {
+    /// Build a filter from API query parameters, ignoring empty values
+    pub fn from_params(params: &HashMap<String, String>) -> Self {
+        let get = |key: &str| {
+            params
+                .get(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            project: get("project").or_else(|| get("cwd")),
+            goal_category: get("goal_category"),
+            outcome: get("outcome"),
+            session_type: get("session_type"),
+            friction_type: get("friction_type"),
+            from: get("from"),
+            to: get("to"),
+        }
+    }
+
+    /// True when no filter is set
+    pub fn is_empty(&self) -> bool {
+        !self.has_date_range() && !self.has_session_criteria()
+    }
+
+    fn has_date_range(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    fn has_facet_criteria(&self) -> bool {
+        self.goal_category.is_some()
+            || self.outcome.is_some()
+            || self.session_type.is_some()
+            || self.friction_type.is_some()
+    }
+
+    fn has_session_criteria(&self) -> bool {
+        self.project.is_some() || self.has_facet_criteria()
+    }
+
+    /// Check whether a YYYY-MM-DD date falls within the from/to range
+    fn matches_date(&self, date: &str) -> bool {
+        if let Some(from) = &self.from {
+            if date < from.as_str() {
+                return false;
+            }
+        }
+        if let Some(to) = &self.to {
+            if date > to.as_str() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Dates in the filter's `from`/`to` range, or else the `days_limit`
+    /// most recent ones
+    pub(crate) fn select_dates(&self, all_dates: Vec<String>, days_limit: usize) -> Vec<String> {
+        if self.has_date_range() {
+            all_dates
+                .into_iter()
+                .filter(|d| self.matches_date(d))
+                .collect()
+        } else {
+            all_dates.into_iter().take(days_limit).collect()
+        }
+    }
+
+    /// Check a session's cwd and facet against the per-session criteria.
+    /// Sessions without facet data never match facet-based criteria.
+    pub(crate) fn matches_session(&self, cwd: Option<&str>, facet: Option<&SessionFacet>) -> bool {
+        if let Some(project) = &self.project {
+            if !cwd.is_some_and(|c| c.contains(project.as_str())) {
+                return false;
+            }
+        }
+        if !self.has_facet_criteria() {
+            return true;
+        }
+        let Some(facet) = facet else {
+            return false;
+        };
+        if let Some(goal) = &self.goal_category {
+            if !facet.goal_categories.contains_key(goal) {
+                return false;
+            }
+        }
+        if let Some(friction) = &self.friction_type {
+            if !facet.friction_counts.contains_key(friction) {
+                return false;
+            }
+        }
+        if let Some(outcome) = &self.outcome {
+            if facet.outcome.as_deref() != Some(outcome.as_str()) {
+                return false;
+            }
+        }
+        if let Some(session_type) = &self.session_type {
+            if facet.session_type.as_deref() != Some(session_type.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 impl InsightsData {
     /// Collect insights data from archives and facets.
-    /// `days` limits the number of most recent days to analyze.
+    /// `days` limits the number of most recent days to analyze, unless the
+    /// filter carries an explicit `from`/`to` range.
     pub fn collect(
         config: &Config,
         days: Option<usize>,
         pricing: &PricingData,
+        filter: &InsightsFilter,
     ) -> anyhow::Result<Self> {
         let manager = ArchiveManager::new(config.clone());
         let all_dates = manager.list_dates()?;
 
         let days_limit = days.unwrap_or(30);
-        let dates: Vec<String> = all_dates.into_iter().take(days_limit).collect();
+        let dates = filter.select_dates(all_dates, days_limit);
 
         // Scan all usage data upfront
-        let all_session_usages = scanner::scan_all_sessions(None, pricing);
-        let usage_summary = scanner::aggregate_usage(&all_session_usages, None);
+        let all_session_usages =
+            scanner::scan_all_sessions(None, pricing, &PathFilter::from_config(config));
+        let usage_summary = if filter.has_date_range() {
+            scanner::aggregate_usage(&all_session_usages, Some(&dates))
+        } else {
+            scanner::aggregate_usage(&all_session_usages, None)
+        };
 
         // Build a lookup: date -> DailyUsage for merging into daily_stats
         let daily_usage_map: HashMap<String, &crate::usage::types::DailyUsage> = usage_summary
@@ -85,12 +237,120 @@ impl InsightsData {
             .map(|d| (d.date.clone(), d))
             .collect();
 
+        // Load facets from Claude Code, indexed by session_id for fast lookup
+        let facets = SessionFacet::load_all(&config.insights.aliases).unwrap_or_default();
+        let facet_map: HashMap<String, &SessionFacet> = facets
+            .iter()
+            .map(|(id, facet)| (id.clone(), facet))
+            .collect();
+
+        // Build per-session details by scanning archive files and matching with facets
+        let filter_sessions = filter.has_session_criteria();
         let mut daily_stats = Vec::new();
         let mut total_sessions = 0;
+        let mut session_details = Vec::new();
+        let mut annotated_facets: HashMap<String, SessionFacet> = HashMap::new();
+        let mut transcripts = Vec::new();
+        let mut language_counts: HashMap<String, usize> = HashMap::new();
 
         for date in &dates {
             let sessions = manager.list_sessions(date).unwrap_or_default();
-            let session_count = sessions.len();
+            let mut session_count = 0;
+
+            for session_name in &sessions {
+                let content = manager.read_session(date, session_name).ok();
+                let session_id = content
+                    .as_deref()
+                    .and_then(extract_session_id_from_frontmatter);
+                let facet = session_id
+                    .as_ref()
+                    .and_then(|id| facet_map.get(id).copied());
+                // The user's own rating wins over the inferred facet
+                let annotation = content.as_deref().and_then(Annotation::read);
+                let stars = annotation.as_ref().and_then(|a| a.stars);
+                let annotated = annotation
+                    .as_ref()
+                    .map(|annotation| SessionFacet::annotated(facet, annotation));
+                let facet = annotated.as_ref().or(facet);
+
+                let cwd = content
+                    .as_deref()
+                    .and_then(|c| extract_frontmatter_field(c, "cwd"));
+                if filter_sessions
+                    && (session_id.is_none() || !filter.matches_session(cwd.as_deref(), facet))
+                {
+                    continue;
+                }
+                let project = cwd.as_deref().map(|c| project_name(c).to_string());
+                session_count += 1;
+                for language in content
+                    .as_deref()
+                    .map(session_languages)
+                    .unwrap_or_default()
+                {
+                    *language_counts.entry(language).or_insert(0) += 1;
+                }
+                if let Some(path) = content
+                    .as_deref()
+                    .and_then(|c| transcript::resolve(config, date, c))
+                {
+                    transcripts.push(path);
+                }
+
+                let Some(session_id) = session_id else {
+                    continue;
+                };
+                let token_usage = all_session_usages.get(&session_id).cloned();
+                if let Some(annotated) = &annotated {
+                    annotated_facets.insert(session_id.clone(), annotated.clone());
+                }
+
+                let insight = if let Some(facet) = facet {
+                    // Determine the most common satisfaction level
+                    let satisfaction = facet
+                        .user_satisfaction_counts
+                        .iter()
+                        .max_by_key(|(_, count)| *count)
+                        .map(|(name, _)| name.clone());
+
+                    SessionInsight {
+                        session_id: session_id.clone(),
+                        date: date.clone(),
+                        session_name: session_name.clone(),
+                        brief_summary: facet.brief_summary.clone(),
+                        outcome: facet.outcome.clone(),
+                        goal_categories: facet.goal_categories.keys().cloned().collect(),
+                        friction_types: facet.friction_counts.keys().cloned().collect(),
+                        friction_detail: facet.friction_detail.clone(),
+                        satisfaction,
+                        stars,
+                        claude_helpfulness: facet.claude_helpfulness.clone(),
+                        session_type: facet.session_type.clone(),
+                        project,
+                        token_usage,
+                    }
+                } else {
+                    // No facet data available for this session
+                    SessionInsight {
+                        session_id: session_id.clone(),
+                        date: date.clone(),
+                        session_name: session_name.clone(),
+                        brief_summary: None,
+                        outcome: None,
+                        goal_categories: Vec::new(),
+                        friction_types: Vec::new(),
+                        friction_detail: None,
+                        satisfaction: None,
+                        stars,
+                        claude_helpfulness: None,
+                        session_type: None,
+                        project,
+                        token_usage,
+                    }
+                };
+                session_details.push(insight);
+            }
+
             total_sessions += session_count;
 
             let has_digest = manager
@@ -100,7 +360,32 @@ impl InsightsData {
                 })
                 .unwrap_or(false);
 
-            let (total_tokens, total_cost) = if let Some(du) = daily_usage_map.get(date.as_str()) {
+            let (total_tokens, total_cost) = if filter_sessions {
+                // Day totals cover only the matching sessions
+                let matching: Vec<&SessionUsage> = session_details
+                    .iter()
+                    .filter(|s| &s.date == date)
+                    .filter_map(|s| s.token_usage.as_ref())
+                    .collect();
+                if matching.is_empty() {
+                    (None, None)
+                } else {
+                    (
+                        Some(
+                            matching
+                                .iter()
+                                .map(|u| {
+                                    u.input_tokens
+                                        + u.output_tokens
+                                        + u.cache_creation_tokens
+                                        + u.cache_read_tokens
+                                })
+                                .sum(),
+                        ),
+                        Some(matching.iter().map(|u| u.total_cost_usd).sum()),
+                    )
+                }
+            } else if let Some(du) = daily_usage_map.get(date.as_str()) {
                 (
                     Some(
                         du.input_tokens
@@ -126,12 +411,22 @@ impl InsightsData {
         // Reverse so oldest first (for charts)
         daily_stats.reverse();
 
-        // Load facets from Claude Code, indexed by session_id for fast lookup
-        let facets = SessionFacet::load_all().unwrap_or_default();
-        let facet_map: HashMap<String, &SessionFacet> = facets
-            .iter()
-            .map(|(id, facet)| (id.clone(), facet))
-            .collect();
+        // When filtering, distributions only cover facets of the matching sessions
+        let mut facets: Vec<(String, SessionFacet)> = if filter.is_empty() {
+            facets
+        } else {
+            facets
+                .into_iter()
+                .filter(|(id, _)| session_details.iter().any(|s| &s.session_id == id))
+                .collect()
+        };
+        // Annotated sessions count with the user's rating, including ones without facets
+        for (id, facet) in facets.iter_mut() {
+            if let Some(annotated) = annotated_facets.remove(id) {
+                *facet = annotated;
+            }
+        }
+        facets.extend(annotated_facets);
 
         // Aggregate goal_categories (HashMap<String, usize> per facet)
         let goal_distribution = aggregate_hashmap_field(&facets, |f| &f.goal_categories);
@@ -143,71 +438,36 @@ impl InsightsData {
         let satisfaction_distribution =
             aggregate_hashmap_field(&facets, |f| &f.user_satisfaction_counts);
 
+        let ratings: Vec<u8> = session_details.iter().filter_map(|s| s.stars).collect();
+        let average_stars = (!ratings.is_empty())
+            .then(|| ratings.iter().map(|&s| s as f64).sum::<f64>() / ratings.len() as f64);
+
         // Aggregate session_type (single string per facet)
         let session_type_distribution = count_option_field(&facets, |f| f.session_type.as_deref());
 
-        // language_distribution is currently empty since facets don't carry language data
-        let language_distribution = Vec::new();
-
-        // Build per-session details by scanning archive files and matching with facets
-        let mut session_details = Vec::new();
-        for date in &dates {
-            let sessions = manager.list_sessions(date).unwrap_or_default();
-            for session_name in &sessions {
-                if let Ok(content) = manager.read_session(date, session_name) {
-                    if let Some(session_id) = extract_session_id_from_frontmatter(&content) {
-                        let token_usage = all_session_usages.get(&session_id).cloned();
-
-                        let insight = if let Some(facet) = facet_map.get(&session_id) {
-                            // Determine the most common satisfaction level
-                            let satisfaction = facet
-                                .user_satisfaction_counts
-                                .iter()
-                                .max_by_key(|(_, count)| *count)
-                                .map(|(name, _)| name.clone());
-
-                            SessionInsight {
-                                session_id: session_id.clone(),
-                                date: date.clone(),
-                                session_name: session_name.clone(),
-                                brief_summary: facet.brief_summary.clone(),
-                                outcome: facet.outcome.clone(),
-                                goal_categories: facet.goal_categories.keys().cloned().collect(),
-                                friction_types: facet.friction_counts.keys().cloned().collect(),
-                                friction_detail: facet.friction_detail.clone(),
-                                satisfaction,
-                                claude_helpfulness: facet.claude_helpfulness.clone(),
-                                session_type: facet.session_type.clone(),
-                                token_usage,
-                            }
-                        } else {
-                            // No facet data available for this session
-                            SessionInsight {
-                                session_id: session_id.clone(),
-                                date: date.clone(),
-                                session_name: session_name.clone(),
-                                brief_summary: None,
-                                outcome: None,
-                                goal_categories: Vec::new(),
-                                friction_types: Vec::new(),
-                                friction_detail: None,
-                                satisfaction: None,
-                                claude_helpfulness: None,
-                                session_type: None,
-                                token_usage,
-                            }
-                        };
-                        session_details.push(insight);
-                    }
-                }
-            }
-        }
+        // Sessions per language of the files they changed
+        let mut language_distribution: Vec<CategoryCount> = language_counts
+            .into_iter()
+            .map(|(name, count)| CategoryCount { name, count })
+            .collect();
+        language_distribution.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
 
         // Calculate trend data using dates in chronological order (oldest first)
         // daily_stats is already reversed to oldest-first at this point
         let chronological_dates: Vec<String> = daily_stats.iter().map(|s| s.date.clone()).collect();
         let trends = TrendData::calculate(config, &chronological_dates, days_limit);
 
+        // Restrict the usage summary to matching sessions when filtering by session
+        let usage_summary = if filter_sessions {
+            let matching: HashMap<String, SessionUsage> = session_details
+                .iter()
+                .filter_map(|s| s.token_usage.clone().map(|u| (s.session_id.clone(), u)))
+                .collect();
+            scanner::aggregate_usage(&matching, None)
+        } else {
+            usage_summary
+        };
+
         Ok(InsightsData {
             total_days: dates.len(),
             total_sessions,
@@ -215,11 +475,18 @@ impl InsightsData {
             goal_distribution,
             friction_distribution,
             satisfaction_distribution,
+            rated_sessions: ratings.len(),
+            average_stars,
             language_distribution,
             session_type_distribution,
             session_details,
             trends,
             usage_summary: Some(usage_summary),
+            activity: ActivityData::calculate(config, chrono::Local::now().date_naive()),
+            tracked_goals: GoalStore::load(config)
+                .map(|goals| goals.stats(&dates))
+                .unwrap_or_default(),
+            work_patterns: WorkPatterns::collect(&transcripts),
         })
     }
 }
@@ -239,7 +506,7 @@ where
         .into_iter()
         .map(|(name, count)| CategoryCount { name, count })
         .collect();
-    result.sort_by(|a, b| b.count.cmp(&a.count));
+    result.sort_by_key(|c| std::cmp::Reverse(c.count));
     result
 }
 
@@ -258,20 +525,39 @@ where
         .into_iter()
         .map(|(name, count)| CategoryCount { name, count })
         .collect();
-    result.sort_by(|a, b| b.count.cmp(&a.count));
+    result.sort_by_key(|c| std::cmp::Reverse(c.count));
     result
 }
 
+/// Languages recorded in a session archive's `languages: [..]` frontmatter
+fn session_languages(content: &str) -> Vec<String> {
+    frontmatter::get(content, "languages")
+        .map(|list| {
+            list.trim_matches(['[', ']'])
+                .split(',')
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Extract session_id from YAML frontmatter in a session archive markdown file.
 /// Looks for `session_id: <value>` between `---` markers.
 fn extract_session_id_from_frontmatter(content: &str) -> Option<String> {
+    extract_frontmatter_field(content, "session_id")
+}
+
+/// Extract a single scalar field from YAML frontmatter between `---` markers.
+pub(crate) fn extract_frontmatter_field(content: &str, field: &str) -> Option<String> {
     if let Some(stripped) = content.strip_prefix("---\n") {
         if let Some(end) = stripped.find("\n---") {
             let frontmatter = &stripped[..end];
             for line in frontmatter.lines() {
                 if let Some((key, value)) = line.split_once(':') {
                     let key = key.trim();
-                    if key == "session_id" {
+                    if key == field {
                         let value = value.trim().trim_matches('"');
                         if !value.is_empty() {
                             return Some(value.to_string());
@@ -283,3 +569,79 @@ fn extract_session_id_from_frontmatter(content: &str) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facet(goal: &str, outcome: &str, friction: Option<&str>) -> SessionFacet {
+        let mut facet: SessionFacet = serde_json::from_str("{}").unwrap();
+        facet.goal_categories.insert(goal.to_string(), 1);
+        facet.outcome = Some(outcome.to_string());
+        if let Some(friction) = friction {
+            facet.friction_counts.insert(friction.to_string(), 1);
+        }
+        facet
+    }
+
+    #[test]
+    fn test_filter_from_params() {
+        let mut params = HashMap::new();
+        params.insert("cwd".to_string(), "claude-daily".to_string());
+        params.insert("outcome".to_string(), " ".to_string());
+        params.insert("from".to_string(), "2026-01-01".to_string());
+        let filter = InsightsFilter::from_params(&params);
+        assert_eq!(filter.project.as_deref(), Some("claude-daily"));
+        assert_eq!(filter.outcome, None);
+        assert_eq!(filter.from.as_deref(), Some("2026-01-01"));
+        assert!(!filter.is_empty());
+        assert!(InsightsFilter::default().is_empty());
+    }
+
+    #[test]
+    fn test_filter_matches_date_range() {
+        let filter = InsightsFilter {
+            from: Some("2026-01-10".to_string()),
+            to: Some("2026-01-20".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches_date("2026-01-09"));
+        assert!(filter.matches_date("2026-01-10"));
+        assert!(filter.matches_date("2026-01-20"));
+        assert!(!filter.matches_date("2026-01-21"));
+    }
+
+    #[test]
+    fn test_filter_matches_session() {
+        let debugging = facet("debugging", "achieved", Some("buggy_code"));
+        let filter = InsightsFilter {
+            project: Some("claude-daily".to_string()),
+            goal_category: Some("debugging".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches_session(Some("/home/u/claude-daily"), Some(&debugging)));
+        assert!(!filter.matches_session(Some("/home/u/other"), Some(&debugging)));
+        assert!(!filter.matches_session(Some("/home/u/claude-daily"), None));
+
+        let friction = InsightsFilter {
+            friction_type: Some("buggy_code".to_string()),
+            outcome: Some("not_achieved".to_string()),
+            ..Default::default()
+        };
+        assert!(!friction.matches_session(None, Some(&debugging)));
+    }
+
+    #[test]
+    fn test_extract_frontmatter_field() {
+        let content = "---\ntitle: Test\ncwd: /home/u/project\nsession_id: \"abc\"\n---\n# Body";
+        assert_eq!(
+            extract_frontmatter_field(content, "cwd"),
+            Some("/home/u/project".to_string())
+        );
+        assert_eq!(
+            extract_session_id_from_frontmatter(content),
+            Some("abc".to_string())
+        );
+        assert_eq!(extract_frontmatter_field(content, "missing"), None);
+    }
+}
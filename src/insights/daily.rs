@@ -1,8 +1,9 @@
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use crate::archive::annotation::Annotation;
+use crate::archive::paths::PathFilter;
 use crate::archive::ArchiveManager;
-use crate::config::Config;
 use crate::usage::pricing::PricingData;
 use crate::usage::scanner;
 use crate::usage::types::SessionUsage;
@@ -68,12 +69,16 @@ pub struct DateInsights {
 
 impl DateInsights {
     /// Collect insights for a specific date by matching session archives with facet data
-    pub fn collect(date: &str, config: &Config, pricing: &PricingData) -> anyhow::Result<Self> {
-        let manager = ArchiveManager::new(config.clone());
+    pub fn collect(
+        date: &str,
+        manager: &ArchiveManager,
+        pricing: &PricingData,
+    ) -> anyhow::Result<Self> {
         let session_names = manager.list_sessions(date).unwrap_or_default();
 
         // Load all facets and index by session_id
-        let all_facets = SessionFacet::load_all().unwrap_or_default();
+        let all_facets =
+            SessionFacet::load_all(&manager.config().insights.aliases).unwrap_or_default();
         let facet_map: HashMap<String, SessionFacet> = all_facets.into_iter().collect();
 
         // Collect session IDs for this date to filter usage scanning
@@ -88,7 +93,11 @@ impl DateInsights {
 
         // Scan usage for only the sessions belonging to this date
         let usage_map = if !date_session_ids.is_empty() {
-            scanner::scan_all_sessions(Some(&date_session_ids), pricing)
+            scanner::scan_all_sessions(
+                Some(&date_session_ids),
+                pricing,
+                &PathFilter::from_config(manager.config()),
+            )
         } else {
             HashMap::new()
         };
@@ -118,8 +127,10 @@ impl DateInsights {
                 None => continue,
             };
 
-            // Look up facet data for this session
-            let facet = facet_map.get(&session_id);
+            // Look up facet data for this session; the user's own rating wins
+            let annotated = Annotation::read(&content)
+                .map(|annotation| SessionFacet::annotated(facet_map.get(&session_id), &annotation));
+            let facet = annotated.as_ref().or(facet_map.get(&session_id));
 
             let (
                 goal_categories,
@@ -212,10 +223,11 @@ impl DateInsights {
             &day_satisfaction_counts,
             sessions_with_friction,
             session_names.len(),
+            &manager.config().insights.satisfaction_weights,
         );
 
         let mut model_distribution: Vec<(String, usize)> = day_model_counts.into_iter().collect();
-        model_distribution.sort_by(|a, b| b.1.cmp(&a.1));
+        model_distribution.sort_by_key(|m| std::cmp::Reverse(m.1));
 
         let day_summary = DayInsightSummary {
             total_sessions: session_names.len(),
@@ -291,6 +303,7 @@ fn generate_recommendations(
     satisfaction_counts: &HashMap<String, usize>,
     sessions_with_friction: usize,
     total_sessions: usize,
+    satisfaction_weights: &BTreeMap<String, f64>,
 ) -> Vec<String> {
     let mut recs = Vec::new();
 
@@ -354,13 +367,13 @@ fn generate_recommendations(
 
     // Positive feedback when things go well
     let total_satisfaction: usize = satisfaction_counts.values().sum();
-    let happy = satisfaction_counts.get("happy").copied().unwrap_or(0);
-    let likely_satisfied = satisfaction_counts
-        .get("likely_satisfied")
-        .copied()
-        .unwrap_or(0);
-    if total_satisfaction > 0 && (happy + likely_satisfied) as f64 / total_satisfaction as f64 > 0.7
-    {
+    // Levels weighted 75 or more (happy, satisfied) count as satisfied
+    let satisfied: usize = satisfaction_counts
+        .iter()
+        .filter(|(level, _)| satisfaction_weights.get(*level).is_some_and(|w| *w >= 75.0))
+        .map(|(_, count)| count)
+        .sum();
+    if total_satisfaction > 0 && satisfied as f64 / total_satisfaction as f64 > 0.7 {
         recs.push("Great collaboration today! Satisfaction levels are high.".to_string());
     }
 
@@ -383,6 +396,7 @@ fn generate_recommendations(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
 
     #[test]
     fn test_extract_session_id() {
@@ -421,7 +435,14 @@ mod tests {
         friction.insert("misunderstood_request".to_string(), 3);
         let outcomes = HashMap::new();
         let satisfaction = HashMap::new();
-        let recs = generate_recommendations(&friction, &outcomes, &satisfaction, 2, 3);
+        let recs = generate_recommendations(
+            &friction,
+            &outcomes,
+            &satisfaction,
+            2,
+            3,
+            &Config::default().insights.satisfaction_weights,
+        );
         assert!(recs.iter().any(|r| r.contains("more specific")));
     }
 
@@ -433,7 +454,14 @@ mod tests {
         let mut satisfaction = HashMap::new();
         satisfaction.insert("happy".to_string(), 4);
         satisfaction.insert("likely_satisfied".to_string(), 1);
-        let recs = generate_recommendations(&friction, &outcomes, &satisfaction, 0, 5);
+        let recs = generate_recommendations(
+            &friction,
+            &outcomes,
+            &satisfaction,
+            0,
+            5,
+            &Config::default().insights.satisfaction_weights,
+        );
         assert!(recs.iter().any(|r| r.contains("Great collaboration")));
     }
 }
@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::archive::annotation::Annotation;
+use crate::config::FacetAliases;
 
 /// Represents facet data for a single Claude Code session.
 /// Loaded from JSON files in ~/.claude/usage-data/facets/
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SessionFacet {
     /// Brief summary of the session
     #[serde(default)]
@@ -41,8 +44,32 @@ pub struct SessionFacet {
 }
 
 impl SessionFacet {
-    /// Load all facets from the default Claude Code facets directory
-    pub fn load_all() -> anyhow::Result<Vec<(String, Self)>> {
+    /// Facet with the user's own rating (from `daily rate` or `daily
+    /// feedback`) replacing the inferred outcome and satisfaction
+    pub fn annotated(facet: Option<&SessionFacet>, annotation: &Annotation) -> Self {
+        let mut facet = facet.cloned().unwrap_or_default();
+        if let Some(outcome) = &annotation.outcome {
+            facet.outcome = Some(outcome.clone());
+        }
+        if let Some(satisfaction) = annotation.effective_satisfaction() {
+            facet.user_satisfaction_counts = HashMap::from([(satisfaction.to_string(), 1)]);
+        }
+        facet
+    }
+
+    /// Rename categories from another facet vocabulary onto the names
+    /// insights use
+    pub fn apply_aliases(&mut self, aliases: &FacetAliases) {
+        rename_keys(&mut self.goal_categories, &aliases.goal);
+        rename_keys(&mut self.friction_counts, &aliases.friction);
+        rename_keys(&mut self.user_satisfaction_counts, &aliases.satisfaction);
+        rename(&mut self.outcome, &aliases.outcome);
+        rename(&mut self.session_type, &aliases.session_type);
+    }
+
+    /// Load all facets from the default Claude Code facets directory, with
+    /// `aliases` applied
+    pub fn load_all(aliases: &FacetAliases) -> anyhow::Result<Vec<(String, Self)>> {
         let facets_dir = dirs::home_dir()
             .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?
             .join(".claude/usage-data/facets");
@@ -58,7 +85,8 @@ impl SessionFacet {
             if path.extension().is_some_and(|e| e == "json") {
                 if let Ok(content) = std::fs::read_to_string(&path) {
                     // Try to parse - skip invalid files
-                    if let Ok(facet) = serde_json::from_str::<SessionFacet>(&content) {
+                    if let Ok(mut facet) = serde_json::from_str::<SessionFacet>(&content) {
+                        facet.apply_aliases(aliases);
                         let session_id = path
                             .file_stem()
                             .unwrap_or_default()
@@ -72,3 +100,22 @@ impl SessionFacet {
         Ok(facets)
     }
 }
+
+/// Rename counted categories, merging the counts of ones that meet
+fn rename_keys(counts: &mut HashMap<String, usize>, aliases: &BTreeMap<String, String>) {
+    if aliases.is_empty() {
+        return;
+    }
+    let mut renamed = HashMap::new();
+    for (key, count) in counts.drain() {
+        let key = aliases.get(&key).cloned().unwrap_or(key);
+        *renamed.entry(key).or_insert(0) += count;
+    }
+    *counts = renamed;
+}
+
+fn rename(value: &mut Option<String>, aliases: &BTreeMap<String, String>) {
+    if let Some(alias) = value.as_ref().and_then(|v| aliases.get(v)) {
+        *value = Some(alias.clone());
+    }
+}
@@ -0,0 +1,188 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::archive::annotation::Annotation;
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+
+use super::collector::{extract_frontmatter_field, InsightsFilter};
+use super::facets::SessionFacet;
+
+/// Sessions that ran into one kind of friction, as evidence behind the chart
+#[derive(Debug, Clone, Serialize)]
+pub struct FrictionDrilldown {
+    pub friction_type: String,
+    /// Times the friction was recorded across all matching sessions
+    pub total_count: usize,
+    /// Matching sessions, newest date first
+    pub sessions: Vec<FrictionExample>,
+}
+
+/// One session exhibiting the friction
+#[derive(Debug, Clone, Serialize)]
+pub struct FrictionExample {
+    pub session_id: String,
+    pub date: String,
+    pub session_name: String,
+    pub title: Option<String>,
+    pub brief_summary: Option<String>,
+    pub outcome: Option<String>,
+    /// Times this friction was recorded in the session
+    pub count: usize,
+    pub friction_detail: Option<String>,
+    /// Dashboard page showing the session's conversation
+    pub link: String,
+    /// API endpoint with the paged conversation
+    pub conversation_url: String,
+}
+
+impl FrictionDrilldown {
+    /// Sessions of the most recent `days` dates (or the filter's `from`/`to`
+    /// range) whose facets record `friction_type`
+    pub fn collect(
+        config: &Config,
+        friction_type: &str,
+        days: Option<usize>,
+        filter: &InsightsFilter,
+    ) -> anyhow::Result<Self> {
+        let facets: HashMap<String, SessionFacet> =
+            SessionFacet::load_all(&config.insights.aliases)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+        Self::from_facets(config, &facets, friction_type, days, filter)
+    }
+
+    fn from_facets(
+        config: &Config,
+        facets: &HashMap<String, SessionFacet>,
+        friction_type: &str,
+        days: Option<usize>,
+        filter: &InsightsFilter,
+    ) -> anyhow::Result<Self> {
+        let manager = ArchiveManager::new(config.clone());
+        let dates = filter.select_dates(manager.list_dates()?, days.unwrap_or(30));
+
+        let mut sessions = Vec::new();
+        for date in &dates {
+            for session_name in manager.list_sessions(date).unwrap_or_default() {
+                let Ok(content) = manager.read_session(date, &session_name) else {
+                    continue;
+                };
+                let Some(session_id) = extract_frontmatter_field(&content, "session_id") else {
+                    continue;
+                };
+                let Some(facet) = facets.get(&session_id) else {
+                    continue;
+                };
+                let Some(&count) = facet.friction_counts.get(friction_type) else {
+                    continue;
+                };
+                // The user's own rating wins over the inferred outcome
+                let facet = match Annotation::read(&content) {
+                    Some(annotation) => SessionFacet::annotated(Some(facet), &annotation),
+                    None => facet.clone(),
+                };
+                let cwd = extract_frontmatter_field(&content, "cwd");
+                if !filter.matches_session(cwd.as_deref(), Some(&facet)) {
+                    continue;
+                }
+
+                sessions.push(FrictionExample {
+                    link: format!("/day/{}/session/{}?tab=conversation", date, session_name),
+                    conversation_url: format!(
+                        "/api/dates/{}/sessions/{}/conversation",
+                        date, session_name
+                    ),
+                    session_id,
+                    date: date.clone(),
+                    title: extract_frontmatter_field(&content, "title"),
+                    session_name,
+                    brief_summary: facet.brief_summary,
+                    outcome: facet.outcome,
+                    count,
+                    friction_detail: facet.friction_detail,
+                });
+            }
+        }
+
+        Ok(Self {
+            friction_type: friction_type.to_string(),
+            total_count: sessions.iter().map(|s| s.count).sum(),
+            sessions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_drilldown_lists_sessions_with_friction() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+
+        for (date, name, id) in [
+            ("2024-01-01", "10_00-auth", "a"),
+            ("2024-01-02", "09_00-css", "b"),
+            ("2024-01-02", "11_00-deploy", "c"),
+        ] {
+            let content = format!("---\ntitle: {}\nsession_id: {}\n---\n\nDone.\n", name, id);
+            manager.write_session(date, name, &content).unwrap();
+        }
+        let facet = |friction: &[(&str, usize)], detail: &str| SessionFacet {
+            friction_counts: friction.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            friction_detail: Some(detail.to_string()),
+            ..Default::default()
+        };
+        let facets = HashMap::from([
+            (
+                "a".to_string(),
+                facet(&[("wrong_approach", 2)], "Rewrote the token cache twice"),
+            ),
+            ("b".to_string(), facet(&[("buggy_code", 1)], "Broken CSS")),
+            (
+                "c".to_string(),
+                facet(&[("wrong_approach", 1)], "Deployed to the wrong region"),
+            ),
+        ]);
+
+        let drilldown = FrictionDrilldown::from_facets(
+            &config,
+            &facets,
+            "wrong_approach",
+            None,
+            &InsightsFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(drilldown.total_count, 3);
+        let names: Vec<&str> = drilldown
+            .sessions
+            .iter()
+            .map(|s| s.session_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["11_00-deploy", "10_00-auth"]);
+        let first = &drilldown.sessions[0];
+        assert_eq!(
+            first.friction_detail.as_deref(),
+            Some("Deployed to the wrong region")
+        );
+        assert_eq!(
+            first.link,
+            "/day/2024-01-02/session/11_00-deploy?tab=conversation"
+        );
+
+        let filter = InsightsFilter {
+            to: Some("2024-01-01".into()),
+            ..Default::default()
+        };
+        let drilldown =
+            FrictionDrilldown::from_facets(&config, &facets, "wrong_approach", None, &filter)
+                .unwrap();
+        assert_eq!(drilldown.sessions.len(), 1);
+    }
+}
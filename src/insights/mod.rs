@@ -1,4 +1,10 @@
+pub mod activity;
+pub mod anomalies;
 pub mod collector;
 pub mod daily;
 pub mod facets;
+pub mod friction;
+pub mod patterns;
+pub mod projects;
+pub mod tools;
 pub mod trends;
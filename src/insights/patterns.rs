@@ -0,0 +1,168 @@
+use chrono::{DateTime, Local, NaiveDateTime, Timelike};
+use serde::Serialize;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::archive::transcript;
+
+/// Messages further apart than this end a focus block
+const FOCUS_GAP_MINUTES: i64 = 15;
+
+/// Number of longest focus blocks kept
+const TOP_FOCUS_BLOCKS: usize = 5;
+
+/// When during the day work happens, from transcript message timestamps
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkPatterns {
+    /// Messages per local hour of day, index 0 = 00:00-00:59
+    pub hourly: Vec<usize>,
+    /// Hour with the most messages
+    pub peak_hour: Option<u32>,
+    /// Percentage of messages sent between 22:00 and 05:59
+    pub late_night_ratio: f64,
+    pub longest_focus_minutes: i64,
+    /// Longest stretches without a break of `FOCUS_GAP_MINUTES`, longest first
+    pub focus_blocks: Vec<FocusBlock>,
+}
+
+/// An uninterrupted stretch of work, possibly across sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusBlock {
+    pub date: String,
+    /// Local time, HH:MM
+    pub start: String,
+    pub end: String,
+    pub minutes: i64,
+}
+
+impl WorkPatterns {
+    /// Read message timestamps from the given transcripts
+    pub fn collect(transcripts: &[PathBuf]) -> Self {
+        let times: Vec<NaiveDateTime> = transcripts
+            .iter()
+            .flat_map(|path| message_times(path))
+            .collect();
+        Self::from_times(times)
+    }
+
+    /// Build patterns from local message times in any order
+    fn from_times(mut times: Vec<NaiveDateTime>) -> Self {
+        times.sort();
+        times.dedup();
+
+        let mut hourly = vec![0; 24];
+        for time in &times {
+            hourly[time.hour() as usize] += 1;
+        }
+        let late_night: usize = hourly[22..].iter().chain(&hourly[..6]).sum();
+        let peak_hour = (0..24u32)
+            .filter(|&h| hourly[h as usize] > 0)
+            .max_by_key(|&h| (hourly[h as usize], std::cmp::Reverse(h)));
+
+        let mut focus_blocks = Vec::new();
+        let mut block: Option<(NaiveDateTime, NaiveDateTime)> = None;
+        for &time in &times {
+            block = match block {
+                Some((start, end)) if (time - end).num_minutes() < FOCUS_GAP_MINUTES => {
+                    Some((start, time))
+                }
+                Some((start, end)) => {
+                    focus_blocks.push(focus_block(start, end));
+                    Some((time, time))
+                }
+                None => Some((time, time)),
+            };
+        }
+        if let Some((start, end)) = block {
+            focus_blocks.push(focus_block(start, end));
+        }
+        focus_blocks.retain(|b| b.minutes > 0);
+        focus_blocks.sort_by_key(|b| std::cmp::Reverse(b.minutes));
+        focus_blocks.truncate(TOP_FOCUS_BLOCKS);
+
+        Self {
+            late_night_ratio: if times.is_empty() {
+                0.0
+            } else {
+                late_night as f64 / times.len() as f64 * 100.0
+            },
+            hourly,
+            peak_hour,
+            longest_focus_minutes: focus_blocks.first().map_or(0, |b| b.minutes),
+            focus_blocks,
+        }
+    }
+}
+
+fn focus_block(start: NaiveDateTime, end: NaiveDateTime) -> FocusBlock {
+    FocusBlock {
+        date: start.format("%Y-%m-%d").to_string(),
+        start: start.format("%H:%M").to_string(),
+        end: end.format("%H:%M").to_string(),
+        minutes: (end - start).num_minutes(),
+    }
+}
+
+/// Local times of the user and assistant messages in a JSONL transcript
+fn message_times(path: &Path) -> Vec<NaiveDateTime> {
+    let Ok(reader) = transcript::open(path) else {
+        return Vec::new();
+    };
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let entry: serde_json::Value = serde_json::from_str(&line).ok()?;
+            let kind = entry.get("type").and_then(|t| t.as_str())?;
+            if kind != "user" && kind != "assistant" {
+                return None;
+            }
+            let timestamp = entry.get("timestamp").and_then(|t| t.as_str())?;
+            DateTime::parse_from_rfc3339(timestamp)
+                .ok()
+                .map(|t| t.with_timezone(&Local).naive_local())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_focus_blocks_and_late_night_ratio() {
+        let patterns = WorkPatterns::from_times(vec![
+            at("2024-01-01 09:00"),
+            at("2024-01-01 09:10"),
+            at("2024-01-01 09:20"),
+            // A 40 minute break starts a new block
+            at("2024-01-01 10:00"),
+            at("2024-01-01 10:05"),
+            at("2024-01-01 23:30"),
+            at("2024-01-01 23:40"),
+            at("2024-01-02 00:10"),
+        ]);
+
+        assert_eq!(patterns.hourly[9], 3);
+        assert_eq!(patterns.peak_hour, Some(9));
+        assert_eq!(patterns.late_night_ratio, 37.5);
+        assert_eq!(patterns.longest_focus_minutes, 20);
+        let blocks: Vec<(&str, &str, i64)> = patterns
+            .focus_blocks
+            .iter()
+            .map(|b| (b.start.as_str(), b.end.as_str(), b.minutes))
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                ("09:00", "09:20", 20),
+                ("23:30", "23:40", 10),
+                ("10:00", "10:05", 5)
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::collector::{CategoryCount, SessionInsight};
+
+/// Insights of the sessions in one project, for `group_by=project`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSegment {
+    /// Last component of the sessions' working directory, or "(unknown)"
+    pub project: String,
+    pub session_count: usize,
+    /// Dates with at least one session in the project
+    pub active_days: usize,
+    /// Percentage of sessions with friction, among those with facet data
+    pub friction_rate: f64,
+    /// Percentage of sessions that achieved or partially achieved their
+    /// goal, among those with an outcome
+    pub success_rate: f64,
+    pub goal_distribution: Vec<CategoryCount>,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+}
+
+/// Label for sessions whose archive records no working directory
+const UNKNOWN_PROJECT: &str = "(unknown)";
+
+/// Group sessions by project, busiest project first
+pub fn segment(sessions: &[SessionInsight]) -> Vec<ProjectSegment> {
+    let mut by_project: BTreeMap<&str, Vec<&SessionInsight>> = BTreeMap::new();
+    for session in sessions {
+        let project = session.project.as_deref().unwrap_or(UNKNOWN_PROJECT);
+        by_project.entry(project).or_default().push(session);
+    }
+
+    let mut segments: Vec<ProjectSegment> = by_project
+        .into_iter()
+        .map(|(project, sessions)| {
+            let active_days: BTreeSet<&str> = sessions.iter().map(|s| s.date.as_str()).collect();
+            let with_outcome: Vec<&str> = sessions
+                .iter()
+                .filter_map(|s| s.outcome.as_deref())
+                .collect();
+            let successful = with_outcome
+                .iter()
+                .filter(|o| matches!(**o, "achieved" | "partially_achieved"))
+                .count();
+            // Sessions without a facet have no friction data either way
+            let with_facet = sessions.iter().filter(|s| s.outcome.is_some()).count();
+            let with_friction = sessions
+                .iter()
+                .filter(|s| !s.friction_types.is_empty())
+                .count();
+
+            let mut goals: BTreeMap<&str, usize> = BTreeMap::new();
+            for goal in sessions.iter().flat_map(|s| &s.goal_categories) {
+                *goals.entry(goal).or_insert(0) += 1;
+            }
+            let mut goal_distribution: Vec<CategoryCount> = goals
+                .into_iter()
+                .map(|(name, count)| CategoryCount {
+                    name: name.to_string(),
+                    count,
+                })
+                .collect();
+            goal_distribution.sort_by_key(|c| Reverse(c.count));
+
+            let usages = sessions.iter().filter_map(|s| s.token_usage.as_ref());
+            ProjectSegment {
+                project: project.to_string(),
+                session_count: sessions.len(),
+                active_days: active_days.len(),
+                friction_rate: percentage(with_friction, with_facet),
+                success_rate: percentage(successful, with_outcome.len()),
+                goal_distribution,
+                total_tokens: usages
+                    .clone()
+                    .map(|u| {
+                        u.input_tokens
+                            + u.output_tokens
+                            + u.cache_creation_tokens
+                            + u.cache_read_tokens
+                    })
+                    .sum(),
+                total_cost: usages.map(|u| u.total_cost_usd).sum(),
+            }
+        })
+        .collect();
+    segments.sort_by_key(|s| Reverse(s.session_count));
+    segments
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(date: &str, project: Option<&str>, outcome: Option<&str>) -> SessionInsight {
+        SessionInsight {
+            session_id: format!("{}-{:?}", date, project),
+            date: date.to_string(),
+            session_name: "session".to_string(),
+            brief_summary: None,
+            outcome: outcome.map(String::from),
+            goal_categories: vec!["debugging".to_string()],
+            friction_types: if outcome == Some("not_achieved") {
+                vec!["wrong_approach".to_string()]
+            } else {
+                Vec::new()
+            },
+            friction_detail: None,
+            satisfaction: None,
+            stars: None,
+            claude_helpfulness: None,
+            session_type: None,
+            project: project.map(String::from),
+            token_usage: None,
+        }
+    }
+
+    #[test]
+    fn test_segment_by_project() {
+        let segments = segment(&[
+            session("2026-01-15", Some("app"), Some("achieved")),
+            session("2026-01-16", Some("app"), Some("not_achieved")),
+            session("2026-01-16", Some("app"), None),
+            session("2026-01-16", Some("site"), Some("partially_achieved")),
+            session("2026-01-16", None, None),
+        ]);
+
+        assert_eq!(segments.len(), 3);
+        let app = &segments[0];
+        assert_eq!(app.project, "app");
+        assert_eq!(app.session_count, 3);
+        assert_eq!(app.active_days, 2);
+        assert_eq!(app.success_rate, 50.0);
+        assert_eq!(app.friction_rate, 50.0);
+        assert_eq!(app.goal_distribution[0].count, 3);
+        assert!(segments
+            .iter()
+            .any(|s| s.project == "(unknown)" && s.success_rate == 0.0));
+    }
+}
@@ -0,0 +1,272 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::archive::{transcript, ArchiveManager};
+use crate::config::Config;
+
+/// Aggregated tool usage statistics across archived sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolInsights {
+    pub total_calls: usize,
+    pub total_failures: usize,
+    pub failure_rate: f64,
+    /// Longest run of tool calls without an intervening user prompt
+    pub longest_chain: usize,
+    /// Per-tool totals, most used first
+    pub tools: Vec<ToolStat>,
+    /// Per-session breakdown, newest date first
+    pub sessions: Vec<SessionToolUsage>,
+}
+
+/// Call and failure counts for a single tool
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStat {
+    pub name: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+}
+
+/// Tool usage for a single archived session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionToolUsage {
+    pub date: String,
+    pub session_name: String,
+    pub total_calls: usize,
+    pub failures: usize,
+    pub longest_chain: usize,
+    pub tools: Vec<ToolStat>,
+}
+
+/// Raw per-transcript tool counts before aggregation
+#[derive(Debug, Default)]
+struct ToolCounts {
+    /// Tool name -> (calls, failures)
+    by_tool: HashMap<String, (usize, usize)>,
+    longest_chain: usize,
+}
+
+impl ToolCounts {
+    fn total_calls(&self) -> usize {
+        self.by_tool.values().map(|(c, _)| c).sum()
+    }
+
+    fn total_failures(&self) -> usize {
+        self.by_tool.values().map(|(_, f)| f).sum()
+    }
+}
+
+impl ToolInsights {
+    /// Collect tool usage from the transcripts referenced by archived sessions
+    /// of the most recent `days` dates.
+    pub fn collect(config: &Config, days: Option<usize>) -> anyhow::Result<Self> {
+        let manager = ArchiveManager::new(config.clone());
+        let dates: Vec<String> = manager
+            .list_dates()?
+            .into_iter()
+            .take(days.unwrap_or(30))
+            .collect();
+
+        let mut totals: HashMap<String, (usize, usize)> = HashMap::new();
+        let mut sessions = Vec::new();
+        let mut longest_chain = 0;
+
+        for date in &dates {
+            for session_name in manager.list_sessions(date).unwrap_or_default() {
+                let Ok(content) = manager.read_session(date, &session_name) else {
+                    continue;
+                };
+                let Some(transcript_path) = transcript::resolve(config, date, &content) else {
+                    continue;
+                };
+                let Some(counts) = parse_tool_usage(&transcript_path) else {
+                    continue;
+                };
+
+                for (name, (calls, failures)) in &counts.by_tool {
+                    let entry = totals.entry(name.clone()).or_insert((0, 0));
+                    entry.0 += calls;
+                    entry.1 += failures;
+                }
+                longest_chain = longest_chain.max(counts.longest_chain);
+
+                sessions.push(SessionToolUsage {
+                    date: date.clone(),
+                    session_name,
+                    total_calls: counts.total_calls(),
+                    failures: counts.total_failures(),
+                    longest_chain: counts.longest_chain,
+                    tools: to_tool_stats(&counts.by_tool),
+                });
+            }
+        }
+
+        let total_calls = totals.values().map(|(c, _)| c).sum();
+        let total_failures = totals.values().map(|(_, f)| f).sum();
+
+        Ok(ToolInsights {
+            total_calls,
+            total_failures,
+            failure_rate: rate(total_failures, total_calls),
+            longest_chain,
+            tools: to_tool_stats(&totals),
+            sessions,
+        })
+    }
+}
+
+/// Convert raw counts into sorted tool stats (most calls first, then by name)
+fn to_tool_stats(counts: &HashMap<String, (usize, usize)>) -> Vec<ToolStat> {
+    let mut stats: Vec<ToolStat> = counts
+        .iter()
+        .map(|(name, (calls, failures))| ToolStat {
+            name: name.clone(),
+            calls: *calls,
+            failures: *failures,
+            failure_rate: rate(*failures, *calls),
+        })
+        .collect();
+    stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
+/// Percentage of `part` in `total`, 0 when total is zero
+fn rate(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+/// Parse a JSONL transcript and count tool_use blocks, tool_result errors,
+/// and the longest chain of tool calls between user prompts.
+fn parse_tool_usage(path: &Path) -> Option<ToolCounts> {
+    let reader = transcript::open(path).ok()?;
+    let lines = reader.lines().map_while(Result::ok);
+    Some(count_tool_usage(lines))
+}
+
+fn count_tool_usage(lines: impl Iterator<Item = String>) -> ToolCounts {
+    let mut counts = ToolCounts::default();
+    // tool_use id -> tool name, for attributing tool_result errors
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    let mut chain = 0;
+
+    for line in lines {
+        if !line.contains("tool_use") && !line.contains("\"type\":\"user\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let content = entry.get("message").and_then(|m| m.get("content"));
+
+        match entry.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let Some(blocks) = content.and_then(|c| c.as_array()) else {
+                    continue;
+                };
+                for block in blocks {
+                    if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                        continue;
+                    }
+                    let name = block
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    if let Some(id) = block.get("id").and_then(|i| i.as_str()) {
+                        tool_names.insert(id.to_string(), name.clone());
+                    }
+                    counts.by_tool.entry(name).or_insert((0, 0)).0 += 1;
+                    chain += 1;
+                    counts.longest_chain = counts.longest_chain.max(chain);
+                }
+            }
+            Some("user") => {
+                let mut has_tool_result = false;
+                if let Some(blocks) = content.and_then(|c| c.as_array()) {
+                    for block in blocks {
+                        if block.get("type").and_then(|t| t.as_str()) != Some("tool_result") {
+                            continue;
+                        }
+                        has_tool_result = true;
+                        let is_error = block
+                            .get("is_error")
+                            .and_then(|e| e.as_bool())
+                            .unwrap_or(false);
+                        if !is_error {
+                            continue;
+                        }
+                        let name = block
+                            .get("tool_use_id")
+                            .and_then(|i| i.as_str())
+                            .and_then(|id| tool_names.get(id))
+                            .cloned()
+                            .unwrap_or_else(|| "unknown".to_string());
+                        counts.by_tool.entry(name).or_insert((0, 0)).1 += 1;
+                    }
+                }
+                // A genuine user prompt ends the current chain
+                if !has_tool_result {
+                    chain = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> impl Iterator<Item = String> {
+        raw.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_count_tool_usage() {
+        let counts = count_tool_usage(lines(&[
+            r#"{"type":"user","message":{"role":"user","content":"fix the build"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":"exit 1"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t2","name":"Edit","input":{}},{"type":"tool_use","id":"t3","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":"ok"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"thanks, now read the docs"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t4","name":"mcp__docs__search","input":{}}]}}"#,
+        ]));
+
+        assert_eq!(counts.by_tool.get("Bash"), Some(&(2, 1)));
+        assert_eq!(counts.by_tool.get("Edit"), Some(&(1, 0)));
+        assert_eq!(counts.by_tool.get("mcp__docs__search"), Some(&(1, 0)));
+        assert_eq!(counts.total_calls(), 4);
+        assert_eq!(counts.total_failures(), 1);
+        assert_eq!(counts.longest_chain, 3);
+    }
+
+    #[test]
+    fn test_to_tool_stats_sorted() {
+        let mut counts = HashMap::new();
+        counts.insert("Read".to_string(), (3, 0));
+        counts.insert("Bash".to_string(), (4, 1));
+        counts.insert("Edit".to_string(), (3, 0));
+        let stats = to_tool_stats(&counts);
+        let names: Vec<&str> = stats.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Bash", "Edit", "Read"]);
+        assert!((stats[0].failure_rate - 25.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_rate_zero_total() {
+        assert_eq!(rate(0, 0), 0.0);
+    }
+}
@@ -1,5 +1,5 @@
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::archive::ArchiveManager;
 use crate::config::Config;
@@ -60,7 +60,7 @@ impl TrendData {
         let manager = ArchiveManager::new(config.clone());
 
         // Load all facets indexed by session_id
-        let all_facets = SessionFacet::load_all().unwrap_or_default();
+        let all_facets = SessionFacet::load_all(&config.insights.aliases).unwrap_or_default();
         let facet_map: HashMap<String, SessionFacet> = all_facets.into_iter().collect();
 
         // Build a mapping: date -> Vec<SessionFacet> by reading session frontmatter
@@ -123,8 +123,9 @@ impl TrendData {
         let previous_success_rate = calc_success_rate(&previous_facets);
         let current_success_rate = calc_success_rate(&current_facets);
 
-        let previous_satisfaction_score = calc_satisfaction_score(&previous_facets);
-        let current_satisfaction_score = calc_satisfaction_score(&current_facets);
+        let weights = &config.insights.satisfaction_weights;
+        let previous_satisfaction_score = calc_satisfaction_score(&previous_facets, weights);
+        let current_satisfaction_score = calc_satisfaction_score(&current_facets, weights);
 
         // Calculate percentage changes
         let sessions_change_pct = pct_change(previous_sessions as f64, current_sessions as f64);
@@ -183,7 +184,7 @@ fn extract_session_id_from_frontmatter(content: &str) -> Option<String> {
 }
 
 /// Calculate friction rate: fraction of sessions that have any friction counts
-fn calc_friction_rate(facets: &[&SessionFacet]) -> f64 {
+pub(super) fn calc_friction_rate(facets: &[&SessionFacet]) -> f64 {
     if facets.is_empty() {
         return 0.0;
     }
@@ -198,7 +199,7 @@ fn calc_friction_rate(facets: &[&SessionFacet]) -> f64 {
 }
 
 /// Calculate success rate: fraction of sessions with "achieved" or "partially_achieved" outcome
-fn calc_success_rate(facets: &[&SessionFacet]) -> f64 {
+pub(super) fn calc_success_rate(facets: &[&SessionFacet]) -> f64 {
     if facets.is_empty() {
         return 0.0;
     }
@@ -214,21 +215,15 @@ fn calc_success_rate(facets: &[&SessionFacet]) -> f64 {
     (successful as f64 / facets.len() as f64) * 100.0
 }
 
-/// Calculate weighted satisfaction score (0-100):
-/// happy=100, likely_satisfied=75, neutral=50, frustrated=25
-fn calc_satisfaction_score(facets: &[&SessionFacet]) -> f64 {
+/// Calculate weighted satisfaction score (0-100) from
+/// `insights.satisfaction_weights`; unlisted levels score 50
+fn calc_satisfaction_score(facets: &[&SessionFacet], weights: &BTreeMap<String, f64>) -> f64 {
     let mut total_weight = 0.0;
     let mut total_count = 0usize;
 
     for facet in facets {
         for (key, &count) in &facet.user_satisfaction_counts {
-            let weight = match key.as_str() {
-                "happy" => 100.0,
-                "likely_satisfied" => 75.0,
-                "neutral" => 50.0,
-                "frustrated" => 25.0,
-                _ => 50.0, // default for unknown categories
-            };
+            let weight = weights.get(key).copied().unwrap_or(50.0);
             total_weight += weight * count as f64;
             total_count += count;
         }
@@ -242,7 +237,7 @@ fn calc_satisfaction_score(facets: &[&SessionFacet]) -> f64 {
 
 /// Calculate percentage change between previous and current values.
 /// Returns 0.0 if the previous value is zero.
-fn pct_change(previous: f64, current: f64) -> f64 {
+pub(super) fn pct_change(previous: f64, current: f64) -> f64 {
     if previous.abs() < f64::EPSILON {
         if current.abs() < f64::EPSILON {
             0.0
@@ -332,6 +327,7 @@ fn format_week_label(date: chrono::NaiveDate) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::FacetAliases;
 
     #[test]
     fn test_pct_change_zero_base() {
@@ -373,7 +369,32 @@ mod tests {
     #[test]
     fn test_calc_satisfaction_score_empty() {
         let facets: Vec<&SessionFacet> = vec![];
-        assert_eq!(calc_satisfaction_score(&facets), 0.0);
+        let weights = Config::default().insights.satisfaction_weights;
+        assert_eq!(calc_satisfaction_score(&facets, &weights), 0.0);
+    }
+
+    #[test]
+    fn test_calc_satisfaction_score_with_aliases_and_weights() {
+        let mut facet = SessionFacet {
+            user_satisfaction_counts: HashMap::from([
+                ("delighted".to_string(), 1),
+                ("happy".to_string(), 1),
+                ("meh".to_string(), 2),
+            ]),
+            ..Default::default()
+        };
+        let aliases = FacetAliases {
+            satisfaction: BTreeMap::from([("delighted".to_string(), "happy".to_string())]),
+            ..Default::default()
+        };
+        facet.apply_aliases(&aliases);
+        assert_eq!(facet.user_satisfaction_counts["happy"], 2);
+
+        // Unlisted levels ("meh") score 50
+        let weights = Config::default().insights.satisfaction_weights;
+        assert_eq!(calc_satisfaction_score(&[&facet], &weights), 75.0);
+        let weights = BTreeMap::from([("happy".to_string(), 90.0), ("meh".to_string(), 30.0)]);
+        assert_eq!(calc_satisfaction_score(&[&facet], &weights), 60.0);
     }
 
     #[test]
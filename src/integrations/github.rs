@@ -0,0 +1,314 @@
+//! Push "Tomorrow's Focus" items from a digest to GitHub issues.
+//!
+//! Each focus card becomes one labeled issue. The issue body carries a hidden
+//! `<!-- daily-focus:{date}:{key} -->` marker, so pushing the same digest again
+//! updates the existing issues instead of opening duplicates. Cards whose title
+//! carries ✅ close their issue.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::archive::SummaryCard;
+use crate::config::Config;
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const PAGE_SIZE: usize = 100;
+const DONE_MARK: &str = "✅";
+
+/// One action item parsed from the digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusItem {
+    /// Stable identity within the date, derived from the title
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub done: bool,
+}
+
+/// What pushing an item did (or would do on a dry run)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushAction {
+    Created,
+    Updated,
+    Closed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PushResult {
+    pub title: String,
+    pub action: PushAction,
+    /// Issue number; None for items a dry run would create
+    pub number: Option<u64>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    html_url: String,
+    /// Present when the "issue" is actually a pull request
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+/// Turn Tomorrow's Focus cards into issue items linking back to `date`
+pub fn focus_items(date: &str, cards: &[SummaryCard]) -> Vec<FocusItem> {
+    cards
+        .iter()
+        .filter_map(|card| {
+            let done = card.title.contains(DONE_MARK);
+            let title = card.title.replace(DONE_MARK, "").trim().to_string();
+            let key = slug(&title);
+            if key.is_empty() {
+                return None;
+            }
+            let body = format!(
+                "{}\n\n---\nFrom the daily digest of {} (`daily view --date {} --summary-only`)\n\n{}",
+                card.content.trim(),
+                date,
+                date,
+                marker(date, &key)
+            );
+            Some(FocusItem {
+                key,
+                title,
+                body,
+                done,
+            })
+        })
+        .collect()
+}
+
+fn marker(date: &str, key: &str) -> String {
+    format!("<!-- daily-focus:{}:{} -->", date, key)
+}
+
+/// Lowercase alphanumeric words joined by `-`, keeping non-ASCII letters
+fn slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Minimal GitHub REST client for the issues API
+pub struct GithubClient {
+    api_url: String,
+    repo: String,
+    label: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl GithubClient {
+    pub fn from_config(config: &Config, repo: &str, label: Option<String>) -> Result<Self> {
+        let settings = &config.integrations.github;
+        if repo.split('/').filter(|part| !part.is_empty()).count() != 2 {
+            anyhow::bail!("Invalid repository '{}'. Use 'owner/name'", repo);
+        }
+        let token = std::env::var(&settings.token_env)
+            .ok()
+            .filter(|token| !token.is_empty())
+            .with_context(|| format!("GitHub token not found in ${}", settings.token_env))?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .user_agent(concat!("daily/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self {
+            api_url: settings
+                .api_url
+                .as_deref()
+                .unwrap_or(DEFAULT_API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            repo: repo.to_string(),
+            label: label.unwrap_or_else(|| settings.label.clone()),
+            token,
+            http,
+        })
+    }
+
+    /// Create, update or close one issue per item; a dry run only reports
+    pub async fn push(
+        &self,
+        date: &str,
+        items: &[FocusItem],
+        dry_run: bool,
+    ) -> Result<Vec<PushResult>> {
+        let existing = self.labeled_issues().await?;
+        let mut results = Vec::with_capacity(items.len());
+
+        for item in items {
+            let marker = marker(date, &item.key);
+            let found = existing
+                .iter()
+                .find(|issue| issue.body.as_deref().is_some_and(|b| b.contains(&marker)));
+
+            let result = match found {
+                None => {
+                    let created = if dry_run {
+                        None
+                    } else {
+                        Some(self.create_issue(item).await?)
+                    };
+                    PushResult {
+                        title: item.title.clone(),
+                        action: PushAction::Created,
+                        number: created.as_ref().map(|i| i.number),
+                        url: created.map(|i| i.html_url),
+                    }
+                }
+                Some(issue) => {
+                    let close = item.done && issue.state == "open";
+                    let changed = issue.title != item.title
+                        || issue.body.as_deref() != Some(item.body.as_str());
+                    let action = match (close, changed) {
+                        (true, _) => PushAction::Closed,
+                        (false, true) => PushAction::Updated,
+                        (false, false) => PushAction::Unchanged,
+                    };
+                    if !dry_run && action != PushAction::Unchanged {
+                        self.update_issue(issue.number, item, close).await?;
+                    }
+                    PushResult {
+                        title: item.title.clone(),
+                        action,
+                        number: Some(issue.number),
+                        url: Some(issue.html_url.clone()),
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// All issues (open and closed) carrying the focus label
+    async fn labeled_issues(&self) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let batch: Vec<Issue> = self
+                .send(self.http.get(self.url("issues")).query(&[
+                    ("labels", self.label.as_str()),
+                    ("state", "all"),
+                    ("per_page", &PAGE_SIZE.to_string()),
+                    ("page", &page.to_string()),
+                ]))
+                .await
+                .context("Failed to list GitHub issues")?;
+            let last = batch.len() < PAGE_SIZE;
+            issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+            if last {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn create_issue(&self, item: &FocusItem) -> Result<Issue> {
+        let mut issue: Issue = self
+            .send(self.http.post(self.url("issues")).json(&serde_json::json!({
+                "title": item.title,
+                "body": item.body,
+                "labels": [self.label],
+            })))
+            .await
+            .with_context(|| format!("Failed to create issue '{}'", item.title))?;
+        if item.done {
+            issue = self.update_issue(issue.number, item, true).await?;
+        }
+        Ok(issue)
+    }
+
+    async fn update_issue(&self, number: u64, item: &FocusItem, close: bool) -> Result<Issue> {
+        let mut patch = serde_json::json!({ "title": item.title, "body": item.body });
+        if close {
+            patch["state"] = "closed".into();
+        }
+        self.send(
+            self.http
+                .patch(self.url(&format!("issues/{}", number)))
+                .json(&patch),
+        )
+        .await
+        .with_context(|| format!("Failed to update issue #{}", number))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/repos/{}/{}", self.api_url, self.repo, path)
+    }
+
+    async fn send<T: for<'de> Deserialize<'de>>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("GitHub API returned {}: {}", status, body.trim());
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(title: &str, content: &str) -> SummaryCard {
+        SummaryCard {
+            title: title.into(),
+            content: content.into(),
+        }
+    }
+
+    #[test]
+    fn test_focus_items() {
+        let items = focus_items(
+            "2024-01-15",
+            &[
+                card("Ship the parser", "- Add tests\n- Release"),
+                card("✅ Fix CI flake", "Done"),
+                card("✅", "Nothing left"),
+            ],
+        );
+        assert_eq!(items.len(), 2);
+
+        assert_eq!(items[0].key, "ship-the-parser");
+        assert_eq!(items[0].title, "Ship the parser");
+        assert!(!items[0].done);
+        assert!(items[0].body.starts_with("- Add tests\n- Release"));
+        assert!(items[0]
+            .body
+            .contains("<!-- daily-focus:2024-01-15:ship-the-parser -->"));
+
+        // The done marker doesn't change the identity, so a later ✅ closes the same issue
+        assert_eq!(items[1].key, "fix-ci-flake");
+        assert_eq!(items[1].title, "Fix CI flake");
+        assert!(items[1].done);
+    }
+
+    #[test]
+    fn test_slug_keeps_unicode_words() {
+        assert_eq!(slug("重构 解析器: v2!"), "重构-解析器-v2");
+        assert_eq!(slug("  --  "), "");
+    }
+}
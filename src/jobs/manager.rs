@@ -1,24 +1,57 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::config::Config;
+use super::orphans::{self, Orphan};
+use super::JobCommand;
+use crate::archive::atomic::write_atomic;
+use crate::config::{Config, JobRetentionConfig};
+use crate::summarizer::{CallTimeout, ModelUsage, QualityReport};
+use crate::usage::overhead;
+use crate::usage::pricing::{ModelPricing, PricingData};
 
 /// Maximum log file size in bytes (1MB)
 const MAX_LOG_SIZE: u64 = 1024 * 1024;
 
+/// How often a queued job re-checks whether it may start
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls to wait for the spawner to register a job before running unqueued
+const QUEUE_REGISTER_POLLS: u32 = 10;
+
+/// A queue lock older than this is assumed to belong to a crashed process
+const QUEUE_LOCK_STALE_SECS: u64 = 30;
+
+/// A digest lock older than this is stale even if its pid looks alive
+/// (pids are reused, and liveness can't be checked off Unix)
+const DIGEST_LOCK_STALE_SECS: u64 = 2 * 60 * 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum JobStatus {
+    /// Waiting for a free slot (see `jobs.max_concurrent`)
+    Queued,
     Running,
     Completed,
-    Failed { error: String },
+    Failed {
+        error: String,
+    },
+}
+
+impl JobStatus {
+    /// Whether the job still has a live process (queued or running)
+    pub fn is_active(&self) -> bool {
+        matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
 }
 
 impl std::fmt::Display for JobStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            JobStatus::Queued => write!(f, "Queued"),
             JobStatus::Running => write!(f, "Running"),
             JobStatus::Completed => write!(f, "Completed"),
             JobStatus::Failed { error } => write!(f, "Failed: {}", error),
@@ -30,15 +63,109 @@ impl std::fmt::Display for JobStatus {
 pub enum JobType {
     SessionEnd,
     AutoSummarize,
+    Backfill,
+    /// Incremental summary written by the Stop hook
+    Checkpoint,
+    /// Daily digest generation
+    Digest,
+    /// Topic index pages refresh
+    Topics,
+    /// Weekly anomaly check
+    Anomalies,
     #[default]
     Manual,
 }
 
+impl JobType {
+    pub const ALL: [JobType; 8] = [
+        JobType::SessionEnd,
+        JobType::AutoSummarize,
+        JobType::Backfill,
+        JobType::Checkpoint,
+        JobType::Digest,
+        JobType::Topics,
+        JobType::Anomalies,
+        JobType::Manual,
+    ];
+
+    /// Queue priority, higher starts first: manual work, then digests,
+    /// then automatic summarizations, with bulk backfill last
+    pub fn priority(&self) -> u8 {
+        match self {
+            JobType::Manual => 3,
+            JobType::Digest => 2,
+            JobType::SessionEnd | JobType::AutoSummarize | JobType::Checkpoint => 1,
+            JobType::Backfill | JobType::Topics | JobType::Anomalies => 0,
+        }
+    }
+
+    /// Whether an interrupted job of this type is started again when the
+    /// dashboard restarts. Checkpoints are redone by the next Stop hook and
+    /// the bulk jobs by their own schedules.
+    pub fn is_resumable(&self) -> bool {
+        matches!(
+            self,
+            JobType::SessionEnd | JobType::AutoSummarize | JobType::Digest
+        )
+    }
+
+    /// Whether the job writes a session archive, which the day's digest
+    /// then reads
+    pub fn is_summarization(&self) -> bool {
+        matches!(
+            self,
+            JobType::SessionEnd
+                | JobType::AutoSummarize
+                | JobType::Backfill
+                | JobType::Checkpoint
+                | JobType::Manual
+        )
+    }
+
+    /// Snake-case name used by the API, e.g. "session_end"
+    pub fn key(&self) -> &'static str {
+        match self {
+            JobType::SessionEnd => "session_end",
+            JobType::AutoSummarize => "auto_summarize",
+            JobType::Backfill => "backfill",
+            JobType::Checkpoint => "checkpoint",
+            JobType::Digest => "digest",
+            JobType::Topics => "topics",
+            JobType::Anomalies => "anomalies",
+            JobType::Manual => "manual",
+        }
+    }
+}
+
+impl std::str::FromStr for JobType {
+    type Err = anyhow::Error;
+
+    /// Parse the API key, e.g. "auto_summarize"
+    fn from_str(s: &str) -> Result<Self> {
+        JobType::ALL
+            .into_iter()
+            .find(|t| t.key() == s)
+            .with_context(|| {
+                let keys: Vec<&str> = JobType::ALL.iter().map(JobType::key).collect();
+                format!(
+                    "Unknown job type '{}', expected one of: {}",
+                    s,
+                    keys.join(", ")
+                )
+            })
+    }
+}
+
 impl std::fmt::Display for JobType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             JobType::SessionEnd => write!(f, "Session End"),
             JobType::AutoSummarize => write!(f, "Auto Summarize"),
+            JobType::Backfill => write!(f, "Backfill"),
+            JobType::Checkpoint => write!(f, "Checkpoint"),
+            JobType::Digest => write!(f, "Digest"),
+            JobType::Topics => write!(f, "Topics"),
+            JobType::Anomalies => write!(f, "Anomalies"),
             JobType::Manual => write!(f, "Manual"),
         }
     }
@@ -55,6 +182,25 @@ pub struct JobInfo {
     pub status: JobStatus,
     #[serde(default)]
     pub job_type: JobType,
+    /// When the job entered the queue; `started_at` is reset once it runs
+    #[serde(default)]
+    pub queued_at: Option<DateTime<Local>>,
+    /// Tokens used by the job's model calls, as reported by the Claude CLI
+    #[serde(default)]
+    pub usage: Option<ModelUsage>,
+    /// Quality score of the summary or digest the job generated
+    #[serde(default)]
+    pub quality: Option<QualityReport>,
+    /// Jobs that must finish before this one may start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Failed because a model call hit `summarization.call_timeout_secs`
+    #[serde(default)]
+    pub timed_out: bool,
+    /// How the job was started, so an interrupted one can be started again
+    /// with the same arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<JobCommand>,
 }
 
 impl JobInfo {
@@ -69,6 +215,28 @@ impl JobInfo {
         end - self.started_at
     }
 
+    /// Date of the archive a summarization job writes: backfill archives at
+    /// the transcript's modification time, the others when they run
+    pub fn archive_date(&self) -> String {
+        let at = match self.job_type {
+            JobType::Backfill => fs::metadata(&self.transcript_path)
+                .and_then(|m| m.modified())
+                .map(DateTime::<Local>::from)
+                .unwrap_or(self.started_at),
+            _ => self.queued_at.unwrap_or(self.started_at),
+        };
+        at.format("%Y-%m-%d").to_string()
+    }
+
+    /// Dependencies still queued or running among `jobs`
+    pub fn waiting_on<'a>(&'a self, jobs: &[JobInfo]) -> Vec<&'a str> {
+        self.depends_on
+            .iter()
+            .filter(|dep| jobs.iter().any(|j| &j.id == *dep && j.status.is_active()))
+            .map(String::as_str)
+            .collect()
+    }
+
     /// Format elapsed time as human-readable string
     pub fn elapsed_human(&self) -> String {
         let elapsed = self.elapsed();
@@ -86,6 +254,7 @@ impl JobInfo {
 
 pub struct JobManager {
     jobs_dir: PathBuf,
+    price_overrides: BTreeMap<String, ModelPricing>,
 }
 
 impl JobManager {
@@ -94,7 +263,10 @@ impl JobManager {
         let jobs_dir = config.storage_path().join("jobs");
         fs::create_dir_all(&jobs_dir).context("Failed to create jobs directory")?;
 
-        Ok(Self { jobs_dir })
+        Ok(Self {
+            jobs_dir,
+            price_overrides: config.usage.price_overrides.clone(),
+        })
     }
 
     /// Generate a unique job ID
@@ -114,7 +286,10 @@ impl JobManager {
         self.jobs_dir.join(format!("{}.log", job_id))
     }
 
-    /// Register a new job
+    /// Register a new job. It starts out queued until its process
+    /// obtains a slot via [`JobManager::wait_for_slot`]. Jobs are started
+    /// with [`super::spawn`], which registers them itself.
+    #[cfg(test)]
     pub fn register(
         &self,
         job_id: &str,
@@ -123,26 +298,82 @@ impl JobManager {
         transcript_path: &Path,
         job_type: JobType,
     ) -> Result<JobInfo> {
+        let command = JobCommand::new(job_type, task_name, transcript_path, Vec::<String>::new());
+        self.register_command(job_id, pid, &command)
+    }
+
+    /// Register the job started for `command`, which stays queued until the
+    /// command's `depends_on` jobs have finished, whether they succeed or not
+    pub fn register_command(
+        &self,
+        job_id: &str,
+        pid: u32,
+        command: &JobCommand,
+    ) -> Result<JobInfo> {
+        let now = Local::now();
         let info = JobInfo {
             id: job_id.to_string(),
             pid,
-            task_name: task_name.to_string(),
-            transcript_path: transcript_path.to_path_buf(),
-            started_at: Local::now(),
+            task_name: command.task_name.clone(),
+            transcript_path: command.target.clone(),
+            started_at: now,
             finished_at: None,
-            status: JobStatus::Running,
-            job_type,
+            status: JobStatus::Queued,
+            job_type: command.job_type.clone(),
+            queued_at: Some(now),
+            usage: None,
+            quality: None,
+            depends_on: command.depends_on.clone(),
+            timed_out: false,
+            command: Some(command.clone()),
         };
 
         self.save_job(&info)?;
         Ok(info)
     }
 
+    /// Wait until this job may run, then mark it running.
+    ///
+    /// A job starts once fewer than `max_concurrent` jobs are running and it is
+    /// first in the queue (highest priority, then FIFO) among the jobs whose
+    /// dependencies have all finished. Jobs that were never
+    /// registered run immediately so a missing record can't block work.
+    pub async fn wait_for_slot(&self, job_id: &str, max_concurrent: usize) -> Result<()> {
+        let mut missing_polls = 0;
+        loop {
+            {
+                let _lock = QueueLock::acquire(&self.jobs_dir)?;
+                match self.load_job(job_id) {
+                    Ok(mut info) => {
+                        if info.status != JobStatus::Queued {
+                            return Ok(());
+                        }
+                        let jobs = self.list(false)?;
+                        if can_start(job_id, &jobs, max_concurrent) {
+                            info.status = JobStatus::Running;
+                            info.started_at = Local::now();
+                            self.save_job(&info)?;
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => {
+                        // The spawner registers right after spawning us; give it a moment
+                        missing_polls += 1;
+                        if missing_polls > QUEUE_REGISTER_POLLS {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+        }
+    }
+
     /// Save job info to disk
     fn save_job(&self, info: &JobInfo) -> Result<()> {
         let path = self.job_path(&info.id);
         let content = serde_json::to_string_pretty(info)?;
-        fs::write(&path, content).context("Failed to save job info")?;
+        write_atomic(&path, content).context("Failed to save job info")?;
         Ok(())
     }
 
@@ -162,6 +393,38 @@ impl JobManager {
         self.save_job(&info)
     }
 
+    /// Record the model usage of a job, estimating its cost from the
+    /// pricing data when the CLI didn't report one
+    pub fn record_usage(&self, job_id: &str, usage: &ModelUsage) -> Result<()> {
+        let mut usage = usage.clone();
+        if usage.cost_usd.is_none() {
+            let pricing = PricingData::load_offline().with_overrides(&self.price_overrides);
+            overhead::estimate_cost(&mut usage, &pricing);
+        }
+        let mut info = self.load_job(job_id)?;
+        info.usage = Some(usage);
+        self.save_job(&info)
+    }
+
+    /// Record the quality score of what a job generated
+    pub fn record_quality(&self, job_id: &str, quality: &QualityReport) -> Result<()> {
+        let mut info = self.load_job(job_id)?;
+        info.quality = Some(quality.clone());
+        self.save_job(&info)
+    }
+
+    /// Mark a job as failed with `error`, noting when it failed because a
+    /// model call ran past `summarization.call_timeout_secs`
+    pub fn mark_error(&self, job_id: &str, error: &anyhow::Error) -> Result<()> {
+        self.mark_failed(job_id, &error.to_string())?;
+        if error.chain().any(|cause| cause.is::<CallTimeout>()) {
+            let mut info = self.load_job(job_id)?;
+            info.timed_out = true;
+            self.save_job(&info)?;
+        }
+        Ok(())
+    }
+
     /// Mark a job as failed
     pub fn mark_failed(&self, job_id: &str, error: &str) -> Result<()> {
         let mut info = self.load_job(job_id)?;
@@ -172,6 +435,144 @@ impl JobManager {
         self.save_job(&info)
     }
 
+    /// Take the lock that serializes digests of `date`, failing if another
+    /// live process holds it. Released when the returned guard is dropped.
+    pub fn lock_digest(&self, date: &str, job_id: Option<&str>) -> Result<DigestLock> {
+        let path = self.jobs_dir.join(format!(".digest-{}.lock", date));
+        let holder = DigestLockHolder {
+            pid: std::process::id(),
+            job_id: job_id.map(str::to_string),
+            started_at: Local::now(),
+        };
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    use std::io::Write;
+                    file.write_all(serde_json::to_string(&holder)?.as_bytes())
+                        .context("Failed to write digest lock")?;
+                    return Ok(DigestLock { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    match self.digest_lock_holder(date) {
+                        Some(other) => anyhow::bail!(
+                            "A digest for {} is already running ({}); wait for it to finish",
+                            date,
+                            other.describe()
+                        ),
+                        None => {
+                            let _ = fs::remove_file(&path);
+                        }
+                    }
+                }
+                Err(e) => return Err(e).context("Failed to acquire digest lock"),
+            }
+        }
+    }
+
+    /// The live process digesting `date`, if any
+    pub fn digest_lock_holder(&self, date: &str) -> Option<DigestLockHolder> {
+        let path = self.jobs_dir.join(format!(".digest-{}.lock", date));
+        let content = fs::read_to_string(&path).ok()?;
+        let Ok(holder) = serde_json::from_str::<DigestLockHolder>(&content) else {
+            // Being written right now, or garbage: only trust it while fresh
+            let fresh = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age.as_secs() <= QUEUE_LOCK_STALE_SECS);
+            return fresh.then(|| DigestLockHolder {
+                pid: 0,
+                job_id: None,
+                started_at: Local::now(),
+            });
+        };
+        let age = (Local::now() - holder.started_at).num_seconds();
+        let stale = age > DIGEST_LOCK_STALE_SECS as i64 || !is_process_alive(holder.pid);
+        (!stale).then_some(holder)
+    }
+
+    /// An active job of `job_type` already queued or running
+    pub fn active_job_of_type(&self, job_type: JobType) -> Result<Option<JobInfo>> {
+        Ok(self
+            .list(false)?
+            .into_iter()
+            .find(|job| job.job_type == job_type))
+    }
+
+    /// An active digest job already queued or running for `date`
+    pub fn active_digest_job(&self, date: &str) -> Result<Option<JobInfo>> {
+        let task_name = format!("digest-{}", date);
+        Ok(self
+            .list(false)?
+            .into_iter()
+            .find(|job| job.job_type == JobType::Digest && job.task_name == task_name))
+    }
+
+    /// When the digests of `date` that failed finished, oldest first.
+    /// Interrupted digests that were resumed don't count.
+    pub fn digest_failures(&self, date: &str) -> Result<Vec<DateTime<Local>>> {
+        let task_name = format!("digest-{}", date);
+        let mut failures: Vec<DateTime<Local>> = self
+            .list(true)?
+            .into_iter()
+            .filter(|job| job.job_type == JobType::Digest && job.task_name == task_name)
+            .filter(|job| match &job.status {
+                JobStatus::Failed { error } => !error.starts_with("Interrupted; resumed"),
+                _ => false,
+            })
+            .filter_map(|job| job.finished_at)
+            .collect();
+        failures.sort();
+        Ok(failures)
+    }
+
+    /// IDs of the summarization jobs queued or running for `date`, which a
+    /// digest of that date waits for
+    pub fn summaries_in_flight(&self, date: &str) -> Result<Vec<String>> {
+        Ok(self
+            .list(false)?
+            .into_iter()
+            .filter(|job| job.job_type.is_summarization() && job.archive_date() == date)
+            .map(|job| job.id)
+            .collect())
+    }
+
+    /// Queued or running jobs whose process is gone, e.g. because the
+    /// dashboard service that spawned them was stopped. Unlike
+    /// [`JobManager::list`] this leaves the records as they are, so the
+    /// caller can start the work again before they are marked failed.
+    pub fn interrupted(&self) -> Result<Vec<JobInfo>> {
+        let mut jobs = vec![];
+        for entry in fs::read_dir(&self.jobs_dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Ok(info) = serde_json::from_str::<JobInfo>(&content) {
+                if info.status.is_active() && !info.is_alive() {
+                    jobs.push(info);
+                }
+            }
+        }
+        jobs.sort_by_key(|j| j.queued_at.unwrap_or(j.started_at));
+        Ok(jobs)
+    }
+
+    /// Close an interrupted job that was started again as `new_job_id`
+    pub fn mark_resumed(&self, job_id: &str, new_job_id: &str) -> Result<()> {
+        self.mark_failed(
+            job_id,
+            &format!("Interrupted; resumed as job {}", new_job_id),
+        )
+    }
+
     /// List all jobs, optionally filtering by status
     pub fn list(&self, include_completed: bool) -> Result<Vec<JobInfo>> {
         let mut jobs = vec![];
@@ -184,7 +585,7 @@ impl JobManager {
                 if let Ok(content) = fs::read_to_string(&path) {
                     if let Ok(mut info) = serde_json::from_str::<JobInfo>(&content) {
                         // Update status if process died unexpectedly
-                        if info.status == JobStatus::Running && !info.is_alive() {
+                        if info.status.is_active() && !info.is_alive() {
                             info.status = JobStatus::Failed {
                                 error: "Process terminated unexpectedly".to_string(),
                             };
@@ -192,7 +593,7 @@ impl JobManager {
                             let _ = self.save_job(&info);
                         }
 
-                        if include_completed || info.status == JobStatus::Running {
+                        if include_completed || info.status.is_active() {
                             jobs.push(info);
                         }
                     }
@@ -201,7 +602,7 @@ impl JobManager {
         }
 
         // Sort by start time, newest first
-        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs.sort_by_key(|j| std::cmp::Reverse(j.started_at));
         Ok(jobs)
     }
 
@@ -224,11 +625,11 @@ impl JobManager {
     pub fn kill(&self, job_id: &str) -> Result<bool> {
         let info = self.load_job(job_id)?;
 
-        if info.status != JobStatus::Running {
+        if !info.status.is_active() {
             return Ok(false);
         }
 
-        let killed = kill_process(info.pid);
+        let killed = kill_tree(info.pid);
 
         if killed {
             self.mark_failed(job_id, "Killed by user")?;
@@ -237,6 +638,45 @@ impl JobManager {
         Ok(killed)
     }
 
+    /// Kill every queued or running job, or those of `job_type`. Returns
+    /// each job with whether its process could be signalled.
+    pub fn kill_all(&self, job_type: Option<&JobType>) -> Result<Vec<(JobInfo, bool)>> {
+        let mut killed = vec![];
+        for job in self.list(false)? {
+            if job_type.is_some_and(|t| *t != job.job_type) {
+                continue;
+            }
+            let ok = self.kill(&job.id)?;
+            killed.push((job, ok));
+        }
+        Ok(killed)
+    }
+
+    /// Job and summarizer processes running without a live job, see
+    /// [`orphans::find_orphans`]
+    pub fn orphans(&self) -> Result<Vec<Orphan>> {
+        let exe = std::env::current_exe().context("Failed to get current executable")?;
+        let program = exe
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("daily");
+        let active: HashSet<String> = self.list(false)?.into_iter().map(|j| j.id).collect();
+        Ok(orphans::find_orphans(
+            &orphans::processes()?,
+            program,
+            &active,
+        ))
+    }
+
+    /// Terminate orphaned processes, returning the pids that were signalled
+    pub fn reap(&self, orphans: &[Orphan]) -> Vec<u32> {
+        orphans
+            .iter()
+            .filter(|orphan| kill_process(orphan.pid))
+            .map(|orphan| orphan.pid)
+            .collect()
+    }
+
     /// Cleanup old jobs
     pub fn cleanup(&self, keep_days: u32) -> Result<usize> {
         let cutoff = Local::now() - chrono::Duration::days(keep_days as i64);
@@ -250,7 +690,7 @@ impl JobManager {
                 if let Ok(content) = fs::read_to_string(&path) {
                     if let Ok(info) = serde_json::from_str::<JobInfo>(&content) {
                         // Only remove completed/failed jobs older than cutoff
-                        if info.status != JobStatus::Running && info.started_at < cutoff {
+                        if !info.status.is_active() && info.started_at < cutoff {
                             // Remove both json and log files
                             let _ = fs::remove_file(&path);
                             let _ = fs::remove_file(self.log_path(&info.id));
@@ -264,6 +704,35 @@ impl JobManager {
         Ok(removed)
     }
 
+    /// Finished jobs to remove under `retention`, oldest first
+    pub fn plan_prune(&self, retention: &JobRetentionConfig) -> Result<Vec<PrunedJob>> {
+        let jobs: Vec<(JobInfo, u64)> = self
+            .list(true)?
+            .into_iter()
+            .map(|job| {
+                let bytes = [self.job_path(&job.id), self.log_path(&job.id)]
+                    .iter()
+                    .filter_map(|path| fs::metadata(path).ok())
+                    .map(|meta| meta.len())
+                    .sum();
+                (job, bytes)
+            })
+            .collect();
+        Ok(select_for_prune(&jobs, retention, Local::now()))
+    }
+
+    /// Remove the record and log of each job, returning the bytes reclaimed
+    pub fn prune(&self, jobs: &[PrunedJob]) -> u64 {
+        let mut reclaimed = 0;
+        for job in jobs {
+            if fs::remove_file(self.job_path(&job.id)).is_ok() {
+                let _ = fs::remove_file(self.log_path(&job.id));
+                reclaimed += job.bytes;
+            }
+        }
+        reclaimed
+    }
+
     /// Create a bounded log file for a job
     pub fn create_log_file(&self, job_id: &str) -> Result<std::fs::File> {
         let path = self.log_path(job_id);
@@ -294,6 +763,192 @@ impl JobManager {
     }
 }
 
+/// Apply `jobs.retention` if `auto_prune` is on, ignoring failures
+pub fn auto_prune(config: &Config) {
+    let retention = &config.jobs.retention;
+    if !retention.auto_prune {
+        return;
+    }
+    let Ok(manager) = JobManager::new(config) else {
+        return;
+    };
+    if let Ok(jobs) = manager.plan_prune(retention) {
+        manager.prune(&jobs);
+    }
+}
+
+/// A finished job selected by the retention rules
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedJob {
+    pub id: String,
+    pub task_name: String,
+    pub started_at: DateTime<Local>,
+    /// Which limit it exceeds: "age", "count" or "size"
+    pub reason: &'static str,
+    /// Size of its record and log
+    pub bytes: u64,
+}
+
+/// Finished jobs (with their size on disk) that exceed `retention` as of
+/// `now`, oldest first. Newer jobs are kept in preference to older ones.
+pub fn select_for_prune(
+    jobs: &[(JobInfo, u64)],
+    retention: &JobRetentionConfig,
+    now: DateTime<Local>,
+) -> Vec<PrunedJob> {
+    let mut finished: Vec<&(JobInfo, u64)> = jobs
+        .iter()
+        .filter(|(job, _)| !job.status.is_active())
+        .collect();
+    finished.sort_by_key(|(job, _)| std::cmp::Reverse(job.started_at));
+
+    let cutoff = retention
+        .max_age_days
+        .map(|days| now - chrono::Duration::days(days as i64));
+    let max_bytes = retention.max_total_mb.map(|mb| mb * 1024 * 1024);
+    let mut kept = 0;
+    let mut kept_bytes = 0;
+    let mut pruned = Vec::new();
+    for (job, bytes) in finished {
+        let finished_at = job.finished_at.unwrap_or(job.started_at);
+        let reason = if cutoff.is_some_and(|cutoff| finished_at < cutoff) {
+            Some("age")
+        } else if retention.max_count.is_some_and(|max| kept >= max) {
+            Some("count")
+        } else if max_bytes.is_some_and(|max| kept_bytes + bytes > max) {
+            Some("size")
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => pruned.push(PrunedJob {
+                id: job.id.clone(),
+                task_name: job.task_name.clone(),
+                started_at: job.started_at,
+                reason,
+                bytes: *bytes,
+            }),
+            None => {
+                kept += 1;
+                kept_bytes += bytes;
+            }
+        }
+    }
+    pruned.reverse();
+    pruned
+}
+
+/// Queued jobs in the order they will start: priority first, then FIFO
+pub fn queue_order(jobs: &[JobInfo]) -> Vec<&JobInfo> {
+    let mut queued: Vec<&JobInfo> = jobs
+        .iter()
+        .filter(|j| j.status == JobStatus::Queued)
+        .collect();
+    queued.sort_by_key(|j| {
+        (
+            std::cmp::Reverse(j.job_type.priority()),
+            j.queued_at.unwrap_or(j.started_at),
+        )
+    });
+    queued
+}
+
+/// 1-based queue position of every queued job, keyed by job ID
+pub fn queue_positions(jobs: &[JobInfo]) -> HashMap<String, usize> {
+    queue_order(jobs)
+        .into_iter()
+        .enumerate()
+        .map(|(i, j)| (j.id.clone(), i + 1))
+        .collect()
+}
+
+/// Whether `job_id` is next in line and a running slot is free. Jobs
+/// still waiting on a dependency are passed over so they don't hold up
+/// the work they wait for.
+fn can_start(job_id: &str, jobs: &[JobInfo], max_concurrent: usize) -> bool {
+    let running = jobs
+        .iter()
+        .filter(|j| j.status == JobStatus::Running)
+        .count();
+    if running >= max_concurrent.max(1) {
+        return false;
+    }
+    queue_order(jobs)
+        .into_iter()
+        .find(|j| j.waiting_on(jobs).is_empty())
+        .is_some_and(|j| j.id == job_id)
+}
+
+/// Exclusive lock over queue decisions, held as a lock file in the jobs directory
+struct QueueLock {
+    path: PathBuf,
+}
+
+impl QueueLock {
+    fn acquire(jobs_dir: &Path) -> Result<Self> {
+        let path = jobs_dir.join(".queue.lock");
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age.as_secs() > QUEUE_LOCK_STALE_SECS);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                    } else {
+                        std::thread::sleep(Duration::from_millis(50));
+                    }
+                }
+                Err(e) => return Err(e).context("Failed to acquire job queue lock"),
+            }
+        }
+    }
+}
+
+impl Drop for QueueLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Who holds a digest lock, as stored in `jobs/.digest-{date}.lock`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestLockHolder {
+    pub pid: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    pub started_at: DateTime<Local>,
+}
+
+impl DigestLockHolder {
+    /// The holder as named in error messages
+    pub fn describe(&self) -> String {
+        match &self.job_id {
+            Some(job_id) => format!("job {}", job_id),
+            None if self.pid == 0 => "another process".to_string(),
+            None => format!("pid {}", self.pid),
+        }
+    }
+}
+
+/// Held while a digest of one date runs, see [`JobManager::lock_digest`]
+pub struct DigestLock {
+    path: PathBuf,
+}
+
+impl Drop for DigestLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
 /// Sanitize task name for use in job ID
 fn sanitize_name(name: &str) -> String {
     name.chars()
@@ -332,10 +987,18 @@ fn is_process_alive(_pid: u32) -> bool {
     true
 }
 
-/// Kill a process
+/// Kill a process. Jobs are spawned as process group leaders, so the
+/// whole group goes, including any `claude` call the job is waiting on.
 #[cfg(unix)]
 fn kill_process(pid: u32) -> bool {
-    unsafe { libc::kill(pid as i32, libc::SIGTERM) == 0 }
+    if pid == 0 {
+        return false;
+    }
+    let pid = pid as i32;
+    unsafe {
+        let target = if libc::getpgid(pid) == pid { -pid } else { pid };
+        libc::kill(target, libc::SIGTERM) == 0
+    }
 }
 
 #[cfg(not(unix))]
@@ -343,6 +1006,21 @@ fn kill_process(_pid: u32) -> bool {
     false
 }
 
+/// Kill a process and everything it started, including processes that
+/// left its group, such as the children of a `claude` call. Returns
+/// whether `pid` itself could be signalled.
+pub fn kill_tree(pid: u32) -> bool {
+    // Collected before any kill, while children still point at their parent
+    let descendants = orphans::processes()
+        .map(|processes| orphans::descendants(&processes, pid))
+        .unwrap_or_default();
+    let killed = kill_process(pid);
+    for child in descendants {
+        kill_process(child);
+    }
+    killed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,4 +1049,269 @@ mod tests {
             "Failed: test"
         );
     }
+
+    fn job(id: &str, status: JobStatus, job_type: JobType, queued_secs: i64) -> JobInfo {
+        let at = Local::now() + chrono::Duration::seconds(queued_secs);
+        JobInfo {
+            id: id.to_string(),
+            pid: 0,
+            task_name: id.to_string(),
+            transcript_path: PathBuf::from("/tmp/t.jsonl"),
+            started_at: at,
+            finished_at: None,
+            status,
+            job_type,
+            queued_at: Some(at),
+            usage: None,
+            quality: None,
+            depends_on: vec![],
+            timed_out: false,
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_order_priority_then_fifo() {
+        let jobs = vec![
+            job("auto-1", JobStatus::Queued, JobType::SessionEnd, 0),
+            job("backfill", JobStatus::Queued, JobType::Backfill, -10),
+            job("auto-2", JobStatus::Queued, JobType::AutoSummarize, 5),
+            job("manual", JobStatus::Queued, JobType::Manual, 10),
+            job("done", JobStatus::Completed, JobType::Manual, -20),
+        ];
+        let order: Vec<&str> = queue_order(&jobs).iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(order, vec!["manual", "auto-1", "auto-2", "backfill"]);
+
+        let positions = queue_positions(&jobs);
+        assert_eq!(positions.get("manual"), Some(&1));
+        assert_eq!(positions.get("backfill"), Some(&4));
+        assert_eq!(positions.get("done"), None);
+    }
+
+    #[test]
+    fn test_can_start_respects_limit_and_order() {
+        let mut jobs = vec![
+            job("running", JobStatus::Running, JobType::SessionEnd, -5),
+            job("first", JobStatus::Queued, JobType::SessionEnd, 0),
+            job("second", JobStatus::Queued, JobType::SessionEnd, 1),
+        ];
+        assert!(!can_start("first", &jobs, 1));
+        assert!(can_start("first", &jobs, 2));
+        assert!(!can_start("second", &jobs, 2));
+
+        jobs[0].status = JobStatus::Completed;
+        assert!(can_start("first", &jobs, 1));
+        // A zero limit still lets one job run
+        assert!(can_start("first", &jobs, 0));
+    }
+
+    #[test]
+    fn test_can_start_waits_for_dependencies() {
+        let mut digest = job("digest", JobStatus::Queued, JobType::Digest, -10);
+        digest.depends_on = vec!["summary".into(), "gone".into()];
+        let mut jobs = vec![
+            digest,
+            job("summary", JobStatus::Queued, JobType::SessionEnd, 0),
+        ];
+        assert_eq!(jobs[0].waiting_on(&jobs), vec!["summary"]);
+        // The digest outranks the summary but can't start before it
+        assert!(!can_start("digest", &jobs, 2));
+        assert!(can_start("summary", &jobs, 2));
+
+        jobs[1].status = JobStatus::Failed {
+            error: "test".into(),
+        };
+        assert!(jobs[0].waiting_on(&jobs).is_empty());
+        assert!(can_start("digest", &jobs, 2));
+    }
+
+    #[test]
+    fn test_job_status_is_active() {
+        assert!(JobStatus::Queued.is_active());
+        assert!(JobStatus::Running.is_active());
+        assert!(!JobStatus::Completed.is_active());
+    }
+
+    #[test]
+    fn test_select_for_prune() {
+        let now = Local::now();
+        let finished = |id: &str, days_ago: i64, bytes: u64| {
+            let mut info = job(id, JobStatus::Completed, JobType::SessionEnd, 0);
+            info.started_at = now - chrono::Duration::days(days_ago);
+            info.finished_at = Some(info.started_at);
+            (info, bytes)
+        };
+        let jobs = vec![
+            finished("old", 40, 10),
+            finished("d3", 3, 600 * 1024),
+            finished("d2", 2, 600 * 1024),
+            finished("d1", 1, 10),
+            finished("d0", 0, 10),
+            (
+                job(
+                    "running",
+                    JobStatus::Running,
+                    JobType::SessionEnd,
+                    -5_000_000,
+                ),
+                10,
+            ),
+        ];
+        let retention = JobRetentionConfig {
+            max_age_days: Some(30),
+            max_count: Some(10),
+            max_total_mb: Some(1),
+            auto_prune: true,
+        };
+
+        let pruned = select_for_prune(&jobs, &retention, now);
+        let reasons: Vec<(&str, &str)> = pruned
+            .iter()
+            .map(|job| (job.id.as_str(), job.reason))
+            .collect();
+        // d2 fits the 1 MB budget after d0 and d1, d3 does not; old is past 30 days
+        assert_eq!(reasons, vec![("old", "age"), ("d3", "size")]);
+
+        let retention = JobRetentionConfig {
+            max_count: Some(3),
+            max_total_mb: None,
+            ..retention
+        };
+        let pruned = select_for_prune(&jobs, &retention, now);
+        assert_eq!(pruned[1].id, "d3");
+        assert_eq!(pruned[1].reason, "count");
+    }
+
+    #[test]
+    fn test_digest_lock() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = JobManager::new(&config).unwrap();
+
+        let lock = manager.lock_digest("2026-03-01", Some("job-1")).unwrap();
+        let err = manager.lock_digest("2026-03-01", None).err().unwrap();
+        assert!(err.to_string().contains("job job-1"));
+        assert!(manager.lock_digest("2026-03-02", None).is_ok());
+
+        drop(lock);
+        assert!(manager.digest_lock_holder("2026-03-01").is_none());
+        let _lock = manager.lock_digest("2026-03-01", None).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interrupted_jobs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = temp_dir.path().to_path_buf();
+        let manager = JobManager::new(&config).unwrap();
+
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = exited.id();
+        exited.wait().unwrap();
+        let transcript = Path::new("/tmp/t.jsonl");
+        manager
+            .register(
+                "dead",
+                dead_pid,
+                "digest-2026-03-01",
+                transcript,
+                JobType::Digest,
+            )
+            .unwrap();
+        manager
+            .register(
+                "live",
+                std::process::id(),
+                "auto-1",
+                transcript,
+                JobType::AutoSummarize,
+            )
+            .unwrap();
+        manager
+            .register(
+                "done",
+                dead_pid,
+                "auto-2",
+                transcript,
+                JobType::AutoSummarize,
+            )
+            .unwrap();
+        manager.mark_completed("done").unwrap();
+
+        let interrupted = manager.interrupted().unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, "dead");
+        // Still queued until dealt with
+        assert_eq!(manager.load_job("dead").unwrap().status, JobStatus::Queued);
+
+        manager.mark_resumed("dead", "next").unwrap();
+        assert!(manager.interrupted().unwrap().is_empty());
+        assert_eq!(
+            manager.load_job("dead").unwrap().status,
+            JobStatus::Failed {
+                error: "Interrupted; resumed as job next".into()
+            }
+        );
+
+        // A resumed digest didn't fail; one that did counts for auto-digest
+        assert!(manager.digest_failures("2026-03-01").unwrap().is_empty());
+        manager
+            .register(
+                "retry",
+                dead_pid,
+                "digest-2026-03-01",
+                transcript,
+                JobType::Digest,
+            )
+            .unwrap();
+        manager.mark_failed("retry", "Invalid API key").unwrap();
+        assert_eq!(manager.digest_failures("2026-03-01").unwrap().len(), 1);
+        assert!(manager.digest_failures("2026-03-02").unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_tree_reaps_children() {
+        use std::time::{Duration, Instant};
+
+        // Whether `pid` is still running; a zombie waiting for its parent is not
+        let running = |pid: u32| {
+            std::process::Command::new("ps")
+                .args(["-o", "stat=", "-p", &pid.to_string()])
+                .output()
+                .map(|out| {
+                    let stat = String::from_utf8_lossy(&out.stdout);
+                    !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+                })
+                .unwrap_or(false)
+        };
+
+        let mut parent = std::process::Command::new("sh")
+            .args(["-c", "sleep 30 & sleep 30 & wait"])
+            .spawn()
+            .unwrap();
+        let pid = parent.id();
+        let started = Instant::now();
+        let children = loop {
+            let processes = orphans::processes().unwrap();
+            let children = orphans::descendants(&processes, pid);
+            if children.len() == 2 || started.elapsed() > Duration::from_secs(5) {
+                break children;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        assert_eq!(children.len(), 2);
+
+        assert!(kill_tree(pid));
+        parent.wait().unwrap();
+        let started = Instant::now();
+        while children.iter().any(|&child| running(child))
+            && started.elapsed() < Duration::from_secs(5)
+        {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(children.iter().all(|&child| !running(child)));
+    }
 }
@@ -1,3 +1,9 @@
 mod manager;
+pub mod offline;
+pub mod orphans;
+mod spawn;
 
-pub use manager::{JobInfo, JobManager, JobStatus, JobType};
+pub use manager::{
+    auto_prune, kill_tree, queue_positions, JobInfo, JobManager, JobStatus, JobType, PrunedJob,
+};
+pub use spawn::{spawn, JobCommand};
@@ -0,0 +1,182 @@
+//! Summarizations waiting for Claude to be reachable again.
+//!
+//! When a session can't be summarized because the Claude CLI has no network
+//! or no valid login, `daily summarize` keeps the request in
+//! `{storage}/offline-queue/{session_id}.json` instead of dropping it. The
+//! next session start, the dashboard and every successful summarization
+//! retry due requests (see `summarization.offline_retry_minutes`), and
+//! `daily queue flush` retries them all at once. A request leaves the queue
+//! once a summarization of its transcript ends for any other reason.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::atomic::write_atomic;
+use crate::config::Config;
+
+/// A summarization request waiting for Claude
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedSummary {
+    pub session_id: String,
+    pub transcript: PathBuf,
+    pub task_name: String,
+    pub cwd: String,
+    /// Local date and time to archive under (`YYYY-MM-DD HH:MM`), so a
+    /// retry on a later day still lands on the session's day
+    pub archive_at: String,
+    pub queued_at: DateTime<Local>,
+    pub last_attempt: DateTime<Local>,
+    pub attempts: u32,
+    /// Why the last attempt failed
+    pub error: String,
+}
+
+impl QueuedSummary {
+    /// Whether `retry_minutes` have passed since the last attempt
+    pub fn is_due(&self, now: DateTime<Local>, retry_minutes: u64) -> bool {
+        now - self.last_attempt >= chrono::Duration::minutes(retry_minutes as i64)
+    }
+}
+
+fn queue_dir(config: &Config) -> PathBuf {
+    config.storage_path().join("offline-queue")
+}
+
+/// Queue entries are keyed by the transcript's file name, Claude's session id
+fn session_id(transcript: &Path) -> String {
+    transcript
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "session".to_string())
+}
+
+fn entry_path(config: &Config, session_id: &str) -> PathBuf {
+    queue_dir(config).join(format!("{}.json", session_id))
+}
+
+/// Queue a failed summarization, or count another failed attempt of one
+/// already queued
+pub fn enqueue(
+    config: &Config,
+    transcript: &Path,
+    task_name: &str,
+    cwd: &str,
+    archive_at: &str,
+    error: &str,
+) -> Result<QueuedSummary> {
+    let session_id = session_id(transcript);
+    let path = entry_path(config, &session_id);
+    let now = Local::now();
+    let entry = match load(&path) {
+        Some(mut entry) => {
+            entry.last_attempt = now;
+            entry.attempts += 1;
+            entry.error = error.to_string();
+            entry
+        }
+        None => QueuedSummary {
+            session_id,
+            transcript: transcript.to_path_buf(),
+            task_name: task_name.to_string(),
+            cwd: cwd.to_string(),
+            archive_at: archive_at.to_string(),
+            queued_at: now,
+            last_attempt: now,
+            attempts: 1,
+            error: error.to_string(),
+        },
+    };
+    save(config, &entry)?;
+    Ok(entry)
+}
+
+/// Record that a retry of `entry` is starting, so it isn't retried again
+/// before it had a chance to finish
+pub fn mark_attempt(config: &Config, entry: &mut QueuedSummary) -> Result<()> {
+    entry.last_attempt = Local::now();
+    save(config, entry)
+}
+
+/// Drop the request for `transcript`; true if one was queued
+pub fn remove(config: &Config, transcript: &Path) -> bool {
+    fs::remove_file(entry_path(config, &session_id(transcript))).is_ok()
+}
+
+/// Queued requests, oldest first
+pub fn list(config: &Config) -> Result<Vec<QueuedSummary>> {
+    let dir = queue_dir(config);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut entries: Vec<QueuedSummary> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| load(&e.path()))
+        .collect();
+    entries.sort_by_key(|e| e.queued_at);
+    Ok(entries)
+}
+
+fn load(path: &Path) -> Option<QueuedSummary> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save(config: &Config, entry: &QueuedSummary) -> Result<()> {
+    let dir = queue_dir(config);
+    fs::create_dir_all(&dir).context("Failed to create offline queue directory")?;
+    let path = entry_path(config, &entry.session_id);
+    write_atomic(&path, serde_json::to_string_pretty(entry)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enqueue_retry_and_remove() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let transcript = Path::new("/projects/app/abc-123.jsonl");
+
+        assert!(list(&config).unwrap().is_empty());
+        let first = enqueue(
+            &config,
+            transcript,
+            "session-1",
+            "/projects/app",
+            "2024-01-15 18:30",
+            "offline",
+        )
+        .unwrap();
+        assert_eq!(first.session_id, "abc-123");
+        assert!(!first.is_due(Local::now(), 15));
+        assert!(first.is_due(Local::now() + chrono::Duration::minutes(15), 15));
+
+        // A second failure keeps the original archive time and counts the attempt
+        enqueue(
+            &config,
+            transcript,
+            "other",
+            "/",
+            "2024-01-16 09:00",
+            "no login",
+        )
+        .unwrap();
+        let queued = list(&config).unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].attempts, 2);
+        assert_eq!(queued[0].archive_at, "2024-01-15 18:30");
+        assert_eq!(queued[0].error, "no login");
+
+        assert!(remove(&config, transcript));
+        assert!(!remove(&config, transcript));
+        assert!(list(&config).unwrap().is_empty());
+    }
+}
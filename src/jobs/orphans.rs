@@ -0,0 +1,218 @@
+//! Stray background processes whose job is gone.
+//!
+//! A job's process normally exits with its record, but after a crash or a
+//! deleted jobs directory a `daily ... --job-id` process may keep running
+//! without one, and a `claude` call it started may outlive it. Both keep
+//! spending tokens nobody will see, so they are found from the process
+//! table and can be killed with `daily jobs reap`.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Processes younger than this are left alone: the spawner registers a
+/// job right after starting it
+pub const MIN_ORPHAN_AGE_SECS: u64 = 60;
+
+/// Marks the `claude` calls made by the summarizer, see `invoke_claude`
+const CLAUDE_MARKERS: [&str; 2] = ["--no-session-persistence", r#"{"hooks":{}}"#];
+
+/// One row of the process table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    pub ppid: u32,
+    pub age_secs: u64,
+    pub args: String,
+}
+
+impl Process {
+    fn program(&self) -> &str {
+        let first = self.args.split_whitespace().next().unwrap_or_default();
+        Path::new(first)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(first)
+    }
+
+    /// Job ID a `daily` process was started for
+    fn job_id(&self) -> Option<&str> {
+        let mut args = self.args.split_whitespace();
+        args.find(|arg| *arg == "--job-id")?;
+        args.next()
+    }
+}
+
+/// A process that no live job accounts for
+#[derive(Debug, Clone, Serialize)]
+pub struct Orphan {
+    pub pid: u32,
+    /// Job ID the process was started for, if it is a daily job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    /// Why it counts as an orphan
+    pub reason: String,
+    pub command: String,
+}
+
+/// Daily job processes without an active job record, and summarizer
+/// `claude` calls whose daily process is gone. `program` is the file name
+/// of the daily executable; `active_jobs` the IDs of queued and running jobs.
+pub fn find_orphans(
+    processes: &[Process],
+    program: &str,
+    active_jobs: &HashSet<String>,
+) -> Vec<Orphan> {
+    let is_daily = |p: &Process| p.program() == program;
+    let mut orphans = vec![];
+    for process in processes {
+        if process.age_secs < MIN_ORPHAN_AGE_SECS {
+            continue;
+        }
+        if is_daily(process) {
+            let Some(job_id) = process.job_id() else {
+                continue;
+            };
+            if !active_jobs.contains(job_id) {
+                orphans.push(Orphan {
+                    pid: process.pid,
+                    job_id: Some(job_id.to_string()),
+                    reason: "job record missing or finished".into(),
+                    command: process.args.clone(),
+                });
+            }
+        } else if process.program() == "claude"
+            && CLAUDE_MARKERS.iter().all(|m| process.args.contains(m))
+        {
+            let parent_alive = processes
+                .iter()
+                .any(|parent| parent.pid == process.ppid && is_daily(parent));
+            if !parent_alive {
+                orphans.push(Orphan {
+                    pid: process.pid,
+                    job_id: None,
+                    reason: "summarizer call outlived its daily process".into(),
+                    command: process.args.clone(),
+                });
+            }
+        }
+    }
+    orphans
+}
+
+/// Pids of every process started by `pid`, directly or not, parents first
+pub fn descendants(processes: &[Process], pid: u32) -> Vec<u32> {
+    let mut found = vec![];
+    let mut parents = vec![pid];
+    while let Some(parent) = parents.pop() {
+        for process in processes {
+            if process.ppid == parent && process.pid != pid && !found.contains(&process.pid) {
+                found.push(process.pid);
+                parents.push(process.pid);
+            }
+        }
+    }
+    found
+}
+
+/// Rows of `ps -o pid=,ppid=,etime=,args=`
+pub fn parse_ps(output: &str) -> Vec<Process> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let ppid = fields.next()?.parse().ok()?;
+            let age_secs = parse_etime(fields.next()?)?;
+            let args = fields.collect::<Vec<_>>().join(" ");
+            Some(Process {
+                pid,
+                ppid,
+                age_secs,
+                args,
+            })
+        })
+        .collect()
+}
+
+/// Seconds in a `ps` elapsed time, `[[dd-]hh:]mm:ss`
+fn parse_etime(etime: &str) -> Option<u64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, etime),
+    };
+    let mut secs = 0;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(days * 86_400 + secs)
+}
+
+/// The current process table
+#[cfg(unix)]
+pub fn processes() -> anyhow::Result<Vec<Process>> {
+    use anyhow::Context;
+    let output = std::process::Command::new("ps")
+        .args(["-axo", "pid=,ppid=,etime=,args="])
+        .output()
+        .context("Failed to run ps")?;
+    anyhow::ensure!(output.status.success(), "ps exited with {}", output.status);
+    Ok(parse_ps(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(unix))]
+pub fn processes() -> anyhow::Result<Vec<Process>> {
+    Ok(vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphans() {
+        let ps = "\
+    1     0 3-02:00:00 /sbin/init
+  200     1    10:00 /usr/local/bin/daily summarize --transcript /t.jsonl --job-id live --foreground
+  201   200    09:58 claude --model sonnet --print -p --output-format json --settings {\"hooks\":{}} --no-session-persistence --strict-mcp-config
+  300     1  1:05:00 /usr/local/bin/daily summarize --transcript /u.jsonl --job-id gone --foreground
+  400     1    30:00 claude --model sonnet --print -p --output-format json --settings {\"hooks\":{}} --no-session-persistence --strict-mcp-config
+  500     1    30:00 claude --resume
+  600     1    00:05 /usr/local/bin/daily digest --date 2024-01-15 --job-id new
+  700     1    30:00 /usr/local/bin/daily show
+";
+        let processes = parse_ps(ps);
+        assert_eq!(processes.len(), 8);
+        assert_eq!(processes[0].age_secs, 3 * 86_400 + 2 * 3600);
+        assert_eq!(processes[3].age_secs, 3900);
+
+        let active = HashSet::from(["live".to_string()]);
+        let orphans = find_orphans(&processes, "daily", &active);
+        let found: Vec<(u32, Option<&str>)> = orphans
+            .iter()
+            .map(|o| (o.pid, o.job_id.as_deref()))
+            .collect();
+        // Interactive claude sessions, the dashboard and just-spawned jobs are left alone
+        assert_eq!(found, vec![(300, Some("gone")), (400, None)]);
+    }
+
+    #[test]
+    fn test_descendants() {
+        let ps = "\
+    1     0 1-00:00:00 /sbin/init
+  200     1    10:00 daily summarize --job-id a
+  201   200    09:58 claude --print
+  202   201    09:50 node mcp-server.js
+  203   202    09:40 sh -c build
+  210   200    09:00 git status
+  300     1    10:00 daily show
+";
+        let processes = parse_ps(ps);
+        // Parents come before their children, however deep
+        assert_eq!(descendants(&processes, 200), vec![201, 210, 202, 203]);
+        assert_eq!(descendants(&processes, 202), vec![203]);
+        assert!(descendants(&processes, 203).is_empty());
+        assert!(descendants(&processes, 999).is_empty());
+        assert_eq!(descendants(&processes, 1).len(), 6);
+    }
+}
@@ -0,0 +1,157 @@
+//! Starting `daily` subcommands as detached background jobs.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+use super::{JobManager, JobType};
+use crate::config::Config;
+
+/// A `daily` subcommand to run in the background as a tracked job, kept
+/// with the job record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCommand {
+    pub job_type: JobType,
+    pub task_name: String,
+    /// Transcript or folder the job works on, as shown by `daily jobs`
+    pub target: PathBuf,
+    /// Arguments after `daily`; `--job-id` is added when spawning
+    pub args: Vec<String>,
+    /// Environment variables besides `DAILY_STORAGE__PATH`
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    /// Jobs that must finish before this one starts (recorded on the job)
+    #[serde(skip)]
+    pub depends_on: Vec<String>,
+    /// Leave the storage to the child's `--cwd` instead of the spawner's
+    #[serde(default)]
+    pub route_by_cwd: bool,
+}
+
+impl JobCommand {
+    pub fn new(
+        job_type: JobType,
+        task_name: impl Into<String>,
+        target: impl Into<PathBuf>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            job_type,
+            task_name: task_name.into(),
+            target: target.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            env: Vec::new(),
+            depends_on: Vec::new(),
+            route_by_cwd: false,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn env(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.env.push((key.to_string(), value.into()));
+        self
+    }
+
+    pub fn after(mut self, depends_on: Vec<String>) -> Self {
+        self.depends_on = depends_on;
+        self
+    }
+
+    /// Let a `daily summarize --cwd` started from the user config archive
+    /// wherever the session project's `.daily.toml` routes it
+    pub fn route_by_cwd(mut self) -> Self {
+        self.route_by_cwd = true;
+        self
+    }
+}
+
+/// Start `command` as a detached process of the running `daily` binary and
+/// register it, returning the job id and the child for callers that wait on
+/// it. The child gets its own process group so it outlives whoever started
+/// it, writes its output to the job log, and is pointed at `config`'s
+/// storage, where its job record lives, even when that storage came from a
+/// profile or a project `.daily.toml` its working directory wouldn't find
+/// (unless the command is [`route_by_cwd`](JobCommand::route_by_cwd)).
+pub fn spawn(config: &Config, command: JobCommand) -> Result<(String, Child)> {
+    let job_manager = JobManager::new(config)?;
+    let job_id = JobManager::generate_job_id(&command.task_name);
+    let log = job_manager.create_log_file(&job_id)?;
+    let log_err = log.try_clone()?;
+
+    let mut cmd = command_for(config, &command, &job_id)?;
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(log_err));
+
+    let child = cmd.spawn().with_context(|| {
+        format!(
+            "Failed to spawn background {} process",
+            command.args.first().map(String::as_str).unwrap_or("daily")
+        )
+    })?;
+    job_manager
+        .register_command(&job_id, child.id(), &command)
+        .context("Failed to register job")?;
+    Ok((job_id, child))
+}
+
+/// The process [`spawn`] starts for `command` as job `job_id`
+fn command_for(config: &Config, command: &JobCommand, job_id: &str) -> Result<Command> {
+    let exe = std::env::current_exe().context("Failed to get current executable")?;
+    let mut cmd = Command::new(&exe);
+    cmd.args(&command.args)
+        .args(["--job-id", job_id])
+        .envs(command.env.iter().map(|(key, value)| (key, value)));
+    if !command.route_by_cwd {
+        cmd.env("DAILY_STORAGE__PATH", config.storage_path());
+    }
+    #[cfg(unix)]
+    cmd.process_group(0);
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_command_for() {
+        let mut config = Config::default();
+        config.storage.path = PathBuf::from("/work/api/.daily");
+        let command = JobCommand::new(JobType::Topics, "topics", "/tmp", ["topics", "update"])
+            .arg("--rebuild")
+            .env("DAILY_SUMMARIZATION__DIGEST_GROUP_BY", "project");
+
+        let cmd = command_for(&config, &command, "topics-1").unwrap();
+        assert_eq!(cmd.get_program(), std::env::current_exe().unwrap());
+        let args: Vec<&OsStr> = cmd.get_args().collect();
+        assert_eq!(
+            args,
+            ["topics", "update", "--rebuild", "--job-id", "topics-1"]
+        );
+        let envs: Vec<(&OsStr, Option<&OsStr>)> = cmd.get_envs().collect();
+        assert!(envs.contains(&(
+            OsStr::new("DAILY_STORAGE__PATH"),
+            Some(OsStr::new("/work/api/.daily"))
+        )));
+        assert!(envs.contains(&(
+            OsStr::new("DAILY_SUMMARIZATION__DIGEST_GROUP_BY"),
+            Some(OsStr::new("project"))
+        )));
+
+        // A routed summarize leaves the storage to its --cwd
+        let routed = command_for(&config, &command.route_by_cwd(), "topics-1").unwrap();
+        assert!(routed
+            .get_envs()
+            .all(|(key, _)| key != OsStr::new("DAILY_STORAGE__PATH")));
+    }
+}
@@ -1,43 +1,104 @@
 mod archive;
+mod ask;
 mod auto_summarize;
+mod backup;
 mod cli;
 mod config;
+mod daemon;
+mod decisions;
+mod embeddings;
+mod export;
+mod flashcards;
+mod goals;
 mod hooks;
+mod i18n;
+mod import;
 mod insights;
+mod integrations;
 mod jobs;
+mod notifications;
+mod publish;
+mod render;
 mod server;
+mod skills;
+mod storage;
 mod summarizer;
+mod todos;
+mod topics;
 mod transcript;
 mod usage;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::args::{Cli, Commands, HookType, JobsAction};
+use cli::args::{
+    BackupAction, Cli, Commands, ConfigAction, DaemonAction, ExportAction, FocusAction, GoalAction,
+    HookType, JobsAction, PricingAction, QueueAction, StorageAction, TemplatesAction, TodoAction,
+    TopicsAction, UsageAction,
+};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let json = cli.json;
+    cli::output::init(cli.no_color, cli.plain);
+    if let Some(profile) = &cli.profile {
+        config::use_profile(profile)?;
+    }
+    if json && !cli.command.supports_json() {
+        eprintln!(
+            "{}: --json isn't supported by this command",
+            i18n::t("error")
+        );
+        std::process::exit(2);
+    }
+
+    // Hooks must return quickly; the jobs they start push their own changes
+    let push_changes = !matches!(
+        cli.command,
+        Commands::Hook { .. } | Commands::Storage { .. } | Commands::Statusline
+    );
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Init {
             storage_path,
             yes,
             haiku,
         } => cli::commands::init::run(storage_path, !yes, haiku).await,
         Commands::Hook { hook_type } => match hook_type {
-            HookType::SessionStart => hooks::session_start::handle().await,
-            HookType::SessionEnd => hooks::session_end::handle().await,
+            HookType::SessionStart => {
+                hooks::events::run("session-start", hooks::session_start::handle()).await
+            }
+            HookType::SessionEnd => {
+                hooks::events::run("session-end", hooks::session_end::handle()).await
+            }
+            HookType::PreCompact => {
+                hooks::events::run("pre-compact", hooks::pre_compact::handle()).await
+            }
+            HookType::Stop => hooks::events::run("stop", hooks::stop::handle()).await,
+            HookType::UserPromptSubmit => {
+                hooks::events::run("user-prompt-submit", hooks::user_prompt_submit::handle()).await
+            }
         },
         Commands::View {
             date,
+            week,
+            last,
+            from,
+            to,
             summary_only,
             list,
-        } => cli::commands::view::run(date, summary_only, list).await,
+        } => {
+            if week || last.is_some() || from.is_some() {
+                cli::commands::view::run_range(week, last, from, to, summary_only, list, json).await
+            } else {
+                cli::commands::view::run(date, summary_only, list, json).await
+            }
+        }
         Commands::Today { summary_only, list } => {
-            cli::commands::view::run_today(summary_only, list).await
+            cli::commands::view::run_today(summary_only, list, json).await
         }
         Commands::Yest { summary_only, list } => {
-            cli::commands::view::run_yesterday(summary_only, list).await
+            cli::commands::view::run_yesterday(summary_only, list, json).await
         }
         Commands::Summarize {
             transcript,
@@ -45,13 +106,55 @@ async fn main() -> Result<()> {
             cwd,
             foreground,
             job_id,
-        } => cli::commands::summarize::run(transcript, task_name, cwd, foreground, job_id).await,
+            archive_at,
+            checkpoint,
+        } => {
+            cli::commands::summarize::run(
+                transcript, task_name, cwd, foreground, job_id, archive_at, checkpoint,
+            )
+            .await
+        }
+        Commands::Backfill {
+            days,
+            concurrency,
+            dry_run,
+        } => cli::commands::backfill::run(days, concurrency, dry_run).await,
+        Commands::Import {
+            source,
+            path,
+            cwd,
+            dry_run,
+            force,
+        } => cli::commands::import::run(source, path, cwd, dry_run, force).await,
         Commands::Digest {
             relative_date,
             date,
             background,
             force,
-        } => cli::commands::digest::run(relative_date, date, background, force).await,
+            keep,
+            group_by,
+            interactive,
+            all_missing,
+            since,
+            concurrency,
+            job_id,
+        } => {
+            if all_missing {
+                cli::commands::digest::run_all_missing(since, concurrency, group_by).await
+            } else {
+                cli::commands::digest::run(
+                    relative_date,
+                    date,
+                    background,
+                    force,
+                    keep,
+                    group_by,
+                    interactive,
+                    job_id,
+                )
+                .await
+            }
+        }
         Commands::ExtractSkill {
             date,
             session,
@@ -65,34 +168,227 @@ async fn main() -> Result<()> {
         Commands::ReviewSkills { install, delete } => {
             cli::commands::skills::run_review(install, delete).await
         }
+        Commands::Pin { target, remove } => cli::commands::pin::run(target, remove, json).await,
+        Commands::Lock { date, remove } => cli::commands::lock::run(date, remove, json).await,
+        Commands::Daemon { action } => match action {
+            DaemonAction::Install { port, host } => {
+                cli::commands::daemon::install(port, host).await
+            }
+            DaemonAction::Uninstall => cli::commands::daemon::uninstall().await,
+            DaemonAction::Status => cli::commands::daemon::status(json).await,
+        },
+        Commands::Statusline => cli::commands::statusline::run(json).await,
+        Commands::Note { text, date } => cli::commands::note::run(text, date, json).await,
+        Commands::Rename { target, topic } => cli::commands::rename::run(target, topic, json).await,
+        Commands::Rate {
+            target,
+            outcome,
+            satisfaction,
+            note,
+            clear,
+        } => cli::commands::rate::run(target, outcome, satisfaction, note, clear, json).await,
+        Commands::Feedback {
+            target,
+            stars,
+            comment,
+        } => cli::commands::rate::feedback(target, stars, comment, json).await,
+        Commands::Ask { question, limit } => cli::commands::ask::run(question, limit, json).await,
+        Commands::Related {
+            target,
+            limit,
+            reindex,
+        } => cli::commands::related::run(target, limit, reindex, json).await,
+        Commands::Stats => cli::commands::stats::run(json).await,
+        Commands::Doctor => cli::commands::doctor::run(json).await,
+        Commands::Prune { dry_run } => cli::commands::prune::run(dry_run, json).await,
+        Commands::Migrate { dry_run } => cli::commands::migrate::run(dry_run, json).await,
+        Commands::Storage { action } => match action {
+            StorageAction::Status => cli::commands::storage::status(json).await,
+            StorageAction::Push { all } => cli::commands::storage::push(all, json).await,
+            StorageAction::Pull => cli::commands::storage::pull(json).await,
+        },
+        Commands::MigrateLayout { from, to, dry_run } => {
+            cli::commands::migrate_layout::run(from, to, dry_run).await
+        }
         Commands::Config {
+            action,
             set_storage,
             show,
             interactive,
-        } => cli::commands::config::run(set_storage, show, interactive).await,
+        } => match action {
+            Some(ConfigAction::Show { origin }) => cli::commands::config::show(origin, json),
+            Some(ConfigAction::Validate) => cli::commands::config::validate(json),
+            Some(ConfigAction::Migrate { dry_run }) => {
+                cli::commands::config::migrate(dry_run, json)
+            }
+            None => cli::commands::config::run(set_storage, show, interactive, json).await,
+        },
         Commands::Install { scope } => cli::commands::install::run(scope).await,
-        Commands::Uninstall { scope } => cli::commands::uninstall::run(scope).await,
-        Commands::UninstallHooks { scope } => cli::commands::uninstall::run_hooks_only(scope).await,
+        Commands::Uninstall { scope, dry_run } => {
+            cli::commands::uninstall::run(scope, dry_run).await
+        }
+        Commands::UninstallHooks { scope, dry_run } => {
+            cli::commands::uninstall::run_hooks_only(scope, dry_run).await
+        }
         Commands::InstallHooks { scope } => cli::commands::install::run_hooks_only(scope).await,
         Commands::Trash => cli::commands::trash::run().await,
         Commands::Update { check, version } => cli::commands::update::run(check, version).await,
+        Commands::Focus { action } => match action {
+            FocusAction::PushGithub {
+                repo,
+                date,
+                label,
+                dry_run,
+            } => cli::commands::focus::push_github(repo, date, label, dry_run, json).await,
+        },
         Commands::Jobs { action } => match action {
-            JobsAction::List { all } => cli::commands::jobs::list(all).await,
+            JobsAction::List { all } => cli::commands::jobs::list(all, json).await,
             JobsAction::Log {
                 job_id,
                 tail,
                 follow,
             } => cli::commands::jobs::log(job_id, tail, follow).await,
             JobsAction::Kill { job_id } => cli::commands::jobs::kill(job_id).await,
+            JobsAction::Killall { job_type } => cli::commands::jobs::killall(job_type, json).await,
+            JobsAction::Reap { dry_run } => cli::commands::jobs::reap(dry_run, json).await,
+            JobsAction::Watch { all, interval } => {
+                cli::commands::jobs_watch::watch(all, interval).await
+            }
             JobsAction::Cleanup { days, dry_run } => {
                 cli::commands::jobs::cleanup(days, dry_run).await
             }
+            JobsAction::Prune { dry_run } => cli::commands::jobs::prune(dry_run, json).await,
+        },
+        Commands::Queue { action } => match action.unwrap_or(QueueAction::List) {
+            QueueAction::List => cli::commands::queue::list(json).await,
+            QueueAction::Flush => cli::commands::queue::flush(json).await,
+        },
+        Commands::Usage {
+            action,
+            by_project,
+            days,
+        } => match action {
+            Some(UsageAction::Blocks {
+                active,
+                recent,
+                token_limit,
+                notify,
+            }) => cli::commands::usage::blocks(active, recent, token_limit, notify, json).await,
+            None => cli::commands::usage::summary(by_project, days, json).await,
+        },
+        Commands::Goal { action } => match action.unwrap_or(GoalAction::List { all: false }) {
+            GoalAction::Add { title } => cli::commands::goal::add(title, json).await,
+            GoalAction::List { all } => cli::commands::goal::list(all, json).await,
+            GoalAction::Done { id } => {
+                cli::commands::goal::set_status(id, goals::GoalStatus::Done, json).await
+            }
+            GoalAction::Reopen { id } => {
+                cli::commands::goal::set_status(id, goals::GoalStatus::Active, json).await
+            }
+            GoalAction::Remove { id } => cli::commands::goal::remove(id, json).await,
+        },
+        Commands::Todo { action } => match action.unwrap_or(TodoAction::List { all: false }) {
+            TodoAction::Add { text } => cli::commands::todo::add(text, json).await,
+            TodoAction::List { all } => cli::commands::todo::list(all, json).await,
+            TodoAction::Done { id } => cli::commands::todo::done(id, json).await,
+        },
+        Commands::Decisions {
+            query,
+            project,
+            limit,
+            write,
+        } => cli::commands::decisions::run(query, project, limit, write, json).await,
+        Commands::Topics { action } => match action.unwrap_or(TopicsAction::List) {
+            TopicsAction::List => cli::commands::topics::list(json).await,
+            TopicsAction::Update {
+                rebuild,
+                background,
+                job_id,
+            } => cli::commands::topics::update(rebuild, background, job_id, json).await,
+        },
+        Commands::Templates { action } => match action {
+            TemplatesAction::Test {
+                template,
+                sessions,
+                output,
+            } => cli::commands::templates::test(template, sessions, output, json).await,
+        },
+        Commands::Pricing { action } => match action {
+            PricingAction::Refresh => cli::commands::pricing::refresh(json).await,
+            PricingAction::Show { model } => cli::commands::pricing::show(model, json),
+        },
+        Commands::Export { what } => match what {
+            ExportAction::Usage {
+                format,
+                from,
+                to,
+                per,
+                output,
+            } => cli::commands::export::usage(format, from, to, per, output).await,
+            ExportAction::Insights {
+                format,
+                from,
+                to,
+                days,
+                per,
+                output,
+            } => cli::commands::export::insights(format, from, to, days, per, output).await,
+            ExportAction::Digest {
+                date,
+                theme,
+                output,
+            } => cli::commands::export::digest(date, theme, output).await,
+            ExportAction::Anki {
+                format,
+                from,
+                to,
+                output,
+            } => cli::commands::export::anki(format, from, to, output).await,
+        },
+        Commands::Publish { out, theme } => cli::commands::publish::run(out, theme, json).await,
+        Commands::Share {
+            date,
+            out,
+            upload,
+            theme,
+        } => cli::commands::share::run(date, out, upload, theme, json).await,
+        Commands::Insights { days, tools } => cli::commands::insights::run(days, tools, json).await,
+        Commands::Anomalies {
+            notify,
+            background,
+            job_id,
+        } => cli::commands::anomalies::run(notify, background, job_id, json).await,
+        Commands::Backup { action } => match action.unwrap_or(BackupAction::List) {
+            BackupAction::Now => cli::commands::backup::now(json).await,
+            BackupAction::List => cli::commands::backup::list(json).await,
+            BackupAction::Restore { timestamp } => {
+                cli::commands::backup::restore(&timestamp, json).await
+            }
         },
-        Commands::Insights { days } => cli::commands::insights::run(days).await,
         Commands::Show {
             port,
             host,
             no_open,
-        } => cli::commands::show::run(port, host, !no_open).await,
+            base_path,
+            tls_cert,
+            tls_key,
+            daemon,
+        } => {
+            cli::commands::show::run(port, host, !no_open, base_path, tls_cert, tls_key, daemon)
+                .await
+        }
+    };
+
+    // Push whatever the command changed to a remote storage backend
+    let configured = config::get_config_path().is_ok_and(|path| path.exists());
+    if push_changes && configured {
+        if let Ok(config) = config::load_config() {
+            storage::flush_quietly(&config).await;
+        }
+    }
+    if let Err(e) = result {
+        eprintln!("{}: {:?}", i18n::t("error"), e);
+        std::process::exit(1);
     }
+    Ok(())
 }
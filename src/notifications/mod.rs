@@ -0,0 +1,4 @@
+pub mod scripts;
+mod webhook;
+
+pub use webhook::{notify, WebhookEvent};
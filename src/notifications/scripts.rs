@@ -0,0 +1,118 @@
+//! User scripts from `hooks.post_archive` and `hooks.post_digest`.
+//!
+//! Each command runs through the shell once a session archive or digest is
+//! written, with the details in `DAILY_*` environment variables. Like
+//! webhooks, scripts are best effort: a failing or hanging script is logged
+//! and never fails the job that triggered it.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// A script still running after this long is killed
+const SCRIPT_TIMEOUT_SECS: u64 = 60;
+
+/// Run the `hooks.post_archive` commands for a newly written session archive
+pub fn post_archive(config: &Config, date: &str, name: &str, session_id: &str, path: &Path) {
+    run_all(
+        config,
+        &config.hooks.post_archive,
+        &[
+            ("DAILY_EVENT", "post_archive"),
+            ("DAILY_DATE", date),
+            ("DAILY_SESSION", name),
+            ("DAILY_SESSION_ID", session_id),
+            ("DAILY_PATH", &path.to_string_lossy()),
+        ],
+    );
+}
+
+/// Run the `hooks.post_digest` commands for a newly written daily.md
+pub fn post_digest(config: &Config, date: &str, path: &Path) {
+    run_all(
+        config,
+        &config.hooks.post_digest,
+        &[
+            ("DAILY_EVENT", "post_digest"),
+            ("DAILY_DATE", date),
+            ("DAILY_PATH", &path.to_string_lossy()),
+        ],
+    );
+}
+
+fn run_all(config: &Config, commands: &[String], envs: &[(&str, &str)]) {
+    let storage = config.storage_path();
+    for command in commands.iter().filter(|c| !c.trim().is_empty()) {
+        let mut cmd = shell(command);
+        cmd.envs(envs.iter().copied())
+            .env("DAILY_STORAGE", &storage)
+            .stdin(Stdio::null());
+        if let Err(e) = run(cmd) {
+            eprintln!("[daily] Hook script '{}' failed: {}", command, e);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+/// Run a script to completion, killing it after [`SCRIPT_TIMEOUT_SECS`]
+fn run(mut cmd: Command) -> anyhow::Result<()> {
+    let mut child = cmd.spawn()?;
+    let deadline = Instant::now() + Duration::from_secs(SCRIPT_TIMEOUT_SECS);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if !status.success() {
+                anyhow::bail!("exited with {}", status);
+            }
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("timed out after {}s", SCRIPT_TIMEOUT_SECS);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_post_digest_scripts_get_env() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let out = dir.path().join("out.txt");
+        config.hooks.post_digest = vec![
+            "exit 3".into(),
+            format!(
+                "echo \"$DAILY_EVENT $DAILY_DATE $DAILY_PATH\" > {}",
+                out.display()
+            ),
+        ];
+
+        post_digest(&config, "2024-01-15", Path::new("/archive/daily.md"));
+        assert_eq!(
+            fs::read_to_string(&out).unwrap().trim(),
+            "post_digest 2024-01-15 /archive/daily.md"
+        );
+    }
+}
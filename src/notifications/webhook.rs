@@ -0,0 +1,170 @@
+use anyhow::Result;
+use chrono::Local;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+
+use crate::config::{Config, WebhookConfig};
+
+/// Per-request timeout; webhooks must never hold up a job for long
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Events that can be delivered to webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    JobCompleted,
+    JobFailed,
+    DigestGenerated,
+    BlockNearLimit,
+    InsightsAnomaly,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::JobCompleted => "job.completed",
+            WebhookEvent::JobFailed => "job.failed",
+            WebhookEvent::DigestGenerated => "digest.generated",
+            WebhookEvent::BlockNearLimit => "usage.block_near_limit",
+            WebhookEvent::InsightsAnomaly => "insights.anomaly",
+        }
+    }
+}
+
+/// JSON body sent to every webhook
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a, T: Serialize> {
+    event: &'a str,
+    timestamp: String,
+    data: T,
+}
+
+/// Deliver an event to all matching webhooks.
+///
+/// Delivery is best effort: failures are logged and never returned, so a
+/// broken endpoint can't fail the job or digest that triggered it.
+pub async fn notify<T: Serialize>(config: &Config, event: WebhookEvent, data: T) {
+    let targets: Vec<&WebhookConfig> = config
+        .notifications
+        .webhooks
+        .iter()
+        .filter(|hook| subscribes_to(hook, event))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let payload = WebhookPayload {
+        event: event.as_str(),
+        timestamp: Local::now().to_rfc3339(),
+        data,
+    };
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("[daily] Failed to encode webhook payload: {}", e);
+            return;
+        }
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("[daily] Failed to create webhook client: {}", e);
+            return;
+        }
+    };
+
+    let deliveries = targets
+        .into_iter()
+        .map(|hook| deliver(&client, hook, event, &body));
+    for (url, result) in futures_util::future::join_all(deliveries).await {
+        if let Err(e) = result {
+            eprintln!("[daily] Webhook {} failed: {}", url, e);
+        }
+    }
+}
+
+/// POST one payload, returning the URL alongside the outcome for logging
+async fn deliver<'a>(
+    client: &reqwest::Client,
+    hook: &'a WebhookConfig,
+    event: WebhookEvent,
+    body: &[u8],
+) -> (&'a str, Result<()>) {
+    let mut request = client
+        .post(&hook.url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", concat!("daily/", env!("CARGO_PKG_VERSION")))
+        .header("X-Daily-Event", event.as_str())
+        .body(body.to_vec());
+    if let Some(secret) = hook.secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header("X-Daily-Signature", sign(secret, body));
+    }
+
+    let result = async {
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status());
+        }
+        Ok(())
+    }
+    .await;
+
+    (hook.url.as_str(), result)
+}
+
+/// Whether a webhook's event filter includes `event` (an empty filter matches all)
+fn subscribes_to(hook: &WebhookConfig, event: WebhookEvent) -> bool {
+    hook.events.is_empty() || hook.events.iter().any(|e| e == "*" || e == event.as_str())
+}
+
+/// Signature header value: `sha256=<hex HMAC-SHA256 of the body>`
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(events: &[&str]) -> WebhookConfig {
+        WebhookConfig {
+            url: "http://localhost/hook".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn test_subscribes_to_event_filter() {
+        assert!(subscribes_to(&hook(&[]), WebhookEvent::JobFailed));
+        assert!(subscribes_to(&hook(&["*"]), WebhookEvent::DigestGenerated));
+        assert!(subscribes_to(
+            &hook(&["job.completed", "job.failed"]),
+            WebhookEvent::JobFailed
+        ));
+        assert!(!subscribes_to(
+            &hook(&["digest.generated"]),
+            WebhookEvent::JobCompleted
+        ));
+    }
+
+    #[test]
+    fn test_sign_matches_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Charts for the published insights page, drawn from the JSON embedded in
+// #insights-data. Plain DOM, no dependencies, so the site works offline.
+(function () {
+  var data = JSON.parse(document.getElementById('insights-data').textContent)
+  var root = document.getElementById('charts')
+
+  function el(tag, text, className) {
+    var node = document.createElement(tag)
+    if (text != null) node.textContent = text
+    if (className) node.className = className
+    return node
+  }
+
+  // A table of labelled horizontal bars; rows are [label, value, shown]
+  function bars(title, rows) {
+    if (!rows.length) return
+    root.appendChild(el('h2', title))
+    var max = Math.max.apply(null, rows.map(function (r) { return r[1] })) || 1
+    var table = el('table')
+    rows.forEach(function (r) {
+      var tr = el('tr')
+      tr.appendChild(el('td', r[0]))
+      var cell = el('td')
+      cell.style.width = '60%'
+      var bar = el('div', null, 'bar')
+      bar.style.width = Math.max(1, (r[1] / max) * 100) + '%'
+      cell.appendChild(bar)
+      tr.appendChild(cell)
+      tr.appendChild(el('td', r[2] != null ? r[2] : String(r[1])))
+      table.appendChild(tr)
+    })
+    root.appendChild(table)
+  }
+
+  function counts(list) {
+    return (list || []).map(function (c) { return [c.name, c.count] })
+  }
+
+  var summary = data.total_sessions + ' sessions over ' + data.total_days + ' days'
+  if (data.usage_summary) {
+    summary += ', $' + data.usage_summary.total_cost_usd.toFixed(2) + ' of Claude Code usage'
+  }
+  root.appendChild(el('p', summary, 'meta'))
+
+  bars('Sessions per day', (data.daily_stats || []).slice(-30).map(function (d) {
+    return [d.date, d.session_count]
+  }))
+  var hourly = (data.work_patterns && data.work_patterns.hourly) || []
+  bars('Messages by hour', hourly.map(function (count, hour) {
+    return [(hour < 10 ? '0' : '') + hour + ':00', count]
+  }).filter(function (r) { return r[1] > 0 }))
+  bars('Goals', counts(data.goal_distribution))
+  bars('Friction', counts(data.friction_distribution))
+  bars('Satisfaction', counts(data.satisfaction_distribution))
+  bars('Session types', counts(data.session_type_distribution))
+  bars('Languages', counts(data.language_distribution))
+})()
@@ -0,0 +1,431 @@
+//! Static HTML site of the whole archive (`daily publish`).
+//!
+//! Every date gets a page with its digest and session list, every session
+//! its own page, plus topic pages and an insights page whose charts are
+//! drawn by a small script from embedded JSON. Links are relative, so the
+//! site can be served from any path (an internal server, GitHub Pages) or
+//! opened from disk.
+//!
+//! Privacy: sessions tagged with one of `publish.exclude_tags` are left out
+//! everywhere, and `publish.redact` patterns are blanked in all text.
+
+pub mod share;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{frontmatter, ArchiveManager};
+use crate::config::Config;
+use crate::insights::collector::{InsightsData, InsightsFilter};
+use crate::render::{self, escape, Theme};
+use crate::topics::TopicIndex;
+use crate::usage::pricing::PricingData;
+
+/// Marks a directory as a published site that may be cleared on republish
+const SITE_MARKER: &str = ".daily-site";
+
+const CHARTS_JS: &str = include_str!("charts.js");
+
+/// What a publish wrote
+#[derive(Debug, Serialize)]
+pub struct PublishReport {
+    pub out: PathBuf,
+    pub dates: usize,
+    pub sessions: usize,
+    pub topics: usize,
+    /// Sessions left out by `publish.exclude_tags`
+    pub excluded: usize,
+}
+
+/// The privacy filter applied to everything published
+struct Privacy {
+    exclude_tags: Vec<String>,
+    redact: Vec<Regex>,
+}
+
+impl Privacy {
+    fn new(config: &Config) -> Result<Self> {
+        let redact = config
+            .publish
+            .redact
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid publish.redact pattern '{}'", pattern))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            exclude_tags: config.publish.exclude_tags.clone(),
+            redact,
+        })
+    }
+
+    fn excludes(&self, tags: &[String]) -> bool {
+        tags.iter().any(|tag| {
+            self.exclude_tags
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(tag))
+        })
+    }
+
+    fn redact(&self, text: &str) -> String {
+        self.redact.iter().fold(text.to_string(), |text, re| {
+            re.replace_all(&text, "[redacted]").into_owned()
+        })
+    }
+
+    /// Redact every string in a JSON value
+    fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(s) => *s = self.redact(s),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|v| self.redact_json(v)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|v| self.redact_json(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Render the archive into `out`. A directory from an earlier publish is
+/// cleared first so removed or newly excluded sessions don't linger; any
+/// other non-empty directory is refused.
+pub fn publish(
+    config: &Config,
+    pricing: &PricingData,
+    out: &Path,
+    theme: Theme,
+) -> Result<PublishReport> {
+    let privacy = Privacy::new(config)?;
+    prepare_out_dir(config, out)?;
+    let site = Site {
+        title: &config.publish.title,
+        theme,
+    };
+    let manager = ArchiveManager::new(config.clone());
+
+    let mut dates = manager.list_dates()?;
+    dates.sort_by(|a, b| b.cmp(a));
+    let mut report = PublishReport {
+        out: out.to_path_buf(),
+        dates: 0,
+        sessions: 0,
+        topics: 0,
+        excluded: 0,
+    };
+    let mut published: HashSet<String> = HashSet::new();
+    let mut date_links = String::new();
+
+    for date in &dates {
+        let mut session_links = String::new();
+        let mut count = 0;
+        for entry in manager.session_index(date).unwrap_or_default() {
+            if privacy.excludes(&entry.tags) {
+                report.excluded += 1;
+                continue;
+            }
+            let Ok(content) = manager.read_session(date, &entry.name) else {
+                continue;
+            };
+            let title = privacy.redact(&entry.title);
+            let header = format!(
+                "{}<div class=\"meta\"><a href=\"index.html\">{}</a></div>\n",
+                site.nav("../"),
+                escape(date)
+            );
+            site.write(
+                &out.join(date).join(format!("{}.html", entry.name)),
+                &title,
+                &header,
+                &render::markdown_to_html(&privacy.redact(&content), theme),
+            )?;
+            session_links.push_str(&format!(
+                "<li><a href=\"{}.html\">{}</a></li>\n",
+                escape(&entry.name),
+                escape(&title)
+            ));
+            published.insert(format!("{}/{}", date, entry.name));
+            count += 1;
+        }
+
+        let digest = manager.read_daily_summary(date).ok();
+        if digest.is_none() && session_links.is_empty() {
+            continue;
+        }
+        let mut body = digest
+            .map(|d| render::markdown_to_html(&privacy.redact(&d), theme))
+            .unwrap_or_default();
+        if !session_links.is_empty() {
+            body.push_str(&format!(
+                "<h2>Sessions</h2>\n<ul>\n{}</ul>\n",
+                session_links
+            ));
+        }
+        site.write(
+            &out.join(date).join("index.html"),
+            date,
+            &format!(
+                "{}<div class=\"meta\">{}</div>\n",
+                site.nav("../"),
+                escape(date)
+            ),
+            &body,
+        )?;
+        date_links.push_str(&format!(
+            "<li><a href=\"{date}/index.html\">{date}</a> <span class=\"meta\">{count} session{s}</span></li>\n",
+            date = escape(date),
+            count = count,
+            s = if count == 1 { "" } else { "s" },
+        ));
+        report.dates += 1;
+        report.sessions += count;
+    }
+
+    site.write(
+        &out.join("index.html"),
+        site.title,
+        &site.nav(""),
+        &format!(
+            "<h1>{}</h1>\n<ul>\n{}</ul>\n",
+            escape(site.title),
+            date_links
+        ),
+    )?;
+
+    report.topics = write_topics(config, &site, &privacy, &manager, &published, out)?;
+    write_insights(config, pricing, &site, &privacy, &published, out)?;
+    fs::create_dir_all(out.join("assets"))?;
+    fs::write(out.join("assets").join("charts.js"), CHARTS_JS)?;
+    fs::write(out.join(SITE_MARKER), "")?;
+    Ok(report)
+}
+
+/// Create `out`, or remove the pages an earlier publish wrote there. Other
+/// files (`.git`, `CNAME` for GitHub Pages) are kept.
+fn prepare_out_dir(config: &Config, out: &Path) -> Result<()> {
+    if out.starts_with(config.storage_path()) {
+        anyhow::bail!(
+            "Output directory {} is inside the storage directory",
+            out.display()
+        );
+    }
+    if out.is_dir() && fs::read_dir(out)?.next().is_some() {
+        if !out.join(SITE_MARKER).exists() {
+            anyhow::bail!(
+                "{} is not empty and was not written by `daily publish`; choose an empty directory",
+                out.display()
+            );
+        }
+        for entry in fs::read_dir(out)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let generated = matches!(name.as_ref(), "index.html" | "insights.html")
+                || (path.is_dir()
+                    && (matches!(name.as_ref(), "assets" | "topics")
+                        || chrono::NaiveDate::parse_from_str(&name, "%Y-%m-%d").is_ok()));
+            if generated {
+                let removed = if path.is_dir() {
+                    fs::remove_dir_all(&path)
+                } else {
+                    fs::remove_file(&path)
+                };
+                removed.with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        }
+    }
+    fs::create_dir_all(out).with_context(|| format!("Failed to create {}", out.display()))
+}
+
+/// Topic pages listing their published sessions; returns the number written
+fn write_topics(
+    config: &Config,
+    site: &Site,
+    privacy: &Privacy,
+    manager: &ArchiveManager,
+    published: &HashSet<String>,
+    out: &Path,
+) -> Result<usize> {
+    let mut topic_links = String::new();
+    let mut written = 0;
+    for topic in TopicIndex::load(config).topics {
+        let mut links = String::new();
+        for reference in topic.sessions.iter().rev() {
+            let Some((date, name)) = reference
+                .split_once('/')
+                .filter(|_| published.contains(reference))
+            else {
+                continue;
+            };
+            let content = manager.read_session(date, name).unwrap_or_default();
+            let title = privacy.redact(frontmatter::get(&content, "title").unwrap_or(name));
+            links.push_str(&format!(
+                "<li><span class=\"meta\">{}</span> <a href=\"../{}/{}.html\">{}</a></li>\n",
+                escape(date),
+                escape(date),
+                escape(name),
+                escape(&title)
+            ));
+        }
+        if links.is_empty() {
+            continue;
+        }
+        let title = privacy.redact(&topic.title);
+        site.write(
+            &out.join("topics").join(format!("{}.html", topic.slug)),
+            &title,
+            &site.nav("../"),
+            &format!(
+                "<h1>{}</h1>\n<p>{}</p>\n<h2>Sessions</h2>\n<ul>\n{}</ul>\n",
+                escape(&title),
+                escape(&privacy.redact(&topic.description)),
+                links
+            ),
+        )?;
+        topic_links.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a> <span class=\"meta\">{}</span></li>\n",
+            escape(&topic.slug),
+            escape(&title),
+            escape(&privacy.redact(&topic.description))
+        ));
+        written += 1;
+    }
+
+    let body = if topic_links.is_empty() {
+        "<h1>Topics</h1>\n<p class=\"meta\">No topics yet. Build them with <code>daily topics update</code>.</p>\n".to_string()
+    } else {
+        format!("<h1>Topics</h1>\n<ul>\n{}</ul>\n", topic_links)
+    };
+    site.write(
+        &out.join("topics").join("index.html"),
+        "Topics",
+        &site.nav("../"),
+        &body,
+    )?;
+    Ok(written)
+}
+
+/// The insights page: redacted insights JSON plus the charts script
+fn write_insights(
+    config: &Config,
+    pricing: &PricingData,
+    site: &Site,
+    privacy: &Privacy,
+    published: &HashSet<String>,
+    out: &Path,
+) -> Result<()> {
+    let all_days = ArchiveManager::new(config.clone()).list_dates()?.len();
+    let mut data = InsightsData::collect(
+        config,
+        Some(all_days.max(1)),
+        pricing,
+        &InsightsFilter::default(),
+    )?;
+    data.session_details
+        .retain(|s| published.contains(&format!("{}/{}", s.date, s.session_name)));
+    let mut json = serde_json::to_value(&data)?;
+    privacy.redact_json(&mut json);
+    // `</` would end the script element early
+    let json = serde_json::to_string(&json)?.replace("</", "<\\/");
+
+    site.write(
+        &out.join("insights.html"),
+        "Insights",
+        &site.nav(""),
+        &format!(
+            "<h1>Insights</h1>\n<div id=\"charts\"></div>\n\
+             <script type=\"application/json\" id=\"insights-data\">{}</script>\n\
+             <script src=\"assets/charts.js\"></script>\n",
+            json
+        ),
+    )
+}
+
+/// Shared page chrome
+struct Site<'a> {
+    title: &'a str,
+    theme: Theme,
+}
+
+impl Site<'_> {
+    /// Navigation links; `root` is the relative path to the site root
+    fn nav(&self, root: &str) -> String {
+        format!(
+            "<nav><a href=\"{root}index.html\">{}</a><a href=\"{root}topics/index.html\">Topics</a><a href=\"{root}insights.html\">Insights</a></nav>\n",
+            escape(self.title),
+        )
+    }
+
+    fn write(&self, path: &Path, title: &str, header: &str, body: &str) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let title = if title == self.title {
+            title.to_string()
+        } else {
+            format!("{} - {}", title, self.title)
+        };
+        fs::write(path, render::page(&title, header, body, self.theme))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_publish_skips_excluded_sessions_and_redacts() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().join("archive");
+        let manager = ArchiveManager::new(config.clone());
+        manager
+            .write_session(
+                "2024-01-15",
+                "10_00-auth",
+                "---\ntitle: \"Auth fix\"\ntags: [claude-code]\n---\n\n# Auth fix\n\nRotated sk-abcdefghijklmnopqrstuvwx.\n",
+            )
+            .unwrap();
+        manager
+            .write_session(
+                "2024-01-15",
+                "11_00-salary",
+                "---\ntitle: \"Salary review\"\ntags: [claude-code, private]\n---\n\n# Salary review\n",
+            )
+            .unwrap();
+        manager
+            .write_daily_summary("2024-01-15", "# Daily Summary\n\nFixed auth.\n")
+            .unwrap();
+
+        let out = dir.path().join("site");
+        let pricing = PricingData::from_map(std::collections::HashMap::new());
+        let report = publish(&config, &pricing, &out, Theme::Light).unwrap();
+        assert_eq!((report.dates, report.sessions, report.excluded), (1, 1, 1));
+
+        let session = fs::read_to_string(out.join("2024-01-15/10_00-auth.html")).unwrap();
+        assert!(session.contains("Rotated [redacted]."));
+        assert!(!out.join("2024-01-15/11_00-salary.html").exists());
+        let day = fs::read_to_string(out.join("2024-01-15/index.html")).unwrap();
+        assert!(day.contains("<a href=\"10_00-auth.html\">Auth fix</a>"));
+        assert!(!day.contains("Salary"));
+        let insights = fs::read_to_string(out.join("insights.html")).unwrap();
+        assert!(insights.contains("id=\"insights-data\""));
+        assert!(out.join("assets/charts.js").exists());
+
+        // Republishing replaces the old pages and keeps other files
+        fs::write(out.join("CNAME"), "notes.example.com").unwrap();
+        fs::create_dir_all(out.join("2023-12-31")).unwrap();
+        publish(&config, &pricing, &out, Theme::Light).unwrap();
+        assert!(out.join("CNAME").exists());
+        assert!(!out.join("2023-12-31").exists());
+
+        // Directories not written by publish are refused
+        let foreign = dir.path().join("docs");
+        fs::create_dir_all(&foreign).unwrap();
+        fs::write(foreign.join("README.md"), "mine").unwrap();
+        assert!(publish(&config, &pricing, &foreign, Theme::Light).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Markdown to HTML for digests read outside the dashboard.
+//!
+//! Digests become standalone pages with inline CSS and syntax-highlighted
+//! code blocks, so they render the same in a browser, an email client or a
+//! saved file. Raw HTML in the markdown is shown as text, not rendered.
+
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+
+use crate::archive::frontmatter;
+
+/// Color scheme of a rendered page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl std::str::FromStr for Theme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "light" => Ok(Theme::Light),
+            "dark" => Ok(Theme::Dark),
+            _ => anyhow::bail!("Unknown theme '{}': expected light or dark", s),
+        }
+    }
+}
+
+impl Theme {
+    /// Bundled syntect theme used for code blocks
+    fn code_theme(self) -> &'static str {
+        match self {
+            Theme::Light => "InspiredGitHub",
+            Theme::Dark => "base16-ocean.dark",
+        }
+    }
+
+    fn palette(self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: "#ffffff",
+                text: "#1f2328",
+                muted: "#59636e",
+                border: "#d1d9e0",
+                link: "#0969da",
+            },
+            Theme::Dark => Palette {
+                background: "#0d1117",
+                text: "#e6edf3",
+                muted: "#9198a1",
+                border: "#3d444d",
+                link: "#4493f8",
+            },
+        }
+    }
+}
+
+/// Page colors of a theme
+struct Palette {
+    background: &'static str,
+    text: &'static str,
+    muted: &'static str,
+    border: &'static str,
+    link: &'static str,
+}
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn themes() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// HTML fragment for a markdown document (frontmatter is dropped)
+pub fn markdown_to_html(markdown: &str, theme: Theme) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let parser = Parser::new_ext(frontmatter::body(markdown), options);
+
+    let mut events = Vec::new();
+    let mut code: Option<(String, String)> = None;
+    for event in parser {
+        match (event, code.as_mut()) {
+            (Event::Start(Tag::CodeBlock(kind)), _) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((lang, String::new()));
+            }
+            (Event::Text(text), Some((_, body))) => body.push_str(&text),
+            (Event::End(TagEnd::CodeBlock), Some(_)) => {
+                let (lang, body) = code.take().unwrap_or_default();
+                events.push(Event::Html(highlight(&body, &lang, theme).into()));
+            }
+            (Event::Html(raw) | Event::InlineHtml(raw), _) => events.push(Event::Text(raw)),
+            (event, _) => events.push(event),
+        }
+    }
+
+    let mut out = String::new();
+    html::push_html(&mut out, events.into_iter());
+    out
+}
+
+/// A highlighted `<pre>` block, or plain escaped text for unknown languages
+fn highlight(code: &str, lang: &str, theme: Theme) -> String {
+    let syntaxes = syntaxes();
+    let syntax = syntaxes
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    syntect::html::highlighted_html_for_string(
+        code,
+        syntaxes,
+        syntax,
+        &themes().themes[theme.code_theme()],
+    )
+    .unwrap_or_else(|_| {
+        let mut out = String::from("<pre><code>");
+        html::push_html(&mut out, std::iter::once(Event::Text(CowStr::from(code))));
+        out.push_str("</code></pre>\n");
+        out
+    })
+}
+
+/// A standalone HTML page for a date's digest
+pub fn digest_page(date: &str, markdown: &str, theme: Theme) -> String {
+    page(
+        &format!("Daily digest: {}", date),
+        &format!("<div class=\"meta\">{}</div>\n", escape(date)),
+        &markdown_to_html(markdown, theme),
+        theme,
+    )
+}
+
+/// A themed page with inline styles. `title` is plain text; `header` (shown
+/// above the content) and `body` are HTML.
+pub fn page(title: &str, header: &str, body: &str, theme: Theme) -> String {
+    let Palette {
+        background,
+        text,
+        muted,
+        border,
+        link,
+    } = theme.palette();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+  body {{ margin: 0; background: {background}; color: {text};
+    font: 16px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; }}
+  main {{ max-width: 760px; margin: 0 auto; padding: 32px 24px; }}
+  nav {{ margin-bottom: 24px; font-size: 14px; }}
+  nav a {{ margin-right: 16px; }}
+  .meta {{ color: {muted}; font-size: 14px; margin-bottom: 8px; }}
+  h1, h2, h3 {{ line-height: 1.25; margin: 1.5em 0 0.5em; }}
+  h1 {{ font-size: 1.8em; margin-top: 0; }}
+  h2 {{ font-size: 1.35em; padding-bottom: 0.3em; border-bottom: 1px solid {border}; }}
+  a {{ color: {link}; }}
+  pre {{ padding: 12px 16px; border-radius: 6px; overflow-x: auto; font-size: 13px; line-height: 1.45; }}
+  code {{ font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }}
+  :not(pre) > code {{ padding: 0.2em 0.4em; border-radius: 4px; background: {border}; font-size: 85%; }}
+  blockquote {{ margin: 0; padding: 0 1em; color: {muted}; border-left: 4px solid {border}; }}
+  table {{ border-collapse: collapse; }}
+  th, td {{ padding: 6px 12px; border: 1px solid {border}; }}
+  hr {{ border: 0; border-top: 1px solid {border}; }}
+  .bar {{ height: 10px; border-radius: 3px; background: {link}; }}
+</style>
+</head>
+<body>
+<main>
+{header}{body}</main>
+</body>
+</html>
+"#,
+        title = escape(title),
+    )
+}
+
+/// Escape text for HTML content and attribute values
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_page_highlights_code_and_escapes_html() {
+        let markdown = "---\ndate: 2024-01-15\n---\n\n# Daily Summary\n\n\
+            | Task | Done |\n|---|---|\n| auth | yes |\n\n\
+            <script>alert(1)</script>\n\n```rust\nfn main() {}\n```\n";
+        let page = digest_page("2024-01-15", markdown, Theme::Dark);
+
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(!page.contains("date: 2024-01-15"));
+        assert!(page.contains("<h1>Daily Summary</h1>"));
+        assert!(page.contains("<td>auth</td>"));
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(!page.contains("<script>"));
+        // Highlighted spans carry inline colors
+        assert!(page.contains("<pre style=\"background-color:#2b303b;\">"));
+        assert!(page.contains("<span style=\"color:#b48ead;\">fn </span>"));
+        assert!("sepia".parse::<Theme>().is_err());
+    }
+}
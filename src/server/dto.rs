@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use crate::jobs::{JobInfo, JobStatus, JobType};
+use crate::archive::citations::CitedSession;
+use crate::archive::permalink::Target;
+use crate::embeddings::RelatedSession;
+use crate::goals::GoalStat;
+use crate::insights::patterns::WorkPatterns;
+use crate::insights::projects::ProjectSegment;
+use crate::jobs::{JobInfo, JobStatus};
+use crate::summarizer::ModelUsage;
 
 /// Generic API response wrapper
 #[derive(Serialize)]
@@ -36,6 +44,12 @@ pub struct DateInfo {
     pub date: String,
     pub session_count: usize,
     pub has_digest: bool,
+    /// Finalized with `daily lock`; digests are not regenerated
+    pub locked: bool,
+    /// The digest was marked read since it was last generated
+    pub read: bool,
+    /// Sessions not marked read since they were last written
+    pub unread_sessions: usize,
 }
 
 /// Brief session info for listing
@@ -44,17 +58,72 @@ pub struct SessionBrief {
     pub name: String,
     pub title: String,
     pub summary_preview: String,
+    pub pinned: bool,
+    /// Marked read since it was last written
+    pub read: bool,
+}
+
+/// One page of a date's (optionally filtered) sessions
+#[derive(Serialize)]
+pub struct SessionPageDto {
+    pub sessions: Vec<SessionBrief>,
+    /// Sessions matching the filters, across all pages
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub has_more: bool,
+}
+
+/// A pinned session with the date it belongs to
+#[derive(Serialize)]
+pub struct PinnedSessionDto {
+    pub date: String,
+    #[serde(flatten)]
+    pub session: SessionBrief,
 }
 
 /// A single card within a daily summary section
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct SummaryCardDto {
     pub title: String,
     pub content: String,
+    /// Sessions the card was drawn from (`summarization.digest_citations`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub sessions: Vec<CitedSession>,
+}
+
+/// A date's digest (if any) plus its session list, as shown by `daily view`
+#[derive(Serialize)]
+pub struct DateArchiveDto {
+    pub date: String,
+    pub summary: Option<DailySummaryDto>,
+    pub sessions: Vec<SessionBrief>,
+}
+
+/// Several days of `daily view --week` / `--last` / `--from`, oldest first
+#[derive(Serialize)]
+pub struct DateRangeDto {
+    pub from: String,
+    pub to: String,
+    pub days: Vec<DateArchiveDto>,
+    pub total_sessions: usize,
+    /// Claude Code usage of the sessions started in the range
+    pub total_cost_usd: f64,
 }
 
 /// Daily summary DTO
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DailySummaryDto {
     pub date: String,
     pub overview: String,
@@ -67,30 +136,89 @@ pub struct DailySummaryDto {
     pub tomorrow_focus: Vec<SummaryCardDto>,
     pub raw_content: String,
     pub file_path: String,
+    /// Finalized with `daily lock`; digests are not regenerated
+    pub locked: bool,
+    /// Language of this translation; null for daily.md itself
+    pub language: Option<String>,
+    /// Languages the digest was translated into (`daily.{lang}.md`)
+    pub languages: Vec<String>,
 }
 
 /// Session detail DTO
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct SessionDetailDto {
     pub name: String,
     pub content: String,
     pub metadata: SessionMetadata,
     pub file_path: String,
+    /// Similar sessions from the embeddings index (empty when disabled).
+    /// GraphQL exposes this as `SessionNode.related` instead.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub related: Vec<RelatedSessionDto>,
+}
+
+/// A session similar to another by embedding distance
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct RelatedSessionDto {
+    pub date: String,
+    pub name: String,
+    pub title: String,
+    pub score: f32,
+}
+
+impl From<RelatedSession> for RelatedSessionDto {
+    fn from(related: RelatedSession) -> Self {
+        Self {
+            date: related.date,
+            name: related.name,
+            title: related.title,
+            score: related.score,
+        }
+    }
 }
 
 /// Session metadata extracted from frontmatter
 #[derive(Serialize, Default)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct SessionMetadata {
     pub title: String,
     pub date: String,
+    /// Stable ID for permalinks, see `/api/resolve`
+    pub id: Option<String>,
     pub session_id: Option<String>,
     pub cwd: Option<String>,
     pub git_branch: Option<String>,
     pub duration: Option<String>,
+    pub pinned: bool,
+    /// Rating recorded with `daily rate`, `daily feedback` or the annotation endpoint
+    pub user_outcome: Option<String>,
+    pub user_satisfaction: Option<String>,
+    pub user_note: Option<String>,
+    pub user_stars: Option<u8>,
 }
 
 /// Job DTO for API responses
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct JobDto {
     pub id: String,
     pub pid: u32,
@@ -101,21 +229,36 @@ pub struct JobDto {
     pub started_at: String,
     pub finished_at: Option<String>,
     pub elapsed: String,
+    /// 1-based position in the job queue, only set while queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// IDs of the jobs this one starts after
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Dependencies still queued or running
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waiting_on: Vec<String>,
+    /// Tokens used by the job's model calls, once recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Box<JobUsageDto>>,
+    /// Quality score of the generated summary or digest, once recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Box<JobQualityDto>>,
+    /// Failed because a model call hit `summarization.call_timeout_secs`
+    #[serde(default)]
+    pub timed_out: bool,
 }
 
 impl From<JobInfo> for JobDto {
     fn from(info: JobInfo) -> Self {
         let (status, status_type) = match &info.status {
+            JobStatus::Queued => ("Queued".to_string(), "queued".to_string()),
             JobStatus::Running => ("Running".to_string(), "running".to_string()),
             JobStatus::Completed => ("Completed".to_string(), "completed".to_string()),
             JobStatus::Failed { error } => (format!("Failed: {}", error), "failed".to_string()),
         };
 
-        let job_type = match &info.job_type {
-            JobType::SessionEnd => "session_end".to_string(),
-            JobType::AutoSummarize => "auto_summarize".to_string(),
-            JobType::Manual => "manual".to_string(),
-        };
+        let job_type = info.job_type.key().to_string();
 
         // Compute elapsed before moving fields
         let elapsed = info.elapsed_human();
@@ -134,6 +277,67 @@ impl From<JobInfo> for JobDto {
             started_at,
             finished_at,
             elapsed,
+            queue_position: None,
+            depends_on: info.depends_on,
+            waiting_on: vec![],
+            usage: info.usage.map(|usage| Box::new(usage.into())),
+            quality: info.quality.map(|quality| {
+                Box::new(JobQualityDto {
+                    score: quality.score,
+                    low: quality.low,
+                    issues: quality.issues,
+                })
+            }),
+            timed_out: info.timed_out,
+        }
+    }
+}
+
+/// Quality score of a job's generated summary or digest
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct JobQualityDto {
+    pub score: u8,
+    /// Below `summarization.min_quality_score`
+    pub low: bool,
+    pub issues: Vec<String>,
+}
+
+/// Token usage of a job's model calls
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct JobUsageDto {
+    pub model: String,
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+impl From<ModelUsage> for JobUsageDto {
+    fn from(usage: ModelUsage) -> Self {
+        Self {
+            model: usage.model,
+            calls: usage.calls,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            cache_read_input_tokens: usage.cache_read_input_tokens,
+            cost_usd: usage.cost_usd,
+            stop_reason: usage.stop_reason,
         }
     }
 }
@@ -150,17 +354,35 @@ pub struct JobLogDto {
 pub struct DigestResponse {
     pub message: String,
     pub session_count: usize,
+    /// `Digest` job tracking the run; progress is written to its log
+    pub job_id: String,
 }
 
-/// WebSocket message types
+/// Where an `/api/resolve` reference points, with the web UI path to open
+#[derive(Serialize)]
+pub struct ResolvedDto {
+    #[serde(flatten)]
+    pub target: Target,
+    pub url: String,
+}
+
+/// WebSocket message types, pushed from `/ws`
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
 pub enum WsMessage {
-    JobUpdated(JobDto),
-    NewSession { date: String, name: String },
-    DigestCompleted { date: String },
+    JobUpdated(Box<JobDto>),
+    NewSession {
+        date: String,
+        name: String,
+    },
+    DigestCompleted {
+        date: String,
+        job_id: String,
+    },
     Connected,
+    /// Sent before the server closes every websocket and exits
+    ServerShutdown,
 }
 
 /// Config DTO for API responses
@@ -169,6 +391,9 @@ pub struct ConfigDto {
     pub storage_path: String,
     pub model: String,
     pub summary_language: String,
+    /// Language the dashboard starts in: `output.ui_language`, or the
+    /// summary language
+    pub ui_language: &'static str,
     pub enable_daily_summary: bool,
     pub enable_extraction_hints: bool,
     pub auto_digest_enabled: bool,
@@ -178,6 +403,72 @@ pub struct ConfigDto {
     pub auto_summarize_enabled: bool,
     pub auto_summarize_on_show: bool,
     pub auto_summarize_inactive_minutes: u64,
+    /// Lint findings for the configured custom templates
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub template_warnings: Vec<TemplateWarningDto>,
+}
+
+/// A lint finding for a custom prompt template
+#[derive(Serialize)]
+pub struct TemplateWarningDto {
+    /// Template field, e.g. "session_summary"
+    pub template: String,
+    /// Warning kind, e.g. "unknown_variable", "missing_required"
+    pub kind: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+}
+
+/// Template validation request
+#[derive(Deserialize)]
+pub struct TemplateValidateRequest {
+    /// Template field, e.g. "session_summary"
+    pub template_type: String,
+    pub template: String,
+}
+
+/// Digest regeneration request
+#[derive(Deserialize, Default)]
+pub struct DigestRegenerateRequest {
+    /// Section keys to keep verbatim, e.g. "reflections"
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+/// Journal note to append to a day
+#[derive(Deserialize)]
+pub struct NoteRequest {
+    pub text: String,
+}
+
+/// Hand edit of a session or digest: full markdown and/or replacement
+/// bodies keyed by `## ` section heading
+#[derive(Deserialize)]
+pub struct ContentEditRequest {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub sections: BTreeMap<String, String>,
+}
+
+/// Session rating request; omitted fields keep their stored value
+#[derive(Deserialize)]
+pub struct SessionAnnotationRequest {
+    pub outcome: Option<String>,
+    pub satisfaction: Option<String>,
+    pub note: Option<String>,
+    /// 1-5 stars; stands in for satisfaction when that is not given
+    pub stars: Option<u8>,
+    /// Remove the stored rating before applying the other fields
+    #[serde(default)]
+    pub clear: bool,
+}
+
+/// Session rename request
+#[derive(Deserialize)]
+pub struct SessionRenameRequest {
+    /// New topic; the rest of the name follows `archive.session_name_template`
+    pub topic: String,
 }
 
 /// Config update request
@@ -229,6 +520,11 @@ pub struct DefaultTemplatesDto {
 
 /// Insights data for the dashboard
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct InsightsDto {
     pub total_days: usize,
     pub total_sessions: usize,
@@ -243,10 +539,21 @@ pub struct InsightsDto {
     pub trends: Option<TrendDto>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_summary: Option<UsageSummaryDto>,
+    pub activity: ActivityDto,
+    pub tracked_goals: Vec<GoalStatDto>,
+    pub work_patterns: WorkPatternsDto,
+    /// Per-project segments, with `group_by=project`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<ProjectSegmentDto>>,
 }
 
 /// Trend analysis data for period-over-period comparison
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct TrendDto {
     pub period_label: String,
     pub comparison_label: String,
@@ -267,6 +574,11 @@ pub struct TrendDto {
 
 /// Weekly breakdown statistics
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct WeeklyStatDto {
     pub week_label: String,
     pub session_count: usize,
@@ -274,7 +586,203 @@ pub struct WeeklyStatDto {
     pub success_rate: f64,
 }
 
+/// Tool usage analytics across archived sessions
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct ToolInsightsDto {
+    pub total_calls: usize,
+    pub total_failures: usize,
+    pub failure_rate: f64,
+    pub longest_chain: usize,
+    pub tools: Vec<ToolStatDto>,
+    pub sessions: Vec<SessionToolUsageDto>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct ToolStatDto {
+    pub name: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct SessionToolUsageDto {
+    pub date: String,
+    pub session_name: String,
+    pub total_calls: usize,
+    pub failures: usize,
+    pub longest_chain: usize,
+    pub tools: Vec<ToolStatDto>,
+}
+
+/// Work reported on a goal within the analyzed dates
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct GoalStatDto {
+    pub id: u32,
+    pub title: String,
+    /// "active" or "done"
+    pub status: String,
+    pub session_count: usize,
+    pub day_count: usize,
+    /// Date the goal was marked done
+    pub completed: Option<String>,
+}
+
+impl From<GoalStat> for GoalStatDto {
+    fn from(stat: GoalStat) -> Self {
+        Self {
+            id: stat.id,
+            title: stat.title,
+            status: stat.status.key().to_string(),
+            session_count: stat.session_count,
+            day_count: stat.day_count,
+            completed: stat.completed,
+        }
+    }
+}
+
+/// Insights of one project's sessions
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct ProjectSegmentDto {
+    pub project: String,
+    pub session_count: usize,
+    pub active_days: usize,
+    pub friction_rate: f64,
+    pub success_rate: f64,
+    pub goal_distribution: Vec<CategoryCountDto>,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+}
+
+impl From<ProjectSegment> for ProjectSegmentDto {
+    fn from(segment: ProjectSegment) -> Self {
+        Self {
+            project: segment.project,
+            session_count: segment.session_count,
+            active_days: segment.active_days,
+            friction_rate: segment.friction_rate,
+            success_rate: segment.success_rate,
+            goal_distribution: segment
+                .goal_distribution
+                .into_iter()
+                .map(|c| CategoryCountDto {
+                    name: c.name,
+                    count: c.count,
+                })
+                .collect(),
+            total_tokens: segment.total_tokens,
+            total_cost: segment.total_cost,
+        }
+    }
+}
+
+/// Hours of day and focus blocks from session transcripts
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct WorkPatternsDto {
+    /// Messages per local hour of day, 24 entries
+    pub hourly: Vec<usize>,
+    pub peak_hour: Option<u32>,
+    /// Percentage of messages sent between 22:00 and 05:59
+    pub late_night_ratio: f64,
+    pub longest_focus_minutes: i64,
+    pub focus_blocks: Vec<FocusBlockDto>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct FocusBlockDto {
+    pub date: String,
+    pub start: String,
+    pub end: String,
+    pub minutes: i64,
+}
+
+impl From<WorkPatterns> for WorkPatternsDto {
+    fn from(p: WorkPatterns) -> Self {
+        Self {
+            hourly: p.hourly,
+            peak_hour: p.peak_hour,
+            late_night_ratio: p.late_night_ratio,
+            longest_focus_minutes: p.longest_focus_minutes,
+            focus_blocks: p
+                .focus_blocks
+                .into_iter()
+                .map(|b| FocusBlockDto {
+                    date: b.date,
+                    start: b.start,
+                    end: b.end,
+                    minutes: b.minutes,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Calendar heatmap and streak statistics
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct ActivityDto {
+    pub heatmap: Vec<HeatmapDayDto>,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub active_days: usize,
+}
+
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct HeatmapDayDto {
+    pub date: String,
+    pub session_count: usize,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DailyStatDto {
     pub date: String,
     pub session_count: usize,
@@ -286,6 +794,11 @@ pub struct DailyStatDto {
 }
 
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct CategoryCountDto {
     pub name: String,
     pub count: usize,
@@ -293,6 +806,11 @@ pub struct CategoryCountDto {
 
 /// Per-session insight combining archive metadata with facet analysis data
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct SessionInsightDto {
     pub session_id: String,
     pub date: String,
@@ -306,11 +824,18 @@ pub struct SessionInsightDto {
     pub claude_helpfulness: Option<String>,
     pub session_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub token_usage: Option<SessionUsageDto>,
 }
 
 /// Per-session insight for a specific date's insights endpoint
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DateSessionInsightDto {
     pub name: String,
     pub session_id: String,
@@ -327,6 +852,11 @@ pub struct DateSessionInsightDto {
 
 /// Aggregated day-level insight summary
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DayInsightSummaryDto {
     pub total_sessions: usize,
     pub sessions_with_friction: usize,
@@ -343,13 +873,49 @@ pub struct DayInsightSummaryDto {
 
 /// Complete date insights response
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DateInsightsDto {
     pub sessions: Vec<DateSessionInsightDto>,
     pub day_summary: DayInsightSummaryDto,
 }
 
+/// Everything the day view shows, from `/api/dates/:date/overview`
+#[derive(Serialize)]
+pub struct DayOverviewDto {
+    pub date: String,
+    pub has_digest: bool,
+    pub session_count: usize,
+    /// The date's daily.md, if one was written
+    pub summary: Option<DailySummaryDto>,
+    pub sessions: Vec<SessionBrief>,
+    pub insights: Option<DateInsightsDto>,
+    pub usage: DayUsageDto,
+    /// Queued or running jobs started that day or digesting it
+    pub jobs: Vec<JobDto>,
+}
+
+/// Token usage of a day's archived sessions
+#[derive(Serialize, Default)]
+pub struct DayUsageDto {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+    pub subagent_cost_usd: f64,
+}
+
 /// Token usage for a single session
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct SessionUsageDto {
     pub session_id: String,
     pub input_tokens: u64,
@@ -357,11 +923,18 @@ pub struct SessionUsageDto {
     pub cache_creation_tokens: u64,
     pub cache_read_tokens: u64,
     pub total_cost_usd: f64,
+    /// Part of `total_cost_usd` spent by sub-agents
+    pub subagent_cost_usd: f64,
     pub model_calls: Vec<ModelUsageCountDto>,
 }
 
 /// Model usage count entry
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct ModelUsageCountDto {
     pub model: String,
     pub count: usize,
@@ -369,6 +942,11 @@ pub struct ModelUsageCountDto {
 
 /// Daily usage data for charts
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct DailyUsageDto {
     pub date: String,
     pub input_tokens: u64,
@@ -379,8 +957,30 @@ pub struct DailyUsageDto {
     pub session_count: usize,
 }
 
+/// Usage totals for one project directory
+#[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
+pub struct ProjectUsageDto {
+    pub project: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+    pub session_count: usize,
+}
+
 /// Global usage summary
 #[derive(Serialize)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(rename_fields = "snake_case")
+)]
 pub struct UsageSummaryDto {
     pub total_input_tokens: u64,
     pub total_output_tokens: u64,
@@ -390,6 +990,38 @@ pub struct UsageSummaryDto {
     pub total_sessions: usize,
     pub model_distribution: Vec<ModelUsageCountDto>,
     pub daily_usage: Vec<DailyUsageDto>,
+    pub projects: Vec<ProjectUsageDto>,
+}
+
+/// New goal
+#[derive(Deserialize)]
+pub struct GoalCreateRequest {
+    pub title: String,
+}
+
+/// Goal changes; omitted fields are left as they are
+#[derive(Deserialize)]
+pub struct GoalUpdateRequest {
+    pub title: Option<String>,
+    /// "active" or "done"
+    pub status: Option<String>,
+}
+
+/// A topic page with the sessions filed under it
+#[derive(Serialize)]
+pub struct TopicDetailDto {
+    #[serde(flatten)]
+    pub topic: crate::topics::Topic,
+    /// Markdown of `topics/{slug}.md`
+    pub content: String,
+}
+
+/// Question to answer from the archive
+#[derive(Deserialize)]
+pub struct AskRequest {
+    pub question: String,
+    /// Maximum number of sessions and digests to use (default 5)
+    pub limit: Option<usize>,
 }
 
 /// Request to install a skill or command from daily summary card
@@ -423,6 +1055,9 @@ pub enum ConversationContentBlock {
         tool_use_id: String,
         name: String,
         input: serde_json::Value,
+        /// Sub-agent conversation launched by this call (Task tool)
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        sidechain: Vec<ConversationMessage>,
     },
 
     /// Result returned from a tool
@@ -431,6 +1066,10 @@ pub enum ConversationContentBlock {
         tool_use_id: String,
         content: String,
     },
+
+    /// Extended thinking, only with `include_thinking=true`
+    #[serde(rename = "thinking")]
+    Thinking { thinking: String },
 }
 
 /// A conversation message (one turn in the chat)
@@ -440,6 +1079,60 @@ pub struct ConversationMessage {
     pub content: Vec<ConversationContentBlock>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<String>,
+    /// Transcript line (1-based) the message starts on; None in sub-agent runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// A conversation message matching a search
+#[derive(Serialize)]
+pub struct ConversationHitDto {
+    /// Position in the conversation, as counted by `total_entries`
+    pub index: usize,
+    /// Page the message is on for the requested page size
+    pub page: usize,
+    pub role: String,
+    /// Text around the first match
+    pub snippet: String,
+}
+
+/// Matches of a find-in-conversation search
+#[derive(Serialize)]
+pub struct ConversationSearchDto {
+    pub query: String,
+    pub hits: Vec<ConversationHitDto>,
+    pub total_entries: usize,
+    pub page_size: usize,
+    pub has_transcript: bool,
+}
+
+/// Where one summary item came from
+#[derive(Serialize)]
+pub struct SourceItemDto {
+    /// The bullet (or summary paragraph) as archived
+    pub text: String,
+    /// Transcript line ranges, first and last line inclusive
+    pub lines: Vec<[usize; 2]>,
+    /// Conversation messages starting within `lines`
+    pub messages: Vec<usize>,
+    /// Page of the first of `messages`
+    pub page: Option<usize>,
+}
+
+/// Sources of the items of one summary section
+#[derive(Serialize)]
+pub struct SectionSourcesDto {
+    /// "summary", "decisions" or "learnings"
+    pub section: String,
+    pub items: Vec<SourceItemDto>,
+}
+
+/// Summary items of a session linked to the conversation
+#[derive(Serialize)]
+pub struct SessionSourcesDto {
+    pub sections: Vec<SectionSourcesDto>,
+    pub page_size: usize,
+    pub has_transcript: bool,
 }
 
 /// Paginated conversation response
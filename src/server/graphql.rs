@@ -0,0 +1,351 @@
+//! GraphQL endpoint exposing the dashboard data with field-level selection,
+//! so a whole view (dates → sessions → details → insights) is one round trip.
+//!
+//! Objects reuse the REST DTOs and keep their snake_case field names, so
+//! responses match the shapes the web client already understands.
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, InputObject, Object, Request, Response, Schema,
+};
+use axum::{Extension, Json};
+use chrono::{Duration, Local};
+use std::sync::Arc;
+
+use crate::archive::paths::PathFilter;
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+use crate::insights::collector::{InsightsData, InsightsFilter};
+use crate::insights::daily::DateInsights;
+use crate::insights::tools::ToolInsights;
+use crate::usage::scanner;
+
+use super::dto::*;
+use super::handlers::{self, AppState};
+
+pub type DailySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema with the shared application state attached
+pub fn build_schema(state: Arc<AppState>) -> DailySchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(state)
+        .finish()
+}
+
+/// POST /api/graphql
+pub async fn graphql_handler(
+    Extension(schema): Extension<DailySchema>,
+    Json(request): Json<Request>,
+) -> Json<Response> {
+    Json(schema.execute(request).await)
+}
+
+fn archive(ctx: &Context<'_>) -> ArchiveManager {
+    ctx.data_unchecked::<Arc<AppState>>().archive()
+}
+
+fn config(ctx: &Context<'_>) -> Config {
+    ctx.data_unchecked::<Arc<AppState>>()
+        .config
+        .read()
+        .unwrap()
+        .clone()
+}
+
+pub struct QueryRoot;
+
+#[Object(rename_fields = "snake_case", rename_args = "snake_case")]
+impl QueryRoot {
+    /// Archive dates, newest first
+    async fn dates(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<DateNode>> {
+        let manager = archive(ctx);
+        let dates = manager.list_dates()?;
+        Ok(dates
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|date| DateNode {
+                info: handlers::build_date_info(&manager, date),
+            })
+            .collect())
+    }
+
+    /// A single archive date, or null if nothing is archived for it
+    async fn date(
+        &self,
+        ctx: &Context<'_>,
+        date: String,
+    ) -> async_graphql::Result<Option<DateNode>> {
+        let manager = archive(ctx);
+        if !manager.list_dates()?.contains(&date) {
+            return Ok(None);
+        }
+        Ok(Some(DateNode {
+            info: handlers::build_date_info(&manager, date),
+        }))
+    }
+
+    /// Pinned sessions across all dates, most recent date first
+    async fn pinned(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<SessionNode>> {
+        let manager = archive(ctx);
+        Ok(handlers::build_pinned_dtos(&manager)?
+            .into_iter()
+            .map(|pinned| SessionNode {
+                date: pinned.date,
+                brief: pinned.session,
+            })
+            .collect())
+    }
+
+    /// Aggregated insights over the last `days` days (or the filter's date
+    /// range), with per-project segments when `group_by` is "project"
+    async fn insights(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 30)] days: usize,
+        filter: Option<InsightsFilterInput>,
+        group_by: Option<String>,
+    ) -> async_graphql::Result<InsightsDto> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let filter = filter.map(InsightsFilter::from).unwrap_or_default();
+        let data = InsightsData::collect(&config(ctx), Some(days), &state.pricing, &filter)?;
+        Ok(handlers::build_grouped_insights_dto(
+            data,
+            group_by.as_deref(),
+        )?)
+    }
+
+    /// Tool call analytics from archived session transcripts
+    async fn tool_insights(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 30)] days: usize,
+    ) -> async_graphql::Result<ToolInsightsDto> {
+        let data = ToolInsights::collect(&config(ctx), Some(days))?;
+        Ok(handlers::build_tool_insights_dto(data))
+    }
+
+    /// Token usage and cost over the last `days` days
+    async fn usage(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 30)] days: usize,
+    ) -> UsageSummaryDto {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let today = Local::now().date_naive();
+        let dates: Vec<String> = (0..days as i64)
+            .map(|i| (today - Duration::days(i)).format("%Y-%m-%d").to_string())
+            .collect();
+        let config = state.config.read().unwrap().clone();
+        let sessions =
+            scanner::scan_all_sessions(None, &state.pricing, &PathFilter::from_config(&config));
+        handlers::map_usage_summary_dto(scanner::aggregate_usage(&sessions, Some(&dates)))
+    }
+
+    /// Background jobs, newest first, including finished ones unless
+    /// `include_completed` is false
+    async fn jobs(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = true)] include_completed: bool,
+        #[graphql(default = 0)] offset: usize,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<JobDto>> {
+        Ok(handlers::build_job_dtos(&config(ctx), include_completed)?
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn job(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<JobDto> {
+        Ok(handlers::build_job_dto(&config(ctx), &id)?)
+    }
+}
+
+/// One archive date with lazily resolved summary, sessions, and insights
+pub struct DateNode {
+    info: DateInfo,
+}
+
+#[Object(rename_fields = "snake_case")]
+impl DateNode {
+    async fn date(&self) -> &str {
+        &self.info.date
+    }
+
+    async fn session_count(&self) -> usize {
+        self.info.session_count
+    }
+
+    async fn has_digest(&self) -> bool {
+        self.info.has_digest
+    }
+
+    async fn read(&self) -> bool {
+        self.info.read
+    }
+
+    async fn unread_sessions(&self) -> usize {
+        self.info.unread_sessions
+    }
+
+    /// Parsed daily.md, or null if the date has none
+    async fn summary(&self, ctx: &Context<'_>) -> Option<DailySummaryDto> {
+        let manager = archive(ctx);
+        handlers::build_daily_summary_dto(&manager, &self.info.date).ok()
+    }
+
+    async fn sessions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<SessionNode>> {
+        let manager = archive(ctx);
+        Ok(handlers::build_session_briefs(&manager, &self.info.date)?
+            .into_iter()
+            .map(|brief| SessionNode {
+                date: self.info.date.clone(),
+                brief,
+            })
+            .collect())
+    }
+
+    async fn insights(&self, ctx: &Context<'_>) -> async_graphql::Result<DateInsightsDto> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let data = DateInsights::collect(&self.info.date, &state.archive(), &state.pricing)?;
+        Ok(handlers::build_date_insights_dto(data))
+    }
+}
+
+/// A session listed under a date; `detail` loads the full archive
+pub struct SessionNode {
+    date: String,
+    brief: SessionBrief,
+}
+
+#[Object(rename_fields = "snake_case")]
+impl SessionNode {
+    async fn date(&self) -> &str {
+        &self.date
+    }
+
+    async fn name(&self) -> &str {
+        &self.brief.name
+    }
+
+    async fn title(&self) -> &str {
+        &self.brief.title
+    }
+
+    async fn summary_preview(&self) -> &str {
+        &self.brief.summary_preview
+    }
+
+    async fn pinned(&self) -> bool {
+        self.brief.pinned
+    }
+
+    async fn read(&self) -> bool {
+        self.brief.read
+    }
+
+    async fn detail(&self, ctx: &Context<'_>) -> async_graphql::Result<SessionDetailDto> {
+        let manager = archive(ctx);
+        Ok(handlers::build_session_detail_dto(
+            &manager,
+            &self.date,
+            self.brief.name.clone(),
+        )?)
+    }
+
+    /// Similar sessions from the embeddings index (empty when disabled)
+    async fn related(&self, ctx: &Context<'_>) -> Vec<RelatedSessionDto> {
+        handlers::related_session_dtos(&config(ctx), &self.date, &self.brief.name).await
+    }
+}
+
+/// Same criteria as the `/api/insights` query parameters
+#[derive(InputObject, Default)]
+#[graphql(rename_fields = "snake_case")]
+pub struct InsightsFilterInput {
+    project: Option<String>,
+    goal_category: Option<String>,
+    outcome: Option<String>,
+    session_type: Option<String>,
+    friction_type: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+impl From<InsightsFilterInput> for InsightsFilter {
+    fn from(input: InsightsFilterInput) -> Self {
+        let non_empty = |v: Option<String>| v.filter(|s| !s.is_empty());
+        InsightsFilter {
+            project: non_empty(input.project),
+            goal_category: non_empty(input.goal_category),
+            outcome: non_empty(input.outcome),
+            session_type: non_empty(input.session_type),
+            friction_type: non_empty(input.friction_type),
+            from: non_empty(input.from),
+            to: non_empty(input.to),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usage::pricing::PricingData;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn schema_for(dir: &TempDir) -> DailySchema {
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        build_schema(Arc::new(AppState::new(
+            config,
+            PricingData::from_map(HashMap::new()),
+            "127.0.0.1",
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_dates_with_nested_sessions() {
+        let dir = TempDir::new().unwrap();
+        let date_dir = dir.path().join("2024-01-15");
+        fs::create_dir_all(&date_dir).unwrap();
+        fs::write(date_dir.join("daily.md"), "# Daily\n").unwrap();
+        fs::write(
+            date_dir.join("fix-bug.md"),
+            "---\ntitle: Fix bug\n---\n\n# Fix bug\n\n## Summary\n\nFixed it.\n",
+        )
+        .unwrap();
+
+        let schema = schema_for(&dir);
+        let response = schema
+            .execute("{ dates { date session_count sessions { name detail { name } } } }")
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json().unwrap();
+        let date = &data["dates"][0];
+        assert_eq!(date["date"], "2024-01-15");
+        assert_eq!(date["session_count"], 1);
+        assert_eq!(date["sessions"][0]["name"], "fix-bug");
+        assert_eq!(date["sessions"][0]["detail"]["name"], "fix-bug");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_date_is_null() {
+        let dir = TempDir::new().unwrap();
+        let schema = schema_for(&dir);
+        let response = schema
+            .execute(r#"{ date(date: "2024-01-01") { date } }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            serde_json::json!({ "date": null })
+        );
+    }
+}
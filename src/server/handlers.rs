@@ -1,52 +1,109 @@
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use std::sync::{Arc, RwLock};
-
-use crate::archive::ArchiveManager;
+use tokio::sync::broadcast;
+
+use crate::archive::annotation::Annotation;
+use crate::archive::cache::ReadCache;
+use crate::archive::citations;
+use crate::archive::index::{SessionFilter, SessionIndexEntry};
+use crate::archive::notes::{self, Note};
+use crate::archive::paths::PathFilter;
+use crate::archive::permalink;
+use crate::archive::sources::{self, SourceMap};
+use crate::archive::stats::ArchiveStats;
+use crate::archive::{daily, edit, frontmatter, locks, transcript, ArchiveManager};
+use crate::ask;
+use crate::cli::commands::digest::spawn_digest_job;
 use crate::config::{save_config, Config};
-use crate::insights::collector::InsightsData;
+use crate::decisions;
+use crate::embeddings;
+use crate::export;
+use crate::goals::{Goal, GoalStatus, GoalStore};
+use crate::hooks::events::{self as hook_events, HookEvent};
+use crate::insights::anomalies;
+use crate::insights::collector::{InsightsData, InsightsFilter};
 use crate::insights::daily::DateInsights;
-use crate::jobs::JobManager;
-use crate::summarizer::Prompts;
+use crate::insights::friction::FrictionDrilldown;
+use crate::insights::projects;
+use crate::insights::tools::{ToolInsights, ToolStat};
+use crate::jobs::{queue_positions, JobManager, JobStatus};
+use crate::render;
+use crate::summarizer::{Prompts, TemplateEngine, TemplateKind};
+use crate::topics::{self, TopicIndex};
+use crate::transcript::sidechain;
+use crate::usage::active;
+use crate::usage::blocks::{self, BlocksReport, TokenLimit};
+use crate::usage::overhead::{self, OverheadSummary};
 use crate::usage::pricing::PricingData;
+use crate::usage::scanner;
 
 use super::dto::*;
+use super::limits::{is_loopback_host, RateLimiter};
+use super::preferences::UiPreferences;
+use super::read_state::ReadState;
+
+/// Related sessions returned with a session's details
+const RELATED_SESSIONS_LIMIT: usize = 5;
+const RELATED_SESSIONS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Events buffered per websocket client before it starts missing them
+const EVENT_CHANNEL_CAPACITY: usize = 64;
 
 /// Shared application state
 pub struct AppState {
     pub config: RwLock<Config>,
     pub pricing: PricingData,
+    /// Server events pushed to websocket clients
+    pub events: broadcast::Sender<WsMessage>,
+    /// Session and summary contents shared across requests
+    pub archive_cache: Arc<ReadCache>,
+    /// Per-client API request allowance (`server.rate_limit_per_minute`)
+    pub rate_limiter: RateLimiter,
+}
+
+impl AppState {
+    /// State of a server listening on `host`, which decides whether loopback
+    /// clients are rate limited when `server.rate_limit_loopback` is unset
+    pub fn new(config: Config, pricing: PricingData, host: &str) -> Self {
+        let limit_loopback = config
+            .server
+            .rate_limit_loopback
+            .unwrap_or_else(|| !is_loopback_host(host));
+        Self {
+            rate_limiter: RateLimiter::new(config.server.rate_limit_per_minute, limit_loopback),
+            config: RwLock::new(config),
+            pricing,
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            archive_cache: Arc::new(ReadCache::new()),
+        }
+    }
+
+    /// Archive manager for the current config, reading through the shared cache
+    pub fn archive(&self) -> ArchiveManager {
+        let config = self.config.read().unwrap().clone();
+        ArchiveManager::with_cache(config, self.archive_cache.clone())
+    }
+
+    /// Broadcast an event to connected websocket clients, if any
+    pub fn emit(&self, message: WsMessage) {
+        let _ = self.events.send(message);
+    }
 }
 
 /// List all available dates
 pub async fn list_dates(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
+    let manager = state.archive();
 
     match manager.list_dates() {
         Ok(dates) => {
             let date_infos: Vec<DateInfo> = dates
                 .into_iter()
-                .map(|date| {
-                    let sessions = manager.list_sessions(&date).unwrap_or_default();
-                    let has_digest = manager
-                        .read_daily_summary(&date)
-                        .map(|content| {
-                            content.contains("## Overview")
-                                && !content.contains("No sessions recorded yet")
-                        })
-                        .unwrap_or(false);
-
-                    DateInfo {
-                        date,
-                        session_count: sessions.len(),
-                        has_digest,
-                    }
-                })
+                .map(|date| build_date_info(&manager, date))
                 .collect();
 
             Json(ApiResponse::success(date_infos))
@@ -55,52 +112,476 @@ pub async fn list_dates(State(state): State<Arc<AppState>>) -> impl IntoResponse
     }
 }
 
+/// Session count and digest state for one archive date
+pub(crate) fn build_date_info(manager: &ArchiveManager, date: String) -> DateInfo {
+    let sessions = manager.list_sessions(&date).unwrap_or_default();
+    let has_digest = manager.has_digest(&date);
+    let read_state = ReadState::load(manager.config());
+    let unread_sessions = sessions
+        .iter()
+        .filter(|name| !read_state.session_read(manager, &date, name))
+        .count();
+
+    DateInfo {
+        locked: locks::is_locked(manager.config(), &date),
+        read: has_digest && read_state.digest_read(manager, &date),
+        unread_sessions,
+        session_count: sessions.len(),
+        has_digest,
+        date,
+    }
+}
+
 /// Get daily summary for a specific date
 pub async fn get_daily_summary(
     State(state): State<Arc<AppState>>,
     Path(date): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> impl IntoResponse {
-    let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
+    let manager = state.archive();
 
-    match manager.read_daily_summary(&date) {
+    match build_translated_summary_dto(&manager, &date, params.get("lang").map(String::as_str)) {
+        Ok(summary) => Json(ApiResponse::success(summary)),
+        Err(e) => Json(ApiResponse::<DailySummaryDto>::error(e.to_string())),
+    }
+}
+
+/// A date's digest as a standalone HTML page.
+/// Query params: `theme` (light or dark, default `output.html_theme`) and
+/// `lang` for a translation from `summarization.digest_languages`.
+pub async fn get_daily_html(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Response {
+    let default_theme = state.config.read().unwrap().output.html_theme.clone();
+    let theme = match params
+        .get("theme")
+        .unwrap_or(&default_theme)
+        .parse::<render::Theme>()
+    {
+        Ok(theme) => theme,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(e.to_string())),
+            )
+                .into_response()
+        }
+    };
+    let manager = state.archive();
+    let content = match params.get("lang") {
+        Some(language) => manager.read_translated_summary(&date, language),
+        None => manager.read_daily_summary(&date),
+    };
+    match content {
         Ok(content) => {
-            let file_path = manager.daily_summary_path(&date);
-            let mut summary = parse_daily_summary(&date, &content);
-            summary.file_path = file_path.to_string_lossy().to_string();
-            Json(ApiResponse::success(summary))
+            axum::response::Html(render::digest_page(&date, &content, theme)).into_response()
         }
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// Save a hand-edited digest, backing up the previous version
+pub async fn update_daily_summary(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    Json(req): Json<ContentEditRequest>,
+) -> impl IntoResponse {
+    let manager = state.archive();
+
+    let result = edited_content(manager.read_daily_summary(&date), &req)
+        .and_then(|content| manager.edit_daily_summary(&date, &content))
+        .and_then(|_| build_daily_summary_dto(&manager, &date));
+    match result {
+        Ok(summary) => Json(ApiResponse::success(summary)),
         Err(e) => Json(ApiResponse::<DailySummaryDto>::error(e.to_string())),
     }
 }
 
-/// List sessions for a specific date
+/// Append a timestamped journal note to a day's daily.md
+pub async fn add_note(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    Json(req): Json<NoteRequest>,
+) -> impl IntoResponse {
+    let manager = state.archive();
+    match notes::add(&manager, &date, &req.text, chrono::Local::now()) {
+        Ok(note) => Json(ApiResponse::success(note)),
+        Err(e) => Json(ApiResponse::<Note>::error(e.to_string())),
+    }
+}
+
+fn edited_content(
+    current: anyhow::Result<String>,
+    req: &ContentEditRequest,
+) -> anyhow::Result<String> {
+    if req.content.is_none() && req.sections.is_empty() {
+        anyhow::bail!("Nothing to save. Send `content` or `sections`");
+    }
+    edit::apply(&current?, req.content.as_deref(), &req.sections)
+}
+
+pub(crate) fn build_daily_summary_dto(
+    manager: &ArchiveManager,
+    date: &str,
+) -> anyhow::Result<DailySummaryDto> {
+    build_translated_summary_dto(manager, date, None)
+}
+
+/// The digest in `language` (a `daily.{lang}.md` translation), or daily.md
+fn build_translated_summary_dto(
+    manager: &ArchiveManager,
+    date: &str,
+    language: Option<&str>,
+) -> anyhow::Result<DailySummaryDto> {
+    let (content, file_path) = match language {
+        Some(language) => (
+            manager.read_translated_summary(date, language)?,
+            manager.translated_summary_path(date, language)?,
+        ),
+        None => (
+            manager.read_daily_summary(date)?,
+            manager.daily_summary_path(date),
+        ),
+    };
+    let mut summary = parse_daily_summary(date, &content);
+    summary.file_path = file_path.to_string_lossy().to_string();
+    summary.locked = locks::is_locked(manager.config(), date);
+    summary.language = language.map(String::from);
+    summary.languages = manager.digest_languages(date);
+    // Translations retitle the cards, so only daily.md is matched up
+    if language.is_none() {
+        let cited = manager.read_citations(date);
+        for (section, cards) in [
+            ("insights", &mut summary.insights),
+            ("tomorrow_focus", &mut summary.tomorrow_focus),
+            ("skills", &mut summary.skills),
+            ("commands", &mut summary.commands),
+        ] {
+            for card in cards.iter_mut() {
+                card.sessions = citations::for_card(&cited, section, &card.title).to_vec();
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// List sessions for a specific date.
+/// Query params: `page`/`page_size` (all sessions when no page size is given),
+/// `title`, `tag` and `has_skill_hints` filters, and `lite=true` to answer
+/// from the metadata index without reading session files for previews.
 pub async fn list_sessions(
     State(state): State<Arc<AppState>>,
     Path(date): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> impl IntoResponse {
-    let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
+    let manager = state.archive();
 
-    match manager.list_sessions(&date) {
-        Ok(sessions) => {
-            let session_briefs: Vec<SessionBrief> = sessions
-                .into_iter()
-                .filter_map(|name| {
-                    manager.read_session(&date, &name).ok().map(|content| {
-                        let (title, summary) = extract_session_preview(&content);
-                        SessionBrief {
-                            name,
-                            title,
-                            summary_preview: summary,
-                        }
-                    })
+    let filter = SessionFilter::from_params(&params);
+    let page: usize = params.get("page").and_then(|p| p.parse().ok()).unwrap_or(0);
+    let page_size: Option<usize> = params.get("page_size").and_then(|p| p.parse().ok());
+    let lite = params.get("lite").is_some_and(|v| v == "true" || v == "1");
+
+    match build_session_page(&manager, &date, &filter, page, page_size, lite) {
+        Ok(page) => Json(ApiResponse::success(page)),
+        Err(e) => Json(ApiResponse::<SessionPageDto>::error(e.to_string())),
+    }
+}
+
+pub(crate) fn build_session_briefs(
+    manager: &ArchiveManager,
+    date: &str,
+) -> anyhow::Result<Vec<SessionBrief>> {
+    Ok(build_session_page(manager, date, &SessionFilter::default(), 0, None, false)?.sessions)
+}
+
+/// Filter and paginate a date's sessions using the metadata index, pinned first.
+/// Only sessions on the requested page are read, and only when previews are wanted.
+pub(crate) fn build_session_page(
+    manager: &ArchiveManager,
+    date: &str,
+    filter: &SessionFilter,
+    page: usize,
+    page_size: Option<usize>,
+    lite: bool,
+) -> anyhow::Result<SessionPageDto> {
+    let mut entries: Vec<SessionIndexEntry> = manager
+        .session_index(date)?
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    // Pinned sessions first, otherwise keep name order
+    entries.sort_by_key(|entry| !entry.pinned);
+
+    let total = entries.len();
+    let page_size = page_size.filter(|size| *size > 0).unwrap_or(total.max(1));
+    let read_state = ReadState::load(manager.config());
+    let sessions = entries
+        .into_iter()
+        .skip(page.saturating_mul(page_size))
+        .take(page_size)
+        .filter_map(|entry| {
+            if lite {
+                Some(SessionBrief {
+                    read: read_state.session_read(manager, date, &entry.name),
+                    name: entry.name,
+                    title: entry.title,
+                    summary_preview: String::new(),
+                    pinned: entry.pinned,
                 })
-                .collect();
+            } else {
+                build_session_brief_with(manager, &read_state, date, entry.name)
+            }
+        })
+        .collect();
+
+    Ok(SessionPageDto {
+        sessions,
+        total,
+        page,
+        page_size,
+        has_more: (page + 1).saturating_mul(page_size) < total,
+    })
+}
+
+fn build_session_brief(manager: &ArchiveManager, date: &str, name: String) -> Option<SessionBrief> {
+    build_session_brief_with(manager, &ReadState::load(manager.config()), date, name)
+}
+
+fn build_session_brief_with(
+    manager: &ArchiveManager,
+    read_state: &ReadState,
+    date: &str,
+    name: String,
+) -> Option<SessionBrief> {
+    let content = manager.read_session(date, &name).ok()?;
+    let (title, summary) = extract_session_preview(&content);
+    Some(SessionBrief {
+        read: read_state.session_read(manager, date, &name),
+        name,
+        title,
+        summary_preview: summary,
+        pinned: frontmatter::is_pinned(&content),
+    })
+}
+
+/// List pinned sessions across all dates
+pub async fn list_pinned(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let manager = state.archive();
+
+    match build_pinned_dtos(&manager) {
+        Ok(pinned) => Json(ApiResponse::success(pinned)),
+        Err(e) => Json(ApiResponse::<Vec<PinnedSessionDto>>::error(e.to_string())),
+    }
+}
+
+pub(crate) fn build_pinned_dtos(manager: &ArchiveManager) -> anyhow::Result<Vec<PinnedSessionDto>> {
+    Ok(manager
+        .list_pinned()?
+        .into_iter()
+        .filter_map(|(date, name)| {
+            build_session_brief(manager, &date, name)
+                .map(|session| PinnedSessionDto { date, session })
+        })
+        .collect())
+}
+
+/// Pin a session
+pub async fn pin_session(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_session_pinned(&state, &date, name, true)
+}
+
+/// Unpin a session
+pub async fn unpin_session(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_session_pinned(&state, &date, name, false)
+}
+
+fn set_session_pinned(
+    state: &AppState,
+    date: &str,
+    name: String,
+    pinned: bool,
+) -> Json<ApiResponse<SessionBrief>> {
+    let manager = state.archive();
+
+    if let Err(e) = manager.set_pinned(date, &name, pinned) {
+        return Json(ApiResponse::error(e.to_string()));
+    }
+    match build_session_brief(&manager, date, name) {
+        Some(brief) => Json(ApiResponse::success(brief)),
+        None => Json(ApiResponse::error("Failed to read session")),
+    }
+}
+
+/// Mark a date's digest read; `?all=true` marks its sessions read too
+pub async fn mark_date_read(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let all = params.get("all").is_some_and(|v| v == "true");
+    set_date_read(&state, date, true, all)
+}
+
+/// Mark a date's digest unread again; `?all=true` its sessions too
+pub async fn mark_date_unread(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let all = params.get("all").is_some_and(|v| v == "true");
+    set_date_read(&state, date, false, all)
+}
 
-            Json(ApiResponse::success(session_briefs))
+/// Whether `date` is a day written exactly as YYYY-MM-DD, as archive
+/// folders are named
+fn is_archive_date(date: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .is_ok_and(|day| day.format("%Y-%m-%d").to_string() == date)
+}
+
+fn set_date_read(state: &AppState, date: String, read: bool, all: bool) -> Response {
+    if !is_archive_date(&date) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<DateInfo>::error(format!(
+                "Invalid date '{}': expected YYYY-MM-DD",
+                date
+            ))),
+        )
+            .into_response();
+    }
+    let manager = state.archive();
+    if !manager.has_sessions(&date) && !manager.has_digest(&date) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<DateInfo>::error(format!(
+                "Nothing archived for {}",
+                date
+            ))),
+        )
+            .into_response();
+    }
+    let config = manager.config();
+    let mut read_state = ReadState::load(config);
+    read_state.mark_digest(&date, read);
+    if all {
+        for name in manager.list_sessions(&date).unwrap_or_default() {
+            read_state.mark_session(&date, &name, read);
         }
-        Err(e) => Json(ApiResponse::<Vec<SessionBrief>>::error(e.to_string())),
+    }
+    if let Err(e) = read_state.save(config) {
+        return Json(ApiResponse::<DateInfo>::error(e.to_string())).into_response();
+    }
+    Json(ApiResponse::success(build_date_info(&manager, date))).into_response()
+}
+
+/// Mark a session read
+pub async fn mark_session_read(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_session_read(&state, &date, name, true)
+}
+
+/// Mark a session unread again
+pub async fn mark_session_unread(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_session_read(&state, &date, name, false)
+}
+
+fn set_session_read(state: &AppState, date: &str, name: String, read: bool) -> Response {
+    if !is_archive_date(date) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<SessionBrief>::error(format!(
+                "Invalid date '{}': expected YYYY-MM-DD",
+                date
+            ))),
+        )
+            .into_response();
+    }
+    let manager = state.archive();
+    if !manager
+        .list_sessions(date)
+        .unwrap_or_default()
+        .contains(&name)
+    {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<SessionBrief>::error(format!(
+                "Session {}/{} not found",
+                date, name
+            ))),
+        )
+            .into_response();
+    }
+    let mut read_state = ReadState::load(manager.config());
+    read_state.mark_session(date, &name, read);
+    if let Err(e) = read_state.save(manager.config()) {
+        return Json(ApiResponse::<SessionBrief>::error(e.to_string())).into_response();
+    }
+    match build_session_brief_with(&manager, &read_state, date, name) {
+        Some(brief) => Json(ApiResponse::success(brief)).into_response(),
+        None => Json(ApiResponse::<SessionBrief>::error("Failed to read session")).into_response(),
+    }
+}
+
+/// Store the user's outcome/satisfaction rating for a session
+pub async fn annotate_session(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+    Json(req): Json<SessionAnnotationRequest>,
+) -> impl IntoResponse {
+    let manager = state.archive();
+
+    let update = Annotation {
+        outcome: req.outcome,
+        satisfaction: req.satisfaction,
+        note: req.note,
+        stars: req.stars,
+    };
+    match manager.set_annotation(&date, &name, update, req.clear) {
+        Ok(annotation) => Json(ApiResponse::success(annotation)),
+        Err(e) => Json(ApiResponse::<Annotation>::error(e.to_string())),
+    }
+}
+
+/// Rename a session to a new topic, returning it under its new name
+pub async fn rename_session(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+    Json(req): Json<SessionRenameRequest>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let manager = state.archive();
+
+    let new_name = match manager.rename_session(&date, &name, &req.topic) {
+        Ok(new_name) => new_name,
+        Err(e) => return Json(ApiResponse::<SessionDetailDto>::error(e.to_string())),
+    };
+    if let Err(e) = embeddings::rename_session(&config, &date, &name, &new_name) {
+        eprintln!(
+            "[daily] Warning: Failed to update the embeddings index: {}",
+            e
+        );
+    }
+    match build_session_detail_dto(&manager, &date, new_name) {
+        Ok(detail) => Json(ApiResponse::success(detail)),
+        Err(e) => Json(ApiResponse::<SessionDetailDto>::error(e.to_string())),
     }
 }
 
@@ -110,36 +591,233 @@ pub async fn get_session(
     Path((date, name)): Path<(String, String)>,
 ) -> impl IntoResponse {
     let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
+    let manager = state.archive();
 
-    match manager.read_session(&date, &name) {
-        Ok(content) => {
-            let metadata = extract_session_metadata(&content);
-            let file_path = manager.session_archive_path(&date, &name);
-            let detail = SessionDetailDto {
-                name,
-                content,
-                metadata,
-                file_path: file_path.to_string_lossy().to_string(),
-            };
+    match build_session_detail_dto(&manager, &date, name) {
+        Ok(mut detail) => {
+            detail.related = related_session_dtos(&config, &date, &detail.name).await;
             Json(ApiResponse::success(detail))
         }
         Err(e) => Json(ApiResponse::<SessionDetailDto>::error(e.to_string())),
     }
 }
 
+/// Save a hand-edited session archive, backing up the previous version
+pub async fn update_session(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+    Json(req): Json<ContentEditRequest>,
+) -> impl IntoResponse {
+    let manager = state.archive();
+
+    let result = edited_content(manager.read_session(&date, &name), &req)
+        .and_then(|content| manager.edit_session(&date, &name, &content))
+        .and_then(|_| build_session_detail_dto(&manager, &date, name));
+    match result {
+        Ok(detail) => Json(ApiResponse::success(detail)),
+        Err(e) => Json(ApiResponse::<SessionDetailDto>::error(e.to_string())),
+    }
+}
+
+/// Related sessions for the detail view; best effort so a slow or
+/// unreachable embeddings provider never holds up the page
+pub(crate) async fn related_session_dtos(
+    config: &Config,
+    date: &str,
+    name: &str,
+) -> Vec<RelatedSessionDto> {
+    if !config.embeddings.enabled {
+        return Vec::new();
+    }
+    let lookup = embeddings::find_related(config, date, name, RELATED_SESSIONS_LIMIT);
+    match tokio::time::timeout(RELATED_SESSIONS_TIMEOUT, lookup).await {
+        Ok(Ok(related)) => related.into_iter().map(Into::into).collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn build_session_detail_dto(
+    manager: &ArchiveManager,
+    date: &str,
+    name: String,
+) -> anyhow::Result<SessionDetailDto> {
+    let content = manager.read_session(date, &name)?;
+    let metadata = extract_session_metadata(&content);
+    let file_path = manager.session_archive_path(date, &name);
+    Ok(SessionDetailDto {
+        name,
+        content,
+        metadata,
+        file_path: file_path.to_string_lossy().to_string(),
+        related: Vec::new(),
+    })
+}
+
 /// List all jobs
-pub async fn list_jobs(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let config = state.config.read().unwrap();
-    match JobManager::new(&config) {
-        Ok(manager) => match manager.list(true) {
-            Ok(jobs) => {
-                let job_dtos: Vec<JobDto> = jobs.into_iter().map(Into::into).collect();
-                Json(ApiResponse::success(job_dtos))
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Response {
+    let config = state.config.read().unwrap().clone();
+    let offset: usize = params
+        .get("offset")
+        .and_then(|o| o.parse().ok())
+        .unwrap_or(0);
+    let limit: Option<usize> = params.get("limit").and_then(|l| l.parse().ok());
+    match build_job_dtos(&config, true) {
+        // Newest first; the total lets the dashboard page through the rest
+        Ok(job_dtos) => {
+            let total = job_dtos.len();
+            let page: Vec<JobDto> = job_dtos
+                .into_iter()
+                .skip(offset)
+                .take(limit.unwrap_or(usize::MAX))
+                .collect();
+            (
+                [("x-total-count", total.to_string())],
+                Json(ApiResponse::success(page)),
+            )
+                .into_response()
+        }
+        Err(e) => Json(ApiResponse::<Vec<JobDto>>::error(e.to_string())).into_response(),
+    }
+}
+
+/// Job DTOs with queue positions and unfinished dependencies filled in
+/// for queued jobs
+pub(crate) fn build_job_dtos(
+    config: &Config,
+    include_completed: bool,
+) -> anyhow::Result<Vec<JobDto>> {
+    let manager = JobManager::new(config)?;
+    let jobs = manager.list(include_completed)?;
+    let positions = queue_positions(&jobs);
+    let waiting: std::collections::HashMap<String, Vec<String>> = jobs
+        .iter()
+        .map(|job| (job.id.clone(), owned(job.waiting_on(&jobs))))
+        .collect();
+    Ok(jobs
+        .into_iter()
+        .map(|job| {
+            let position = positions.get(&job.id).copied();
+            let waiting_on = waiting.get(&job.id).cloned().unwrap_or_default();
+            JobDto {
+                queue_position: position,
+                waiting_on,
+                ..job.into()
             }
-            Err(e) => Json(ApiResponse::<Vec<JobDto>>::error(e.to_string())),
+        })
+        .collect())
+}
+
+fn owned(ids: Vec<&str>) -> Vec<String> {
+    ids.into_iter().map(str::to_string).collect()
+}
+
+/// Tokens and cost spent by daily's own summarization and digest jobs,
+/// optionally limited to the last `days` days
+pub async fn get_usage_overhead(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let days: Option<i64> = params.get("days").and_then(|d| d.parse().ok());
+    let jobs = JobManager::new(&config).and_then(|manager| manager.list(true));
+    match jobs {
+        Ok(jobs) => Json(ApiResponse::success(overhead::summarize(&jobs, days))),
+        Err(e) => Json(ApiResponse::<OverheadSummary>::error(e.to_string())),
+    }
+}
+
+/// Usage grouped into 5-hour billing blocks. `token_limit` is a number or
+/// "max" and defaults to `usage.block_token_limit`.
+pub async fn get_usage_blocks(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let days: Option<i64> = params.get("days").and_then(|d| d.parse().ok());
+    let limit = match params.get("token_limit") {
+        Some(limit) => match limit.parse::<TokenLimit>() {
+            Ok(limit) => Some(limit),
+            Err(e) => return Json(ApiResponse::<BlocksReport>::error(e.to_string())),
         },
-        Err(e) => Json(ApiResponse::<Vec<JobDto>>::error(e.to_string())),
+        None => config.usage.block_token_limit.map(TokenLimit::Tokens),
+    };
+    Json(ApiResponse::success(blocks::report(
+        &state.pricing,
+        &PathFilter::from_config(&config),
+        limit,
+        days,
+    )))
+}
+
+/// Sessions whose transcripts were written in the last `minutes` (default 5)
+pub async fn list_active_sessions(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let minutes = params
+        .get("minutes")
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(active::DEFAULT_WINDOW_MINUTES);
+    let sessions = active::active_sessions(&config, &state.pricing, minutes, chrono::Local::now());
+    Json(ApiResponse::success(sessions))
+}
+
+/// Storage-level statistics of the archive, for the dashboard footer
+pub async fn get_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match ArchiveStats::collect(&config) {
+        Ok(stats) => Json(ApiResponse::success(stats)),
+        Err(e) => Json(ApiResponse::<ArchiveStats>::error(format!(
+            "Failed to collect archive stats: {}",
+            e
+        ))),
+    }
+}
+
+/// The most recent `daily hook` invocations, newest first
+pub async fn list_hook_events(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let events: Vec<HookEvent> = hook_events::recent(&config, hook_events::RECENT_EVENTS);
+    Json(ApiResponse::success(events))
+}
+
+/// Resolve `?ref=` (a session ID, `YYYY-MM-DD/name` or `YYYY-MM-DD`) to
+/// the current location of the session or digest and its web UI path
+pub async fn resolve_ref(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Response {
+    let Some(reference) = params.get("ref").filter(|r| !r.trim().is_empty()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<ResolvedDto>::error("Missing ref".to_string())),
+        )
+            .into_response();
+    };
+    let manager = state.archive();
+    match permalink::resolve(&manager, reference) {
+        Ok(Some(target)) => Json(ApiResponse::success(ResolvedDto {
+            url: target.web_path(),
+            target,
+        }))
+        .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<ResolvedDto>::error(format!(
+                "Nothing found for {}",
+                reference
+            ))),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<ResolvedDto>::error(e.to_string())),
+        )
+            .into_response(),
     }
 }
 
@@ -149,15 +827,31 @@ pub async fn get_job(
     Path(job_id): Path<String>,
 ) -> impl IntoResponse {
     let config = state.config.read().unwrap();
-    match JobManager::new(&config) {
-        Ok(manager) => match manager.load_job(&job_id) {
-            Ok(job) => Json(ApiResponse::success(JobDto::from(job))),
-            Err(e) => Json(ApiResponse::<JobDto>::error(e.to_string())),
-        },
+    match build_job_dto(&config, &job_id) {
+        Ok(job) => Json(ApiResponse::success(job)),
         Err(e) => Json(ApiResponse::<JobDto>::error(e.to_string())),
     }
 }
 
+pub(crate) fn build_job_dto(config: &Config, job_id: &str) -> anyhow::Result<JobDto> {
+    let manager = JobManager::new(config)?;
+    let job = manager.load_job(job_id)?;
+    let (queue_position, waiting_on) = if job.status == JobStatus::Queued {
+        let active = manager.list(false).unwrap_or_default();
+        (
+            queue_positions(&active).get(job_id).copied(),
+            owned(job.waiting_on(&active)),
+        )
+    } else {
+        (None, vec![])
+    };
+    Ok(JobDto {
+        queue_position,
+        waiting_on,
+        ..job.into()
+    })
+}
+
 /// Get job log
 pub async fn get_job_log(
     State(state): State<Arc<AppState>>,
@@ -202,59 +896,225 @@ pub async fn trigger_digest(
     State(state): State<Arc<AppState>>,
     Path(date): Path<String>,
 ) -> impl IntoResponse {
+    start_digest_job(&state, date, false, &[])
+}
+
+/// Regenerate an existing digest, keeping hand-edited sections and those in `keep`
+pub async fn regenerate_digest(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+    body: Option<Json<DigestRegenerateRequest>>,
+) -> impl IntoResponse {
+    let keep = body.map(|Json(req)| req.keep).unwrap_or_default();
+    if let Err(e) = daily::validate_section_keys(&keep) {
+        return Json(ApiResponse::<DigestResponse>::error(e.to_string()));
+    }
+    start_digest_job(&state, date, true, &keep)
+}
+
+fn start_digest_job(
+    state: &Arc<AppState>,
+    date: String,
+    force: bool,
+    keep: &[String],
+) -> Json<ApiResponse<DigestResponse>> {
     let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
-
-    // Check if there are sessions to digest
-    match manager.list_sessions(&date) {
-        Ok(sessions) => {
-            if sessions.is_empty() {
-                return Json(ApiResponse::<DigestResponse>::error(format!(
-                    "No sessions found for {}",
-                    date
-                )));
-            }
+    let manager = state.archive();
 
-            // Spawn background digest process
-            let exe = match std::env::current_exe() {
-                Ok(e) => e,
-                Err(e) => {
-                    return Json(ApiResponse::<DigestResponse>::error(format!(
-                        "Failed to get executable: {}",
-                        e
-                    )));
-                }
-            };
+    // Locked days can only be redone from the CLI with --force
+    if let Err(e) = locks::ensure_unlocked(&config, &date, "digest") {
+        return Json(ApiResponse::error(e.to_string()));
+    }
 
-            match std::process::Command::new(&exe)
-                .args(["digest", "--date", &date])
-                .stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn()
-            {
-                Ok(_) => Json(ApiResponse::success(DigestResponse {
-                    message: format!("Digest started for {} ({} sessions)", date, sessions.len()),
-                    session_count: sessions.len(),
-                })),
-                Err(e) => Json(ApiResponse::<DigestResponse>::error(format!(
-                    "Failed to start digest: {}",
-                    e
-                ))),
-            }
+    // Check if there are sessions to digest (regeneration only needs the digest)
+    let session_count = match manager.list_sessions(&date) {
+        Ok(sessions) if sessions.is_empty() && !(force && manager.has_digest(&date)) => {
+            return Json(ApiResponse::error(format!(
+                "No sessions found for {}",
+                date
+            )));
+        }
+        Ok(sessions) => sessions.len(),
+        Err(e) => return Json(ApiResponse::error(e.to_string())),
+    };
+
+    match spawn_digest_job(&config, &date, force, keep) {
+        Ok((job_id, child)) => {
+            watch_digest_job(state.clone(), config, date.clone(), job_id.clone(), child);
+            let verb = if force { "Regeneration" } else { "Digest" };
+            Json(ApiResponse::success(DigestResponse {
+                message: format!("{} started for {} ({} sessions)", verb, date, session_count),
+                session_count,
+                job_id,
+            }))
         }
-        Err(e) => Json(ApiResponse::<DigestResponse>::error(e.to_string())),
+        Err(e) => Json(ApiResponse::error(format!("Failed to start digest: {}", e))),
     }
 }
 
+/// Wait for a digest process to exit, then announce the outcome to websocket clients
+fn watch_digest_job(
+    state: Arc<AppState>,
+    config: Config,
+    date: String,
+    job_id: String,
+    mut child: std::process::Child,
+) {
+    tokio::spawn(async move {
+        let _ = tokio::task::spawn_blocking(move || child.wait()).await;
+        let Ok(job) = build_job_dto(&config, &job_id) else {
+            return;
+        };
+        let completed = job.status_type == "completed";
+        state.emit(WsMessage::JobUpdated(Box::new(job)));
+        if completed {
+            state.emit(WsMessage::DigestCompleted { date, job_id });
+        }
+    });
+}
+
 /// Health check endpoint
 pub async fn health_check() -> impl IntoResponse {
     (StatusCode::OK, "OK")
 }
 
+/// All goals, active and done
+pub async fn list_goals(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match GoalStore::load(&config) {
+        Ok(store) => Json(ApiResponse::success(store.goals)),
+        Err(e) => Json(ApiResponse::<Vec<Goal>>::error(e.to_string())),
+    }
+}
+
+/// Add an active goal
+pub async fn create_goal(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<GoalCreateRequest>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let result = GoalStore::load(&config).and_then(|mut store| {
+        let goal = store.add(&req.title)?.clone();
+        store.save(&config)?;
+        Ok(goal)
+    });
+    match result {
+        Ok(goal) => Json(ApiResponse::success(goal)),
+        Err(e) => Json(ApiResponse::<Goal>::error(e.to_string())),
+    }
+}
+
+/// Rename a goal or mark it done or active again
+pub async fn update_goal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u32>,
+    Json(req): Json<GoalUpdateRequest>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let result = GoalStore::load(&config).and_then(|mut store| {
+        let status = req
+            .status
+            .as_deref()
+            .map(str::parse::<GoalStatus>)
+            .transpose()?;
+        let goal = store.get_mut(id)?;
+        if let Some(title) = req.title.as_deref().map(str::trim) {
+            if title.is_empty() {
+                anyhow::bail!("Goal title is empty");
+            }
+            goal.title = title.to_string();
+        }
+        if let Some(status) = status {
+            goal.set_status(status);
+        }
+        let goal = goal.clone();
+        store.save(&config)?;
+        Ok(goal)
+    });
+    match result {
+        Ok(goal) => Json(ApiResponse::success(goal)),
+        Err(e) => Json(ApiResponse::<Goal>::error(e.to_string())),
+    }
+}
+
+/// Delete a goal and its recorded progress
+pub async fn delete_goal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u32>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let result = GoalStore::load(&config).and_then(|mut store| {
+        let goal = store.remove(id)?;
+        store.save(&config)?;
+        Ok(goal)
+    });
+    match result {
+        Ok(goal) => Json(ApiResponse::success(goal)),
+        Err(e) => Json(ApiResponse::<Goal>::error(e.to_string())),
+    }
+}
+
+/// Key decisions from all sessions, newest first.
+/// Query params: `query` (every word must match), `project`, `limit` (default 50).
+pub async fn list_decisions(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let manager = state.archive();
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(50);
+    let query = params.get("query").map(String::as_str);
+    let project = params.get("project").map(String::as_str);
+    match decisions::search(&manager, query, project, limit) {
+        Ok(found) => Json(ApiResponse::success(found)),
+        Err(e) => Json(ApiResponse::<Vec<decisions::Decision>>::error(
+            e.to_string(),
+        )),
+    }
+}
+
+/// All topics of the knowledge base and when they were last refreshed
+pub async fn list_topics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    Json(ApiResponse::success(TopicIndex::load(&config)))
+}
+
+/// One topic with its rendered page
+pub async fn get_topic(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let Some(topic) = TopicIndex::load(&config).get(&slug).cloned() else {
+        return Json(ApiResponse::<TopicDetailDto>::error(format!(
+            "Topic '{}' not found",
+            slug
+        )));
+    };
+    let path = topics::topics_dir(&config).join(format!("{}.md", topic.slug));
+    match std::fs::read_to_string(&path) {
+        Ok(content) => Json(ApiResponse::success(TopicDetailDto { topic, content })),
+        Err(e) => Json(ApiResponse::<TopicDetailDto>::error(e.to_string())),
+    }
+}
+
+/// Answer a question from past sessions and digests, with the sources cited
+pub async fn ask(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AskRequest>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match ask::ask(&config, &req.question, req.limit.unwrap_or(5)).await {
+        Ok(answer) => Json(ApiResponse::success(answer)),
+        Err(e) => Json(ApiResponse::<ask::Answer>::error(format!("{:#}", e))),
+    }
+}
+
 /// Install a skill or command from a daily summary card
 pub async fn install_card(Json(req): Json<InstallCardRequest>) -> impl IntoResponse {
-    let name = to_kebab_case(&req.title);
+    let name = crate::archive::card_slug(&req.title);
 
     let home = match dirs::home_dir() {
         Some(h) => h,
@@ -325,65 +1185,95 @@ pub async fn install_card(Json(req): Json<InstallCardRequest>) -> impl IntoRespo
     }))
 }
 
-/// Convert a title string to kebab-case
-fn to_kebab_case(title: &str) -> String {
-    // Remove markdown formatting like backticks and /
-    let cleaned: String = title
-        .chars()
-        .map(|c| {
-            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
-                c
-            } else if c == ' ' || c == '/' {
-                '-'
-            } else if c.is_alphanumeric() {
-                // Keep non-ASCII alphanumeric (CJK etc.)
-                c
-            } else {
-                '-'
-            }
-        })
-        .collect();
+/// Dashboard preferences saved on the server
+pub async fn get_ui_preferences(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match UiPreferences::load(&config) {
+        Ok(prefs) => Json(ApiResponse::success(prefs)),
+        Err(e) => Json(ApiResponse::<UiPreferences>::error(e.to_string())),
+    }
+}
 
-    // Collapse multiple dashes and trim
-    let mut result = String::new();
-    let mut prev_dash = false;
-    for c in cleaned.chars() {
-        if c == '-' {
-            if !prev_dash && !result.is_empty() {
-                result.push('-');
-            }
-            prev_dash = true;
-        } else {
-            result.push(c.to_ascii_lowercase());
-            prev_dash = false;
-        }
+/// Replace the saved dashboard preferences
+pub async fn put_ui_preferences(
+    State(state): State<Arc<AppState>>,
+    Json(prefs): Json<UiPreferences>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match prefs.save(&config) {
+        Ok(prefs) => Json(ApiResponse::success(prefs)),
+        Err(e) => Json(ApiResponse::<UiPreferences>::error(e.to_string())),
     }
-    result.trim_matches('-').to_string()
 }
 
 /// Get current configuration
 pub async fn get_config(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let config = state.config.read().unwrap();
-    let config_dto = ConfigDto {
+    Json(ApiResponse::success(build_config_dto(&config)))
+}
+
+/// Map config into its API representation, including template lint warnings
+pub(crate) fn build_config_dto(config: &Config) -> ConfigDto {
+    let templates = &config.prompt_templates;
+    let template_warnings = [
+        (TemplateKind::SessionSummary, &templates.session_summary),
+        (TemplateKind::DailySummary, &templates.daily_summary),
+        (TemplateKind::SkillExtract, &templates.skill_extract),
+        (TemplateKind::CommandExtract, &templates.command_extract),
+    ]
+    .into_iter()
+    .filter_map(|(kind, template)| template.as_deref().map(|t| (kind, t)))
+    .flat_map(|(kind, template)| validate_template_dto(kind, template))
+    .collect();
+
+    ConfigDto {
         storage_path: config.storage.path.to_string_lossy().to_string(),
         model: config.summarization.model.clone(),
         summary_language: config.summarization.summary_language.clone(),
+        ui_language: config.ui_language().code(),
         enable_daily_summary: config.summarization.enable_daily_summary,
         enable_extraction_hints: config.summarization.enable_extraction_hints,
         auto_digest_enabled: config.summarization.auto_digest_enabled,
         digest_time: config.summarization.digest_time.clone(),
         author: config.archive.author.clone(),
         prompt_templates: PromptTemplatesDto {
-            session_summary: config.prompt_templates.session_summary.clone(),
-            daily_summary: config.prompt_templates.daily_summary.clone(),
-            skill_extract: config.prompt_templates.skill_extract.clone(),
-            command_extract: config.prompt_templates.command_extract.clone(),
+            session_summary: templates.session_summary.clone(),
+            daily_summary: templates.daily_summary.clone(),
+            skill_extract: templates.skill_extract.clone(),
+            command_extract: templates.command_extract.clone(),
         },
         auto_summarize_enabled: config.summarization.auto_summarize_enabled,
         auto_summarize_on_show: config.summarization.auto_summarize_on_show,
         auto_summarize_inactive_minutes: config.summarization.auto_summarize_inactive_minutes,
-    };
-    Json(ApiResponse::success(config_dto))
+        template_warnings,
+    }
+}
+
+/// Run the template linter and map its findings to DTOs
+fn validate_template_dto(kind: TemplateKind, template: &str) -> Vec<TemplateWarningDto> {
+    TemplateEngine::validate(template, kind)
+        .into_iter()
+        .map(|w| TemplateWarningDto {
+            template: kind.name().to_string(),
+            kind: w.kind.as_str().to_string(),
+            message: w.message,
+            variable: w.variable,
+        })
+        .collect()
+}
+
+/// Validate a custom template without saving it
+pub async fn validate_template(Json(req): Json<TemplateValidateRequest>) -> impl IntoResponse {
+    match TemplateKind::from_name(&req.template_type) {
+        Some(kind) => Json(ApiResponse::success(validate_template_dto(
+            kind,
+            &req.template,
+        ))),
+        None => Json(ApiResponse::<Vec<TemplateWarningDto>>::error(format!(
+            "Unknown template type '{}'",
+            req.template_type
+        ))),
+    }
 }
 
 /// Update configuration
@@ -478,27 +1368,9 @@ pub async fn update_config(
         )));
     }
 
-    // Return updated config
-    let config_dto = ConfigDto {
-        storage_path: config.storage.path.to_string_lossy().to_string(),
-        model: config.summarization.model.clone(),
-        summary_language: config.summarization.summary_language.clone(),
-        enable_daily_summary: config.summarization.enable_daily_summary,
-        enable_extraction_hints: config.summarization.enable_extraction_hints,
-        auto_digest_enabled: config.summarization.auto_digest_enabled,
-        digest_time: config.summarization.digest_time.clone(),
-        author: config.archive.author.clone(),
-        prompt_templates: PromptTemplatesDto {
-            session_summary: config.prompt_templates.session_summary.clone(),
-            daily_summary: config.prompt_templates.daily_summary.clone(),
-            skill_extract: config.prompt_templates.skill_extract.clone(),
-            command_extract: config.prompt_templates.command_extract.clone(),
-        },
-        auto_summarize_enabled: config.summarization.auto_summarize_enabled,
-        auto_summarize_on_show: config.summarization.auto_summarize_on_show,
-        auto_summarize_inactive_minutes: config.summarization.auto_summarize_inactive_minutes,
-    };
-    Json(ApiResponse::success(config_dto))
+    // Return updated config; custom templates are saved even with lint
+    // warnings so the UI can show them next to the editor
+    Json(ApiResponse::success(build_config_dto(&config)))
 }
 
 /// Get default prompt templates
@@ -516,150 +1388,376 @@ pub async fn get_default_templates() -> impl IntoResponse {
     Json(ApiResponse::success(defaults))
 }
 
-/// Get insights data
+/// Get insights data, optionally filtered by project, facet fields, and date range.
+/// `group_by=project` adds per-project segments. `format=csv` returns one
+/// row per session (or `per=day`) instead.
 pub async fn get_insights(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
-) -> impl IntoResponse {
+) -> Response {
     let config = state.config.read().unwrap().clone();
     let days: usize = params
         .get("days")
         .and_then(|d| d.parse().ok())
         .unwrap_or(30);
+    let filter = InsightsFilter::from_params(&params);
 
-    match InsightsData::collect(&config, Some(days), &state.pricing) {
-        Ok(data) => {
-            let dto = InsightsDto {
-                total_days: data.total_days,
-                total_sessions: data.total_sessions,
-                daily_stats: data
-                    .daily_stats
-                    .into_iter()
-                    .map(|s| DailyStatDto {
-                        date: s.date,
-                        session_count: s.session_count,
-                        has_digest: s.has_digest,
-                        total_tokens: s.total_tokens,
-                        total_cost: s.total_cost,
-                    })
-                    .collect(),
-                goal_distribution: data
-                    .goal_distribution
-                    .into_iter()
-                    .map(|c| CategoryCountDto {
-                        name: c.name,
-                        count: c.count,
-                    })
-                    .collect(),
-                friction_distribution: data
-                    .friction_distribution
-                    .into_iter()
-                    .map(|c| CategoryCountDto {
-                        name: c.name,
-                        count: c.count,
-                    })
-                    .collect(),
-                satisfaction_distribution: data
-                    .satisfaction_distribution
-                    .into_iter()
-                    .map(|c| CategoryCountDto {
-                        name: c.name,
-                        count: c.count,
-                    })
-                    .collect(),
-                language_distribution: data
-                    .language_distribution
-                    .into_iter()
-                    .map(|c| CategoryCountDto {
-                        name: c.name,
-                        count: c.count,
-                    })
-                    .collect(),
-                session_type_distribution: data
-                    .session_type_distribution
-                    .into_iter()
-                    .map(|c| CategoryCountDto {
-                        name: c.name,
-                        count: c.count,
-                    })
-                    .collect(),
-                session_details: data
-                    .session_details
-                    .into_iter()
-                    .map(|s| SessionInsightDto {
-                        session_id: s.session_id,
-                        date: s.date,
-                        session_name: s.session_name,
-                        brief_summary: s.brief_summary,
-                        outcome: s.outcome,
-                        goal_categories: s.goal_categories,
-                        friction_types: s.friction_types,
-                        friction_detail: s.friction_detail,
-                        satisfaction: s.satisfaction,
-                        claude_helpfulness: s.claude_helpfulness,
-                        session_type: s.session_type,
-                        token_usage: s.token_usage.map(map_session_usage_dto),
-                    })
-                    .collect(),
-                trends: data.trends.map(|t| TrendDto {
-                    period_label: t.period_label,
-                    comparison_label: t.comparison_label,
-                    current_sessions: t.current_sessions,
-                    previous_sessions: t.previous_sessions,
-                    sessions_change_pct: t.sessions_change_pct,
-                    current_friction_rate: t.current_friction_rate,
-                    previous_friction_rate: t.previous_friction_rate,
-                    friction_change_pct: t.friction_change_pct,
-                    current_success_rate: t.current_success_rate,
-                    previous_success_rate: t.previous_success_rate,
-                    success_change_pct: t.success_change_pct,
-                    current_satisfaction_score: t.current_satisfaction_score,
-                    previous_satisfaction_score: t.previous_satisfaction_score,
-                    satisfaction_change_pct: t.satisfaction_change_pct,
-                    weekly_stats: t
-                        .weekly_stats
-                        .into_iter()
-                        .map(|w| WeeklyStatDto {
-                            week_label: w.week_label,
-                            session_count: w.session_count,
-                            friction_rate: w.friction_rate,
-                            success_rate: w.success_rate,
-                        })
-                        .collect(),
-                }),
-                usage_summary: data.usage_summary.map(|u| UsageSummaryDto {
-                    total_input_tokens: u.total_input_tokens,
-                    total_output_tokens: u.total_output_tokens,
-                    total_cache_creation_tokens: u.total_cache_creation_tokens,
-                    total_cache_read_tokens: u.total_cache_read_tokens,
-                    total_cost_usd: u.total_cost_usd,
-                    total_sessions: u.total_sessions,
-                    model_distribution: u
-                        .model_distribution
-                        .into_iter()
-                        .map(|m| ModelUsageCountDto {
-                            model: m.model,
-                            count: m.count,
-                        })
-                        .collect(),
-                    daily_usage: u
-                        .daily_usage
-                        .into_iter()
-                        .map(|d| DailyUsageDto {
-                            date: d.date,
-                            input_tokens: d.input_tokens,
-                            output_tokens: d.output_tokens,
-                            cache_creation_tokens: d.cache_creation_tokens,
-                            cache_read_tokens: d.cache_read_tokens,
-                            total_cost_usd: d.total_cost_usd,
-                            session_count: d.session_count,
-                        })
-                        .collect(),
-                }),
-            };
-            Json(ApiResponse::success(dto))
+    if let Some(format) = params.get("format").filter(|f| f.as_str() != "json") {
+        let table = export_params(format, &params).and_then(|(format, per)| {
+            export::insights_table(&config, &state.pricing, Some(days), &filter, per, format)
+        });
+        return csv_response(table, "insights.csv");
+    }
+
+    let group_by = params
+        .get("group_by")
+        .map(|g| g.trim())
+        .filter(|g| !g.is_empty());
+    match InsightsData::collect(&config, Some(days), &state.pricing, &filter)
+        .and_then(|data| build_grouped_insights_dto(data, group_by))
+    {
+        Ok(dto) => Json(ApiResponse::success(dto)).into_response(),
+        Err(e) => Json(ApiResponse::<InsightsDto>::error(e.to_string())).into_response(),
+    }
+}
+
+/// Claude Code usage rows for spreadsheets: one per session (or `per=day`)
+/// started between `from` and `to`, as JSON or with `format=csv` as CSV
+pub async fn get_usage_export(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Response {
+    let config = state.config.read().unwrap().clone();
+    let from = params.get("from").map(String::as_str);
+    let to = params.get("to").map(String::as_str);
+    let (format, per) =
+        match export_params(params.get("format").map_or("json", String::as_str), &params) {
+            Ok(parsed) => parsed,
+            Err(e) => return Json(ApiResponse::<()>::error(e.to_string())).into_response(),
+        };
+    if format == export::Format::Csv {
+        return csv_response(
+            export::usage_table(&config, &state.pricing, from, to, per, format),
+            "usage.csv",
+        );
+    }
+
+    let rows = export::usage_rows(
+        &scanner::scan_all_sessions(None, &state.pricing, &PathFilter::from_config(&config)),
+        from,
+        to,
+    );
+    match per {
+        export::Granularity::Session => Json(ApiResponse::success(rows)).into_response(),
+        export::Granularity::Day => {
+            Json(ApiResponse::success(export::usage_day_rows(&rows))).into_response()
         }
-        Err(e) => Json(ApiResponse::<InsightsDto>::error(e.to_string())),
+    }
+}
+
+/// Export format and row granularity from `format` and `per` query params
+fn export_params(
+    format: &str,
+    params: &std::collections::HashMap<String, String>,
+) -> anyhow::Result<(export::Format, export::Granularity)> {
+    let per = params.get("per").map_or("session", String::as_str);
+    Ok((format.parse()?, per.parse()?))
+}
+
+/// A CSV download, or the usual error envelope
+fn csv_response(table: anyhow::Result<String>, filename: &str) -> Response {
+    match table {
+        Ok(csv) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            csv,
+        )
+            .into_response(),
+        Err(e) => Json(ApiResponse::<()>::error(e.to_string())).into_response(),
+    }
+}
+
+pub(crate) fn build_insights_dto(data: InsightsData) -> InsightsDto {
+    InsightsDto {
+        total_days: data.total_days,
+        total_sessions: data.total_sessions,
+        daily_stats: data
+            .daily_stats
+            .into_iter()
+            .map(|s| DailyStatDto {
+                date: s.date,
+                session_count: s.session_count,
+                has_digest: s.has_digest,
+                total_tokens: s.total_tokens,
+                total_cost: s.total_cost,
+            })
+            .collect(),
+        goal_distribution: data
+            .goal_distribution
+            .into_iter()
+            .map(|c| CategoryCountDto {
+                name: c.name,
+                count: c.count,
+            })
+            .collect(),
+        friction_distribution: data
+            .friction_distribution
+            .into_iter()
+            .map(|c| CategoryCountDto {
+                name: c.name,
+                count: c.count,
+            })
+            .collect(),
+        satisfaction_distribution: data
+            .satisfaction_distribution
+            .into_iter()
+            .map(|c| CategoryCountDto {
+                name: c.name,
+                count: c.count,
+            })
+            .collect(),
+        language_distribution: data
+            .language_distribution
+            .into_iter()
+            .map(|c| CategoryCountDto {
+                name: c.name,
+                count: c.count,
+            })
+            .collect(),
+        session_type_distribution: data
+            .session_type_distribution
+            .into_iter()
+            .map(|c| CategoryCountDto {
+                name: c.name,
+                count: c.count,
+            })
+            .collect(),
+        session_details: data
+            .session_details
+            .into_iter()
+            .map(|s| SessionInsightDto {
+                session_id: s.session_id,
+                date: s.date,
+                session_name: s.session_name,
+                brief_summary: s.brief_summary,
+                outcome: s.outcome,
+                goal_categories: s.goal_categories,
+                friction_types: s.friction_types,
+                friction_detail: s.friction_detail,
+                satisfaction: s.satisfaction,
+                claude_helpfulness: s.claude_helpfulness,
+                session_type: s.session_type,
+                project: s.project,
+                token_usage: s.token_usage.map(map_session_usage_dto),
+            })
+            .collect(),
+        trends: data.trends.map(|t| TrendDto {
+            period_label: t.period_label,
+            comparison_label: t.comparison_label,
+            current_sessions: t.current_sessions,
+            previous_sessions: t.previous_sessions,
+            sessions_change_pct: t.sessions_change_pct,
+            current_friction_rate: t.current_friction_rate,
+            previous_friction_rate: t.previous_friction_rate,
+            friction_change_pct: t.friction_change_pct,
+            current_success_rate: t.current_success_rate,
+            previous_success_rate: t.previous_success_rate,
+            success_change_pct: t.success_change_pct,
+            current_satisfaction_score: t.current_satisfaction_score,
+            previous_satisfaction_score: t.previous_satisfaction_score,
+            satisfaction_change_pct: t.satisfaction_change_pct,
+            weekly_stats: t
+                .weekly_stats
+                .into_iter()
+                .map(|w| WeeklyStatDto {
+                    week_label: w.week_label,
+                    session_count: w.session_count,
+                    friction_rate: w.friction_rate,
+                    success_rate: w.success_rate,
+                })
+                .collect(),
+        }),
+        usage_summary: data.usage_summary.map(map_usage_summary_dto),
+        activity: ActivityDto {
+            heatmap: data
+                .activity
+                .heatmap
+                .into_iter()
+                .map(|d| HeatmapDayDto {
+                    date: d.date,
+                    session_count: d.session_count,
+                })
+                .collect(),
+            current_streak: data.activity.current_streak,
+            longest_streak: data.activity.longest_streak,
+            active_days: data.activity.active_days,
+        },
+        tracked_goals: data.tracked_goals.into_iter().map(Into::into).collect(),
+        work_patterns: data.work_patterns.into(),
+        projects: None,
+    }
+}
+
+/// Insights DTO with segments by `group_by` ("project"), if given
+pub(crate) fn build_grouped_insights_dto(
+    data: InsightsData,
+    group_by: Option<&str>,
+) -> anyhow::Result<InsightsDto> {
+    let projects = match group_by {
+        None => None,
+        Some("project") => Some(projects::segment(&data.session_details)),
+        Some(other) => anyhow::bail!("Unknown group_by '{}': expected project", other),
+    };
+    let mut dto = build_insights_dto(data);
+    dto.projects = projects.map(|segments| segments.into_iter().map(Into::into).collect());
+    Ok(dto)
+}
+
+pub(crate) fn map_usage_summary_dto(u: crate::usage::types::UsageSummary) -> UsageSummaryDto {
+    UsageSummaryDto {
+        total_input_tokens: u.total_input_tokens,
+        total_output_tokens: u.total_output_tokens,
+        total_cache_creation_tokens: u.total_cache_creation_tokens,
+        total_cache_read_tokens: u.total_cache_read_tokens,
+        total_cost_usd: u.total_cost_usd,
+        total_sessions: u.total_sessions,
+        model_distribution: u
+            .model_distribution
+            .into_iter()
+            .map(|m| ModelUsageCountDto {
+                model: m.model,
+                count: m.count,
+            })
+            .collect(),
+        daily_usage: u
+            .daily_usage
+            .into_iter()
+            .map(|d| DailyUsageDto {
+                date: d.date,
+                input_tokens: d.input_tokens,
+                output_tokens: d.output_tokens,
+                cache_creation_tokens: d.cache_creation_tokens,
+                cache_read_tokens: d.cache_read_tokens,
+                total_cost_usd: d.total_cost_usd,
+                session_count: d.session_count,
+            })
+            .collect(),
+        projects: u.projects.into_iter().map(map_project_usage_dto).collect(),
+    }
+}
+
+fn map_project_usage_dto(p: crate::usage::types::ProjectUsage) -> ProjectUsageDto {
+    ProjectUsageDto {
+        project: p.project,
+        input_tokens: p.input_tokens,
+        output_tokens: p.output_tokens,
+        cache_creation_tokens: p.cache_creation_tokens,
+        cache_read_tokens: p.cache_read_tokens,
+        total_cost_usd: p.total_cost_usd,
+        session_count: p.session_count,
+    }
+}
+
+/// Claude Code usage per project directory, most expensive first,
+/// optionally limited to sessions started in the last `days` days
+pub async fn get_usage_projects(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let days: Option<i64> = params.get("days").and_then(|d| d.parse().ok());
+    let summary =
+        scanner::summarize_recent(&state.pricing, &PathFilter::from_config(&config), days);
+    Json(ApiResponse::success(
+        summary
+            .projects
+            .into_iter()
+            .map(map_project_usage_dto)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Sessions showing one friction type, with the facet's friction detail and
+/// links to each conversation. Accepts `days` and the `/insights` filters.
+pub async fn get_friction_insights(
+    State(state): State<Arc<AppState>>,
+    Path(friction_type): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let days: usize = params
+        .get("days")
+        .and_then(|d| d.parse().ok())
+        .unwrap_or(30);
+    let filter = InsightsFilter::from_params(&params);
+
+    match FrictionDrilldown::collect(&config, &friction_type, Some(days), &filter) {
+        Ok(data) => Json(ApiResponse::success(data)),
+        Err(e) => Json(ApiResponse::<FrictionDrilldown>::error(e.to_string())),
+    }
+}
+
+/// The last seven days' cost, friction and success rates against the
+/// trailing weekly baseline, with anomalies flagged
+pub async fn get_insight_anomalies(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    Json(ApiResponse::success(anomalies::detect(
+        &config,
+        &state.pricing,
+        chrono::Local::now().date_naive(),
+    )))
+}
+
+/// Get tool usage analytics parsed from archived session transcripts
+pub async fn get_tool_insights(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    let days: usize = params
+        .get("days")
+        .and_then(|d| d.parse().ok())
+        .unwrap_or(30);
+
+    match ToolInsights::collect(&config, Some(days)) {
+        Ok(data) => Json(ApiResponse::success(build_tool_insights_dto(data))),
+        Err(e) => Json(ApiResponse::<ToolInsightsDto>::error(e.to_string())),
+    }
+}
+
+pub(crate) fn build_tool_insights_dto(data: ToolInsights) -> ToolInsightsDto {
+    ToolInsightsDto {
+        total_calls: data.total_calls,
+        total_failures: data.total_failures,
+        failure_rate: data.failure_rate,
+        longest_chain: data.longest_chain,
+        tools: data.tools.into_iter().map(map_tool_stat_dto).collect(),
+        sessions: data
+            .sessions
+            .into_iter()
+            .map(|s| SessionToolUsageDto {
+                date: s.date,
+                session_name: s.session_name,
+                total_calls: s.total_calls,
+                failures: s.failures,
+                longest_chain: s.longest_chain,
+                tools: s.tools.into_iter().map(map_tool_stat_dto).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn map_tool_stat_dto(t: ToolStat) -> ToolStatDto {
+    ToolStatDto {
+        name: t.name,
+        calls: t.calls,
+        failures: t.failures,
+        failure_rate: t.failure_rate,
     }
 }
 
@@ -668,50 +1766,106 @@ pub async fn get_date_insights(
     State(state): State<Arc<AppState>>,
     Path(date): Path<String>,
 ) -> impl IntoResponse {
-    let config = state.config.read().unwrap().clone();
-
-    match DateInsights::collect(&date, &config, &state.pricing) {
-        Ok(data) => {
-            let dto = DateInsightsDto {
-                sessions: data
-                    .sessions
-                    .into_iter()
-                    .map(|s| DateSessionInsightDto {
-                        name: s.name,
-                        session_id: s.session_id,
-                        brief_summary: s.brief_summary,
-                        outcome: s.outcome,
-                        goal_categories: s.goal_categories,
-                        friction_types: s.friction_types,
-                        friction_detail: s.friction_detail,
-                        satisfaction: s.satisfaction,
-                        claude_helpfulness: s.claude_helpfulness,
-                        token_usage: s.token_usage.map(map_session_usage_dto),
-                    })
-                    .collect(),
-                day_summary: DayInsightSummaryDto {
-                    total_sessions: data.day_summary.total_sessions,
-                    sessions_with_friction: data.day_summary.sessions_with_friction,
-                    overall_satisfaction: data.day_summary.overall_satisfaction,
-                    top_goals: data.day_summary.top_goals,
-                    top_frictions: data.day_summary.top_frictions,
-                    recommendations: data.day_summary.recommendations,
-                    total_tokens: data.day_summary.total_tokens,
-                    total_cost: data.day_summary.total_cost_usd,
-                    model_distribution: data
-                        .day_summary
-                        .model_distribution
-                        .into_iter()
-                        .map(|(model, count)| ModelUsageCountDto { model, count })
-                        .collect(),
-                },
-            };
-            Json(ApiResponse::success(dto))
-        }
+    match DateInsights::collect(&date, &state.archive(), &state.pricing) {
+        Ok(data) => Json(ApiResponse::success(build_date_insights_dto(data))),
         Err(e) => Json(ApiResponse::<DateInsightsDto>::error(e.to_string())),
     }
 }
 
+pub(crate) fn build_date_insights_dto(data: DateInsights) -> DateInsightsDto {
+    DateInsightsDto {
+        sessions: data
+            .sessions
+            .into_iter()
+            .map(|s| DateSessionInsightDto {
+                name: s.name,
+                session_id: s.session_id,
+                brief_summary: s.brief_summary,
+                outcome: s.outcome,
+                goal_categories: s.goal_categories,
+                friction_types: s.friction_types,
+                friction_detail: s.friction_detail,
+                satisfaction: s.satisfaction,
+                claude_helpfulness: s.claude_helpfulness,
+                token_usage: s.token_usage.map(map_session_usage_dto),
+            })
+            .collect(),
+        day_summary: DayInsightSummaryDto {
+            total_sessions: data.day_summary.total_sessions,
+            sessions_with_friction: data.day_summary.sessions_with_friction,
+            overall_satisfaction: data.day_summary.overall_satisfaction,
+            top_goals: data.day_summary.top_goals,
+            top_frictions: data.day_summary.top_frictions,
+            recommendations: data.day_summary.recommendations,
+            total_tokens: data.day_summary.total_tokens,
+            total_cost: data.day_summary.total_cost_usd,
+            model_distribution: data
+                .day_summary
+                .model_distribution
+                .into_iter()
+                .map(|(model, count)| ModelUsageCountDto { model, count })
+                .collect(),
+        },
+    }
+}
+
+/// Digest status, session briefs, insights, usage and active jobs of one
+/// date in a single response, so the day view needs one request
+pub async fn get_day_overview(
+    State(state): State<Arc<AppState>>,
+    Path(date): Path<String>,
+) -> impl IntoResponse {
+    let config = state.config.read().unwrap().clone();
+    match build_day_overview(&state.archive(), &config, &state.pricing, &date) {
+        Ok(overview) => Json(ApiResponse::success(overview)),
+        Err(e) => Json(ApiResponse::<DayOverviewDto>::error(e.to_string())),
+    }
+}
+
+pub(crate) fn build_day_overview(
+    manager: &ArchiveManager,
+    config: &Config,
+    pricing: &PricingData,
+    date: &str,
+) -> anyhow::Result<DayOverviewDto> {
+    let sessions = build_session_briefs(manager, date)?;
+    let summary = build_daily_summary_dto(manager, date).ok();
+    let insights = DateInsights::collect(date, manager, pricing)
+        .ok()
+        .map(build_date_insights_dto);
+
+    let mut usage = DayUsageDto::default();
+    for session_usage in insights
+        .iter()
+        .flat_map(|insights| &insights.sessions)
+        .filter_map(|session| session.token_usage.as_ref())
+    {
+        usage.input_tokens += session_usage.input_tokens;
+        usage.output_tokens += session_usage.output_tokens;
+        usage.cache_creation_tokens += session_usage.cache_creation_tokens;
+        usage.cache_read_tokens += session_usage.cache_read_tokens;
+        usage.total_cost_usd += session_usage.total_cost_usd;
+        usage.subagent_cost_usd += session_usage.subagent_cost_usd;
+    }
+
+    let digest_task = format!("digest-{}", date);
+    let jobs = build_job_dtos(config, false)?
+        .into_iter()
+        .filter(|job| job.task_name == digest_task || job.started_at.starts_with(date))
+        .collect();
+
+    Ok(DayOverviewDto {
+        date: date.to_string(),
+        has_digest: manager.has_digest(date),
+        session_count: sessions.len(),
+        summary,
+        sessions,
+        insights,
+        usage,
+        jobs,
+    })
+}
+
 /// Get session conversation (transcript parsed into chat messages)
 pub async fn get_session_conversation(
     State(state): State<Arc<AppState>>,
@@ -719,11 +1873,11 @@ pub async fn get_session_conversation(
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> impl IntoResponse {
     let config = state.config.read().unwrap().clone();
-    let manager = ArchiveManager::new(config);
+    let manager = state.archive();
 
-    // Read session markdown to extract transcript_path from frontmatter
+    // Prefer the archived transcript copy; the original may have been cleaned up
     let transcript_path = match manager.read_session(&date, &name) {
-        Ok(content) => extract_transcript_path(&content),
+        Ok(content) => transcript::resolve(&config, &date, &content),
         Err(e) => {
             return Json(ApiResponse::<ConversationDto>::error(format!(
                 "Failed to read session: {}",
@@ -732,23 +1886,7 @@ pub async fn get_session_conversation(
         }
     };
 
-    let transcript_path = match transcript_path {
-        Some(p) => p,
-        None => {
-            return Json(ApiResponse::success(ConversationDto {
-                messages: vec![],
-                total_entries: 0,
-                has_transcript: false,
-                page: 0,
-                page_size: 0,
-                has_more: false,
-            }))
-        }
-    };
-
-    // Check if transcript file exists
-    let path = std::path::Path::new(&transcript_path);
-    if !path.exists() {
+    let Some(transcript_path) = transcript_path else {
         return Json(ApiResponse::success(ConversationDto {
             messages: vec![],
             total_entries: 0,
@@ -757,15 +1895,16 @@ pub async fn get_session_conversation(
             page_size: 0,
             has_more: false,
         }));
-    }
+    };
 
     let page: usize = params.get("page").and_then(|p| p.parse().ok()).unwrap_or(0);
     let page_size: usize = params
         .get("page_size")
         .and_then(|p| p.parse().ok())
         .unwrap_or(50);
+    let include_thinking = params.get("include_thinking").is_some_and(|v| v == "true");
 
-    match parse_transcript_to_conversation(&transcript_path, page, page_size) {
+    match parse_transcript_to_conversation(&transcript_path, page, page_size, include_thinking) {
         Ok(dto) => Json(ApiResponse::success(dto)),
         Err(e) => Json(ApiResponse::<ConversationDto>::error(format!(
             "Failed to parse transcript: {}",
@@ -774,79 +1913,179 @@ pub async fn get_session_conversation(
     }
 }
 
-// Helper functions
+/// Find messages of a session's conversation matching `q`, with the page
+/// (for `page_size`) each one is on
+pub async fn search_session_conversation(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let query = params.get("q").map(|q| q.trim()).unwrap_or_default();
+    if query.is_empty() {
+        return Json(ApiResponse::<ConversationSearchDto>::error(
+            "Missing search query 'q'".to_string(),
+        ));
+    }
+    let page_size: usize = params
+        .get("page_size")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(50);
+    // Must match the conversation request for indices and pages to line up
+    let include_thinking = params.get("include_thinking").is_some_and(|v| v == "true");
+    let config = state.config.read().unwrap().clone();
+    let manager = state.archive();
 
-fn parse_daily_summary(date: &str, content: &str) -> DailySummaryDto {
-    let extract_section = |header: &str| -> Option<String> {
-        let pattern = format!("## {}\n", header);
-        if let Some(start) = content.find(&pattern) {
-            let start = start + pattern.len();
-            let end = content[start..]
-                .find("\n## ")
-                .map(|i| start + i)
-                .unwrap_or(content.len());
-            let section = content[start..end].trim().to_string();
-            if section.is_empty() || section == "No sessions recorded yet." {
-                None
-            } else {
-                Some(section)
+    let transcript_path = match manager.read_session(&date, &name) {
+        Ok(content) => transcript::resolve(&config, &date, &content),
+        Err(e) => {
+            return Json(ApiResponse::error(format!("Failed to read session: {}", e)));
+        }
+    };
+    let Some(transcript_path) = transcript_path else {
+        return Json(ApiResponse::success(ConversationSearchDto {
+            query: query.to_string(),
+            hits: vec![],
+            total_entries: 0,
+            page_size,
+            has_transcript: false,
+        }));
+    };
+
+    let query = query.to_string();
+    let searched = tokio::task::spawn_blocking(move || {
+        load_conversation(&transcript_path, include_thinking).map(|messages| {
+            let hits = search_conversation(&messages, &query, page_size);
+            ConversationSearchDto {
+                query,
+                hits,
+                total_entries: messages.len(),
+                page_size,
+                has_transcript: true,
             }
-        } else {
-            None
+        })
+    })
+    .await;
+    match searched {
+        Ok(Ok(dto)) => Json(ApiResponse::success(dto)),
+        Ok(Err(e)) => Json(ApiResponse::error(format!(
+            "Failed to parse transcript: {}",
+            e
+        ))),
+        Err(e) => Json(ApiResponse::error(e.to_string())),
+    }
+}
+
+/// Link the Summary, Key Decisions and Learnings items of a session to the
+/// transcript lines and conversation pages they were based on
+pub async fn get_session_sources(
+    State(state): State<Arc<AppState>>,
+    Path((date, name)): Path<(String, String)>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let section = params.get("section").map(String::as_str);
+    if let Some(section) = section {
+        if !sources::SECTIONS.iter().any(|(key, _)| *key == section) {
+            return Json(ApiResponse::<SessionSourcesDto>::error(format!(
+                "Unknown section '{}': expected summary, decisions or learnings",
+                section
+            )));
+        }
+    }
+    let page_size: usize = params
+        .get("page_size")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(50);
+    let include_thinking = params.get("include_thinking").is_some_and(|v| v == "true");
+    let config = state.config.read().unwrap().clone();
+
+    let content = match state.archive().read_session(&date, &name) {
+        Ok(content) => content,
+        Err(e) => return Json(ApiResponse::error(format!("Failed to read session: {}", e))),
+    };
+    let transcript_path = transcript::resolve(&config, &date, &content);
+    let has_transcript = transcript_path.is_some();
+    let messages = match transcript_path {
+        Some(path) if SourceMap::read(&content).is_some() => {
+            tokio::task::spawn_blocking(move || load_conversation(&path, include_thinking))
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .unwrap_or_default()
         }
+        _ => Vec::new(),
     };
 
-    // Parse a section into cards by splitting on ### subsections.
-    // Backward compat: if no ### found, wrap entire section as a single card.
-    let extract_cards = |header: &str| -> Vec<SummaryCardDto> {
-        let section = match extract_section(header) {
-            Some(s) => s,
-            None => return Vec::new(),
-        };
+    Json(ApiResponse::success(SessionSourcesDto {
+        sections: link_sources(&content, &messages, section, page_size),
+        page_size,
+        has_transcript,
+    }))
+}
 
-        let mut cards: Vec<SummaryCardDto> = Vec::new();
-        let mut current_title: Option<String> = None;
-        let mut current_lines: Vec<String> = Vec::new();
-
-        for line in section.lines() {
-            if let Some(title) = line.strip_prefix("### ") {
-                // Flush previous card
-                if let Some(prev_title) = current_title.take() {
-                    let body = current_lines.join("\n").trim().to_string();
-                    if !body.is_empty() {
-                        cards.push(SummaryCardDto {
-                            title: prev_title,
-                            content: body,
-                        });
+/// Summary items of an archive with their line ranges and the messages
+/// starting within them
+fn link_sources(
+    content: &str,
+    messages: &[ConversationMessage],
+    only: Option<&str>,
+    page_size: usize,
+) -> Vec<SectionSourcesDto> {
+    let map = SourceMap::read(content).unwrap_or_default();
+    sources::SECTIONS
+        .iter()
+        .map(|(key, _)| *key)
+        .filter(|key| only.is_none_or(|only| only == *key))
+        .map(|key| {
+            let ranges = map.items(key);
+            let items = sources::section_items(content, key)
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let lines = ranges.get(i).cloned().unwrap_or_default();
+                    let within: Vec<usize> = messages
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, msg)| {
+                            msg.line.is_some_and(|line| {
+                                lines
+                                    .iter()
+                                    .any(|[start, end]| (*start..=*end).contains(&line))
+                            })
+                        })
+                        .map(|(index, _)| index)
+                        .collect();
+                    SourceItemDto {
+                        text: text.to_string(),
+                        page: within.first().map(|index| index / page_size.max(1)),
+                        lines,
+                        messages: within,
                     }
-                }
-                current_title = Some(title.trim().to_string());
-                current_lines.clear();
-            } else {
-                current_lines.push(line.to_string());
+                })
+                .collect();
+            SectionSourcesDto {
+                section: key.to_string(),
+                items,
             }
-        }
+        })
+        .collect()
+}
 
-        // Flush last card
-        if let Some(prev_title) = current_title {
-            let body = current_lines.join("\n").trim().to_string();
-            if !body.is_empty() {
-                cards.push(SummaryCardDto {
-                    title: prev_title,
-                    content: body,
-                });
-            }
-        }
+// Helper functions
 
-        // Backward compatibility: if no ### subsections found, wrap as single card
-        if cards.is_empty() && !section.is_empty() {
-            cards.push(SummaryCardDto {
-                title: header.to_string(),
-                content: section,
-            });
-        }
+fn parse_daily_summary(date: &str, content: &str) -> DailySummaryDto {
+    let extract_section = |header: &str| -> Option<String> {
+        crate::archive::section(content, header).map(str::to_string)
+    };
 
-        cards
+    let extract_cards = |header: &str| -> Vec<SummaryCardDto> {
+        crate::archive::section_cards(content, header)
+            .into_iter()
+            .map(|card| SummaryCardDto {
+                title: card.title,
+                content: card.content,
+                sessions: Vec::new(),
+            })
+            .collect()
     };
 
     // Extract session names from frontmatter or content
@@ -869,122 +2108,33 @@ fn parse_daily_summary(date: &str, content: &str) -> DailySummaryDto {
         Vec::new()
     };
 
-    // Skills & Commands live under "## Skills & Commands Identified" with
-    // "### Potential Skills" and "### Potential Commands" as sub-markers.
-    // The LLM-generated card content may contain rogue ## headings (e.g. "## 可复用技能"),
-    // so we locate these markers directly in the full content rather than relying on
-    // extract_section which would be broken by embedded ## headings.
-    let extract_skills_commands = || -> (Vec<SummaryCardDto>, Vec<SummaryCardDto>) {
-        let skills_marker = "\n### Potential Skills\n";
-        let commands_marker = "\n### Potential Commands\n";
-
-        // Also support file starting with the marker (no leading newline)
-        let skills_start = content
-            .find(skills_marker)
-            .map(|i| i + skills_marker.len())
-            .or_else(|| {
-                content
-                    .strip_prefix("### Potential Skills\n")
-                    .map(|_| "### Potential Skills\n".len())
-            });
-        let commands_start = content
-            .find(commands_marker)
-            .map(|i| i + commands_marker.len());
-
-        // Find the footer marker: "---" followed by "*Generated" or "*Archived"
-        let find_footer = |from: usize| -> usize {
-            let mut search_from = from;
-            loop {
-                match content[search_from..].find("\n---\n") {
-                    Some(pos) => {
-                        let after = search_from + pos + 4; // skip past "\n---\n"
-                        if content[after..].starts_with('*') {
-                            return search_from + pos;
-                        }
-                        // This "---" is a content separator (e.g. between cards), skip it
-                        search_from = after;
-                    }
-                    None => return content.len(),
-                }
-            }
-        };
-
-        // Skills section: from after "### Potential Skills\n" to "### Potential Commands\n" (or footer)
-        let skills_text = skills_start.map(|start| {
-            let end = content[start..]
-                .find(commands_marker)
-                .map(|i| start + i)
-                .unwrap_or_else(|| find_footer(start));
-            content[start..end].trim()
-        });
-
-        // Commands section: from after "### Potential Commands\n" to the footer
-        let commands_text = commands_start.map(|start| {
-            let end = find_footer(start);
-            content[start..end].trim()
-        });
-
-        let parse_cards_from_text = |text: Option<&str>| -> Vec<SummaryCardDto> {
-            let section = match text {
-                Some(s) if !s.is_empty() => s,
-                _ => return Vec::new(),
-            };
-            let mut cards: Vec<SummaryCardDto> = Vec::new();
-            let mut current_title: Option<String> = None;
-            let mut current_lines: Vec<String> = Vec::new();
-
-            for line in section.lines() {
-                // Card titles are ### level; skip any ## lines (LLM noise)
-                if let Some(title) = line.strip_prefix("### ") {
-                    if let Some(prev_title) = current_title.take() {
-                        let body = current_lines.join("\n").trim().to_string();
-                        if !body.is_empty() {
-                            cards.push(SummaryCardDto {
-                                title: prev_title,
-                                content: body,
-                            });
-                        }
-                    }
-                    current_title = Some(title.trim().to_string());
-                    current_lines.clear();
-                } else if line.starts_with("## ") {
-                    // Skip rogue H2 headings embedded in LLM content
-                    continue;
-                } else {
-                    current_lines.push(line.to_string());
-                }
-            }
-            if let Some(prev_title) = current_title {
-                let body = current_lines.join("\n").trim().to_string();
-                if !body.is_empty() {
-                    cards.push(SummaryCardDto {
-                        title: prev_title,
-                        content: body,
-                    });
-                }
-            }
-            cards
-        };
-
-        let skills = parse_cards_from_text(skills_text);
-        let commands = parse_cards_from_text(commands_text);
-        (skills, commands)
+    let (skills, commands) = crate::archive::skill_command_cards(content);
+    let to_dtos = |cards: Vec<crate::archive::SummaryCard>| -> Vec<SummaryCardDto> {
+        cards
+            .into_iter()
+            .map(|card| SummaryCardDto {
+                title: card.title,
+                content: card.content,
+                sessions: Vec::new(),
+            })
+            .collect()
     };
 
-    let (skills, commands) = extract_skills_commands();
-
     DailySummaryDto {
         date: date.to_string(),
         overview: extract_section("Overview").unwrap_or_default(),
         session_count: sessions.len(),
         sessions,
         insights: extract_cards("Key Insights"),
-        skills,
-        commands,
+        skills: to_dtos(skills),
+        commands: to_dtos(commands),
         reflections: extract_section("Reflections"),
         tomorrow_focus: extract_cards("Tomorrow's Focus"),
         raw_content: content.to_string(),
         file_path: String::new(), // Will be set by caller
+        language: None,
+        languages: Vec::new(),
+        locked: false,
     }
 }
 
@@ -1029,70 +2179,249 @@ fn extract_session_preview(content: &str) -> (String, String) {
     (title, summary)
 }
 
-/// Extract transcript_path from session markdown YAML frontmatter
-fn extract_transcript_path(content: &str) -> Option<String> {
-    if let Some(stripped) = content.strip_prefix("---\n") {
-        if let Some(end) = stripped.find("\n---") {
-            let frontmatter = &stripped[..end];
-            for line in frontmatter.lines() {
-                if let Some((key, value)) = line.split_once(':') {
-                    let key = key.trim();
-                    if key == "transcript_path" {
-                        let value = value.trim().trim_matches('"');
-                        if value != "N/A" && !value.is_empty() {
-                            return Some(value.to_string());
-                        }
-                    }
-                }
+/// Parse JSONL transcript file into paginated ConversationDto.
+///
+/// Sub-agent sidechains, inline or in separate files, are nested under the
+/// Task tool_use block that launched them rather than shown as main turns.
+fn parse_transcript_to_conversation(
+    path: &std::path::Path,
+    page: usize,
+    page_size: usize,
+    include_thinking: bool,
+) -> anyhow::Result<ConversationDto> {
+    let final_messages = load_conversation(path, include_thinking)?;
+    let total_entries = final_messages.len();
+
+    // Paginate
+    let start = page * page_size;
+    let end = (start + page_size).min(total_entries);
+    let has_more = end < total_entries;
+    let page_messages = if start < total_entries {
+        final_messages[start..end].to_vec()
+    } else {
+        vec![]
+    };
+
+    Ok(ConversationDto {
+        messages: page_messages,
+        total_entries,
+        has_transcript: true,
+        page,
+        page_size,
+        has_more,
+    })
+}
+
+/// All messages of a transcript, with sub-agent runs nested under the
+/// tool calls that started them
+fn load_conversation(
+    path: &std::path::Path,
+    include_thinking: bool,
+) -> anyhow::Result<Vec<ConversationMessage>> {
+    use std::io::BufRead;
+
+    let reader = transcript::open(path)?;
+
+    // Inline sidechain entries, grouped per sub-agent run in file order
+    let mut chains: Vec<(Option<String>, Vec<serde_json::Value>)> = Vec::new();
+    let mut read_error = None;
+    let entries = reader
+        .lines()
+        .map_while(|line| line.map_err(|e| read_error = Some(e)).ok())
+        .enumerate()
+        .filter_map(|(index, line)| {
+            serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .map(|entry| (Some(index + 1), entry))
+        })
+        .filter(|(_, entry)| {
+            if !sidechain::is_sidechain(entry) {
+                return true;
+            }
+            let agent = sidechain::agent_id(entry).map(String::from);
+            let starts_run = entry.get("parentUuid").is_none_or(|p| p.is_null());
+            let existing = match &agent {
+                Some(_) => chains.iter_mut().find(|(id, _)| *id == agent),
+                None if !starts_run => chains.iter_mut().rev().find(|(id, _)| id.is_none()),
+                None => None,
+            };
+            match existing {
+                Some((_, run)) => run.push(entry.clone()),
+                None => chains.push((agent, vec![entry.clone()])),
             }
+            false
+        });
+    let mut conversation = build_conversation(entries, include_thinking);
+    if let Some(e) = read_error {
+        return Err(e.into());
+    }
+
+    for file in sidechain::files(path) {
+        let Ok(reader) = transcript::open(&file) else {
+            continue;
+        };
+        let entries: Vec<serde_json::Value> = reader
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        let agent = entries
+            .iter()
+            .find_map(sidechain::agent_id)
+            .map(String::from)
+            .or_else(|| {
+                sidechain::session_stem(&file)
+                    .and_then(|stem| stem.strip_prefix("agent-"))
+                    .map(String::from)
+            });
+        chains.push((agent, entries));
+    }
+
+    for (agent, entries) in chains {
+        let run = build_conversation(entries.into_iter().map(|e| (None, e)), include_thinking);
+        let prompt = run
+            .messages
+            .iter()
+            .find_map(|msg| match msg.content.first() {
+                Some(ConversationContentBlock::Text { text }) if msg.role == "user" => {
+                    Some(text.trim().to_string())
+                }
+                _ => None,
+            });
+        let target = agent
+            .and_then(|id| conversation.agent_tool_uses.get(&id).cloned())
+            .or_else(|| prompt.and_then(|p| conversation.agent_prompts.get(&p).cloned()));
+        if let Some(target) = target {
+            attach_sidechain(&mut conversation.messages, &target, run.messages);
         }
     }
-    None
+
+    Ok(conversation.messages)
 }
 
-/// Parse JSONL transcript file into paginated ConversationDto
-fn parse_transcript_to_conversation(
-    path: &str,
-    page: usize,
+/// Messages containing `query` (case-insensitive) in their text, tool
+/// calls, tool results or nested sub-agent turns, by conversation index
+fn search_conversation(
+    messages: &[ConversationMessage],
+    query: &str,
     page_size: usize,
-) -> anyhow::Result<ConversationDto> {
-    use std::io::{BufRead, BufReader};
+) -> Vec<ConversationHitDto> {
+    let needle = query.to_lowercase();
+    messages
+        .iter()
+        .enumerate()
+        .filter_map(|(index, message)| {
+            let snippet = message_snippet(message, &needle)?;
+            Some(ConversationHitDto {
+                index,
+                page: index / page_size.max(1),
+                role: message.role.clone(),
+                snippet,
+            })
+        })
+        .collect()
+}
 
-    let file = std::fs::File::open(path)?;
-    let reader = BufReader::new(file);
+/// Text around the first match of `needle` in `message`
+fn message_snippet(message: &ConversationMessage, needle: &str) -> Option<String> {
+    message.content.iter().find_map(|block| match block {
+        ConversationContentBlock::Text { text } => snippet(text, needle),
+        ConversationContentBlock::ToolUse {
+            name,
+            input,
+            sidechain,
+            ..
+        } => snippet(name, needle)
+            .or_else(|| snippet(&input.to_string(), needle))
+            .or_else(|| {
+                sidechain
+                    .iter()
+                    .find_map(|turn| message_snippet(turn, needle))
+            }),
+        ConversationContentBlock::ToolResult { content, .. } => snippet(content, needle),
+        ConversationContentBlock::Thinking { thinking } => snippet(thinking, needle),
+    })
+}
+
+/// Characters of context kept on each side of a match
+const SNIPPET_CONTEXT: usize = 60;
+
+fn snippet(text: &str, needle: &str) -> Option<String> {
+    let needle_len = needle.chars().count();
+    let start = text.char_indices().map(|(i, _)| i).find(|&i| {
+        text[i..]
+            .chars()
+            .flat_map(char::to_lowercase)
+            .take(needle_len)
+            .eq(needle.chars())
+    })?;
+    let before: String = text[..start]
+        .chars()
+        .rev()
+        .take(SNIPPET_CONTEXT)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = text[start..]
+        .chars()
+        .take(needle_len + SNIPPET_CONTEXT)
+        .collect();
+    let mut snippet = String::new();
+    if start > before.len() {
+        snippet.push('…');
+    }
+    snippet.push_str(&before);
+    snippet.push_str(&after);
+    if text.len() > start + after.len() {
+        snippet.push('…');
+    }
+    Some(snippet.split_whitespace().collect::<Vec<_>>().join(" "))
+}
 
+/// Messages of one transcript (or sidechain) plus what's needed to link
+/// sub-agent runs back to the tool call that started them
+struct ParsedConversation {
+    messages: Vec<ConversationMessage>,
+    /// Sub-agent id -> tool_use_id, from `toolUseResult.agentId`
+    agent_tool_uses: std::collections::HashMap<String, String>,
+    /// Sub-agent prompt -> tool_use_id, for transcripts without agent ids
+    agent_prompts: std::collections::HashMap<String, String>,
+}
+
+/// `entries` are paired with their line in the transcript, if they have one
+fn build_conversation(
+    entries: impl Iterator<Item = (Option<usize>, serde_json::Value)>,
+    include_thinking: bool,
+) -> ParsedConversation {
     let mut conversation_messages: Vec<ConversationMessage> = Vec::new();
     // Collect tool results keyed by tool_use_id for later pairing
     let mut tool_results: std::collections::HashMap<String, String> =
         std::collections::HashMap::new();
+    let mut agent_tool_uses = std::collections::HashMap::new();
+    let mut agent_prompts = std::collections::HashMap::new();
 
     // Buffer for merging consecutive assistant entries
     let mut current_assistant_blocks: Vec<ConversationContentBlock> = Vec::new();
     let mut current_assistant_timestamp: Option<String> = None;
+    let mut current_assistant_line: Option<usize> = None;
 
     let flush_assistant = |blocks: &mut Vec<ConversationContentBlock>,
                            ts: &mut Option<String>,
+                           line: &mut Option<usize>,
                            messages: &mut Vec<ConversationMessage>| {
         if !blocks.is_empty() {
             messages.push(ConversationMessage {
                 role: "assistant".to_string(),
                 content: std::mem::take(blocks),
                 timestamp: ts.take(),
+                line: line.take(),
             });
         }
+        *line = None;
     };
 
-    for line in reader.lines() {
-        let line = line?;
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        let entry: serde_json::Value = match serde_json::from_str(&line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
+    for (line, entry) in entries {
         let entry_type = entry
             .get("type")
             .and_then(|v| v.as_str())
@@ -1109,6 +2438,7 @@ fn parse_transcript_to_conversation(
                 flush_assistant(
                     &mut current_assistant_blocks,
                     &mut current_assistant_timestamp,
+                    &mut current_assistant_line,
                     &mut conversation_messages,
                 );
 
@@ -1119,18 +2449,19 @@ fn parse_transcript_to_conversation(
                     .or_else(|| entry.get("content"));
 
                 match content_val {
-                    Some(serde_json::Value::String(text)) => {
-                        if !text.trim().is_empty() {
-                            conversation_messages.push(ConversationMessage {
-                                role: "user".to_string(),
-                                content: vec![ConversationContentBlock::Text {
-                                    text: text.clone(),
-                                }],
-                                timestamp,
-                            });
-                        }
+                    Some(serde_json::Value::String(text)) if !text.trim().is_empty() => {
+                        conversation_messages.push(ConversationMessage {
+                            role: "user".to_string(),
+                            content: vec![ConversationContentBlock::Text { text: text.clone() }],
+                            timestamp,
+                            line,
+                        });
                     }
                     Some(serde_json::Value::Array(arr)) => {
+                        let agent = entry
+                            .get("toolUseResult")
+                            .and_then(|r| r.get("agentId"))
+                            .and_then(|v| v.as_str());
                         // Tool result blocks - collect for pairing
                         for block in arr {
                             let block_type =
@@ -1142,6 +2473,10 @@ fn parse_transcript_to_conversation(
                                     // Extract text from content
                                     let result_text = extract_tool_result_text(block);
                                     tool_results.insert(tool_use_id.to_string(), result_text);
+                                    if let Some(agent) = agent {
+                                        agent_tool_uses
+                                            .insert(agent.to_string(), tool_use_id.to_string());
+                                    }
                                 }
                             }
                         }
@@ -1158,6 +2493,9 @@ fn parse_transcript_to_conversation(
                 if current_assistant_timestamp.is_none() {
                     current_assistant_timestamp = timestamp;
                 }
+                if current_assistant_line.is_none() {
+                    current_assistant_line = line;
+                }
 
                 match content_val {
                     Some(serde_json::Value::Array(blocks)) => {
@@ -1191,33 +2529,53 @@ fn parse_transcript_to_conversation(
                                         .get("input")
                                         .cloned()
                                         .unwrap_or(serde_json::Value::Null);
+                                    if sidechain::AGENT_TOOLS.contains(&name.as_str()) {
+                                        if let Some(prompt) =
+                                            input.get("prompt").and_then(|v| v.as_str())
+                                        {
+                                            agent_prompts
+                                                .insert(prompt.trim().to_string(), tool_id.clone());
+                                        }
+                                    }
                                     let input = truncate_json_value(input, 500);
                                     current_assistant_blocks.push(
                                         ConversationContentBlock::ToolUse {
                                             tool_use_id: tool_id,
                                             name,
                                             input,
+                                            sidechain: Vec::new(),
                                         },
                                     );
                                 }
-                                // Skip thinking blocks
+                                "thinking" if include_thinking => {
+                                    if let Some(thinking) =
+                                        block.get("thinking").and_then(|v| v.as_str())
+                                    {
+                                        if !thinking.trim().is_empty() {
+                                            current_assistant_blocks.push(
+                                                ConversationContentBlock::Thinking {
+                                                    thinking: thinking.to_string(),
+                                                },
+                                            );
+                                        }
+                                    }
+                                }
+                                // Skip thinking (unless asked for) and redacted thinking
                                 _ => {}
                             }
                         }
                     }
-                    Some(serde_json::Value::String(text)) => {
+                    Some(serde_json::Value::String(text)) if !text.trim().is_empty() => {
                         // Old format: content as string
-                        if !text.trim().is_empty() {
-                            if current_assistant_timestamp.is_none() {
-                                current_assistant_timestamp = entry
-                                    .get("timestamp")
-                                    .and_then(|v| v.as_str())
-                                    .map(String::from);
-                            }
-                            current_assistant_blocks.push(ConversationContentBlock::Text {
-                                text: text.to_string(),
-                            });
+                        if current_assistant_timestamp.is_none() {
+                            current_assistant_timestamp = entry
+                                .get("timestamp")
+                                .and_then(|v| v.as_str())
+                                .map(String::from);
                         }
+                        current_assistant_blocks.push(ConversationContentBlock::Text {
+                            text: text.to_string(),
+                        });
                     }
                     _ => {}
                 }
@@ -1231,6 +2589,7 @@ fn parse_transcript_to_conversation(
     flush_assistant(
         &mut current_assistant_blocks,
         &mut current_assistant_timestamp,
+        &mut current_assistant_line,
         &mut conversation_messages,
     );
 
@@ -1257,32 +2616,40 @@ fn parse_transcript_to_conversation(
                 role: msg.role,
                 content: new_content,
                 timestamp: msg.timestamp,
+                line: msg.line,
             });
         } else {
             final_messages.push(msg);
         }
     }
 
-    let total_entries = final_messages.len();
-
-    // Paginate
-    let start = page * page_size;
-    let end = (start + page_size).min(total_entries);
-    let has_more = end < total_entries;
-    let page_messages = if start < total_entries {
-        final_messages[start..end].to_vec()
-    } else {
-        vec![]
-    };
+    ParsedConversation {
+        messages: final_messages,
+        agent_tool_uses,
+        agent_prompts,
+    }
+}
 
-    Ok(ConversationDto {
-        messages: page_messages,
-        total_entries,
-        has_transcript: true,
-        page,
-        page_size,
-        has_more,
-    })
+/// Nest a sub-agent run under the tool_use block with `tool_use_id`
+fn attach_sidechain(
+    messages: &mut [ConversationMessage],
+    tool_use_id: &str,
+    run: Vec<ConversationMessage>,
+) {
+    let block = messages
+        .iter_mut()
+        .flat_map(|msg| msg.content.iter_mut())
+        .find_map(|block| match block {
+            ConversationContentBlock::ToolUse {
+                tool_use_id: id,
+                sidechain,
+                ..
+            } if id == tool_use_id => Some(sidechain),
+            _ => None,
+        });
+    if let Some(sidechain) = block {
+        sidechain.extend(run);
+    }
 }
 
 /// Extract text from a tool_result content block
@@ -1359,6 +2726,7 @@ fn map_session_usage_dto(u: crate::usage::types::SessionUsage) -> SessionUsageDt
         cache_creation_tokens: u.cache_creation_tokens,
         cache_read_tokens: u.cache_read_tokens,
         total_cost_usd: u.total_cost_usd,
+        subagent_cost_usd: u.subagent_cost_usd,
         model_calls: u
             .model_calls
             .into_iter()
@@ -1381,10 +2749,16 @@ fn extract_session_metadata(content: &str) -> SessionMetadata {
                     match key {
                         "title" => metadata.title = value.to_string(),
                         "date" => metadata.date = value.to_string(),
+                        "id" => metadata.id = Some(value.to_string()),
                         "session_id" => metadata.session_id = Some(value.to_string()),
                         "cwd" => metadata.cwd = Some(value.to_string()),
                         "git_branch" => metadata.git_branch = Some(value.to_string()),
                         "duration" => metadata.duration = Some(value.to_string()),
+                        "pinned" => metadata.pinned = value == "true",
+                        "user_outcome" => metadata.user_outcome = Some(value.to_string()),
+                        "user_satisfaction" => metadata.user_satisfaction = Some(value.to_string()),
+                        "user_note" => metadata.user_note = Some(value.to_string()),
+                        "user_stars" => metadata.user_stars = value.parse().ok(),
                         _ => {}
                     }
                 }
@@ -1394,3 +2768,291 @@ fn extract_session_metadata(content: &str) -> SessionMetadata {
 
     metadata
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tool_use_sidechain(message: &ConversationMessage) -> Option<&Vec<ConversationMessage>> {
+        message.content.iter().find_map(|block| match block {
+            ConversationContentBlock::ToolUse { sidechain, .. } => Some(sidechain),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_conversation_nests_sidechains_under_task() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"content":"Find the bug"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Task","input":{"prompt":"Search for panics"}},{"type":"tool_use","id":"t2","name":"Task","input":{"prompt":"Read the tests"}}]}}"#,
+            r#"{"type":"user","isSidechain":true,"parentUuid":null,"message":{"content":"Search for panics"}}"#,
+            r#"{"type":"assistant","isSidechain":true,"parentUuid":"x","message":{"content":[{"type":"text","text":"Found one in parser.rs"}]}}"#,
+            r#"{"type":"user","toolUseResult":{"agentId":"a2"},"message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":"Tests read"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed"}]}}"#,
+        ];
+        fs::write(&transcript, lines.join("\n")).unwrap();
+
+        let subagents = dir.path().join("abc").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        fs::write(
+            subagents.join("agent-a2.jsonl"),
+            [
+                r#"{"type":"user","isSidechain":true,"agentId":"a2","message":{"content":"Read the tests"}}"#,
+                r#"{"type":"assistant","isSidechain":true,"agentId":"a2","message":{"content":[{"type":"text","text":"They pass"}]}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let dto = parse_transcript_to_conversation(&transcript, 0, 50, false).unwrap();
+        // Sidechain turns don't show up as main conversation messages
+        assert_eq!(dto.total_entries, 3);
+
+        let task_message = &dto.messages[1];
+        let nested: Vec<_> = task_message
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ConversationContentBlock::ToolUse {
+                    tool_use_id,
+                    sidechain,
+                    ..
+                } => Some((tool_use_id.as_str(), sidechain.len())),
+                _ => None,
+            })
+            .collect();
+        // Inline run linked by prompt, file run linked by agent id
+        assert_eq!(nested, vec![("t1", 2), ("t2", 2)]);
+        assert!(tool_use_sidechain(&dto.messages[2]).is_none());
+    }
+
+    #[test]
+    fn test_conversation_thinking_blocks() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"content":"Rename it"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"The user means the module","signature":"x"},{"type":"redacted_thinking","data":"y"},{"type":"text","text":"Done"}]}}"#,
+        ];
+        fs::write(&transcript, lines.join("\n")).unwrap();
+
+        let hidden = parse_transcript_to_conversation(&transcript, 0, 50, false).unwrap();
+        assert_eq!(hidden.messages[1].content.len(), 1);
+
+        let shown = parse_transcript_to_conversation(&transcript, 0, 50, true).unwrap();
+        let json = serde_json::to_value(&shown.messages[1].content).unwrap();
+        assert_eq!(json[0]["type"], "thinking");
+        assert_eq!(json[0]["thinking"], "The user means the module");
+        assert_eq!(json[1]["type"], "text");
+        assert_eq!(json.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_link_sources_to_conversation() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"content":"Why does the parser panic?"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"An unwrap"}]}}"#,
+            r#"{"type":"user","message":{"content":"Use a lock instead"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done"}]}}"#,
+        ];
+        fs::write(&transcript, lines.join("\n")).unwrap();
+        let messages = load_conversation(&transcript, false).unwrap();
+        assert_eq!(messages[2].line, Some(3));
+
+        let content = "---\ntitle: \"Fix\"\nsources: {\"decisions\":[[[3,4]],[]]}\n---\n\n\
+                       ## Summary\n\nFixed it.\n\n## Key Decisions & Trade-offs\n\n\
+                       - Use a lock\n- Retry once\n\n## Code Changes\n\nNone\n";
+        let sections = link_sources(content, &messages, None, 2);
+        assert_eq!(sections.len(), 3);
+        assert!(sections[0].items[0].messages.is_empty());
+        let decisions = &sections[1].items;
+        assert_eq!(decisions[0].text, "Use a lock");
+        assert_eq!(decisions[0].lines, vec![[3, 4]]);
+        assert_eq!(decisions[0].messages, vec![2, 3]);
+        assert_eq!(decisions[0].page, Some(1));
+        assert_eq!(decisions[1].page, None);
+        assert_eq!(
+            link_sources(content, &messages, Some("learnings"), 2).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_search_conversation() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"content":"Why does the Parser panic?"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Grep","input":{"pattern":"unwrap"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"src/parser.rs:10 unwrap()"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed the parser."}]}}"#,
+        ];
+        fs::write(&transcript, lines.join("\n")).unwrap();
+
+        let messages = load_conversation(&transcript, false).unwrap();
+        let hits = search_conversation(&messages, "PARSER", 2);
+        let found: Vec<_> = hits.iter().map(|hit| (hit.index, hit.page)).collect();
+        assert_eq!(found, vec![(0, 0), (1, 0), (2, 1)]);
+        assert_eq!(hits[0].snippet, "Why does the Parser panic?");
+        assert_eq!(search_conversation(&messages, "unwrap", 50).len(), 1);
+        assert!(search_conversation(&messages, "segfault", 50).is_empty());
+
+        let long = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let around = snippet(&long, "needle").unwrap();
+        assert!(around.starts_with('…') && around.ends_with('…'));
+        assert_eq!(
+            around.chars().count(),
+            2 * SNIPPET_CONTEXT + "needle".len() + 2
+        );
+    }
+
+    #[test]
+    fn test_day_overview() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        manager
+            .write_session(
+                "2026-03-01",
+                "fix-bug",
+                "---\ntitle: \"Fix bug\"\n---\n\n# Fix bug\n",
+            )
+            .unwrap();
+        let pricing = PricingData::from_map(std::collections::HashMap::new());
+
+        let overview = build_day_overview(&manager, &config, &pricing, "2026-03-01").unwrap();
+        assert_eq!(overview.session_count, 1);
+        assert_eq!(overview.sessions[0].name, "fix-bug");
+        assert!(!overview.has_digest);
+        // The placeholder daily.md is there, but no digest yet
+        assert!(overview.summary.is_some());
+        assert!(overview.jobs.is_empty());
+
+        manager
+            .write_daily_summary(
+                "2026-03-01",
+                "---\ndate: 2026-03-01\n---\n\n## Overview\nDone.\n",
+            )
+            .unwrap();
+        let overview = build_day_overview(&manager, &config, &pricing, "2026-03-01").unwrap();
+        assert!(overview.has_digest);
+        assert_eq!(overview.summary.unwrap().overview, "Done.");
+    }
+
+    #[tokio::test]
+    async fn test_edits_cannot_escape_storage() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let dir = TempDir::new().unwrap();
+        let storage = dir.path().join("storage");
+        let mut config = Config::default();
+        config.storage.path = storage.clone();
+        ArchiveManager::new(config.clone())
+            .write_session("2024-01-15", "fix-bug", "---\ntitle: \"Fix bug\"\n---\n")
+            .unwrap();
+        let outside = dir.path().join("outside.md");
+        fs::write(&outside, "keep me").unwrap();
+        fs::create_dir_all(dir.path().join("x")).unwrap();
+        fs::write(dir.path().join("x").join("daily.md"), "keep me").unwrap();
+
+        let state = Arc::new(AppState::new(
+            config,
+            PricingData::from_map(std::collections::HashMap::new()),
+            "127.0.0.1",
+        ));
+        let app = super::super::router::create_router(state);
+        for uri in [
+            "/api/dates/2024-01-15/sessions/..%2F..%2Foutside",
+            "/api/dates/..%2Fx",
+            "/api/dates/2024-01-15/sessions/..%2F..%2Foutside/rename",
+        ] {
+            let body = r#"{"content": "overwritten", "topic": "moved"}"#;
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::patch(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(json["success"], false, "{}", uri);
+        }
+
+        assert_eq!(fs::read_to_string(&outside).unwrap(), "keep me");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("x").join("daily.md")).unwrap(),
+            "keep me"
+        );
+        // No backups were written next to the targets
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_checks_date_and_session() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        ArchiveManager::new(config.clone())
+            .write_session(
+                "2024-01-15",
+                "fix-bug",
+                "---\nschema_version: 2\ntitle: \"Fix bug\"\n---\n",
+            )
+            .unwrap();
+        let state = Arc::new(AppState::new(
+            config.clone(),
+            PricingData::from_map(std::collections::HashMap::new()),
+            "127.0.0.1",
+        ));
+        let app = super::super::router::create_router(state);
+
+        for (uri, status) in [
+            ("/api/dates/2024-01-15/mark-read", StatusCode::OK),
+            (
+                "/api/dates/2024-01-15/sessions/fix-bug/mark-read",
+                StatusCode::OK,
+            ),
+            ("/api/dates/yesterday/mark-read", StatusCode::BAD_REQUEST),
+            ("/api/dates/2024-01-16/mark-read", StatusCode::NOT_FOUND),
+            (
+                "/api/dates/2024-1-15/sessions/fix-bug/mark-read",
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                "/api/dates/2024-01-15/sessions/typo/mark-read",
+                StatusCode::NOT_FOUND,
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::post(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{}", uri);
+        }
+
+        let manager = ArchiveManager::new(config.clone());
+        let read_state = ReadState::load(&config);
+        assert!(read_state.session_read(&manager, "2024-01-15", "fix-bug"));
+        assert!(!read_state.session_read(&manager, "2024-01-15", "typo"));
+    }
+}
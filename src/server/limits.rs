@@ -0,0 +1,157 @@
+//! Per-client rate limiting for the API.
+//!
+//! Each client address gets a bucket of `server.rate_limit_per_minute`
+//! requests that refills continuously; a request finding its bucket empty
+//! is answered with 429 and a `Retry-After` header. Loopback clients (the
+//! dashboard on the same machine, `daily statusline`) are not limited while
+//! the server listens on a loopback address, unless
+//! `server.rate_limit_loopback` says otherwise: behind a reverse proxy on
+//! the same machine every client is loopback.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::dto::ApiResponse;
+use super::handlers::AppState;
+
+/// Buckets kept before idle (full) ones are dropped
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets keyed by client address
+pub struct RateLimiter {
+    per_minute: u32,
+    limit_loopback: bool,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Allow `per_minute` requests per client (0 = no limit), with loopback
+    /// clients exempt unless `limit_loopback`
+    pub fn new(per_minute: u32, limit_loopback: bool) -> Self {
+        Self {
+            per_minute,
+            limit_loopback,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take one request from `ip`'s bucket at `now`; on refusal, the
+    /// seconds until a request would be allowed
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        if self.per_minute == 0 || (ip.is_loopback() && !self.limit_loopback) {
+            return Ok(());
+        }
+        let capacity = self.per_minute as f64;
+        let per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * per_sec < capacity
+            });
+        }
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * per_sec;
+        bucket.tokens = (bucket.tokens + refill).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / per_sec).ceil() as u64)
+        }
+    }
+}
+
+/// Whether the dashboard listening on `host` is reachable from this
+/// machine only
+pub fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Middleware refusing API requests over the client's rate limit
+pub async fn rate_limit(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(ip) = client {
+        if let Err(retry_after) = state.rate_limiter.check(ip, Instant::now()) {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(ApiResponse::<()>::error(format!(
+                    "Too many requests; retry in {}s",
+                    retry_after
+                ))),
+            )
+                .into_response();
+        }
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2, false);
+        let client: IpAddr = "192.168.1.20".parse().unwrap();
+        let start = Instant::now();
+        assert!(limiter.check(client, start).is_ok());
+        assert!(limiter.check(client, start).is_ok());
+        assert_eq!(limiter.check(client, start), Err(30));
+        // Other clients and loopback have their own allowance
+        assert!(limiter.check("10.0.0.3".parse().unwrap(), start).is_ok());
+        for _ in 0..10 {
+            assert!(limiter.check("127.0.0.1".parse().unwrap(), start).is_ok());
+        }
+        assert!(limiter
+            .check(client, start + Duration::from_secs(30))
+            .is_ok());
+        assert!(RateLimiter::new(0, false).check(client, start).is_ok());
+
+        // Behind a reverse proxy loopback is every client
+        let proxied = RateLimiter::new(1, true);
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!(proxied.check(loopback, start).is_ok());
+        assert_eq!(proxied.check(loopback, start), Err(60));
+    }
+
+    #[test]
+    fn test_is_loopback_host() {
+        for host in ["127.0.0.1", "localhost", "::1", "[::1]"] {
+            assert!(is_loopback_host(host), "{}", host);
+        }
+        for host in ["0.0.0.0", "192.168.1.20", "::", "daily.lan"] {
+            assert!(!is_loopback_host(host), "{}", host);
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub mod dto;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod handlers;
+pub mod limits;
+pub mod preferences;
+pub mod read_state;
 pub mod router;
 pub mod static_files;
+pub mod tls;
+pub mod ws;
 
 pub use router::create_router;
@@ -0,0 +1,136 @@
+//! Dashboard preferences kept in the storage directory.
+//!
+//! Layout, date range, theme and hidden widgets are saved to
+//! `{storage}/ui-preferences.json` so the dashboard looks the same in every
+//! browser. The server only checks the values it understands; widget ids
+//! are whatever the dashboard uses.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::storage;
+
+/// Selected date range: the last `days`, or `from`..=`to`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DateRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+/// Dashboard settings shared by all browsers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiPreferences {
+    /// Widget ids in display order
+    #[serde(default)]
+    pub layout: Vec<String>,
+    #[serde(default)]
+    pub hidden_widgets: Vec<String>,
+    #[serde(default)]
+    pub date_range: Option<DateRange>,
+    /// "light" or "dark"; unset follows the system
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Set by the server on save
+    #[serde(default)]
+    pub updated: Option<DateTime<Local>>,
+}
+
+fn path(config: &Config) -> PathBuf {
+    config.storage_path().join("ui-preferences.json")
+}
+
+impl UiPreferences {
+    /// Saved preferences; the defaults if none were saved yet
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = path(config);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Check and store these preferences, replacing the saved ones
+    pub fn save(mut self, config: &Config) -> Result<Self> {
+        self.validate()?;
+        self.updated = Some(Local::now());
+        let path = path(config);
+        fs::create_dir_all(config.storage_path())?;
+        storage::write_file(config, &path, serde_json::to_string_pretty(&self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(theme) = &self.theme {
+            if theme != "light" && theme != "dark" {
+                anyhow::bail!("Unknown theme '{}': expected light or dark", theme);
+            }
+        }
+        if let Some(range) = &self.date_range {
+            for date in [&range.from, &range.to].into_iter().flatten() {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date '{}': expected YYYY-MM-DD", date))?;
+            }
+            if range.days == Some(0) {
+                anyhow::bail!("Date range must cover at least one day");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_preferences() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        assert_eq!(
+            UiPreferences::load(&config).unwrap(),
+            UiPreferences::default()
+        );
+
+        let prefs = UiPreferences {
+            layout: vec!["usage".into(), "goals".into()],
+            hidden_widgets: vec!["anomalies".into()],
+            date_range: Some(DateRange {
+                days: Some(30),
+                ..Default::default()
+            }),
+            theme: Some("dark".into()),
+            updated: None,
+        };
+        let saved = prefs.clone().save(&config).unwrap();
+        assert!(saved.updated.is_some());
+        assert_eq!(UiPreferences::load(&config).unwrap(), saved);
+
+        let bad_theme = UiPreferences {
+            theme: Some("neon".into()),
+            ..prefs.clone()
+        };
+        assert!(bad_theme.save(&config).is_err());
+        let bad_range = UiPreferences {
+            date_range: Some(DateRange {
+                from: Some("last week".into()),
+                ..Default::default()
+            }),
+            ..prefs
+        };
+        assert!(bad_range.save(&config).is_err());
+        assert_eq!(UiPreferences::load(&config).unwrap(), saved);
+    }
+}
@@ -0,0 +1,132 @@
+//! Which digests and sessions have been reviewed on the dashboard.
+//!
+//! Read times are saved to `{storage}/read-state.json`, so each profile,
+//! having its own storage, keeps its own. A digest or session rewritten
+//! after it was read (a regenerated digest, a re-summarized session)
+//! counts as unread again.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::ArchiveManager;
+use crate::config::Config;
+use crate::storage;
+
+fn path(config: &Config) -> PathBuf {
+    config.storage_path().join("read-state.json")
+}
+
+/// When each digest and session was last marked read
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadState {
+    /// Date of the digest
+    #[serde(default)]
+    digests: BTreeMap<String, DateTime<Local>>,
+    /// `{date}/{name}` of the session
+    #[serde(default)]
+    sessions: BTreeMap<String, DateTime<Local>>,
+}
+
+impl ReadState {
+    /// Saved read state; nothing read when there is none or it can't be
+    /// parsed, so a damaged file never breaks the listing
+    pub fn load(config: &Config) -> Self {
+        fs::read_to_string(path(config))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = path(config);
+        fs::create_dir_all(config.storage_path())?;
+        storage::write_file(config, &path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn mark_digest(&mut self, date: &str, read: bool) {
+        mark(&mut self.digests, date.to_string(), read);
+    }
+
+    pub fn mark_session(&mut self, date: &str, name: &str, read: bool) {
+        mark(&mut self.sessions, format!("{}/{}", date, name), read);
+    }
+
+    /// Whether the date's digest was read since it was last written
+    pub fn digest_read(&self, manager: &ArchiveManager, date: &str) -> bool {
+        read_since(self.digests.get(date), &manager.daily_summary_path(date))
+    }
+
+    /// Whether the session was read since it was last written
+    pub fn session_read(&self, manager: &ArchiveManager, date: &str, name: &str) -> bool {
+        read_since(
+            self.sessions.get(&format!("{}/{}", date, name)),
+            &manager.session_archive_path(date, name),
+        )
+    }
+}
+
+fn mark(times: &mut BTreeMap<String, DateTime<Local>>, key: String, read: bool) {
+    if read {
+        times.insert(key, Local::now());
+    } else {
+        times.remove(&key);
+    }
+}
+
+/// Read at `read_at` and not modified since. A compacted session has no
+/// plain file left, and compaction doesn't make it unread.
+fn read_since(read_at: Option<&DateTime<Local>>, path: &Path) -> bool {
+    let Some(read_at) = read_at else {
+        return false;
+    };
+    match fs::metadata(path).and_then(|meta| meta.modified()) {
+        Ok(modified) => DateTime::<Local>::from(modified) <= *read_at,
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_state_resets_on_rewrite() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        manager
+            .write_session("2024-01-15", "fix-bug", "---\nschema_version: 2\n---\n")
+            .unwrap();
+
+        let mut state = ReadState::load(&config);
+        assert!(!state.session_read(&manager, "2024-01-15", "fix-bug"));
+        state.mark_session("2024-01-15", "fix-bug", true);
+        state.mark_digest("2024-01-15", true);
+        state.save(&config).unwrap();
+
+        let mut state = ReadState::load(&config);
+        assert!(state.session_read(&manager, "2024-01-15", "fix-bug"));
+        assert!(state.digest_read(&manager, "2024-01-15"));
+
+        // Rewriting the session makes it unread again
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        manager
+            .write_session(
+                "2024-01-15",
+                "fix-bug",
+                "---\nschema_version: 2\n---\n\nv2\n",
+            )
+            .unwrap();
+        assert!(!state.session_read(&manager, "2024-01-15", "fix-bug"));
+
+        state.mark_digest("2024-01-15", false);
+        assert!(!state.digest_read(&manager, "2024-01-15"));
+    }
+}
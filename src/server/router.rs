@@ -1,4 +1,7 @@
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    response::Redirect,
     routing::{get, patch, post},
     Router,
 };
@@ -8,39 +11,138 @@ use tower_http::cors::{Any, CorsLayer};
 use super::handlers::{self, AppState};
 use super::static_files::serve_static;
 
-/// Create the main router with all routes
+/// Create the main router with all routes, under `server.base_path` if set
 pub fn create_router(state: Arc<AppState>) -> Router {
+    let server = state.config.read().unwrap().server.clone();
+    let base_path = normalize_base_path(server.base_path.as_deref().unwrap_or_default());
+
     // API routes
     let api_routes = Router::new()
         // Date/Archive routes
         .route("/dates", get(handlers::list_dates))
-        .route("/dates/:date", get(handlers::get_daily_summary))
+        .route(
+            "/dates/:date",
+            get(handlers::get_daily_summary).patch(handlers::update_daily_summary),
+        )
         .route("/dates/:date/digest", post(handlers::trigger_digest))
+        .route("/dates/:date/html", get(handlers::get_daily_html))
+        .route(
+            "/dates/:date/digest/regenerate",
+            post(handlers::regenerate_digest),
+        )
+        .route("/dates/:date/notes", post(handlers::add_note))
+        .route(
+            "/dates/:date/mark-read",
+            post(handlers::mark_date_read).delete(handlers::mark_date_unread),
+        )
         .route("/dates/:date/insights", get(handlers::get_date_insights))
+        .route("/dates/:date/overview", get(handlers::get_day_overview))
         .route("/dates/:date/sessions", get(handlers::list_sessions))
-        .route("/dates/:date/sessions/:name", get(handlers::get_session))
+        .route(
+            "/dates/:date/sessions/:name",
+            get(handlers::get_session).patch(handlers::update_session),
+        )
+        .route(
+            "/dates/:date/sessions/:name/pin",
+            post(handlers::pin_session).delete(handlers::unpin_session),
+        )
+        .route(
+            "/dates/:date/sessions/:name/mark-read",
+            post(handlers::mark_session_read).delete(handlers::mark_session_unread),
+        )
+        .route(
+            "/dates/:date/sessions/:name/annotation",
+            patch(handlers::annotate_session),
+        )
+        .route(
+            "/dates/:date/sessions/:name/rename",
+            patch(handlers::rename_session),
+        )
+        .route("/pinned", get(handlers::list_pinned))
+        .route("/sessions/active", get(handlers::list_active_sessions))
         .route(
             "/dates/:date/sessions/:name/conversation",
             get(handlers::get_session_conversation),
         )
+        .route(
+            "/dates/:date/sessions/:name/conversation/search",
+            get(handlers::search_session_conversation),
+        )
+        .route(
+            "/dates/:date/sessions/:name/sources",
+            get(handlers::get_session_sources),
+        )
         // Job routes
         .route("/jobs", get(handlers::list_jobs))
         .route("/jobs/:id", get(handlers::get_job))
         .route("/jobs/:id/log", get(handlers::get_job_log))
         .route("/jobs/:id/kill", post(handlers::kill_job))
+        .route("/usage/overhead", get(handlers::get_usage_overhead))
+        .route("/usage/blocks", get(handlers::get_usage_blocks))
+        .route("/usage/projects", get(handlers::get_usage_projects))
+        .route("/usage/export", get(handlers::get_usage_export))
+        .route("/stats", get(handlers::get_stats))
+        .route("/resolve", get(handlers::resolve_ref))
+        .route("/hooks/events", get(handlers::list_hook_events))
         // Config routes
         .route("/config", get(handlers::get_config))
         .route("/config", patch(handlers::update_config))
+        .route(
+            "/ui/preferences",
+            get(handlers::get_ui_preferences).put(handlers::put_ui_preferences),
+        )
         .route(
             "/config/templates/defaults",
             get(handlers::get_default_templates),
         )
+        .route(
+            "/config/templates/validate",
+            post(handlers::validate_template),
+        )
         // Health check
         .route("/health", get(handlers::health_check))
+        // Goals tracked across days
+        .route(
+            "/goals",
+            get(handlers::list_goals).post(handlers::create_goal),
+        )
+        .route(
+            "/goals/:id",
+            patch(handlers::update_goal).delete(handlers::delete_goal),
+        )
+        // Knowledge base topics
+        .route("/decisions", get(handlers::list_decisions))
+        .route("/topics", get(handlers::list_topics))
+        .route("/topics/:slug", get(handlers::get_topic))
+        // Questions answered from the archive
+        .route("/ask", post(handlers::ask))
         // Install skill/command from summary card
         .route("/install", post(handlers::install_card))
         // Insights routes
-        .route("/insights", get(handlers::get_insights));
+        .route("/insights", get(handlers::get_insights))
+        .route("/insights/tools", get(handlers::get_tool_insights))
+        .route("/insights/anomalies", get(handlers::get_insight_anomalies))
+        .route(
+            "/insights/friction/:friction_type",
+            get(handlers::get_friction_insights),
+        );
+
+    // GraphQL endpoint (single round trip for composite dashboard views)
+    #[cfg(feature = "graphql")]
+    let api_routes = api_routes.route(
+        "/graphql",
+        post(super::graphql::graphql_handler)
+            .layer(axum::Extension(super::graphql::build_schema(state.clone()))),
+    );
+
+    // Bounded bodies (templates, notes) and per-client request rates, for
+    // servers reachable beyond localhost
+    let api_routes = api_routes
+        .layer(DefaultBodyLimit::max(server.max_body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            super::limits::rate_limit,
+        ));
 
     // CORS layer for development
     let cors = CorsLayer::new()
@@ -49,9 +151,43 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .allow_headers(Any);
 
     // Combine routes
-    Router::new()
+    let app = Router::new()
         .nest("/api", api_routes)
-        .fallback_service(serve_static())
+        // Live events (job updates, finished digests)
+        .route("/ws", get(super::ws::ws_handler))
+        .fallback_service(serve_static(base_path.clone()))
         .layer(cors)
-        .with_state(state)
+        .with_state(state);
+    if base_path.is_empty() {
+        return app;
+    }
+
+    // Behind a reverse proxy path prefix, e.g. https://home.lan/daily/
+    let root = format!("{}/", base_path);
+    Router::new()
+        .route("/", get(move || async move { Redirect::temporary(&root) }))
+        .nest(&base_path, app)
+}
+
+/// `server.base_path` as "/prefix" without a trailing slash, or "" for the root
+pub fn normalize_base_path(path: &str) -> String {
+    let trimmed = path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_base_path() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path("/"), "");
+        assert_eq!(normalize_base_path("daily"), "/daily");
+        assert_eq!(normalize_base_path("/home/daily/"), "/home/daily");
+    }
 }
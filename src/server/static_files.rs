@@ -1,7 +1,6 @@
 use axum::{
     body::Body,
     http::{header, Response, StatusCode, Uri},
-    response::IntoResponse,
 };
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
@@ -11,23 +10,26 @@ use std::borrow::Cow;
 #[folder = "web/dist"]
 struct DashboardAssets;
 
-/// Create a service that serves embedded static files
-pub fn serve_static() -> axum::routing::MethodRouter {
-    axum::routing::get(static_handler)
+/// Create a service that serves embedded static files. `base_path` is the
+/// prefix the dashboard is mounted under ("" for the root).
+pub fn serve_static(base_path: String) -> axum::routing::MethodRouter {
+    axum::routing::get(move |uri: Uri| static_handler(uri, base_path))
 }
 
 /// Handle static file requests
-async fn static_handler(uri: Uri) -> impl IntoResponse {
+async fn static_handler(uri: Uri, base_path: String) -> Response<Body> {
     let path = uri.path().trim_start_matches('/');
 
     // Try to get the file directly
-    if let Some(content) = get_embedded_file(path) {
-        return content;
+    if !path.is_empty() && path != "index.html" {
+        if let Some(content) = get_embedded_file(path) {
+            return content;
+        }
     }
 
     // For SPA routing: if no file found and not an API route, serve index.html
     if !path.starts_with("api/") {
-        if let Some(content) = get_embedded_file("index.html") {
+        if let Some(content) = index_html(&base_path) {
             return content;
         }
     }
@@ -39,10 +41,29 @@ async fn static_handler(uri: Uri) -> impl IntoResponse {
         .unwrap()
 }
 
+/// index.html with the base path injected, so asset URLs, API calls and
+/// client-side routes resolve under the prefix
+fn index_html(base_path: &str) -> Option<Response<Body>> {
+    let content = DashboardAssets::get("index.html")?;
+    let html = String::from_utf8_lossy(&content.data);
+    let head = format!(
+        "<head>\n  <base href=\"{base}/\">\n  <script>window.__DAILY_BASE__ = \"{base}\"</script>",
+        base = base_path
+    );
+    let html = html.replacen("<head>", &head, 1);
+
+    Some(
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .header(header::CACHE_CONTROL, "no-cache")
+            .body(Body::from(html))
+            .unwrap(),
+    )
+}
+
 /// Get embedded file content with proper content type
 fn get_embedded_file(path: &str) -> Option<Response<Body>> {
-    let path = if path.is_empty() { "index.html" } else { path };
-
     DashboardAssets::get(path).map(|content| {
         let mime = mime_guess::from_path(path)
             .first_or_octet_stream()
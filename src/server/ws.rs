@@ -0,0 +1,88 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use super::dto::WsMessage;
+use super::handlers::AppState;
+
+/// Push server events (job updates, finished digests) to dashboard clients
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events))
+}
+
+async fn stream_events(mut socket: WebSocket, mut events: Receiver<WsMessage>) {
+    if send(&mut socket, &WsMessage::Connected).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(WsMessage::ServerShutdown) => {
+                    // Let clients tell a restart from a dropped connection
+                    let _ = send(&mut socket, &WsMessage::ServerShutdown).await;
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+                Ok(message) => {
+                    if send(&mut socket, &message).await.is_err() {
+                        break;
+                    }
+                }
+                // A slow client misses events rather than stalling the server
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Clients only listen; pings are answered by axum
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &WsMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::usage::pricing::PricingData;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_emitted_events_reach_subscribers() {
+        let state = AppState::new(
+            Config::default(),
+            PricingData::from_map(HashMap::new()),
+            "127.0.0.1",
+        );
+        // Emitting without subscribers is a no-op
+        state.emit(WsMessage::Connected);
+
+        let mut events = state.events.subscribe();
+        state.emit(WsMessage::DigestCompleted {
+            date: "2024-01-15".into(),
+            job_id: "job-1".into(),
+        });
+
+        let json = serde_json::to_value(events.recv().await.unwrap()).unwrap();
+        assert_eq!(json["type"], "DigestCompleted");
+        assert_eq!(json["data"]["date"], "2024-01-15");
+        assert_eq!(json["data"]["job_id"], "job-1");
+    }
+}
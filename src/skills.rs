@@ -0,0 +1,476 @@
+//! Deduplication of extracted skills.
+//!
+//! Before a skill extracted from a session is saved for review it is compared
+//! with the installed skills (`~/.claude/skills`) and the ones already pending,
+//! so the same lesson learned every week doesn't pile up as near-identical
+//! skills. Similarity is the word overlap of the skills' names and
+//! descriptions; the thresholds come from `[skills]` in the config.
+//!
+//! A pending skill is `pending-skills/{date}/{name}.md`, with any scripts,
+//! references and assets the extractor wrote next to it in `{date}/{name}/`.
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::frontmatter;
+use crate::config::{Config, SkillsConfig};
+use crate::storage::{self, Change};
+
+/// Words too common to say anything about what a skill does
+const STOPWORDS: &[&str] = &[
+    "and", "are", "for", "from", "how", "into", "its", "that", "the", "this", "use", "using",
+    "when", "with", "you", "your",
+];
+
+/// Folders a skill package may hold files in, besides SKILL.md
+const PACKAGE_DIRS: &[&str] = &["scripts", "references", "assets"];
+
+const FILE_START: &str = "=== FILE:";
+const FILE_END: &str = "=== END FILE ===";
+
+/// An extracted skill: SKILL.md plus the files it points to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillPackage {
+    pub skill: String,
+    pub files: Vec<SkillFile>,
+}
+
+/// A file of a skill package, by path relative to the skill folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Split an extraction response into the SKILL.md part (returned as-is,
+/// still to be unwrapped from its code block) and the
+/// `=== FILE: path ===` ... `=== END FILE ===` blocks after it. Files
+/// outside the package folders, or escaping them, are dropped.
+pub fn split_package(response: &str) -> (&str, Vec<SkillFile>) {
+    let Some(start) = response.find(FILE_START) else {
+        return (response, Vec::new());
+    };
+
+    let mut files = Vec::new();
+    let mut rest = &response[start..];
+    while let Some(block) = rest.strip_prefix(FILE_START) {
+        let (header, body) = block.split_once('\n').unwrap_or((block, ""));
+        let path = header.trim().trim_end_matches('=').trim().to_string();
+        let (content, after) = body.split_once(FILE_END).unwrap_or((body, ""));
+        if is_package_path(&path) {
+            files.push(SkillFile {
+                path,
+                content: unfence(content),
+            });
+        }
+        rest = match after.find(FILE_START) {
+            Some(next) => &after[next..],
+            None => "",
+        };
+    }
+    (&response[..start], files)
+}
+
+fn is_package_path(path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').collect();
+    parts.len() >= 2
+        && PACKAGE_DIRS.contains(&parts[0])
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && *part != "." && *part != ".." && !part.contains('\\'))
+}
+
+/// File content with a code fence the model wrapped it in removed
+fn unfence(content: &str) -> String {
+    let trimmed = content.trim_matches('\n');
+    let mut lines: Vec<&str> = trimmed.lines().collect();
+    if lines.len() >= 2
+        && lines[0].trim_start().starts_with("```")
+        && lines[lines.len() - 1].trim() == "```"
+    {
+        lines = lines[1..lines.len() - 1].to_vec();
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Folder holding the package files of the pending skill at `skill_path`
+pub fn files_dir(skill_path: &Path) -> PathBuf {
+    skill_path.with_extension("")
+}
+
+/// Write package files under `dir`, replacing what was there
+pub fn write_files(dir: &Path, files: &[SkillFile]) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    for file in files {
+        let path = dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &file.content)?;
+        #[cfg(unix)]
+        if file.path.starts_with("scripts/") {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy the folder tree `from` into `to`, overwriting files of the same name
+pub fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Where a known skill lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillKind {
+    Installed,
+    Pending { date: String },
+}
+
+/// An installed or pending skill, as far as comparison needs it
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub name: String,
+    pub description: String,
+    pub path: PathBuf,
+    pub kind: SkillKind,
+}
+
+/// What happened to a newly extracted skill
+#[derive(Debug, PartialEq)]
+pub enum Saved {
+    /// Saved as a new pending skill
+    New(PathBuf),
+    /// Saved as a pending update of the installed skill `of`
+    Update { path: PathBuf, of: String },
+    /// Replaced a near-identical pending skill, now seen `occurrences` times
+    Merged { path: PathBuf, occurrences: u32 },
+    /// Dropped as a near-identical copy of the installed skill `of`
+    Duplicate { of: String },
+}
+
+/// Folder of pending skills, by date
+pub fn pending_dir(config: &Config) -> PathBuf {
+    config.storage_path().join("pending-skills")
+}
+
+/// Folder Claude Code loads user skills from
+pub fn installed_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".claude")
+        .join("skills")
+}
+
+/// A frontmatter value of a skill, with surrounding quotes removed. Skills
+/// come from the model, so this scans lines rather than trusting the layout.
+pub fn field(content: &str, key: &str) -> Option<String> {
+    let prefix = format!("{}:", key);
+    content.lines().find_map(|line| {
+        let value = line.trim().strip_prefix(&prefix)?.trim();
+        let value = value.trim_matches('"').trim_matches('\'');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Name of a skill from its frontmatter
+pub fn skill_name(content: &str) -> String {
+    field(content, "name").unwrap_or_else(|| {
+        // Fallback with timestamp
+        format!("skill-{}", chrono::Local::now().format("%H%M%S"))
+    })
+}
+
+/// Skills installed under `dir` (`{name}/SKILL.md`)
+pub fn installed_skills(dir: &Path) -> Vec<Skill> {
+    let mut skills = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path().join("SKILL.md");
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let name = field(&content, "name")
+            .unwrap_or_else(|| entry.file_name().to_string_lossy().to_string());
+        skills.push(Skill {
+            name,
+            description: field(&content, "description").unwrap_or_default(),
+            path,
+            kind: SkillKind::Installed,
+        });
+    }
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+/// Skills pending review under `dir` (`{date}/{name}.md`), oldest first
+pub fn pending_skills(dir: &Path) -> Vec<Skill> {
+    let mut skills = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let date = entry.file_name().to_string_lossy().to_string();
+        for file in fs::read_dir(entry.path()).into_iter().flatten().flatten() {
+            let path = file.path();
+            if path.extension().is_none_or(|e| e != "md") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            skills.push(Skill {
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                description: field(&content, "description").unwrap_or_default(),
+                path,
+                kind: SkillKind::Pending { date: date.clone() },
+            });
+        }
+    }
+    // {date}/{name}.md sorts by date, then name
+    skills.sort_by(|a, b| a.path.cmp(&b.path));
+    skills
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> Option<f64> {
+    let union = a.union(b).count();
+    (union > 0).then(|| a.intersection(b).count() as f64 / union as f64)
+}
+
+/// Similarity of two skills from 0 to 1: the same name counts as identical,
+/// otherwise name words weigh 0.6 and description words 0.4
+pub fn similarity(name: &str, description: &str, other: &Skill) -> f64 {
+    if name.eq_ignore_ascii_case(&other.name) {
+        return 1.0;
+    }
+    let names = jaccard(&words(name), &words(&other.name)).unwrap_or(0.0);
+    match jaccard(&words(description), &words(&other.description)) {
+        Some(descriptions) => 0.6 * names + 0.4 * descriptions,
+        None => names,
+    }
+}
+
+/// Save a skill extracted on `date` for review, unless it duplicates one
+/// that is installed or pending, see the module docs
+pub fn save_pending(config: &Config, date: &str, package: &SkillPackage) -> Result<Saved> {
+    let saved = save_pending_in(
+        &pending_dir(config),
+        &installed_dir(),
+        &config.skills,
+        date,
+        package,
+    )?;
+    if let Saved::New(path) | Saved::Update { path, .. } | Saved::Merged { path, .. } = &saved {
+        storage::record(config, path, Change::Write);
+        for file in storage::files_under(config, &files_dir(path)) {
+            storage::record(config, &file, Change::Write);
+        }
+    }
+    Ok(saved)
+}
+
+fn save_pending_in(
+    pending_dir: &Path,
+    installed_dir: &Path,
+    settings: &SkillsConfig,
+    date: &str,
+    package: &SkillPackage,
+) -> Result<Saved> {
+    let content = &package.skill;
+    let name = skill_name(content);
+    let description = field(content, "description").unwrap_or_default();
+
+    let best = |skills: Vec<Skill>| {
+        skills
+            .into_iter()
+            .map(|skill| (similarity(&name, &description, &skill), skill))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+    };
+    let installed = best(installed_skills(installed_dir));
+    let pending = best(pending_skills(pending_dir));
+
+    if let Some((score, skill)) = &installed {
+        if *score >= settings.duplicate_threshold {
+            return Ok(Saved::Duplicate {
+                of: skill.name.clone(),
+            });
+        }
+    }
+
+    if let Some((score, skill)) = &pending {
+        if *score >= settings.duplicate_threshold {
+            // Keep the newest wording, remember how often it came up
+            let existing = fs::read_to_string(&skill.path).unwrap_or_default();
+            let occurrences = field(&existing, "occurrences")
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(1)
+                + 1;
+            let mut merged =
+                frontmatter::set(content, "occurrences", Some(&occurrences.to_string()));
+            if let Some(of) = frontmatter::get(&existing, "updates") {
+                if field(content, "updates").is_none() {
+                    merged = frontmatter::set(&merged, "updates", Some(of));
+                }
+            }
+            fs::write(&skill.path, merged)?;
+            write_files(&files_dir(&skill.path), &package.files)?;
+            return Ok(Saved::Merged {
+                path: skill.path.clone(),
+                occurrences,
+            });
+        }
+    }
+
+    let dir = pending_dir.join(date);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.md", name));
+
+    if let Some((score, skill)) = installed {
+        if score >= settings.update_threshold {
+            fs::write(
+                &path,
+                frontmatter::set(content, "updates", Some(&skill.name)),
+            )?;
+            write_files(&files_dir(&path), &package.files)?;
+            return Ok(Saved::Update {
+                path,
+                of: skill.name,
+            });
+        }
+    }
+
+    fs::write(&path, content)?;
+    write_files(&files_dir(&path), &package.files)?;
+    Ok(Saved::New(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill(name: &str, description: &str) -> String {
+        format!(
+            "---\nname: {}\ndescription: \"{}\"\n---\n\n# {}\n",
+            name, description, name
+        )
+    }
+
+    #[test]
+    fn test_save_pending_dedupes() {
+        let dir = TempDir::new().unwrap();
+        let pending = dir.path().join("pending-skills");
+        let installed = dir.path().join("skills");
+        fs::create_dir_all(installed.join("fix-cargo-workspace-deps")).unwrap();
+        fs::write(
+            installed.join("fix-cargo-workspace-deps/SKILL.md"),
+            skill(
+                "fix-cargo-workspace-deps",
+                "Fix broken dependency declarations in a Cargo workspace",
+            ),
+        )
+        .unwrap();
+        let settings = SkillsConfig::default();
+        let save = |date: &str, content: &str| {
+            let package = SkillPackage {
+                skill: content.to_string(),
+                files: Vec::new(),
+            };
+            save_pending_in(&pending, &installed, &settings, date, &package).unwrap()
+        };
+
+        // The same skill again is dropped
+        let again = skill("fix-cargo-workspace-deps", "Repair Cargo workspace deps");
+        assert_eq!(
+            save("2024-01-08", &again),
+            Saved::Duplicate {
+                of: "fix-cargo-workspace-deps".into()
+            }
+        );
+
+        // A close variant becomes an update of the installed skill
+        let variant = skill(
+            "fix-cargo-workspace-features",
+            "Fix feature declarations in a Cargo workspace",
+        );
+        let Saved::Update { path, of } = save("2024-01-08", &variant) else {
+            panic!("expected an update");
+        };
+        assert_eq!(of, "fix-cargo-workspace-deps");
+        let saved = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            frontmatter::get(&saved, "updates"),
+            Some("fix-cargo-workspace-deps")
+        );
+
+        // Unrelated skills are new, and merged when they come up again
+        let tokio = skill("debug-flaky-tokio-tests", "Find races in async tokio tests");
+        assert!(matches!(save("2024-01-08", &tokio), Saved::New(_)));
+        let merged = save("2024-01-15", &tokio);
+        assert_eq!(
+            merged,
+            Saved::Merged {
+                path: pending.join("2024-01-08/debug-flaky-tokio-tests.md"),
+                occurrences: 2
+            }
+        );
+        assert!(!pending.join("2024-01-15").exists());
+        assert_eq!(pending_skills(&pending).len(), 2);
+    }
+
+    #[test]
+    fn test_split_package() {
+        let response = "```markdown\n---\nname: check-deps\n---\n\nRun scripts/check.sh\n```\n\n\
+=== FILE: scripts/check.sh ===\n```bash\ncargo tree -d\n```\n=== END FILE ===\n\n\
+=== FILE: references/notes.md ===\n# Notes\n=== END FILE ===\n\n\
+=== FILE: ../escape.sh ===\nrm -rf /\n=== END FILE ===\n";
+
+        let (skill, files) = split_package(response);
+        assert!(skill.contains("name: check-deps") && !skill.contains("FILE"));
+        assert_eq!(
+            files,
+            vec![
+                SkillFile {
+                    path: "scripts/check.sh".into(),
+                    content: "cargo tree -d\n".into()
+                },
+                SkillFile {
+                    path: "references/notes.md".into(),
+                    content: "# Notes\n".into()
+                },
+            ]
+        );
+
+        let dir = TempDir::new().unwrap();
+        let pending = dir.path().join("2024-01-08/check-deps");
+        write_files(&pending, &files).unwrap();
+        copy_tree(&pending, &dir.path().join("installed")).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("installed/scripts/check.sh")).unwrap(),
+            "cargo tree -d\n"
+        );
+    }
+}
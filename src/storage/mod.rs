@@ -0,0 +1,570 @@
+//! Storage backends the archive is mirrored to: a local folder, an
+//! S3-compatible bucket or a WebDAV share.
+//!
+//! Remote backends mirror the archive rather than hold it: `ArchiveManager`
+//! stays synchronous and always reads, writes and lists `storage.path`, and
+//! only the sync below talks to a [`Storage`]. With a remote backend that
+//! folder is a working copy: every archive write or delete goes through
+//! [`write_file`] or [`remove_file`], which append it to a journal
+//! (`.storage-pending`), and [`flush`] replays the journal against the
+//! backend after each command. A push takes the journal over first
+//! (`.storage-pending.flushing`), so changes recorded while it runs start a
+//! new journal rather than being lost. `daily storage pull` fetches what
+//! other machines pushed.
+
+mod local;
+mod s3;
+mod webdav;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+pub use webdav::WebDavStorage;
+
+use crate::archive::atomic::write_atomic;
+use crate::config::Config;
+
+/// Journal of archive changes not yet pushed to a remote backend
+const JOURNAL: &str = ".storage-pending";
+
+/// Changes taken from the journal by a push that has not finished
+const FLUSHING: &str = ".storage-pending.flushing";
+
+/// Held while appending to the journal or taking it over
+const JOURNAL_LOCK: &str = ".storage-pending.lock";
+
+/// Object store holding archive files under `/`-separated keys relative to
+/// the archive root, e.g. `2024-01-15/daily.md`
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Human-readable location, e.g. `s3://bucket/prefix`
+    fn location(&self) -> String;
+
+    /// File contents, or None if there is no such key
+    async fn read(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn write(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Remove a key; removing a missing key is not an error
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Every key under `prefix` (all keys for ""), in no particular order
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+}
+
+/// The configured backend
+pub fn open(config: &Config) -> Result<Box<dyn Storage>> {
+    match config.storage.backend.as_str() {
+        "local" => Ok(Box::new(LocalStorage::new(config.storage_path()))),
+        "s3" => Ok(Box::new(S3Storage::from_config(&config.storage.s3)?)),
+        "webdav" => Ok(Box::new(WebDavStorage::from_config(
+            &config.storage.webdav,
+        )?)),
+        other => anyhow::bail!(
+            "Unknown storage.backend '{}': expected local, s3 or webdav",
+            other
+        ),
+    }
+}
+
+/// Whether archive changes have to be pushed somewhere
+pub fn is_remote(config: &Config) -> bool {
+    config.storage.backend != "local"
+}
+
+/// A change to push, as recorded in the journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Write,
+    Delete,
+}
+
+/// Remember that `path` under the storage folder was written or deleted.
+/// Does nothing for the local backend.
+pub fn record(config: &Config, path: &Path, change: Change) {
+    if !is_remote(config) {
+        return;
+    }
+    let root = config.storage_path();
+    let Some(key) = key_for(&root, path) else {
+        return;
+    };
+    let op = match change {
+        Change::Write => "put",
+        Change::Delete => "del",
+    };
+    let appended = with_journal_lock(&root, || {
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(root.join(JOURNAL))?;
+        writeln!(journal, "{} {}", op, key)?;
+        Ok(())
+    });
+    if let Err(e) = appended {
+        eprintln!("[daily] Warning: Failed to record {} for sync: {}", key, e);
+    }
+}
+
+/// Write a file under the storage folder atomically and queue it for the
+/// backend. Everything written there besides local state (dotfiles, jobs)
+/// goes through this or [`remove_file`].
+pub fn write_file(config: &Config, path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    write_atomic(path, contents)?;
+    record(config, path, Change::Write);
+    Ok(())
+}
+
+/// Delete a file under the storage folder and queue the delete
+pub fn remove_file(config: &Config, path: &Path) -> Result<()> {
+    fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    record(config, path, Change::Delete);
+    Ok(())
+}
+
+/// `/`-separated key of `path` relative to `root`
+fn key_for(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Run `f` holding the journal lock, which is released when it returns
+fn with_journal_lock<T>(root: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(root.join(JOURNAL_LOCK))?;
+    lock.lock()?;
+    f()
+}
+
+/// Pending changes, including those of a push in progress
+fn pending(root: &Path) -> BTreeMap<String, Change> {
+    let mut journal = fs::read_to_string(root.join(FLUSHING)).unwrap_or_default();
+    journal.push_str(&fs::read_to_string(root.join(JOURNAL)).unwrap_or_default());
+    parse_journal(&journal)
+}
+
+/// Changes in journal lines, last one per key wins
+fn parse_journal(journal: &str) -> BTreeMap<String, Change> {
+    journal
+        .lines()
+        .filter_map(|line| match line.split_once(' ')? {
+            ("put", key) => Some((key.to_string(), Change::Write)),
+            ("del", key) => Some((key.to_string(), Change::Delete)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Number of changes waiting to be pushed
+pub fn pending_count(config: &Config) -> usize {
+    pending(&config.storage_path()).len()
+}
+
+/// What a push or pull transferred
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub location: String,
+    pub uploaded: Vec<String>,
+    pub deleted: Vec<String>,
+    pub downloaded: Vec<String>,
+    /// Changes that failed and stay queued, as `key: error`
+    pub failed: Vec<String>,
+}
+
+/// Push journaled changes to the configured backend. Changes that fail stay
+/// in the journal for the next run.
+pub async fn flush(config: &Config) -> Result<SyncReport> {
+    if !is_remote(config) {
+        return Ok(SyncReport::default());
+    }
+    let remote = open(config)?;
+    flush_to(&config.storage_path(), remote.as_ref()).await
+}
+
+async fn flush_to(root: &Path, remote: &dyn Storage) -> Result<SyncReport> {
+    let mut report = SyncReport {
+        location: remote.location(),
+        ..Default::default()
+    };
+    let journal = root.join(JOURNAL);
+    let flushing = root.join(FLUSHING);
+    // Changes left by an interrupted push go first
+    let taken = with_journal_lock(root, || {
+        let mut taken = fs::read_to_string(&flushing).unwrap_or_default();
+        if let Ok(recorded) = fs::read_to_string(&journal) {
+            taken.push_str(&recorded);
+            write_atomic(&flushing, &taken)?;
+            fs::remove_file(&journal)?;
+        }
+        Ok(taken)
+    })?;
+    let changes = parse_journal(&taken);
+    if changes.is_empty() {
+        let _ = fs::remove_file(&flushing);
+        return Ok(report);
+    }
+
+    let mut remaining = Vec::new();
+    for (key, change) in changes {
+        let local = root.join(&key);
+        let result = match change {
+            // A file that is gone again by now only needs deleting remotely
+            Change::Write if local.is_file() => match fs::read(&local) {
+                Ok(data) => remote.write(&key, data).await,
+                Err(e) => Err(e.into()),
+            },
+            _ => remote.delete(&key).await,
+        };
+        match result {
+            Ok(()) if change == Change::Write && local.is_file() => report.uploaded.push(key),
+            Ok(()) => report.deleted.push(key),
+            Err(e) => {
+                report.failed.push(format!("{}: {:#}", key, e));
+                let op = if change == Change::Write {
+                    "put"
+                } else {
+                    "del"
+                };
+                remaining.push(format!("{} {}\n", op, key));
+            }
+        }
+    }
+
+    // Failed changes go back ahead of those recorded during the push,
+    // which are newer
+    with_journal_lock(root, || {
+        if !remaining.is_empty() {
+            let recorded = fs::read_to_string(&journal).unwrap_or_default();
+            write_atomic(&journal, remaining.concat() + &recorded)?;
+        }
+        let _ = fs::remove_file(&flushing);
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+/// Push journaled changes after a command, warning instead of failing
+pub async fn flush_quietly(config: &Config) {
+    if !is_remote(config) || pending_count(config) == 0 {
+        return;
+    }
+    match flush(config).await {
+        Ok(report) => {
+            if !report.failed.is_empty() {
+                eprintln!(
+                    "[daily] Warning: {} change(s) not pushed to {}; retried next time or with `daily storage push`",
+                    report.failed.len(),
+                    report.location
+                );
+            }
+        }
+        Err(e) => eprintln!("[daily] Warning: Failed to push archive changes: {:#}", e),
+    }
+}
+
+/// Queue every file under the storage folder for upload, e.g. after
+/// switching an existing archive to a remote backend
+pub fn queue_all(config: &Config) -> Result<usize> {
+    let files = files_under(config, &config.storage_path());
+    for path in &files {
+        record(config, path, Change::Write);
+    }
+    Ok(files.len())
+}
+
+/// Files under `dir` (the storage folder or a folder in it) that belong in
+/// the remote copy, e.g. to record a folder that was written or moved
+pub fn files_under(config: &Config, dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_files(&config.storage_path(), dir, &mut files);
+    files
+}
+
+/// Everything but dotfiles, jobs and the offline queue, which are local state
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || (dir == root && (name == "jobs" || name == "offline-queue")) {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => collect_files(root, &path, files),
+            Ok(kind) if kind.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Download remote files missing from the storage folder
+pub async fn pull(config: &Config) -> Result<SyncReport> {
+    let remote = open(config)?;
+    pull_from(&config.storage_path(), remote.as_ref()).await
+}
+
+async fn pull_from(root: &Path, remote: &dyn Storage) -> Result<SyncReport> {
+    let mut report = SyncReport {
+        location: remote.location(),
+        ..Default::default()
+    };
+    let pending = pending(root);
+    let mut keys = remote.list("").await?;
+    keys.sort();
+    for key in keys {
+        let local = root.join(&key);
+        // Local changes not pushed yet win over the remote copy
+        if local.exists() || pending.contains_key(&key) {
+            continue;
+        }
+        let Some(data) = remote.read(&key).await? else {
+            continue;
+        };
+        if let Some(dir) = local.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        write_atomic(&local, data)?;
+        report.downloaded.push(key);
+    }
+    Ok(report)
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters (and `/`
+/// when `keep_slash`), as S3 signing and WebDAV URLs require
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Text of every `<...name>` element in an XML document, whatever its
+/// namespace prefix, with the basic entities unescaped
+fn xml_values(xml: &str, name: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        let local = tag.rsplit(':').next().unwrap_or(tag);
+        if tag.starts_with('/') || tag.ends_with('/') || local != name {
+            continue;
+        }
+        let Some(close) = rest.find("</") else {
+            break;
+        };
+        values.push(
+            rest[..close]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+    }
+    values
+}
+
+/// Read a credential from the environment variable named in the config
+fn env_secret(var: &str, what: &str) -> Result<String> {
+    std::env::var(var)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .with_context(|| format!("{} not found in ${}", what, var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_flush_and_pull() {
+        let local_dir = TempDir::new().unwrap();
+        let remote_dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = local_dir.path().to_path_buf();
+        config.storage.backend = "s3".into();
+        let root = config.storage_path();
+        let remote = LocalStorage::new(remote_dir.path().to_path_buf());
+
+        let daily = root.join("2024-01-15").join("daily.md");
+        let session = root.join("2024-01-15").join("fix-bug.md");
+        fs::create_dir_all(daily.parent().unwrap()).unwrap();
+        fs::write(&daily, "digest").unwrap();
+        fs::write(&session, "session").unwrap();
+        record(&config, &daily, Change::Write);
+        record(&config, &session, Change::Write);
+        record(&config, &daily, Change::Write);
+        assert_eq!(pending_count(&config), 2);
+
+        let report = flush_to(&root, &remote).await.unwrap();
+        assert_eq!(report.uploaded.len(), 2);
+        assert_eq!(pending_count(&config), 0);
+        assert_eq!(
+            remote.read("2024-01-15/daily.md").await.unwrap().unwrap(),
+            b"digest"
+        );
+
+        fs::remove_file(&session).unwrap();
+        record(&config, &session, Change::Delete);
+        let report = flush_to(&root, &remote).await.unwrap();
+        assert_eq!(report.deleted, vec!["2024-01-15/fix-bug.md"]);
+
+        // Another machine pulls what was pushed
+        let other = TempDir::new().unwrap();
+        let report = pull_from(other.path(), &remote).await.unwrap();
+        assert_eq!(report.downloaded, vec!["2024-01-15/daily.md"]);
+        assert!(other.path().join("2024-01-15/daily.md").is_file());
+    }
+
+    /// Backend that records another change while pushing `a.md` and
+    /// fails to store `b.md`
+    struct BusyStorage {
+        config: Config,
+        inner: LocalStorage,
+    }
+
+    #[async_trait]
+    impl Storage for BusyStorage {
+        fn location(&self) -> String {
+            self.inner.location()
+        }
+
+        async fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            self.inner.read(key).await
+        }
+
+        async fn write(&self, key: &str, data: Vec<u8>) -> Result<()> {
+            match key {
+                "a.md" => {
+                    let c = self.config.storage_path().join("c.md");
+                    fs::write(&c, "c").unwrap();
+                    record(&self.config, &c, Change::Write);
+                }
+                "b.md" => anyhow::bail!("connection reset"),
+                _ => {}
+            }
+            self.inner.write(key, data).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.inner.delete(key).await
+        }
+
+        async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+            self.inner.list(prefix).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_recorded_during_flush_are_kept() {
+        let local_dir = TempDir::new().unwrap();
+        let remote_dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = local_dir.path().to_path_buf();
+        config.storage.backend = "s3".into();
+        let root = config.storage_path();
+        for name in ["a.md", "b.md"] {
+            fs::write(root.join(name), name).unwrap();
+            record(&config, &root.join(name), Change::Write);
+        }
+        let remote = BusyStorage {
+            config: config.clone(),
+            inner: LocalStorage::new(remote_dir.path().to_path_buf()),
+        };
+
+        let report = flush_to(&root, &remote).await.unwrap();
+        assert_eq!(report.uploaded, vec!["a.md"]);
+        assert_eq!(report.failed.len(), 1);
+        // The failed change stays queued ahead of the one recorded meanwhile
+        assert_eq!(
+            fs::read_to_string(root.join(JOURNAL)).unwrap(),
+            "put b.md\nput c.md\n"
+        );
+        assert!(!root.join(FLUSHING).exists());
+
+        let report = flush_to(&root, &remote.inner).await.unwrap();
+        assert_eq!(report.uploaded, vec!["b.md", "c.md"]);
+        assert_eq!(pending_count(&config), 0);
+    }
+
+    /// Serve `router` on a free local port, returning its base URL
+    pub(super) async fn serve(router: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_failed_push_is_replayed() {
+        let local_dir = TempDir::new().unwrap();
+        let remote_dir = TempDir::new().unwrap();
+        // A WebDAV share that is down
+        let down = serve(
+            axum::Router::new().fallback(|| async { axum::http::StatusCode::SERVICE_UNAVAILABLE }),
+        )
+        .await;
+        let mut config = Config::default();
+        config.storage.path = local_dir.path().to_path_buf();
+        config.storage.backend = "webdav".into();
+        config.storage.webdav.url = Some(format!("{}/dav", down));
+        let root = config.storage_path();
+        write_file(&config, &root.join("daily.md"), "digest").unwrap();
+        write_file(&config, &root.join("old.md"), "old").unwrap();
+        remove_file(&config, &root.join("old.md")).unwrap();
+
+        let report = flush(&config).await.unwrap();
+        assert!(report.uploaded.is_empty() && report.deleted.is_empty());
+        assert_eq!(report.failed.len(), 2);
+        assert_eq!(pending_count(&config), 2);
+        assert!(!root.join(FLUSHING).exists());
+
+        // The next push replays both changes
+        let remote = LocalStorage::new(remote_dir.path().to_path_buf());
+        let report = flush_to(&root, &remote).await.unwrap();
+        assert_eq!(report.uploaded, vec!["daily.md"]);
+        assert_eq!(report.deleted, vec!["old.md"]);
+        assert_eq!(pending_count(&config), 0);
+        assert_eq!(remote.read("daily.md").await.unwrap().unwrap(), b"digest");
+    }
+
+    #[test]
+    fn test_xml_values() {
+        let xml = "<D:multistatus><D:response><D:href>/dav/a%20b.md</D:href></D:response>\
+                   <d:response><d:href>/dav/x&amp;y/</d:href></d:response></D:multistatus>";
+        assert_eq!(xml_values(xml, "href"), vec!["/dav/a%20b.md", "/dav/x&y/"]);
+        assert_eq!(uri_encode("2024-01-15/a b.md", true), "2024-01-15/a%20b.md");
+    }
+}
@@ -0,0 +1,359 @@
+//! Backend for a WebDAV share, e.g. a NAS or Nextcloud folder.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Method, StatusCode, Url};
+use std::time::Duration;
+
+use super::{env_secret, uri_encode, xml_values, Storage};
+use crate::config::WebDavConfig;
+
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+
+pub struct WebDavStorage {
+    /// Folder URL, always ending in `/`
+    base: Url,
+    username: Option<String>,
+    password: Option<String>,
+    http: reqwest::Client,
+}
+
+impl WebDavStorage {
+    pub fn from_config(config: &WebDavConfig) -> Result<Self> {
+        let url = config
+            .url
+            .as_deref()
+            .filter(|url| !url.is_empty())
+            .context("storage.webdav.url is not set")?;
+        let base = Url::parse(&format!("{}/", url.trim_end_matches('/')))
+            .with_context(|| format!("Invalid storage.webdav.url '{}'", url))?;
+        let password = match &config.username {
+            Some(_) => Some(env_secret(&config.password_env, "WebDAV password")?),
+            None => None,
+        };
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .user_agent(concat!("daily/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self {
+            base,
+            username: config.username.clone(),
+            password,
+            http,
+        })
+    }
+
+    fn url(&self, key: &str) -> Result<Url> {
+        Ok(self.base.join(&uri_encode(key, true))?)
+    }
+
+    fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        let request = self.http.request(method, url);
+        match &self.username {
+            Some(user) => request.basic_auth(user, self.password.as_deref()),
+            None => request,
+        }
+    }
+
+    /// Create the collections leading up to `key`, parent first
+    async fn make_parents(&self, key: &str) -> Result<()> {
+        let mut path = String::new();
+        let parts: Vec<&str> = key.split('/').collect();
+        for part in &parts[..parts.len().saturating_sub(1)] {
+            path.push_str(part);
+            path.push('/');
+            let response = self
+                .request(Method::from_bytes(b"MKCOL")?, self.url(&path)?)
+                .send()
+                .await?;
+            // 405: the collection exists already
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                anyhow::bail!("WebDAV MKCOL {} failed: HTTP {}", path, status);
+            }
+        }
+        Ok(())
+    }
+
+    /// Entries directly inside the collection `dir` ("" for the root), as
+    /// (key, is_collection)
+    async fn entries(&self, dir: &str) -> Result<Vec<(String, bool)>> {
+        let url = self.url(dir)?;
+        let response = self
+            .request(Method::from_bytes(b"PROPFIND")?, url.clone())
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(r#"<?xml version="1.0"?><propfind xmlns="DAV:"><prop><resourcetype/></prop></propfind>"#)
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("WebDAV PROPFIND {} failed: HTTP {}", dir, response.status());
+        }
+        let body = response.text().await?;
+
+        let mut entries = Vec::new();
+        for href in xml_values(&body, "href") {
+            // Servers answer with absolute paths or full URLs
+            let Ok(entry) = url.join(&href) else {
+                continue;
+            };
+            let Some(relative) = entry.path().strip_prefix(self.base.path()) else {
+                continue;
+            };
+            let key = percent_decode(relative);
+            let is_dir = key.ends_with('/');
+            let key = key.trim_end_matches('/').to_string();
+            if key.is_empty() || key == dir.trim_end_matches('/') {
+                continue;
+            }
+            entries.push((key, is_dir));
+        }
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl Storage for WebDavStorage {
+    fn location(&self) -> String {
+        self.base.to_string()
+    }
+
+    async fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let response = self.request(Method::GET, self.url(key)?).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("WebDAV GET {} failed: HTTP {}", key, response.status());
+        }
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
+    async fn write(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let url = self.url(key)?;
+        let mut response = self
+            .request(Method::PUT, url.clone())
+            .body(data.clone())
+            .send()
+            .await?;
+        // 409 Conflict: the parent collection does not exist yet
+        if response.status() == StatusCode::CONFLICT {
+            self.make_parents(key).await?;
+            response = self.request(Method::PUT, url).body(data).send().await?;
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("WebDAV PUT {} failed: HTTP {}", key, response.status());
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, self.url(key)?).send().await?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            anyhow::bail!("WebDAV DELETE {} failed: HTTP {}", key, status);
+        }
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut pending = vec![String::new()];
+        while let Some(dir) = pending.pop() {
+            for (key, is_dir) in self.entries(&dir).await? {
+                let name = key.rsplit('/').next().unwrap_or(&key);
+                if name.starts_with('.') {
+                    continue;
+                }
+                if is_dir {
+                    if prefix.starts_with(&key) || key.starts_with(prefix) {
+                        pending.push(format!("{}/", key));
+                    }
+                } else if key.starts_with(prefix) {
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// Undo `%XX` escapes in a URL path
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use axum::http::{HeaderMap, Method, StatusCode, Uri};
+    use axum::response::IntoResponse;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Share {
+        files: BTreeMap<String, Vec<u8>>,
+        /// Collection paths, ending in `/`
+        dirs: BTreeSet<String>,
+    }
+
+    /// Share under `/dav/` that wants basic auth, refuses a PUT into a
+    /// missing collection with 409 and answers PROPFIND with `Depth: 1`
+    async fn fake_share() -> String {
+        let share = Arc::new(Mutex::new(Share {
+            dirs: BTreeSet::from(["/dav/".to_string()]),
+            ..Default::default()
+        }));
+        let handler = move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+            let share = share.clone();
+            async move {
+                // "alice:secret"
+                if headers.get("authorization").and_then(|v| v.to_str().ok())
+                    != Some("Basic YWxpY2U6c2VjcmV0")
+                {
+                    return StatusCode::UNAUTHORIZED.into_response();
+                }
+                let path = uri.path().to_string();
+                let parent = format!(
+                    "{}/",
+                    &path[..path.trim_end_matches('/').rfind('/').unwrap()]
+                );
+                let mut share = share.lock().unwrap();
+                match method.as_str() {
+                    "PUT" if !share.dirs.contains(&parent) => StatusCode::CONFLICT.into_response(),
+                    "PUT" => {
+                        share.files.insert(path, body.to_vec());
+                        StatusCode::CREATED.into_response()
+                    }
+                    "MKCOL" if share.dirs.contains(&path) => {
+                        StatusCode::METHOD_NOT_ALLOWED.into_response()
+                    }
+                    "MKCOL" => {
+                        share.dirs.insert(path);
+                        StatusCode::CREATED.into_response()
+                    }
+                    "GET" => match share.files.get(&path) {
+                        Some(data) => data.clone().into_response(),
+                        None => StatusCode::NOT_FOUND.into_response(),
+                    },
+                    "DELETE" => match share.files.remove(&path) {
+                        Some(_) => StatusCode::NO_CONTENT.into_response(),
+                        None => StatusCode::NOT_FOUND.into_response(),
+                    },
+                    "PROPFIND" if share.dirs.contains(&path) => {
+                        let child = |entry: &&String| {
+                            entry.strip_prefix(&path).is_some_and(|rest| {
+                                !rest.is_empty() && !rest.trim_end_matches('/').contains('/')
+                            })
+                        };
+                        let hrefs: String = std::iter::once(&path)
+                            .chain(share.dirs.iter().filter(child))
+                            .chain(share.files.keys().filter(child))
+                            .map(|href| {
+                                format!("<d:response><d:href>{}</d:href></d:response>", href)
+                            })
+                            .collect();
+                        (
+                            StatusCode::MULTI_STATUS,
+                            format!("<d:multistatus xmlns:d=\"DAV:\">{}</d:multistatus>", hrefs),
+                        )
+                            .into_response()
+                    }
+                    _ => StatusCode::NOT_FOUND.into_response(),
+                }
+            }
+        };
+        crate::storage::tests::serve(axum::Router::new().fallback(handler)).await
+    }
+
+    #[tokio::test]
+    async fn test_webdav_storage() {
+        std::env::set_var("DAILY_TEST_WEBDAV_PASSWORD", "secret");
+        let config = WebDavConfig {
+            url: Some(format!("{}/dav/", fake_share().await)),
+            username: Some("alice".into()),
+            password_env: "DAILY_TEST_WEBDAV_PASSWORD".into(),
+        };
+        let storage = WebDavStorage::from_config(&config).unwrap();
+
+        // Parent collections are created on the first conflict
+        storage
+            .write("2024-01-15/daily.md", b"digest".to_vec())
+            .await
+            .unwrap();
+        storage
+            .write("2024-01-15/fix bug.md", b"session".to_vec())
+            .await
+            .unwrap();
+        storage
+            .write("topics/index.md", b"topics".to_vec())
+            .await
+            .unwrap();
+        storage.write(".hidden", b"local".to_vec()).await.unwrap();
+        assert_eq!(
+            storage
+                .read("2024-01-15/fix bug.md")
+                .await
+                .unwrap()
+                .unwrap(),
+            b"session"
+        );
+        assert!(storage.read("2024-01-16/daily.md").await.unwrap().is_none());
+
+        let mut keys = storage.list("").await.unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "2024-01-15/daily.md",
+                "2024-01-15/fix bug.md",
+                "topics/index.md"
+            ]
+        );
+        assert_eq!(
+            storage.list("topics/").await.unwrap(),
+            vec!["topics/index.md"]
+        );
+
+        storage.delete("2024-01-15/fix bug.md").await.unwrap();
+        storage.delete("2024-01-15/fix bug.md").await.unwrap();
+        assert!(storage
+            .read("2024-01-15/fix bug.md")
+            .await
+            .unwrap()
+            .is_none());
+
+        let anonymous = WebDavStorage::from_config(&WebDavConfig {
+            username: None,
+            ..config
+        })
+        .unwrap();
+        let err = anonymous.write("a.md", b"a".to_vec()).await.unwrap_err();
+        assert!(err.to_string().contains("401"), "{}", err);
+    }
+}
@@ -0,0 +1,108 @@
+//! Prompt size budget.
+//!
+//! Token counts are estimated without a tokenizer: about four characters
+//! per token for ASCII text and one token per character otherwise (CJK
+//! text tokenizes close to that). Transcripts over budget keep their
+//! beginning and end, which carry the request and the outcome, and drop
+//! whole lines from the middle.
+
+/// Rough token count of `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text.chars().fold((0usize, 0usize), |(a, o), c| {
+        if c.is_ascii() {
+            (a + 1, o)
+        } else {
+            (a, o + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// A transcript cut down to a token budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fitted {
+    pub text: String,
+    /// Lines removed from the middle
+    pub dropped_lines: usize,
+    /// Estimated tokens removed
+    pub dropped_tokens: usize,
+}
+
+/// Fit `text` into `budget` estimated tokens by dropping lines from the
+/// middle, leaving a marker where they were
+pub fn fit(text: &str, budget: usize) -> Fitted {
+    let total = estimate_tokens(text);
+    if total <= budget {
+        return Fitted {
+            text: text.to_string(),
+            dropped_lines: 0,
+            dropped_tokens: 0,
+        };
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    let cost = |line: &str| estimate_tokens(line) + 1;
+    // Room for the omission marker
+    let budget = budget.saturating_sub(32);
+    let (head_budget, tail_budget) = (budget * 2 / 3, budget - budget * 2 / 3);
+
+    let mut head = 0;
+    let mut used = 0;
+    while head < lines.len() && used + cost(lines[head]) <= head_budget {
+        used += cost(lines[head]);
+        head += 1;
+    }
+    let mut tail = lines.len();
+    used = 0;
+    while tail > head && used + cost(lines[tail - 1]) <= tail_budget {
+        used += cost(lines[tail - 1]);
+        tail -= 1;
+    }
+
+    let dropped = &lines[head..tail];
+    let dropped_tokens = dropped.iter().map(|l| cost(l)).sum();
+    let mut fitted = lines[..head].join("\n");
+    fitted.push_str(&format!(
+        "\n\n[... {} lines (~{} tokens) omitted to fit the prompt budget ...]\n\n",
+        dropped.len(),
+        dropped_tokens
+    ));
+    fitted.push_str(&lines[tail..].join("\n"));
+
+    Fitted {
+        text: fitted,
+        dropped_lines: dropped.len(),
+        dropped_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("修复错误"), 4);
+    }
+
+    #[test]
+    fn test_fit_keeps_head_and_tail() {
+        let text: Vec<String> = (0..1000)
+            .map(|i| format!("line {} of the transcript", i))
+            .collect();
+        let text = text.join("\n");
+
+        let fitted = fit(&text, 500);
+        assert!(estimate_tokens(&fitted.text) <= 500);
+        assert!(fitted.text.starts_with("line 0 "));
+        assert!(fitted.text.ends_with("line 999 of the transcript"));
+        assert!(fitted.text.contains("omitted to fit the prompt budget"));
+        assert!(fitted.dropped_lines > 900);
+
+        let unchanged = fit("short", 500);
+        assert_eq!(unchanged.text, "short");
+        assert_eq!(unchanged.dropped_lines, 0);
+    }
+}
@@ -1,12 +1,31 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::io::Write;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, IsTerminal, Read, Write};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use super::prompts::Prompts;
-use crate::archive::{ArchiveManager, DailySummary, SessionArchive, SummaryCard};
+use super::json;
+use super::prompts::{DailySummaryPromptInput, Prompts};
+use super::quality::{self, QualityReport};
+use super::stream::StreamLog;
+use super::triviality;
+use crate::archive::citations::{self, CitedSession};
+use crate::archive::layout::{self, sanitize_topic, SessionNameVars};
+use crate::archive::sources::SourceMap;
+use crate::archive::{frontmatter, notes, permalink};
+use crate::archive::{ArchiveManager, DailySummary, ProjectSection, SessionArchive, SummaryCard};
 use crate::config::Config;
-use crate::transcript::TranscriptParser;
+use crate::decisions::project_name;
+use crate::goals::{GoalReport, GoalStore};
+use crate::jobs::kill_tree;
+use crate::skills::{self, SkillPackage};
+use crate::todos::{self, TodoStore};
+use crate::topics::TopicAssignments;
+use crate::transcript::{TranscriptData, TranscriptParser};
 
 /// Response structure from session summarization
 #[derive(Debug, Deserialize)]
@@ -16,6 +35,10 @@ struct SessionSummaryResponse {
     decisions: String,
     learnings: String,
     skill_hints: String,
+    /// User request numbers behind each item, by section; parsed leniently
+    /// so a malformed map doesn't fail the summary
+    #[serde(skip)]
+    sources: BTreeMap<String, Vec<Vec<usize>>>,
 }
 
 /// Response structure from daily summarization
@@ -28,180 +51,548 @@ struct DailySummaryResponse {
     commands: Vec<SummaryCard>,
     reflections: String,
     tomorrow_focus: Vec<SummaryCard>,
+    #[serde(default)]
+    goal_progress: Vec<GoalReport>,
+    /// Session numbers behind each card, with `digest_citations` on
+    #[serde(default)]
+    citations: BTreeMap<String, Vec<Vec<usize>>>,
+    /// One card per project, titled with its name, when grouping by project
+    #[serde(default)]
+    projects: Vec<SummaryCard>,
 }
 
+/// Result envelope printed by `claude --print --output-format json`
+#[derive(Debug, Deserialize)]
+struct CliResult {
+    #[serde(default)]
+    result: String,
+    #[serde(default)]
+    is_error: bool,
+    #[serde(default)]
+    subtype: Option<String>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    total_cost_usd: Option<f64>,
+    #[serde(default)]
+    usage: Option<CliUsage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CliUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+/// Token usage reported by the Claude CLI, summed over a job's model calls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Model the calls were made with, as configured (e.g. "haiku")
+    #[serde(default)]
+    pub model: String,
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    /// Cost reported by the CLI, when it reports one
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// Stop reason of the last call, e.g. "end_turn" or "max_tokens"
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+}
+
+impl ModelUsage {
+    fn add(&mut self, result: &CliResult) {
+        self.calls += 1;
+        if let Some(usage) = &result.usage {
+            self.input_tokens += usage.input_tokens;
+            self.output_tokens += usage.output_tokens;
+            self.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+            self.cache_read_input_tokens += usage.cache_read_input_tokens;
+        }
+        if let Some(cost) = result.total_cost_usd {
+            *self.cost_usd.get_or_insert(0.0) += cost;
+        }
+        self.stop_reason = result.stop_reason.clone();
+    }
+
+    /// Input tokens including cache writes and reads
+    pub fn total_input_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
+}
+
+/// A Claude CLI call that ran past `summarization.call_timeout_secs` and
+/// was killed
+#[derive(Debug)]
+pub struct CallTimeout {
+    pub secs: u64,
+}
+
+impl std::fmt::Display for CallTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Claude CLI call timed out after {}s (summarization.call_timeout_secs)",
+            self.secs
+        )
+    }
+}
+
+impl std::error::Error for CallTimeout {}
+
+/// Error messages of the Claude CLI (and the Node network errors it passes
+/// on) when there is no network or no valid login. These are matched as
+/// written, so output that merely mentions a `/login` route or a connection
+/// isn't mistaken for one.
+const UNREACHABLE_MARKERS: &[&str] = &[
+    "api error: connection error",
+    "api error (connection error",
+    "unable to connect to api",
+    "typeerror: fetch failed",
+    "getaddrinfo enotfound",
+    "getaddrinfo eai_again",
+    "connect econnrefused",
+    "connect etimedout",
+    "connect enetunreach",
+    "read econnreset",
+    "invalid api key · please run /login",
+    "not logged in · please run /login",
+    "oauth token has expired",
+    "\"type\":\"authentication_error\"",
+];
+
+/// A Claude CLI call that failed because the API couldn't be reached or
+/// the CLI isn't logged in; the request is worth retrying later
+#[derive(Debug)]
+pub struct Unreachable {
+    pub message: String,
+}
+
+impl Unreachable {
+    /// `message` as an [`Unreachable`] error if it reads like a network or
+    /// login failure
+    pub fn detect(message: &str) -> Option<Self> {
+        let lower = message.to_lowercase();
+        UNREACHABLE_MARKERS
+            .iter()
+            .any(|marker| lower.contains(marker))
+            .then(|| Self {
+                message: message.trim().to_string(),
+            })
+    }
+}
+
+impl std::fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Claude is unreachable: {}", self.message)
+    }
+}
+
+impl std::error::Error for Unreachable {}
+
 /// Engine for summarizing transcripts using Claude CLI
 pub struct SummarizerEngine {
     config: Config,
+    usage: Mutex<ModelUsage>,
+    quality: Mutex<Option<QualityReport>>,
 }
 
 impl SummarizerEngine {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            usage: Mutex::new(ModelUsage::default()),
+            quality: Mutex::new(None),
+        }
+    }
+
+    /// Usage of all model calls made by this engine so far
+    pub fn usage(&self) -> ModelUsage {
+        self.usage.lock().unwrap().clone()
+    }
+
+    /// Quality of the last summary or digest this engine generated
+    pub fn quality(&self) -> Option<QualityReport> {
+        self.quality.lock().unwrap().clone()
+    }
+
+    /// Score a parsed result with `score`; when it is low and retries are
+    /// enabled, ask once more with the issues listed and keep the better
+    /// answer. The final score is logged and kept for [`Self::quality`].
+    fn check_quality<T>(
+        &self,
+        what: &str,
+        prompt: &str,
+        first: T,
+        parse: impl Fn(&str) -> Result<T>,
+        score: impl Fn(&T) -> QualityReport,
+    ) -> Result<T> {
+        let mut best = first;
+        let mut report = score(&best);
+        if report.low && self.config.summarization.retry_low_quality {
+            eprintln!(
+                "[daily] {} scored {}/100 ({}), asking the model to improve it...",
+                what,
+                report.score,
+                report.issues.join("; ")
+            );
+            match self.invoke_for_json(&report.correction(prompt), &parse) {
+                Ok(retry) => {
+                    let retry_report = score(&retry);
+                    if retry_report.score > report.score {
+                        best = retry;
+                        report = retry_report;
+                    }
+                }
+                Err(e) => eprintln!("[daily] Quality retry failed: {:#}", e),
+            }
+        }
+
+        if report.low {
+            eprintln!(
+                "[daily] Warning: low-quality {} ({}/100): {}",
+                what.to_lowercase(),
+                report.score,
+                report.issues.join("; ")
+            );
+        } else {
+            eprintln!("[daily] {} quality: {}/100", what, report.score);
+        }
+        *self.quality.lock().unwrap() = Some(report);
+        Ok(best)
     }
 
-    /// Invoke Claude CLI with a prompt and return the response
+    /// Invoke Claude CLI with a prompt and return the response. The
+    /// response streams into the job log as it is written (see [`StreamLog`]).
     fn invoke_claude(&self, prompt: &str) -> Result<String> {
-        let mut child = Command::new("claude")
-            .args([
-                "--model",
-                &self.config.summarization.model,
-                "--print", // Print response and exit
-                "-p",      // Prompt mode
-                // Disable hooks to prevent infinite loop (daily hooks -> claude -> daily hooks -> ...)
-                "--settings",
-                r#"{"hooks":{}}"#,
-                // Disable session persistence to avoid generating transcripts for internal calls
-                "--no-session-persistence",
-                // Disable MCP to avoid file watcher errors in non-interactive mode
-                "--strict-mcp-config",
-            ])
+        self.invoke_model(&self.config.summarization.model, prompt)
+    }
+
+    /// [`Self::invoke_claude`] with another model than `summarization.model`
+    fn invoke_model(&self, model: &str, prompt: &str) -> Result<String> {
+        let mut command = Command::new("claude");
+        command.args([
+            "--model",
+            model,
+            "--print", // Print response and exit
+            "-p",      // Prompt mode
+            // One JSON event per line, ending with the result envelope
+            // (usage and stop reason); stream-json needs --verbose
+            "--output-format",
+            "stream-json",
+            "--verbose",
+            "--include-partial-messages",
+            // Disable hooks to prevent infinite loop (daily hooks -> claude -> daily hooks -> ...)
+            "--settings",
+            r#"{"hooks":{}}"#,
+            // Disable session persistence to avoid generating transcripts for internal calls
+            "--no-session-persistence",
+            // Disable MCP to avoid file watcher errors in non-interactive mode
+            "--strict-mcp-config",
+        ]);
+        let stdout = self.run_cli(command, prompt)?;
+        self.read_result(&stdout, model)
+    }
+
+    /// Run a Claude CLI `command` with `prompt` on stdin and return its
+    /// stdout, killing it after `summarization.call_timeout_secs`
+    fn run_cli(&self, mut command: Command, prompt: &str) -> Result<String> {
+        let mut child = command
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
             .context("Failed to spawn claude CLI. Is it installed?")?;
 
+        // Kill the call and whatever it started once it runs too long.
+        // Dropping `done` when the call ends stops the watchdog.
+        let timeout = self.config.summarization.call_timeout_secs;
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let (done, finished) = mpsc::channel::<()>();
+        let watchdog = (timeout > 0).then(|| {
+            let pid = child.id();
+            let timed_out = Arc::clone(&timed_out);
+            std::thread::spawn(move || {
+                if finished.recv_timeout(Duration::from_secs(timeout))
+                    == Err(RecvTimeoutError::Timeout)
+                {
+                    timed_out.store(true, Ordering::SeqCst);
+                    kill_tree(pid);
+                }
+            })
+        });
+
         // Write prompt to stdin
         if let Some(mut stdin) = child.stdin.take() {
-            stdin
-                .write_all(prompt.as_bytes())
-                .context("Failed to write prompt to claude")?;
+            if let Err(e) = stdin.write_all(prompt.as_bytes()) {
+                // Killing the call mid-write breaks the pipe; that is
+                // reported as the timeout below
+                if !timed_out.load(Ordering::SeqCst) {
+                    return Err(e).context("Failed to write prompt to claude");
+                }
+            }
         }
 
-        let output = child
-            .wait_with_output()
-            .context("Failed to wait for claude")?;
+        // Drain stderr alongside stdout so neither pipe fills up
+        let stderr = child.stderr.take().map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        });
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Claude CLI failed: {}", stderr);
+        // Echo the response text only into job logs; a terminal gets the
+        // progress markers alone
+        let mut log = StreamLog::new(std::io::stderr(), !std::io::stderr().is_terminal());
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                log.event(&line.context("Failed to read from claude")?);
+            }
         }
+        let stdout = log.finish();
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    }
-
-    /// Extract JSON from Claude's response (handles markdown code blocks)
-    fn extract_json(&self, response: &str) -> Result<String> {
-        // Try to find JSON in code block first
-        if let Some(start) = response.find("```json") {
-            if let Some(end) = response[start..]
-                .find("```\n")
-                .or(response[start..].rfind("```"))
-            {
-                let json_start = start + 7; // Skip ```json
-                let json_end = start + end;
-                if json_end > json_start {
-                    return Ok(response[json_start..json_end].trim().to_string());
-                }
+        let status = child.wait().context("Failed to wait for claude")?;
+        drop(done);
+        if let Some(watchdog) = watchdog {
+            let _ = watchdog.join();
+        }
+        if timed_out.load(Ordering::SeqCst) {
+            eprintln!(
+                "[daily] Claude CLI call killed after {}s without finishing",
+                timeout
+            );
+            return Err(CallTimeout { secs: timeout }.into());
+        }
+        if !status.success() {
+            let stderr = stderr
+                .and_then(|handle| handle.join().ok())
+                .unwrap_or_default();
+            if let Some(unreachable) = Unreachable::detect(&stderr) {
+                return Err(unreachable.into());
             }
+            anyhow::bail!("Claude CLI failed: {}", stderr);
         }
+        Ok(stdout)
+    }
 
-        // Try to find raw JSON object
-        if let Some(start) = response.find('{') {
-            if let Some(end) = response.rfind('}') {
-                if end > start {
-                    return Ok(response[start..=end].to_string());
-                }
+    /// Response text from the CLI's result envelope, recording its usage.
+    /// Output that isn't an envelope (older CLI versions) is used as is.
+    fn read_result(&self, stdout: &str, model: &str) -> Result<String> {
+        let Ok(result) = serde_json::from_str::<CliResult>(stdout.trim()) else {
+            return Ok(stdout.to_string());
+        };
+        {
+            let mut usage = self.usage.lock().unwrap();
+            usage.model = model.to_string();
+            usage.add(&result);
+        }
+
+        if result.is_error {
+            if let Some(unreachable) = Unreachable::detect(&result.result) {
+                return Err(unreachable.into());
             }
+            anyhow::bail!(
+                "Claude CLI returned an error ({}): {}",
+                result.subtype.as_deref().unwrap_or("unknown"),
+                result.result
+            );
+        }
+        if result.stop_reason.as_deref() == Some("max_tokens") {
+            eprintln!("[daily] Warning: response hit the output token limit and may be truncated");
         }
+        Ok(result.result)
+    }
+
+    /// Invoke Claude and parse its JSON answer with `parse`. If that fails,
+    /// the raw response goes to the job log and Claude is asked once to
+    /// correct its JSON.
+    fn invoke_for_json<T>(&self, prompt: &str, parse: impl Fn(&str) -> Result<T>) -> Result<T> {
+        let response = self.invoke_claude(prompt)?;
+        let error = match parse(&response) {
+            Ok(parsed) => return Ok(parsed),
+            Err(e) => e,
+        };
+        eprintln!("[daily] Failed to parse JSON response: {:#}", error);
+        eprintln!("[daily] Raw response:\n{}", response);
+        eprintln!("[daily] Asking the model to fix its JSON...");
 
-        // Return as-is if no JSON found
-        Ok(response.to_string())
+        let retry = self.invoke_claude(&Prompts::fix_json(&response, &format!("{:#}", error)))?;
+        parse(&retry).inspect_err(|_| {
+            eprintln!("[daily] Raw response after retry:\n{}", retry);
+        })
     }
 
-    /// Summarize a session transcript and create archive
+    /// Summarize a session transcript and create an archive dated at `archived_at`
     pub async fn summarize_session(
         &self,
         transcript_path: &std::path::Path,
         _task_name: &str,
         cwd: &str,
+        archived_at: chrono::NaiveDateTime,
     ) -> Result<SessionArchive> {
-        // Parse transcript
         let transcript_data = TranscriptParser::parse(transcript_path)?;
-        let transcript_text = TranscriptParser::to_condensed_text(&transcript_data);
+        let session_id = transcript_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        self.summarize_transcript(
+            &transcript_data,
+            &session_id,
+            transcript_path,
+            cwd,
+            archived_at,
+        )
+        .await
+    }
+
+    /// Summarize already-parsed session data, e.g. a conversation imported
+    /// from another tool, into an archive dated at `archived_at`
+    pub async fn summarize_transcript(
+        &self,
+        transcript_data: &TranscriptData,
+        session_id: &str,
+        transcript_path: &std::path::Path,
+        cwd: &str,
+        archived_at: chrono::NaiveDateTime,
+    ) -> Result<SessionArchive> {
+        let transcript_text = TranscriptParser::to_condensed_text(transcript_data);
 
         // Get git branch
         let git_branch = crate::archive::session::get_git_branch(cwd);
+        let project = std::path::Path::new(cwd)
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
 
-        // Build prompt and invoke Claude (using custom template if configured)
-        let language = &self.config.summarization.summary_language;
-        let custom_template = self.config.prompt_templates.session_summary.as_deref();
-        let prompt = Prompts::session_summary_with_template(
-            custom_template,
-            &transcript_text,
-            cwd,
-            git_branch.as_deref(),
-            language,
-        );
-
-        let response = self.invoke_claude(&prompt)?;
-        let json_str = self.extract_json(&response)?;
-
-        // Parse response - use Value first for debugging on failure
-        let summary_response: SessionSummaryResponse = match serde_json::from_str(&json_str) {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[daily] Failed to parse JSON: {}", e);
-                eprintln!("[daily] Raw JSON: {}", json_str);
-                // Try flexible parsing: convert array fields to strings
-                let mut v: serde_json::Value =
-                    serde_json::from_str(&json_str).context("Failed to parse as JSON Value")?;
-                if let Some(obj) = v.as_object_mut() {
-                    for key in ["decisions", "learnings", "skill_hints", "summary", "topic"] {
-                        if let Some(val) = obj.get(key) {
-                            if val.is_array() {
-                                if let Some(arr) = val.as_array() {
-                                    let joined = arr
-                                        .iter()
-                                        .map(|item| {
-                                            item.as_str()
-                                                .map(|s| format!("- {}", s))
-                                                .unwrap_or_else(|| format!("- {}", item))
-                                        })
-                                        .collect::<Vec<_>>()
-                                        .join("\n");
-                                    obj.insert(key.to_string(), serde_json::Value::String(joined));
-                                }
-                            }
-                        }
-                    }
-                }
-                serde_json::from_value(v).context("Failed to parse summary response after fix")?
+        // Small sessions get a stub summary instead of a full model call
+        let triviality = &self.config.summarization.triviality;
+        let trivial = triviality::within_thresholds(transcript_data, &transcript_text, triviality)
+            && self.confirm_trivial(&transcript_text);
+        let summary_response = if trivial {
+            eprintln!("[daily] Trivial session, archiving with a stub summary");
+            SessionSummaryResponse {
+                topic: triviality::stub_topic(transcript_data),
+                summary: triviality::stub_summary(
+                    &triviality.summary_template,
+                    transcript_data,
+                    &project,
+                ),
+                decisions: String::new(),
+                learnings: String::new(),
+                skill_hints: String::new(),
+                sources: BTreeMap::new(),
             }
+        } else {
+            // Build prompt and invoke Claude (using custom template if configured)
+            let language = &self.config.summarization.summary_language;
+            let custom_template = self.config.prompt_templates.session_summary.as_deref();
+            let prompt = Prompts::session_summary_with_template(
+                custom_template,
+                &transcript_text,
+                cwd,
+                git_branch.as_deref(),
+                language,
+                self.config.summarization.max_prompt_tokens,
+            );
+
+            let summary_response: SessionSummaryResponse =
+                self.invoke_for_json(&prompt, parse_session_summary)?;
+            let min_score = self.config.summarization.min_quality_score;
+            self.check_quality(
+                "Summary",
+                &prompt,
+                summary_response,
+                parse_session_summary,
+                |r| {
+                    quality::score_session(
+                        &r.topic,
+                        &r.summary,
+                        &r.decisions,
+                        &r.learnings,
+                        min_score,
+                    )
+                },
+            )?
         };
 
-        // Build title from time + AI-generated topic
-        // Format: HH_MM-topic (e.g., "14_55-fix-auth-bug")
-        let now = chrono::Local::now();
-        let time_prefix = now.format("%H_%M").to_string();
+        // Build title from archive.session_name_template
+        // Default format: HH_MM-topic (e.g., "14_55-fix-auth-bug")
         let topic = sanitize_topic(&summary_response.topic);
-        let title = format!("{}-{}", time_prefix, topic);
+        let title = layout::session_name(
+            &self.config.archive.session_name_template,
+            &SessionNameVars {
+                archived_at,
+                topic: &topic,
+                project: &project,
+                session_id,
+            },
+        );
 
         // Build archive
-        let today = now.format("%Y-%m-%d").to_string();
-        let session_id = transcript_path
-            .file_stem()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+        let today = archived_at.format("%Y-%m-%d").to_string();
 
-        let archive = SessionArchive::new(title, today, session_id, cwd.to_string())
+        let archive = SessionArchive::new(title, today, session_id.to_string(), cwd.to_string())
             .with_transcript_path(transcript_path.to_string_lossy().to_string())
-            .with_transcript_data(&transcript_data)
+            .with_transcript_data(transcript_data)
             .with_summary(
                 summary_response.summary,
                 summary_response.decisions,
                 summary_response.learnings,
                 summary_response.skill_hints,
-            );
+            )
+            .with_sources(SourceMap::from_requests(
+                &summary_response.sources,
+                &transcript_data.user_message_lines,
+                transcript_data.line_count,
+            ));
 
         // Set git branch
         let mut archive = archive;
         archive.git_branch = git_branch;
+        archive.trivial = trivial;
 
         Ok(archive)
     }
 
+    /// With `summarization.triviality.model_check`, ask the cheap check
+    /// model whether a session within the thresholds is really trivial.
+    /// Without it, or when the check fails, the thresholds decide.
+    fn confirm_trivial(&self, transcript_text: &str) -> bool {
+        let triviality = &self.config.summarization.triviality;
+        if !triviality.model_check {
+            return true;
+        }
+        let prompt = Prompts::triviality_check(transcript_text);
+        match self
+            .invoke_model(&triviality.check_model, &prompt)
+            .and_then(|response| triviality::parse_check(&response))
+        {
+            Ok((trivial, reason)) => {
+                eprintln!(
+                    "[daily] {} says the session is {}: {}",
+                    triviality.check_model,
+                    if trivial { "trivial" } else { "substantive" },
+                    reason
+                );
+                trivial
+            }
+            Err(e) => {
+                eprintln!("[daily] Triviality check failed, using thresholds: {:#}", e);
+                true
+            }
+        }
+    }
+
     /// Update daily summary with all sessions
     pub async fn update_daily_summary(&self, date: &str) -> Result<DailySummary> {
         let manager = ArchiveManager::new(self.config.clone());
@@ -219,41 +610,155 @@ impl SummarizerEngine {
 
         // Collect session summaries, filtering out trivial sessions (1-2 turns)
         let mut session_data = Vec::new();
-        for session_name in &sessions {
+        let mut session_summaries = Vec::new();
+        // Sessions by the number they are given in the prompt
+        let mut cited_sessions = Vec::new();
+        let cite = self.config.summarization.digest_citations;
+        let by_project = self.config.summarization.digest_group_by == "project";
+        let mut projects: Vec<ProjectSection> = Vec::new();
+        for (i, session_name) in sessions.iter().enumerate() {
+            // Progress lines end up in the job log when run as a Digest job
+            eprintln!(
+                "[daily] Digest progress: {}/{} sessions ({})",
+                i + 1,
+                sessions.len(),
+                session_name
+            );
             if let Ok(content) = manager.read_session(date, session_name) {
                 // Extract summary from markdown (simplified extraction)
                 let summary = extract_summary_from_markdown(&content);
-                // Skip trivial sessions: very short summaries indicate 1-2 turn or empty sessions
-                if summary.len() < 80 {
+                session_summaries.push((session_name.clone(), summary.clone()));
+                // Skip trivial sessions: stubs from the triviality pre-pass, and very
+                // short summaries that indicate 1-2 turn or empty sessions
+                if summary.len() < 80 || frontmatter::get(&content, "trivial") == Some("true") {
                     continue;
                 }
-                session_data.push(serde_json::json!({
-                    "content": summary
-                }));
+                let mut entry = serde_json::json!({ "content": summary });
+                if cite {
+                    cited_sessions.push(CitedSession {
+                        id: frontmatter::get(&content, permalink::ID_KEY)
+                            .unwrap_or(session_name)
+                            .to_string(),
+                        name: session_name.clone(),
+                    });
+                    entry["session"] = cited_sessions.len().into();
+                }
+                if let Some(cwd) = frontmatter::get(&content, "cwd").filter(|_| by_project) {
+                    let project = project_name(cwd);
+                    entry["project"] = project.into();
+                    match projects.iter_mut().find(|p| p.project == project) {
+                        Some(section) => section.sessions.push(session_name.clone()),
+                        None => projects.push(ProjectSection {
+                            project: project.to_string(),
+                            cwd: cwd.to_string(),
+                            sessions: vec![session_name.clone()],
+                            content: String::new(),
+                        }),
+                    }
+                }
+                session_data.push(entry);
             }
         }
 
         let sessions_json = serde_json::to_string_pretty(&session_data)?;
+        eprintln!(
+            "[daily] Generating digest from {} sessions...",
+            session_data.len()
+        );
 
         // Build prompt and invoke Claude (with existing summary if present, using custom template if configured)
         let language = &self.config.summarization.summary_language;
         let custom_template = self.config.prompt_templates.daily_summary.as_deref();
-        let prompt = Prompts::daily_summary_with_template(
+        let goals = GoalStore::load(&self.config).unwrap_or_else(|e| {
+            eprintln!("[daily] Warning: Failed to load goals: {:#}", e);
+            GoalStore::default()
+        });
+        let active: Vec<(u32, &str)> = goals.active().map(|g| (g.id, g.title.as_str())).collect();
+        let todo_store = TodoStore::load(&self.config).unwrap_or_else(|e| {
+            eprintln!("[daily] Warning: Failed to load TODOs: {:#}", e);
+            TodoStore::default()
+        });
+        let todo_checks = todo_store.check(date, &session_summaries);
+        let open_todos: Vec<&str> = todo_store
+            .pending(date)
+            .filter(|t| !todo_checks.iter().any(|c| c.todo == t.id))
+            .map(|t| t.text.as_str())
+            .collect();
+        let day_notes = existing_summary
+            .as_deref()
+            .map(notes::list)
+            .unwrap_or_default();
+        let notes: Vec<(&str, &str)> = day_notes
+            .iter()
+            .map(|n| (n.time.as_str(), n.text.as_str()))
+            .collect();
+        let project_names: Vec<&str> = projects.iter().map(|p| p.project.as_str()).collect();
+        let goals_section = Prompts::goals_section(&active, language);
+        let todos_section = Prompts::todos_section(&open_todos, language);
+        let notes_section = Prompts::notes_section(&notes, language);
+        let citations_section = if cite {
+            Prompts::citations_section(language)
+        } else {
+            String::new()
+        };
+        let projects_section = Prompts::projects_section(&project_names, language);
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
             custom_template,
-            &sessions_json,
+            sessions_json: &sessions_json,
             date,
-            existing_summary.as_deref(),
+            existing_summary: existing_summary.as_deref(),
+            goals_section: &goals_section,
+            todos_section: &todos_section,
+            notes_section: &notes_section,
+            citations_section: &citations_section,
+            projects_section: &projects_section,
+            language,
+        });
+        let parse = |response: &str| -> Result<DailySummaryResponse> {
+            json::parse(response).context("Failed to parse daily summary response")
+        };
+        let daily_response = self.invoke_for_json(&prompt, parse)?;
+        let min_score = self.config.summarization.min_quality_score;
+        let mut daily_response =
+            self.check_quality("Digest", &prompt, daily_response, parse, |r| {
+                let cards: Vec<&SummaryCard> = r
+                    .insights
+                    .iter()
+                    .chain(&r.skills)
+                    .chain(&r.commands)
+                    .chain(&r.tomorrow_focus)
+                    .collect();
+                quality::score_digest(
+                    &r.overview,
+                    &r.session_details,
+                    &r.reflections,
+                    &cards,
+                    &sessions,
+                    min_score,
+                )
+            })?;
+
+        todos::merge_focus(
+            &mut daily_response.tomorrow_focus,
+            &open_todos,
+            &todo_checks,
             language,
         );
-        let response = self.invoke_claude(&prompt)?;
-        let json_str = self.extract_json(&response)?;
 
-        // Parse response
-        let daily_response: DailySummaryResponse =
-            serde_json::from_str(&json_str).context("Failed to parse daily summary response")?;
+        let cited = citations::from_numbers(
+            &daily_response.citations,
+            &[
+                ("insights", &daily_response.insights),
+                ("tomorrow_focus", &daily_response.tomorrow_focus),
+                ("skills", &daily_response.skills),
+                ("commands", &daily_response.commands),
+            ],
+            &cited_sessions,
+        );
 
         // Build daily summary
         let mut summary = DailySummary::new(date.to_string());
+        summary.citations = cited;
         summary.sessions = sessions;
         summary = summary.with_content(
             daily_response.overview,
@@ -264,20 +769,127 @@ impl SummarizerEngine {
             daily_response.reflections,
             daily_response.tomorrow_focus,
         );
+        // Only progress on goals that were asked about counts
+        summary.goal_progress = daily_response
+            .goal_progress
+            .into_iter()
+            .filter_map(|mut report| {
+                let (_, title) = active.iter().find(|(id, _)| *id == report.goal)?;
+                report.title = title.to_string();
+                (!report.progress.trim().is_empty()).then_some(report)
+            })
+            .collect();
+        summary.completed_todos = todo_checks;
+        // Only projects that had sessions count, in the order the model wrote them
+        summary.projects = daily_response
+            .projects
+            .into_iter()
+            .filter_map(|card| {
+                let section = projects.iter().find(|p| p.project == card.title.trim())?;
+                (!card.content.trim().is_empty()).then(|| ProjectSection {
+                    content: card.content,
+                    ..section.clone()
+                })
+            })
+            .collect();
 
         Ok(summary)
     }
 
-    /// Extract skill from session
-    pub async fn extract_skill(&self, session_content: &str, hint: Option<&str>) -> Result<String> {
+    /// Extract skill from session, with any scripts/references/assets files
+    /// the model wrote for it
+    pub async fn extract_skill(
+        &self,
+        session_content: &str,
+        hint: Option<&str>,
+    ) -> Result<SkillPackage> {
         let language = &self.config.summarization.summary_language;
         let custom_template = self.config.prompt_templates.skill_extract.as_deref();
         let prompt =
             Prompts::extract_skill_with_template(custom_template, session_content, hint, language);
         let response = self.invoke_claude(&prompt)?;
 
-        // Extract markdown from response
-        extract_markdown_from_response(&response)
+        // Extract markdown from response, files follow the SKILL.md block
+        let (skill, files) = skills::split_package(&response);
+        Ok(SkillPackage {
+            skill: extract_markdown_from_response(skill)?,
+            files,
+        })
+    }
+
+    /// Free-text answer to a prompt built by `Prompts::ask`
+    pub async fn answer(&self, prompt: &str) -> Result<String> {
+        self.invoke_claude(prompt)
+    }
+
+    /// `content`, a saved digest, translated into each of `languages`. The
+    /// translations run in parallel.
+    pub fn translate_digest(
+        &self,
+        content: &str,
+        languages: &[String],
+    ) -> Vec<(String, Result<String>)> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = languages
+                .iter()
+                .map(|language| {
+                    let prompt = Prompts::translate_digest(content, language);
+                    let handle = scope.spawn(move || self.invoke_claude(&prompt));
+                    (language.clone(), handle)
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|(language, handle)| {
+                    let translated = handle
+                        .join()
+                        .unwrap_or_else(|_| Err(anyhow::anyhow!("Translation thread panicked")))
+                        .map(|response| strip_outer_fence(&response));
+                    (language, translated)
+                })
+                .collect()
+        })
+    }
+
+    /// A new body for section `heading` of `digest`, the date's digest, written
+    /// from the date's session summaries without regenerating the rest
+    pub async fn regenerate_digest_section(
+        &self,
+        date: &str,
+        digest: &str,
+        heading: &str,
+    ) -> Result<String> {
+        let manager = ArchiveManager::new(self.config.clone());
+        let sessions: String = manager
+            .list_sessions(date)?
+            .iter()
+            .filter_map(|name| {
+                let content = manager.read_session(date, name).ok()?;
+                Some(format!(
+                    "### {}\n{}\n\n",
+                    name,
+                    extract_summary_from_markdown(&content)
+                ))
+            })
+            .collect();
+        let language = &self.config.summarization.summary_language;
+        let prompt = Prompts::regenerate_digest_section(digest, &sessions, heading, language);
+        let response = self.invoke_claude(&prompt)?;
+        let body = section_reply_body(&response, heading);
+        if body.is_empty() {
+            anyhow::bail!("Claude returned an empty {} section", heading);
+        }
+        Ok(body)
+    }
+
+    /// File sessions under existing or new topics; both are JSON arrays as
+    /// built by `topics::update`
+    pub async fn assign_topics(&self, topics: &str, sessions: &str) -> Result<TopicAssignments> {
+        let language = &self.config.summarization.summary_language;
+        let prompt = Prompts::assign_topics(topics, sessions, language);
+        self.invoke_for_json(&prompt, |response| {
+            json::parse(response).context("Failed to parse topic assignments")
+        })
     }
 
     /// Extract command from session
@@ -301,46 +913,6 @@ impl SummarizerEngine {
     }
 }
 
-/// Sanitize topic for use in filename
-fn sanitize_topic(topic: &str) -> String {
-    // Convert to lowercase, replace spaces with hyphens, remove invalid chars
-    let sanitized: String = topic
-        .trim()
-        .to_lowercase()
-        .chars()
-        .map(|c| match c {
-            'a'..='z' | '0'..='9' | '-' => c,
-            ' ' | '_' => '-',
-            _ => '-',
-        })
-        .collect();
-
-    // Remove consecutive hyphens and trim
-    let mut result = String::new();
-    let mut prev_hyphen = false;
-    for c in sanitized.chars() {
-        if c == '-' {
-            if !prev_hyphen && !result.is_empty() {
-                result.push(c);
-            }
-            prev_hyphen = true;
-        } else {
-            result.push(c);
-            prev_hyphen = false;
-        }
-    }
-
-    // Trim trailing hyphen and limit length
-    let result = result.trim_end_matches('-').to_string();
-    if result.len() > 50 {
-        result[..50].trim_end_matches('-').to_string()
-    } else if result.is_empty() {
-        "session".to_string()
-    } else {
-        result
-    }
-}
-
 /// Extract summary section from session markdown
 fn extract_summary_from_markdown(content: &str) -> String {
     // Look for ## Summary section
@@ -357,7 +929,62 @@ fn extract_summary_from_markdown(content: &str) -> String {
     content.chars().take(500).collect()
 }
 
+/// Body of a regenerated digest section, without a code fence or the
+/// section's own heading should the reply include them
+fn section_reply_body(response: &str, heading: &str) -> String {
+    let body = strip_outer_fence(response);
+    let body = body.trim();
+    body.strip_prefix(&format!("## {}", heading))
+        .unwrap_or(body)
+        .trim()
+        .to_string()
+}
+
+/// Parse a session summary, accepting arrays where strings are expected
+fn parse_session_summary(response: &str) -> Result<SessionSummaryResponse> {
+    let mut value = json::parse_value(response)?;
+    let mut sources = BTreeMap::new();
+    if let Some(obj) = value.as_object_mut() {
+        if let Some(cited) = obj.remove("sources") {
+            sources = serde_json::from_value(cited).unwrap_or_default();
+        }
+        for key in ["decisions", "learnings", "skill_hints", "summary", "topic"] {
+            if let Some(arr) = obj.get(key).and_then(|v| v.as_array()) {
+                let joined = arr
+                    .iter()
+                    .map(|item| {
+                        item.as_str()
+                            .map(|s| format!("- {}", s))
+                            .unwrap_or_else(|| format!("- {}", item))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                obj.insert(key.to_string(), serde_json::Value::String(joined));
+            }
+        }
+    }
+    let mut parsed: SessionSummaryResponse =
+        serde_json::from_value(value).context("Failed to parse summary response")?;
+    parsed.sources = sources;
+    Ok(parsed)
+}
+
 /// Extract markdown content from Claude response
+/// A response with any code fence wrapping the whole of it removed; fences
+/// inside are kept
+fn strip_outer_fence(response: &str) -> String {
+    let trimmed = response.trim();
+    if let Some(inner) = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    {
+        if let Some((_, body)) = inner.split_once('\n') {
+            return body.trim().to_string();
+        }
+    }
+    trimmed.to_string()
+}
+
 fn extract_markdown_from_response(response: &str) -> Result<String> {
     // Try to find markdown in code block
     if let Some(start) = response.find("```markdown") {
@@ -402,6 +1029,133 @@ More content.
         assert!(summary.contains("This is the summary"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_slow_cli_call_times_out_and_fails_the_job() {
+        use crate::jobs::{JobManager, JobStatus, JobType};
+        use std::time::Instant;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        config.summarization.call_timeout_secs = 1;
+        let engine = SummarizerEngine::new(config.clone());
+
+        // A CLI that hangs, with a child holding its stdout open
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & sleep 30"]);
+        let started = Instant::now();
+        let error = engine.run_cli(command, "prompt").unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(15));
+        assert!(error.is::<CallTimeout>());
+
+        let jobs = JobManager::new(&config).unwrap();
+        jobs.register(
+            "slow",
+            std::process::id(),
+            "slow",
+            std::path::Path::new("/tmp/t.jsonl"),
+            JobType::Manual,
+        )
+        .unwrap();
+        jobs.mark_error("slow", &error.context("Summarization failed"))
+            .unwrap();
+        let info = jobs.load_job("slow").unwrap();
+        assert!(info.timed_out);
+        assert!(matches!(info.status, JobStatus::Failed { .. }));
+    }
+
+    #[test]
+    fn test_call_killed_while_writing_the_prompt_times_out() {
+        let mut config = Config::default();
+        config.summarization.call_timeout_secs = 1;
+        let engine = SummarizerEngine::new(config);
+
+        // A CLI that never reads a prompt larger than the pipe buffer
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30"]);
+        let prompt = "x".repeat(4 * 1024 * 1024);
+        let error = engine.run_cli(command, &prompt).unwrap_err();
+        assert!(error.is::<CallTimeout>(), "{:#}", error);
+    }
+
+    #[test]
+    fn test_read_result_envelope() {
+        let engine = SummarizerEngine::new(Config::default());
+        let stdout = r#"{"type":"result","subtype":"success","is_error":false,"result":"{\"topic\": \"x\"}","stop_reason":"end_turn","total_cost_usd":0.0125,"usage":{"input_tokens":1200,"cache_read_input_tokens":300,"output_tokens":150}}"#;
+        assert_eq!(
+            engine.read_result(stdout, "sonnet").unwrap(),
+            "{\"topic\": \"x\"}"
+        );
+        engine.read_result(stdout, "sonnet").unwrap();
+
+        let usage = engine.usage();
+        assert_eq!(usage.calls, 2);
+        assert_eq!(usage.total_input_tokens(), 3000);
+        assert_eq!(usage.output_tokens, 300);
+        assert_eq!(usage.cost_usd, Some(0.025));
+        assert_eq!(usage.stop_reason.as_deref(), Some("end_turn"));
+
+        // Plain text from older CLI versions passes through
+        assert_eq!(
+            engine.read_result("plain text", "sonnet").unwrap(),
+            "plain text"
+        );
+
+        let error =
+            r#"{"type":"result","subtype":"error_max_turns","is_error":true,"result":"limit"}"#;
+        assert!(engine.read_result(error, "sonnet").is_err());
+
+        // Network and login failures are told apart so the request can be queued
+        let offline = r#"{"type":"result","subtype":"success","is_error":true,"result":"API Error: Connection error."}"#;
+        let e = engine.read_result(offline, "sonnet").unwrap_err();
+        assert!(e.is::<Unreachable>());
+        assert!(!engine
+            .read_result(error, "sonnet")
+            .unwrap_err()
+            .is::<Unreachable>());
+        assert!(Unreachable::detect("Invalid API key · Please run /login").is_some());
+        assert!(Unreachable::detect(
+            r#"API Error: 401 {"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#
+        )
+        .is_some());
+        assert!(Unreachable::detect("Error: getaddrinfo ENOTFOUND api.anthropic.com").is_some());
+    }
+
+    #[test]
+    fn test_unreachable_ignores_mentions() {
+        // Output that only mentions logins or connections is the prompt's doing
+        for message in [
+            "Claude CLI returned an error: the session added a /login route",
+            "Not logged in users are redirected to /login",
+            "Handled connection error and fetch failed cases in the API client",
+            "Invalid API key format in config.rs was rejected by the validator",
+        ] {
+            assert!(Unreachable::detect(message).is_none(), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_parse_session_summary() {
+        let response = "Here is the summary:\n```json\n{\"topic\": \"fix-bug\", \"summary\": \"Fixed it\", \"decisions\": [\"Use a lock\", \"Retry once\"], \"learnings\": \"\", \"skill_hints\": \"\",}\n```";
+        let parsed = parse_session_summary(response).unwrap();
+        assert_eq!(parsed.topic, "fix-bug");
+        assert_eq!(parsed.decisions, "- Use a lock\n- Retry once");
+        assert!(parsed.sources.is_empty());
+
+        let cited = "{\"topic\": \"x\", \"summary\": \"s\", \"decisions\": \"- d\", \"learnings\": \"\", \"skill_hints\": \"\", \"sources\": {\"decisions\": [[2, 3]]}}";
+        let parsed = parse_session_summary(cited).unwrap();
+        assert_eq!(parsed.sources["decisions"], vec![vec![2, 3]]);
+        // A malformed source map is ignored rather than failing the summary
+        let malformed = cited.replace("[[2, 3]]", "\"request 2\"");
+        assert!(parse_session_summary(&malformed)
+            .unwrap()
+            .sources
+            .is_empty());
+
+        assert!(parse_session_summary("I could not summarize this session.").is_err());
+    }
+
     #[test]
     fn test_extract_markdown_from_response() {
         let response = r#"Here is the skill:
@@ -417,4 +1171,29 @@ Done!"#;
         let md = extract_markdown_from_response(response).unwrap();
         assert!(md.contains("name: test-skill"));
     }
+
+    #[test]
+    fn test_section_reply_body() {
+        assert_eq!(
+            section_reply_body(
+                "```markdown\n## Reflections\n\nSlow CI.\n```",
+                "Reflections"
+            ),
+            "Slow CI."
+        );
+        assert_eq!(
+            section_reply_body("\nSlow CI.\n", "Reflections"),
+            "Slow CI."
+        );
+    }
+
+    #[test]
+    fn test_strip_outer_fence() {
+        let digest = "# Daily\n\n```bash\ncargo test\n```\n\nDone.";
+        assert_eq!(strip_outer_fence(digest), digest);
+        assert_eq!(
+            strip_outer_fence(&format!("```markdown\n{}\n```\n", digest)),
+            digest
+        );
+    }
 }
@@ -0,0 +1,223 @@
+//! Tolerant JSON extraction from model responses.
+//!
+//! Models sometimes wrap the JSON in prose, put it in a fenced block, or
+//! emit JSON5-isms. `extract` finds the object (fenced ```json block first,
+//! then the first balanced `{...}`), and `repair` fixes the common
+//! mistakes: trailing commas, comments, unquoted keys, single-quoted
+//! strings and raw newlines inside strings.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Parse the JSON object in `response` into `T`, repairing it if needed
+pub fn parse<T: DeserializeOwned>(response: &str) -> Result<T> {
+    serde_json::from_value(parse_value(response)?).context("Unexpected JSON structure")
+}
+
+/// Parse the JSON object in `response`, repairing it if needed
+pub fn parse_value(response: &str) -> Result<Value> {
+    let json = extract(response).context("No JSON object found in response")?;
+    match serde_json::from_str(json) {
+        Ok(value) => Ok(value),
+        Err(original) => serde_json::from_str(&repair(json))
+            .with_context(|| format!("Invalid JSON even after repair (originally: {})", original)),
+    }
+}
+
+/// The JSON object text in `response`
+pub fn extract(response: &str) -> Option<&str> {
+    fenced_blocks(response)
+        .into_iter()
+        .filter_map(balanced_object)
+        .next()
+        .or_else(|| balanced_object(response))
+}
+
+/// Bodies of ``` fenced blocks, ```json ones first
+fn fenced_blocks(response: &str) -> Vec<&str> {
+    let mut tagged = Vec::new();
+    let mut other = Vec::new();
+    let mut rest = response;
+    while let Some(start) = rest.find("```") {
+        let after = &rest[start + 3..];
+        let Some(newline) = after.find('\n') else {
+            break;
+        };
+        let lang = after[..newline].trim();
+        let body = &after[newline + 1..];
+        let Some(end) = body.find("```") else {
+            // Unterminated block, e.g. the response was cut off
+            other.push(body);
+            break;
+        };
+        if lang.eq_ignore_ascii_case("json") || lang.eq_ignore_ascii_case("json5") {
+            tagged.push(&body[..end]);
+        } else {
+            other.push(&body[..end]);
+        }
+        rest = &body[end + 3..];
+    }
+    tagged.extend(other);
+    tagged
+}
+
+/// First `{...}` in `text` with balanced braces, ignoring braces in strings.
+/// Falls back to everything from the first `{` to the last `}`.
+fn balanced_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in text[start..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[start..=start + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    let end = text.rfind('}')?;
+    (end > start).then(|| &text[start..=end])
+}
+
+/// Rewrite JSON5-style text as strict JSON
+pub fn repair(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' | '\'' => {
+                i = copy_string(&chars, i, &mut out);
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            ',' => {
+                // Drop trailing commas before a closing bracket
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let is_key = chars[i..]
+                    .iter()
+                    .find(|c| !c.is_whitespace())
+                    .is_some_and(|c| *c == ':');
+                if is_key {
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                } else {
+                    out.push_str(&word);
+                }
+                continue;
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Copy the string literal starting at `start` as a double-quoted JSON
+/// string, returning the index after it
+fn copy_string(chars: &[char], start: usize, out: &mut String) -> usize {
+    let quote = chars[start];
+    out.push('"');
+    let mut i = start + 1;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                // `\'` is not a JSON escape
+                if chars[i + 1] == '\'' {
+                    out.push('\'');
+                } else {
+                    out.push(c);
+                    out.push(chars[i + 1]);
+                }
+                i += 2;
+                continue;
+            }
+            c if c == quote => {
+                out.push('"');
+                return i + 1;
+            }
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out.push('"');
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_from_prose_and_fences() {
+        let response = "Here you go:\n```json\n{\"a\": \"x } y\"}\n```\nHope this helps {really}.";
+        assert_eq!(extract(response), Some("{\"a\": \"x } y\"}"));
+
+        let response = "Sure! {\"a\": {\"b\": 1}} and then {\"c\": 2}";
+        assert_eq!(extract(response), Some("{\"a\": {\"b\": 1}}"));
+
+        assert_eq!(extract("no json here"), None);
+    }
+
+    #[test]
+    fn test_repair_json5() {
+        let broken = "{\n  // the topic\n  topic: 'fix-bug',\n  \"summary\": \"line one\nline two\",\n  \"tags\": [\"a\", \"b\",],\n}";
+        let value = parse_value(broken).unwrap();
+        assert_eq!(value["topic"], "fix-bug");
+        assert_eq!(value["summary"], "line one\nline two");
+        assert_eq!(value["tags"], serde_json::json!(["a", "b"]));
+
+        // Valid JSON is left alone, including commas and words inside strings
+        let valid = "{\"text\": \"a, ] b: c\", \"ok\": true, \"n\": null}";
+        assert_eq!(repair(valid), valid);
+    }
+}
@@ -1,6 +1,13 @@
+pub mod budget;
 mod engine;
+mod json;
 mod prompts;
+pub mod quality;
+mod stream;
 mod template;
+mod triviality;
 
-pub use engine::SummarizerEngine;
+pub use engine::{CallTimeout, ModelUsage, SummarizerEngine, Unreachable};
 pub use prompts::Prompts;
+pub use quality::QualityReport;
+pub use template::{TemplateEngine, TemplateKind};
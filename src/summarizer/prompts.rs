@@ -1,11 +1,28 @@
 use chrono::Timelike;
 use std::collections::HashMap;
 
+use super::budget;
 use super::template::TemplateEngine;
 
 /// Prompts for Claude CLI summarization
 pub struct Prompts;
 
+/// What goes into the daily summary prompt; the optional sections are
+/// built by the matching `Prompts::*_section` and left empty when unused
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DailySummaryPromptInput<'a> {
+    pub custom_template: Option<&'a str>,
+    pub sessions_json: &'a str,
+    pub date: &'a str,
+    pub existing_summary: Option<&'a str>,
+    pub goals_section: &'a str,
+    pub todos_section: &'a str,
+    pub notes_section: &'a str,
+    pub citations_section: &'a str,
+    pub projects_section: &'a str,
+    pub language: &'a str,
+}
+
 // Default template constants for session summary
 const SESSION_SUMMARY_EN: &str = r#"You are analyzing a Claude Code session transcript. Generate a comprehensive summary in JSON format.
 
@@ -23,10 +40,13 @@ Generate a JSON response with this exact structure:
   "summary": "2-3 sentence overview including CONCRETE RESULTS (answers found, solutions implemented, code written). Never just describe the action - always include what was produced or discovered.",
   "decisions": "Key decisions made and their rationale (markdown list format)",
   "learnings": "Key learnings from this session (markdown list format)",
-  "skill_hints": "Potential reusable skills (only if passes quality gate, see below)"
+  "skill_hints": "Potential reusable skills (only if passes quality gate, see below)",
+  "sources": {"summary": [[1, 2]], "decisions": [[3], [2, 4]], "learnings": [[4]]}
 }
 ```
 
+For sources, give one list per item of summary, decisions and learnings, in the same order as the items (the summary counts as one item): the numbers of the User Requests that item is based on.
+
 ## Skill Quality Gate
 Only suggest skills that pass ALL three criteria:
 1. **Did you hit a pitfall?** Did debugging, trial-and-error, or non-obvious discovery occur?
@@ -60,10 +80,13 @@ const SESSION_SUMMARY_ZH: &str = r#"你正在分析一个 Claude Code 会话记
   "summary": "2-3句话概述，包含具体成果（找到的答案、实现的解决方案、编写的代码）。不要只描述动作，总是包含产出或发现。",
   "decisions": "关键决策及其理由（markdown 列表格式）",
   "learnings": "本次会话的关键收获（markdown 列表格式）",
-  "skill_hints": "可复用的技能提示（仅当通过质量门禁时）"
+  "skill_hints": "可复用的技能提示（仅当通过质量门禁时）",
+  "sources": {"summary": [[1, 2]], "decisions": [[3], [2, 4]], "learnings": [[4]]}
 }
 ```
 
+sources 中为 summary、decisions 和 learnings 的每一项各给出一个列表，顺序与各项相同（summary 整体算一项），列出该项所依据的用户请求（User Requests）编号。
+
 ## 技能质量门禁（沉淀三问）
 只有通过全部三个标准才能提取技能：
 1. **踩过坑吗？** 是否经历了调试、试错或非显而易见的发现？
@@ -123,7 +146,15 @@ A skill consists of:
 
 ## Output Format:
 
-Generate ONLY the SKILL.md content. If you identify scripts, references, or assets that would be helpful, mention them in the instructions but don't generate them.
+Generate the SKILL.md content first. If the instructions rely on a script, reference, or asset, write it out after the SKILL.md block, one file at a time, without a code fence:
+
+```
+=== FILE: scripts/script-name.py ===
+[complete file content]
+=== END FILE ===
+```
+
+Only use paths under scripts/, references/ or assets/, and only add files SKILL.md points to.
 
 ```markdown
 ---
@@ -155,9 +186,9 @@ For tool integration skills:
 - Common operations
 - Examples and patterns
 
-[If scripts would be helpful, mention: "For deterministic execution, consider adding scripts/script-name.py"]
-[If detailed docs needed, mention: "For detailed reference, see references/topic.md"]
-[If templates needed, mention: "Template files in assets/"]
+[If a script makes a fragile step deterministic, point to it: "Run scripts/script-name.py"]
+[If detailed docs are needed, point to them: "For detailed reference, see references/topic.md"]
+[If templates are needed, point to them: "Template files in assets/"]
 
 ## Examples
 
@@ -171,7 +202,7 @@ CRITICAL GUIDELINES:
 - Only include information Claude doesn't already know
 - Prefer concise examples over verbose explanations
 
-Output ONLY the markdown content (or NOT_EXTRACTABLE message)."#;
+Output ONLY the SKILL.md content and its files (or NOT_EXTRACTABLE message)."#;
 
 const SKILL_EXTRACT_ZH: &str = r#"你正在从一个 Claude Code 会话中提取可复用的技能。
 
@@ -214,7 +245,15 @@ NOT_EXTRACTABLE: [原因]
 
 ## 输出格式：
 
-仅生成 SKILL.md 内容。如果你识别出有用的 scripts、references 或 assets，在指令中提及它们但不生成它们。
+先生成 SKILL.md 内容。如果指令依赖某个 script、reference 或 asset，在 SKILL.md 代码块之后逐个写出这些文件，不要使用代码块包裹：
+
+```
+=== FILE: scripts/script-name.py ===
+[完整的文件内容]
+=== END FILE ===
+```
+
+路径只能位于 scripts/、references/ 或 assets/ 下，且只添加 SKILL.md 中引用的文件。
 
 ```markdown
 ---
@@ -246,9 +285,9 @@ description: "全面的描述，作为主要触发机制。包含：(1) 技能
 - 常见操作
 - 示例和模式
 
-[如果脚本会有帮助，提及："对于确定性执行，考虑添加 scripts/script-name.py"]
-[如果需要详细文档，提及："详细参考见 references/topic.md"]
-[如果需要模板，提及："模板文件在 assets/"]
+[如果脚本能让脆弱的步骤变得确定，引用它："运行 scripts/script-name.py"]
+[如果需要详细文档，引用它："详细参考见 references/topic.md"]
+[如果需要模板，引用它："模板文件在 assets/"]
 
 ## 示例
 
@@ -262,7 +301,7 @@ description: "全面的描述，作为主要触发机制。包含：(1) 技能
 - 只包含 Claude 还不知道的信息
 - 优先使用简洁的示例而不是冗长的解释
 
-仅输出 markdown 内容（或 NOT_EXTRACTABLE 消息）。"#;
+仅输出 SKILL.md 内容及其文件（或 NOT_EXTRACTABLE 消息）。"#;
 
 // Default template constants for command extraction
 const COMMAND_EXTRACT_EN: &str = r#"Generate a complete slash command file for Claude Code based on this session.
@@ -333,6 +372,11 @@ const DAILY_SUMMARY_EN: &str = r#"You are analyzing Claude Code sessions from {{
 - Time periods: {{periods_desc}}
 {{existing_section}}
 {{sessions_section}}
+{{goals_section}}
+{{todos_section}}
+{{notes_section}}
+{{citations_section}}
+{{projects_section}}
 
 ## Your Task
 
@@ -396,6 +440,11 @@ const DAILY_SUMMARY_ZH: &str = r#"你正在分析 {{date}} 的 Claude Code 会
 - 时间段：{{periods_desc}}
 {{existing_section}}
 {{sessions_section}}
+{{goals_section}}
+{{todos_section}}
+{{notes_section}}
+{{citations_section}}
+{{projects_section}}
 
 ## 你的任务
 
@@ -497,13 +546,17 @@ impl Prompts {
     // Template-based Prompt Generation
     // ============================================
 
-    /// Generate prompt for session summarization with optional custom template
+    /// Generate prompt for session summarization with optional custom template.
+    ///
+    /// With a non-zero `max_prompt_tokens`, the transcript is cut down so the
+    /// whole prompt stays under that estimated size; dropped lines are logged.
     pub fn session_summary_with_template(
         custom_template: Option<&str>,
         transcript_text: &str,
         cwd: &str,
         git_info: Option<&str>,
         language: &str,
+        max_prompt_tokens: usize,
     ) -> String {
         let git_str = git_info.unwrap_or("N/A");
 
@@ -511,11 +564,27 @@ impl Prompts {
             custom_template.unwrap_or_else(|| Self::default_session_summary_template(language));
 
         let mut vars = HashMap::new();
-        vars.insert("transcript", transcript_text);
+        vars.insert("transcript", "");
         vars.insert("cwd", cwd);
         vars.insert("git_branch", git_str);
         vars.insert("language", language);
 
+        let fitted;
+        let transcript_text = if max_prompt_tokens == 0 {
+            transcript_text
+        } else {
+            let overhead = budget::estimate_tokens(&TemplateEngine::render(template, &vars));
+            fitted = budget::fit(transcript_text, max_prompt_tokens.saturating_sub(overhead));
+            if fitted.dropped_lines > 0 {
+                eprintln!(
+                    "[daily] Transcript over prompt budget ({} tokens): dropped {} lines (~{} tokens) from the middle",
+                    max_prompt_tokens, fitted.dropped_lines, fitted.dropped_tokens
+                );
+            }
+            &fitted.text
+        };
+        vars.insert("transcript", transcript_text);
+
         TemplateEngine::render(template, &vars)
     }
 
@@ -570,13 +639,19 @@ impl Prompts {
     }
 
     /// Generate prompt for daily summary with optional custom template
-    pub fn daily_summary_with_template(
-        custom_template: Option<&str>,
-        sessions_json: &str,
-        date: &str,
-        existing_summary: Option<&str>,
-        language: &str,
-    ) -> String {
+    pub fn daily_summary_with_template(input: &DailySummaryPromptInput) -> String {
+        let DailySummaryPromptInput {
+            custom_template,
+            sessions_json,
+            date,
+            existing_summary,
+            goals_section,
+            todos_section,
+            notes_section,
+            citations_section,
+            projects_section,
+            language,
+        } = *input;
         let now = chrono::Local::now();
         let current_time = now.format("%H:%M").to_string();
         let current_hour = now.hour();
@@ -655,15 +730,297 @@ impl Prompts {
         vars.insert("existing_section", existing_section.as_str());
         vars.insert("sessions_section", sessions_section.as_str());
         vars.insert("sessions_json", sessions_json);
+        vars.insert("goals_section", goals_section);
+        vars.insert("todos_section", todos_section);
+        vars.insert("notes_section", notes_section);
+        vars.insert("citations_section", citations_section);
+        vars.insert("projects_section", projects_section);
         vars.insert("language", language);
 
         TemplateEngine::render(template, &vars)
     }
+
+    /// Digest prompt section listing the active goals as `(id, title)` and
+    /// asking for a `goal_progress` array; empty when there are none
+    pub fn goals_section(goals: &[(u32, &str)], language: &str) -> String {
+        if goals.is_empty() {
+            return String::new();
+        }
+        let list: String = goals
+            .iter()
+            .map(|(id, title)| format!("- {}: {}\n", id, title))
+            .collect();
+        if language == "zh" {
+            format!(
+                "\n## 当前目标\n\n{}\n对于今天的工作推进了的每个目标，在 JSON 中加入 \"goal_progress\" 数组：\
+                 [{{\"goal\": <目标编号>, \"progress\": \"一句话说明进展\", \"sessions\": <相关会话数>}}]。\
+                 没有推进的目标不要列出；没有任何进展时使用 []。\n",
+                list
+            )
+        } else {
+            format!(
+                "\n## Active Goals\n\n{}\nFor each goal the day's work moved forward, add it to a \"goal_progress\" \
+                 array in the JSON: [{{\"goal\": <goal id>, \"progress\": \"one sentence on what was done\", \
+                 \"sessions\": <number of sessions that worked on it>}}]. Leave out goals with no \
+                 progress; use [] if there is none.\n",
+                list
+            )
+        }
+    }
+
+    /// Digest prompt section listing the open TODOs, which belong in
+    /// Tomorrow's Focus; empty when there are none
+    pub fn todos_section(todos: &[&str], language: &str) -> String {
+        if todos.is_empty() {
+            return String::new();
+        }
+        let list: String = todos.iter().map(|text| format!("- {}\n", text)).collect();
+        if language == "zh" {
+            format!(
+                "\n## 待办事项\n\n用户记录的未完成待办：\n\n{}\n\
+                 在 tomorrow_focus 中为每一项加入一张卡片，标题与待办原文完全一致。\n",
+                list
+            )
+        } else {
+            format!(
+                "\n## Open TODOs\n\nTODOs the user captured that are still open:\n\n{}\n\
+                 Add a tomorrow_focus card for each one, titled with its text exactly as written.\n",
+                list
+            )
+        }
+    }
+
+    /// Digest prompt section asking which numbered sessions each card is
+    /// based on, for `summarization.digest_citations`
+    pub fn citations_section(language: &str) -> String {
+        if language == "zh" {
+            "\n## 来源会话\n\n每个会话都带有编号 \"session\"。在 JSON 中加入 \"citations\"：\
+             {\"insights\": [[1, 2]], \"tomorrow_focus\": [[3]], \"skills\": [], \"commands\": []}，\
+             为每个卡片数组的每一项各给出一个列表，顺序与卡片相同，列出该卡片所依据的会话编号。\
+             正文中仍然不要提及会话。\n"
+                .to_string()
+        } else {
+            "\n## Source Sessions\n\nEach session has a number in \"session\". Add \"citations\" to the JSON: \
+             {\"insights\": [[1, 2]], \"tomorrow_focus\": [[3]], \"skills\": [], \"commands\": []}, \
+             with one list per card of each array, in the same order as the cards: the numbers of the \
+             sessions that card is based on. Still keep sessions out of the prose.\n"
+                .to_string()
+        }
+    }
+
+    /// Digest prompt section asking for a subsection per project, for
+    /// `summarization.digest_group_by = "project"`; empty when there are none
+    pub fn projects_section(projects: &[&str], language: &str) -> String {
+        if projects.is_empty() {
+            return String::new();
+        }
+        let list: String = projects
+            .iter()
+            .map(|name| format!("- {}\n", name))
+            .collect();
+        if language == "zh" {
+            format!(
+                "\n## 按项目分组\n\n每个会话都带有所属项目 \"project\"。今天涉及的项目：\n\n{}\n\
+                 在 JSON 中加入 \"projects\" 数组：[{{\"title\": \"<项目名>\", \"content\": \"markdown\"}}]，\
+                 每个项目一张卡片，标题与上面的项目名完全一致，内容概括该项目今天完成的工作、决策和下一步。\n",
+                list
+            )
+        } else {
+            format!(
+                "\n## By Project\n\nEach session names its project in \"project\". Projects worked on today:\n\n{}\n\
+                 Add a \"projects\" array to the JSON: [{{\"title\": \"<project name>\", \"content\": \"markdown\"}}], \
+                 one card per project titled with its name exactly as listed, covering what was done, \
+                 decided and left next in that project.\n",
+                list
+            )
+        }
+    }
+
+    /// Digest prompt section with the day's hand-written notes as
+    /// `(time, text)`; empty when there are none
+    pub fn notes_section(notes: &[(&str, &str)], language: &str) -> String {
+        if notes.is_empty() {
+            return String::new();
+        }
+        let list: String = notes
+            .iter()
+            .map(|(time, text)| format!("- {} {}\n", time, text))
+            .collect();
+        if language == "zh" {
+            format!(
+                "\n## 手写笔记\n\n以下是用户今天手动记录的笔记，并非来自会话：\n\n{}\n\
+                 请把它们纳入概述、见解和反思中，视同当天工作的一部分。\n",
+                list
+            )
+        } else {
+            format!(
+                "\n## Journal Notes\n\nNotes the user wrote down by hand today, outside any session:\n\n{}\n\
+                 Work them into the overview, insights and reflections as part of the day.\n",
+                list
+            )
+        }
+    }
+
+    /// Prompt answering a question from numbered archive excerpts, given as
+    /// `(reference, excerpt)` pairs and cited back as `[n]`
+    pub fn ask(question: &str, sources: &[(String, String)], language: &str) -> String {
+        let mut excerpts = String::new();
+        for (i, (reference, excerpt)) in sources.iter().enumerate() {
+            excerpts.push_str(&format!(
+                "[{}] {}\n```markdown\n{}\n```\n\n",
+                i + 1,
+                reference,
+                excerpt
+            ));
+        }
+        if language == "zh" {
+            format!(
+                "以下是我过去工作会话和每日摘要的存档摘录：\n\n{}\
+                 请仅根据这些摘录回答问题：{}\n\n\
+                 在引用的内容后用 [1]、[2] 这样的编号注明出处。\
+                 如果摘录中没有答案，请直接说明，不要猜测。用中文回答，使用 Markdown，保持简洁。",
+                excerpts, question
+            )
+        } else {
+            format!(
+                "Below are excerpts from archives of my past work sessions and daily digests:\n\n{}\
+                 Using only these excerpts, answer my question: {}\n\n\
+                 Cite the sources you rely on with their numbers, like [1] or [2], right after \
+                 the statements they support. If the excerpts don't answer the question, say so \
+                 instead of guessing. Answer concisely in Markdown.",
+                excerpts, question
+            )
+        }
+    }
+
+    /// Prompt translating a finished digest into `language`
+    pub fn translate_digest(content: &str, language: &str) -> String {
+        let target = if language == "zh" {
+            "Simplified Chinese"
+        } else {
+            "English"
+        };
+        format!(
+            "Translate this daily work digest into {}.\n\n\
+             Keep the Markdown structure exactly as it is: the frontmatter, every `## ` and `### ` \
+             heading (leave headings in English so the digest can still be parsed), links, file \
+             paths, commands, code blocks and the footer. Translate only the prose, and keep \
+             technical terms that are usually left untranslated. Reply with the translated \
+             Markdown only, without a code fence around it.\n\n\
+             <digest>\n{}\n</digest>",
+            target, content
+        )
+    }
+
+    /// Prompt rewriting only the `## {heading}` section of a digest from the
+    /// day's session summaries
+    pub fn regenerate_digest_section(
+        digest: &str,
+        sessions: &str,
+        heading: &str,
+        language: &str,
+    ) -> String {
+        let target = if language == "zh" {
+            "Simplified Chinese"
+        } else {
+            "English"
+        };
+        format!(
+            "Below is a daily work digest and the summaries of the sessions it was written from. \
+             Write a new version of its \"{heading}\" section only, in {target}, from the session \
+             summaries. Keep the section's format: the same kind of paragraphs, lists or `### ` \
+             cards as the current version. Don't repeat what the other sections already say.\n\n\
+             Reply with the body of the section only, without the `## {heading}` heading and \
+             without a code fence around it.\n\n\
+             <sessions>\n{sessions}\n</sessions>\n\n\
+             <digest>\n{digest}\n</digest>"
+        )
+    }
+
+    /// Prompt filing sessions under knowledge base topics
+    pub fn assign_topics(topics: &str, sessions: &str, language: &str) -> String {
+        let title_language = if language == "zh" {
+            "Write topic titles and descriptions in Chinese; keep slugs in English."
+        } else {
+            "Write topic titles and descriptions in English."
+        };
+        format!(
+            "You maintain a knowledge base that groups coding sessions by topic.\n\n\
+             Existing topics:\n```json\n{}\n```\n\n\
+             New sessions:\n```json\n{}\n```\n\n\
+             File every new session under exactly one topic. Prefer an existing topic; \
+             create a new one only when none fits. Topics name a lasting subject area \
+             (a feature, subsystem, technology or practice such as \"authentication\" or \
+             \"ci-pipeline\"), broad enough to collect several sessions over time, never a \
+             single task or date. {}\n\n\
+             Output only a JSON object in a ```json block:\n\
+             {{\"new_topics\": [{{\"slug\": \"kebab-case-slug\", \"title\": \"Title\", \
+             \"description\": \"One sentence\"}}], \
+             \"assignments\": [{{\"session\": \"<session from the input>\", \"topic\": \"<slug>\"}}]}}",
+            topics, sessions, title_language
+        )
+    }
+
+    /// Pre-pass asking a cheap model whether a short session is worth a
+    /// full summary
+    pub fn triviality_check(transcript: &str) -> String {
+        format!(
+            "Decide whether this Claude Code session is trivial: a quick question, \
+             lookup or throwaway exchange with nothing worth recording in a work journal. \
+             Sessions that make a decision, explain a non-obvious fix, or plan or discuss \
+             real work are not trivial.\n\n\
+             Transcript:\n```\n{}\n```\n\n\
+             Output only a JSON object in a ```json block:\n\
+             {{\"trivial\": true, \"reason\": \"One short sentence\"}}",
+            transcript
+        )
+    }
+
+    /// Follow-up prompt asking the model to correct a response that
+    /// couldn't be parsed as JSON
+    pub fn fix_json(response: &str, error: &str) -> String {
+        format!(
+            "Your previous response could not be parsed as JSON ({}).\n\n\
+             Previous response:\n```\n{}\n```\n\n\
+             Output only the corrected JSON object in a ```json block, with the same \
+             fields and content. Use double quotes, escape quotes and newlines inside \
+             strings, and do not add trailing commas or comments.",
+            error, response
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::summarizer::template::TemplateKind;
+
+    #[test]
+    fn test_default_templates_pass_validation() {
+        for lang in ["en", "zh"] {
+            for (template, kind) in [
+                (
+                    Prompts::default_session_summary_template(lang),
+                    TemplateKind::SessionSummary,
+                ),
+                (
+                    Prompts::default_daily_summary_template(lang),
+                    TemplateKind::DailySummary,
+                ),
+                (
+                    Prompts::default_skill_extract_template(lang),
+                    TemplateKind::SkillExtract,
+                ),
+                (
+                    Prompts::default_command_extract_template(lang),
+                    TemplateKind::CommandExtract,
+                ),
+            ] {
+                let warnings = TemplateEngine::validate(template, kind);
+                assert!(warnings.is_empty(), "{:?} ({}): {:?}", kind, lang, warnings);
+            }
+        }
+    }
 
     #[test]
     fn test_session_summary_prompt_en() {
@@ -673,6 +1030,7 @@ mod tests {
             "/home/user/project",
             Some("main"),
             "en",
+            0,
         );
 
         assert!(prompt.contains("Working Directory: /home/user/project"));
@@ -687,6 +1045,7 @@ mod tests {
             "/home/user/project",
             Some("main"),
             "zh",
+            0,
         );
 
         assert!(prompt.contains("工作目录：/home/user/project"));
@@ -694,27 +1053,46 @@ mod tests {
     }
 
     #[test]
-    fn test_daily_summary_prompt() {
-        let prompt = Prompts::daily_summary_with_template(
-            None,
-            r#"[{"title": "test", "summary": "test summary"}]"#,
-            "2026-01-16",
+    fn test_session_summary_prompt_fits_budget() {
+        let transcript: Vec<String> = (0..5000)
+            .map(|i| format!("{}. User asked about step {}", i, i))
+            .collect();
+        let prompt = Prompts::session_summary_with_template(
             None,
+            &transcript.join("\n"),
+            "/home/user/project",
+            Some("main"),
             "en",
+            4000,
         );
 
+        assert!(budget::estimate_tokens(&prompt) <= 4000);
+        assert!(prompt.contains("0. User asked about step 0"));
+        assert!(prompt.contains("4999. User asked about step 4999"));
+        assert!(prompt.contains("Working Directory: /home/user/project"));
+    }
+
+    #[test]
+    fn test_daily_summary_prompt() {
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: r#"[{"title": "test", "summary": "test summary"}]"#,
+            date: "2026-01-16",
+            language: "en",
+            ..Default::default()
+        });
+
         assert!(prompt.contains("2026-01-16"));
     }
 
     #[test]
     fn test_daily_summary_prompt_with_existing() {
-        let prompt = Prompts::daily_summary_with_template(
-            None,
-            r#"[{"title": "new", "summary": "new summary"}]"#,
-            "2026-01-16",
-            Some("Previous overview content"),
-            "en",
-        );
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: r#"[{"title": "new", "summary": "new summary"}]"#,
+            date: "2026-01-16",
+            existing_summary: Some("Previous overview content"),
+            language: "en",
+            ..Default::default()
+        });
 
         assert!(prompt.contains("2026-01-16"));
         assert!(prompt.contains("Previous overview content"));
@@ -723,15 +1101,95 @@ mod tests {
 
     #[test]
     fn test_daily_summary_prompt_zh() {
-        let prompt = Prompts::daily_summary_with_template(
-            None,
-            r#"[{"title": "test", "summary": "test summary"}]"#,
-            "2026-01-16",
-            None,
-            "zh",
-        );
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: r#"[{"title": "test", "summary": "test summary"}]"#,
+            date: "2026-01-16",
+            language: "zh",
+            ..Default::default()
+        });
 
         assert!(prompt.contains("2026-01-16"));
         assert!(prompt.contains("时间上下文"));
     }
+
+    #[test]
+    fn test_daily_summary_prompt_goals() {
+        let goals = Prompts::goals_section(&[(1, "Ship the v2 API")], "en");
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: "[]",
+            date: "2026-01-16",
+            goals_section: &goals,
+            language: "en",
+            ..Default::default()
+        });
+
+        assert!(prompt.contains("## Active Goals\n\n- 1: Ship the v2 API\n"));
+        assert!(prompt.contains("\"goal_progress\""));
+        assert!(Prompts::goals_section(&[], "en").is_empty());
+    }
+
+    #[test]
+    fn test_daily_summary_prompt_todos() {
+        let todos = Prompts::todos_section(&["write release notes"], "en");
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: "[]",
+            date: "2026-01-16",
+            todos_section: &todos,
+            language: "en",
+            ..Default::default()
+        });
+
+        assert!(prompt.contains("## Open TODOs\n\nTODOs the user captured"));
+        assert!(prompt.contains("- write release notes\n"));
+        assert!(prompt.contains("Add a tomorrow_focus card for each one"));
+        assert!(Prompts::todos_section(&[], "en").is_empty());
+    }
+
+    #[test]
+    fn test_daily_summary_prompt_citations() {
+        let citations = Prompts::citations_section("en");
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: "[]",
+            date: "2026-01-16",
+            citations_section: &citations,
+            language: "en",
+            ..Default::default()
+        });
+
+        assert!(prompt.contains("## Source Sessions"));
+        assert!(prompt.contains("Add \"citations\" to the JSON: {\"insights\""));
+        assert!(!Prompts::citations_section("zh").contains("  "));
+    }
+
+    #[test]
+    fn test_daily_summary_prompt_notes() {
+        let notes = Prompts::notes_section(&[("09:30", "Call with the infra team")], "en");
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: "[]",
+            date: "2026-01-16",
+            notes_section: &notes,
+            language: "en",
+            ..Default::default()
+        });
+
+        assert!(prompt.contains("## Journal Notes"));
+        assert!(prompt.contains("- 09:30 Call with the infra team\n\nWork them into"));
+        assert!(Prompts::notes_section(&[], "en").is_empty());
+    }
+
+    #[test]
+    fn test_daily_summary_prompt_projects() {
+        let projects = Prompts::projects_section(&["claude-daily", "website"], "en");
+        let prompt = Prompts::daily_summary_with_template(&DailySummaryPromptInput {
+            sessions_json: "[]",
+            date: "2026-01-16",
+            projects_section: &projects,
+            language: "en",
+            ..Default::default()
+        });
+
+        assert!(prompt.contains("## By Project"));
+        assert!(prompt.contains("- claude-daily\n- website\n"));
+        assert!(Prompts::projects_section(&[], "en").is_empty());
+    }
 }
@@ -0,0 +1,194 @@
+//! Heuristic quality checks on generated summaries and digests.
+//!
+//! Each result is scored from 0 to 100 after parsing: points are taken off
+//! for missing fields, summaries that name nothing concrete, and digests
+//! that leak session file names. Scores below
+//! `summarization.min_quality_score` are flagged in the job record and, with
+//! `summarization.retry_low_quality`, answered once more with the issues
+//! spelled out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::archive::SummaryCard;
+
+/// Topics too vague to tell sessions apart
+const GENERIC_TOPICS: &[&str] = &[
+    "session", "work", "misc", "general", "update", "updates", "changes", "untitled", "task",
+    "coding",
+];
+
+/// Score of a generated result and what lowered it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityReport {
+    /// 0-100; 100 means no problems were found
+    pub score: u8,
+    /// Whether the score was below the configured minimum
+    #[serde(default)]
+    pub low: bool,
+    /// One line per problem found
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+impl QualityReport {
+    fn new(penalties: Vec<(u8, String)>, min_score: u8) -> Self {
+        let lost: u32 = penalties.iter().map(|(points, _)| u32::from(*points)).sum();
+        let score = 100u32.saturating_sub(lost) as u8;
+        Self {
+            score,
+            low: score < min_score,
+            issues: penalties.into_iter().map(|(_, issue)| issue).collect(),
+        }
+    }
+
+    /// Follow-up to a prompt asking for an answer without these issues
+    pub fn correction(&self, prompt: &str) -> String {
+        let issues: String = self
+            .issues
+            .iter()
+            .map(|issue| format!("- {}\n", issue))
+            .collect();
+        format!(
+            "{}\n\nA previous answer to this request had these problems:\n{}\n\
+             Answer again in the same JSON format, fixing them.",
+            prompt, issues
+        )
+    }
+}
+
+/// Whether `text` names something concrete: code in backticks, a file path,
+/// a module path, a command-line flag, an issue number or a snake_case name
+pub fn has_artifact(text: &str) -> bool {
+    if text.contains('`') || text.contains("::") {
+        return true;
+    }
+    text.split_whitespace().any(|word| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '#');
+        let inner = |sep: char| {
+            word.split(sep).count() > 1
+                && word
+                    .split(sep)
+                    .all(|part| part.chars().next().is_some_and(char::is_alphanumeric))
+        };
+        (word.starts_with("--") && word.len() > 2)
+            || (word.starts_with('#')
+                && word[1..].chars().all(|c| c.is_ascii_digit())
+                && word.len() > 1)
+            || inner('/')
+            || inner('_')
+            || (inner('.') && word.chars().any(char::is_alphabetic))
+    })
+}
+
+/// Score a session summary
+pub fn score_session(
+    topic: &str,
+    summary: &str,
+    decisions: &str,
+    learnings: &str,
+    min_score: u8,
+) -> QualityReport {
+    let mut penalties = Vec::new();
+
+    let topic = topic.trim();
+    if topic.is_empty() {
+        penalties.push((30, "topic is empty".to_string()));
+    } else if GENERIC_TOPICS.contains(&topic.to_lowercase().as_str()) {
+        penalties.push((15, format!("topic '{}' is too generic", topic)));
+    }
+
+    let words = summary.split_whitespace().count();
+    if words == 0 {
+        penalties.push((50, "summary is empty".to_string()));
+    } else if words < 15 {
+        penalties.push((20, format!("summary is very short ({} words)", words)));
+    }
+
+    if words > 0
+        && ![summary, decisions, learnings]
+            .iter()
+            .any(|t| has_artifact(t))
+    {
+        penalties.push((
+            25,
+            "summary names no concrete artifacts (files, commands, functions)".to_string(),
+        ));
+    }
+
+    QualityReport::new(penalties, min_score)
+}
+
+/// Score a digest; `sessions` are the archive names of the day, which
+/// belong in the digest's frontmatter but not in its prose
+pub fn score_digest(
+    overview: &str,
+    session_details: &str,
+    reflections: &str,
+    cards: &[&SummaryCard],
+    sessions: &[String],
+    min_score: u8,
+) -> QualityReport {
+    let mut penalties = Vec::new();
+
+    if overview.trim().is_empty() {
+        penalties.push((40, "overview is empty".to_string()));
+    }
+    if session_details.trim().is_empty() {
+        penalties.push((20, "key work section is empty".to_string()));
+    }
+
+    let mut text = format!("{}\n{}\n{}", overview, session_details, reflections);
+    for card in cards {
+        text.push_str(&format!("\n{}\n{}", card.title, card.content));
+    }
+    let mut leaked = 0;
+    for name in sessions {
+        if text.contains(name.as_str()) && leaked < 3 {
+            leaked += 1;
+            penalties.push((15, format!("session name '{}' appears in the text", name)));
+        }
+    }
+
+    QualityReport::new(penalties, min_score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores() {
+        let good = score_session(
+            "fix-auth-bug",
+            "Fixed the token refresh race in `auth/session.rs` by holding the lock across \
+             the refresh call; added a regression test that runs two refreshes at once.",
+            "",
+            "",
+            60,
+        );
+        assert_eq!(good.score, 100);
+        assert!(!good.low && good.issues.is_empty());
+
+        let thin = score_session("work", "Did some fixes.", "", "", 60);
+        assert_eq!(thin.score, 40);
+        assert!(thin.low);
+        assert_eq!(thin.issues.len(), 3);
+
+        let sessions = vec!["14_55-fix-auth-bug".to_string()];
+        let digest = score_digest(
+            "Worked on 14_55-fix-auth-bug all afternoon.",
+            "Auth fixes",
+            "",
+            &[],
+            &sessions,
+            60,
+        );
+        assert_eq!(digest.score, 85);
+        assert!(!digest.low);
+        assert!(digest.issues[0].contains("14_55-fix-auth-bug"));
+
+        assert!(has_artifact("ran cargo test --workspace"));
+        assert!(has_artifact("see #412"));
+        assert!(!has_artifact("Fixed a bug. Then tested it."));
+    }
+}
@@ -0,0 +1,197 @@
+//! Following a response as the Claude CLI streams it.
+//!
+//! With `--output-format stream-json` the CLI prints one JSON event per
+//! line: text deltas while the model writes, then the same result envelope
+//! `--output-format json` prints. The text is echoed to the job log line by
+//! line, with a progress marker (tokens so far, time elapsed) every
+//! [`PROGRESS_INTERVAL`], so a digest that takes minutes visibly stays alive.
+
+use serde_json::Value;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use super::budget::estimate_tokens;
+
+/// Time between progress markers while a response streams in
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Prefix of response lines echoed to the log, telling them apart from
+/// the job's own `[daily]` messages
+const ECHO_PREFIX: &str = "[claude] ";
+
+/// State of one streamed response
+pub struct StreamLog<W: Write> {
+    out: W,
+    /// Echo the response text, not just progress markers
+    echo: bool,
+    started: Instant,
+    last_marker: Instant,
+    /// Text received since the last complete line
+    line: String,
+    /// All text received so far
+    text: String,
+    /// Output tokens as reported by the API, when it reports them
+    reported_tokens: Option<u64>,
+    saw_delta: bool,
+    /// The final result event, as printed
+    result: Option<String>,
+    /// Lines that weren't stream events, e.g. from an older CLI that
+    /// printed a plain response
+    other: String,
+}
+
+impl<W: Write> StreamLog<W> {
+    pub fn new(out: W, echo: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            out,
+            echo,
+            started: now,
+            last_marker: now,
+            line: String::new(),
+            text: String::new(),
+            reported_tokens: None,
+            saw_delta: false,
+            result: None,
+            other: String::new(),
+        }
+    }
+
+    /// Handle one line of the CLI's output
+    pub fn event(&mut self, line: &str) {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            self.other.push_str(line);
+            self.other.push('\n');
+            return;
+        };
+        match event["type"].as_str() {
+            Some("stream_event") => self.stream_event(&event["event"]),
+            // Whole message; only used when the CLI sent no deltas
+            Some("assistant") if !self.saw_delta => {
+                if let Some(blocks) = event["message"]["content"].as_array() {
+                    for block in blocks {
+                        if let Some(text) = block["text"].as_str() {
+                            self.push_text(text);
+                        }
+                    }
+                }
+            }
+            Some("result") => self.result = Some(line.to_string()),
+            Some(_) => {}
+            None => {
+                self.other.push_str(line);
+                self.other.push('\n');
+            }
+        }
+        if self.last_marker.elapsed() >= PROGRESS_INTERVAL {
+            self.last_marker = Instant::now();
+            self.flush_line();
+            let _ = writeln!(
+                self.out,
+                "[daily] Still generating: {} tokens, {}s elapsed",
+                self.tokens(),
+                self.started.elapsed().as_secs()
+            );
+        }
+    }
+
+    fn stream_event(&mut self, event: &Value) {
+        match event["type"].as_str() {
+            Some("content_block_delta") => {
+                if let Some(text) = event["delta"]["text"].as_str() {
+                    self.saw_delta = true;
+                    self.push_text(text);
+                }
+            }
+            Some("message_delta") => {
+                if let Some(tokens) = event["usage"]["output_tokens"].as_u64() {
+                    self.reported_tokens = Some(tokens);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.line.push_str(text);
+        while let Some(end) = self.line.find('\n') {
+            let line: String = self.line.drain(..=end).collect();
+            if self.echo {
+                let _ = writeln!(self.out, "{}{}", ECHO_PREFIX, line.trim_end());
+            }
+        }
+    }
+
+    fn flush_line(&mut self) {
+        if self.echo && !self.line.is_empty() {
+            let _ = writeln!(self.out, "{}{}", ECHO_PREFIX, self.line.trim_end());
+        }
+        self.line.clear();
+    }
+
+    /// Output tokens so far, estimated from the text until the API reports them
+    fn tokens(&self) -> String {
+        match self.reported_tokens {
+            Some(tokens) => tokens.to_string(),
+            None => format!("~{}", estimate_tokens(&self.text)),
+        }
+    }
+
+    /// Finish the log and return what [`SummarizerEngine`] reads a result
+    /// from: the result envelope, or for CLIs that don't stream, their
+    /// plain output
+    ///
+    /// [`SummarizerEngine`]: super::SummarizerEngine
+    pub fn finish(mut self) -> String {
+        self.flush_line();
+        if !self.text.is_empty() {
+            let _ = writeln!(
+                self.out,
+                "[daily] Response complete: {} tokens in {}s",
+                self.tokens(),
+                self.started.elapsed().as_secs()
+            );
+        }
+        self.result.unwrap_or(self.other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_log() {
+        let mut out = Vec::new();
+        let mut log = StreamLog::new(&mut out, true);
+        for line in [
+            r#"{"type":"system","subtype":"init"}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","delta":{"type":"text_delta","text":"{\"overview\":"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","delta":{"type":"text_delta","text":" \"Busy\"\n}"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"message_delta","usage":{"output_tokens":7}}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"not echoed twice"}]}}"#,
+            r#"{"type":"result","is_error":false,"result":"{\"overview\": \"Busy\"\n}"}"#,
+        ] {
+            log.event(line);
+        }
+        let result = log.finish();
+        assert!(result.starts_with(r#"{"type":"result""#));
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[claude] {\"overview\": \"Busy\"",
+                "[claude] }",
+                "[daily] Response complete: 7 tokens in 0s"
+            ]
+        );
+
+        // A CLI that doesn't stream prints its response as is
+        let mut log = StreamLog::new(Vec::new(), false);
+        log.event("plain text");
+        assert_eq!(log.finish(), "plain text\n");
+    }
+}
@@ -4,6 +4,104 @@ use std::collections::HashMap;
 /// Supports {{variable}} syntax (Handlebars-style)
 pub struct TemplateEngine;
 
+/// Which prompt a template is used for, determining its available variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    SessionSummary,
+    DailySummary,
+    SkillExtract,
+    CommandExtract,
+}
+
+impl TemplateKind {
+    pub const ALL: [TemplateKind; 4] = [
+        TemplateKind::SessionSummary,
+        TemplateKind::DailySummary,
+        TemplateKind::SkillExtract,
+        TemplateKind::CommandExtract,
+    ];
+
+    /// Config field name for this template, e.g. "session_summary"
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemplateKind::SessionSummary => "session_summary",
+            TemplateKind::DailySummary => "daily_summary",
+            TemplateKind::SkillExtract => "skill_extract",
+            TemplateKind::CommandExtract => "command_extract",
+        }
+    }
+
+    /// Parse a config field name back into a template kind
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    /// Variables substituted when rendering this kind of template
+    pub fn known_variables(&self) -> &'static [&'static str] {
+        match self {
+            TemplateKind::SessionSummary => &["transcript", "cwd", "git_branch", "language"],
+            TemplateKind::DailySummary => &[
+                "date",
+                "current_time",
+                "current_period",
+                "periods_desc",
+                "existing_section",
+                "sessions_section",
+                "sessions_json",
+                "goals_section",
+                "todos_section",
+                "notes_section",
+                "citations_section",
+                "projects_section",
+                "language",
+            ],
+            TemplateKind::SkillExtract => &["session_content", "skill_hint", "today", "language"],
+            TemplateKind::CommandExtract => &["session_content", "command_hint", "language"],
+        }
+    }
+
+    /// Variables without which the prompt has no input to work on
+    pub fn required_variables(&self) -> &'static [&'static str] {
+        match self {
+            TemplateKind::SessionSummary => &["transcript"],
+            TemplateKind::DailySummary => &["sessions_section"],
+            TemplateKind::SkillExtract | TemplateKind::CommandExtract => &["session_content"],
+        }
+    }
+}
+
+/// Category of a template lint finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateWarningKind {
+    /// `{{name}}` that is never substituted for this template kind
+    UnknownVariable,
+    /// A required placeholder such as `{{transcript}}` is absent
+    MissingRequired,
+    /// `{{` without a matching `}}`
+    UnclosedPlaceholder,
+    /// Single `{`/`}` braces (e.g. in a JSON example) do not pair up
+    UnbalancedBraces,
+}
+
+impl TemplateWarningKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TemplateWarningKind::UnknownVariable => "unknown_variable",
+            TemplateWarningKind::MissingRequired => "missing_required",
+            TemplateWarningKind::UnclosedPlaceholder => "unclosed_placeholder",
+            TemplateWarningKind::UnbalancedBraces => "unbalanced_braces",
+        }
+    }
+}
+
+/// A structured warning produced by [`TemplateEngine::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateWarning {
+    pub kind: TemplateWarningKind,
+    pub message: String,
+    pub variable: Option<String>,
+}
+
 impl TemplateEngine {
     /// Render a template by replacing {{variable}} placeholders with values
     ///
@@ -22,9 +120,87 @@ impl TemplateEngine {
         result
     }
 
+    /// Lint a custom template for the given kind.
+    /// Returns an empty list when the template is safe to use.
+    pub fn validate(template: &str, kind: TemplateKind) -> Vec<TemplateWarning> {
+        let mut warnings = Vec::new();
+        let used = Self::extract_variables(template);
+
+        for var in &used {
+            if !kind.known_variables().contains(&var.as_str()) {
+                warnings.push(TemplateWarning {
+                    kind: TemplateWarningKind::UnknownVariable,
+                    message: format!(
+                        "Unknown variable {{{{{}}}}} will not be substituted (available: {})",
+                        var,
+                        kind.known_variables().join(", ")
+                    ),
+                    variable: Some(var.clone()),
+                });
+            }
+        }
+
+        for required in kind.required_variables() {
+            if !used.iter().any(|v| v == required) {
+                warnings.push(TemplateWarning {
+                    kind: TemplateWarningKind::MissingRequired,
+                    message: format!("Missing required placeholder {{{{{}}}}}", required),
+                    variable: Some(required.to_string()),
+                });
+            }
+        }
+
+        // Strip well-formed placeholders, then check what braces remain
+        let mut rest = template;
+        let mut stripped = String::with_capacity(template.len());
+        while let Some(start) = rest.find("{{") {
+            stripped.push_str(&rest[..start]);
+            match rest[start + 2..].find("}}") {
+                Some(end) => rest = &rest[start + 2 + end + 2..],
+                None => {
+                    warnings.push(TemplateWarning {
+                        kind: TemplateWarningKind::UnclosedPlaceholder,
+                        message: "Found '{{' without a matching '}}'".to_string(),
+                        variable: None,
+                    });
+                    rest = &rest[start + 2..];
+                }
+            }
+        }
+        stripped.push_str(rest);
+
+        let mut depth: i64 = 0;
+        let mut underflow = false;
+        for c in stripped.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth < 0 {
+                        underflow = true;
+                        depth = 0;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if underflow || depth != 0 {
+            warnings.push(TemplateWarning {
+                kind: TemplateWarningKind::UnbalancedBraces,
+                message: if underflow {
+                    "Unbalanced braces: '}' without a matching '{'".to_string()
+                } else {
+                    format!("Unbalanced braces: {} unclosed '{{'", depth)
+                },
+                variable: None,
+            });
+        }
+
+        warnings
+    }
+
     /// Extract all variable names used in a template
     /// Useful for validation and UI hints
-    #[allow(dead_code)]
     pub fn extract_variables(template: &str) -> Vec<String> {
         let mut variables = Vec::new();
         let mut chars = template.chars().peekable();
@@ -108,6 +284,58 @@ mod tests {
         assert!(vars.contains(&"item".to_string()));
     }
 
+    #[test]
+    fn test_validate_clean_template() {
+        let template = "Summarize {{transcript}} in {{language}}. Reply as {\"title\": \"...\"}";
+        assert!(TemplateEngine::validate(template, TemplateKind::SessionSummary).is_empty());
+    }
+
+    #[test]
+    fn test_validate_unknown_and_missing() {
+        let warnings =
+            TemplateEngine::validate("Summarize {{transcrpt}}", TemplateKind::SessionSummary);
+        let kinds: Vec<TemplateWarningKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TemplateWarningKind::UnknownVariable,
+                TemplateWarningKind::MissingRequired
+            ]
+        );
+        assert_eq!(warnings[0].variable.as_deref(), Some("transcrpt"));
+        assert_eq!(warnings[1].variable.as_deref(), Some("transcript"));
+    }
+
+    #[test]
+    fn test_validate_unbalanced_braces() {
+        let warnings = TemplateEngine::validate(
+            "{{session_content}} Output: {\"name\": {\"a\": 1}",
+            TemplateKind::CommandExtract,
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, TemplateWarningKind::UnbalancedBraces);
+
+        let warnings =
+            TemplateEngine::validate("{{session_content}} }", TemplateKind::SkillExtract);
+        assert_eq!(warnings[0].kind, TemplateWarningKind::UnbalancedBraces);
+    }
+
+    #[test]
+    fn test_validate_unclosed_placeholder() {
+        let warnings =
+            TemplateEngine::validate("{{sessions_section}} {{date", TemplateKind::DailySummary);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, TemplateWarningKind::UnclosedPlaceholder);
+    }
+
+    #[test]
+    fn test_template_kind_names_roundtrip() {
+        for kind in TemplateKind::ALL {
+            assert_eq!(TemplateKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(TemplateKind::from_name("nope"), None);
+    }
+
     #[test]
     fn test_extract_variables_empty() {
         let template = "No variables here";
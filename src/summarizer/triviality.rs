@@ -0,0 +1,145 @@
+//! Triviality pre-pass before session summarization.
+//!
+//! Many sessions are a question and an answer that don't merit a full
+//! summary. A session within `summarization.triviality.max_turns` prompts
+//! and `max_tokens` estimated transcript tokens, that changed no files, is
+//! archived with a stub summary rendered from `summary_template` instead.
+//! With `model_check`, the cheap `check_model` confirms each such session
+//! first, and the ones it calls substantive get the full summary.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::budget::estimate_tokens;
+use super::json;
+use super::template::TemplateEngine;
+use crate::config::TrivialityConfig;
+use crate::transcript::TranscriptData;
+
+/// Characters of the first prompt kept in the stub summary
+const FIRST_PROMPT_CHARS: usize = 200;
+
+/// Words of the first prompt used as a trivial session's topic
+const TOPIC_WORDS: usize = 6;
+
+/// Whether the session is small enough to be trivial by the configured
+/// thresholds; `transcript_text` is the condensed transcript
+pub fn within_thresholds(
+    data: &TranscriptData,
+    transcript_text: &str,
+    config: &TrivialityConfig,
+) -> bool {
+    config.enabled
+        && data.files_modified.is_empty()
+        && prompts(data).count() <= config.max_turns
+        && estimate_tokens(transcript_text) <= config.max_tokens
+}
+
+/// Non-blank prompts of the session
+fn prompts(data: &TranscriptData) -> impl Iterator<Item = &str> {
+    data.user_messages
+        .iter()
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+}
+
+/// Summary of a trivial session from `template`
+pub fn stub_summary(template: &str, data: &TranscriptData, project: &str) -> String {
+    let first = prompts(data).next().unwrap_or_default();
+    let first_line = first.lines().next().unwrap_or_default();
+    let mut first_prompt: String = first_line.chars().take(FIRST_PROMPT_CHARS).collect();
+    if first_prompt.len() < first.len() {
+        first_prompt.push('…');
+    }
+    let turns = prompts(data).count().to_string();
+    let variables = HashMap::from([
+        ("first_prompt", first_prompt.as_str()),
+        ("turns", turns.as_str()),
+        ("project", project),
+    ]);
+    TemplateEngine::render(template, &variables)
+}
+
+/// Topic of a trivial session: the first words of its first prompt
+pub fn stub_topic(data: &TranscriptData) -> String {
+    prompts(data)
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .take(TOPIC_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckResponse {
+    trivial: bool,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Verdict of the `check_model` pre-pass: whether the session is trivial,
+/// and why
+pub fn parse_check(response: &str) -> Result<(bool, String)> {
+    let check: CheckResponse = json::parse(response).context("Failed to parse triviality check")?;
+    Ok((check.trivial, check.reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(prompts: &[&str], files: &[&str]) -> TranscriptData {
+        TranscriptData {
+            entries: vec![],
+            user_messages: prompts.iter().map(|p| p.to_string()).collect(),
+            assistant_messages: vec!["Sure.".into()],
+            tool_calls: vec![],
+            files_modified: files.iter().map(|f| f.to_string()).collect(),
+            subagent_prompts: vec![],
+            summary: None,
+            user_message_lines: vec![],
+            line_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_triviality() {
+        let config = TrivialityConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let quick = data(&["what does `git rebase --onto` do?", " "], &[]);
+        assert!(within_thresholds(&quick, "short", &config));
+        assert!(!within_thresholds(&quick, &"word ".repeat(5000), &config));
+        assert!(!within_thresholds(
+            &data(&["a", "b", "c"], &[]),
+            "short",
+            &config
+        ));
+        assert!(!within_thresholds(
+            &data(&["fix it"], &["src/main.rs"]),
+            "short",
+            &config
+        ));
+        assert!(!within_thresholds(
+            &quick,
+            "short",
+            &TrivialityConfig::default()
+        ));
+
+        assert_eq!(
+            stub_summary(&config.summary_template, &quick, "app"),
+            "Short session (1 prompt(s)) in app: what does `git rebase --onto` do?"
+        );
+        assert_eq!(stub_topic(&quick), "what does `git rebase --onto` do?");
+
+        assert_eq!(
+            parse_check("```json\n{\"trivial\": false, \"reason\": \"design discussion\"}\n```")
+                .unwrap(),
+            (false, "design discussion".to_string())
+        );
+        assert!(parse_check("not sure").is_err());
+    }
+}
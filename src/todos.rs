@@ -0,0 +1,310 @@
+//! Quick TODOs captured with `daily todo add`.
+//!
+//! TODOs are kept in `{storage}/todos.json`. Each digest carries the open
+//! ones into its "Tomorrow's Focus" and checks off (✅) those the day's
+//! session summaries show as done; like goal progress, checks are recorded
+//! against the digest's date, so regenerating it re-evaluates that day.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::archive::SummaryCard;
+use crate::config::Config;
+use crate::storage;
+
+/// Words too common to tell whether a session did the TODO
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "onto", "that", "this", "then", "than", "add",
+    "fix", "make", "use", "get", "set", "update",
+];
+
+/// A TODO and, once done, how it was checked off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Todo {
+    pub id: u32,
+    pub text: String,
+    pub created: DateTime<Local>,
+    #[serde(default)]
+    pub done: Option<DateTime<Local>>,
+    /// Date of the digest that checked it off; `None` when marked done
+    /// with `daily todo done`
+    #[serde(default)]
+    pub done_on: Option<String>,
+    /// Session whose summary showed it done
+    #[serde(default)]
+    pub session: Option<String>,
+}
+
+impl Todo {
+    pub fn is_done(&self) -> bool {
+        self.done.is_some()
+    }
+}
+
+/// A TODO a digest found done in one of the day's sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoCheck {
+    pub todo: u32,
+    pub text: String,
+    pub session: String,
+}
+
+/// All TODOs, in order of capture
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TodoStore {
+    #[serde(default)]
+    pub todos: Vec<Todo>,
+}
+
+fn store_path(config: &Config) -> PathBuf {
+    config.storage_path().join("todos.json")
+}
+
+impl TodoStore {
+    /// Saved TODOs; none if the file doesn't exist yet
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = store_path(config);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = store_path(config);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        storage::write_file(config, &path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add an open TODO and return it
+    pub fn add(&mut self, text: &str) -> Result<&Todo> {
+        let text = text.trim();
+        if text.is_empty() {
+            anyhow::bail!("TODO text is empty");
+        }
+        let id = self.todos.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        self.todos.push(Todo {
+            id,
+            text: text.to_string(),
+            created: Local::now(),
+            done: None,
+            done_on: None,
+            session: None,
+        });
+        Ok(self.todos.last().unwrap())
+    }
+
+    /// Mark a TODO done by hand
+    pub fn complete(&mut self, id: u32) -> Result<&Todo> {
+        let todo = self
+            .todos
+            .iter_mut()
+            .find(|t| t.id == id)
+            .with_context(|| format!("No TODO with id {}", id))?;
+        if todo.done.is_none() {
+            todo.done = Some(Local::now());
+        }
+        Ok(todo)
+    }
+
+    /// TODOs a digest of `date` should consider: open ones, and those a
+    /// previous digest of the same date checked off
+    pub fn pending<'a>(&'a self, date: &'a str) -> impl Iterator<Item = &'a Todo> + 'a {
+        self.todos
+            .iter()
+            .filter(move |t| !t.is_done() || t.done_on.as_deref() == Some(date))
+    }
+
+    /// Pending TODOs that one of the `(name, summary)` sessions shows done
+    pub fn check(&self, date: &str, sessions: &[(String, String)]) -> Vec<TodoCheck> {
+        self.pending(date)
+            .filter_map(|todo| {
+                let (name, _) = sessions
+                    .iter()
+                    .find(|(_, summary)| mentions(summary, &todo.text))?;
+                Some(TodoCheck {
+                    todo: todo.id,
+                    text: todo.text.clone(),
+                    session: name.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Replace the checks recorded for `date` with a digest's; TODOs done
+    /// by hand are left alone
+    pub fn record(&mut self, date: &str, checks: &[TodoCheck]) {
+        let now = Local::now();
+        for todo in &mut self.todos {
+            if todo.done_on.as_deref() == Some(date) {
+                todo.done = None;
+                todo.done_on = None;
+                todo.session = None;
+            }
+            if todo.is_done() {
+                continue;
+            }
+            if let Some(check) = checks.iter().find(|c| c.todo == todo.id) {
+                todo.done = Some(now);
+                todo.done_on = Some(date.to_string());
+                todo.session = Some(check.session.clone());
+            }
+        }
+    }
+}
+
+/// Whether `summary` mentions every significant word of `text`. Text with
+/// no such word (e.g. Chinese without spaces) must appear as a whole.
+pub fn mentions(summary: &str, text: &str) -> bool {
+    let summary = summary.to_lowercase();
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3 && !STOP_WORDS.contains(w))
+        .collect();
+    if words.is_empty() {
+        return summary.contains(text.trim());
+    }
+    words.iter().all(|w| summary.contains(w))
+}
+
+/// Bring the digest's Tomorrow's Focus in line with the TODO list: cards
+/// for `checks` get ✅ in their title, and every `open` TODO has a card.
+/// Cards are matched on their title, ignoring case and a ✅.
+pub fn merge_focus(
+    cards: &mut Vec<SummaryCard>,
+    open: &[&str],
+    checks: &[TodoCheck],
+    language: &str,
+) {
+    let zh = language == "zh";
+    let position = |cards: &[SummaryCard], text: &str| {
+        cards.iter().position(|card| {
+            card.title
+                .trim()
+                .trim_start_matches('✅')
+                .trim()
+                .eq_ignore_ascii_case(text.trim())
+        })
+    };
+    for check in checks {
+        let title = format!("✅ {}", check.text);
+        match position(cards, &check.text) {
+            Some(i) => cards[i].title = title,
+            None => cards.push(SummaryCard {
+                title,
+                content: if zh {
+                    "已根据今天的会话自动勾选。"
+                } else {
+                    "Checked off from the day's sessions."
+                }
+                .to_string(),
+            }),
+        }
+    }
+    for text in open {
+        if checks.iter().any(|c| c.text == *text) || position(cards, text).is_some() {
+            continue;
+        }
+        cards.push(SummaryCard {
+            title: text.to_string(),
+            content: if zh {
+                "待办清单中尚未完成。"
+            } else {
+                "Still open on the TODO list."
+            }
+            .to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_and_record() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+
+        let mut store = TodoStore::default();
+        store.add("Fix the flaky login test").unwrap();
+        store.add("write release notes").unwrap();
+        store.add("升级依赖").unwrap();
+        assert!(store.add("  ").is_err());
+
+        let sessions = vec![
+            (
+                "09_10-auth".to_string(),
+                "Tracked down why the login test was flaky and fixed it".to_string(),
+            ),
+            (
+                "14_00-deps".to_string(),
+                "今天升级依赖并修复了构建".to_string(),
+            ),
+        ];
+        let checks = store.check("2024-01-15", &sessions);
+        let ids: Vec<u32> = checks.iter().map(|c| c.todo).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(checks[0].session, "09_10-auth");
+
+        store.complete(2).unwrap();
+        store.record("2024-01-15", &checks);
+        store.save(&config).unwrap();
+
+        let mut store = TodoStore::load(&config).unwrap();
+        assert!(store.todos.iter().all(Todo::is_done));
+        assert_eq!(store.todos[0].done_on.as_deref(), Some("2024-01-15"));
+        assert_eq!(store.todos[1].done_on, None);
+
+        // Regenerating the digest re-evaluates its own checks only
+        store.record("2024-01-15", &checks[..1]);
+        assert!(!store.todos[2].is_done());
+        assert!(store.todos[1].is_done());
+        assert_eq!(store.pending("2024-01-16").count(), 1);
+    }
+
+    #[test]
+    fn test_merge_focus() {
+        let card = |title: &str| SummaryCard {
+            title: title.into(),
+            content: "From the model".into(),
+        };
+        let mut cards = vec![card("Fix the flaky login test"), card("Review PR")];
+        let checks = vec![TodoCheck {
+            todo: 1,
+            text: "fix the flaky login test".into(),
+            session: "09_10-auth".into(),
+        }];
+        merge_focus(
+            &mut cards,
+            &[
+                "fix the flaky login test",
+                "write release notes",
+                "review pr",
+            ],
+            &checks,
+            "en",
+        );
+        let titles: Vec<&str> = cards.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "✅ fix the flaky login test",
+                "Review PR",
+                "write release notes"
+            ]
+        );
+        assert_eq!(cards[0].content, "From the model");
+    }
+}
@@ -0,0 +1,472 @@
+//! Knowledge base pages grouping sessions from all dates by topic.
+//!
+//! `{storage}/topics/topics.json` records which topic each session belongs
+//! to. An update sends the sessions not filed yet, with the existing topics,
+//! to the summarizer model, which puts each under an existing topic or a new
+//! one. Assignments are kept, so later updates only pay for new sessions.
+//! Every topic then gets a page (`topics/{slug}.md`) linking its sessions,
+//! their key decisions and skill ideas, and `topics/index.md` lists them all.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::daily::section;
+use crate::archive::layout::sanitize_topic;
+use crate::archive::session::{has_skill_hints, CHECKPOINT_PREFIX};
+use crate::archive::{frontmatter, ArchiveManager};
+use crate::config::Config;
+use crate::storage;
+use crate::summarizer::SummarizerEngine;
+
+/// Sessions sent to the model per call
+const BATCH_SIZE: usize = 40;
+
+/// Characters of each session's summary sent to the model
+const SUMMARY_CHARS: usize = 400;
+
+/// Decisions listed per session on a topic page
+const DECISIONS_PER_SESSION: usize = 3;
+
+/// A subject sessions from any date are filed under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Topic {
+    /// File name of the page, e.g. "authentication"
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// `date/name` of each session, oldest first
+    #[serde(default)]
+    pub sessions: Vec<String>,
+}
+
+/// All topics and when they were last refreshed
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TopicIndex {
+    #[serde(default)]
+    pub updated: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub topics: Vec<Topic>,
+}
+
+/// Topics the model created and where it filed each session
+#[derive(Debug, Default, Deserialize)]
+pub struct TopicAssignments {
+    #[serde(default)]
+    pub new_topics: Vec<NewTopic>,
+    #[serde(default)]
+    pub assignments: Vec<Assignment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewTopic {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Assignment {
+    /// `date/name`
+    pub session: String,
+    /// Slug of an existing or new topic
+    pub topic: String,
+}
+
+/// What an update did
+#[derive(Debug, Serialize)]
+pub struct UpdateStats {
+    pub topics: usize,
+    /// Sessions filed this run
+    pub assigned: usize,
+    /// Sessions the model left out; they are retried next time
+    pub unassigned: usize,
+}
+
+/// Where topic pages and the assignment file live
+pub fn topics_dir(config: &Config) -> PathBuf {
+    config.storage_path().join("topics")
+}
+
+fn index_path(config: &Config) -> PathBuf {
+    topics_dir(config).join("topics.json")
+}
+
+impl TopicIndex {
+    /// Saved topics; an empty index if none were built yet
+    pub fn load(config: &Config) -> Self {
+        fs::read_to_string(index_path(config))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, config: &Config) -> Result<()> {
+        let path = index_path(config);
+        fs::create_dir_all(topics_dir(config))?;
+        storage::write_file(config, &path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, slug: &str) -> Option<&Topic> {
+        self.topics.iter().find(|t| t.slug == slug)
+    }
+
+    fn assigned(&self) -> HashSet<&str> {
+        self.topics
+            .iter()
+            .flat_map(|t| t.sessions.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Forget sessions that no longer exist, and topics left without any
+    fn retain_sessions(&mut self, existing: &HashSet<String>) {
+        for topic in &mut self.topics {
+            topic.sessions.retain(|s| existing.contains(s));
+        }
+        self.topics.retain(|t| !t.sessions.is_empty());
+    }
+
+    /// Add the model's new topics and file the sessions of `batch` it
+    /// assigned; anything else it returned is ignored. Returns how many
+    /// sessions were filed.
+    fn apply(&mut self, response: TopicAssignments, batch: &HashSet<String>) -> usize {
+        for new in response.new_topics {
+            let slug = sanitize_topic(&new.slug);
+            if slug.is_empty() || self.get(&slug).is_some() {
+                continue;
+            }
+            self.topics.push(Topic {
+                slug,
+                title: new.title.trim().to_string(),
+                description: new.description.trim().to_string(),
+                sessions: Vec::new(),
+            });
+        }
+
+        let mut filed = 0;
+        for assignment in response.assignments {
+            let session = assignment.session.trim();
+            if !batch.contains(session) || self.assigned().contains(session) {
+                continue;
+            }
+            let slug = sanitize_topic(&assignment.topic);
+            if let Some(topic) = self.topics.iter_mut().find(|t| t.slug == slug) {
+                topic.sessions.push(session.to_string());
+                topic.sessions.sort();
+                filed += 1;
+            }
+        }
+        self.topics.retain(|t| !t.sessions.is_empty());
+        self.topics.sort_by(|a, b| a.title.cmp(&b.title));
+        filed
+    }
+}
+
+/// Whether `topics.interval_hours` have passed since the last update
+pub fn is_due(config: &Config) -> bool {
+    TopicIndex::load(config).updated.is_none_or(|updated| {
+        Local::now() - updated >= chrono::Duration::hours(config.topics.interval_hours.into())
+    })
+}
+
+/// File new sessions under topics and rewrite every topic page. With
+/// `rebuild`, existing assignments are dropped and all sessions refiled.
+pub async fn update(
+    config: &Config,
+    engine: &SummarizerEngine,
+    rebuild: bool,
+) -> Result<UpdateStats> {
+    let manager = ArchiveManager::new(config.clone());
+    let mut index = if rebuild {
+        TopicIndex::default()
+    } else {
+        TopicIndex::load(config)
+    };
+
+    let sessions = all_sessions(&manager)?;
+    let existing: HashSet<String> = sessions.iter().map(|(r, _)| r.clone()).collect();
+    index.retain_sessions(&existing);
+
+    let assigned = index.assigned();
+    let pending: Vec<&(String, String)> = sessions
+        .iter()
+        .filter(|(r, _)| !assigned.contains(r.as_str()))
+        .collect();
+
+    let mut filed = 0;
+    let batches = pending.len().div_ceil(BATCH_SIZE);
+    for (i, batch) in pending.chunks(BATCH_SIZE).enumerate() {
+        eprintln!(
+            "[daily] Topics progress: batch {}/{} ({} sessions)",
+            i + 1,
+            batches,
+            batch.len()
+        );
+        let briefs: Vec<serde_json::Value> = batch
+            .iter()
+            .map(|(reference, content)| {
+                serde_json::json!({
+                    "session": reference,
+                    "title": frontmatter::get(content, "title").unwrap_or_default(),
+                    "summary": section(content, "Summary")
+                        .unwrap_or_default()
+                        .chars()
+                        .take(SUMMARY_CHARS)
+                        .collect::<String>(),
+                })
+            })
+            .collect();
+        let topics: Vec<serde_json::Value> = index
+            .topics
+            .iter()
+            .map(|t| serde_json::json!({"slug": t.slug, "title": t.title, "description": t.description}))
+            .collect();
+
+        let response = engine
+            .assign_topics(
+                &serde_json::to_string_pretty(&topics)?,
+                &serde_json::to_string_pretty(&briefs)?,
+            )
+            .await?;
+        let refs: HashSet<String> = batch.iter().map(|(r, _)| r.clone()).collect();
+        filed += index.apply(response, &refs);
+    }
+
+    write_pages(config, &manager, &index)?;
+    index.updated = Some(Local::now());
+    index.save(config)?;
+
+    Ok(UpdateStats {
+        topics: index.topics.len(),
+        assigned: filed,
+        unassigned: pending.len() - filed,
+    })
+}
+
+/// `(date/name, content)` of every session except checkpoints, oldest first
+fn all_sessions(manager: &ArchiveManager) -> Result<Vec<(String, String)>> {
+    let mut sessions = Vec::new();
+    for date in manager.list_dates()?.into_iter().rev() {
+        for name in manager.list_sessions(&date)? {
+            if name.starts_with(CHECKPOINT_PREFIX) {
+                continue;
+            }
+            if let Ok(content) = manager.read_session(&date, &name) {
+                sessions.push((format!("{}/{}", date, name), content));
+            }
+        }
+    }
+    Ok(sessions)
+}
+
+/// Write a page per topic and the index page, removing pages of topics that
+/// no longer exist
+fn write_pages(config: &Config, manager: &ArchiveManager, index: &TopicIndex) -> Result<()> {
+    let dir = topics_dir(config);
+    fs::create_dir_all(&dir)?;
+
+    let current: HashSet<String> = index
+        .topics
+        .iter()
+        .map(|t| format!("{}.md", t.slug))
+        .collect();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.ends_with(".md") && file_name != "index.md" && !current.contains(&file_name) {
+            storage::remove_file(config, &entry.path())?;
+        }
+    }
+
+    for topic in &index.topics {
+        storage::write_file(
+            config,
+            &dir.join(format!("{}.md", topic.slug)),
+            render_page(config, manager, topic),
+        )?;
+    }
+    storage::write_file(config, &dir.join("index.md"), render_index(index))
+}
+
+/// Markdown page of one topic; links are relative to the topics directory
+fn render_page(config: &Config, manager: &ArchiveManager, topic: &Topic) -> String {
+    let mut sessions = String::new();
+    let mut decisions = String::new();
+    let mut skills = String::new();
+
+    for reference in topic.sessions.iter().rev() {
+        let Some((date, name)) = reference.split_once('/') else {
+            continue;
+        };
+        let Ok(content) = manager.read_session(date, name) else {
+            continue;
+        };
+        let title = frontmatter::get(&content, "title").unwrap_or(name);
+        let link = format!("[{}]({})", title, session_link(config, date, name));
+        sessions.push_str(&format!("- {} · {}\n", date, link));
+
+        for decision in
+            bullets(section(&content, "Key Decisions & Trade-offs")).take(DECISIONS_PER_SESSION)
+        {
+            decisions.push_str(&format!("- {} ({})\n", decision, link));
+        }
+
+        let hints = section(&content, "Potential Skills/Commands")
+            .map(|s| s.split("\n---").next().unwrap_or_default());
+        if hints.is_some_and(has_skill_hints) {
+            for hint in bullets(hints) {
+                skills.push_str(&format!("- {} ({})\n", hint, link));
+            }
+        }
+    }
+
+    let none = "_None yet._\n";
+    format!(
+        "---\ntitle: \"{title}\"\ntags: [topic, claude-code]\nsession_count: {count}\nupdated: {updated}\n---\n\n\
+         # {title}\n\n{description}\n\n\
+         ## Sessions\n\n{sessions}\n\
+         ## Key Decisions\n\n{decisions}\n\
+         ## Skills & Commands\n\n{skills}",
+        title = topic.title,
+        count = topic.sessions.len(),
+        updated = Local::now().to_rfc3339(),
+        description = topic.description,
+        sessions = sessions,
+        decisions = if decisions.is_empty() { none } else { &decisions },
+        skills = if skills.is_empty() { none } else { &skills },
+    )
+}
+
+fn render_index(index: &TopicIndex) -> String {
+    let mut out = String::from("# Topics\n\n");
+    for topic in &index.topics {
+        out.push_str(&format!(
+            "- [{}]({}.md) ({} sessions) - {}\n",
+            topic.title,
+            topic.slug,
+            topic.sessions.len(),
+            topic.description
+        ));
+    }
+    out
+}
+
+/// Path of a session archive relative to the topics directory
+fn session_link(config: &Config, date: &str, name: &str) -> String {
+    let storage = config.storage_path();
+    let date_dir = config.date_dir(date);
+    let relative = date_dir.strip_prefix(&storage).unwrap_or(Path::new(date));
+    format!("../{}/{}.md", relative.to_string_lossy(), name)
+}
+
+/// Top-level list items of a section, without their markers
+pub(crate) fn bullets(section: Option<&str>) -> impl Iterator<Item = &str> {
+    section
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_apply_and_render_topics() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        let manager = ArchiveManager::new(config.clone());
+        manager
+            .write_session(
+                "2024-01-02",
+                "10_00-login",
+                "---\ntitle: \"Login flow\"\n---\n\n## Summary\n\nReworked login.\n\n\
+                 ## Key Decisions & Trade-offs\n\n- Kept sessions in cookies\n- Dropped JWT\n\n\
+                 ## Potential Skills/Commands\n\nNone identified\n\n---\n*Archived*\n",
+            )
+            .unwrap();
+
+        let mut index = TopicIndex::default();
+        let batch: HashSet<String> = ["2024-01-02/10_00-login".to_string()].into();
+        let filed = index.apply(
+            TopicAssignments {
+                new_topics: vec![
+                    NewTopic {
+                        slug: "Authentication".into(),
+                        title: "Authentication".into(),
+                        description: "Login and sessions".into(),
+                    },
+                    NewTopic {
+                        slug: "unused".into(),
+                        title: "Unused".into(),
+                        description: String::new(),
+                    },
+                ],
+                assignments: vec![
+                    Assignment {
+                        session: "2024-01-02/10_00-login".into(),
+                        topic: "authentication".into(),
+                    },
+                    Assignment {
+                        session: "2024-01-02/not-in-batch".into(),
+                        topic: "authentication".into(),
+                    },
+                ],
+            },
+            &batch,
+        );
+        assert_eq!(filed, 1);
+        assert_eq!(index.topics.len(), 1);
+        assert_eq!(index.topics[0].slug, "authentication");
+
+        let page = render_page(&config, &manager, &index.topics[0]);
+        assert!(page.contains("- 2024-01-02 · [Login flow](../2024-01-02/10_00-login.md)"));
+        assert!(page.contains("- Dropped JWT ([Login flow]"));
+        assert!(page.contains("## Skills & Commands\n\n_None yet._"));
+
+        index.retain_sessions(&HashSet::new());
+        assert!(index.topics.is_empty());
+    }
+
+    #[test]
+    fn test_topic_and_goal_writes_are_queued_for_sync() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().to_path_buf();
+        config.storage.backend = "s3".into();
+        let manager = ArchiveManager::new(config.clone());
+        let index = TopicIndex {
+            updated: None,
+            topics: vec![Topic {
+                slug: "authentication".into(),
+                title: "Authentication".into(),
+                description: String::new(),
+                sessions: Vec::new(),
+            }],
+        };
+        index.save(&config).unwrap();
+        write_pages(&config, &manager, &index).unwrap();
+        let mut goals = crate::goals::GoalStore::default();
+        goals.add("ship billing").unwrap();
+        goals.save(&config).unwrap();
+
+        let journal = fs::read_to_string(dir.path().join(".storage-pending")).unwrap();
+        for key in [
+            "topics/topics.json",
+            "topics/authentication.md",
+            "topics/index.md",
+            "goals.json",
+        ] {
+            assert!(journal.contains(&format!("put {}\n", key)), "{}", key);
+        }
+    }
+}
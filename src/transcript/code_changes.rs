@@ -0,0 +1,282 @@
+//! Files a session changed, from its Edit/Write tool calls.
+//!
+//! Line counts are estimates: an edit adds the lines of its new text and
+//! removes those of the old, and a Write counts every line as added since
+//! the previous content isn't in the transcript.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::TranscriptData;
+
+/// Changes made to one file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    /// Language guessed from the file extension, if known
+    pub language: Option<&'static str>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Totals for one language across changed files
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageChange {
+    pub language: &'static str,
+    pub files: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Files changed by a session, in the order first touched
+#[derive(Debug, Clone, Default)]
+pub struct CodeChanges {
+    pub files: Vec<FileChange>,
+}
+
+impl CodeChanges {
+    /// Collect the Edit, MultiEdit, Write and NotebookEdit calls of a
+    /// transcript, sub-agents included. Files known only by name (from
+    /// imported logs) are listed without line counts.
+    pub fn collect(data: &TranscriptData) -> Self {
+        let mut changes = Self::default();
+        for entry in &data.entries {
+            let blocks = entry
+                .extra
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .and_then(Value::as_array);
+            for block in blocks.into_iter().flatten() {
+                if block.get("type").and_then(Value::as_str) != Some("tool_use") {
+                    continue;
+                }
+                if let (Some(name), Some(input)) = (
+                    block.get("name").and_then(Value::as_str),
+                    block.get("input"),
+                ) {
+                    changes.record(name, input);
+                }
+            }
+        }
+        for call in &data.tool_calls {
+            changes.record(&call.name, &call.input);
+        }
+        for path in &data.files_modified {
+            changes.file(path);
+        }
+        changes
+    }
+
+    fn record(&mut self, tool: &str, input: &Value) {
+        let text = |key: &str| input.get(key).and_then(Value::as_str).unwrap_or("");
+        let (path, added, removed) = match tool {
+            "Edit" => (
+                text("file_path"),
+                line_count(text("new_string")),
+                line_count(text("old_string")),
+            ),
+            "MultiEdit" => {
+                let edits = input.get("edits").and_then(Value::as_array);
+                let lines = |key: &str| -> usize {
+                    edits
+                        .into_iter()
+                        .flatten()
+                        .map(|e| line_count(e.get(key).and_then(Value::as_str).unwrap_or("")))
+                        .sum()
+                };
+                (text("file_path"), lines("new_string"), lines("old_string"))
+            }
+            "Write" => (text("file_path"), line_count(text("content")), 0),
+            "NotebookEdit" => (text("notebook_path"), line_count(text("new_source")), 0),
+            _ => return,
+        };
+        if path.is_empty() {
+            return;
+        }
+        let file = self.file(path);
+        file.added += added;
+        file.removed += removed;
+    }
+
+    fn file(&mut self, path: &str) -> &mut FileChange {
+        let index = match self.files.iter().position(|f| f.path == path) {
+            Some(index) => index,
+            None => {
+                self.files.push(FileChange {
+                    path: path.to_string(),
+                    language: language_of(path),
+                    ..Default::default()
+                });
+                self.files.len() - 1
+            }
+        };
+        &mut self.files[index]
+    }
+
+    /// Per-language totals, most lines changed first
+    pub fn languages(&self) -> Vec<LanguageChange> {
+        let mut totals: BTreeMap<&'static str, LanguageChange> = BTreeMap::new();
+        for file in &self.files {
+            let Some(language) = file.language else {
+                continue;
+            };
+            let total = totals.entry(language).or_insert(LanguageChange {
+                language,
+                files: 0,
+                added: 0,
+                removed: 0,
+            });
+            total.files += 1;
+            total.added += file.added;
+            total.removed += file.removed;
+        }
+        let mut languages: Vec<LanguageChange> = totals.into_values().collect();
+        languages.sort_by_key(|l| std::cmp::Reverse(l.added + l.removed));
+        languages
+    }
+
+    /// Body of an archive's "Code Changes" section
+    pub fn to_markdown(&self) -> String {
+        if self.files.is_empty() {
+            return "_No files modified._".to_string();
+        }
+        let mut lines: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| {
+                let mut line = format!("- `{}`", f.path);
+                if let Some(language) = f.language {
+                    line.push_str(&format!(" ({})", language));
+                }
+                if f.added + f.removed > 0 {
+                    line.push_str(&format!(" +{} −{}", f.added, f.removed));
+                }
+                line
+            })
+            .collect();
+
+        let added: usize = self.files.iter().map(|f| f.added).sum();
+        let removed: usize = self.files.iter().map(|f| f.removed).sum();
+        lines.push(String::new());
+        lines.push(format!(
+            "**Total**: {}, about +{} −{} lines",
+            file_count(self.files.len()),
+            added,
+            removed
+        ));
+        let languages = self.languages();
+        if !languages.is_empty() {
+            let breakdown: Vec<String> = languages
+                .iter()
+                .map(|l| {
+                    format!(
+                        "{} ({}, +{} −{})",
+                        l.language,
+                        file_count(l.files),
+                        l.added,
+                        l.removed
+                    )
+                })
+                .collect();
+            lines.push(format!("**Languages**: {}", breakdown.join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+fn file_count(files: usize) -> String {
+    match files {
+        1 => "1 file".to_string(),
+        n => format!("{} files", n),
+    }
+}
+
+fn line_count(text: &str) -> usize {
+    text.lines().count()
+}
+
+/// Programming language of a file, by extension
+pub fn language_of(path: &str) -> Option<&'static str> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    if name == "Dockerfile" {
+        return Some("Dockerfile");
+    }
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" | "pyi" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "scala" => "Scala",
+        "ex" | "exs" => "Elixir",
+        "dart" => "Dart",
+        "lua" => "Lua",
+        "sh" | "bash" | "zsh" | "fish" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "md" | "mdx" => "Markdown",
+        "json" | "jsonc" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "ipynb" => "Jupyter",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TranscriptParser;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_code_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"Fix the parser"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"src/parser.rs","old_string":"a\nb","new_string":"a\nb\nc"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Write","input":{"file_path":"web/App.tsx","content":"x\ny"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"MultiEdit","input":{"file_path":"src/parser.rs","edits":[{"old_string":"d","new_string":""}]}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Read","input":{"file_path":"README"}}]}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let data = TranscriptParser::parse(&path).unwrap();
+
+        let changes = CodeChanges::collect(&data);
+        assert_eq!(changes.files.len(), 2);
+        assert_eq!(
+            changes.files[0],
+            FileChange {
+                path: "src/parser.rs".into(),
+                language: Some("Rust"),
+                added: 3,
+                removed: 3,
+            }
+        );
+        let languages = changes.languages();
+        assert_eq!(languages[0].language, "Rust");
+        assert_eq!(languages[1].language, "TypeScript");
+        assert_eq!(languages[1].added, 2);
+
+        let markdown = changes.to_markdown();
+        assert!(markdown.contains("- `web/App.tsx` (TypeScript) +2 −0"));
+        assert!(markdown.contains("**Total**: 2 files, about +5 −3 lines"));
+        assert!(
+            markdown.contains("**Languages**: Rust (1 file, +3 −3), TypeScript (1 file, +2 −0)")
+        );
+        assert_eq!(language_of("Makefile"), None);
+    }
+}
@@ -1,4 +1,8 @@
+pub mod code_changes;
 mod parser;
+pub mod resume;
+pub mod sidechain;
 
+pub use parser::ToolCall;
 pub use parser::TranscriptData;
 pub use parser::TranscriptParser;
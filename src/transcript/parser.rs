@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
+use super::sidechain;
+use crate::archive::transcript;
+
 /// A single entry in the Claude Code transcript JSONL file
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TranscriptEntry {
@@ -21,8 +23,18 @@ pub struct TranscriptEntry {
     pub extra: std::collections::HashMap<String, serde_json::Value>,
 }
 
+impl TranscriptEntry {
+    /// Whether this entry belongs to a sub-agent sidechain
+    pub fn is_sidechain(&self) -> bool {
+        self.extra
+            .get("isSidechain")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+}
+
 /// Parsed transcript data with extracted information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[allow(dead_code)]
 pub struct TranscriptData {
     pub entries: Vec<TranscriptEntry>,
@@ -30,7 +42,14 @@ pub struct TranscriptData {
     pub assistant_messages: Vec<String>,
     pub tool_calls: Vec<ToolCall>,
     pub files_modified: Vec<String>,
+    /// Prompts given to sub-agents (Task tool sidechains)
+    pub subagent_prompts: Vec<String>,
     pub summary: Option<String>,
+    /// Line (1-based) of each of `user_messages` in the main transcript
+    /// file; empty for conversations imported from other tools
+    pub user_message_lines: Vec<usize>,
+    /// Lines in the main transcript file
+    pub line_count: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +72,18 @@ impl TranscriptData {
         // Check if all user messages are just whitespace
         self.user_messages.iter().all(|msg| msg.trim().is_empty())
     }
+
+    /// Working directory recorded by Claude Code on transcript entries
+    pub fn cwd(&self) -> Option<String> {
+        self.entries.iter().find_map(|entry| {
+            entry
+                .extra
+                .get("cwd")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        })
+    }
 }
 
 /// Parser for Claude Code transcript JSONL files
@@ -101,43 +132,79 @@ impl TranscriptParser {
         None
     }
 
-    /// Parse a transcript file and extract relevant information
+    /// Parse a transcript file and extract relevant information.
+    ///
+    /// Sub-agent transcripts stored next to the session are read too; their
+    /// prompts go to `subagent_prompts` instead of `user_messages`.
     pub fn parse<P: AsRef<Path>>(path: P) -> Result<TranscriptData> {
-        let file = File::open(path.as_ref()).context("Failed to open transcript file")?;
-        let reader = BufReader::new(file);
+        let path = path.as_ref();
+        let mut data = TranscriptData {
+            entries: Vec::new(),
+            user_messages: Vec::new(),
+            assistant_messages: Vec::new(),
+            tool_calls: Vec::new(),
+            files_modified: Vec::new(),
+            subagent_prompts: Vec::new(),
+            summary: None,
+            user_message_lines: Vec::new(),
+            line_count: 0,
+        };
+
+        let reader = transcript::open(path).context("Failed to open transcript file")?;
+        Self::parse_lines(reader, false, &mut data)?;
+
+        for sidechain_path in sidechain::files(path) {
+            // A broken sub-agent file shouldn't lose the main session
+            match transcript::open(&sidechain_path) {
+                Ok(reader) => Self::parse_lines(reader, true, &mut data)?,
+                Err(e) => eprintln!("[daily] Warning: {}", e),
+            }
+        }
 
-        let mut entries = Vec::new();
-        let mut user_messages = Vec::new();
-        let mut assistant_messages = Vec::new();
-        let mut tool_calls = Vec::new();
-        let mut files_modified = Vec::new();
-        let mut summary = None;
+        Ok(data)
+    }
 
-        for line in reader.lines() {
+    fn parse_lines(
+        reader: impl BufRead,
+        sidechain_file: bool,
+        data: &mut TranscriptData,
+    ) -> Result<()> {
+        for (index, line) in reader.lines().enumerate() {
             let line = line.context("Failed to read line")?;
+            if !sidechain_file {
+                data.line_count = index + 1;
+            }
             if line.trim().is_empty() {
                 continue;
             }
 
             match serde_json::from_str::<TranscriptEntry>(&line) {
                 Ok(entry) => {
+                    let is_sidechain = sidechain_file || entry.is_sidechain();
+
                     // Extract user messages
                     // Support both old format (role: "user") and new format (type: "user")
                     if entry.role.as_deref() == Some("user")
                         || entry.entry_type.as_deref() == Some("user")
                     {
                         if let Some(text) = Self::extract_message_content(&entry) {
-                            user_messages.push(text);
+                            if is_sidechain {
+                                data.subagent_prompts.push(text);
+                            } else {
+                                data.user_messages.push(text);
+                                data.user_message_lines.push(index + 1);
+                            }
                         }
                     }
 
                     // Extract assistant messages
                     // Support both old format (role: "assistant") and new format (type: "assistant")
-                    if entry.role.as_deref() == Some("assistant")
-                        || entry.entry_type.as_deref() == Some("assistant")
+                    if !is_sidechain
+                        && (entry.role.as_deref() == Some("assistant")
+                            || entry.entry_type.as_deref() == Some("assistant"))
                     {
                         if let Some(text) = Self::extract_message_content(&entry) {
-                            assistant_messages.push(text);
+                            data.assistant_messages.push(text);
                         }
                     }
 
@@ -155,22 +222,22 @@ impl TranscriptParser {
                                 if let Some(file_path) =
                                     input.get("file_path").and_then(|v| v.as_str())
                                 {
-                                    if !files_modified.contains(&file_path.to_string()) {
-                                        files_modified.push(file_path.to_string());
+                                    if !data.files_modified.contains(&file_path.to_string()) {
+                                        data.files_modified.push(file_path.to_string());
                                     }
                                 }
                             }
                         }
 
-                        tool_calls.push(tool_call);
+                        data.tool_calls.push(tool_call);
                     }
 
                     // Extract summary if present
-                    if entry.entry_type.as_deref() == Some("TranscriptSummary") {
-                        summary = entry.summary.clone();
+                    if !is_sidechain && entry.entry_type.as_deref() == Some("TranscriptSummary") {
+                        data.summary = entry.summary.clone();
                     }
 
-                    entries.push(entry);
+                    data.entries.push(entry);
                 }
                 Err(e) => {
                     // Log but don't fail on parse errors for individual lines
@@ -178,15 +245,7 @@ impl TranscriptParser {
                 }
             }
         }
-
-        Ok(TranscriptData {
-            entries,
-            user_messages,
-            assistant_messages,
-            tool_calls,
-            files_modified,
-            summary,
-        })
+        Ok(())
     }
 
     /// Get a condensed text representation of the transcript for summarization
@@ -224,6 +283,15 @@ impl TranscriptParser {
             text.push('\n');
         }
 
+        // Add sub-agent work
+        if !data.subagent_prompts.is_empty() {
+            text.push_str("## Sub-agent Tasks\n\n");
+            for prompt in &data.subagent_prompts {
+                text.push_str(&format!("- {}\n", truncate_text(prompt, 200)));
+            }
+            text.push('\n');
+        }
+
         // Add existing summary if available
         if let Some(summary) = &data.summary {
             text.push_str("## Existing Summary\n\n");
@@ -263,7 +331,10 @@ mod tests {
             assistant_messages: vec![],
             tool_calls: vec![],
             files_modified: vec![],
+            subagent_prompts: vec![],
             summary: None,
+            user_message_lines: vec![],
+            line_count: 0,
         }
     }
 
@@ -287,6 +358,17 @@ mod tests {
         assert!(!data.is_empty());
     }
 
+    #[test]
+    fn test_cwd_from_entries() {
+        let mut data = create_empty_transcript_data();
+        data.entries = vec![
+            serde_json::from_str(r#"{"type":"summary","summary":"x"}"#).unwrap(),
+            serde_json::from_str(r#"{"type":"user","cwd":"/home/u/project"}"#).unwrap(),
+        ];
+        assert_eq!(data.cwd(), Some("/home/u/project".to_string()));
+        assert_eq!(create_empty_transcript_data().cwd(), None);
+    }
+
     #[test]
     fn test_is_empty_mixed_messages() {
         let mut data = create_empty_transcript_data();
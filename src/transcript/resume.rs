@@ -0,0 +1,146 @@
+//! Resume chains: `claude --resume` starts a new JSONL that replays the earlier
+//! conversation, so one logical session ends up in several transcripts.
+//!
+//! The replayed entries keep their original `sessionId`, which names the
+//! sessions being resumed. Transcripts without that field are matched by an
+//! identical first entry `uuid` in an older transcript of the same project.
+
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+use super::parser::TranscriptData;
+use super::sidechain;
+use crate::archive::transcript;
+
+/// Sessions that the transcript at `path` resumes, oldest first
+pub fn parents(path: &Path, data: &TranscriptData) -> Vec<String> {
+    let Some(own_id) = sidechain::session_stem(path) else {
+        return Vec::new();
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    for entry in data.entries.iter().filter(|e| !e.is_sidechain()) {
+        let Some(id) = entry.extra.get("sessionId").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if id != own_id && !ids.iter().any(|known| known == id) {
+            ids.push(id.to_string());
+        }
+    }
+    if !ids.is_empty() {
+        return ids;
+    }
+
+    prefix_parents(path, own_id, data)
+}
+
+/// Older sibling transcripts starting with the same entry as this one
+fn prefix_parents(path: &Path, own_id: &str, data: &TranscriptData) -> Vec<String> {
+    let Some(first_uuid) = data
+        .entries
+        .iter()
+        .find_map(|e| e.extra.get("uuid").and_then(|v| v.as_str()))
+    else {
+        return Vec::new();
+    };
+    let (Some(dir), Ok(modified)) = (path.parent(), fs::metadata(path).and_then(|m| m.modified()))
+    else {
+        return Vec::new();
+    };
+
+    let mut parents: Vec<(std::time::SystemTime, String)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && !sidechain::is_sidechain_file(p))
+        .filter_map(|p| {
+            let id = sidechain::session_stem(&p)?.to_string();
+            let sibling_modified = fs::metadata(&p).and_then(|m| m.modified()).ok()?;
+            (id != own_id && sibling_modified < modified && first_uuid_of(&p)? == first_uuid)
+                .then_some((sibling_modified, id))
+        })
+        .collect();
+    parents.sort();
+    parents.into_iter().map(|(_, id)| id).collect()
+}
+
+fn first_uuid_of(path: &Path) -> Option<String> {
+    transcript::open(path)
+        .ok()?
+        .lines()
+        .map_while(Result::ok)
+        .take(20)
+        .find_map(|line| {
+            let entry: serde_json::Value = serde_json::from_str(&line).ok()?;
+            entry.get("uuid").and_then(|v| v.as_str()).map(String::from)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::TranscriptParser;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parents_from_session_ids() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("c.jsonl");
+        fs::write(
+            &path,
+            [
+                r#"{"type":"user","sessionId":"a","uuid":"1","message":{"content":"hi"}}"#,
+                r#"{"type":"user","sessionId":"b","uuid":"2","message":{"content":"again"}}"#,
+                r#"{"type":"user","sessionId":"a","isSidechain":true,"message":{"content":"x"}}"#,
+                r#"{"type":"user","sessionId":"c","uuid":"3","message":{"content":"more"}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let data = TranscriptParser::parse(&path).unwrap();
+        assert_eq!(parents(&path, &data), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_parents_from_identical_prefix() {
+        let dir = TempDir::new().unwrap();
+        let first = r#"{"type":"user","uuid":"u1","message":{"content":"hi"}}"#;
+        let older = dir.path().join("old.jsonl");
+        let other = dir.path().join("other.jsonl");
+        fs::write(&older, first).unwrap();
+        fs::write(
+            &other,
+            r#"{"type":"user","uuid":"u9","message":{"content":"unrelated"}}"#,
+        )
+        .unwrap();
+
+        let resumed = dir.path().join("new.jsonl");
+        fs::write(
+            &resumed,
+            format!(
+                "{}\n{}",
+                first, r#"{"type":"user","uuid":"u2","message":{"content":"continue"}}"#
+            ),
+        )
+        .unwrap();
+        // The resumed transcript is strictly newer, even where mtimes are coarse
+        let now = SystemTime::now();
+        for (path, age) in [(&older, 120), (&other, 60), (&resumed, 0)] {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+
+        let data = TranscriptParser::parse(&resumed).unwrap();
+        assert_eq!(parents(&resumed, &data), vec!["old"]);
+        let data = TranscriptParser::parse(&older).unwrap();
+        assert!(parents(&older, &data).is_empty());
+    }
+}
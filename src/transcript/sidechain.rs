@@ -0,0 +1,167 @@
+//! Sub-agent (Task tool) transcripts, which Claude Code records as sidechains.
+//!
+//! Sidechain entries carry `isSidechain: true` and live either inline in the
+//! session JSONL (older versions) or in separate files: `agent-*.jsonl` next to
+//! the session whose `sessionId` is the parent, or `{session_id}/subagents/*.jsonl`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::archive::transcript;
+
+/// Tool names that launch a sub-agent
+pub const AGENT_TOOLS: [&str; 2] = ["Task", "Agent"];
+
+/// Whether a transcript entry belongs to a sidechain
+pub fn is_sidechain(entry: &serde_json::Value) -> bool {
+    entry
+        .get("isSidechain")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Sub-agent id of a sidechain entry, if recorded
+pub fn agent_id(entry: &serde_json::Value) -> Option<&str> {
+    entry.get("agentId").and_then(|v| v.as_str())
+}
+
+/// Session id from a transcript path: file name without `.jsonl` / `.jsonl.zst`
+pub fn session_stem(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    let name = name.strip_suffix(".zst").unwrap_or(name);
+    name.strip_suffix(".jsonl")
+}
+
+/// Whether a JSONL file holds a sub-agent transcript rather than a session
+pub fn is_sidechain_file(path: &Path) -> bool {
+    session_stem(path).is_some_and(|stem| stem.starts_with("agent-"))
+        || path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .is_some_and(|name| name == "subagents")
+}
+
+/// Session a sidechain file belongs to: the folder above `subagents/`, or the
+/// `sessionId` recorded on its entries
+pub fn parent_session_id(path: &Path) -> Option<String> {
+    let dir = path.parent()?;
+    if dir.file_name().is_some_and(|name| name == "subagents") {
+        return dir
+            .parent()
+            .and_then(|session_dir| session_dir.file_name())
+            .and_then(|name| name.to_str())
+            .map(String::from);
+    }
+    transcript::open(path)
+        .ok()?
+        .lines()
+        .map_while(Result::ok)
+        .take(20)
+        .find_map(|line| {
+            let entry: serde_json::Value = serde_json::from_str(&line).ok()?;
+            entry
+                .get("sessionId")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        })
+}
+
+/// Sub-agent transcript files belonging to the session at `transcript_path`
+pub fn files(transcript_path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(session_id)) = (transcript_path.parent(), session_stem(transcript_path))
+    else {
+        return Vec::new();
+    };
+
+    let mut files = jsonl_files(&dir.join(session_id).join("subagents"));
+    files.extend(
+        jsonl_files(dir)
+            .into_iter()
+            .filter(|path| session_stem(path).is_some_and(|stem| stem.starts_with("agent-")))
+            .filter(|path| cached_parent_session_id(path).as_deref() == Some(session_id)),
+    );
+    files
+}
+
+/// [`parent_session_id`] of a sibling `agent-*.jsonl`, remembered for the
+/// process so parsing every session in a folder opens each agent file once
+/// rather than once per session. Only found ids are kept, as a file just
+/// created may not have its first entry yet.
+fn cached_parent_session_id(path: &Path) -> Option<String> {
+    static PARENTS: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+    let parents = PARENTS.get_or_init(Default::default);
+    let cached = parents.lock().ok().and_then(|p| p.get(path).cloned());
+    if cached.is_some() {
+        return cached;
+    }
+    let id = parent_session_id(path)?;
+    if let Ok(mut parents) = parents.lock() {
+        parents.insert(path.to_path_buf(), id.clone());
+    }
+    Some(id)
+}
+
+fn jsonl_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && session_stem(p).is_some())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sidechain_files() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("abc.jsonl");
+        fs::write(&transcript, "{}\n").unwrap();
+
+        let subagents = dir.path().join("abc").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        fs::write(subagents.join("agent-1.jsonl"), "{}\n").unwrap();
+        fs::write(
+            dir.path().join("agent-2.jsonl"),
+            "{\"isSidechain\":true,\"sessionId\":\"abc\"}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("agent-3.jsonl"),
+            "{\"isSidechain\":true,\"sessionId\":\"other\"}\n",
+        )
+        .unwrap();
+
+        let found = files(&transcript);
+        assert_eq!(
+            found,
+            vec![
+                subagents.join("agent-1.jsonl"),
+                dir.path().join("agent-2.jsonl")
+            ]
+        );
+        assert!(found.iter().all(|path| is_sidechain_file(path)));
+        assert!(!is_sidechain_file(&transcript));
+        assert_eq!(parent_session_id(&found[0]).as_deref(), Some("abc"));
+        assert_eq!(parent_session_id(&found[1]).as_deref(), Some("abc"));
+
+        // An agent file's parent is read once, then remembered
+        fs::write(
+            dir.path().join("agent-2.jsonl"),
+            "{\"isSidechain\":true,\"sessionId\":\"other\"}\n",
+        )
+        .unwrap();
+        assert_eq!(files(&transcript), found);
+    }
+}
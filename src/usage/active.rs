@@ -0,0 +1,194 @@
+//! Sessions receiving messages right now.
+//!
+//! A session counts as active while its transcript (or one of its sub-agent
+//! transcripts) was written within the last few minutes. Transcripts come
+//! from the prompt heartbeats of the UserPromptSubmit hook plus a scan of
+//! `~/.claude/projects/`, so sessions started before the hooks were
+//! installed show up too.
+
+use chrono::{DateTime, Duration, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::pricing::PricingData;
+use super::scanner::{self, collect_jsonl_files, decode_project_dir};
+use crate::archive::paths::PathFilter;
+use crate::config::Config;
+use crate::hooks::activity;
+use crate::transcript::sidechain;
+
+/// Minutes without a transcript write after which a session is idle
+pub const DEFAULT_WINDOW_MINUTES: u64 = 5;
+
+/// A session whose transcript is still being written
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSession {
+    pub session_id: String,
+    /// Working directory of the session, or the project decoded from its folder
+    pub project: Option<String>,
+    pub transcript_path: PathBuf,
+    pub started_at: Option<DateTime<Local>>,
+    pub last_activity_at: DateTime<Local>,
+    /// Seconds since `started_at` (or since the transcript was created)
+    pub elapsed_seconds: i64,
+    /// Prompts seen by the UserPromptSubmit hook, if it is installed
+    pub prompt_count: Option<u64>,
+    /// Input, output and cache tokens so far, sub-agents included
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+/// Sessions with a transcript write in the last `window_minutes`, most
+/// recently active first
+pub fn active_sessions(
+    config: &Config,
+    pricing: &PricingData,
+    window_minutes: u64,
+    now: DateTime<Local>,
+) -> Vec<ActiveSession> {
+    let projects_dir = dirs::home_dir().map(|home| home.join(".claude").join("projects"));
+    collect(
+        config,
+        projects_dir.as_deref(),
+        pricing,
+        window_minutes,
+        now,
+    )
+}
+
+fn collect(
+    config: &Config,
+    projects_dir: Option<&Path>,
+    pricing: &PricingData,
+    window_minutes: u64,
+    now: DateTime<Local>,
+) -> Vec<ActiveSession> {
+    let since = now - Duration::minutes(window_minutes as i64);
+    let paths = PathFilter::from_config(config);
+
+    // Heartbeats first: they know the working directory and prompt count
+    let heartbeats: HashMap<String, activity::SessionActivity> =
+        activity::list(config).into_iter().collect();
+    let mut transcripts: HashMap<String, PathBuf> = heartbeats
+        .iter()
+        .map(|(id, a)| (id.clone(), a.transcript_path.clone()))
+        .collect();
+    if let Some(dir) = projects_dir.filter(|dir| dir.exists()) {
+        for path in collect_jsonl_files(&dir.to_path_buf()) {
+            if sidechain::is_sidechain_file(&path) {
+                continue;
+            }
+            if let Some(id) = sidechain::session_stem(&path) {
+                transcripts.entry(id.to_string()).or_insert(path);
+            }
+        }
+    }
+
+    let mut sessions: Vec<ActiveSession> = transcripts
+        .into_iter()
+        .filter_map(|(session_id, path)| {
+            let last_activity_at = last_write(&path, since)?;
+            if last_activity_at < since {
+                return None;
+            }
+            let heartbeat = heartbeats.get(&session_id);
+            let usage = scanner::scan_transcript(&path, pricing);
+            let started_at = usage
+                .first_timestamp
+                .as_deref()
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.with_timezone(&Local))
+                .or_else(|| {
+                    let created = fs::metadata(&path).and_then(|m| m.created()).ok()?;
+                    Some(DateTime::<Local>::from(created))
+                });
+            let project = heartbeat
+                .map(|a| a.cwd.display().to_string())
+                .filter(|cwd| !cwd.is_empty())
+                .or_else(|| {
+                    let folder = path.parent()?.file_name()?.to_str()?;
+                    Some(decode_project_dir(folder))
+                });
+            if !paths.allows(project.as_deref().map(Path::new)) {
+                return None;
+            }
+            Some(ActiveSession {
+                total_tokens: usage.input_tokens
+                    + usage.output_tokens
+                    + usage.cache_creation_tokens
+                    + usage.cache_read_tokens,
+                total_cost_usd: usage.total_cost_usd,
+                elapsed_seconds: started_at.map_or(0, |at| (now - at).num_seconds().max(0)),
+                prompt_count: heartbeat.map(|a| a.prompt_count),
+                session_id,
+                project,
+                transcript_path: path,
+                started_at,
+                last_activity_at,
+            })
+        })
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity_at));
+    sessions
+}
+
+/// Latest write to a transcript or its sub-agent files. Sub-agent files are
+/// only checked for transcripts touched in the last hour, since a long
+/// sub-agent run leaves the main transcript quiet.
+fn last_write(path: &Path, since: DateTime<Local>) -> Option<DateTime<Local>> {
+    let modified = |p: &Path| -> Option<DateTime<Local>> {
+        let time = fs::metadata(p).and_then(|m| m.modified()).ok()?;
+        Some(DateTime::<Local>::from(time))
+    };
+    let main = modified(path)?;
+    if main >= since || main < since - Duration::hours(1) {
+        return Some(main);
+    }
+    Some(
+        sidechain::files(path)
+            .iter()
+            .filter_map(|p| modified(p))
+            .fold(main, DateTime::max),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_active_sessions_from_heartbeats_and_projects() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.storage.path = dir.path().join("storage");
+        let projects = dir.path().join("projects");
+        let project_dir = projects.join("-work-app");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        let line = r#"{"type":"assistant","timestamp":"2024-01-15T10:00:00Z","message":{"id":"m1","model":"claude-sonnet-4","usage":{"input_tokens":100,"output_tokens":50}},"requestId":"r1"}"#;
+        let hooked = project_dir.join("aaa.jsonl");
+        fs::write(&hooked, format!("{}\n", line)).unwrap();
+        fs::write(project_dir.join("bbb.jsonl"), "").unwrap();
+        activity::record_prompt(&config, "aaa", &hooked, Path::new("/work/app")).unwrap();
+
+        let pricing = PricingData::from_map(HashMap::new());
+        let now = Local::now();
+        let sessions = collect(&config, Some(&projects), &pricing, 5, now);
+        assert_eq!(sessions.len(), 2);
+        let hooked = sessions.iter().find(|s| s.session_id == "aaa").unwrap();
+        assert_eq!(hooked.project.as_deref(), Some("/work/app"));
+        assert_eq!(hooked.prompt_count, Some(1));
+        assert_eq!(hooked.total_tokens, 150);
+        assert!(hooked.elapsed_seconds > 0);
+        let scanned = sessions.iter().find(|s| s.session_id == "bbb").unwrap();
+        assert_eq!(scanned.prompt_count, None);
+        assert_eq!(scanned.total_tokens, 0);
+
+        // Idle once the window has passed
+        let later = now + Duration::minutes(10);
+        assert!(collect(&config, Some(&projects), &pricing, 5, later).is_empty());
+    }
+}
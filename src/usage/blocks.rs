@@ -0,0 +1,338 @@
+//! Five-hour billing blocks, as tracked by ccusage.
+//!
+//! Claude plans meter usage in rolling 5-hour windows that open with the
+//! first message after the previous window closed. A block starts at that
+//! message's timestamp floored to the hour and ends 5 hours later; a gap of
+//! 5 hours or more without messages also closes it. The active block gets a
+//! burn rate and a projection of where it will end up at the current pace.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use super::pricing::PricingData;
+use super::scanner;
+use super::types::UsageEntry;
+use crate::archive::paths::PathFilter;
+
+pub const BLOCK_HOURS: i64 = 5;
+
+/// Token limit a block is measured against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenLimit {
+    Tokens(u64),
+    /// The largest total of any completed block
+    Max,
+}
+
+impl std::str::FromStr for TokenLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("max") {
+            return Ok(TokenLimit::Max);
+        }
+        s.replace('_', "")
+            .parse()
+            .map(TokenLimit::Tokens)
+            .with_context(|| format!("Invalid token limit '{}': expected a number or 'max'", s))
+    }
+}
+
+/// Tokens per minute and dollars per hour of the active block so far
+#[derive(Debug, Clone, Serialize)]
+pub struct BurnRate {
+    pub tokens_per_minute: f64,
+    pub cost_per_hour: f64,
+}
+
+/// Where the active block ends up if the current burn rate holds
+#[derive(Debug, Clone, Serialize)]
+pub struct Projection {
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub remaining_minutes: i64,
+}
+
+/// A block's tokens relative to the token limit
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitStatus {
+    pub token_limit: u64,
+    pub used_percent: f64,
+    /// Share of the limit at the end of the block, for the active block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_percent: Option<f64>,
+    /// When the limit is reached at the current burn rate, if within the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exhausted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageBlock {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub first_activity: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub is_active: bool,
+    pub entries: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burn_rate: Option<BurnRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projection: Option<Projection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<LimitStatus>,
+}
+
+impl UsageBlock {
+    fn open(start: DateTime<Utc>, entry: &UsageEntry) -> Self {
+        Self {
+            start,
+            end: start + Duration::hours(BLOCK_HOURS),
+            first_activity: entry.timestamp,
+            last_activity: entry.timestamp,
+            is_active: false,
+            entries: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            total_tokens: 0,
+            cost_usd: 0.0,
+            models: Vec::new(),
+            burn_rate: None,
+            projection: None,
+            limit: None,
+        }
+    }
+
+    fn add(&mut self, entry: &UsageEntry) {
+        self.last_activity = entry.timestamp;
+        self.entries += 1;
+        self.input_tokens += entry.input_tokens;
+        self.output_tokens += entry.output_tokens;
+        self.cache_creation_tokens += entry.cache_creation_tokens;
+        self.cache_read_tokens += entry.cache_read_tokens;
+        self.total_tokens += entry.total_tokens();
+        self.cost_usd += entry.cost_usd;
+    }
+
+    /// Burn rate and projection for a block that is still open at `now`
+    fn project(&mut self, now: DateTime<Utc>) {
+        let elapsed = (self.last_activity - self.first_activity).num_seconds() as f64 / 60.0;
+        if elapsed <= 0.0 {
+            return;
+        }
+        let tokens_per_minute = self.total_tokens as f64 / elapsed;
+        let cost_per_minute = self.cost_usd / elapsed;
+        let remaining_minutes = (self.end - now).num_minutes().max(0);
+        self.burn_rate = Some(BurnRate {
+            tokens_per_minute,
+            cost_per_hour: cost_per_minute * 60.0,
+        });
+        self.projection = Some(Projection {
+            total_tokens: self.total_tokens
+                + (tokens_per_minute * remaining_minutes as f64).round() as u64,
+            cost_usd: self.cost_usd + cost_per_minute * remaining_minutes as f64,
+            remaining_minutes,
+        });
+    }
+
+    fn apply_limit(&mut self, token_limit: u64, now: DateTime<Utc>) {
+        if token_limit == 0 {
+            return;
+        }
+        let percent = |tokens: u64| tokens as f64 * 100.0 / token_limit as f64;
+        let exhausted_at = self.burn_rate.as_ref().and_then(|rate| {
+            if self.total_tokens >= token_limit {
+                return Some(now);
+            }
+            let minutes = (token_limit - self.total_tokens) as f64 / rate.tokens_per_minute;
+            let at = now + Duration::seconds((minutes * 60.0) as i64);
+            (at < self.end).then_some(at)
+        });
+        self.limit = Some(LimitStatus {
+            token_limit,
+            used_percent: percent(self.total_tokens),
+            projected_percent: self.projection.as_ref().map(|p| percent(p.total_tokens)),
+            exhausted_at,
+        });
+    }
+}
+
+/// Group `entries` (oldest first) into billing blocks as of `now`
+pub fn identify_blocks(entries: &[UsageEntry], now: DateTime<Utc>) -> Vec<UsageBlock> {
+    let block_length = Duration::hours(BLOCK_HOURS);
+    let mut blocks: Vec<UsageBlock> = Vec::new();
+    let mut models = BTreeSet::new();
+
+    for entry in entries {
+        let starts_new = blocks.last().is_none_or(|block| {
+            entry.timestamp >= block.end || entry.timestamp - block.last_activity >= block_length
+        });
+        if starts_new {
+            if let Some(block) = blocks.last_mut() {
+                block.models = std::mem::take(&mut models).into_iter().collect();
+            }
+            let start = entry
+                .timestamp
+                .duration_trunc(Duration::hours(1))
+                .unwrap_or(entry.timestamp);
+            blocks.push(UsageBlock::open(start, entry));
+        }
+        let block = blocks.last_mut().expect("a block was just opened");
+        block.add(entry);
+        if !entry.model.is_empty() && entry.model != "<synthetic>" {
+            models.insert(entry.model.clone());
+        }
+    }
+
+    if let Some(block) = blocks.last_mut() {
+        block.models = models.into_iter().collect();
+        if now < block.end && now - block.last_activity < block_length {
+            block.is_active = true;
+            block.project(now);
+        }
+    }
+    blocks
+}
+
+/// Measure every block against `limit`, returning the resolved token count
+pub fn apply_limit(
+    blocks: &mut [UsageBlock],
+    limit: TokenLimit,
+    now: DateTime<Utc>,
+) -> Option<u64> {
+    let token_limit = match limit {
+        TokenLimit::Tokens(tokens) => tokens,
+        TokenLimit::Max => blocks
+            .iter()
+            .filter(|block| !block.is_active)
+            .map(|block| block.total_tokens)
+            .max()?,
+    };
+    for block in blocks.iter_mut() {
+        block.apply_limit(token_limit, now);
+    }
+    Some(token_limit)
+}
+
+/// Blocks across all transcripts, as shown by `daily usage blocks`
+#[derive(Debug, Clone, Serialize)]
+pub struct BlocksReport {
+    /// Resolved token limit the blocks are measured against
+    pub token_limit: Option<u64>,
+    pub blocks: Vec<UsageBlock>,
+}
+
+impl BlocksReport {
+    pub fn active(&self) -> Option<&UsageBlock> {
+        self.blocks.iter().find(|block| block.is_active)
+    }
+}
+
+/// Scan `~/.claude/projects/` into blocks, keeping those that started in
+/// the last `days` days. A `max` limit still looks at all of history.
+pub fn report(
+    pricing: &PricingData,
+    paths: &PathFilter,
+    limit: Option<TokenLimit>,
+    days: Option<i64>,
+) -> BlocksReport {
+    let now = Utc::now();
+    let mut blocks = identify_blocks(&scanner::scan_entries(pricing, paths), now);
+    let token_limit = limit.and_then(|limit| apply_limit(&mut blocks, limit, now));
+    if let Some(days) = days {
+        let since = now - Duration::days(days);
+        blocks.retain(|block| block.start >= since);
+    }
+    BlocksReport {
+        token_limit,
+        blocks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(timestamp: &str, tokens: u64) -> UsageEntry {
+        UsageEntry {
+            timestamp: at(timestamp),
+            model: "claude-sonnet-4-5".into(),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost_usd: tokens as f64 / 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_identify_blocks() {
+        let entries = vec![
+            entry("2026-03-01T09:20:00Z", 100),
+            entry("2026-03-01T13:50:00Z", 100),
+            // Past the 5-hour window that opened at 09:00
+            entry("2026-03-01T14:10:00Z", 300),
+            // Gap of more than 5 hours
+            entry("2026-03-01T20:30:00Z", 50),
+            entry("2026-03-01T21:30:00Z", 70),
+        ];
+        let now = at("2026-03-01T22:30:00Z");
+        let blocks = identify_blocks(&entries, now);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].start, at("2026-03-01T09:00:00Z"));
+        assert_eq!(blocks[0].end, at("2026-03-01T14:00:00Z"));
+        assert_eq!(blocks[0].total_tokens, 200);
+        assert_eq!(blocks[1].start, at("2026-03-01T14:00:00Z"));
+        assert_eq!(blocks[1].models, vec!["claude-sonnet-4-5".to_string()]);
+        assert!(!blocks[1].is_active);
+
+        let active = &blocks[2];
+        assert!(active.is_active);
+        assert_eq!(active.total_tokens, 120);
+        // 120 tokens over 60 minutes, 150 minutes left until 01:00
+        let rate = active.burn_rate.as_ref().unwrap();
+        assert!((rate.tokens_per_minute - 2.0).abs() < 1e-9);
+        let projection = active.projection.as_ref().unwrap();
+        assert_eq!(projection.remaining_minutes, 150);
+        assert_eq!(projection.total_tokens, 420);
+    }
+
+    #[test]
+    fn test_apply_limit() {
+        let entries = vec![
+            entry("2026-03-01T09:00:00Z", 400),
+            entry("2026-03-01T20:00:00Z", 100),
+            entry("2026-03-01T21:00:00Z", 100),
+        ];
+        let now = at("2026-03-01T22:00:00Z");
+        let mut blocks = identify_blocks(&entries, now);
+
+        assert_eq!(apply_limit(&mut blocks, TokenLimit::Max, now), Some(400));
+        let limit = blocks[1].limit.as_ref().unwrap();
+        assert!((limit.used_percent - 50.0).abs() < 1e-9);
+        // 200 tokens per hour: 400 is reached at 23:00, before the 01:00 end
+        assert_eq!(limit.exhausted_at, Some(at("2026-03-01T23:00:00Z")));
+
+        assert_eq!("max".parse::<TokenLimit>().unwrap(), TokenLimit::Max);
+        assert_eq!(
+            "500_000".parse::<TokenLimit>().unwrap(),
+            TokenLimit::Tokens(500_000)
+        );
+        assert!("lots".parse::<TokenLimit>().is_err());
+    }
+}
@@ -1,3 +1,6 @@
+pub mod active;
+pub mod blocks;
+pub mod overhead;
 pub mod pricing;
 pub mod scanner;
 pub mod types;
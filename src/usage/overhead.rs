@@ -0,0 +1,178 @@
+//! Cost of running daily itself: the tokens its summarization and digest
+//! jobs spend, as recorded on each job.
+
+use chrono::{Duration, Local};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::pricing::PricingData;
+use crate::jobs::JobInfo;
+use crate::summarizer::ModelUsage;
+
+/// Claude CLI model aliases and the pricing entry used to estimate them
+const MODEL_ALIASES: [(&str, &str); 3] = [
+    ("haiku", "claude-haiku-4-5"),
+    ("sonnet", "claude-sonnet-4-5"),
+    ("opus", "claude-opus-4-6"),
+];
+
+/// Token and cost totals for a group of jobs
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OverheadTotals {
+    pub jobs: usize,
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+impl OverheadTotals {
+    fn add(&mut self, usage: &ModelUsage) {
+        self.jobs += 1;
+        self.calls += usage.calls;
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cache_creation_tokens += usage.cache_creation_input_tokens;
+        self.cache_read_tokens += usage.cache_read_input_tokens;
+        self.total_cost_usd += usage.cost_usd.unwrap_or(0.0);
+    }
+}
+
+/// Overhead totals, overall and split by job type and by day
+#[derive(Debug, Clone, Serialize)]
+pub struct OverheadSummary {
+    #[serde(flatten)]
+    pub totals: OverheadTotals,
+    /// Job type (e.g. "session_end", "digest") to totals
+    pub by_job_type: BTreeMap<String, OverheadTotals>,
+    /// Date (YYYY-MM-DD) to totals, by job start
+    pub daily: BTreeMap<String, OverheadTotals>,
+}
+
+/// Aggregate recorded usage of `jobs`, optionally only those started in
+/// the last `days` days. Jobs without recorded usage are skipped.
+pub fn summarize(jobs: &[JobInfo], days: Option<i64>) -> OverheadSummary {
+    let since = days.map(|days| Local::now() - Duration::days(days));
+    let mut summary = OverheadSummary {
+        totals: OverheadTotals::default(),
+        by_job_type: BTreeMap::new(),
+        daily: BTreeMap::new(),
+    };
+
+    for job in jobs {
+        let Some(usage) = &job.usage else {
+            continue;
+        };
+        if since.is_some_and(|since| job.started_at < since) {
+            continue;
+        }
+        summary.totals.add(usage);
+        summary
+            .by_job_type
+            .entry(job.job_type.key().to_string())
+            .or_default()
+            .add(usage);
+        summary
+            .daily
+            .entry(job.started_at.format("%Y-%m-%d").to_string())
+            .or_default()
+            .add(usage);
+    }
+    summary
+}
+
+/// Fill in `cost_usd` from the pricing data when the CLI didn't report it
+pub fn estimate_cost(usage: &mut ModelUsage, pricing: &PricingData) {
+    if usage.cost_usd.is_some() {
+        return;
+    }
+    let model = MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == usage.model)
+        .map(|(_, model)| *model)
+        .unwrap_or(&usage.model);
+    usage.cost_usd = Some(pricing.calculate_cost(
+        model,
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_creation_input_tokens,
+        usage.cache_read_input_tokens,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{JobStatus, JobType};
+    use std::path::PathBuf;
+
+    fn job(job_type: JobType, days_ago: i64, cost: f64) -> JobInfo {
+        let at = Local::now() - Duration::days(days_ago);
+        JobInfo {
+            id: format!("job-{}", days_ago),
+            pid: 0,
+            task_name: "task".into(),
+            transcript_path: PathBuf::from("/tmp/t.jsonl"),
+            started_at: at,
+            finished_at: Some(at),
+            status: JobStatus::Completed,
+            job_type,
+            queued_at: Some(at),
+            usage: Some(ModelUsage {
+                calls: 1,
+                input_tokens: 1000,
+                output_tokens: 100,
+                cost_usd: Some(cost),
+                ..Default::default()
+            }),
+            quality: None,
+            depends_on: vec![],
+            timed_out: false,
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_overhead() {
+        let mut unrecorded = job(JobType::Manual, 0, 0.0);
+        unrecorded.usage = None;
+        let jobs = vec![
+            job(JobType::SessionEnd, 0, 0.01),
+            job(JobType::Digest, 1, 0.05),
+            job(JobType::SessionEnd, 40, 0.02),
+            unrecorded,
+        ];
+
+        let all = summarize(&jobs, None);
+        assert_eq!(all.totals.jobs, 3);
+        assert!((all.totals.total_cost_usd - 0.08).abs() < 1e-9);
+        assert_eq!(all.by_job_type["session_end"].jobs, 2);
+        assert_eq!(all.daily.len(), 3);
+
+        let recent = summarize(&jobs, Some(30));
+        assert_eq!(recent.totals.jobs, 2);
+        assert_eq!(recent.totals.input_tokens, 2000);
+    }
+
+    #[test]
+    fn test_estimate_cost_resolves_aliases() {
+        let pricing = PricingData::load_offline();
+        let mut usage = ModelUsage {
+            model: "haiku".into(),
+            input_tokens: 1_000_000,
+            ..Default::default()
+        };
+        estimate_cost(&mut usage, &pricing);
+        assert!(usage.cost_usd.unwrap() > 0.0);
+
+        // A reported cost is kept
+        let mut reported = ModelUsage {
+            cost_usd: Some(0.5),
+            ..usage.clone()
+        };
+        estimate_cost(&mut reported, &pricing);
+        assert_eq!(reported.cost_usd, Some(0.5));
+    }
+}
@@ -1,5 +1,6 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
 const LITELLM_PRICING_URL: &str =
@@ -7,6 +8,9 @@ const LITELLM_PRICING_URL: &str =
 
 const FETCH_TIMEOUT_SECS: u64 = 15;
 
+/// Cached pricing older than this is reported as stale
+const STALE_AFTER_DAYS: i64 = 7;
+
 /// Token threshold for tiered pricing in 1M context window models.
 /// Claude models charge higher rates for tokens above this threshold.
 const TIERED_THRESHOLD: u64 = 200_000;
@@ -23,7 +27,7 @@ const PROVIDER_PREFIXES: &[&str] = &[
 
 /// Per-model pricing data from LiteLLM dataset.
 /// All costs are per individual token (e.g. 3e-6 = $3 per million tokens).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
 pub struct ModelPricing {
     pub input_cost_per_token: Option<f64>,
     pub output_cost_per_token: Option<f64>,
@@ -36,9 +40,55 @@ pub struct ModelPricing {
     pub cache_read_input_token_cost_above_200k_tokens: Option<f64>,
 }
 
+impl ModelPricing {
+    /// These costs, with unset ones taken from `base`
+    fn merged_over(&self, base: Option<&ModelPricing>) -> ModelPricing {
+        let base = base.cloned().unwrap_or_default();
+        ModelPricing {
+            input_cost_per_token: self.input_cost_per_token.or(base.input_cost_per_token),
+            output_cost_per_token: self.output_cost_per_token.or(base.output_cost_per_token),
+            cache_creation_input_token_cost: self
+                .cache_creation_input_token_cost
+                .or(base.cache_creation_input_token_cost),
+            cache_read_input_token_cost: self
+                .cache_read_input_token_cost
+                .or(base.cache_read_input_token_cost),
+            input_cost_per_token_above_200k_tokens: self
+                .input_cost_per_token_above_200k_tokens
+                .or(base.input_cost_per_token_above_200k_tokens),
+            output_cost_per_token_above_200k_tokens: self
+                .output_cost_per_token_above_200k_tokens
+                .or(base.output_cost_per_token_above_200k_tokens),
+            cache_creation_input_token_cost_above_200k_tokens: self
+                .cache_creation_input_token_cost_above_200k_tokens
+                .or(base.cache_creation_input_token_cost_above_200k_tokens),
+            cache_read_input_token_cost_above_200k_tokens: self
+                .cache_read_input_token_cost_above_200k_tokens
+                .or(base.cache_read_input_token_cost_above_200k_tokens),
+        }
+    }
+}
+
+/// Where loaded pricing data came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingSource {
+    /// Fetched from LiteLLM just now
+    Live,
+    /// The file cache of an earlier fetch
+    Cache,
+    /// The snapshot compiled into the binary
+    Embedded,
+}
+
 /// Loaded pricing data for all models.
 pub struct PricingData {
     models: HashMap<String, ModelPricing>,
+    source: PricingSource,
+    /// When the data was fetched from LiteLLM (None for the embedded snapshot)
+    fetched_at: Option<DateTime<Utc>>,
+    /// Model names priced by `usage.price_overrides`
+    overrides: HashSet<String>,
 }
 
 impl PricingData {
@@ -62,7 +112,13 @@ impl PricingData {
         // Try loading from file cache
         match Self::load_cache() {
             Ok(data) => {
-                eprintln!("[daily] Using cached pricing data");
+                match data.age().filter(|age| *age > Duration::days(STALE_AFTER_DAYS)) {
+                    Some(age) => eprintln!(
+                        "[daily] Using cached pricing data from {} days ago (run `daily pricing refresh` to update)",
+                        age.num_days()
+                    ),
+                    None => eprintln!("[daily] Using cached pricing data"),
+                }
                 return data;
             }
             Err(_) => {
@@ -74,6 +130,63 @@ impl PricingData {
         Self::embedded_fallback()
     }
 
+    /// Load pricing data without the network: file cache → embedded snapshot.
+    pub fn load_offline() -> Self {
+        Self::load_cache().unwrap_or_else(|_| Self::embedded_fallback())
+    }
+
+    /// Apply `usage.price_overrides`: each override is stored under its own
+    /// model name, so it wins the direct match, with costs it leaves unset
+    /// taken from the LiteLLM entry the name resolved to before
+    pub fn with_overrides(mut self, overrides: &BTreeMap<String, ModelPricing>) -> Self {
+        for (model, costs) in overrides {
+            let merged = costs.merged_over(self.get_model_pricing(model));
+            self.models.insert(model.clone(), merged);
+            self.overrides.insert(model.clone());
+        }
+        self
+    }
+
+    /// Whether `model` (a dataset key from `lookup`) comes from the config
+    pub fn is_override(&self, model: &str) -> bool {
+        self.overrides.contains(model)
+    }
+
+    /// Fetch pricing data from LiteLLM and replace the file cache
+    pub async fn refresh() -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let data = Self::fetch_from_url()
+            .await
+            .context("Failed to fetch pricing from LiteLLM")?;
+        Self::save_cache(&data).context("Failed to write the pricing cache")?;
+        Ok(data)
+    }
+
+    pub fn source(&self) -> PricingSource {
+        self.source
+    }
+
+    pub fn fetched_at(&self) -> Option<DateTime<Utc>> {
+        self.fetched_at
+    }
+
+    /// Time since the data was fetched
+    pub fn age(&self) -> Option<Duration> {
+        self.fetched_at.map(|at| Utc::now() - at)
+    }
+
+    /// Whether the data is a cache older than a week
+    pub fn is_stale(&self) -> bool {
+        self.source == PricingSource::Cache
+            && self
+                .age()
+                .is_some_and(|age| age > Duration::days(STALE_AFTER_DAYS))
+    }
+
+    pub fn model_count(&self) -> usize {
+        self.models.len()
+    }
+
     /// Fetch pricing data from LiteLLM GitHub URL
     async fn fetch_from_url() -> anyhow::Result<Self> {
         let client = reqwest::Client::builder()
@@ -89,7 +202,12 @@ impl PricingData {
         let raw: HashMap<String, serde_json::Value> = response.json().await?;
         let models = Self::parse_raw_data(raw);
 
-        Ok(PricingData { models })
+        Ok(PricingData {
+            models,
+            source: PricingSource::Live,
+            fetched_at: Some(Utc::now()),
+            overrides: HashSet::new(),
+        })
     }
 
     /// Parse raw JSON data into typed ModelPricing, skipping entries that fail
@@ -108,11 +226,11 @@ impl PricingData {
     }
 
     /// Cache file path: ~/.config/daily/pricing_cache.json
-    fn cache_path() -> Option<PathBuf> {
+    pub fn cache_path() -> Option<PathBuf> {
         dirs::config_dir().map(|d| d.join("daily").join("pricing_cache.json"))
     }
 
-    /// Save pricing data to file cache
+    /// Save pricing data to file cache, without config overrides
     fn save_cache(data: &PricingData) -> anyhow::Result<()> {
         let path = Self::cache_path().ok_or_else(|| anyhow::anyhow!("No config dir"))?;
 
@@ -120,51 +238,74 @@ impl PricingData {
             std::fs::create_dir_all(parent)?;
         }
 
-        let serializable: HashMap<&String, &ModelPricing> = data.models.iter().collect();
+        let serializable: HashMap<&String, &ModelPricing> = data
+            .models
+            .iter()
+            .filter(|(name, _)| !data.overrides.contains(*name))
+            .collect();
         let json = serde_json::to_string(&serializable)?;
         std::fs::write(&path, json)?;
 
         Ok(())
     }
 
-    /// Load pricing data from file cache
+    /// Load pricing data from file cache; its modification time is when it was fetched
     fn load_cache() -> anyhow::Result<PricingData> {
         let path = Self::cache_path().ok_or_else(|| anyhow::anyhow!("No config dir"))?;
         let json = std::fs::read_to_string(&path)?;
         let models: HashMap<String, ModelPricing> = serde_json::from_str(&json)?;
-        Ok(PricingData { models })
+        let fetched_at = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        Ok(PricingData {
+            models,
+            source: PricingSource::Cache,
+            fetched_at,
+            overrides: HashSet::new(),
+        })
     }
 
     /// Embedded fallback pricing from LiteLLM snapshot (compile-time embedded).
     /// This covers all Claude/Anthropic models without network access.
     fn embedded_fallback() -> Self {
         let json_data = include_str!("litellm_pricing.json");
-        match serde_json::from_str::<HashMap<String, ModelPricing>>(json_data) {
-            Ok(models) => PricingData { models },
-            Err(_) => PricingData {
-                models: HashMap::new(),
-            },
+        PricingData {
+            models: serde_json::from_str(json_data).unwrap_or_default(),
+            source: PricingSource::Embedded,
+            fetched_at: None,
+            overrides: HashSet::new(),
         }
     }
 
     /// Create PricingData from pre-built HashMap (for testing)
     #[cfg(test)]
     pub fn from_map(models: HashMap<String, ModelPricing>) -> Self {
-        PricingData { models }
+        PricingData {
+            models,
+            source: PricingSource::Embedded,
+            fetched_at: None,
+            overrides: HashSet::new(),
+        }
     }
 
     /// Look up pricing for a model name, trying provider prefix candidates and fuzzy match.
     pub fn get_model_pricing(&self, model_name: &str) -> Option<&ModelPricing> {
+        self.lookup(model_name).map(|(_, pricing)| pricing)
+    }
+
+    /// Like `get_model_pricing`, also returning the dataset entry that matched
+    pub fn lookup(&self, model_name: &str) -> Option<(&str, &ModelPricing)> {
         // 1. Direct match
-        if let Some(pricing) = self.models.get(model_name) {
-            return Some(pricing);
+        if let Some((key, pricing)) = self.models.get_key_value(model_name) {
+            return Some((key, pricing));
         }
 
         // 2. Try with provider prefixes
         for prefix in PROVIDER_PREFIXES {
             let candidate = format!("{}{}", prefix, model_name);
-            if let Some(pricing) = self.models.get(&candidate) {
-                return Some(pricing);
+            if let Some((key, pricing)) = self.models.get_key_value(&candidate) {
+                return Some((key, pricing));
             }
         }
 
@@ -173,7 +314,7 @@ impl PricingData {
         for (key, value) in &self.models {
             let key_lower = key.to_lowercase();
             if key_lower.contains(&lower) || lower.contains(&key_lower) {
-                return Some(value);
+                return Some((key, value));
             }
         }
 
@@ -360,6 +501,47 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_lookup_reports_matched_entry() {
+        let pricing = tiered_pricing();
+        let (key, _) = pricing.lookup("claude-sonnet-4-5-20250929").unwrap();
+        assert_eq!(key, "anthropic/claude-sonnet-4-5-20250929");
+        assert!(pricing.lookup("gpt-nonexistent").is_none());
+        assert_eq!(pricing.source(), PricingSource::Embedded);
+        assert!(!pricing.is_stale());
+    }
+
+    #[test]
+    fn test_price_overrides() {
+        let mut overrides = BTreeMap::new();
+        // Negotiated input rate; the rest comes from the matched LiteLLM entry
+        overrides.insert(
+            "claude-sonnet-4-5-20250929".to_string(),
+            ModelPricing {
+                input_cost_per_token: Some(2e-6),
+                ..Default::default()
+            },
+        );
+        overrides.insert(
+            "corp-proxy-model".to_string(),
+            ModelPricing {
+                input_cost_per_token: Some(1e-6),
+                output_cost_per_token: Some(5e-6),
+                ..Default::default()
+            },
+        );
+        let pricing = tiered_pricing().with_overrides(&overrides);
+
+        let cost = pricing.calculate_cost("claude-sonnet-4-5-20250929", 1_000_000, 1_000_000, 0, 0);
+        // 200k at $2/M + 800k at the LiteLLM $6/M tier, plus the LiteLLM output rate
+        let expected = 0.4 + 4.8 + 200_000.0 * 15e-6 + 800_000.0 * 22.5e-6;
+        assert!((cost - expected).abs() < 0.001);
+        assert!(pricing.is_override("claude-sonnet-4-5-20250929"));
+
+        let cost = pricing.calculate_cost("corp-proxy-model", 1_000_000, 1_000_000, 0, 0);
+        assert!((cost - 6.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_fuzzy_matching() {
         let pricing = tiered_pricing();
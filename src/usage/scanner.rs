@@ -1,17 +1,23 @@
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::pricing::PricingData;
 use super::types::*;
+use crate::archive::paths::PathFilter;
+use crate::transcript::sidechain;
 
 /// Scan all JSONL session files under `~/.claude/projects/`.
 ///
-/// If `session_ids` is provided, only scan files whose filename stem matches.
+/// Sub-agent transcripts count toward their parent session. If `session_ids`
+/// is provided, only sessions with those ids are scanned. Projects left out by
+/// `paths` are skipped.
 /// Returns a map from session_id to SessionUsage.
 pub fn scan_all_sessions(
     session_ids: Option<&[String]>,
     pricing: &PricingData,
+    paths: &PathFilter,
 ) -> HashMap<String, SessionUsage> {
     let projects_dir = match dirs::home_dir() {
         Some(home) => home.join(".claude").join("projects"),
@@ -24,13 +30,19 @@ pub fn scan_all_sessions(
 
     let jsonl_files = collect_jsonl_files(&projects_dir);
     let mut result: HashMap<String, SessionUsage> = HashMap::new();
+    let mut projects: HashMap<String, String> = HashMap::new();
     // Global dedup set: messageId:requestId across ALL files (matches ccusage behavior)
     let mut global_seen: HashSet<String> = HashSet::new();
 
     for path in jsonl_files {
-        let session_id = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(stem) => stem.to_string(),
-            None => continue,
+        let is_sidechain_file = sidechain::is_sidechain_file(&path);
+        let session_id = if is_sidechain_file {
+            sidechain::parent_session_id(&path)
+        } else {
+            path.file_stem().and_then(|s| s.to_str()).map(String::from)
+        };
+        let Some(session_id) = session_id else {
+            continue;
         };
 
         // Skip if we have a filter and this session is not in it
@@ -40,14 +52,76 @@ pub fn scan_all_sessions(
             }
         }
 
-        if let Some(usage) = parse_session_file(&path, &session_id, pricing, &mut global_seen) {
-            result.insert(session_id, usage);
+        let project = project_of(&path, &projects_dir, &mut projects);
+        if !paths.allows(project.as_deref().map(Path::new)) {
+            continue;
+        }
+
+        if let Some(mut usage) = parse_session_file(
+            &path,
+            &session_id,
+            is_sidechain_file,
+            pricing,
+            &mut global_seen,
+        ) {
+            usage.project = project;
+            match result.entry(session_id) {
+                Entry::Occupied(mut existing) => existing.get_mut().merge(usage),
+                Entry::Vacant(slot) => {
+                    slot.insert(usage);
+                }
+            }
         }
     }
 
     result
 }
 
+/// Project of a transcript: the first folder under projects/ names it, also
+/// for sub-agent files. Decoded names are cached in `projects`.
+fn project_of(
+    path: &Path,
+    projects_dir: &Path,
+    projects: &mut HashMap<String, String>,
+) -> Option<String> {
+    path.strip_prefix(projects_dir)
+        .ok()
+        .and_then(|rel| rel.components().next())
+        .filter(|_| path.parent() != Some(projects_dir))
+        .map(|folder| folder.as_os_str().to_string_lossy().into_owned())
+        .map(|folder| {
+            projects
+                .entry(folder)
+                .or_insert_with_key(|folder| decode_project_dir(folder))
+                .clone()
+        })
+}
+
+/// Usage so far of the session at `transcript_path`, sub-agent files included
+pub fn scan_transcript(transcript_path: &Path, pricing: &PricingData) -> SessionUsage {
+    let session_id = sidechain::session_stem(transcript_path).unwrap_or_default();
+    let mut usage = SessionUsage {
+        session_id: session_id.to_string(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let files = std::iter::once(transcript_path.to_path_buf())
+        .map(|path| (path, false))
+        .chain(
+            sidechain::files(transcript_path)
+                .into_iter()
+                .map(|path| (path, true)),
+        );
+    for (path, sidechain_file) in files {
+        if let Some(file_usage) =
+            parse_session_file(&path, session_id, sidechain_file, pricing, &mut seen)
+        {
+            usage.merge(file_usage);
+        }
+    }
+    usage
+}
+
 /// Aggregate session usages into a global summary.
 ///
 /// If `date_filter` is provided (as YYYY-MM-DD strings), only include sessions
@@ -64,6 +138,7 @@ pub fn aggregate_usage(
     let mut total_sessions = 0usize;
     let mut model_counts: HashMap<String, (usize, f64)> = HashMap::new();
     let mut daily_map: HashMap<String, DailyUsageAccum> = HashMap::new();
+    let mut project_map: HashMap<String, ProjectUsage> = HashMap::new();
 
     for usage in session_usages.values() {
         let session_date = usage
@@ -117,6 +192,25 @@ pub fn aggregate_usage(
             daily.total_cost_usd += usage.total_cost_usd;
             daily.session_count += 1;
         }
+
+        let project = usage.project.as_deref().unwrap_or("(unknown)");
+        let totals = project_map
+            .entry(project.to_string())
+            .or_insert_with(|| ProjectUsage {
+                project: project.to_string(),
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_cost_usd: 0.0,
+                session_count: 0,
+            });
+        totals.input_tokens += usage.input_tokens;
+        totals.output_tokens += usage.output_tokens;
+        totals.cache_creation_tokens += usage.cache_creation_tokens;
+        totals.cache_read_tokens += usage.cache_read_tokens;
+        totals.total_cost_usd += usage.total_cost_usd;
+        totals.session_count += 1;
     }
 
     let mut model_distribution: Vec<ModelUsageCount> = model_counts
@@ -127,7 +221,7 @@ pub fn aggregate_usage(
             total_cost_usd: cost,
         })
         .collect();
-    model_distribution.sort_by(|a, b| b.count.cmp(&a.count));
+    model_distribution.sort_by_key(|m| std::cmp::Reverse(m.count));
 
     let mut daily_usage: Vec<DailyUsage> = daily_map
         .into_values()
@@ -143,6 +237,9 @@ pub fn aggregate_usage(
         .collect();
     daily_usage.sort_by(|a, b| a.date.cmp(&b.date));
 
+    let mut projects: Vec<ProjectUsage> = project_map.into_values().collect();
+    projects.sort_by(|a, b| b.total_cost_usd.total_cmp(&a.total_cost_usd));
+
     UsageSummary {
         total_input_tokens: total_input,
         total_output_tokens: total_output,
@@ -152,9 +249,31 @@ pub fn aggregate_usage(
         total_sessions,
         model_distribution,
         daily_usage,
+        projects,
     }
 }
 
+/// Usage summary of all sessions, or of those started in the last `days`
+/// days (today counts as one)
+pub fn summarize_recent(
+    pricing: &PricingData,
+    paths: &PathFilter,
+    days: Option<i64>,
+) -> UsageSummary {
+    let sessions = scan_all_sessions(None, pricing, paths);
+    let dates: Option<Vec<String>> = days.map(|days| {
+        let today = chrono::Local::now().date_naive();
+        (0..days.max(1))
+            .map(|ago| {
+                (today - chrono::Duration::days(ago))
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+            .collect()
+    });
+    aggregate_usage(&sessions, dates.as_deref())
+}
+
 struct DailyUsageAccum {
     date: String,
     input_tokens: u64,
@@ -165,8 +284,46 @@ struct DailyUsageAccum {
     session_count: usize,
 }
 
+/// Project path from a `~/.claude/projects/` folder name.
+///
+/// Claude Code replaces `/` and `.` with `-`, so `-Users-me-my-app` could be
+/// `/Users/me/my-app` or `/Users/me/my/app`. Segments are joined back with
+/// `-` where that names an existing directory; an empty segment (`--`) is a
+/// leading `.`. Paths that no longer exist decode with `/` between segments.
+pub fn decode_project_dir(folder: &str) -> String {
+    let Some(rest) = folder.strip_prefix('-') else {
+        return folder.to_string();
+    };
+    let mut parts: Vec<String> = Vec::new();
+    let mut dot = false;
+    for segment in rest.split('-') {
+        if segment.is_empty() {
+            dot = true;
+            continue;
+        }
+        parts.push(if dot {
+            format!(".{}", segment)
+        } else {
+            segment.to_string()
+        });
+        dot = false;
+    }
+
+    let mut path = PathBuf::from("/");
+    let mut i = 0;
+    while i < parts.len() {
+        let end = (i + 1..=parts.len())
+            .rev()
+            .find(|&end| path.join(parts[i..end].join("-")).exists())
+            .unwrap_or(i + 1);
+        path.push(parts[i..end].join("-"));
+        i = end;
+    }
+    path.to_string_lossy().into_owned()
+}
+
 /// Collect all .jsonl files recursively under a directory
-fn collect_jsonl_files(dir: &PathBuf) -> Vec<PathBuf> {
+pub(crate) fn collect_jsonl_files(dir: &PathBuf) -> Vec<PathBuf> {
     let mut files = Vec::new();
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
@@ -181,22 +338,142 @@ fn collect_jsonl_files(dir: &PathBuf) -> Vec<PathBuf> {
     files
 }
 
-/// Parse a single JSONL session file and extract usage data
+/// Parse a single JSONL session file and extract usage data.
+/// Entries of a sidechain file, or flagged `isSidechain`, also count as sub-agent cost.
 fn parse_session_file(
     path: &PathBuf,
     session_id: &str,
+    sidechain_file: bool,
     pricing: &PricingData,
     global_seen: &mut HashSet<String>,
 ) -> Option<SessionUsage> {
-    let file = std::fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let messages = read_messages(path, pricing, global_seen)?;
+    if messages.is_empty() {
+        return None;
+    }
 
     let mut usage = SessionUsage {
         session_id: session_id.to_string(),
         ..Default::default()
     };
+    for message in messages {
+        if let Some(model) = message.model {
+            *usage.model_calls.entry(model).or_insert(0) += 1;
+        }
+        usage.input_tokens += message.input_tokens;
+        usage.output_tokens += message.output_tokens;
+        usage.cache_creation_tokens += message.cache_creation_tokens;
+        usage.cache_read_tokens += message.cache_read_tokens;
+        usage.total_cost_usd += message.cost_usd;
+        if sidechain_file || message.sidechain {
+            usage.subagent_cost_usd += message.cost_usd;
+        }
+        if usage.first_timestamp.is_none() {
+            usage.first_timestamp = message.timestamp;
+        }
+    }
+
+    Some(usage)
+}
+
+/// Every deduplicated assistant message with usage under `~/.claude/projects/`,
+/// oldest first. Messages without a parseable timestamp, or of projects left
+/// out by `paths`, are skipped.
+pub fn scan_entries(pricing: &PricingData, paths: &PathFilter) -> Vec<UsageEntry> {
+    let Some(projects_dir) = dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+    else {
+        return Vec::new();
+    };
+
+    let mut global_seen: HashSet<String> = HashSet::new();
+    let mut projects: HashMap<String, String> = HashMap::new();
+    let mut entries: Vec<UsageEntry> = collect_jsonl_files(&projects_dir)
+        .iter()
+        .filter(|path| {
+            let project = project_of(path, &projects_dir, &mut projects);
+            paths.allows(project.as_deref().map(Path::new))
+        })
+        .filter_map(|path| read_messages(path, pricing, &mut global_seen))
+        .flatten()
+        .filter_map(|message| {
+            let timestamp = chrono::DateTime::parse_from_rfc3339(message.timestamp.as_deref()?)
+                .ok()?
+                .with_timezone(&chrono::Utc);
+            Some(UsageEntry {
+                timestamp,
+                model: message.model.unwrap_or_default(),
+                input_tokens: message.input_tokens,
+                output_tokens: message.output_tokens,
+                cache_creation_tokens: message.cache_creation_tokens,
+                cache_read_tokens: message.cache_read_tokens,
+                cost_usd: message.cost_usd,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|e| e.timestamp);
+    entries
+}
+
+/// Cost of the messages sent since `since`. Only transcripts written since
+/// then are read, so this stays cheap enough for a status line.
+pub fn cost_since(
+    pricing: &PricingData,
+    paths: &PathFilter,
+    since: chrono::DateTime<chrono::Utc>,
+) -> f64 {
+    let Some(projects_dir) = dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+    else {
+        return 0.0;
+    };
+
+    let mut global_seen: HashSet<String> = HashSet::new();
+    let mut projects: HashMap<String, String> = HashMap::new();
+    collect_jsonl_files(&projects_dir)
+        .iter()
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| chrono::DateTime::<chrono::Utc>::from(modified) >= since)
+        })
+        .filter(|path| {
+            let project = project_of(path, &projects_dir, &mut projects);
+            paths.allows(project.as_deref().map(Path::new))
+        })
+        .filter_map(|path| read_messages(path, pricing, &mut global_seen))
+        .flatten()
+        .filter(|message| {
+            message
+                .timestamp
+                .as_deref()
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+                .is_some_and(|ts| ts >= since)
+        })
+        .map(|message| message.cost_usd)
+        .sum()
+}
 
-    let mut found_any = false;
+/// One assistant message's usage as read from a transcript
+struct MessageUsage {
+    timestamp: Option<String>,
+    model: Option<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    cost_usd: f64,
+    sidechain: bool,
+}
+
+/// Assistant messages with usage in a JSONL file, skipping those already
+/// in `global_seen` (messageId:requestId, matching ccusage's dedup)
+fn read_messages(
+    path: &PathBuf,
+    pricing: &PricingData,
+    global_seen: &mut HashSet<String>,
+) -> Option<Vec<MessageUsage>> {
+    let file = std::fs::File::open(path).ok()?;
+    let reader = BufReader::new(file);
+    let mut messages = Vec::new();
 
     for line in reader.lines() {
         let line = match line {
@@ -241,62 +518,50 @@ fn parse_session_file(
             }
         }
 
-        // Extract model
-        if let Some(model) = message.get("model").and_then(|v| v.as_str()) {
-            *usage.model_calls.entry(model.to_string()).or_insert(0) += 1;
-        }
+        let model = message
+            .get("model")
+            .and_then(|v| v.as_str())
+            .map(String::from);
 
         // Extract usage tokens and calculate per-message cost
-        if let Some(msg_usage) = message.get("usage") {
-            let input = msg_usage
-                .get("input_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-            let output = msg_usage
-                .get("output_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-            let cache_creation = msg_usage
-                .get("cache_creation_input_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-            let cache_read = msg_usage
-                .get("cache_read_input_tokens")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            usage.input_tokens += input;
-            usage.output_tokens += output;
-            usage.cache_creation_tokens += cache_creation;
-            usage.cache_read_tokens += cache_read;
-            found_any = true;
-
-            // Calculate cost per message using the actual model for this message
-            let msg_model = message
-                .get("model")
-                .and_then(|v| v.as_str())
-                .unwrap_or("claude-sonnet");
-            usage.total_cost_usd +=
-                pricing.calculate_cost(msg_model, input, output, cache_creation, cache_read);
-        }
-
-        // Extract first timestamp
-        if usage.first_timestamp.is_none() {
-            if let Some(ts) = entry.get("timestamp").and_then(|v| v.as_str()) {
-                usage.first_timestamp = Some(ts.to_string());
-            }
-        }
-    }
+        let Some(msg_usage) = message.get("usage") else {
+            continue;
+        };
+        let tokens = |key: &str| msg_usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let input = tokens("input_tokens");
+        let output = tokens("output_tokens");
+        let cache_creation = tokens("cache_creation_input_tokens");
+        let cache_read = tokens("cache_read_input_tokens");
+
+        // Calculate cost per message using the actual model for this message
+        let cost = pricing.calculate_cost(
+            model.as_deref().unwrap_or("claude-sonnet"),
+            input,
+            output,
+            cache_creation,
+            cache_read,
+        );
 
-    if !found_any {
-        return None;
+        messages.push(MessageUsage {
+            timestamp: entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            model,
+            input_tokens: input,
+            output_tokens: output,
+            cache_creation_tokens: cache_creation,
+            cache_read_tokens: cache_read,
+            cost_usd: cost,
+            sidechain: sidechain::is_sidechain(&entry),
+        });
     }
 
-    Some(usage)
+    Some(messages)
 }
 
 /// Extract YYYY-MM-DD date from an ISO 8601 timestamp string
-fn extract_date_from_timestamp(ts: &str) -> Option<String> {
+pub fn extract_date_from_timestamp(ts: &str) -> Option<String> {
     // Handles "2026-02-05T18:48:19.274Z" format
     if ts.len() >= 10 {
         let date = &ts[..10];
@@ -366,4 +631,110 @@ mod tests {
         assert_eq!(summary.total_sessions, 1);
         assert_eq!(summary.total_input_tokens, 1000);
     }
+
+    #[test]
+    fn test_aggregate_by_project() {
+        let session = |id: &str, project: Option<&str>, cost: f64| {
+            (
+                id.to_string(),
+                SessionUsage {
+                    session_id: id.to_string(),
+                    input_tokens: 100,
+                    total_cost_usd: cost,
+                    project: project.map(String::from),
+                    ..Default::default()
+                },
+            )
+        };
+        let sessions: HashMap<String, SessionUsage> = [
+            session("s1", Some("/work/client-a"), 0.01),
+            session("s2", Some("/work/client-b"), 0.05),
+            session("s3", Some("/work/client-a"), 0.02),
+            session("s4", None, 0.001),
+        ]
+        .into_iter()
+        .collect();
+
+        let projects = aggregate_usage(&sessions, None).projects;
+        assert_eq!(projects.len(), 3);
+        assert_eq!(projects[0].project, "/work/client-b");
+        assert_eq!(projects[1].project, "/work/client-a");
+        assert_eq!(projects[1].session_count, 2);
+        assert!((projects[1].total_cost_usd - 0.03).abs() < 1e-9);
+        assert_eq!(projects[2].project, "(unknown)");
+    }
+
+    #[test]
+    fn test_decode_project_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().canonicalize().unwrap().join("my-app");
+        std::fs::create_dir_all(project.join(".config")).unwrap();
+        let encode = |path: &std::path::Path| path.to_string_lossy().replace(['/', '.'], "-");
+
+        assert_eq!(
+            decode_project_dir(&encode(&project)),
+            project.to_string_lossy()
+        );
+        let dotted = project.join(".config");
+        assert_eq!(
+            decode_project_dir(&encode(&dotted)),
+            dotted.to_string_lossy()
+        );
+        // Gone from disk: every hyphen becomes a separator
+        assert_eq!(
+            decode_project_dir("-nonexistent-my-app"),
+            "/nonexistent/my/app"
+        );
+    }
+
+    #[test]
+    fn test_sidechain_file_usage_merges_into_parent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut models = HashMap::new();
+        models.insert(
+            "claude-sonnet-4".to_string(),
+            super::super::pricing::ModelPricing {
+                input_cost_per_token: Some(3e-6),
+                output_cost_per_token: Some(15e-6),
+                cache_creation_input_token_cost: None,
+                cache_read_input_token_cost: None,
+                input_cost_per_token_above_200k_tokens: None,
+                output_cost_per_token_above_200k_tokens: None,
+                cache_creation_input_token_cost_above_200k_tokens: None,
+                cache_read_input_token_cost_above_200k_tokens: None,
+            },
+        );
+        let pricing = PricingData::from_map(models);
+        let line = |id: &str, sidechain: bool| {
+            format!(
+                r#"{{"type":"assistant","isSidechain":{sidechain},"timestamp":"2026-02-05T10:00:00Z","message":{{"id":"{id}","model":"claude-sonnet-4","usage":{{"input_tokens":100,"output_tokens":10}}}}}}"#
+            )
+        };
+
+        let main = dir.path().join("abc.jsonl");
+        std::fs::write(
+            &main,
+            format!("{}\n{}\n", line("m1", false), line("m2", true)),
+        )
+        .unwrap();
+        let agent = dir
+            .path()
+            .join("abc")
+            .join("subagents")
+            .join("agent-1.jsonl");
+        std::fs::create_dir_all(agent.parent().unwrap()).unwrap();
+        std::fs::write(&agent, line("m3", false)).unwrap();
+
+        let mut seen = HashSet::new();
+        let mut usage = parse_session_file(&main, "abc", false, &pricing, &mut seen).unwrap();
+        let agent_usage = parse_session_file(&agent, "abc", true, &pricing, &mut seen).unwrap();
+        assert_eq!(sidechain::parent_session_id(&agent).as_deref(), Some("abc"));
+        usage.merge(agent_usage);
+
+        assert_eq!(usage.input_tokens, 300);
+        assert_eq!(usage.model_calls["claude-sonnet-4"], 3);
+        // One inline sidechain message plus the sub-agent file
+        assert!(usage.subagent_cost_usd > 0.0);
+        assert!((usage.subagent_cost_usd - usage.total_cost_usd * 2.0 / 3.0).abs() < 1e-9);
+    }
 }
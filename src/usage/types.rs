@@ -1,6 +1,26 @@
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::HashMap;
 
+/// Usage of a single assistant message
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageEntry {
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl UsageEntry {
+    /// All tokens of the message, including cache writes and reads
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
 /// Token usage data for a single session
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct SessionUsage {
@@ -10,10 +30,36 @@ pub struct SessionUsage {
     pub cache_creation_tokens: u64,
     pub cache_read_tokens: u64,
     pub total_cost_usd: f64,
+    /// Part of `total_cost_usd` spent by sub-agents (Task tool sidechains)
+    pub subagent_cost_usd: f64,
     /// Model name -> call count
     pub model_calls: HashMap<String, usize>,
     /// Earliest timestamp seen in this session
     pub first_timestamp: Option<String>,
+    /// Project directory, decoded from the `~/.claude/projects/` folder name
+    pub project: Option<String>,
+}
+
+impl SessionUsage {
+    /// Add usage recorded in another file of the same session
+    pub fn merge(&mut self, other: SessionUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.total_cost_usd += other.total_cost_usd;
+        self.subagent_cost_usd += other.subagent_cost_usd;
+        for (model, count) in other.model_calls {
+            *self.model_calls.entry(model).or_insert(0) += count;
+        }
+        self.first_timestamp = match (self.first_timestamp.take(), other.first_timestamp) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if self.project.is_none() {
+            self.project = other.project;
+        }
+    }
 }
 
 /// Aggregated usage for a single day
@@ -28,6 +74,18 @@ pub struct DailyUsage {
     pub session_count: usize,
 }
 
+/// Aggregated usage for a single project directory
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectUsage {
+    pub project: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_cost_usd: f64,
+    pub session_count: usize,
+}
+
 /// Model usage distribution entry
 #[derive(Debug, Clone, Serialize)]
 pub struct ModelUsageCount {
@@ -47,4 +105,6 @@ pub struct UsageSummary {
     pub total_sessions: usize,
     pub model_distribution: Vec<ModelUsageCount>,
     pub daily_usage: Vec<DailyUsage>,
+    /// Per-project totals, most expensive first
+    pub projects: Vec<ProjectUsage>,
 }
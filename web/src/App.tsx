@@ -7,6 +7,7 @@ import { SessionDetail } from './pages/SessionDetail'
 import { JobsMonitor } from './pages/JobsMonitor'
 import { Settings } from './pages/Settings'
 import { Insights } from './pages/Insights'
+import { Permalink } from './pages/Permalink'
 
 export default function App() {
   return (
@@ -23,6 +24,7 @@ export default function App() {
         <Route path="jobs" element={<JobsMonitor />} />
         <Route path="settings" element={<Settings />} />
         <Route path="insights" element={<Insights />} />
+        <Route path="go/*" element={<Permalink />} />
       </Route>
     </Routes>
   )
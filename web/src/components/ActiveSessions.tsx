@@ -0,0 +1,58 @@
+import { useState, useEffect } from 'react'
+import { useApi } from '../hooks/useApi'
+import type { ActiveSession } from '../hooks/useApi'
+import { useLanguage } from '../contexts/LanguageContext'
+import { formatCost, formatTokenCount } from './UsageCharts'
+
+const POLL_INTERVAL_MS = 30_000
+
+function formatElapsed(seconds: number): string {
+  const minutes = Math.floor(seconds / 60)
+  if (minutes < 60) return `${minutes}m`
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`
+}
+
+/** Live panel of sessions whose transcripts are still being written */
+export function ActiveSessions() {
+  const [sessions, setSessions] = useState<ActiveSession[]>([])
+  const { fetchActiveSessions } = useApi()
+  const { t } = useLanguage()
+
+  useEffect(() => {
+    const load = () => {
+      fetchActiveSessions()
+        .then(setSessions)
+        .catch(console.error)
+    }
+    load()
+    const timer = setInterval(load, POLL_INTERVAL_MS)
+    return () => clearInterval(timer)
+  }, [fetchActiveSessions])
+
+  if (sessions.length === 0) return null
+
+  return (
+    <div className="mb-8 rounded-lg border border-green-500/30 bg-green-500/5 p-4">
+      <h2 className="flex items-center gap-2 text-sm font-semibold text-green-500 mb-3">
+        <span className="w-2 h-2 rounded-full bg-green-500 animate-pulse" />
+        {t('active.title')}
+      </h2>
+      <ul className="space-y-2">
+        {sessions.map((session) => (
+          <li key={session.session_id} className="flex items-center justify-between text-sm">
+            <span className="font-mono truncate" title={session.transcript_path}>
+              {session.project ?? session.session_id}
+            </span>
+            <span className="flex gap-4 text-gray-500 shrink-0">
+              <span>{formatElapsed(session.elapsed_seconds)}</span>
+              <span>
+                {formatTokenCount(session.total_tokens)} {t('active.tokens')}
+              </span>
+              <span>{formatCost(session.total_cost_usd)}</span>
+            </span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  )
+}
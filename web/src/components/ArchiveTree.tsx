@@ -3,7 +3,7 @@ import { useNavigate, useLocation } from 'react-router-dom'
 import { motion, AnimatePresence } from 'framer-motion'
 import { format, parseISO, isToday, isYesterday } from 'date-fns'
 import { useApi } from '../hooks/useApi'
-import type { DateItem, Session } from '../hooks/useApi'
+import type { DateItem, PinnedSession, Session } from '../hooks/useApi'
 import { cn } from '../lib/utils'
 import { useLanguage } from '../contexts/LanguageContext'
 
@@ -20,8 +20,9 @@ type NavItem =
 export function ArchiveTree() {
   const [dates, setDates] = useState<DateItem[]>([])
   const [dateStates, setDateStates] = useState<Record<string, DateNodeState>>({})
+  const [pinned, setPinned] = useState<PinnedSession[]>([])
   const dateRefs = useRef<Record<string, HTMLDivElement | null>>({})
-  const { fetchDates, fetchSessions, loading } = useApi()
+  const { fetchDates, fetchSessions, fetchPinned, loading } = useApi()
   const navigate = useNavigate()
   const location = useLocation()
   const { t } = useLanguage()
@@ -114,6 +115,11 @@ export function ArchiveTree() {
       .catch(console.error)
   }, [fetchDates])
 
+  // Reload pinned sessions on navigation so pin changes show up
+  useEffect(() => {
+    fetchPinned().then(setPinned).catch(console.error)
+  }, [fetchPinned, location.pathname])
+
   // Auto-expand and scroll to date when navigating from calendar
   useEffect(() => {
     const match = location.pathname.match(/^\/day\/(\d{4}-\d{2}-\d{2})/)
@@ -205,6 +211,33 @@ export function ArchiveTree() {
 
   return (
     <div className="h-full overflow-y-auto p-4 space-y-1">
+      {pinned.length > 0 && (
+        <div className="pb-2 mb-2 border-b border-gray-200 dark:border-orange-500/20">
+          <div className="px-3 py-1 text-xs text-gray-500 font-medium">
+            {t('archive.pinned')} ({pinned.length})
+          </div>
+          {pinned.map((session) => {
+            const path = `/day/${session.date}/session/${encodeURIComponent(session.name)}`
+            return (
+              <button
+                key={`${session.date}/${session.name}`}
+                onClick={() => navigate(path)}
+                className={cn(
+                  'w-full flex items-center gap-2 px-3 py-2 rounded-lg text-left text-sm transition-colors',
+                  isActive(path)
+                    ? 'bg-orange-500/20 text-orange-500 dark:text-orange-400 border border-orange-500/30'
+                    : 'hover:bg-gray-100 dark:hover:bg-daily-light text-gray-700 dark:text-gray-300'
+                )}
+                title={session.title || session.name}
+              >
+                <span className="text-base">📌</span>
+                <span className="truncate flex-1">{session.title || session.name}</span>
+                <span className="text-xs text-gray-500 tabular-nums shrink-0">{session.date}</span>
+              </button>
+            )
+          })}
+        </div>
+      )}
       {dates.map((dateItem) => {
         const state = dateStates[dateItem.date] || { expanded: false, sessions: [], sessionsLoaded: false }
 
@@ -234,6 +267,12 @@ export function ArchiveTree() {
                 <div className="flex items-center gap-2">
                   <span className="font-medium text-sm tabular-nums">{dateItem.date}</span>
                   <span className="text-xs text-gray-500">{getDateLabel(dateItem.date)}</span>
+                  {((dateItem.has_digest && dateItem.read === false) || !!dateItem.unread_sessions) && (
+                    <span
+                      className="ml-auto size-2 rounded-full bg-orange-500 shrink-0"
+                      title={t('archive.unread')}
+                    />
+                  )}
                 </div>
                 <div className="text-xs text-gray-500 mt-0.5">
                   {dateItem.session_count} {dateItem.session_count === 1 ? t('archive.session') : t('archive.sessions')}
@@ -264,6 +303,9 @@ export function ArchiveTree() {
                     >
                       <span className="text-base">📝</span>
                       <span>{t('archive.dailySummary')}</span>
+                      {dateItem.has_digest && dateItem.read === false && (
+                        <span className="ml-auto size-2 rounded-full bg-orange-500 shrink-0" title={t('archive.unread')} />
+                      )}
                     </button>
 
                     {/* Sessions */}
@@ -285,8 +327,11 @@ export function ArchiveTree() {
                               )}
                               title={session.title || session.name}
                             >
-                              <span className="text-base">📄</span>
+                              <span className="text-base">{session.pinned ? '📌' : '📄'}</span>
                               <span className="truncate">{session.title || session.name}</span>
+                              {session.read === false && (
+                                <span className="ml-auto size-2 rounded-full bg-orange-500 shrink-0" title={t('archive.unread')} />
+                              )}
                             </button>
                           ))}
                         </div>
@@ -31,7 +31,7 @@ export function ChatView({ date, name }: ChatViewProps) {
 
   const loadPage = async (p: number) => {
     try {
-      const data = await fetchConversation(date, name, p)
+      const data = await fetchConversation(date, name, p, 50, true)
       if (p === 0) {
         setMessages(data.messages)
       } else {
@@ -158,9 +158,16 @@ function ContentBlockRenderer({ block }: { block: ConversationContentBlock }) {
         </div>
       )
     case 'tool_use':
-      return <ToolCallBlock name={block.name} input={block.input} toolUseId={block.tool_use_id} />
+      return (
+        <>
+          <ToolCallBlock name={block.name} input={block.input} toolUseId={block.tool_use_id} />
+          {block.sidechain && block.sidechain.length > 0 && <SidechainBlock messages={block.sidechain} />}
+        </>
+      )
     case 'tool_result':
       return <ToolResultBlock content={block.content} />
+    case 'thinking':
+      return <ThinkingBlock thinking={block.thinking} />
     default:
       return null
   }
@@ -202,6 +209,50 @@ function ToolCallBlock({ name, input, toolUseId: _toolUseId }: { name: string; i
   )
 }
 
+function SidechainBlock({ messages }: { messages: ConversationMessage[] }) {
+  const [expanded, setExpanded] = useState(false)
+  const { t } = useLanguage()
+
+  return (
+    <div className="my-1 border-l-2 border-orange-500/30 pl-2">
+      <button
+        onClick={() => setExpanded(!expanded)}
+        className="w-full text-left px-2 py-1 text-xs text-orange-400 hover:text-orange-300 transition-colors"
+      >
+        {t('chatView.subagent', { count: messages.length })}
+      </button>
+      {expanded && (
+        <div className="flex flex-col gap-2 mt-1">
+          {messages.map((msg, i) => (
+            <MessageBubble key={i} message={msg} />
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
+
+function ThinkingBlock({ thinking }: { thinking: string }) {
+  const [expanded, setExpanded] = useState(false)
+  const { t } = useLanguage()
+
+  return (
+    <div className="my-1 border-l-2 border-gray-400/30 pl-2">
+      <button
+        onClick={() => setExpanded(!expanded)}
+        className="w-full text-left px-2 py-1 text-xs italic text-gray-500 hover:text-gray-400 transition-colors"
+      >
+        {t('chatView.thinking')}
+      </button>
+      {expanded && (
+        <div className="px-2 py-1 text-xs leading-relaxed whitespace-pre-wrap text-gray-500 dark:text-gray-400 max-h-80 overflow-auto">
+          {thinking}
+        </div>
+      )}
+    </div>
+  )
+}
+
 function ToolResultBlock({ content }: { content: string }) {
   const [expanded, setExpanded] = useState(false)
   const { t } = useLanguage()
@@ -9,6 +9,7 @@ interface JobCardProps {
 
 export function JobCard({ job, onKill }: JobCardProps) {
   const isRunning = job.status_type === 'running'
+  const isQueued = job.status_type === 'queued'
   const isFailed = job.status_type === 'failed'
   const isAutoSummarize = job.job_type === 'auto_summarize'
   const { t } = useLanguage()
@@ -19,6 +20,16 @@ export function JobCard({ job, onKill }: JobCardProps) {
         return t('jobs.sessionEnd')
       case 'auto_summarize':
         return t('jobs.autoSummarize')
+      case 'backfill':
+        return t('jobs.backfill')
+      case 'checkpoint':
+        return t('jobs.checkpoint')
+      case 'digest':
+        return t('jobs.digest')
+      case 'topics':
+        return t('jobs.topics')
+      case 'anomalies':
+        return t('jobs.anomalies')
       case 'manual':
         return t('jobs.manual')
       default:
@@ -43,8 +54,9 @@ export function JobCard({ job, onKill }: JobCardProps) {
         'p-4 rounded-lg border transition-colors',
         'bg-gray-50 dark:bg-daily-light',
         isRunning && 'border-orange-400 dark:border-orange-500/40',
+        isQueued && 'border-yellow-400 dark:border-yellow-500/40',
         isFailed && 'border-red-400 dark:border-red-500/40',
-        !isRunning && !isFailed && 'border-gray-200 dark:border-gray-700'
+        !isRunning && !isQueued && !isFailed && 'border-gray-200 dark:border-gray-700'
       )}
     >
       <div className="flex items-start justify-between">
@@ -55,8 +67,9 @@ export function JobCard({ job, onKill }: JobCardProps) {
               className={cn(
                 'size-2 rounded-full',
                 isRunning && 'bg-orange-500 animate-pulse',
+                isQueued && 'bg-yellow-500',
                 isFailed && 'bg-red-500',
-                !isRunning && !isFailed && 'bg-green-500'
+                !isRunning && !isQueued && !isFailed && 'bg-green-500'
               )}
             />
             <span className="font-medium text-gray-900 dark:text-gray-100 truncate">
@@ -71,6 +84,36 @@ export function JobCard({ job, onKill }: JobCardProps) {
             >
               {getJobTypeLabel(job.job_type)}
             </span>
+            {/* Queue position */}
+            {isQueued && (
+              <span className="px-2 py-0.5 text-xs rounded-full bg-yellow-500/20 text-yellow-500">
+                {t('jobs.queued', { position: job.queue_position ?? '?' })}
+              </span>
+            )}
+            {/* Unfinished dependencies */}
+            {isQueued && job.waiting_on && job.waiting_on.length > 0 && (
+              <span
+                className="px-2 py-0.5 text-xs rounded-full bg-yellow-500/20 text-yellow-500"
+                title={job.waiting_on.join('\n')}
+              >
+                {t('jobs.waitingOn', { count: job.waiting_on.length })}
+              </span>
+            )}
+            {/* Low-quality result */}
+            {job.quality?.low && (
+              <span
+                className="px-2 py-0.5 text-xs rounded-full bg-amber-500/20 text-amber-500"
+                title={job.quality.issues.join('\n')}
+              >
+                {t('jobs.lowQuality', { score: job.quality.score })}
+              </span>
+            )}
+            {/* Model call ran past summarization.call_timeout_secs */}
+            {isFailed && job.timed_out && (
+              <span className="px-2 py-0.5 text-xs rounded-full bg-red-500/20 text-red-500">
+                {t('jobs.timedOut')}
+              </span>
+            )}
             {/* Auto-summarize indicator */}
             {isAutoSummarize && (
               <span
@@ -97,7 +140,7 @@ export function JobCard({ job, onKill }: JobCardProps) {
         </div>
 
         {/* Actions */}
-        {isRunning && onKill && (
+        {(isRunning || isQueued) && onKill && (
           <button
             onClick={() => onKill(job.id)}
             className={cn(
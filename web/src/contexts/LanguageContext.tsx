@@ -1,5 +1,6 @@
-import { createContext, useContext, useState, useCallback, ReactNode } from 'react'
+import { createContext, useContext, useState, useCallback, useEffect, ReactNode } from 'react'
 import { type Language, type TranslationKey, getTranslation } from '../i18n'
+import { BASE_PATH } from '../hooks/useApi'
 
 interface LanguageContextType {
   language: Language
@@ -20,6 +21,18 @@ export function LanguageProvider({ children }: { children: ReactNode }) {
     return 'en'
   })
 
+  // Without a choice made in this browser, follow `output.ui_language`
+  useEffect(() => {
+    if (localStorage.getItem(STORAGE_KEY)) return
+    fetch(`${BASE_PATH}/api/config`)
+      .then((res) => res.json())
+      .then((res) => {
+        const lang = res?.data?.ui_language
+        if (lang === 'en' || lang === 'zh') setLanguageState(lang)
+      })
+      .catch(() => {})
+  }, [])
+
   const setLanguage = (lang: Language) => {
     setLanguageState(lang)
     localStorage.setItem(STORAGE_KEY, lang)
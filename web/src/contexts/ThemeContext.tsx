@@ -1,4 +1,5 @@
 import { createContext, useContext, useEffect, useState, ReactNode } from 'react'
+import { fetchUiPreferences, updateUiPreferences } from '../hooks/useApi'
 
 type Theme = 'light' | 'dark'
 
@@ -29,6 +30,15 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
     localStorage.setItem(STORAGE_KEY, theme)
   }, [theme])
 
+  // The theme saved on the server wins, so it follows across browsers
+  useEffect(() => {
+    fetchUiPreferences()
+      .then(prefs => {
+        if (prefs.theme) setThemeState(prefs.theme)
+      })
+      .catch(() => {})
+  }, [])
+
   useEffect(() => {
     const mediaQuery = window.matchMedia('(prefers-color-scheme: dark)')
     const handleChange = (e: MediaQueryListEvent) => {
@@ -41,12 +51,13 @@ export function ThemeProvider({ children }: { children: ReactNode }) {
     return () => mediaQuery.removeEventListener('change', handleChange)
   }, [])
 
-  const toggleTheme = () => {
-    setThemeState(prev => prev === 'dark' ? 'light' : 'dark')
-  }
-
   const setTheme = (newTheme: Theme) => {
     setThemeState(newTheme)
+    updateUiPreferences({ theme: newTheme }).catch(() => {})
+  }
+
+  const toggleTheme = () => {
+    setTheme(theme === 'dark' ? 'light' : 'dark')
   }
 
   return (
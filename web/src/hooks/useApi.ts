@@ -1,16 +1,31 @@
 import { useState, useCallback } from 'react'
 
-const API_BASE = '/api'
+declare global {
+  interface Window {
+    /** Path prefix injected by the server when `server.base_path` is set */
+    __DAILY_BASE__?: string
+  }
+}
+
+export const BASE_PATH = window.__DAILY_BASE__ ?? ''
+const API_BASE = `${BASE_PATH}/api`
 
 export interface DateItem {
   date: string
   session_count: number
   has_digest: boolean
+  /** Finalized with `daily lock`; the digest is not regenerated */
+  locked?: boolean
+  /** The digest was marked read since it was last generated */
+  read?: boolean
+  unread_sessions?: number
 }
 
 export interface SummaryCard {
   title: string
   content: string
+  /** Sessions the card was drawn from, with `summarization.digest_citations` on */
+  sessions?: { id: string; name: string }[]
 }
 
 export interface DailySummary {
@@ -21,38 +36,200 @@ export interface DailySummary {
   tomorrow_focus: SummaryCard[]
   file_path?: string
   raw_content?: string
+  locked?: boolean
+  /** Language of this translation; null for daily.md itself */
+  language?: string | null
+  /** Languages the digest was translated into (`summarization.digest_languages`) */
+  languages?: string[]
 }
 
 export interface Session {
   name: string
   title?: string
   summary_preview?: string
+  pinned?: boolean
+  read?: boolean
+}
+
+export interface SessionPage {
+  sessions: Session[]
+  total: number
+  page: number
+  page_size: number
+  has_more: boolean
+}
+
+export interface SessionQuery {
+  page?: number
+  page_size?: number
+  title?: string
+  tag?: string
+  has_skill_hints?: boolean
+  lite?: boolean
+}
+
+export interface PinnedSession extends Session {
+  date: string
 }
 
 export interface SessionDetail {
   content: string
   metadata?: {
+    /** Stable ID for permalinks, see `resolveRef` */
+    id?: string | null
     title?: string
     cwd?: string
     git_branch?: string
     duration?: string
+    pinned?: boolean
+    user_outcome?: string | null
+    user_satisfaction?: string | null
+    user_note?: string | null
+    user_stars?: number | null
   }
   file_path?: string
+  related?: RelatedSession[]
+}
+
+/** Where `/api/resolve` found a reference, with the UI path to open */
+export type ResolvedRef =
+  | { kind: 'session'; date: string; name: string; id: string | null; url: string }
+  | { kind: 'digest'; date: string; url: string }
+
+/** Full markdown and/or replacement bodies keyed by `## ` heading */
+export interface ContentEdit {
+  content?: string
+  sections?: Record<string, string>
+}
+
+export interface SessionAnnotation {
+  outcome?: string | null
+  satisfaction?: string | null
+  note?: string | null
+  stars?: number | null
+}
+
+export interface RelatedSession {
+  date: string
+  name: string
+  title: string
+  score: number
 }
 
 export interface Job {
   id: string
   task_name: string
   status: string
-  status_type: 'running' | 'completed' | 'failed'
-  job_type: 'session_end' | 'auto_summarize' | 'manual'
+  status_type: 'queued' | 'running' | 'completed' | 'failed'
+  job_type: 'session_end' | 'auto_summarize' | 'backfill' | 'checkpoint' | 'digest' | 'topics' | 'anomalies' | 'manual'
   started_at: string
   elapsed: string
+  queue_position?: number
+  depends_on?: string[]
+  waiting_on?: string[]
+  usage?: JobUsage
+  quality?: JobQuality
+  timed_out?: boolean
+}
+
+export interface JobQuality {
+  score: number
+  low: boolean
+  issues: string[]
+}
+
+export interface JobUsage {
+  model?: string
+  calls: number
+  input_tokens: number
+  output_tokens: number
+  cache_creation_input_tokens: number
+  cache_read_input_tokens: number
+  cost_usd?: number
+  stop_reason?: string
+}
+
+export interface OverheadTotals {
+  jobs: number
+  calls: number
+  input_tokens: number
+  output_tokens: number
+  cache_creation_tokens: number
+  cache_read_tokens: number
+  total_cost_usd: number
+}
+
+export interface UsageOverhead extends OverheadTotals {
+  by_job_type: Record<string, OverheadTotals>
+  daily: Record<string, OverheadTotals>
+}
+
+export interface UsageBlock {
+  start: string
+  end: string
+  first_activity: string
+  last_activity: string
+  is_active: boolean
+  entries: number
+  input_tokens: number
+  output_tokens: number
+  cache_creation_tokens: number
+  cache_read_tokens: number
+  total_tokens: number
+  cost_usd: number
+  models: string[]
+  burn_rate?: { tokens_per_minute: number; cost_per_hour: number }
+  projection?: { total_tokens: number; cost_usd: number; remaining_minutes: number }
+  limit?: {
+    token_limit: number
+    used_percent: number
+    projected_percent?: number
+    exhausted_at?: string
+  }
+}
+
+export interface UsageBlocks {
+  token_limit?: number
+  blocks: UsageBlock[]
+}
+
+export interface ActiveSession {
+  session_id: string
+  project?: string
+  transcript_path: string
+  started_at?: string
+  last_activity_at: string
+  elapsed_seconds: number
+  prompt_count?: number
+  total_tokens: number
+  total_cost_usd: number
 }
 
 export interface DigestResponse {
   message: string
   session_count: number
+  job_id: string
+}
+
+export type ServerEvent =
+  | { type: 'Connected' }
+  | { type: 'JobUpdated'; data: Job }
+  | { type: 'NewSession'; data: { date: string; name: string } }
+  | { type: 'DigestCompleted'; data: { date: string; job_id: string } }
+  | { type: 'ServerShutdown' }
+
+/** Subscribe to live server events; returns a function that closes the socket */
+export function subscribeEvents(onEvent: (event: ServerEvent) => void): () => void {
+  const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:'
+  const socket = new WebSocket(`${protocol}//${window.location.host}${BASE_PATH}/ws`)
+  socket.onmessage = (message) => {
+    try {
+      onEvent(JSON.parse(message.data) as ServerEvent)
+    } catch {
+      // Ignore malformed events
+    }
+  }
+  return () => socket.close()
 }
 
 export interface PromptTemplates {
@@ -84,6 +261,8 @@ export interface Config {
   storage_path: string
   model: string
   summary_language: string
+  /** `output.ui_language`, or the summary language when unset */
+  ui_language: string
   enable_daily_summary: boolean
   enable_extraction_hints: boolean
   auto_digest_enabled: boolean
@@ -93,6 +272,44 @@ export interface Config {
   auto_summarize_enabled: boolean
   auto_summarize_on_show: boolean
   auto_summarize_inactive_minutes: number
+  template_warnings?: TemplateWarning[]
+}
+
+export interface TemplateWarning {
+  template: string
+  kind: 'unknown_variable' | 'missing_required' | 'unclosed_placeholder' | 'unbalanced_braces'
+  message: string
+  variable?: string
+}
+
+export interface Topic {
+  slug: string
+  title: string
+  description: string
+  sessions: string[]
+}
+
+export interface TopicIndex {
+  updated: string | null
+  topics: Topic[]
+}
+
+export interface TopicDetail extends Topic {
+  content: string
+}
+
+export interface AskSource {
+  kind: 'session' | 'digest'
+  date: string
+  name: string | null
+  title: string
+  score: number
+}
+
+export interface AskAnswer {
+  question: string
+  answer: string
+  sources: AskSource[]
 }
 
 export interface ConfigUpdate {
@@ -116,6 +333,7 @@ export interface SessionUsage {
   cache_creation_tokens: number
   cache_read_tokens: number
   total_cost_usd: number
+  subagent_cost_usd: number
   model_calls: ModelUsageCount[]
 }
 
@@ -134,6 +352,31 @@ export interface DailyUsageData {
   session_count: number
 }
 
+export interface ProjectUsageData {
+  project: string
+  input_tokens: number
+  output_tokens: number
+  cache_creation_tokens: number
+  cache_read_tokens: number
+  total_cost_usd: number
+  session_count: number
+}
+
+export interface ArchiveStats {
+  storage_path: string
+  days: number
+  sessions: number
+  digests: number
+  total_bytes: number
+  largest_sessions: { date: string; name: string; bytes: number }[]
+  avg_session_chars: number
+  avg_digest_chars: number
+  pending_skills: number
+  pending_commands: number
+  oldest_date?: string
+  newest_date?: string
+}
+
 export interface UsageSummary {
   total_input_tokens: number
   total_output_tokens: number
@@ -143,6 +386,7 @@ export interface UsageSummary {
   total_sessions: number
   model_distribution: ModelUsageCount[]
   daily_usage: DailyUsageData[]
+  projects: ProjectUsageData[]
 }
 
 export interface SessionInsight {
@@ -155,11 +399,24 @@ export interface SessionInsight {
   friction_types: string[]
   friction_detail: string | null
   satisfaction: string | null
+  stars?: number | null
   claude_helpfulness: string | null
   session_type: string | null
+  project?: string
   token_usage?: SessionUsage
 }
 
+export interface ProjectSegment {
+  project: string
+  session_count: number
+  active_days: number
+  friction_rate: number
+  success_rate: number
+  goal_distribution: CategoryCount[]
+  total_tokens: number
+  total_cost: number
+}
+
 export interface WeeklyStat {
   week_label: string
   session_count: number
@@ -185,6 +442,16 @@ export interface TrendData {
   weekly_stats: WeeklyStat[]
 }
 
+export interface InsightsFilters {
+  project?: string
+  goal_category?: string
+  outcome?: string
+  session_type?: string
+  friction_type?: string
+  from?: string
+  to?: string
+}
+
 export interface InsightsData {
   total_days: number
   total_sessions: number
@@ -192,11 +459,129 @@ export interface InsightsData {
   goal_distribution: CategoryCount[]
   friction_distribution: CategoryCount[]
   satisfaction_distribution: CategoryCount[]
+  rated_sessions?: number
+  average_stars?: number | null
   language_distribution: CategoryCount[]
   session_type_distribution: CategoryCount[]
   session_details: SessionInsight[]
   trends?: TrendData
   usage_summary?: UsageSummary
+  activity: ActivityData
+  tracked_goals: GoalStat[]
+  work_patterns: WorkPatterns
+  projects?: ProjectSegment[]
+}
+
+export interface MetricChange {
+  metric: 'cost' | 'friction_rate' | 'success_rate'
+  current: number
+  baseline: number
+  change_pct: number
+  anomalous: boolean
+  driver: string | null
+  message: string
+}
+
+export interface AnomalyReport {
+  from: string
+  to: string
+  baseline_weeks: number
+  metrics: MetricChange[]
+}
+
+export interface FrictionExample {
+  session_id: string
+  date: string
+  session_name: string
+  title: string | null
+  brief_summary: string | null
+  outcome: string | null
+  count: number
+  friction_detail: string | null
+  link: string
+  conversation_url: string
+}
+
+export interface FrictionDrilldown {
+  friction_type: string
+  total_count: number
+  sessions: FrictionExample[]
+}
+
+export interface FocusBlock {
+  date: string
+  start: string
+  end: string
+  minutes: number
+}
+
+export interface WorkPatterns {
+  hourly: number[]
+  peak_hour: number | null
+  late_night_ratio: number
+  longest_focus_minutes: number
+  focus_blocks: FocusBlock[]
+}
+
+export interface GoalStat {
+  id: number
+  title: string
+  status: 'active' | 'done'
+  session_count: number
+  day_count: number
+  completed: string | null
+}
+
+export interface GoalProgress {
+  date: string
+  note: string
+  sessions: number
+}
+
+export interface Goal {
+  id: number
+  title: string
+  status: 'active' | 'done'
+  created: string
+  completed: string | null
+  progress: GoalProgress[]
+}
+
+export interface ToolStat {
+  name: string
+  calls: number
+  failures: number
+  failure_rate: number
+}
+
+export interface SessionToolUsage {
+  date: string
+  session_name: string
+  total_calls: number
+  failures: number
+  longest_chain: number
+  tools: ToolStat[]
+}
+
+export interface ToolInsights {
+  total_calls: number
+  total_failures: number
+  failure_rate: number
+  longest_chain: number
+  tools: ToolStat[]
+  sessions: SessionToolUsage[]
+}
+
+export interface HeatmapDay {
+  date: string
+  session_count: number
+}
+
+export interface ActivityData {
+  heatmap: HeatmapDay[]
+  current_streak: number
+  longest_streak: number
+  active_days: number
 }
 
 export interface DailyStat {
@@ -242,15 +627,37 @@ export interface DateInsights {
   day_summary: DayInsightSummary
 }
 
+export interface DayUsage {
+  input_tokens: number
+  output_tokens: number
+  cache_creation_tokens: number
+  cache_read_tokens: number
+  total_cost_usd: number
+  subagent_cost_usd: number
+}
+
+export interface DayOverview {
+  date: string
+  has_digest: boolean
+  session_count: number
+  summary: DailySummary | null
+  sessions: Session[]
+  insights: DateInsights | null
+  usage: DayUsage
+  jobs: Job[]
+}
+
 export type ConversationContentBlock =
   | { type: 'text'; text: string }
-  | { type: 'tool_use'; tool_use_id: string; name: string; input: unknown }
+  | { type: 'tool_use'; tool_use_id: string; name: string; input: unknown; sidechain?: ConversationMessage[] }
   | { type: 'tool_result'; tool_use_id: string; content: string }
+  | { type: 'thinking'; thinking: string }
 
 export interface ConversationMessage {
   role: 'user' | 'assistant'
   content: ConversationContentBlock[]
   timestamp?: string
+  line?: number
 }
 
 export interface ConversationData {
@@ -262,12 +669,45 @@ export interface ConversationData {
   has_more: boolean
 }
 
+export interface ConversationHit {
+  index: number
+  page: number
+  role: 'user' | 'assistant'
+  snippet: string
+}
+
+export interface ConversationSearch {
+  query: string
+  hits: ConversationHit[]
+  total_entries: number
+  page_size: number
+  has_transcript: boolean
+}
+
+export interface SourceItem {
+  text: string
+  lines: [number, number][]
+  messages: number[]
+  page: number | null
+}
+
+export interface SessionSources {
+  sections: { section: 'summary' | 'decisions' | 'learnings'; items: SourceItem[] }[]
+  page_size: number
+  has_transcript: boolean
+}
+
 export interface InstallCardResponse {
   name: string
   path: string
   message: string
 }
 
+interface GraphQLResponse<T> {
+  data?: T
+  errors?: { message: string }[]
+}
+
 interface ApiResponse<T> {
   success: boolean
   data?: T
@@ -278,6 +718,58 @@ interface RequestOptions extends RequestInit {
   headers?: Record<string, string>
 }
 
+/** Dashboard preferences saved on the server, shared by all browsers */
+export interface UiPreferences {
+  /** Widget ids in display order */
+  layout: string[]
+  hidden_widgets: string[]
+  /** The last `days`, or `from`..`to` (YYYY-MM-DD) */
+  date_range?: { days?: number; from?: string; to?: string } | null
+  /** Unset follows the system */
+  theme?: 'light' | 'dark' | null
+  updated?: string | null
+}
+
+async function preferencesRequest(options?: RequestInit): Promise<UiPreferences> {
+  const res = await fetch(`${API_BASE}/ui/preferences`, {
+    headers: { 'Content-Type': 'application/json' },
+    ...options,
+  })
+  const data: ApiResponse<UiPreferences> = await res.json()
+  if (!data.success) {
+    throw new Error(data.error || 'Request failed')
+  }
+  return data.data as UiPreferences
+}
+
+export function fetchUiPreferences(): Promise<UiPreferences> {
+  return preferencesRequest()
+}
+
+/** Change some preferences, keeping the rest as saved */
+export async function updateUiPreferences(changes: Partial<UiPreferences>): Promise<UiPreferences> {
+  const current = await fetchUiPreferences()
+  return preferencesRequest({
+    method: 'PUT',
+    body: JSON.stringify({ ...current, ...changes }),
+  })
+}
+
+/** Download link for a CSV export: `usage/export` or `insights` with query params */
+export function csvExportUrl(
+  endpoint: 'usage/export' | 'insights',
+  params: Record<string, string> = {}
+): string {
+  const query = new URLSearchParams({ ...params, format: 'csv' })
+  return `${API_BASE}/${endpoint}?${query.toString()}`
+}
+
+/** Standalone HTML page of a date's digest (`theme`: light or dark) */
+export function digestHtmlUrl(date: string, theme?: 'light' | 'dark'): string {
+  const query = theme ? `?theme=${theme}` : ''
+  return `${API_BASE}/dates/${date}/html${query}`
+}
+
 export function useApi() {
   const [loading, setLoading] = useState(false)
   const [error, setError] = useState<string | null>(null)
@@ -307,24 +799,118 @@ export function useApi() {
     }
   }, [])
 
+  // Composite views in one round trip; fields keep the REST snake_case names
+  const graphql = useCallback(async <T>(query: string, variables?: Record<string, unknown>): Promise<T> => {
+    setLoading(true)
+    setError(null)
+    try {
+      const res = await fetch(`${API_BASE}/graphql`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ query, variables }),
+      })
+      const body: GraphQLResponse<T> = await res.json()
+      if (body.errors?.length) {
+        throw new Error(body.errors.map((e) => e.message).join('; '))
+      }
+      return body.data as T
+    } catch (err) {
+      const message = err instanceof Error ? err.message : 'Unknown error'
+      setError(message)
+      throw err
+    } finally {
+      setLoading(false)
+    }
+  }, [])
+
   const fetchDates = useCallback(() => request<DateItem[]>('/dates'), [request])
 
   const fetchDailySummary = useCallback(
-    (date: string) => request<DailySummary>(`/dates/${date}`),
+    (date: string, lang?: string) =>
+      request<DailySummary>(`/dates/${date}${lang ? `?lang=${encodeURIComponent(lang)}` : ''}`),
     [request]
   )
 
-  const fetchSessions = useCallback(
-    (date: string) => request<Session[]>(`/dates/${date}/sessions`),
+  const fetchSessionPage = useCallback(
+    (date: string, query: SessionQuery = {}) => {
+      const params = new URLSearchParams()
+      Object.entries(query).forEach(([key, value]) => {
+        if (value !== undefined && value !== '') params.set(key, String(value))
+      })
+      const qs = params.toString()
+      return request<SessionPage>(`/dates/${date}/sessions${qs ? `?${qs}` : ''}`)
+    },
     [request]
   )
 
+  const fetchSessions = useCallback(
+    (date: string) => fetchSessionPage(date).then((page) => page.sessions),
+    [fetchSessionPage]
+  )
+
   const fetchSession = useCallback(
     (date: string, name: string) => request<SessionDetail>(`/dates/${date}/sessions/${encodeURIComponent(name)}`),
     [request]
   )
 
-  const fetchJobs = useCallback(() => request<Job[]>('/jobs'), [request])
+  const updateDailySummary = useCallback(
+    (date: string, edit: ContentEdit) =>
+      request<DailySummary>(`/dates/${date}`, {
+        method: 'PATCH',
+        body: JSON.stringify(edit),
+      }),
+    [request]
+  )
+
+  const updateSession = useCallback(
+    (date: string, name: string, edit: ContentEdit) =>
+      request<SessionDetail>(`/dates/${date}/sessions/${encodeURIComponent(name)}`, {
+        method: 'PATCH',
+        body: JSON.stringify(edit),
+      }),
+    [request]
+  )
+
+  const fetchPinned = useCallback(() => request<PinnedSession[]>('/pinned'), [request])
+
+  const setSessionPinned = useCallback(
+    (date: string, name: string, pinned: boolean) =>
+      request<Session>(`/dates/${date}/sessions/${encodeURIComponent(name)}/pin`, {
+        method: pinned ? 'POST' : 'DELETE',
+      }),
+    [request]
+  )
+
+  // Without `name` the date's digest; `all` marks its sessions too
+  const setRead = useCallback(
+    (date: string, read: boolean, name?: string, all = false) => {
+      const path = name
+        ? `/dates/${date}/sessions/${encodeURIComponent(name)}/mark-read`
+        : `/dates/${date}/mark-read${all ? '?all=true' : ''}`
+      return request<Session | DateItem>(path, { method: read ? 'POST' : 'DELETE' })
+    },
+    [request]
+  )
+
+  const rateSession = useCallback(
+    (date: string, name: string, annotation: SessionAnnotation & { clear?: boolean }) =>
+      request<SessionAnnotation>(`/dates/${date}/sessions/${encodeURIComponent(name)}/annotation`, {
+        method: 'PATCH',
+        body: JSON.stringify(annotation),
+      }),
+    [request]
+  )
+
+  const fetchJobs = useCallback(
+    (page: { limit?: number; offset?: number } = {}) => {
+      const params = new URLSearchParams()
+      if (page.limit) params.set('limit', String(page.limit))
+      if (page.offset) params.set('offset', String(page.offset))
+      const query = params.toString()
+      return request<Job[]>(query ? `/jobs?${query}` : '/jobs')
+    },
+    [request]
+  )
 
   const fetchJob = useCallback(
     (id: string) => request<Job>(`/jobs/${id}`),
@@ -341,11 +927,57 @@ export function useApi() {
     [request]
   )
 
+  const fetchUsageOverhead = useCallback(
+    (days?: number) =>
+      request<UsageOverhead>(days ? `/usage/overhead?days=${days}` : '/usage/overhead'),
+    [request]
+  )
+
+  const fetchStats = useCallback(() => request<ArchiveStats>('/stats'), [request])
+
+  // A session ID, `YYYY-MM-DD/name` or `YYYY-MM-DD`
+  const resolveRef = useCallback(
+    (ref: string) => request<ResolvedRef>(`/resolve?ref=${encodeURIComponent(ref)}`),
+    [request]
+  )
+
+  const fetchUsageProjects = useCallback(
+    (days?: number) =>
+      request<ProjectUsageData[]>(days ? `/usage/projects?days=${days}` : '/usage/projects'),
+    [request]
+  )
+
+  const fetchUsageBlocks = useCallback(
+    (days?: number, tokenLimit?: number | 'max') => {
+      const params = new URLSearchParams()
+      if (days) params.set('days', String(days))
+      if (tokenLimit) params.set('token_limit', String(tokenLimit))
+      const query = params.toString()
+      return request<UsageBlocks>(query ? `/usage/blocks?${query}` : '/usage/blocks')
+    },
+    [request]
+  )
+
+  const fetchActiveSessions = useCallback(
+    (minutes?: number) =>
+      request<ActiveSession[]>(minutes ? `/sessions/active?minutes=${minutes}` : '/sessions/active'),
+    [request]
+  )
+
   const triggerDigest = useCallback(
     (date: string) => request<DigestResponse>(`/dates/${date}/digest`, { method: 'POST' }),
     [request]
   )
 
+  const regenerateDigest = useCallback(
+    (date: string, keep: string[] = []) =>
+      request<DigestResponse>(`/dates/${date}/digest/regenerate`, {
+        method: 'POST',
+        body: JSON.stringify({ keep }),
+      }),
+    [request]
+  )
+
   const fetchConfig = useCallback(() => request<Config>('/config'), [request])
 
   const updateConfig = useCallback(
@@ -362,24 +994,84 @@ export function useApi() {
     [request]
   )
 
+  const validateTemplate = useCallback(
+    (templateType: string, template: string) =>
+      request<TemplateWarning[]>('/config/templates/validate', {
+        method: 'POST',
+        body: JSON.stringify({ template_type: templateType, template }),
+      }),
+    [request]
+  )
+
   const fetchInsights = useCallback(
-    (days: number = 30) => request<InsightsData>(`/insights?days=${days}`),
+    (days: number = 30, filters: InsightsFilters = {}) => {
+      const params = new URLSearchParams({ days: String(days) })
+      for (const [key, value] of Object.entries(filters)) {
+        if (value) params.set(key, value)
+      }
+      return request<InsightsData>(`/insights?${params.toString()}`)
+    },
+    [request]
+  )
+
+  const fetchToolInsights = useCallback(
+    (days: number = 30) => request<ToolInsights>(`/insights/tools?days=${days}`),
+    [request]
+  )
+
+  const fetchAnomalies = useCallback(
+    () => request<AnomalyReport>('/insights/anomalies'),
+    [request]
+  )
+
+  const fetchFrictionExamples = useCallback(
+    (frictionType: string, days: number = 30, filters: InsightsFilters = {}) => {
+      const params = new URLSearchParams({ days: String(days) })
+      for (const [key, value] of Object.entries(filters)) {
+        if (value) params.set(key, value)
+      }
+      return request<FrictionDrilldown>(
+        `/insights/friction/${encodeURIComponent(frictionType)}?${params.toString()}`
+      )
+    },
     [request]
   )
 
   const fetchConversation = useCallback(
-    (date: string, name: string, page: number = 0, pageSize: number = 50) =>
+    (date: string, name: string, page: number = 0, pageSize: number = 50, includeThinking: boolean = false) =>
       request<ConversationData>(
-        `/dates/${date}/sessions/${encodeURIComponent(name)}/conversation?page=${page}&page_size=${pageSize}`
+        `/dates/${date}/sessions/${encodeURIComponent(name)}/conversation?page=${page}&page_size=${pageSize}&include_thinking=${includeThinking}`
+      ),
+    [request]
+  )
+
+  const searchConversation = useCallback(
+    (date: string, name: string, query: string, pageSize: number = 50, includeThinking: boolean = false) =>
+      request<ConversationSearch>(
+        `/dates/${date}/sessions/${encodeURIComponent(name)}/conversation/search?q=${encodeURIComponent(query)}&page_size=${pageSize}&include_thinking=${includeThinking}`
       ),
     [request]
   )
 
+  const fetchSessionSources = useCallback(
+    (date: string, name: string, section?: string, pageSize: number = 50, includeThinking: boolean = false) => {
+      const params = new URLSearchParams({ page_size: String(pageSize), include_thinking: String(includeThinking) })
+      if (section) params.set('section', section)
+      return request<SessionSources>(`/dates/${date}/sessions/${encodeURIComponent(name)}/sources?${params.toString()}`)
+    },
+    [request]
+  )
+
   const fetchDateInsights = useCallback(
     (date: string) => request<DateInsights>(`/dates/${date}/insights`),
     [request]
   )
 
+  const fetchDayOverview = useCallback(
+    (date: string) => request<DayOverview>(`/dates/${date}/overview`),
+    [request]
+  )
+
   const installCard = useCallback(
     (title: string, content: string, cardType: 'skill' | 'command') =>
       request<InstallCardResponse>('/install', {
@@ -389,24 +1081,94 @@ export function useApi() {
     [request]
   )
 
+  const fetchGoals = useCallback(() => request<Goal[]>('/goals'), [request])
+
+  const createGoal = useCallback(
+    (title: string) =>
+      request<Goal>('/goals', {
+        method: 'POST',
+        body: JSON.stringify({ title }),
+      }),
+    [request]
+  )
+
+  const updateGoal = useCallback(
+    (id: number, changes: { title?: string; status?: 'active' | 'done' }) =>
+      request<Goal>(`/goals/${id}`, {
+        method: 'PATCH',
+        body: JSON.stringify(changes),
+      }),
+    [request]
+  )
+
+  const deleteGoal = useCallback(
+    (id: number) => request<Goal>(`/goals/${id}`, { method: 'DELETE' }),
+    [request]
+  )
+
+  const fetchTopics = useCallback(() => request<TopicIndex>('/topics'), [request])
+
+  const fetchTopic = useCallback(
+    (slug: string) => request<TopicDetail>(`/topics/${encodeURIComponent(slug)}`),
+    [request]
+  )
+
+  const ask = useCallback(
+    (question: string, limit?: number) =>
+      request<AskAnswer>('/ask', {
+        method: 'POST',
+        body: JSON.stringify({ question, limit }),
+      }),
+    [request]
+  )
+
   return {
     loading,
     error,
     fetchDates,
     fetchDailySummary,
     fetchSessions,
+    fetchSessionPage,
     fetchSession,
+    fetchPinned,
+    setSessionPinned,
+    setRead,
+    updateDailySummary,
+    updateSession,
+    rateSession,
     fetchJobs,
     fetchJob,
     fetchJobLog,
     killJob,
+    fetchUsageOverhead,
+    fetchUsageProjects,
+    fetchStats,
+    resolveRef,
+    fetchUsageBlocks,
+    fetchActiveSessions,
     triggerDigest,
+    regenerateDigest,
     fetchConfig,
     updateConfig,
     fetchDefaultTemplates,
+    validateTemplate,
     fetchInsights,
+    fetchToolInsights,
+    fetchFrictionExamples,
+    fetchAnomalies,
     fetchConversation,
+    searchConversation,
+    fetchSessionSources,
     fetchDateInsights,
+    fetchDayOverview,
     installCard,
+    fetchGoals,
+    createGoal,
+    updateGoal,
+    deleteGoal,
+    fetchTopics,
+    fetchTopic,
+    ask,
+    graphql,
   }
 }
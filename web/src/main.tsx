@@ -4,13 +4,14 @@ import { BrowserRouter } from 'react-router-dom'
 import { ThemeProvider } from './contexts/ThemeContext'
 import { LanguageProvider } from './contexts/LanguageContext'
 import App from './App'
+import { BASE_PATH } from './hooks/useApi'
 import './index.css'
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
     <LanguageProvider>
       <ThemeProvider>
-        <BrowserRouter>
+        <BrowserRouter basename={BASE_PATH || undefined}>
           <App />
         </BrowserRouter>
       </ThemeProvider>
@@ -6,6 +6,7 @@ import type { DateItem } from '../hooks/useApi'
 import { cn } from '../lib/utils'
 import { format, parseISO, isToday, isYesterday } from 'date-fns'
 import { useLanguage } from '../contexts/LanguageContext'
+import { ActiveSessions } from '../components/ActiveSessions'
 
 export function DailyList() {
   const [dates, setDates] = useState<DateItem[]>([])
@@ -51,6 +52,8 @@ export function DailyList() {
     <div className="max-w-4xl mx-auto px-6 py-8">
       <h1 className="text-3xl font-bold mb-8 text-balance">{t('archive.title')}</h1>
 
+      <ActiveSessions />
+
       {dates.length === 0 ? (
         <div className="text-center py-12">
           <p className="text-gray-500 text-lg">{t('archive.noArchives')}</p>
@@ -93,6 +96,11 @@ export function DailyList() {
                           {t('archive.digest')}
                         </span>
                       )}
+                      {item.locked && (
+                        <span className="text-xs text-gray-400 bg-gray-500/10 px-2 py-0.5 rounded">
+                          {t('archive.locked')}
+                        </span>
+                      )}
                     </div>
                   </div>
                   <svg
@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react'
 import { useParams, Link } from 'react-router-dom'
 import { motion } from 'framer-motion'
-import { useApi } from '../hooks/useApi'
+import { useApi, subscribeEvents, digestHtmlUrl } from '../hooks/useApi'
 import type { DailySummary, DateInsights, DateSessionInsight, SummaryCard } from '../hooks/useApi'
 import { MarkdownRenderer } from '../components/MarkdownRenderer'
 import { cn } from '../lib/utils'
@@ -48,6 +48,9 @@ const getOutcomeBadgeClass = (outcome: string | null): string => {
 // Collapsible summary card component
 function CollapsibleCard({ card, defaultOpen = true }: { card: SummaryCard; defaultOpen?: boolean }) {
   const [open, setOpen] = useState(defaultOpen)
+  const { date } = useParams<{ date: string }>()
+  const { t } = useLanguage()
+  const sessions = card.sessions ?? []
 
   return (
     <div className="border border-gray-200 dark:border-gray-700/50 rounded-lg overflow-hidden">
@@ -55,7 +58,12 @@ function CollapsibleCard({ card, defaultOpen = true }: { card: SummaryCard; defa
         onClick={() => setOpen(!open)}
         className="w-full flex items-center justify-between px-4 py-3 text-left bg-white dark:bg-daily-light hover:bg-gray-50 dark:hover:bg-gray-800/50 transition-colors"
       >
-        <span className="text-sm font-semibold text-gray-800 dark:text-gray-200">{card.title}</span>
+        <span
+          className="text-sm font-semibold text-gray-800 dark:text-gray-200"
+          title={sessions.length > 0 ? t('dayDetail.citedSessions', { sessions: sessions.map((s) => s.name).join(', ') }) : undefined}
+        >
+          {card.title}
+        </span>
         <svg
           className={cn('size-4 text-gray-400 transition-transform', open && 'rotate-180')}
           fill="none" viewBox="0 0 24 24" stroke="currentColor"
@@ -68,6 +76,19 @@ function CollapsibleCard({ card, defaultOpen = true }: { card: SummaryCard; defa
           <div className="text-sm text-gray-600 dark:text-gray-400 markdown-content">
             <MarkdownRenderer content={card.content} />
           </div>
+          {sessions.length > 0 && (
+            <div className="mt-2 flex flex-wrap gap-1.5">
+              {sessions.map((session) => (
+                <Link
+                  key={session.id}
+                  to={`/day/${date}/session/${encodeURIComponent(session.name)}`}
+                  className="text-xs px-1.5 py-0.5 rounded bg-gray-100 dark:bg-gray-800 text-gray-500 dark:text-gray-400 hover:text-orange-500 dark:hover:text-orange-400 transition-colors"
+                >
+                  {session.name}
+                </Link>
+              ))}
+            </div>
+          )}
         </div>
       )}
     </div>
@@ -452,7 +473,7 @@ export function DayDetail() {
   const [digestMessage, setDigestMessage] = useState<string | null>(null)
   const [copySuccess, setCopySuccess] = useState(false)
   const [insights, setInsights] = useState<DateInsights | null>(null)
-  const { fetchDailySummary, triggerDigest, fetchDateInsights, installCard, loading, error } = useApi()
+  const { fetchDailySummary, triggerDigest, fetchDayOverview, installCard, setRead, loading, error } = useApi()
   const { t } = useLanguage()
 
   // Get satisfaction indicator (used in insights tab)
@@ -489,9 +510,12 @@ export function DayDetail() {
 
   useEffect(() => {
     if (!date) return
-    fetchDailySummary(date)
-      .then(summary => {
+    fetchDayOverview(date)
+      .then(({ summary, insights }) => {
+        setInsights(insights)
+        if (!summary) return
         setSummary(summary)
+        setRead(date, true).catch(console.error)
         if (summary.raw_content) {
           const content = extractContent(summary.raw_content)
           setDigestContent(content || null)
@@ -507,11 +531,7 @@ export function DayDetail() {
         }
       })
       .catch(console.error)
-
-    fetchDateInsights(date)
-      .then(setInsights)
-      .catch(() => {})
-  }, [date, fetchDailySummary, fetchDateInsights])
+  }, [date, fetchDayOverview, setRead])
 
   const handleRegenerate = async () => {
     if (!date || digestLoading) return
@@ -525,12 +545,20 @@ export function DayDetail() {
       const pollInterval = 3000
       const maxAttempts = 30
       let attempt = 0
+      let finished = false
+      let unsubscribe = () => {}
+      const finish = () => {
+        finished = true
+        unsubscribe()
+      }
 
-      const poll = async () => {
-        attempt++
+      const poll = async (scheduleNext = true) => {
+        if (finished) return
+        if (scheduleNext) attempt++
         try {
           const newSummary = await fetchDailySummary(date)
           if (newSummary.raw_content && newSummary.raw_content !== previousRaw) {
+            finish()
             setSummary(newSummary)
             const content = extractContent(newSummary.raw_content)
             setDigestContent(content || null)
@@ -543,14 +571,31 @@ export function DayDetail() {
           // Ignore fetch errors during polling
         }
 
+        if (!scheduleNext || finished) return
         if (attempt < maxAttempts) {
           setTimeout(poll, pollInterval)
         } else {
+          finish()
           setDigestLoading(false)
           setDigestMessage(t('dayDetail.regenerationSlow'))
         }
       }
 
+      // Live events surface completion or failure without waiting for the next poll
+      unsubscribe = subscribeEvents((event) => {
+        if (event.type === 'DigestCompleted' && event.data.job_id === response.job_id) {
+          poll(false)
+        } else if (
+          event.type === 'JobUpdated' &&
+          event.data.id === response.job_id &&
+          event.data.status_type === 'failed'
+        ) {
+          finish()
+          setDigestLoading(false)
+          setDigestMessage(event.data.status)
+        }
+      })
+
       setTimeout(poll, 5000)
     } catch (err) {
       setDigestMessage(err instanceof Error ? err.message : 'Failed to regenerate daily summary')
@@ -649,9 +694,34 @@ export function DayDetail() {
               </button>
             )}
 
+            {digestContent && date && (
+              <a
+                href={digestHtmlUrl(date)}
+                target="_blank"
+                rel="noopener noreferrer"
+                className={cn(
+                  'px-3 py-2 rounded-lg text-sm font-medium transition-colors',
+                  'bg-orange-500/20 text-orange-400 hover:bg-orange-500/30',
+                  'border border-orange-500/30 hover:border-orange-500/50'
+                )}
+              >
+                {t('dayDetail.openHtml')}
+              </a>
+            )}
+
+            {summary?.locked && (
+              <span
+                className="px-3 py-2 rounded-lg text-sm font-medium text-gray-400 bg-gray-500/10 border border-gray-500/20"
+                title={t('dayDetail.lockedHint')}
+              >
+                {t('archive.locked')}
+              </span>
+            )}
+
             <button
               onClick={handleRegenerate}
-              disabled={digestLoading}
+              disabled={digestLoading || summary?.locked}
+              title={summary?.locked ? t('dayDetail.lockedHint') : undefined}
               className={cn(
                 'px-4 py-2 rounded-lg text-sm font-medium transition-colors',
                 'bg-orange-500/20 text-orange-400 hover:bg-orange-500/30',
@@ -268,6 +268,54 @@ export function Insights() {
             </ResponsiveContainer>
           </ChartCard>
 
+          {/* Work Patterns */}
+          {data.work_patterns.peak_hour !== null && (
+            <div className="mt-4">
+              <ChartCard title={t('insights.workPatterns')}>
+                <div className="grid grid-cols-3 gap-4 mb-4">
+                  <StatCard
+                    label={t('insights.peakHour')}
+                    value={`${String(data.work_patterns.peak_hour).padStart(2, '0')}:00`}
+                  />
+                  <StatCard
+                    label={t('insights.lateNight')}
+                    value={`${data.work_patterns.late_night_ratio.toFixed(0)}%`}
+                    sub="22:00 - 06:00"
+                  />
+                  <StatCard
+                    label={t('insights.longestFocus')}
+                    value={`${data.work_patterns.longest_focus_minutes}m`}
+                  />
+                </div>
+                <ResponsiveContainer width="100%" height={180}>
+                  <BarChart data={data.work_patterns.hourly.map((count, hour) => ({ hour: `${hour}`, count }))}>
+                    <CartesianGrid strokeDasharray="3 3" stroke="#374151" opacity={0.2} />
+                    <XAxis dataKey="hour" tick={{ fontSize: 11, fill: '#9ca3af' }} />
+                    <YAxis tick={{ fontSize: 11, fill: '#9ca3af' }} allowDecimals={false} />
+                    <Tooltip content={<CustomTooltip />} />
+                    <Bar dataKey="count" name="messages" fill="#f97316" radius={[4, 4, 0, 0]} />
+                  </BarChart>
+                </ResponsiveContainer>
+                {data.work_patterns.focus_blocks.length > 0 && (
+                  <div className="mt-4">
+                    <p className="text-xs text-gray-500 dark:text-gray-400 mb-2">{t('insights.focusBlocks')}</p>
+                    <div className="flex flex-wrap gap-2">
+                      {data.work_patterns.focus_blocks.map((block) => (
+                        <span
+                          key={`${block.date}-${block.start}`}
+                          className="px-3 py-1 rounded-lg bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 text-sm text-gray-600 dark:text-gray-300"
+                        >
+                          {block.date.slice(5)} {block.start}-{block.end}{' '}
+                          <span className="font-bold text-orange-500 dark:text-orange-400">{block.minutes}m</span>
+                        </span>
+                      ))}
+                    </div>
+                  </div>
+                )}
+              </ChartCard>
+            </div>
+          )}
+
           {/* Distribution Charts */}
           <div className="grid grid-cols-1 md:grid-cols-2 gap-4 mt-4">
             {/* Languages */}
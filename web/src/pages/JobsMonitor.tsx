@@ -5,13 +5,16 @@ import type { Job } from '../hooks/useApi'
 import { JobCard } from '../components/JobCard'
 import { useLanguage } from '../contexts/LanguageContext'
 
+/** Newest jobs shown; older ones stay on disk until `jobs.retention` prunes them */
+const JOBS_PAGE_SIZE = 100
+
 export function JobsMonitor() {
   const [jobs, setJobs] = useState<Job[]>([])
   const { fetchJobs, killJob, error } = useApi()
   const { t } = useLanguage()
 
   const loadJobs = useCallback(() => {
-    fetchJobs()
+    fetchJobs({ limit: JOBS_PAGE_SIZE })
       .then(setJobs)
       .catch(console.error)
   }, [fetchJobs])
@@ -32,8 +35,9 @@ export function JobsMonitor() {
     }
   }
 
-  const runningJobs = jobs.filter((j) => j.status_type === 'running')
-  const completedJobs = jobs.filter((j) => j.status_type !== 'running')
+  const isActive = (j: Job) => j.status_type === 'running' || j.status_type === 'queued'
+  const runningJobs = jobs.filter(isActive)
+  const completedJobs = jobs.filter((j) => !isActive(j))
 
   return (
     <div className="max-w-4xl mx-auto px-6 py-8">
@@ -0,0 +1,27 @@
+import { useEffect, useState } from 'react'
+import { Navigate, useParams } from 'react-router-dom'
+import { useApi } from '../hooks/useApi'
+import { useLanguage } from '../contexts/LanguageContext'
+
+/** `/go/<ref>`: a stable link that opens the session or digest it names */
+export function Permalink() {
+  const { '*': ref = '' } = useParams()
+  const { resolveRef, error } = useApi()
+  const { t } = useLanguage()
+  const [url, setUrl] = useState<string | null>(null)
+
+  useEffect(() => {
+    resolveRef(ref)
+      .then((resolved) => setUrl(resolved.url))
+      .catch(() => {})
+  }, [ref, resolveRef])
+
+  if (url) {
+    return <Navigate to={url} replace />
+  }
+  return (
+    <div className="p-8 text-sm text-gray-500">
+      {error ? t('permalink.notFound', { ref }) : t('permalink.resolving')}
+    </div>
+  )
+}
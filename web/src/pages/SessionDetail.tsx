@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react'
-import { useParams, Link } from 'react-router-dom'
+import { useParams, useSearchParams, Link } from 'react-router-dom'
 import { motion } from 'framer-motion'
 import { useApi } from '../hooks/useApi'
 import type { SessionDetail as SessionDetailType } from '../hooks/useApi'
@@ -12,8 +12,11 @@ export function SessionDetail() {
   const { date, name } = useParams<{ date: string; name: string }>()
   const [session, setSession] = useState<SessionDetailType | null>(null)
   const [copySuccess, setCopySuccess] = useState(false)
-  const [activeTab, setActiveTab] = useState<'summary' | 'conversation'>('summary')
-  const { fetchSession, loading, error } = useApi()
+  const [searchParams] = useSearchParams()
+  const [activeTab, setActiveTab] = useState<'summary' | 'conversation'>(
+    searchParams.get('tab') === 'conversation' ? 'conversation' : 'summary'
+  )
+  const { fetchSession, setSessionPinned, setRead, loading, error } = useApi()
   const { t } = useLanguage()
 
   const handleCopyContent = async () => {
@@ -32,12 +35,26 @@ export function SessionDetail() {
     }
   }
 
+  const handleTogglePin = async () => {
+    if (!session || !date || !name) return
+    const pinned = !session.metadata?.pinned
+    try {
+      await setSessionPinned(date, name, pinned)
+      setSession({ ...session, metadata: { ...session.metadata, pinned } })
+    } catch (err) {
+      console.error('Failed to update pin:', err)
+    }
+  }
+
   useEffect(() => {
     if (!date || !name) return
     fetchSession(date, name)
-      .then(setSession)
+      .then((session) => {
+        setSession(session)
+        return setRead(date, true, name)
+      })
       .catch(console.error)
-  }, [date, name, fetchSession])
+  }, [date, name, fetchSession, setRead])
 
   if (loading && !session) {
     return (
@@ -87,6 +104,19 @@ export function SessionDetail() {
 
               {/* Action Buttons */}
               <div className="flex items-center gap-2">
+                <button
+                  onClick={handleTogglePin}
+                  className={cn(
+                    'px-3 py-2 rounded-lg text-sm font-medium transition-colors',
+                    'border flex items-center gap-2',
+                    session.metadata?.pinned
+                      ? 'bg-orange-500 text-white border-orange-500 hover:bg-orange-600'
+                      : 'bg-orange-500/20 text-orange-400 hover:bg-orange-500/30 border-orange-500/30 hover:border-orange-500/50'
+                  )}
+                >
+                  <span>📌</span>
+                  <span>{session.metadata?.pinned ? t('sessionDetail.unpin') : t('sessionDetail.pin')}</span>
+                </button>
                 <button
                   onClick={handleCopyContent}
                   className={cn(
@@ -187,6 +217,26 @@ export function SessionDetail() {
               <ChatView date={date!} name={name!} />
             </div>
           )}
+
+          {/* Related Sessions (embeddings index) */}
+          {session.related && session.related.length > 0 && (
+            <div className="mt-8">
+              <h2 className="text-sm font-medium text-gray-500 mb-3">{t('sessionDetail.related')}</h2>
+              <div className="space-y-1">
+                {session.related.map((related) => (
+                  <Link
+                    key={`${related.date}/${related.name}`}
+                    to={`/day/${related.date}/session/${encodeURIComponent(related.name)}`}
+                    className="flex items-center gap-3 px-3 py-2 rounded-lg text-sm hover:bg-gray-100 dark:hover:bg-daily-light transition-colors"
+                  >
+                    <span className="text-xs text-gray-500 tabular-nums shrink-0">{related.date}</span>
+                    <span className="truncate flex-1 text-gray-700 dark:text-gray-300">{related.title}</span>
+                    <span className="text-xs text-gray-400 tabular-nums shrink-0">{Math.round(related.score * 100)}%</span>
+                  </Link>
+                ))}
+              </div>
+            </div>
+          )}
         </motion.div>
       )}
     </div>
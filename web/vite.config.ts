@@ -3,7 +3,8 @@ import react from '@vitejs/plugin-react'
 
 export default defineConfig({
   plugins: [react()],
-  base: '/',
+  // Relative asset URLs; the server injects <base href> for server.base_path
+  base: './',
   server: {
     proxy: {
       '/api': {